@@ -30,6 +30,19 @@ if [ "${NO_RUN}" != "1" ] && [ "${NO_RUN}" != "true" ]; then
     "${CARGO}" test --target "${TARGET}" --no-default-features --features "async-cancel,bytes,legacy,macros,utils,sync"
     "${CARGO}" test --target "${TARGET}" --no-default-features --features "async-cancel,bytes,legacy,macros,utils,sync" --release
 
+    case "${TARGET}" in
+    *linux*)
+        # enable legacy driver with epoll instead of mio
+        "${CARGO}" test --target "${TARGET}" --no-default-features --features "async-cancel,bytes,legacy-epoll,macros,utils,sync"
+        "${CARGO}" test --target "${TARGET}" --no-default-features --features "async-cancel,bytes,legacy-epoll,macros,utils,sync" --release
+        ;;
+    *windows*)
+        # enable iocp driver
+        "${CARGO}" test --target "${TARGET}" --no-default-features --features "async-cancel,bytes,iocp,macros,utils,sync"
+        "${CARGO}" test --target "${TARGET}" --no-default-features --features "async-cancel,bytes,iocp,macros,utils,sync" --release
+        ;;
+    esac
+
     if [ "${TARGET}" = "x86_64-unknown-linux-gnu" ] || [ "${TARGET}" = "i686-unknown-linux-gnu" ]; then
         # only enabled uring driver
         "${CARGO}" test --target "${TARGET}" --no-default-features --features "async-cancel,bytes,iouring,macros,utils"
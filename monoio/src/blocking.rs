@@ -15,51 +15,95 @@ pub trait ThreadPool {
     /// Monoio runtime will call `schedule_task` on `spawn_blocking`.
     /// ThreadPool impl must execute it now or later.
     fn schedule_task(&self, task: BlockingTask);
+
+    /// Monoio runtime will call `try_schedule_task` on [`try_spawn_blocking`]. ThreadPool impl
+    /// may return the task back if it can not execute it, e.g. its queue is full.
+    ///
+    /// The default implementation schedules the task with `schedule_task`.
+    fn try_schedule_task(&self, task: BlockingTask) -> Result<(), BlockingTask> {
+        self.schedule_task(task);
+        Ok(())
+    }
 }
 
 /// Error on waiting blocking task.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum JoinError {
-    /// Task is canceled.
+    /// Task is canceled, i.e. it is dropped by the thread pool or aborted before it starts.
     Canceled,
 }
 
+/// Error of [`try_spawn_blocking`] when the thread pool rejects the task, e.g. its queue is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct RejectedError;
+
+impl std::fmt::Display for RejectedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("blocking task rejected by the thread pool")
+    }
+}
+
+impl std::error::Error for RejectedError {}
+
 /// BlockingTask is contrusted by monoio, ThreadPool impl
 /// will execute it with `.run()`.
 pub struct BlockingTask {
     task: Option<crate::task::Task<NoopScheduler>>,
     blocking_vtable: &'static BlockingTaskVtable,
+    // Counts the task as queued in the runtime metrics until it starts or is dropped.
+    queued: Option<QueuedGuard>,
+}
+
+struct QueuedGuard(std::sync::Arc<std::sync::atomic::AtomicUsize>);
+
+impl QueuedGuard {
+    fn new(queued: &std::sync::Arc<std::sync::atomic::AtomicUsize>) -> Self {
+        queued.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        Self(queued.clone())
+    }
+}
+
+impl Drop for QueuedGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, std::sync::atomic::Ordering::Relaxed);
+    }
 }
 
 unsafe impl Send for BlockingTask {}
 
 struct BlockingTaskVtable {
-    pub(crate) drop: unsafe fn(&mut crate::task::Task<NoopScheduler>),
+    pub(crate) fail: unsafe fn(&mut crate::task::Task<NoopScheduler>, JoinError),
 }
 
 fn blocking_vtable<V>() -> &'static BlockingTaskVtable {
     &BlockingTaskVtable {
-        drop: blocking_task_drop::<V>,
+        fail: blocking_task_fail::<V>,
     }
 }
 
-fn blocking_task_drop<V>(task: &mut crate::task::Task<NoopScheduler>) {
-    let mut opt: Option<Result<V, JoinError>> = Some(Err(JoinError::Canceled));
+fn blocking_task_fail<V>(task: &mut crate::task::Task<NoopScheduler>, err: JoinError) {
+    let mut opt: Option<Result<V, JoinError>> = Some(Err(err));
     unsafe { task.finish((&mut opt) as *mut _ as *mut ()) };
 }
 
 impl Drop for BlockingTask {
     fn drop(&mut self) {
         if let Some(task) = self.task.as_mut() {
-            unsafe { (self.blocking_vtable.drop)(task) };
+            unsafe { (self.blocking_vtable.fail)(task, JoinError::Canceled) };
         }
     }
 }
 
 impl BlockingTask {
-    /// Run task.
+    /// Run task. If it is aborted with its `JoinHandle` before, it is canceled instead.
     #[inline]
     pub fn run(mut self) {
+        self.queued.take();
+        if self.task.as_ref().is_some_and(|task| task.is_cancelled()) {
+            // Dropped as canceled.
+            return;
+        }
         let task = self.task.take().unwrap();
         task.run();
         // // if we are within a runtime, just run it.
@@ -87,11 +131,58 @@ pub enum BlockingStrategy {
 /// `spawn_blocking` is used for executing a task(without async) with heavy computation or blocking
 /// io.
 ///
-/// To used it, users may initialize a thread pool and attach it on creating runtime.
+/// To used it, users may initialize a thread pool and attach it on creating runtime, e.g. a
+/// [`SharedThreadPool`] shared by the runtimes in the process.
 /// Users can also set `BlockingStrategy` for a runtime when there is no thread pool.
+///
+/// The task can be canceled with [`JoinHandle::abort`] before it starts, and the handle returns
+/// [`JoinError::Canceled`] then. It runs to the end once started.
 /// WARNING: DO NOT USE THIS FOR ASYNC TASK! Async tasks will not be executed but only built the
 /// future!
 pub fn spawn_blocking<F, R>(func: F) -> JoinHandle<Result<R, JoinError>>
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: Send + 'static,
+{
+    match spawn_blocking_inner(func, false) {
+        Ok(join) => join,
+        Err(_) => unreachable!(),
+    }
+}
+
+/// Like [`spawn_blocking`], but returns [`RejectedError`] if the attached thread pool can not
+/// accept the task, e.g. the queue of a [`SharedThreadPool`] is full, so the callers can shed
+/// the load or retry later.
+///
+/// # Examples
+///
+/// ```
+/// use monoio::blocking::{try_spawn_blocking, SharedThreadPool};
+///
+/// let pool = SharedThreadPool::builder().queue_bound(16).build().unwrap();
+/// let mut rt = monoio::RuntimeBuilder::<monoio::FusionDriver>::new()
+///     .attach_thread_pool(Box::new(pool))
+///     .build()
+///     .unwrap();
+/// rt.block_on(async {
+///     match try_spawn_blocking(|| 1) {
+///         Ok(join) => assert_eq!(join.await.unwrap(), 1),
+///         Err(e) => eprintln!("{e}"),
+///     }
+/// });
+/// ```
+pub fn try_spawn_blocking<F, R>(func: F) -> Result<JoinHandle<Result<R, JoinError>>, RejectedError>
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: Send + 'static,
+{
+    spawn_blocking_inner(func, true)
+}
+
+fn spawn_blocking_inner<F, R>(
+    func: F,
+    try_schedule: bool,
+) -> Result<JoinHandle<Result<R, JoinError>>, RejectedError>
 where
     F: FnOnce() -> R + Send + 'static,
     R: Send + 'static,
@@ -100,11 +191,23 @@ where
     let (task, join) = new_task(DEFAULT_THREAD_ID, fut, NoopScheduler);
     crate::runtime::CURRENT.with(|inner| {
         let handle = &inner.blocking_handle;
+        trace_event!(
+            attached = matches!(handle, BlockingHandle::Attached(_)),
+            "blocking task dispatched"
+        );
         match handle {
-            BlockingHandle::Attached(shared) => shared.schedule_task(BlockingTask {
-                task: Some(task),
-                blocking_vtable: blocking_vtable::<R>(),
-            }),
+            BlockingHandle::Attached(shared) => {
+                let task = BlockingTask {
+                    task: Some(task),
+                    blocking_vtable: blocking_vtable::<R>(),
+                    queued: Some(QueuedGuard::new(&inner.blocking_queued)),
+                };
+                if !try_schedule {
+                    shared.schedule_task(task);
+                } else if shared.try_schedule_task(task).is_err() {
+                    return Err(RejectedError);
+                }
+            }
             BlockingHandle::Empty(BlockingStrategy::ExecuteLocal) => task.run(),
             BlockingHandle::Empty(BlockingStrategy::Panic) => {
                 // For users: if you see this panic, you have 2 choices:
@@ -115,9 +218,21 @@ where
                 panic!("execute blocking task without thread pool attached")
             }
         }
-    });
+        Ok(())
+    })?;
+
+    Ok(join)
+}
 
-    join
+/// Whether [`spawn_blocking`] runs the task instead of panicking on current runtime.
+#[cfg(feature = "sync")]
+pub(crate) fn can_spawn_blocking() -> bool {
+    crate::runtime::CURRENT.with(|inner| {
+        !matches!(
+            inner.blocking_handle,
+            BlockingHandle::Empty(BlockingStrategy::Panic)
+        )
+    })
 }
 
 /// DefaultThreadPool is a simple wrapped `threadpool::ThreadPool` that implement
@@ -145,6 +260,262 @@ impl ThreadPool for DefaultThreadPool {
     }
 }
 
+/// A thread pool for the blocking tasks, which is shared by all the runtimes in the process it
+/// is attached to, e.g. one runtime per core. It is cheap to clone, and the threads exit after
+/// all the clones are dropped and the queued tasks are done.
+///
+/// It keeps `min_threads` threads, and spawns more threads up to `max_threads` when there are
+/// queued tasks and no idle thread. The threads above `min_threads` exit after being idle for
+/// `keep_alive`. If the queue is bounded, the tasks scheduled when it is full are rejected:
+/// [`try_spawn_blocking`] returns [`RejectedError`], and the `JoinHandle`s of [`spawn_blocking`]
+/// return [`JoinError::Canceled`] immediately. The tasks are rejected the same way if no thread
+/// is alive and spawning one fails.
+///
+/// ```
+/// use monoio::blocking::SharedThreadPool;
+///
+/// let pool = SharedThreadPool::builder()
+///     .max_threads(4)
+///     .queue_bound(1024)
+///     .build()
+///     .unwrap();
+/// let threads: Vec<_> = (0..2)
+///     .map(|_| {
+///         let pool = pool.clone();
+///         std::thread::spawn(move || {
+///             let mut rt = monoio::RuntimeBuilder::<monoio::FusionDriver>::new()
+///                 .attach_thread_pool(Box::new(pool))
+///                 .build()
+///                 .unwrap();
+///             rt.block_on(async { monoio::spawn_blocking(|| 1).await.unwrap() })
+///         })
+///     })
+///     .collect();
+/// for thread in threads {
+///     assert_eq!(thread.join().unwrap(), 1);
+/// }
+/// ```
+#[derive(Clone)]
+pub struct SharedThreadPool {
+    handle: std::sync::Arc<PoolHandle>,
+}
+
+/// Builder of [`SharedThreadPool`].
+#[derive(Debug, Clone)]
+pub struct SharedThreadPoolBuilder {
+    min_threads: usize,
+    max_threads: usize,
+    keep_alive: std::time::Duration,
+    queue_bound: Option<usize>,
+    thread_name: String,
+}
+
+impl Default for SharedThreadPoolBuilder {
+    fn default() -> Self {
+        Self {
+            min_threads: 0,
+            max_threads: 512,
+            keep_alive: std::time::Duration::from_secs(10),
+            queue_bound: None,
+            thread_name: "monoio-blocking".into(),
+        }
+    }
+}
+
+impl SharedThreadPoolBuilder {
+    /// Create a builder with the default settings: no thread is kept, at most 512 threads, the
+    /// idle threads exit after 10 seconds, and the queue is unbounded.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Threads kept even if they are idle, which are spawned on building.
+    #[must_use]
+    pub fn min_threads(mut self, n: usize) -> Self {
+        self.min_threads = n;
+        self
+    }
+
+    /// Max threads of the pool, at least 1.
+    #[must_use]
+    pub fn max_threads(mut self, n: usize) -> Self {
+        self.max_threads = n.max(1);
+        self
+    }
+
+    /// How long the threads above `min_threads` wait for new tasks before exiting.
+    #[must_use]
+    pub fn keep_alive(mut self, keep_alive: std::time::Duration) -> Self {
+        self.keep_alive = keep_alive;
+        self
+    }
+
+    /// Max tasks waiting for the threads, the tasks scheduled beyond it are rejected.
+    ///
+    /// [`try_spawn_blocking`] reports the rejection, [`spawn_blocking`] cancels the task.
+    #[must_use]
+    pub fn queue_bound(mut self, n: usize) -> Self {
+        self.queue_bound = Some(n);
+        self
+    }
+
+    /// Name of the threads.
+    #[must_use]
+    pub fn thread_name(mut self, name: impl Into<String>) -> Self {
+        self.thread_name = name.into();
+        self
+    }
+
+    /// Build the pool and spawn the `min_threads` threads.
+    pub fn build(self) -> std::io::Result<SharedThreadPool> {
+        let max_threads = self.max_threads.max(self.min_threads);
+        let inner = std::sync::Arc::new(PoolInner {
+            state: std::sync::Mutex::new(PoolState {
+                queue: std::collections::VecDeque::new(),
+                threads: 0,
+                idle: 0,
+                shutdown: false,
+            }),
+            condvar: std::sync::Condvar::new(),
+            min_threads: self.min_threads,
+            max_threads,
+            keep_alive: self.keep_alive,
+            queue_bound: self.queue_bound,
+            thread_name: self.thread_name,
+        });
+        {
+            let mut state = inner.state.lock().unwrap();
+            for _ in 0..inner.min_threads {
+                inner.spawn_thread(&mut state)?;
+            }
+        }
+        Ok(SharedThreadPool {
+            handle: std::sync::Arc::new(PoolHandle(inner)),
+        })
+    }
+}
+
+impl SharedThreadPool {
+    /// Create a builder of the pool.
+    pub fn builder() -> SharedThreadPoolBuilder {
+        SharedThreadPoolBuilder::new()
+    }
+
+    /// Threads alive in the pool.
+    pub fn num_threads(&self) -> usize {
+        self.handle.0.state.lock().unwrap().threads
+    }
+
+    /// Tasks waiting for the threads.
+    pub fn queued_tasks(&self) -> usize {
+        self.handle.0.state.lock().unwrap().queue.len()
+    }
+}
+
+impl ThreadPool for SharedThreadPool {
+    fn schedule_task(&self, task: BlockingTask) {
+        // The rejected task is dropped, so its `JoinHandle` returns `JoinError::Canceled`.
+        let _ = self.try_schedule_task(task);
+    }
+
+    fn try_schedule_task(&self, task: BlockingTask) -> Result<(), BlockingTask> {
+        let inner = &self.handle.0;
+        let mut state = inner.state.lock().unwrap();
+        if inner
+            .queue_bound
+            .is_some_and(|bound| state.queue.len() >= bound)
+        {
+            return Err(task);
+        }
+        state.queue.push_back(task);
+        if state.queue.len() > state.idle
+            && state.threads < inner.max_threads
+            && inner.spawn_thread(&mut state).is_err()
+            && state.threads == 0
+        {
+            // The task stays queued for the other threads if it fails, but there is none.
+            return Err(state.queue.pop_back().unwrap());
+        }
+        if state.idle > 0 {
+            inner.condvar.notify_one();
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Debug for SharedThreadPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = self.handle.0.state.lock().unwrap();
+        f.debug_struct("SharedThreadPool")
+            .field("threads", &state.threads)
+            .field("idle", &state.idle)
+            .field("queued", &state.queue.len())
+            .finish()
+    }
+}
+
+/// Shuts down the pool when all the clones are dropped, the threads hold the inner only.
+struct PoolHandle(std::sync::Arc<PoolInner>);
+
+impl Drop for PoolHandle {
+    fn drop(&mut self) {
+        self.0.state.lock().unwrap().shutdown = true;
+        self.0.condvar.notify_all();
+    }
+}
+
+struct PoolInner {
+    state: std::sync::Mutex<PoolState>,
+    condvar: std::sync::Condvar,
+    min_threads: usize,
+    max_threads: usize,
+    keep_alive: std::time::Duration,
+    queue_bound: Option<usize>,
+    thread_name: String,
+}
+
+struct PoolState {
+    queue: std::collections::VecDeque<BlockingTask>,
+    threads: usize,
+    idle: usize,
+    shutdown: bool,
+}
+
+impl PoolInner {
+    fn spawn_thread(self: &std::sync::Arc<Self>, state: &mut PoolState) -> std::io::Result<()> {
+        let inner = self.clone();
+        std::thread::Builder::new()
+            .name(self.thread_name.clone())
+            .spawn(move || inner.run())?;
+        state.threads += 1;
+        Ok(())
+    }
+
+    fn run(&self) {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            if let Some(task) = state.queue.pop_front() {
+                drop(state);
+                // Keep the thread for the other tasks, the panic is reported by the hook.
+                let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| task.run()));
+                state = self.state.lock().unwrap();
+                continue;
+            }
+            if state.shutdown {
+                break;
+            }
+            state.idle += 1;
+            let (next, timeout) = self.condvar.wait_timeout(state, self.keep_alive).unwrap();
+            state = next;
+            state.idle -= 1;
+            if timeout.timed_out() && state.queue.is_empty() && state.threads > self.min_threads {
+                break;
+            }
+        }
+        state.threads -= 1;
+    }
+}
+
 pub(crate) struct NoopScheduler;
 
 impl crate::task::Schedule for NoopScheduler {
@@ -324,4 +695,82 @@ mod tests {
             assert_eq!(result6.unwrap(), "hello spawn_blocking6!");
         });
     }
+
+    #[test]
+    fn shared_pool_threads() {
+        let pool = super::SharedThreadPool::builder()
+            .min_threads(1)
+            .max_threads(2)
+            .keep_alive(std::time::Duration::from_millis(50))
+            .build()
+            .unwrap();
+        assert_eq!(pool.num_threads(), 1);
+        let mut rt = crate::RuntimeBuilder::<crate::FusionDriver>::new()
+            .attach_thread_pool(Box::new(pool.clone()))
+            .build()
+            .unwrap();
+        rt.block_on(async {
+            let joins: Vec<_> = (0..4)
+                .map(|i| {
+                    crate::spawn_blocking(move || {
+                        std::thread::sleep(std::time::Duration::from_millis(50));
+                        i
+                    })
+                })
+                .collect();
+            for (i, join) in joins.into_iter().enumerate() {
+                assert_eq!(join.await.unwrap(), i);
+            }
+        });
+        assert!(pool.num_threads() <= 2);
+        // The threads above `min_threads` exit after the keep-alive.
+        std::thread::sleep(std::time::Duration::from_millis(300));
+        assert_eq!(pool.num_threads(), 1);
+    }
+
+    #[test]
+    fn shared_pool_reject_and_abort() {
+        use std::sync::{
+            atomic::{AtomicBool, Ordering},
+            mpsc, Arc,
+        };
+
+        let pool = super::SharedThreadPool::builder()
+            .max_threads(1)
+            .queue_bound(1)
+            .build()
+            .unwrap();
+        let mut rt = crate::RuntimeBuilder::<crate::FusionDriver>::new()
+            .attach_thread_pool(Box::new(pool.clone()))
+            .build()
+            .unwrap();
+        rt.block_on(async {
+            let (started_tx, started_rx) = mpsc::channel();
+            let (release_tx, release_rx) = mpsc::channel::<()>();
+            let running = crate::spawn_blocking(move || {
+                started_tx.send(()).unwrap();
+                release_rx.recv().unwrap();
+            });
+            started_rx.recv().unwrap();
+
+            let ran = Arc::new(AtomicBool::new(false));
+            let ran_ = ran.clone();
+            let queued = crate::spawn_blocking(move || ran_.store(true, Ordering::Relaxed));
+            assert_eq!(pool.queued_tasks(), 1);
+            // The queue is full.
+            assert_eq!(
+                super::try_spawn_blocking(|| ()).err(),
+                Some(super::RejectedError)
+            );
+            let rejected = crate::spawn_blocking(|| ());
+            assert!(matches!(rejected.await, Err(super::JoinError::Canceled)));
+
+            // Canceled since it does not start yet.
+            queued.abort();
+            release_tx.send(()).unwrap();
+            running.await.unwrap();
+            assert!(matches!(queued.await, Err(super::JoinError::Canceled)));
+            assert!(!ran.load(Ordering::Relaxed));
+        });
+    }
 }
@@ -0,0 +1,195 @@
+use std::{
+    alloc::{alloc, dealloc, handle_alloc_error, Layout},
+    fmt,
+    ops::{Deref, DerefMut},
+    ptr::NonNull,
+};
+
+use super::{IoBuf, IoBufMut};
+
+/// A buffer with its memory aligned to a given alignment, which is required by the IO on files
+/// opened with `O_DIRECT`(see [`OpenOptions::direct`]).
+///
+/// With direct IO, the address and the length of the buffer, and the offset of the file must
+/// usually be aligned to the logical block size of the device(often 512 or 4096 bytes).
+///
+/// [`OpenOptions::direct`]: crate::fs::OpenOptions::direct
+pub struct AlignedBuf {
+    ptr: NonNull<u8>,
+    len: usize,
+    layout: Layout,
+}
+
+// Safety: the buffer owns the memory like `Vec<u8>`.
+unsafe impl Send for AlignedBuf {}
+unsafe impl Sync for AlignedBuf {}
+
+impl AlignedBuf {
+    /// The default alignment, which is the page size on most platforms.
+    pub const DEFAULT_ALIGN: usize = 4096;
+
+    /// Create an empty buffer of `capacity` bytes aligned to
+    /// [`DEFAULT_ALIGN`](Self::DEFAULT_ALIGN).
+    #[inline]
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::new(capacity, Self::DEFAULT_ALIGN)
+    }
+
+    /// Create an empty buffer of `capacity` bytes aligned to `align`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is not a power of two, or `capacity` overflows `isize` when rounded up
+    /// to `align`.
+    pub fn new(capacity: usize, align: usize) -> Self {
+        let layout = Layout::from_size_align(capacity, align)
+            .expect("invalid capacity or alignment of AlignedBuf");
+        let ptr = if capacity == 0 {
+            // A dangling but aligned pointer, like `Vec` does.
+            unsafe { NonNull::new_unchecked(align as *mut u8) }
+        } else {
+            // Safety: the size of the layout is not zero.
+            NonNull::new(unsafe { alloc(layout) }).unwrap_or_else(|| handle_alloc_error(layout))
+        };
+        Self {
+            ptr,
+            len: 0,
+            layout,
+        }
+    }
+
+    /// Create a buffer of `len` zeros aligned to `align`.
+    pub fn zeroed(len: usize, align: usize) -> Self {
+        let mut buf = Self::new(len, align);
+        // Safety: the capacity is `len`.
+        unsafe {
+            buf.ptr.as_ptr().write_bytes(0, len);
+            buf.len = len;
+        }
+        buf
+    }
+
+    /// Returns the number of initialized bytes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if there is no initialized bytes.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of bytes the buffer can hold.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.layout.size()
+    }
+
+    /// Returns the alignment of the buffer.
+    #[inline]
+    pub fn align(&self) -> usize {
+        self.layout.align()
+    }
+
+    /// Shortens the initialized bytes to `len`. It has no effect if `len` is greater than the
+    /// current length.
+    #[inline]
+    pub fn truncate(&mut self, len: usize) {
+        self.len = self.len.min(len);
+    }
+
+    /// Clears the initialized bytes, the capacity is kept.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Appends the bytes of `data`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the remaining capacity is less than the length of `data`, since the buffer can
+    /// not grow without losing the alignment.
+    pub fn extend_from_slice(&mut self, data: &[u8]) {
+        assert!(
+            data.len() <= self.capacity() - self.len,
+            "AlignedBuf capacity exceeded"
+        );
+        // Safety: the remaining capacity is checked.
+        unsafe {
+            self.ptr
+                .as_ptr()
+                .add(self.len)
+                .copy_from_nonoverlapping(data.as_ptr(), data.len());
+        }
+        self.len += data.len();
+    }
+}
+
+impl Drop for AlignedBuf {
+    fn drop(&mut self) {
+        if self.layout.size() != 0 {
+            // Safety: the memory is allocated with the layout.
+            unsafe { dealloc(self.ptr.as_ptr(), self.layout) };
+        }
+    }
+}
+
+impl Deref for AlignedBuf {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        // Safety: the first `len` bytes are initialized.
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl DerefMut for AlignedBuf {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [u8] {
+        // Safety: the first `len` bytes are initialized.
+        unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl fmt::Debug for AlignedBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AlignedBuf")
+            .field("len", &self.len)
+            .field("capacity", &self.capacity())
+            .field("align", &self.align())
+            .finish()
+    }
+}
+
+unsafe impl IoBuf for AlignedBuf {
+    #[inline]
+    fn read_ptr(&self) -> *const u8 {
+        self.ptr.as_ptr()
+    }
+
+    #[inline]
+    fn bytes_init(&self) -> usize {
+        self.len
+    }
+}
+
+unsafe impl IoBufMut for AlignedBuf {
+    #[inline]
+    fn write_ptr(&mut self) -> *mut u8 {
+        self.ptr.as_ptr()
+    }
+
+    #[inline]
+    fn bytes_total(&mut self) -> usize {
+        self.capacity()
+    }
+
+    #[inline]
+    unsafe fn set_init(&mut self, init_len: usize) {
+        self.len = init_len;
+    }
+}
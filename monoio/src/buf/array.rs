@@ -0,0 +1,170 @@
+use std::{
+    fmt,
+    ops::{Deref, DerefMut},
+};
+
+use super::{IoBuf, IoBufMut};
+
+/// A buffer of `N` bytes with the length of the initialized bytes tracked, for the small
+/// fixed-size reads and writes(e.g. headers and length prefixes).
+///
+/// Unlike `Vec<u8>`, the size is encoded in the type, and it never grows. The array is boxed,
+/// since the memory of a buffer passed to the kernel must not move while the operation is in
+/// flight.
+///
+/// # Examples
+///
+/// ```
+/// use monoio::buf::ArrayBuf;
+///
+/// let mut header = ArrayBuf::<8>::new();
+/// header.extend_from_slice(&42u32.to_be_bytes());
+/// assert_eq!(header.len(), 4);
+/// assert_eq!(ArrayBuf::<8>::CAPACITY, 8);
+/// ```
+pub struct ArrayBuf<const N: usize> {
+    buf: Box<[u8; N]>,
+    len: usize,
+}
+
+impl<const N: usize> ArrayBuf<N> {
+    /// The capacity of the buffer.
+    pub const CAPACITY: usize = N;
+
+    /// Create an empty buffer.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            buf: Box::new([0; N]),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of initialized bytes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if there is no initialized bytes.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns true if all the bytes are initialized.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Clears the initialized bytes.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Appends the bytes of `data`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the remaining capacity is less than the length of `data`.
+    pub fn extend_from_slice(&mut self, data: &[u8]) {
+        assert!(data.len() <= N - self.len, "ArrayBuf capacity exceeded");
+        self.buf[self.len..self.len + data.len()].copy_from_slice(data);
+        self.len += data.len();
+    }
+
+    /// Returns the whole array, including the bytes not initialized by reading(which are
+    /// zeros or stale data).
+    #[inline]
+    pub fn as_array(&self) -> &[u8; N] {
+        &self.buf
+    }
+
+    /// Consumes the buffer, returning the boxed array.
+    #[inline]
+    pub fn into_inner(self) -> Box<[u8; N]> {
+        self.buf
+    }
+}
+
+impl<const N: usize> Default for ArrayBuf<N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for ArrayBuf<N> {
+    /// Create a full buffer from the array.
+    #[inline]
+    fn from(array: [u8; N]) -> Self {
+        Self {
+            buf: Box::new(array),
+            len: N,
+        }
+    }
+}
+
+impl<const N: usize> From<Box<[u8; N]>> for ArrayBuf<N> {
+    /// Create a full buffer from the boxed array.
+    #[inline]
+    fn from(buf: Box<[u8; N]>) -> Self {
+        Self { buf, len: N }
+    }
+}
+
+impl<const N: usize> Deref for ArrayBuf<N> {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+}
+
+impl<const N: usize> DerefMut for ArrayBuf<N> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut [u8] {
+        &mut self.buf[..self.len]
+    }
+}
+
+impl<const N: usize> fmt::Debug for ArrayBuf<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArrayBuf")
+            .field("data", &&self[..])
+            .field("capacity", &N)
+            .finish()
+    }
+}
+
+unsafe impl<const N: usize> IoBuf for ArrayBuf<N> {
+    #[inline]
+    fn read_ptr(&self) -> *const u8 {
+        self.buf.as_ptr()
+    }
+
+    #[inline]
+    fn bytes_init(&self) -> usize {
+        self.len
+    }
+}
+
+unsafe impl<const N: usize> IoBufMut for ArrayBuf<N> {
+    #[inline]
+    fn write_ptr(&mut self) -> *mut u8 {
+        self.buf.as_mut_ptr()
+    }
+
+    #[inline]
+    fn bytes_total(&mut self) -> usize {
+        N
+    }
+
+    #[inline]
+    unsafe fn set_init(&mut self, init_len: usize) {
+        self.len = init_len;
+    }
+}
@@ -0,0 +1,226 @@
+//! Provided buffer ring.
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+use std::{
+    alloc::{alloc_zeroed, dealloc, Layout},
+    cell::Cell,
+    sync::atomic::{AtomicU16, Ordering},
+};
+use std::{cell::RefCell, io};
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+use io_uring::types::BufRingEntry;
+
+use super::provided::{alloc_bufs, Backend, BufGroup};
+
+/// A ring of provided buffers.
+///
+/// With io_uring driver, the ring is registered with `IORING_REGISTER_PBUF_RING`(requires kernel
+/// 5.19+). Compared to [`BufGroup`] created by [`BufGroup::new`], giving back a buffer to the
+/// ring only needs a memory write instead of submitting an operation. With legacy driver, the
+/// buffers are picked in userspace.
+///
+/// It can be passed to the operations accepting a buffer group, and the buffers they return
+/// are given back to the ring when dropped.
+///
+/// # Examples
+///
+/// ```no_run
+/// use monoio::{buf::BufRing, io::stream::Stream, net::TcpStream};
+///
+/// async fn recv(stream: &TcpStream) -> std::io::Result<()> {
+///     let ring = BufRing::builder(0).ring_entries(64).buf_len(4096).build()?;
+///     let mut incoming = stream.recv_multi(&ring)?;
+///     while let Some(buf) = incoming.next().await {
+///         println!("received {} bytes", buf?.len());
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct BufRing {
+    group: BufGroup,
+}
+
+impl BufRing {
+    /// Create a builder with the given buffer group id.
+    #[inline]
+    pub fn builder(bgid: u16) -> BufRingBuilder {
+        BufRingBuilder::new(bgid)
+    }
+
+    /// Buffer group id.
+    #[inline]
+    pub fn bgid(&self) -> u16 {
+        self.group.bgid()
+    }
+
+    /// Length of each buffer.
+    #[inline]
+    pub fn buf_len(&self) -> usize {
+        self.group.buf_len()
+    }
+
+    /// Count of ring entries, which is also the count of buffers.
+    #[inline]
+    pub fn ring_entries(&self) -> u16 {
+        self.group.buf_cnt()
+    }
+}
+
+impl AsRef<BufGroup> for BufRing {
+    #[inline]
+    fn as_ref(&self) -> &BufGroup {
+        &self.group
+    }
+}
+
+/// Builder for [`BufRing`].
+#[derive(Debug, Clone)]
+pub struct BufRingBuilder {
+    bgid: u16,
+    ring_entries: u16,
+    buf_len: usize,
+}
+
+impl BufRingBuilder {
+    const DEFAULT_RING_ENTRIES: u16 = 128;
+    const DEFAULT_BUF_LEN: usize = 4096;
+    const MAX_RING_ENTRIES: u16 = 32768;
+
+    /// Create a builder with the given buffer group id.
+    pub fn new(bgid: u16) -> Self {
+        Self {
+            bgid,
+            ring_entries: Self::DEFAULT_RING_ENTRIES,
+            buf_len: Self::DEFAULT_BUF_LEN,
+        }
+    }
+
+    /// Set ring entries, which must be a power of 2 and no more than 32768. The default value
+    /// is 128.
+    #[must_use]
+    pub fn ring_entries(mut self, ring_entries: u16) -> Self {
+        self.ring_entries = ring_entries;
+        self
+    }
+
+    /// Set the length of each buffer. The default value is 4096.
+    #[must_use]
+    pub fn buf_len(mut self, buf_len: usize) -> Self {
+        self.buf_len = buf_len;
+        self
+    }
+
+    /// Build the ring and register it to the current runtime.
+    ///
+    /// # Panics
+    ///
+    /// Panics if it is called outside of a monoio runtime.
+    pub fn build(self) -> io::Result<BufRing> {
+        let Self {
+            bgid,
+            ring_entries,
+            buf_len,
+        } = self;
+        if !ring_entries.is_power_of_two() || ring_entries > Self::MAX_RING_ENTRIES {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "ring entries must be a power of 2 and no more than 32768",
+            ));
+        }
+        let mem = alloc_bufs(ring_entries, buf_len)?;
+
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        if !crate::driver::op::is_legacy() {
+            let ring = match RingState::register(bgid, ring_entries) {
+                Ok(ring) => ring,
+                Err(e) => {
+                    unsafe { super::provided::dealloc_bufs(mem, ring_entries, buf_len) };
+                    return Err(e);
+                }
+            };
+            for bid in 0..ring_entries {
+                ring.push(bid, unsafe { mem.add(bid as usize * buf_len) }, buf_len);
+            }
+            return Ok(BufRing {
+                group: BufGroup::from_parts(bgid, ring_entries, buf_len, mem, Backend::Ring(ring)),
+            });
+        }
+
+        let free = RefCell::new((0..ring_entries).rev().collect());
+        Ok(BufRing {
+            group: BufGroup::from_parts(bgid, ring_entries, buf_len, mem, Backend::Userspace(free)),
+        })
+    }
+}
+
+/// Shared memory ring registered to the kernel.
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+pub(crate) struct RingState {
+    ring: *mut BufRingEntry,
+    mask: u16,
+    // Local tail which is published to the kernel after every push.
+    tail: Cell<u16>,
+}
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+impl RingState {
+    const PAGE_SIZE: usize = 4096;
+
+    fn layout(ring_entries: u16) -> Layout {
+        let size = ring_entries as usize * std::mem::size_of::<BufRingEntry>();
+        unsafe { Layout::from_size_align_unchecked(size, Self::PAGE_SIZE) }
+    }
+
+    fn register(bgid: u16, ring_entries: u16) -> io::Result<Self> {
+        let layout = Self::layout(ring_entries);
+        let ring = unsafe { alloc_zeroed(layout) } as *mut BufRingEntry;
+        if ring.is_null() {
+            return Err(io::ErrorKind::OutOfMemory.into());
+        }
+        // Safety: the ring memory is valid until it is unregistered.
+        let res = crate::driver::with_uring_submitter(|s| unsafe {
+            s.register_buf_ring(ring as u64, ring_entries, bgid)
+        })
+        .unwrap_or_else(|| Err(io::ErrorKind::Unsupported.into()));
+        if let Err(e) = res {
+            unsafe { dealloc(ring as *mut u8, layout) };
+            return Err(e);
+        }
+        Ok(Self {
+            ring,
+            mask: ring_entries - 1,
+            tail: Cell::new(0),
+        })
+    }
+
+    /// Give a buffer to the kernel.
+    pub(crate) fn push(&self, bid: u16, addr: *mut u8, len: usize) {
+        let tail = self.tail.get();
+        unsafe {
+            let entry = &mut *self.ring.add((tail & self.mask) as usize);
+            entry.set_addr(addr as u64);
+            entry.set_len(len as u32);
+            entry.set_bid(bid);
+        }
+        let tail = tail.wrapping_add(1);
+        self.tail.set(tail);
+        // Safety: the tail lives in the first entry of the ring, which is shared with the kernel.
+        unsafe {
+            let tail_ptr = BufRingEntry::tail(self.ring) as *const AtomicU16;
+            (*tail_ptr).store(tail, Ordering::Release);
+        }
+    }
+
+    /// Unregister the ring and free the ring memory. Returns false if the ring is not
+    /// unregistered, in which case the memory is leaked since the kernel may still use it.
+    pub(crate) fn unregister(&self, bgid: u16) -> bool {
+        let unregistered = crate::driver::with_uring_submitter(|s| s.unregister_buf_ring(bgid))
+            .is_some_and(|res| res.is_ok());
+        if unregistered {
+            unsafe { dealloc(self.ring as *mut u8, Self::layout(self.mask + 1)) };
+        }
+        unregistered
+    }
+}
@@ -0,0 +1,400 @@
+//! Registered (fixed) buffers.
+//!
+//! Registered buffers are mapped into the kernel once with `IORING_REGISTER_BUFFERS`, so the
+//! operations using them(`READ_FIXED` and `WRITE_FIXED`) do not need to pin and unpin the pages
+//! on every call.
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    future::Future,
+    io,
+    ops::{Deref, DerefMut},
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+use super::{
+    provided::{alloc_bufs, dealloc_bufs},
+    IoBuf, IoBufMut,
+};
+
+/// A pool of buffers registered to the io_uring instance of the current runtime.
+///
+/// Buffers are checked out with [`FixedBufPool::try_next`] or [`FixedBufPool::next`] and
+/// returned to the pool when the [`FixedBuf`] is dropped. The pool is cheap to clone; all clones
+/// share the same buffers.
+///
+/// An io_uring instance can only hold one registered buffer table at a time, so creating a
+/// second pool while another is alive in the same runtime fails with `EBUSY`. With legacy
+/// driver the buffers are not registered, and the fixed operations fall back to the normal ones.
+#[derive(Clone)]
+pub struct FixedBufPool {
+    inner: Rc<PoolInner>,
+}
+
+pub(super) struct PoolInner {
+    buf_len: usize,
+    buf_cnt: u16,
+    mem: *mut u8,
+    free: RefCell<Vec<u16>>,
+    registered: bool,
+    /// Tasks waiting for a free buffer, with the id of their `Checkout`.
+    waiters: RefCell<VecDeque<(u64, Waker)>>,
+    next_waiter: Cell<u64>,
+}
+
+impl FixedBufPool {
+    /// Create a pool with `buf_cnt` buffers of `buf_len` bytes and register them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if it is called outside of a monoio runtime.
+    pub fn new(buf_cnt: u16, buf_len: usize) -> io::Result<Self> {
+        Ok(Self {
+            inner: PoolInner::new(buf_cnt, buf_len, true)?,
+        })
+    }
+
+    /// Check out a free buffer. Returns `None` if all buffers are in use.
+    ///
+    /// The returned buffer is empty; its capacity is [`FixedBufPool::buf_len`].
+    #[inline]
+    pub fn try_next(&self) -> Option<FixedBuf> {
+        self.inner.try_checkout()
+    }
+
+    /// Check out a free buffer, waiting for one to be given back if all buffers are in use.
+    /// The waiting tasks get the buffers in order.
+    #[inline]
+    pub fn next(&self) -> impl Future<Output = FixedBuf> + '_ {
+        Checkout::new(&self.inner)
+    }
+
+    /// Length of each buffer.
+    #[inline]
+    pub fn buf_len(&self) -> usize {
+        self.inner.buf_len()
+    }
+
+    /// Count of buffers.
+    #[inline]
+    pub fn buf_cnt(&self) -> u16 {
+        self.inner.buf_cnt()
+    }
+
+    /// Count of buffers not checked out.
+    #[inline]
+    pub fn available(&self) -> usize {
+        self.inner.available()
+    }
+
+    /// If the buffers are registered to the kernel.
+    #[inline]
+    pub fn is_registered(&self) -> bool {
+        self.inner.registered()
+    }
+}
+
+impl PoolInner {
+    /// Allocate `buf_cnt` buffers of `buf_len` bytes, and register them if `register` is true
+    /// and the current runtime is io_uring.
+    pub(super) fn new(buf_cnt: u16, buf_len: usize, register: bool) -> io::Result<Rc<Self>> {
+        let mem = alloc_bufs(buf_cnt, buf_len)?;
+        #[allow(unused_mut)]
+        let mut registered = false;
+
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        if register {
+            let iovecs: Vec<libc::iovec> = (0..buf_cnt as usize)
+                .map(|i| libc::iovec {
+                    iov_base: unsafe { mem.add(i * buf_len) } as _,
+                    iov_len: buf_len,
+                })
+                .collect();
+            // Safety: the memory is valid until the buffers are unregistered.
+            let res =
+                crate::driver::with_uring_submitter(|s| unsafe { s.register_buffers(&iovecs) });
+            match res {
+                Some(Ok(())) => registered = true,
+                Some(Err(e)) => {
+                    unsafe { dealloc_bufs(mem, buf_cnt, buf_len) };
+                    return Err(e);
+                }
+                None => (),
+            }
+        }
+
+        #[cfg(not(all(target_os = "linux", feature = "iouring")))]
+        let _ = register;
+
+        Ok(Rc::new(PoolInner {
+            buf_len,
+            buf_cnt,
+            mem,
+            free: RefCell::new((0..buf_cnt).rev().collect()),
+            registered,
+            waiters: RefCell::new(VecDeque::new()),
+            next_waiter: Cell::new(0),
+        }))
+    }
+
+    #[inline]
+    fn buf_ptr(&self, index: u16) -> *mut u8 {
+        unsafe { self.mem.add(index as usize * self.buf_len) }
+    }
+
+    #[inline]
+    pub(super) fn buf_len(&self) -> usize {
+        self.buf_len
+    }
+
+    #[inline]
+    pub(super) fn buf_cnt(&self) -> u16 {
+        self.buf_cnt
+    }
+
+    #[inline]
+    pub(super) fn available(&self) -> usize {
+        self.free.borrow().len()
+    }
+
+    #[inline]
+    pub(super) fn registered(&self) -> bool {
+        self.registered
+    }
+
+    pub(super) fn try_checkout(self: &Rc<Self>) -> Option<FixedBuf> {
+        let index = self.free.borrow_mut().pop()?;
+        Some(FixedBuf {
+            pool: self.clone(),
+            index,
+            len: 0,
+        })
+    }
+
+    /// Wake the first waiting task if there is a free buffer.
+    fn wake_next(&self) {
+        if self.free.borrow().is_empty() {
+            return;
+        }
+        let waiter = self.waiters.borrow_mut().pop_front();
+        if let Some((_, waker)) = waiter {
+            waker.wake();
+        }
+    }
+}
+
+/// Future of checking out a buffer, which waits in the queue of the pool.
+pub(super) struct Checkout<'a> {
+    pool: &'a Rc<PoolInner>,
+    /// Id in the queue, if it has been queued.
+    id: Option<u64>,
+}
+
+impl<'a> Checkout<'a> {
+    #[inline]
+    pub(super) fn new(pool: &'a Rc<PoolInner>) -> Self {
+        Self { pool, id: None }
+    }
+}
+
+impl Future for Checkout<'_> {
+    type Output = FixedBuf;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<FixedBuf> {
+        let this = self.get_mut();
+        let mut waiters = this.pool.waiters.borrow_mut();
+        let queued = this
+            .id
+            .and_then(|id| waiters.iter().position(|(i, _)| *i == id));
+        // Do not jump the queue unless it is woken(removed from the queue).
+        if queued.is_none() && (this.id.is_some() || waiters.is_empty()) {
+            if let Some(buf) = this.pool.try_checkout() {
+                this.id = None;
+                return Poll::Ready(buf);
+            }
+        }
+        match queued {
+            Some(pos) => waiters[pos].1.clone_from(cx.waker()),
+            None => {
+                let id = match this.id {
+                    // Woken but the buffer is taken, queue again at the front.
+                    Some(id) => {
+                        waiters.push_front((id, cx.waker().clone()));
+                        id
+                    }
+                    None => {
+                        let id = this.pool.next_waiter.get();
+                        this.pool.next_waiter.set(id + 1);
+                        waiters.push_back((id, cx.waker().clone()));
+                        id
+                    }
+                };
+                this.id = Some(id);
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl Drop for Checkout<'_> {
+    fn drop(&mut self) {
+        let Some(id) = self.id else { return };
+        let mut waiters = self.pool.waiters.borrow_mut();
+        match waiters.iter().position(|(i, _)| *i == id) {
+            Some(pos) => {
+                waiters.remove(pos);
+            }
+            None => {
+                // It has been woken for a free buffer, pass it on.
+                drop(waiters);
+                self.pool.wake_next();
+            }
+        }
+    }
+}
+
+impl Drop for PoolInner {
+    fn drop(&mut self) {
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        if self.registered {
+            // If the runtime is gone, the ring is closed and the buffers are released with it.
+            let res = crate::driver::with_uring_submitter(|s| s.unregister_buffers());
+            if let Some(Err(_)) = res {
+                // The kernel may still hold the buffers, we leak the memory.
+                return;
+            }
+        }
+        unsafe { dealloc_bufs(self.mem, self.buf_cnt, self.buf_len) };
+    }
+}
+
+impl std::fmt::Debug for FixedBufPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FixedBufPool")
+            .field("buf_len", &self.inner.buf_len)
+            .field("buf_cnt", &self.inner.buf_cnt)
+            .field("available", &self.available())
+            .field("registered", &self.inner.registered)
+            .finish()
+    }
+}
+
+/// A buffer checked out from a [`FixedBufPool`]. It is given back to the pool when dropped.
+pub struct FixedBuf {
+    pool: Rc<PoolInner>,
+    index: u16,
+    len: usize,
+}
+
+impl FixedBuf {
+    /// Index of the buffer in the registered buffer table.
+    #[inline]
+    pub fn buf_index(&self) -> u16 {
+        self.index
+    }
+
+    /// Capacity of the buffer.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.pool.buf_len
+    }
+
+    /// Clear the buffer.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Append the slice to the buffer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the remaining capacity is not enough.
+    pub fn extend_from_slice(&mut self, src: &[u8]) {
+        assert!(
+            src.len() <= self.capacity() - self.len,
+            "fixed buffer capacity overflow"
+        );
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                src.as_ptr(),
+                self.pool.buf_ptr(self.index).add(self.len),
+                src.len(),
+            )
+        };
+        self.len += src.len();
+    }
+
+    /// If the buffer is registered to the kernel.
+    #[allow(unused)]
+    #[inline]
+    pub(crate) fn is_registered(&self) -> bool {
+        self.pool.registered
+    }
+}
+
+impl Deref for FixedBuf {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        unsafe { std::slice::from_raw_parts(self.pool.buf_ptr(self.index), self.len) }
+    }
+}
+
+impl DerefMut for FixedBuf {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { std::slice::from_raw_parts_mut(self.pool.buf_ptr(self.index), self.len) }
+    }
+}
+
+unsafe impl IoBuf for FixedBuf {
+    #[inline]
+    fn read_ptr(&self) -> *const u8 {
+        self.pool.buf_ptr(self.index)
+    }
+
+    #[inline]
+    fn bytes_init(&self) -> usize {
+        self.len
+    }
+}
+
+unsafe impl IoBufMut for FixedBuf {
+    #[inline]
+    fn write_ptr(&mut self) -> *mut u8 {
+        self.pool.buf_ptr(self.index)
+    }
+
+    #[inline]
+    fn bytes_total(&mut self) -> usize {
+        self.pool.buf_len
+    }
+
+    #[inline]
+    unsafe fn set_init(&mut self, pos: usize) {
+        self.len = pos;
+    }
+}
+
+impl Drop for FixedBuf {
+    #[inline]
+    fn drop(&mut self) {
+        self.pool.free.borrow_mut().push(self.index);
+        self.pool.wake_next();
+    }
+}
+
+impl std::fmt::Debug for FixedBuf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FixedBuf")
+            .field("buf_index", &self.index)
+            .field("len", &self.len)
+            .field("capacity", &self.pool.buf_len)
+            .finish()
+    }
+}
@@ -294,6 +294,32 @@ pub unsafe trait IoBufMut: Unpin + 'static {
         let (begin, end) = parse_range(range, self.bytes_total());
         SliceMut::new_unchecked(self, begin, end)
     }
+
+    /// Returns a view of the uninitialized part of the buffer, so the data read into it is
+    /// appended after the initialized bytes instead of overwriting them.
+    ///
+    /// Reading into an empty view returns `Ok(0)`, so reserve the capacity first, e.g. with
+    /// `Vec::reserve` or `BytesMut::reserve`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use monoio::buf::{IoBuf, IoBufMut};
+    ///
+    /// let mut buf = Vec::with_capacity(16);
+    /// buf.extend_from_slice(b"hello");
+    /// let spare = buf.spare_mut();
+    /// assert_eq!((spare.begin(), spare.end()), (5, 16));
+    /// ```
+    #[inline]
+    fn spare_mut(mut self) -> SliceMut<Self>
+    where
+        Self: Sized,
+        Self: IoBuf,
+    {
+        let (begin, end) = (self.bytes_init(), self.bytes_total());
+        SliceMut::new(self, begin, end)
+    }
 }
 
 unsafe impl IoBufMut for Vec<u8> {
@@ -13,7 +13,7 @@ mod io_vec_buf;
 pub use io_vec_buf::{IoVecBuf, IoVecBufMut, VecBuf};
 
 mod slice;
-pub use slice::{IoVecWrapper, IoVecWrapperMut, Slice, SliceMut};
+pub use slice::{IoVecWrapper, IoVecWrapperMut, SharedBuf, Slice, SliceMut};
 
 mod raw_buf;
 pub use raw_buf::{RawBuf, RawBufVectored};
@@ -24,6 +24,24 @@ pub(crate) use vec_wrapper::{read_vec_meta, write_vec_meta, IoVecMeta};
 mod msg;
 pub use msg::{MsgBuf, MsgBufMut, MsgMeta};
 
+mod provided;
+pub use provided::{BufGroup, ProvidedBuf};
+
+mod buf_ring;
+pub use buf_ring::{BufRing, BufRingBuilder};
+
+mod fixed;
+pub use fixed::{FixedBuf, FixedBufPool};
+
+mod pool;
+pub use pool::BufPool;
+
+mod aligned;
+pub use aligned::AlignedBuf;
+
+mod array;
+pub use array::ArrayBuf;
+
 pub(crate) fn deref(buf: &impl IoBuf) -> &[u8] {
     // Safety: the `IoBuf` trait is marked as unsafe and is expected to be
     // implemented correctly.
@@ -0,0 +1,118 @@
+//! A general-purpose pool of buffers.
+
+use std::{future::Future, io, rc::Rc};
+
+use super::{
+    fixed::{Checkout, PoolInner},
+    FixedBuf,
+};
+
+/// A pool of fixed-size buffers owned by the current thread, like a slab of buffers for a
+/// runtime.
+///
+/// Buffers are checked out with [`BufPool::get`], which waits for a buffer to be given back if
+/// all of them are in use, so the pool also limits the memory used. A buffer is given back when
+/// it is dropped. The pool is cheap to clone; all clones share the same buffers.
+///
+/// The buffers are [`FixedBuf`], which can be used with all the operations taking
+/// [`IoBuf`](super::IoBuf) or [`IoBufMut`](super::IoBufMut). With
+/// [`new_registered`](Self::new_registered), they are also registered to io_uring like
+/// [`FixedBufPool`](super::FixedBufPool), so the fixed operations(e.g.
+/// [`File::read_fixed_at`](crate::fs::File::read_fixed_at)) avoid mapping the pages on every
+/// call.
+///
+/// # Examples
+///
+/// ```no_run
+/// use monoio::{buf::BufPool, io::AsyncReadRent, net::TcpStream};
+///
+/// #[monoio::main]
+/// async fn main() -> std::io::Result<()> {
+///     let pool = BufPool::new(64, 16 * 1024)?;
+///     let mut stream = TcpStream::connect("127.0.0.1:8080").await?;
+///     let buf = pool.get().await;
+///     let (res, buf) = stream.read(buf).await;
+///     println!("read {} bytes", res?);
+///     drop(buf); // the buffer is given back to the pool
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct BufPool {
+    inner: Rc<PoolInner>,
+}
+
+impl BufPool {
+    /// Create a pool with `buf_cnt` buffers of `buf_len` bytes.
+    pub fn new(buf_cnt: u16, buf_len: usize) -> io::Result<Self> {
+        Ok(Self {
+            inner: PoolInner::new(buf_cnt, buf_len, false)?,
+        })
+    }
+
+    /// Create a pool with `buf_cnt` buffers of `buf_len` bytes, and register them to the
+    /// io_uring instance of the current runtime. The buffers are not registered with legacy
+    /// driver.
+    ///
+    /// An io_uring instance can only hold one registered buffer table at a time, so it fails
+    /// with `EBUSY` if another registered pool is alive in the same runtime.
+    ///
+    /// # Panics
+    ///
+    /// Panics if it is called outside of a monoio runtime.
+    pub fn new_registered(buf_cnt: u16, buf_len: usize) -> io::Result<Self> {
+        Ok(Self {
+            inner: PoolInner::new(buf_cnt, buf_len, true)?,
+        })
+    }
+
+    /// Check out a free buffer, waiting for one to be given back if all buffers are in use.
+    /// The waiting tasks get the buffers in order.
+    ///
+    /// The returned buffer is empty; its capacity is [`BufPool::buf_len`].
+    #[inline]
+    pub fn get(&self) -> impl Future<Output = FixedBuf> + '_ {
+        Checkout::new(&self.inner)
+    }
+
+    /// Check out a free buffer. Returns `None` if all buffers are in use.
+    #[inline]
+    pub fn try_get(&self) -> Option<FixedBuf> {
+        self.inner.try_checkout()
+    }
+
+    /// Length of each buffer.
+    #[inline]
+    pub fn buf_len(&self) -> usize {
+        self.inner.buf_len()
+    }
+
+    /// Count of buffers.
+    #[inline]
+    pub fn buf_cnt(&self) -> u16 {
+        self.inner.buf_cnt()
+    }
+
+    /// Count of buffers not checked out.
+    #[inline]
+    pub fn available(&self) -> usize {
+        self.inner.available()
+    }
+
+    /// If the buffers are registered to the kernel.
+    #[inline]
+    pub fn is_registered(&self) -> bool {
+        self.inner.registered()
+    }
+}
+
+impl std::fmt::Debug for BufPool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BufPool")
+            .field("buf_len", &self.buf_len())
+            .field("buf_cnt", &self.buf_cnt())
+            .field("available", &self.available())
+            .field("registered", &self.is_registered())
+            .finish()
+    }
+}
@@ -0,0 +1,319 @@
+//! Provided buffers.
+//!
+//! With provided buffers, the buffer is not decided when the operation is submitted, but picked
+//! from a buffer group when the data arrives. It is the foundation of multishot receiving.
+
+use std::{
+    cell::RefCell,
+    io,
+    ops::{Deref, DerefMut},
+    rc::Rc,
+};
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+use super::buf_ring::RingState;
+use super::{IoBuf, IoBufMut};
+
+/// A group of buffers that can be selected by the operations which receive data.
+///
+/// With io_uring driver, the buffers are provided to the kernel(requires kernel 5.7+), and the
+/// kernel picks one when data arrives. With legacy driver, the buffers are picked in userspace
+/// before doing the syscall.
+///
+/// The group is cheap to clone; all clones share the same buffers. When all clones and all
+/// [`ProvidedBuf`] taken from it are dropped, the buffers are removed from the kernel.
+#[derive(Clone)]
+pub struct BufGroup {
+    inner: Rc<BufGroupInner>,
+}
+
+pub(crate) struct BufGroupInner {
+    bgid: u16,
+    buf_len: usize,
+    buf_cnt: u16,
+    mem: *mut u8,
+    backend: Backend,
+}
+
+pub(crate) enum Backend {
+    /// Buffers are picked in userspace. Used with legacy driver.
+    Userspace(RefCell<Vec<u16>>),
+    /// Buffers are provided with `IORING_OP_PROVIDE_BUFFERS`.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    Provided,
+    /// Buffers are provided with a registered buffer ring.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    Ring(RingState),
+}
+
+impl BufGroup {
+    /// Create a buffer group with `buf_cnt` buffers of `buf_len` bytes and group id `bgid`.
+    ///
+    /// Buffer group id must be unique among the groups alive in the current runtime.
+    ///
+    /// # Panics
+    ///
+    /// Panics if it is called outside of a monoio runtime.
+    pub fn new(bgid: u16, buf_cnt: u16, buf_len: usize) -> io::Result<Self> {
+        let mem = alloc_bufs(buf_cnt, buf_len)?;
+
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        if !crate::driver::op::is_legacy() {
+            // Safety: the memory is valid until the group is removed.
+            if let Err(e) = unsafe {
+                crate::driver::op::Op::provide_buffers(mem, buf_len as i32, buf_cnt, bgid, 0)
+            } {
+                unsafe { dealloc_bufs(mem, buf_cnt, buf_len) };
+                return Err(e);
+            }
+            return Ok(Self::from_parts(
+                bgid,
+                buf_cnt,
+                buf_len,
+                mem,
+                Backend::Provided,
+            ));
+        }
+
+        Ok(Self::from_parts(
+            bgid,
+            buf_cnt,
+            buf_len,
+            mem,
+            Backend::Userspace(RefCell::new((0..buf_cnt).rev().collect())),
+        ))
+    }
+
+    pub(crate) fn from_parts(
+        bgid: u16,
+        buf_cnt: u16,
+        buf_len: usize,
+        mem: *mut u8,
+        backend: Backend,
+    ) -> Self {
+        Self {
+            inner: Rc::new(BufGroupInner {
+                bgid,
+                buf_len,
+                buf_cnt,
+                mem,
+                backend,
+            }),
+        }
+    }
+
+    /// Buffer group id.
+    #[inline]
+    pub fn bgid(&self) -> u16 {
+        self.inner.bgid
+    }
+
+    /// Length of each buffer.
+    #[inline]
+    pub fn buf_len(&self) -> usize {
+        self.inner.buf_len
+    }
+
+    /// Count of buffers.
+    #[inline]
+    pub fn buf_cnt(&self) -> u16 {
+        self.inner.buf_cnt
+    }
+
+    /// Take a buffer in userspace. Only works when buffers are not provided to the kernel.
+    #[allow(unused)]
+    pub(crate) fn take_free(&self) -> Option<(u16, *mut u8)> {
+        match &self.inner.backend {
+            Backend::Userspace(free) => {
+                let bid = free.borrow_mut().pop()?;
+                Some((bid, self.inner.buf_ptr(bid)))
+            }
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            _ => None,
+        }
+    }
+
+    /// Give back a buffer taken by `take_free` which is not filled.
+    #[allow(unused)]
+    pub(crate) fn put_free(&self, bid: u16) {
+        self.inner.recycle(bid);
+    }
+
+    /// Wrap a selected buffer with `len` bytes filled.
+    ///
+    /// # Safety
+    /// The buffer must be selected from this group and `len` bytes must be initialized.
+    pub(crate) unsafe fn selected(&self, bid: u16, len: usize) -> ProvidedBuf {
+        ProvidedBuf {
+            group: self.inner.clone(),
+            bid,
+            len,
+        }
+    }
+}
+
+impl AsRef<BufGroup> for BufGroup {
+    #[inline]
+    fn as_ref(&self) -> &BufGroup {
+        self
+    }
+}
+
+impl BufGroupInner {
+    #[inline]
+    pub(crate) fn buf_ptr(&self, bid: u16) -> *mut u8 {
+        unsafe { self.mem.add(bid as usize * self.buf_len) }
+    }
+
+    fn recycle(&self, bid: u16) {
+        match &self.backend {
+            Backend::Userspace(free) => free.borrow_mut().push(bid),
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            Backend::Provided => {
+                // If the runtime is gone, the buffer is lost, which is harmless.
+                let _ = unsafe {
+                    crate::driver::op::Op::provide_buffers(
+                        self.buf_ptr(bid),
+                        self.buf_len as i32,
+                        1,
+                        self.bgid,
+                        bid,
+                    )
+                };
+            }
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            Backend::Ring(ring) => ring.push(bid, self.buf_ptr(bid), self.buf_len),
+        }
+    }
+}
+
+impl Drop for BufGroupInner {
+    fn drop(&mut self) {
+        let released = match &self.backend {
+            Backend::Userspace(_) => true,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            Backend::Provided => {
+                crate::driver::op::Op::remove_buffers(self.buf_cnt, self.bgid).is_ok()
+            }
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            Backend::Ring(ring) => ring.unregister(self.bgid),
+        };
+        // If the kernel may still hold the buffers, we leak the memory.
+        if released {
+            unsafe { dealloc_bufs(self.mem, self.buf_cnt, self.buf_len) };
+        }
+    }
+}
+
+pub(crate) fn alloc_bufs(buf_cnt: u16, buf_len: usize) -> io::Result<*mut u8> {
+    if buf_cnt == 0 || buf_len == 0 || buf_len > i32::MAX as usize {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "invalid buffer group size",
+        ));
+    }
+    let mem = Box::leak(vec![0_u8; buf_len * buf_cnt as usize].into_boxed_slice());
+    #[cfg(feature = "utils")]
+    crate::utils::numa::bind_memory(mem.as_mut_ptr(), mem.len());
+    Ok(mem.as_mut_ptr())
+}
+
+pub(crate) unsafe fn dealloc_bufs(mem: *mut u8, buf_cnt: u16, buf_len: usize) {
+    drop(Box::from_raw(std::ptr::slice_from_raw_parts_mut(
+        mem,
+        buf_len * buf_cnt as usize,
+    )));
+}
+
+impl std::fmt::Debug for BufGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BufGroup")
+            .field("bgid", &self.inner.bgid)
+            .field("buf_len", &self.inner.buf_len)
+            .field("buf_cnt", &self.inner.buf_cnt)
+            .finish()
+    }
+}
+
+/// A buffer selected from a [`BufGroup`]. It is given back to the group when dropped.
+pub struct ProvidedBuf {
+    group: Rc<BufGroupInner>,
+    bid: u16,
+    len: usize,
+}
+
+impl ProvidedBuf {
+    /// Buffer id in the group.
+    #[inline]
+    pub fn bid(&self) -> u16 {
+        self.bid
+    }
+
+    /// Buffer group id.
+    #[inline]
+    pub fn bgid(&self) -> u16 {
+        self.group.bgid
+    }
+}
+
+impl Deref for ProvidedBuf {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        unsafe { std::slice::from_raw_parts(self.group.buf_ptr(self.bid), self.len) }
+    }
+}
+
+impl DerefMut for ProvidedBuf {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { std::slice::from_raw_parts_mut(self.group.buf_ptr(self.bid), self.len) }
+    }
+}
+
+unsafe impl IoBuf for ProvidedBuf {
+    #[inline]
+    fn read_ptr(&self) -> *const u8 {
+        self.group.buf_ptr(self.bid)
+    }
+
+    #[inline]
+    fn bytes_init(&self) -> usize {
+        self.len
+    }
+}
+
+unsafe impl IoBufMut for ProvidedBuf {
+    #[inline]
+    fn write_ptr(&mut self) -> *mut u8 {
+        self.group.buf_ptr(self.bid)
+    }
+
+    #[inline]
+    fn bytes_total(&mut self) -> usize {
+        self.group.buf_len
+    }
+
+    #[inline]
+    unsafe fn set_init(&mut self, pos: usize) {
+        self.len = pos;
+    }
+}
+
+impl Drop for ProvidedBuf {
+    #[inline]
+    fn drop(&mut self) {
+        self.group.recycle(self.bid);
+    }
+}
+
+impl std::fmt::Debug for ProvidedBuf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProvidedBuf")
+            .field("bgid", &self.group.bgid)
+            .field("bid", &self.bid)
+            .field("len", &self.len)
+            .finish()
+    }
+}
@@ -1,4 +1,4 @@
-use std::ops;
+use std::{cell::Cell, ops, rc::Rc};
 
 use super::{IoVecBuf, IoVecBufMut};
 use crate::buf::{IoBuf, IoBufMut};
@@ -153,14 +153,15 @@ impl<T: IoBuf> ops::Deref for SliceMut<T> {
     fn deref(&self) -> &[u8] {
         let buf_bytes = super::deref(&self.buf);
         let end = std::cmp::min(self.end, buf_bytes.len());
-        &buf_bytes[self.begin..end]
+        let begin = std::cmp::min(self.begin, end);
+        &buf_bytes[begin..end]
     }
 }
 
 unsafe impl<T: IoBuf> IoBuf for SliceMut<T> {
     #[inline]
     fn read_ptr(&self) -> *const u8 {
-        super::deref(&self.buf)[self.begin..].as_ptr()
+        unsafe { self.buf.read_ptr().add(self.begin) }
     }
 
     #[inline]
@@ -245,6 +246,15 @@ impl<T> Slice<T> {
     }
 }
 
+#[cfg(feature = "bytes")]
+impl From<Slice<bytes::Bytes>> for bytes::Bytes {
+    /// Returns the viewed range of the `Bytes` without copying.
+    #[inline]
+    fn from(slice: Slice<bytes::Bytes>) -> Self {
+        bytes::Bytes::slice(&slice.buf, slice.begin..slice.end)
+    }
+}
+
 unsafe impl<T: IoBuf> IoBuf for Slice<T> {
     #[inline]
     fn read_ptr(&self) -> *const u8 {
@@ -257,6 +267,270 @@ unsafe impl<T: IoBuf> IoBuf for Slice<T> {
     }
 }
 
+impl<T: IoBuf + IoBufMut> SliceMut<T> {
+    /// Converts the slice into a window of a shared buffer, which can be split into
+    /// non-overlapping windows used by concurrent operations.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use monoio::buf::IoBufMut;
+    ///
+    /// let buf = vec![0; 8];
+    /// let (head, tail) = buf.slice_mut(..).into_shared().split_at(3);
+    /// assert_eq!((head.begin(), head.end()), (0, 3));
+    /// assert_eq!((tail.begin(), tail.end()), (3, 8));
+    ///
+    /// let merged = head.try_merge(tail).ok().unwrap();
+    /// let buf = merged.try_unshare().ok().unwrap().into_inner();
+    /// assert_eq!(buf.len(), 8);
+    /// ```
+    pub fn into_shared(self) -> SliceMut<SharedBuf<T>> {
+        SliceMut {
+            buf: SharedBuf::new_mut(self.buf),
+            begin: self.begin,
+            end: self.end,
+        }
+    }
+}
+
+impl<T> SliceMut<SharedBuf<T>> {
+    /// Splits the window into two at the offset `at` relative to its beginning.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at` is larger than the length of the window.
+    pub fn split_at(self, at: usize) -> (Self, Self) {
+        assert!(at <= self.end - self.begin, "split offset out of range");
+        let mid = self.begin + at;
+        let tail = SliceMut {
+            buf: self.buf.share(),
+            begin: mid,
+            end: self.end,
+        };
+        let head = SliceMut {
+            buf: self.buf,
+            begin: self.begin,
+            end: mid,
+        };
+        (head, tail)
+    }
+
+    /// Merges the window with the one directly following it in the same buffer.
+    ///
+    /// The windows are given back if they are not adjacent windows of the same buffer.
+    pub fn try_merge(self, other: Self) -> Result<Self, (Self, Self)> {
+        if !self.buf.same(&other.buf) || self.end != other.begin {
+            return Err((self, other));
+        }
+        Ok(SliceMut {
+            buf: self.buf,
+            begin: self.begin,
+            end: other.end,
+        })
+    }
+
+    /// Returns the slice of the original buffer if this is the only window left.
+    ///
+    /// The initialized length of the buffer is raised to the furthest byte initialized
+    /// through any window, so bytes before it that were not written are left as they were
+    /// before sharing.
+    pub fn try_unshare(self) -> Result<SliceMut<T>, Self>
+    where
+        T: IoBuf + IoBufMut,
+    {
+        let SliceMut { buf, begin, end } = self;
+        match buf.try_unwrap() {
+            Ok((mut inner, init)) => {
+                if init > inner.bytes_init() {
+                    unsafe { inner.set_init(init) };
+                }
+                Ok(SliceMut {
+                    buf: inner,
+                    begin,
+                    end,
+                })
+            }
+            Err(buf) => Err(SliceMut { buf, begin, end }),
+        }
+    }
+}
+
+impl<T: IoBuf> Slice<T> {
+    /// Converts the slice into a window of a shared buffer, which can be split into
+    /// non-overlapping windows used by concurrent operations.
+    pub fn into_shared(self) -> Slice<SharedBuf<T>> {
+        Slice {
+            buf: SharedBuf::new(self.buf),
+            begin: self.begin,
+            end: self.end,
+        }
+    }
+}
+
+impl<T> Slice<SharedBuf<T>> {
+    /// Splits the window into two at the offset `at` relative to its beginning.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at` is larger than the length of the window.
+    pub fn split_at(self, at: usize) -> (Self, Self) {
+        assert!(at <= self.end - self.begin, "split offset out of range");
+        let mid = self.begin + at;
+        let tail = Slice {
+            buf: self.buf.share(),
+            begin: mid,
+            end: self.end,
+        };
+        let head = Slice {
+            buf: self.buf,
+            begin: self.begin,
+            end: mid,
+        };
+        (head, tail)
+    }
+
+    /// Merges the window with the one directly following it in the same buffer.
+    ///
+    /// The windows are given back if they are not adjacent windows of the same buffer.
+    pub fn try_merge(self, other: Self) -> Result<Self, (Self, Self)> {
+        if !self.buf.same(&other.buf) || self.end != other.begin {
+            return Err((self, other));
+        }
+        Ok(Slice {
+            buf: self.buf,
+            begin: self.begin,
+            end: other.end,
+        })
+    }
+
+    /// Returns the slice of the original buffer if this is the only window left.
+    pub fn try_unshare(self) -> Result<Slice<T>, Self> {
+        let Slice { buf, begin, end } = self;
+        match buf.try_unwrap() {
+            Ok((inner, _)) => Ok(Slice {
+                buf: inner,
+                begin,
+                end,
+            }),
+            Err(buf) => Err(Slice { buf, begin, end }),
+        }
+    }
+}
+
+/// A buffer owned together by the windows split from a [`Slice`] or [`SliceMut`].
+///
+/// It is created by `into_shared` and only reachable through the windows, which never
+/// overlap, so the operations on different windows do not race on the same bytes.
+pub struct SharedBuf<T> {
+    inner: Rc<SharedInner<T>>,
+}
+
+struct SharedInner<T> {
+    buf: T,
+    ptr: *mut u8,
+    total: usize,
+    // The furthest byte initialized through any window.
+    init: Cell<usize>,
+}
+
+impl<T: IoBuf> SharedBuf<T> {
+    fn new(buf: T) -> Self {
+        let ptr = buf.read_ptr() as *mut u8;
+        let init = buf.bytes_init();
+        Self {
+            inner: Rc::new(SharedInner {
+                buf,
+                ptr,
+                total: init,
+                init: Cell::new(init),
+            }),
+        }
+    }
+}
+
+impl<T: IoBuf + IoBufMut> SharedBuf<T> {
+    fn new_mut(mut buf: T) -> Self {
+        let total = buf.bytes_total();
+        let ptr = buf.write_ptr();
+        let init = buf.bytes_init();
+        Self {
+            inner: Rc::new(SharedInner {
+                buf,
+                ptr,
+                total,
+                init: Cell::new(init),
+            }),
+        }
+    }
+}
+
+impl<T> SharedBuf<T> {
+    #[inline]
+    fn share(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+
+    #[inline]
+    fn same(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+
+    fn try_unwrap(self) -> Result<(T, usize), Self> {
+        match Rc::try_unwrap(self.inner) {
+            Ok(inner) => Ok((inner.buf, inner.init.get())),
+            Err(inner) => Err(Self { inner }),
+        }
+    }
+
+    /// Returns the number of windows sharing the buffer.
+    #[inline]
+    pub fn windows(&self) -> usize {
+        Rc::strong_count(&self.inner)
+    }
+
+    /// Returns the original buffer.
+    ///
+    /// The bytes in the windows of the other pending operations may change at any time.
+    #[inline]
+    pub fn get_ref(&self) -> &T {
+        &self.inner.buf
+    }
+}
+
+unsafe impl<T: IoBuf> IoBuf for SharedBuf<T> {
+    #[inline]
+    fn read_ptr(&self) -> *const u8 {
+        self.inner.ptr
+    }
+
+    #[inline]
+    fn bytes_init(&self) -> usize {
+        self.inner.init.get()
+    }
+}
+
+unsafe impl<T: IoBufMut> IoBufMut for SharedBuf<T> {
+    #[inline]
+    fn write_ptr(&mut self) -> *mut u8 {
+        self.inner.ptr
+    }
+
+    #[inline]
+    fn bytes_total(&mut self) -> usize {
+        self.inner.total
+    }
+
+    #[inline]
+    unsafe fn set_init(&mut self, pos: usize) {
+        if pos > self.inner.init.get() {
+            self.inner.init.set(pos);
+        }
+    }
+}
+
 /// A wrapper to make IoVecBuf impl IoBuf.
 pub struct IoVecWrapper<T> {
     // we must make sure raw contains at least one iovec.
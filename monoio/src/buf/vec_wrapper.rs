@@ -1,5 +1,5 @@
 #[cfg(windows)]
-use {std::ops::Add, windows_sys::Win32::Networking::WinSock::WSABUF};
+use windows_sys::Win32::Networking::WinSock::WSABUF;
 
 use super::{IoBuf, IoBufMut, IoVecBuf, IoVecBufMut};
 
@@ -26,11 +26,13 @@ pub(crate) fn read_vec_meta<T: IoVecBuf>(buf: &T) -> IoVecMeta {
             data.push(iovec);
             len += iovec.iov_len;
         }
-        IoVecMeta {
+        let mut meta = IoVecMeta {
             data,
             offset: 0,
             len,
-        }
+        };
+        meta.skip_empty();
+        meta
     }
     #[cfg(windows)]
     {
@@ -45,11 +47,13 @@ pub(crate) fn read_vec_meta<T: IoVecBuf>(buf: &T) -> IoVecMeta {
             len += wsabuf.len;
         }
         let len = len as _;
-        IoVecMeta {
+        let mut meta = IoVecMeta {
             data,
             offset: 0,
             len,
-        }
+        };
+        meta.skip_empty();
+        meta
     }
 }
 
@@ -67,11 +71,13 @@ pub(crate) fn write_vec_meta<T: IoVecBufMut>(buf: &mut T) -> IoVecMeta {
             data.push(iovec);
             len += iovec.iov_len;
         }
-        IoVecMeta {
+        let mut meta = IoVecMeta {
             data,
             offset: 0,
             len,
-        }
+        };
+        meta.skip_empty();
+        meta
     }
     #[cfg(windows)]
     {
@@ -86,11 +92,13 @@ pub(crate) fn write_vec_meta<T: IoVecBufMut>(buf: &mut T) -> IoVecMeta {
             len += wsabuf.len;
         }
         let len = len as _;
-        IoVecMeta {
+        let mut meta = IoVecMeta {
             data,
             offset: 0,
             len,
-        }
+        };
+        meta.skip_empty();
+        meta
     }
 }
 
@@ -113,10 +121,11 @@ impl IoVecMeta {
                     std::cmp::Ordering::Equal => {
                         offset += 1;
                         self.offset = offset;
+                        self.skip_empty();
                         return;
                     }
                     std::cmp::Ordering::Greater => {
-                        let _ = unsafe { iovec.iov_base.add(amt) };
+                        iovec.iov_base = unsafe { iovec.iov_base.add(amt) };
                         iovec.iov_len -= amt;
                         self.offset = offset;
                         return;
@@ -142,10 +151,11 @@ impl IoVecMeta {
                     std::cmp::Ordering::Equal => {
                         offset += 1;
                         self.offset = offset;
+                        self.skip_empty();
                         return;
                     }
                     std::cmp::Ordering::Greater => {
-                        _ = wsabuf.len.add(amt);
+                        wsabuf.buf = unsafe { wsabuf.buf.add(amt as usize) };
                         wsabuf.len -= amt;
                         self.offset = offset;
                         return;
@@ -159,6 +169,27 @@ impl IoVecMeta {
     pub(crate) fn len(&self) -> usize {
         self.len
     }
+
+    /// Skip the empty buffers, so that the first one always has data unless all are consumed.
+    /// Otherwise the io which only handles the first buffer may think it is closed.
+    fn skip_empty(&mut self) {
+        #[cfg(unix)]
+        while self
+            .data
+            .get(self.offset)
+            .is_some_and(|iovec| iovec.iov_len == 0)
+        {
+            self.offset += 1;
+        }
+        #[cfg(windows)]
+        while self
+            .data
+            .get(self.offset)
+            .is_some_and(|wsabuf| wsabuf.len == 0)
+        {
+            self.offset += 1;
+        }
+    }
 }
 
 unsafe impl IoVecBuf for IoVecMeta {
@@ -168,7 +199,7 @@ unsafe impl IoVecBuf for IoVecMeta {
     }
     #[cfg(unix)]
     fn read_iovec_len(&self) -> usize {
-        self.data.len()
+        self.data.len() - self.offset
     }
     #[cfg(windows)]
     fn read_wsabuf_ptr(&self) -> *const WSABUF {
@@ -176,7 +207,7 @@ unsafe impl IoVecBuf for IoVecMeta {
     }
     #[cfg(windows)]
     fn read_wsabuf_len(&self) -> usize {
-        self.data.len()
+        self.data.len() - self.offset
     }
 }
 
@@ -188,7 +219,7 @@ unsafe impl IoVecBufMut for IoVecMeta {
 
     #[cfg(unix)]
     fn write_iovec_len(&mut self) -> usize {
-        self.data.len()
+        self.data.len() - self.offset
     }
 
     #[cfg(windows)]
@@ -198,7 +229,7 @@ unsafe impl IoVecBufMut for IoVecMeta {
 
     #[cfg(windows)]
     fn write_wsabuf_len(&mut self) -> usize {
-        self.data.len()
+        self.data.len() - self.offset
     }
 
     unsafe fn set_init(&mut self, pos: usize) {
@@ -209,18 +240,21 @@ unsafe impl IoVecBufMut for IoVecMeta {
 impl<'t, T: IoBuf> From<&'t T> for IoVecMeta {
     fn from(buf: &'t T) -> Self {
         let ptr = buf.read_ptr() as *const _ as *mut _;
-        let len = buf.bytes_init() as _;
+        let len = buf.bytes_init();
         #[cfg(unix)]
         let item = libc::iovec {
             iov_base: ptr,
             iov_len: len,
         };
         #[cfg(windows)]
-        let item = WSABUF { buf: ptr, len };
+        let item = WSABUF {
+            buf: ptr,
+            len: len as _,
+        };
         Self {
             data: vec![item],
             offset: 0,
-            len: 1,
+            len,
         }
     }
 }
@@ -228,18 +262,21 @@ impl<'t, T: IoBuf> From<&'t T> for IoVecMeta {
 impl<'t, T: IoBufMut> From<&'t mut T> for IoVecMeta {
     fn from(buf: &'t mut T) -> Self {
         let ptr = buf.write_ptr() as *mut _;
-        let len = buf.bytes_total() as _;
+        let len = buf.bytes_total();
         #[cfg(unix)]
         let item = libc::iovec {
             iov_base: ptr,
             iov_len: len,
         };
         #[cfg(windows)]
-        let item = WSABUF { buf: ptr, len };
+        let item = WSABUF {
+            buf: ptr,
+            len: len as _,
+        };
         Self {
             data: vec![item],
             offset: 0,
-            len: 1,
+            len,
         }
     }
 }
@@ -288,4 +325,26 @@ mod tests {
             assert_eq!(meta.data[2].len, 30);
         }
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_consume() {
+        let iovec = VecBuf::from(vec![vec![1; 10], vec![], vec![2; 20]]);
+        let mut meta = read_vec_meta(&iovec);
+        meta.consume(4);
+        assert_eq!(meta.read_iovec_len(), 3);
+        let first = unsafe { *meta.read_iovec_ptr() };
+        assert_eq!(first.iov_len, 6);
+        assert_eq!(unsafe { *(first.iov_base as *const u8) }, 1);
+        // Consume across the boundary, the empty one is skipped.
+        meta.consume(6);
+        assert_eq!(meta.read_iovec_len(), 1);
+        meta.consume(15);
+        let last = unsafe { *meta.read_iovec_ptr() };
+        assert_eq!(last.iov_len, 5);
+        let origin = unsafe { *iovec.read_iovec_ptr().add(2) };
+        assert_eq!(last.iov_base as usize, origin.iov_base as usize + 15);
+        meta.consume(5);
+        assert_eq!(meta.read_iovec_len(), 0);
+    }
 }
@@ -2,15 +2,11 @@ use std::{io, marker::PhantomData};
 
 #[cfg(all(target_os = "linux", feature = "iouring"))]
 use crate::driver::IoUringDriver;
+#[cfg(all(windows, feature = "iocp"))]
+use crate::driver::IocpDriver;
 #[cfg(feature = "legacy")]
 use crate::driver::LegacyDriver;
-#[cfg(any(feature = "legacy", feature = "iouring"))]
-use crate::utils::thread_id::gen_id;
-use crate::{
-    driver::Driver,
-    time::{driver::TimeDriver, Clock},
-    Runtime,
-};
+use crate::{driver::Driver, time::driver::TimeDriver, utils::thread_id::gen_id, Runtime};
 
 // ===== basic builder structure definition =====
 
@@ -21,6 +17,25 @@ pub struct RuntimeBuilder<D> {
 
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     urb: io_uring::Builder,
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    uring_opts: UringOpts,
+
+    // read CLOCK_MONOTONIC_COARSE for Instant::recent
+    coarse_clock: bool,
+    // custom clock of the runtime
+    clock: Option<Box<dyn crate::time::Clock + Send>>,
+    // cooperative scheduling budget of a task poll
+    coop_budget: Option<u8>,
+    // run queue settings
+    scheduler_opts: crate::scheduler::SchedulerOpts,
+    // what to do when a spawned task panics
+    panic_policy: crate::runtime::PanicPolicy,
+    // numa node of the thread and the memory
+    #[cfg(feature = "utils")]
+    numa_node: Option<usize>,
+    // watchdog of the blocked runtime thread
+    #[cfg(feature = "watchdog")]
+    watchdog: Option<crate::runtime::watchdog::Watchdog>,
 
     // blocking handle
     #[cfg(feature = "sync")]
@@ -29,6 +44,49 @@ pub struct RuntimeBuilder<D> {
     _mark: PhantomData<D>,
 }
 
+/// Extra io_uring settings not covered by [`io_uring::Builder`].
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+#[derive(Debug, Default, Clone)]
+pub(crate) struct UringOpts {
+    /// Size of the sparse fixed file table to register.
+    pub(crate) fixed_files: Option<u32>,
+    /// Try `IORING_SETUP_COOP_TASKRUN` and `IORING_SETUP_TASKRUN_FLAG`.
+    pub(crate) coop_taskrun: bool,
+    /// Try `IORING_SETUP_SINGLE_ISSUER`.
+    pub(crate) single_issuer: bool,
+    /// Try `IORING_SETUP_DEFER_TASKRUN`.
+    pub(crate) defer_taskrun: bool,
+    /// Try `IORING_REGISTER_RING_FDS`.
+    pub(crate) register_ring_fd: bool,
+    /// What to do when the CQ may overflow.
+    pub(crate) cq_overflow: crate::driver::CqOverflowPolicy,
+    /// When to submit the queued SQEs.
+    pub(crate) submit_policy: crate::driver::SubmitPolicy,
+    /// Limits of bounded and unbounded io-wq workers.
+    pub(crate) iowq_max_workers: Option<[u32; 2]>,
+    /// CPUs the io-wq workers run on.
+    pub(crate) iowq_affinity: Option<Vec<usize>>,
+    /// Restrictions to register.
+    pub(crate) restrictions: Option<Vec<crate::driver::Restriction>>,
+    /// Build the ring with `IORING_SETUP_SQE128` and `IORING_SETUP_CQE32`.
+    pub(crate) big_entries: bool,
+    /// `IORING_SETUP_SQPOLL` idle time and cpu, kept for the big ring.
+    pub(crate) sqpoll: Option<(u32, Option<u32>)>,
+    /// `IORING_SETUP_CQSIZE`, kept for the big ring.
+    pub(crate) cq_entries: Option<u32>,
+    /// Build the ring with `IORING_SETUP_IOPOLL`.
+    pub(crate) iopoll: bool,
+    /// Drive sockets with the epoll poller instead of io_uring.
+    #[cfg(feature = "poll-io")]
+    pub(crate) hybrid: bool,
+    /// Record the latency of the ops.
+    pub(crate) op_latency: bool,
+    /// Report the ops slower than the threshold.
+    pub(crate) slow_op_threshold: Option<std::time::Duration>,
+    /// Called with the slow ops.
+    pub(crate) slow_op_callback: Option<crate::runtime::metrics::SlowOpCallback>,
+}
+
 scoped_thread_local!(pub(crate) static BUILD_THREAD_ID: usize);
 
 impl<T> Default for RuntimeBuilder<T> {
@@ -40,7 +98,6 @@ impl<T> Default for RuntimeBuilder<T> {
     /// [monoio::blocking::BlockingStrategy] is to execute tasks on the local thread. In other
     /// words, there is no thread pool involved—all blocking I/O operations and heavy computations
     /// will block the current thread.
-    #[must_use]
     fn default() -> Self {
         RuntimeBuilder::<T>::new()
     }
@@ -62,6 +119,17 @@ impl<T> RuntimeBuilder<T> {
 
             #[cfg(all(target_os = "linux", feature = "iouring"))]
             urb: io_uring::IoUring::builder(),
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            uring_opts: UringOpts::default(),
+            coarse_clock: false,
+            clock: None,
+            coop_budget: Some(crate::task::coop::DEFAULT_BUDGET),
+            scheduler_opts: Default::default(),
+            panic_policy: Default::default(),
+            #[cfg(feature = "utils")]
+            numa_node: None,
+            #[cfg(feature = "watchdog")]
+            watchdog: None,
 
             #[cfg(feature = "sync")]
             blocking_handle: crate::blocking::BlockingStrategy::ExecuteLocal.into(),
@@ -98,6 +166,10 @@ direct_build!(TimeDriver<IoUringDriver>);
 direct_build!(LegacyDriver);
 #[cfg(feature = "legacy")]
 direct_build!(TimeDriver<LegacyDriver>);
+#[cfg(all(windows, feature = "iocp"))]
+direct_build!(IocpDriver);
+#[cfg(all(windows, feature = "iocp"))]
+direct_build!(TimeDriver<IocpDriver>);
 
 // ===== builder impl =====
 
@@ -105,6 +177,10 @@ direct_build!(TimeDriver<LegacyDriver>);
 impl Buildable for LegacyDriver {
     fn build(this: RuntimeBuilder<Self>) -> io::Result<Runtime<LegacyDriver>> {
         let thread_id = gen_id();
+        #[cfg(feature = "utils")]
+        if let Some(node) = this.numa_node {
+            crate::utils::bind_to_numa_node(node)?;
+        }
         #[cfg(feature = "sync")]
         let blocking_handle = this.blocking_handle;
 
@@ -117,7 +193,50 @@ impl Buildable for LegacyDriver {
             let context = crate::runtime::Context::new(blocking_handle);
             #[cfg(not(feature = "sync"))]
             let context = crate::runtime::Context::new();
-            Ok(Runtime::new(context, driver))
+            #[cfg(feature = "watchdog")]
+            let context = context.with_watchdog(this.watchdog);
+            Ok(Runtime::new(
+                context
+                    .with_clock(this.clock, this.coarse_clock)
+                    .with_coop_budget(this.coop_budget)
+                    .with_scheduler_opts(&this.scheduler_opts)
+                    .with_panic_policy(this.panic_policy),
+                driver,
+            ))
+        })
+    }
+}
+
+#[cfg(all(windows, feature = "iocp"))]
+impl Buildable for IocpDriver {
+    fn build(this: RuntimeBuilder<Self>) -> io::Result<Runtime<IocpDriver>> {
+        let thread_id = gen_id();
+        #[cfg(feature = "utils")]
+        if let Some(node) = this.numa_node {
+            crate::utils::bind_to_numa_node(node)?;
+        }
+        #[cfg(feature = "sync")]
+        let blocking_handle = this.blocking_handle;
+
+        BUILD_THREAD_ID.set(&thread_id, || {
+            let driver = match this.entries {
+                Some(entries) => IocpDriver::new_with_entries(entries)?,
+                None => IocpDriver::new()?,
+            };
+            #[cfg(feature = "sync")]
+            let context = crate::runtime::Context::new(blocking_handle);
+            #[cfg(not(feature = "sync"))]
+            let context = crate::runtime::Context::new();
+            #[cfg(feature = "watchdog")]
+            let context = context.with_watchdog(this.watchdog);
+            Ok(Runtime::new(
+                context
+                    .with_clock(this.clock, this.coarse_clock)
+                    .with_coop_budget(this.coop_budget)
+                    .with_scheduler_opts(&this.scheduler_opts)
+                    .with_panic_policy(this.panic_policy),
+                driver,
+            ))
         })
     }
 }
@@ -126,19 +245,77 @@ impl Buildable for LegacyDriver {
 impl Buildable for IoUringDriver {
     fn build(this: RuntimeBuilder<Self>) -> io::Result<Runtime<IoUringDriver>> {
         let thread_id = gen_id();
+        #[cfg(feature = "utils")]
+        if let Some(node) = this.numa_node {
+            crate::utils::bind_to_numa_node(node)?;
+        }
         #[cfg(feature = "sync")]
         let blocking_handle = this.blocking_handle;
 
         BUILD_THREAD_ID.set(&thread_id, || {
             let driver = match this.entries {
-                Some(entries) => IoUringDriver::new_with_entries(&this.urb, entries)?,
-                None => IoUringDriver::new(&this.urb)?,
+                Some(entries) => {
+                    IoUringDriver::new_with_entries(&this.urb, entries, &this.uring_opts)?
+                }
+                None => IoUringDriver::new(&this.urb, &this.uring_opts)?,
             };
+            driver.apply_opts(&this.uring_opts)?;
             #[cfg(feature = "sync")]
             let context = crate::runtime::Context::new(blocking_handle);
             #[cfg(not(feature = "sync"))]
             let context = crate::runtime::Context::new();
-            Ok(Runtime::new(context, driver))
+            #[cfg(feature = "watchdog")]
+            let context = context.with_watchdog(this.watchdog);
+            Ok(Runtime::new(
+                context
+                    .with_clock(this.clock, this.coarse_clock)
+                    .with_coop_budget(this.coop_budget)
+                    .with_scheduler_opts(&this.scheduler_opts)
+                    .with_panic_policy(this.panic_policy),
+                driver,
+            ))
+        })
+    }
+}
+
+impl<D: Driver> RuntimeBuilder<D> {
+    /// Build the runtime with a driver created by the caller, which is usually implemented
+    /// outside monoio. The settings for the builtin drivers(e.g. entries) are ignored.
+    ///
+    /// To enable the timer, implement [`Buildable`] for the driver with this method, then build
+    /// `RuntimeBuilder<TimeDriver<D>>`(see [`Driver`] for the requirements of the driver).
+    #[must_use]
+    pub fn build_with_driver(self, driver: D) -> Runtime<D> {
+        let thread_id = gen_id();
+        #[cfg(feature = "sync")]
+        let blocking_handle = self.blocking_handle;
+
+        BUILD_THREAD_ID.set(&thread_id, || {
+            #[cfg(feature = "sync")]
+            let context = {
+                use crate::driver::{thread, unpark::Unpark, UnparkHandle};
+
+                let unpark: std::sync::Arc<dyn Unpark> = std::sync::Arc::new(driver.unpark());
+                let (waker_sender, waker_receiver) = flume::unbounded::<std::task::Waker>();
+                thread::register_unpark_handle(thread_id, UnparkHandle::Custom(unpark));
+                thread::register_waker_sender(thread_id, waker_sender);
+
+                let mut context = crate::runtime::Context::new(blocking_handle);
+                context.remote_wakers = Some(waker_receiver);
+                context
+            };
+            #[cfg(not(feature = "sync"))]
+            let context = crate::runtime::Context::new();
+            #[cfg(feature = "watchdog")]
+            let context = context.with_watchdog(self.watchdog);
+            Runtime::new(
+                context
+                    .with_clock(self.clock, self.coarse_clock)
+                    .with_coop_budget(self.coop_budget)
+                    .with_scheduler_opts(&self.scheduler_opts)
+                    .with_panic_policy(self.panic_policy),
+                driver,
+            )
         })
     }
 }
@@ -161,13 +338,250 @@ impl<D> RuntimeBuilder<D> {
     /// Replaces the default [`io_uring::Builder`], which controls the settings for the
     /// inner `io_uring` API.
     ///
-    /// Refer to the [`io_uring::Builder`] documentation for all the supported methods.
+    /// Refer to the [`io_uring::Builder`] documentation for all the supported methods. It is not
+    /// used with [`uring_big_entries`](Self::uring_big_entries).
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     #[must_use]
     pub fn uring_builder(mut self, urb: io_uring::Builder) -> Self {
         self.urb = urb;
         self
     }
+
+    /// Enable `IORING_SETUP_SQPOLL`: a kernel thread polls the submission queue, so submitting
+    /// does not need a syscall while the thread is busy. The thread goes to sleep after being
+    /// idle for `idle_ms` milliseconds, and is pinned to `cpu` if given.
+    ///
+    /// Before kernel 5.11 it requires `CAP_SYS_ADMIN`.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[must_use]
+    pub fn uring_sqpoll(mut self, idle_ms: u32, cpu: Option<u32>) -> Self {
+        self.urb.setup_sqpoll(idle_ms);
+        if let Some(cpu) = cpu {
+            self.urb.setup_sqpoll_cpu(cpu);
+        }
+        self.uring_opts.sqpoll = Some((idle_ms, cpu));
+        self
+    }
+
+    /// Try `IORING_SETUP_COOP_TASKRUN`(requires kernel 5.19+): the kernel does not interrupt
+    /// the thread to post completions, but defers the work to its next transition into the
+    /// kernel. The runtime always enters the kernel to reap completions, so it is safe to
+    /// enable.
+    ///
+    /// If the kernel does not support it, the runtime is built without it.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[must_use]
+    pub fn uring_coop_taskrun(mut self, enable: bool) -> Self {
+        self.uring_opts.coop_taskrun = enable;
+        self
+    }
+
+    /// Try `IORING_SETUP_SINGLE_ISSUER`(requires kernel 6.0+): hint the kernel that only the
+    /// runtime thread submits to the ring, which lets it skip some synchronization.
+    ///
+    /// If the kernel does not support it, the runtime is built without it.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[must_use]
+    pub fn uring_single_issuer(mut self, enable: bool) -> Self {
+        self.uring_opts.single_issuer = enable;
+        self
+    }
+
+    /// Try `IORING_SETUP_DEFER_TASKRUN`(requires kernel 6.1+): the completion work is only run
+    /// when the runtime asks for completions, which batches it and avoids interrupting the
+    /// thread. It implies `IORING_SETUP_SINGLE_ISSUER`, and can not be combined with
+    /// [`uring_sqpoll`](Self::uring_sqpoll).
+    ///
+    /// If the kernel does not support it, or it is combined with SQPOLL, the runtime falls back
+    /// to the other flags enabled.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[must_use]
+    pub fn uring_defer_taskrun(mut self, enable: bool) -> Self {
+        self.uring_opts.defer_taskrun = enable;
+        self
+    }
+
+    /// Run in hybrid mode: files are still read and written with io_uring, but sockets are driven
+    /// by readiness with an epoll poller, like the legacy driver. The poller is waited on by the
+    /// ring with `POLL_ADD`, so the runtime still parks on io_uring only.
+    ///
+    /// It is for deployments which trust io_uring only for storage. Sockets created or accepted
+    /// by the runtime are set to non-blocking and registered to the poller.
+    #[cfg(all(target_os = "linux", feature = "iouring", feature = "poll-io"))]
+    #[must_use]
+    pub fn uring_hybrid_net(mut self, enable: bool) -> Self {
+        self.uring_opts.hybrid = enable;
+        self
+    }
+
+    /// Build the ring with `IORING_SETUP_SQE128` and `IORING_SETUP_CQE32`(requires kernel 5.19+),
+    /// which doubles the size of the SQE and the CQE. They are required by some `URING_CMD`
+    /// commands like NVMe passthrough, see [`UringCmd`](crate::driver::UringCmd).
+    ///
+    /// The ring is built with a new [`io_uring::Builder`] instead of the one set by
+    /// [`uring_builder`](Self::uring_builder), so only the settings of this builder apply.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[must_use]
+    pub fn uring_big_entries(mut self, enable: bool) -> Self {
+        self.uring_opts.big_entries = enable;
+        self
+    }
+
+    /// Set the size of the completion queue with `IORING_SETUP_CQSIZE`, which is twice the
+    /// entries by default. It must be no less than the entries.
+    ///
+    /// A larger CQ avoids the overflow when there are many operations in flight, see
+    /// [`CqOverflowPolicy`](crate::driver::CqOverflowPolicy).
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[must_use]
+    pub fn uring_cq_entries(mut self, entries: u32) -> Self {
+        self.urb.setup_cqsize(entries);
+        self.uring_opts.cq_entries = Some(entries);
+        self
+    }
+
+    /// Build the ring with `IORING_SETUP_IOPOLL`: the completions of the IO are reaped by
+    /// polling the device instead of being notified by interrupts, which cuts the latency of the
+    /// fast block devices like NVMe SSDs.
+    ///
+    /// It is for the runtimes dedicated to polled block IO. Only the reads and writes on files
+    /// opened with `O_DIRECT`(see [`OpenOptions::direct`](crate::fs::OpenOptions::direct)) on
+    /// devices with polling queues are supported, other operations like sockets and timeouts
+    /// fail. The runtime busy polls the ring instead of sleeping, so the thread keeps a CPU busy.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[must_use]
+    pub fn uring_iopoll(mut self, enable: bool) -> Self {
+        self.uring_opts.iopoll = enable;
+        self
+    }
+
+    /// Record the latency of the io_uring operations, from pushing the SQE to reaping the CQE,
+    /// into a histogram per opcode, which can be read with
+    /// [`runtime::op_latency`](crate::runtime::op_latency). The operations slower than
+    /// `slow_threshold` are reported with their opcode and fd, as `tracing` events when the
+    /// `tracing` feature is enabled, and to the callback set with
+    /// [`uring_slow_op_callback`](Self::uring_slow_op_callback).
+    ///
+    /// It reads the clock twice per operation, so it is disabled by default.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[must_use]
+    pub fn uring_op_latency(
+        mut self,
+        enable: bool,
+        slow_threshold: Option<std::time::Duration>,
+    ) -> Self {
+        self.uring_opts.op_latency = enable;
+        self.uring_opts.slow_op_threshold = slow_threshold;
+        self
+    }
+
+    /// Call `callback` on the runtime thread with each io_uring operation slower than the
+    /// threshold set with [`uring_op_latency`](Self::uring_op_latency).
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// let builder = monoio::RuntimeBuilder::<monoio::IoUringDriver>::new()
+    ///     .uring_op_latency(true, Some(Duration::from_millis(10)))
+    ///     .uring_slow_op_callback(|op| {
+    ///         eprintln!("slow op {} on fd {}: {:?}", op.opcode, op.fd, op.latency);
+    ///     });
+    /// ```
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[must_use]
+    pub fn uring_slow_op_callback(
+        mut self,
+        callback: impl Fn(&crate::runtime::SlowOp) + Send + Sync + 'static,
+    ) -> Self {
+        self.uring_opts.slow_op_callback = Some(crate::runtime::metrics::SlowOpCallback(
+            std::sync::Arc::new(callback),
+        ));
+        self
+    }
+
+    /// Set what to do when the in-flight operations may overflow the completion queue. The
+    /// default is [`CqOverflowPolicy::Flush`](crate::driver::CqOverflowPolicy::Flush).
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[must_use]
+    pub fn uring_cq_overflow(mut self, policy: crate::driver::CqOverflowPolicy) -> Self {
+        self.uring_opts.cq_overflow = policy;
+        self
+    }
+
+    /// Set when the queued SQEs are submitted to the kernel, see
+    /// [`SubmitPolicy`](crate::driver::SubmitPolicy).
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[must_use]
+    pub fn uring_submit_policy(mut self, policy: crate::driver::SubmitPolicy) -> Self {
+        self.uring_opts.submit_policy = policy;
+        self
+    }
+
+    /// Limit the number of io-wq workers per NUMA node with `IORING_REGISTER_IOWQ_MAX_WORKERS`
+    /// (requires kernel 5.15+). The io-wq workers are kernel threads running the operations which
+    /// can not be completed inline, like buffered file IO.
+    ///
+    /// `bounded` is for the operations expected to finish in time(e.g. regular files), and
+    /// `unbounded` for those may never finish(e.g. sockets). `0` keeps the current limit.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[must_use]
+    pub fn uring_iowq_max_workers(mut self, bounded: u32, unbounded: u32) -> Self {
+        self.uring_opts.iowq_max_workers = Some([bounded, unbounded]);
+        self
+    }
+
+    /// Pin the io-wq workers to the given cpus with `IORING_REGISTER_IOWQ_AFF`(requires kernel
+    /// 5.14+). By default they inherit the affinity of the thread building the runtime.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[must_use]
+    pub fn uring_iowq_affinity(mut self, cpus: impl IntoIterator<Item = usize>) -> Self {
+        self.uring_opts.iowq_affinity = Some(cpus.into_iter().collect());
+        self
+    }
+
+    /// Lock the ring down with `IORING_REGISTER_RESTRICTIONS`(requires kernel 5.10+): only the
+    /// opcodes, register opcodes and SQE flags allowed can be used once the runtime is built,
+    /// e.g. before dropping privileges.
+    ///
+    /// The opcodes used by the runtime itself(`ASYNC_CANCEL`, `TIMEOUT` on old kernels, `READ`
+    /// and `MSG_RING` with `sync`, `POLL_ADD` with `poll-io`) are always allowed. The other
+    /// operations whose opcode is not allowed are executed with syscalls when they can be,
+    /// see [`capabilities`](crate::driver::capabilities), and fail with `EACCES` otherwise.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[must_use]
+    pub fn uring_restrictions(
+        mut self,
+        restrictions: impl IntoIterator<Item = crate::driver::Restriction>,
+    ) -> Self {
+        self.uring_opts.restrictions = Some(restrictions.into_iter().collect());
+        self
+    }
+
+    /// Try registering the ring fd with `IORING_REGISTER_RING_FDS`(requires kernel 5.18+), so
+    /// `io_uring_enter` refers to the registered index instead of looking up the fd table on
+    /// every call.
+    ///
+    /// The registration belongs to the thread building the runtime. If the kernel does not
+    /// support it, the runtime is built without it.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[must_use]
+    pub fn uring_register_ring_fd(mut self, enable: bool) -> Self {
+        self.uring_opts.register_ring_fd = enable;
+        self
+    }
+
+    /// Register a sparse fixed file table with `nr` slots(requires kernel 5.19+).
+    ///
+    /// Fds can then be installed into the table with methods like
+    /// [`TcpStream::register_fixed`](crate::net::TcpStream::register_fixed), and the
+    /// operations on them refer to the slot instead of the fd, which saves the per-op fd
+    /// lookup and reference counting in the kernel.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[must_use]
+    pub fn with_fixed_files(mut self, nr: u32) -> Self {
+        self.uring_opts.fixed_files = Some(nr);
+        self
+    }
 }
 
 // ===== FusionDriver =====
@@ -185,6 +599,16 @@ impl RuntimeBuilder<FusionDriver> {
             let builder = RuntimeBuilder::<IoUringDriver> {
                 entries: self.entries,
                 urb: self.urb,
+                uring_opts: self.uring_opts,
+                coarse_clock: self.coarse_clock,
+                clock: self.clock,
+                coop_budget: self.coop_budget,
+                scheduler_opts: self.scheduler_opts,
+                panic_policy: self.panic_policy,
+                #[cfg(feature = "utils")]
+                numa_node: self.numa_node,
+                #[cfg(feature = "watchdog")]
+                watchdog: self.watchdog,
                 #[cfg(feature = "sync")]
                 blocking_handle: self.blocking_handle,
                 _mark: PhantomData,
@@ -195,6 +619,16 @@ impl RuntimeBuilder<FusionDriver> {
             let builder = RuntimeBuilder::<LegacyDriver> {
                 entries: self.entries,
                 urb: self.urb,
+                uring_opts: self.uring_opts,
+                coarse_clock: self.coarse_clock,
+                clock: self.clock,
+                coop_budget: self.coop_budget,
+                scheduler_opts: self.scheduler_opts,
+                panic_policy: self.panic_policy,
+                #[cfg(feature = "utils")]
+                numa_node: self.numa_node,
+                #[cfg(feature = "watchdog")]
+                watchdog: self.watchdog,
                 #[cfg(feature = "sync")]
                 blocking_handle: self.blocking_handle,
                 _mark: PhantomData,
@@ -209,6 +643,15 @@ impl RuntimeBuilder<FusionDriver> {
     pub fn build(self) -> io::Result<crate::FusionRuntime<LegacyDriver>> {
         let builder = RuntimeBuilder::<LegacyDriver> {
             entries: self.entries,
+            coarse_clock: self.coarse_clock,
+            clock: self.clock,
+            coop_budget: self.coop_budget,
+            scheduler_opts: self.scheduler_opts,
+            panic_policy: self.panic_policy,
+            #[cfg(feature = "utils")]
+            numa_node: self.numa_node,
+            #[cfg(feature = "watchdog")]
+            watchdog: self.watchdog,
             #[cfg(feature = "sync")]
             blocking_handle: self.blocking_handle,
             _mark: PhantomData,
@@ -222,6 +665,16 @@ impl RuntimeBuilder<FusionDriver> {
         let builder = RuntimeBuilder::<IoUringDriver> {
             entries: self.entries,
             urb: self.urb,
+            uring_opts: self.uring_opts,
+            coarse_clock: self.coarse_clock,
+            clock: self.clock,
+            coop_budget: self.coop_budget,
+            scheduler_opts: self.scheduler_opts,
+            panic_policy: self.panic_policy,
+            #[cfg(feature = "utils")]
+            numa_node: self.numa_node,
+            #[cfg(feature = "watchdog")]
+            watchdog: self.watchdog,
             #[cfg(feature = "sync")]
             blocking_handle: self.blocking_handle,
             _mark: PhantomData,
@@ -241,6 +694,16 @@ impl RuntimeBuilder<TimeDriver<FusionDriver>> {
             let builder = RuntimeBuilder::<TimeDriver<IoUringDriver>> {
                 entries: self.entries,
                 urb: self.urb,
+                uring_opts: self.uring_opts,
+                coarse_clock: self.coarse_clock,
+                clock: self.clock,
+                coop_budget: self.coop_budget,
+                scheduler_opts: self.scheduler_opts,
+                panic_policy: self.panic_policy,
+                #[cfg(feature = "utils")]
+                numa_node: self.numa_node,
+                #[cfg(feature = "watchdog")]
+                watchdog: self.watchdog,
                 #[cfg(feature = "sync")]
                 blocking_handle: self.blocking_handle,
                 _mark: PhantomData,
@@ -251,6 +714,16 @@ impl RuntimeBuilder<TimeDriver<FusionDriver>> {
             let builder = RuntimeBuilder::<TimeDriver<LegacyDriver>> {
                 entries: self.entries,
                 urb: self.urb,
+                uring_opts: self.uring_opts,
+                coarse_clock: self.coarse_clock,
+                clock: self.clock,
+                coop_budget: self.coop_budget,
+                scheduler_opts: self.scheduler_opts,
+                panic_policy: self.panic_policy,
+                #[cfg(feature = "utils")]
+                numa_node: self.numa_node,
+                #[cfg(feature = "watchdog")]
+                watchdog: self.watchdog,
                 #[cfg(feature = "sync")]
                 blocking_handle: self.blocking_handle,
                 _mark: PhantomData,
@@ -265,6 +738,15 @@ impl RuntimeBuilder<TimeDriver<FusionDriver>> {
     pub fn build(self) -> io::Result<crate::FusionRuntime<TimeDriver<LegacyDriver>>> {
         let builder = RuntimeBuilder::<TimeDriver<LegacyDriver>> {
             entries: self.entries,
+            coarse_clock: self.coarse_clock,
+            clock: self.clock,
+            coop_budget: self.coop_budget,
+            scheduler_opts: self.scheduler_opts,
+            panic_policy: self.panic_policy,
+            #[cfg(feature = "utils")]
+            numa_node: self.numa_node,
+            #[cfg(feature = "watchdog")]
+            watchdog: self.watchdog,
             #[cfg(feature = "sync")]
             blocking_handle: self.blocking_handle,
             _mark: PhantomData,
@@ -278,6 +760,16 @@ impl RuntimeBuilder<TimeDriver<FusionDriver>> {
         let builder = RuntimeBuilder::<TimeDriver<IoUringDriver>> {
             entries: self.entries,
             urb: self.urb,
+            uring_opts: self.uring_opts,
+            coarse_clock: self.coarse_clock,
+            clock: self.clock,
+            coop_budget: self.coop_budget,
+            scheduler_opts: self.scheduler_opts,
+            panic_policy: self.panic_policy,
+            #[cfg(feature = "utils")]
+            numa_node: self.numa_node,
+            #[cfg(feature = "watchdog")]
+            watchdog: self.watchdog,
             #[cfg(feature = "sync")]
             blocking_handle: self.blocking_handle,
             _mark: PhantomData,
@@ -295,6 +787,8 @@ mod time_wrap {
 impl time_wrap::TimeWrapable for IoUringDriver {}
 #[cfg(feature = "legacy")]
 impl time_wrap::TimeWrapable for LegacyDriver {}
+#[cfg(all(windows, feature = "iocp"))]
+impl time_wrap::TimeWrapable for IocpDriver {}
 #[cfg(any(all(target_os = "linux", feature = "iouring"), feature = "legacy"))]
 impl time_wrap::TimeWrapable for FusionDriver {}
 
@@ -311,12 +805,23 @@ where
             entries: this.entries,
             #[cfg(all(target_os = "linux", feature = "iouring"))]
             urb: this.urb,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            uring_opts: this.uring_opts,
+            coarse_clock: this.coarse_clock,
+            clock: this.clock,
+            coop_budget: this.coop_budget,
+            scheduler_opts: this.scheduler_opts,
+            panic_policy: this.panic_policy,
+            #[cfg(feature = "utils")]
+            numa_node: this.numa_node,
+            #[cfg(feature = "watchdog")]
+            watchdog: this.watchdog,
             #[cfg(feature = "sync")]
             blocking_handle: this.blocking_handle,
             _mark: PhantomData,
         })?;
 
-        let timer_driver = TimeDriver::new(driver, Clock::new());
+        let timer_driver = TimeDriver::new(driver, context.clock.clone());
         context.time_handle = Some(timer_driver.handle.clone());
         Ok(Runtime {
             driver: timer_driver,
@@ -339,6 +844,17 @@ impl<D: time_wrap::TimeWrapable> RuntimeBuilder<D> {
             entries,
             #[cfg(all(target_os = "linux", feature = "iouring"))]
             urb,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            uring_opts,
+            coarse_clock,
+            clock,
+            coop_budget,
+            scheduler_opts,
+            panic_policy,
+            #[cfg(feature = "utils")]
+            numa_node,
+            #[cfg(feature = "watchdog")]
+            watchdog,
             #[cfg(feature = "sync")]
             blocking_handle,
             ..
@@ -347,6 +863,17 @@ impl<D: time_wrap::TimeWrapable> RuntimeBuilder<D> {
             entries,
             #[cfg(all(target_os = "linux", feature = "iouring"))]
             urb,
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            uring_opts,
+            coarse_clock,
+            clock,
+            coop_budget,
+            scheduler_opts,
+            panic_policy,
+            #[cfg(feature = "utils")]
+            numa_node,
+            #[cfg(feature = "watchdog")]
+            watchdog,
             #[cfg(feature = "sync")]
             blocking_handle,
             _mark: PhantomData,
@@ -367,6 +894,147 @@ impl<D> RuntimeBuilder<D> {
         self
     }
 
+    /// Bind the runtime thread to the cpus of the NUMA node, and allocate the rings and the
+    /// buffers of [`crate::buf`] from the memory of the node, see
+    /// [`bind_to_numa_node`](crate::utils::bind_to_numa_node). The thread is bound when the
+    /// runtime of a builtin driver is built, and building fails with
+    /// [`Unsupported`](std::io::ErrorKind::Unsupported) on non-linux platforms.
+    #[cfg(feature = "utils")]
+    #[must_use]
+    pub fn with_numa_node(mut self, node: usize) -> Self {
+        self.numa_node = Some(node);
+        self
+    }
+
+    /// Read `CLOCK_MONOTONIC_COARSE` for [`Instant::recent`](crate::time::Instant::recent)
+    /// on Linux, which is cheaper than the default clock but has a resolution of a few
+    /// milliseconds. It is disabled by default, and ignored with the `test-util` feature.
+    #[must_use]
+    pub fn with_coarse_clock(mut self, enable: bool) -> Self {
+        self.coarse_clock = enable;
+        self
+    }
+
+    /// Use a custom clock for the runtime, e.g. reading the TSC or simulated, which
+    /// [`Instant::now`](crate::time::Instant::now) and all the timers are routed through.
+    /// [`with_coarse_clock`](Self::with_coarse_clock) is ignored with it.
+    #[must_use]
+    pub fn with_clock(mut self, clock: impl crate::time::Clock + Send) -> Self {
+        self.clock = Some(Box::new(clock));
+        self
+    }
+
+    /// Set the cooperative scheduling budget, i.e. how many io operations a task can complete
+    /// in one poll before they return `Pending` and yield to the other tasks, so a task whose
+    /// resources are always ready cannot starve the others. `None` disables it, and
+    /// [`unconstrained`](crate::task::unconstrained) opts a single future out. It is 128 by
+    /// default.
+    #[must_use]
+    pub fn with_coop_budget(mut self, budget: Option<u8>) -> Self {
+        self.coop_budget = budget;
+        self
+    }
+
+    /// Put the task woken or spawned by the running task into a LIFO slot, which runs next
+    /// instead of waiting behind the queue. It reduces the latency of message passing between
+    /// tasks, e.g. request and response over a channel. A task in the slot runs ahead of the
+    /// priorities, and at most 3 times in a row before the queue. It is disabled by default.
+    #[must_use]
+    pub fn with_lifo_slot(mut self, enable: bool) -> Self {
+        self.scheduler_opts.lifo_slot = enable;
+        self
+    }
+
+    /// Set the initial capacity of the run queue, which grows if needed. It is 4096 by default.
+    #[must_use]
+    pub fn with_task_queue_capacity(mut self, capacity: usize) -> Self {
+        self.scheduler_opts.queue_capacity = capacity;
+        self
+    }
+
+    /// Set the max tasks to run before checking the driver for io and timers. A smaller value
+    /// favors the latency of io, e.g. RPC, and a larger one favors the throughput of tasks, e.g.
+    /// streaming. It is twice the number of the queued tasks at the time by default.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0.
+    #[must_use]
+    pub fn with_max_tasks_per_tick(mut self, n: usize) -> Self {
+        assert!(n > 0, "max tasks per tick must be larger than 0");
+        self.scheduler_opts.max_tasks_per_tick = Some(n);
+        self
+    }
+
+    /// Set what to do when a spawned task panics, see [`PanicPolicy`](crate::runtime::PanicPolicy).
+    /// By default the panic unwinds out of `block_on`.
+    #[must_use]
+    pub fn with_panic_policy(mut self, policy: crate::runtime::PanicPolicy) -> Self {
+        self.panic_policy = policy;
+        self
+    }
+
+    /// Call `hook` with the panic when a spawned task panics, and keep running the other tasks.
+    /// It is a shortcut of [`PanicPolicy::Hook`](crate::runtime::PanicPolicy::Hook).
+    ///
+    /// ```
+    /// let mut rt = monoio::RuntimeBuilder::<monoio::FusionDriver>::new()
+    ///     .with_panic_hook(|panic| {
+    ///         eprintln!(
+    ///             "task spawned at {} panicked: {:?}",
+    ///             panic.spawned_at(),
+    ///             panic.message()
+    ///         );
+    ///     })
+    ///     .build()
+    ///     .unwrap();
+    /// rt.block_on(async {
+    ///     let task = monoio::spawn(async { panic!("oops") });
+    ///     while !task.is_finished() {
+    ///         monoio::task::yield_now().await;
+    ///     }
+    /// });
+    /// ```
+    #[must_use]
+    pub fn with_panic_hook(
+        self,
+        hook: impl Fn(&crate::runtime::TaskPanic<'_>) + Send + Sync + 'static,
+    ) -> Self {
+        self.with_panic_policy(crate::runtime::PanicPolicy::Hook(std::sync::Arc::new(hook)))
+    }
+
+    /// Start a watchdog thread, which calls `callback` when the runtime thread has not ticked
+    /// the driver for `timeout`, with the task being polled at the time and where it is spawned.
+    /// It helps to catch the blocking calls in the tasks, which stall all the other tasks on the
+    /// thread. The callback is called on the watchdog thread, once per blocked tick.
+    ///
+    /// The runtime is not considered blocked while it is parked or not running.
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// let mut rt = monoio::RuntimeBuilder::<monoio::FusionDriver>::new()
+    ///     .with_watchdog(Duration::from_millis(100), |report| {
+    ///         eprintln!("runtime blocked: {report:?}");
+    ///     })
+    ///     .build()
+    ///     .unwrap();
+    /// rt.block_on(async {});
+    /// ```
+    #[cfg(feature = "watchdog")]
+    #[must_use]
+    pub fn with_watchdog(
+        mut self,
+        timeout: std::time::Duration,
+        callback: impl Fn(&crate::runtime::BlockedReactor) + Send + Sync + 'static,
+    ) -> Self {
+        self.watchdog = Some(crate::runtime::watchdog::Watchdog {
+            timeout,
+            callback: std::sync::Arc::new(callback),
+        });
+        self
+    }
+
     /// Set blocking strategy, this will overwrite thread pool setting.
     /// If `BlockingStrategy::Panic` is used, it will panic if `spawn_blocking` on this thread.
     /// If `BlockingStrategy::ExecuteLocal` is used, it will execute with current thread, and may
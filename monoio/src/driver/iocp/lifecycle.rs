@@ -0,0 +1,168 @@
+//! IOCP operation lifecycle, which is the same as the uring one.
+
+use std::{
+    io,
+    os::windows::io::RawSocket,
+    task::{Context, Poll, Waker},
+};
+
+use windows_sys::Win32::{
+    Foundation::FALSE,
+    Networking::WinSock::{WSAGetLastError, WSAGetOverlappedResult},
+    System::IO::OVERLAPPED,
+};
+
+use crate::utils::slab::Ref;
+
+enum Lifecycle {
+    /// The operation has been started and is currently in-flight
+    Submitted,
+
+    /// The submitter is waiting for the completion of the operation
+    Waiting(Waker),
+
+    /// The submitter no longer has interest in the operation result. The state
+    /// must be passed to the driver and held until the operation completes.
+    #[allow(dead_code)]
+    Ignored(Box<dyn std::any::Any>),
+
+    /// The operation has completed with the number of bytes transferred.
+    Completed(io::Result<u32>),
+}
+
+/// The `OVERLAPPED` of an operation, whose address is posted back with the completion.
+#[repr(C)]
+pub(crate) struct Overlapped {
+    raw: OVERLAPPED,
+    index: usize,
+}
+
+impl Overlapped {
+    /// Get the slab index of the operation from the posted `OVERLAPPED`.
+    ///
+    /// # Safety
+    /// The pointer must be the `raw` of an `Overlapped`.
+    pub(crate) unsafe fn index(ptr: *const OVERLAPPED) -> usize {
+        (*ptr.cast::<Overlapped>()).index
+    }
+}
+
+pub(crate) struct OverlappedLifecycle {
+    socket: RawSocket,
+    overlapped: Box<Overlapped>,
+    lifecycle: Lifecycle,
+}
+
+impl OverlappedLifecycle {
+    #[inline]
+    pub(crate) fn new(socket: RawSocket) -> Self {
+        Self {
+            socket,
+            overlapped: Box::new(Overlapped {
+                raw: unsafe { std::mem::zeroed() },
+                index: 0,
+            }),
+            lifecycle: Lifecycle::Submitted,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn socket(&self) -> RawSocket {
+        self.socket
+    }
+
+    /// The `OVERLAPPED` to start the operation with, which stays at the same address until the
+    /// lifecycle is removed.
+    #[inline]
+    pub(crate) fn overlapped(&mut self, index: usize) -> *mut OVERLAPPED {
+        self.overlapped.index = index;
+        &mut self.overlapped.raw
+    }
+
+    /// If the completion of the operation is not posted yet.
+    #[inline]
+    pub(crate) fn in_flight(&self) -> bool {
+        !matches!(self.lifecycle, Lifecycle::Completed(_))
+    }
+}
+
+impl Ref<'_, OverlappedLifecycle> {
+    /// Complete the operation with the error it fails to start with, there is nothing posted.
+    pub(crate) fn fail(mut self, err: io::Error) {
+        self.lifecycle = Lifecycle::Completed(Err(err));
+    }
+
+    /// Complete the operation whose completion is posted to the port.
+    pub(crate) fn complete(mut self) {
+        let mut transferred = 0;
+        let mut flags = 0;
+        let ok = unsafe {
+            WSAGetOverlappedResult(
+                self.socket as _,
+                &self.overlapped.raw,
+                &mut transferred,
+                FALSE,
+                &mut flags,
+            )
+        };
+        let result = if ok == FALSE {
+            Err(io::Error::from_raw_os_error(unsafe { WSAGetLastError() }))
+        } else {
+            Ok(transferred)
+        };
+
+        let ref_mut = &mut self.lifecycle;
+        match ref_mut {
+            Lifecycle::Submitted => {
+                *ref_mut = Lifecycle::Completed(result);
+            }
+            Lifecycle::Waiting(_) => match std::mem::replace(ref_mut, Lifecycle::Completed(result))
+            {
+                Lifecycle::Waiting(waker) => waker.wake(),
+                _ => unsafe { std::hint::unreachable_unchecked() },
+            },
+            Lifecycle::Ignored(..) => {
+                self.remove();
+            }
+            Lifecycle::Completed(..) => unsafe { std::hint::unreachable_unchecked() },
+        }
+    }
+
+    pub(crate) fn poll_op(mut self, cx: &mut Context<'_>) -> Poll<io::Result<u32>> {
+        let ref_mut = &mut self.lifecycle;
+        match ref_mut {
+            Lifecycle::Submitted => {
+                *ref_mut = Lifecycle::Waiting(cx.waker().clone());
+                return Poll::Pending;
+            }
+            Lifecycle::Waiting(waker) => {
+                if !waker.will_wake(cx.waker()) {
+                    *ref_mut = Lifecycle::Waiting(cx.waker().clone());
+                }
+                return Poll::Pending;
+            }
+            _ => {}
+        }
+
+        match self.remove().lifecycle {
+            Lifecycle::Completed(result) => Poll::Ready(result),
+            _ => unsafe { std::hint::unreachable_unchecked() },
+        }
+    }
+
+    // return if the op must has been finished
+    pub(crate) fn drop_op<T: 'static>(mut self, data: &mut Option<T>) -> bool {
+        let ref_mut = &mut self.lifecycle;
+        match ref_mut {
+            Lifecycle::Submitted | Lifecycle::Waiting(_) => {
+                *ref_mut = Lifecycle::Ignored(Box::new(data.take()));
+                return false;
+            }
+            Lifecycle::Completed(..) => {
+                self.remove();
+            }
+            Lifecycle::Ignored(..) => unsafe { std::hint::unreachable_unchecked() },
+        }
+        true
+    }
+}
@@ -0,0 +1,381 @@
+//! Monoio IOCP Driver.
+//!
+//! The operations on sockets are started as overlapped operations, and their completions are
+//! posted to the IO completion port of the driver, which is the same completion model as the
+//! uring driver. The operations without an overlapped version are executed with syscalls on the
+//! runtime thread, which block the thread if the socket is not ready.
+
+use std::{
+    cell::UnsafeCell,
+    io,
+    os::windows::io::RawSocket,
+    rc::Rc,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use windows_sys::Win32::{
+    Foundation::{HANDLE, WAIT_TIMEOUT},
+    Networking::WinSock::{WSAGetLastError, WSA_IO_PENDING},
+    System::IO::{CancelIoEx, OVERLAPPED_ENTRY},
+};
+
+use self::lifecycle::{Overlapped, OverlappedLifecycle};
+use super::{
+    legacy::iocp::CompletionPort,
+    op::{CompletionMeta, Op, OpAble},
+    Driver, Inner, CURRENT,
+};
+use crate::utils::slab::Slab;
+
+mod lifecycle;
+
+/// The index of the ops executed with syscalls, which are not started on the port.
+pub(crate) const SYNC_INDEX: usize = usize::MAX - 1;
+
+/// Check the result of starting an overlapped operation, which is started if it succeeds or is
+/// pending. Its completion is posted to the port in both cases.
+pub(crate) fn check_started(failed: bool) -> io::Result<()> {
+    if failed {
+        let err = unsafe { WSAGetLastError() };
+        if err != WSA_IO_PENDING {
+            return Err(io::Error::from_raw_os_error(err));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "sync")]
+const TOKEN_WAKEUP: mio::Token = mio::Token(1 << 31);
+
+pub(crate) struct IocpInner {
+    port: Arc<CompletionPort>,
+    ops: Slab<OverlappedLifecycle>,
+    entries: Box<[OVERLAPPED_ENTRY]>,
+
+    // Number of the ops whose completion is not posted yet
+    in_flight: usize,
+
+    // Leak the ops on drop, set if they are still in flight when the runtime shuts down
+    leak_ops: bool,
+
+    #[cfg(feature = "sync")]
+    shared_waker: Arc<super::legacy::waker::EventWaker>,
+
+    // Waker receiver
+    #[cfg(feature = "sync")]
+    waker_receiver: flume::Receiver<std::task::Waker>,
+}
+
+/// Driver with IO completion port.
+pub struct IocpDriver {
+    inner: Rc<UnsafeCell<IocpInner>>,
+
+    // Used for drop
+    #[cfg(feature = "sync")]
+    thread_id: usize,
+}
+
+impl IocpDriver {
+    const DEFAULT_ENTRIES: u32 = 1024;
+
+    pub(crate) fn new() -> io::Result<Self> {
+        Self::new_with_entries(Self::DEFAULT_ENTRIES)
+    }
+
+    /// Create the driver, which dequeues up to `entries` completions at a time.
+    pub(crate) fn new_with_entries(entries: u32) -> io::Result<Self> {
+        let port = Arc::new(CompletionPort::new(0)?);
+
+        #[cfg(feature = "sync")]
+        let shared_waker = Arc::new(super::legacy::waker::EventWaker::new(
+            super::legacy::iocp::Waker::with_port(port.clone(), TOKEN_WAKEUP),
+        ));
+        #[cfg(feature = "sync")]
+        let (waker_sender, waker_receiver) = flume::unbounded::<std::task::Waker>();
+        #[cfg(feature = "sync")]
+        let thread_id = crate::builder::BUILD_THREAD_ID.with(|id| *id);
+
+        let inner = IocpInner {
+            port,
+            ops: Slab::new(),
+            entries: (0..entries.max(1))
+                .map(|_| unsafe { std::mem::zeroed() })
+                .collect(),
+            in_flight: 0,
+            leak_ops: false,
+            #[cfg(feature = "sync")]
+            shared_waker,
+            #[cfg(feature = "sync")]
+            waker_receiver,
+        };
+        let driver = Self {
+            inner: Rc::new(UnsafeCell::new(inner)),
+            #[cfg(feature = "sync")]
+            thread_id,
+        };
+
+        // Register unpark handle
+        #[cfg(feature = "sync")]
+        {
+            let unpark = driver.unpark();
+            super::thread::register_unpark_handle(thread_id, unpark.into());
+            super::thread::register_waker_sender(thread_id, waker_sender);
+        }
+
+        Ok(driver)
+    }
+
+    fn inner_park(&self, mut timeout: Option<Duration>) -> io::Result<()> {
+        let inner = unsafe { &mut *self.inner.get() };
+
+        #[allow(unused_mut)]
+        let mut need_wait = true;
+        #[cfg(feature = "sync")]
+        {
+            // Process foreign wakers
+            let mut foreign = 0;
+            while let Ok(w) = inner.waker_receiver.try_recv() {
+                w.wake();
+                foreign += 1;
+                need_wait = false;
+            }
+
+            // Set status as not awake if we are going to sleep
+            if need_wait {
+                inner
+                    .shared_waker
+                    .awake
+                    .store(false, std::sync::atomic::Ordering::Release);
+            }
+
+            // Process foreign wakers left
+            while let Ok(w) = inner.waker_receiver.try_recv() {
+                w.wake();
+                foreign += 1;
+                need_wait = false;
+            }
+            crate::runtime::metrics::foreign_wakeups(foreign);
+        }
+
+        if !need_wait {
+            timeout = Some(Duration::ZERO);
+        }
+
+        let result = inner
+            .port
+            .get_many(&mut inner.entries, timeout)
+            .map(|e| e.len());
+        #[cfg(feature = "sync")]
+        inner
+            .shared_waker
+            .awake
+            .store(true, std::sync::atomic::Ordering::Release);
+        let n = match result {
+            Ok(n) => n,
+            Err(ref e) if e.raw_os_error() == Some(WAIT_TIMEOUT as i32) => 0,
+            Err(e) => return Err(e),
+        };
+        for i in 0..n {
+            let overlapped = inner.entries[i].lpOverlapped;
+            // posted by unpark
+            if overlapped.is_null() {
+                continue;
+            }
+            let index = unsafe { Overlapped::index(overlapped) };
+            inner.in_flight -= 1;
+            trace_event!(index, "op completed");
+            if let Some(lifecycle) = inner.ops.get(index) {
+                lifecycle.complete();
+            }
+        }
+        Ok(())
+    }
+
+    /// Associate the socket with the port, so the completions of its overlapped operations are
+    /// posted to the port.
+    pub(crate) fn register(this: &Rc<UnsafeCell<IocpInner>>, socket: RawSocket) -> io::Result<()> {
+        let inner = unsafe { &*this.get() };
+        inner.port.add_handle(0, socket as HANDLE)
+    }
+}
+
+impl IocpInner {
+    pub(crate) fn submit_with_data<T>(
+        this: &Rc<UnsafeCell<IocpInner>>,
+        mut data: T,
+    ) -> io::Result<Op<T>>
+    where
+        T: OpAble,
+    {
+        let Some(socket) = data.iocp_socket() else {
+            return Ok(Op {
+                driver: Inner::Iocp(this.clone()),
+                index: SYNC_INDEX,
+                data: Some(data),
+            });
+        };
+
+        let inner = unsafe { &mut *this.get() };
+        let index = inner.ops.insert(OverlappedLifecycle::new(socket));
+        let mut lifecycle = unsafe { inner.ops.get(index).unwrap_unchecked() };
+        let overlapped = lifecycle.overlapped(index);
+        // Safety: the overlapped and the buffers of data are kept until the completion.
+        match unsafe { data.iocp_call(overlapped) } {
+            Ok(()) => inner.in_flight += 1,
+            Err(e) => lifecycle.fail(e),
+        }
+        trace_event!(index, "op submitted");
+        Ok(Op {
+            driver: Inner::Iocp(this.clone()),
+            index,
+            data: Some(data),
+        })
+    }
+
+    pub(crate) fn poll_op<T: OpAble>(
+        this: &Rc<UnsafeCell<Self>>,
+        data: &mut T,
+        index: usize,
+        cx: &mut Context<'_>,
+    ) -> Poll<CompletionMeta> {
+        if index == SYNC_INDEX {
+            let result = OpAble::legacy_call(data);
+            return Poll::Ready(CompletionMeta { result, flags: 0 });
+        }
+        let inner = unsafe { &mut *this.get() };
+        let lifecycle = unsafe { inner.ops.get(index).unwrap_unchecked() };
+        let result = ready!(lifecycle.poll_op(cx));
+        Poll::Ready(CompletionMeta {
+            result: data.iocp_complete(result),
+            flags: 0,
+        })
+    }
+
+    pub(crate) fn drop_op<T: 'static>(
+        this: &Rc<UnsafeCell<Self>>,
+        index: usize,
+        data: &mut Option<T>,
+    ) {
+        if index == usize::MAX || index == SYNC_INDEX {
+            // already finished or nothing on the port
+            return;
+        }
+        let inner = unsafe { &mut *this.get() };
+        if let Some(mut lifecycle) = inner.ops.get(index) {
+            let _socket = lifecycle.socket();
+            let _overlapped = lifecycle.overlapped(index);
+            let _must_finished = lifecycle.drop_op(data);
+            #[cfg(feature = "async-cancel")]
+            if !_must_finished {
+                // It fails if the op has completed, whose completion is posted anyway.
+                unsafe { CancelIoEx(_socket as HANDLE, _overlapped) };
+            }
+        }
+    }
+
+    pub(crate) unsafe fn cancel_op(this: &Rc<UnsafeCell<Self>>, index: usize) {
+        if index == SYNC_INDEX {
+            return;
+        }
+        let inner = &mut *this.get();
+        if let Some(mut lifecycle) = inner.ops.get(index) {
+            if lifecycle.in_flight() {
+                let socket = lifecycle.socket();
+                CancelIoEx(socket as HANDLE, lifecycle.overlapped(index));
+            }
+        }
+    }
+
+    pub(crate) fn metrics(this: &Rc<UnsafeCell<Self>>) -> super::DriverMetrics {
+        let inner = unsafe { &*this.get() };
+        super::DriverMetrics {
+            in_flight: inner.in_flight,
+            slab_size: inner.ops.len(),
+            ..Default::default()
+        }
+    }
+
+    /// Cancel all the in-flight ops for the runtime shutdown.
+    pub(crate) fn cancel_all(this: &Rc<UnsafeCell<Self>>) {
+        let inner = unsafe { &mut *this.get() };
+        if inner.in_flight == 0 {
+            return;
+        }
+        let mut found = 0;
+        let mut index = 0;
+        while found < inner.in_flight && index < SYNC_INDEX {
+            if let Some(mut lifecycle) = inner.ops.get(index) {
+                if lifecycle.in_flight() {
+                    let socket = lifecycle.socket();
+                    unsafe { CancelIoEx(socket as HANDLE, lifecycle.overlapped(index)) };
+                    found += 1;
+                }
+            }
+            index += 1;
+        }
+    }
+
+    /// The buffers of the ops still in flight are leaked rather than freed when the driver is
+    /// dropped, since the kernel may still write to them.
+    pub(crate) fn shutdown(this: &Rc<UnsafeCell<Self>>) {
+        let inner = unsafe { &mut *this.get() };
+        inner.leak_ops = inner.in_flight != 0;
+    }
+
+    #[cfg(feature = "sync")]
+    pub(crate) fn unpark(this: &Rc<UnsafeCell<Self>>) -> super::legacy::UnparkHandle {
+        let inner = unsafe { &*this.get() };
+        let weak = Arc::downgrade(&inner.shared_waker);
+        super::legacy::UnparkHandle(weak)
+    }
+}
+
+impl Driver for IocpDriver {
+    fn with<R>(&self, f: impl FnOnce() -> R) -> R {
+        let inner = Inner::Iocp(self.inner.clone());
+        CURRENT.set(&inner, f)
+    }
+
+    fn submit(&self) -> io::Result<()> {
+        // The ops are started when submitted, so only process the completions.
+        self.park_timeout(Duration::ZERO)
+    }
+
+    fn park(&self) -> io::Result<()> {
+        self.inner_park(None)
+    }
+
+    fn park_timeout(&self, duration: Duration) -> io::Result<()> {
+        self.inner_park(Some(duration))
+    }
+
+    #[cfg(feature = "sync")]
+    type Unpark = super::legacy::UnparkHandle;
+
+    #[cfg(feature = "sync")]
+    fn unpark(&self) -> Self::Unpark {
+        IocpInner::unpark(&self.inner)
+    }
+}
+
+impl Drop for IocpDriver {
+    fn drop(&mut self) {
+        // Deregister thread id
+        #[cfg(feature = "sync")]
+        {
+            use crate::driver::thread::{unregister_unpark_handle, unregister_waker_sender};
+            unregister_unpark_handle(self.thread_id);
+            unregister_waker_sender(self.thread_id);
+        }
+    }
+}
+
+impl Drop for IocpInner {
+    fn drop(&mut self) {
+        if self.leak_ops {
+            std::mem::forget(std::mem::replace(&mut self.ops, Slab::new()));
+        }
+    }
+}
@@ -0,0 +1,280 @@
+//! Native epoll poller for Linux, enabled by the `legacy-epoll` feature.
+//!
+//! It provides the subset of mio `Poll` API the legacy driver uses, but calls epoll directly:
+//! - The timeout is passed to `epoll_pwait2`(requires kernel 5.11+) with nanosecond precision, and
+//!   falls back to `epoll_wait` rounded up to milliseconds on older kernels.
+//! - Fds are registered edge-triggered with `EPOLLEXCLUSIVE`(requires kernel 4.6+), so a fd shared
+//!   by several runtimes(e.g. a listener) only wakes up one of them. The driver never modifies a
+//!   registration, which `EPOLLEXCLUSIVE` does not allow.
+//! - The driver is woken up from other threads with an eventfd.
+
+use std::{
+    io,
+    os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd},
+    ptr,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+use mio::{Interest, Token};
+
+macro_rules! syscall {
+    ($fn: ident ( $($arg: expr),* $(,)* ) ) => {{
+        let res = unsafe { libc::$fn($($arg, )*) };
+        if res == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(res)
+        }
+    }};
+}
+
+// If `epoll_pwait2` is supported by the kernel.
+static PWAIT2: AtomicBool = AtomicBool::new(true);
+// If `EPOLLEXCLUSIVE` is supported by the kernel.
+static EXCLUSIVE: AtomicBool = AtomicBool::new(true);
+
+/// A source which can be registered to the [`Registry`].
+pub(crate) trait Source {
+    fn raw_fd(&self) -> RawFd;
+}
+
+impl Source for mio::unix::SourceFd<'_> {
+    #[inline]
+    fn raw_fd(&self) -> RawFd {
+        *self.0
+    }
+}
+
+pub(crate) struct Poller {
+    registry: Registry,
+}
+
+pub(crate) struct Registry {
+    ep: OwnedFd,
+}
+
+impl AsRawFd for Poller {
+    fn as_raw_fd(&self) -> RawFd {
+        self.registry.ep.as_raw_fd()
+    }
+}
+
+impl Poller {
+    pub(crate) fn new() -> io::Result<Self> {
+        let ep = syscall!(epoll_create1(libc::EPOLL_CLOEXEC))?;
+        Ok(Self {
+            registry: Registry {
+                ep: unsafe { OwnedFd::from_raw_fd(ep) },
+            },
+        })
+    }
+
+    #[inline]
+    pub(crate) fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    pub(crate) fn poll(
+        &mut self,
+        events: &mut Events,
+        timeout: Option<Duration>,
+    ) -> io::Result<()> {
+        let ep = self.registry.ep.as_raw_fd();
+        let max = events.list.capacity().min(libc::c_int::MAX as usize) as libc::c_int;
+        events.list.clear();
+
+        let n = match timeout {
+            Some(to) if PWAIT2.load(Ordering::Relaxed) => {
+                let ts = libc::timespec {
+                    tv_sec: to.as_secs().min(libc::time_t::MAX as u64) as libc::time_t,
+                    tv_nsec: to.subsec_nanos() as _,
+                };
+                let res = unsafe {
+                    libc::syscall(
+                        libc::SYS_epoll_pwait2,
+                        ep,
+                        events.list.as_mut_ptr(),
+                        max,
+                        &ts as *const libc::timespec,
+                        ptr::null::<libc::sigset_t>(),
+                        0usize,
+                    )
+                };
+                if res == -1 {
+                    let e = io::Error::last_os_error();
+                    if e.raw_os_error() != Some(libc::ENOSYS) {
+                        return Err(e);
+                    }
+                    PWAIT2.store(false, Ordering::Relaxed);
+                    return self.poll(events, timeout);
+                }
+                res as usize
+            }
+            _ => {
+                let timeout = timeout
+                    .map(|to| {
+                        // Round up, so a sub-millisecond timeout does not turn into a busy loop.
+                        let ms = to
+                            .checked_add(Duration::from_nanos(999_999))
+                            .unwrap_or(to)
+                            .as_millis();
+                        ms.min(libc::c_int::MAX as u128) as libc::c_int
+                    })
+                    .unwrap_or(-1);
+                syscall!(epoll_wait(ep, events.list.as_mut_ptr(), max, timeout))? as usize
+            }
+        };
+        unsafe { events.list.set_len(n) };
+        Ok(())
+    }
+}
+
+impl Registry {
+    pub(crate) fn register(
+        &self,
+        source: &mut impl Source,
+        token: Token,
+        interest: Interest,
+    ) -> io::Result<()> {
+        let mut flags = libc::EPOLLET;
+        if interest.is_readable() {
+            flags |= libc::EPOLLIN;
+        }
+        if interest.is_writable() {
+            flags |= libc::EPOLLOUT;
+        }
+
+        let fd = source.raw_fd();
+        if EXCLUSIVE.load(Ordering::Relaxed) {
+            match self.ctl_add(fd, flags | libc::EPOLLEXCLUSIVE, token) {
+                Err(e) if e.raw_os_error() == Some(libc::EINVAL) => {
+                    EXCLUSIVE.store(false, Ordering::Relaxed)
+                }
+                res => return res,
+            }
+        }
+        // `EPOLLRDHUP` is not allowed with `EPOLLEXCLUSIVE`.
+        self.ctl_add(fd, flags | libc::EPOLLRDHUP, token)
+    }
+
+    pub(crate) fn deregister(&self, source: &mut impl Source) -> io::Result<()> {
+        syscall!(epoll_ctl(
+            self.ep.as_raw_fd(),
+            libc::EPOLL_CTL_DEL,
+            source.raw_fd(),
+            ptr::null_mut()
+        ))
+        .map(|_| ())
+    }
+
+    fn ctl_add(&self, fd: RawFd, flags: libc::c_int, token: Token) -> io::Result<()> {
+        let mut event = libc::epoll_event {
+            events: flags as u32,
+            u64: token.0 as u64,
+        };
+        syscall!(epoll_ctl(
+            self.ep.as_raw_fd(),
+            libc::EPOLL_CTL_ADD,
+            fd,
+            &mut event
+        ))
+        .map(|_| ())
+    }
+}
+
+/// Event list for [`Poller::poll`].
+pub(crate) struct Events {
+    list: Vec<libc::epoll_event>,
+}
+
+impl Events {
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Self {
+            list: Vec::with_capacity(capacity),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &Event> {
+        self.list
+            .iter()
+            .map(|ev| unsafe { &*(ev as *const libc::epoll_event as *const Event) })
+    }
+}
+
+#[repr(transparent)]
+pub(crate) struct Event {
+    inner: libc::epoll_event,
+}
+
+impl Event {
+    #[inline]
+    fn events(&self) -> libc::c_int {
+        self.inner.events as libc::c_int
+    }
+
+    #[inline]
+    pub(crate) fn token(&self) -> Token {
+        Token(self.inner.u64 as usize)
+    }
+
+    #[inline]
+    pub(crate) fn is_readable(&self) -> bool {
+        self.events() & (libc::EPOLLIN | libc::EPOLLPRI) != 0
+    }
+
+    #[inline]
+    pub(crate) fn is_writable(&self) -> bool {
+        self.events() & libc::EPOLLOUT != 0
+    }
+
+    #[inline]
+    pub(crate) fn is_read_closed(&self) -> bool {
+        let events = self.events();
+        events & libc::EPOLLHUP != 0
+            || (events & libc::EPOLLIN != 0 && events & libc::EPOLLRDHUP != 0)
+    }
+
+    #[inline]
+    pub(crate) fn is_write_closed(&self) -> bool {
+        let events = self.events();
+        events & libc::EPOLLHUP != 0
+            || (events & libc::EPOLLOUT != 0 && events & libc::EPOLLERR != 0)
+            || events == libc::EPOLLERR
+    }
+}
+
+/// Wake up the [`Poller`] from other threads.
+#[cfg(feature = "sync")]
+pub(crate) struct Waker {
+    fd: OwnedFd,
+}
+
+#[cfg(feature = "sync")]
+impl Waker {
+    pub(crate) fn new(registry: &Registry, token: Token) -> io::Result<Self> {
+        let fd = syscall!(eventfd(0, libc::EFD_CLOEXEC | libc::EFD_NONBLOCK))?;
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+        registry.ctl_add(fd.as_raw_fd(), libc::EPOLLET | libc::EPOLLIN, token)?;
+        Ok(Self { fd })
+    }
+
+    pub(crate) fn wake(&self) -> io::Result<()> {
+        let buf = 1u64.to_ne_bytes();
+        match syscall!(write(self.fd.as_raw_fd(), buf.as_ptr().cast(), buf.len())) {
+            Ok(_) => Ok(()),
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                // The counter is about to overflow, reset it and wake again.
+                let mut buf = [0u8; 8];
+                let _ = syscall!(read(
+                    self.fd.as_raw_fd(),
+                    buf.as_mut_ptr().cast(),
+                    buf.len()
+                ));
+                self.wake()
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
@@ -17,6 +17,11 @@ impl Waker {
         })
     }
 
+    /// Create the waker posting to the port directly, which is used by the IOCP driver.
+    pub fn with_port(port: Arc<CompletionPort>, token: mio::Token) -> Waker {
+        Waker { token, port }
+    }
+
     pub fn wake(&self) -> io::Result<()> {
         let mut ev = Event::new(self.token);
         ev.set_readable();
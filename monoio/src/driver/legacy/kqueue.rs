@@ -0,0 +1,258 @@
+//! Native kqueue poller for macOS and FreeBSD.
+//!
+//! It provides the subset of mio `Poll` API the legacy driver uses, but calls `kevent`
+//! directly. Fds are registered edge-triggered(`EV_CLEAR`), and the driver is woken up from
+//! other threads with an `EVFILT_USER` event.
+
+use std::{
+    io, mem,
+    os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd},
+    ptr,
+    time::Duration,
+};
+
+use mio::{Interest, Token};
+
+macro_rules! syscall {
+    ($fn: ident ( $($arg: expr),* $(,)* ) ) => {{
+        let res = unsafe { libc::$fn($($arg, )*) };
+        if res == -1 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(res)
+        }
+    }};
+}
+
+/// A source which can be registered to the [`Registry`].
+pub(crate) trait Source {
+    fn raw_fd(&self) -> RawFd;
+}
+
+impl Source for mio::unix::SourceFd<'_> {
+    #[inline]
+    fn raw_fd(&self) -> RawFd {
+        *self.0
+    }
+}
+
+#[inline]
+fn kevent(ident: usize, filter: i16, flags: u16, fflags: u32, token: Token) -> libc::kevent {
+    // The field types differ between platforms, so we fill a zeroed one.
+    let mut kev: libc::kevent = unsafe { mem::zeroed() };
+    kev.ident = ident as _;
+    kev.filter = filter;
+    kev.flags = flags;
+    kev.fflags = fflags;
+    kev.udata = token.0 as _;
+    kev
+}
+
+pub(crate) struct Poller {
+    registry: Registry,
+}
+
+pub(crate) struct Registry {
+    kq: OwnedFd,
+}
+
+impl AsRawFd for Poller {
+    fn as_raw_fd(&self) -> RawFd {
+        self.registry.kq.as_raw_fd()
+    }
+}
+
+impl Poller {
+    pub(crate) fn new() -> io::Result<Self> {
+        let kq = syscall!(kqueue())?;
+        let kq = unsafe { OwnedFd::from_raw_fd(kq) };
+        syscall!(fcntl(kq.as_raw_fd(), libc::F_SETFD, libc::FD_CLOEXEC))?;
+        Ok(Self {
+            registry: Registry { kq },
+        })
+    }
+
+    #[inline]
+    pub(crate) fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    pub(crate) fn poll(
+        &mut self,
+        events: &mut Events,
+        timeout: Option<Duration>,
+    ) -> io::Result<()> {
+        let timeout = timeout.map(|to| libc::timespec {
+            tv_sec: to.as_secs().min(libc::time_t::MAX as u64) as libc::time_t,
+            tv_nsec: to.subsec_nanos() as _,
+        });
+        let timeout = timeout
+            .as_ref()
+            .map(|t| t as *const libc::timespec)
+            .unwrap_or(ptr::null());
+
+        events.list.clear();
+        let n = syscall!(kevent(
+            self.registry.kq.as_raw_fd(),
+            ptr::null(),
+            0,
+            events.list.as_mut_ptr(),
+            events.list.capacity() as libc::c_int,
+            timeout,
+        ))?;
+        unsafe { events.list.set_len(n as usize) };
+        Ok(())
+    }
+}
+
+impl Registry {
+    pub(crate) fn register(
+        &self,
+        source: &mut impl Source,
+        token: Token,
+        interest: Interest,
+    ) -> io::Result<()> {
+        let fd = source.raw_fd() as usize;
+        let flags = libc::EV_ADD | libc::EV_CLEAR | libc::EV_RECEIPT;
+        let mut changes: [libc::kevent; 2] = unsafe { mem::zeroed() };
+        let mut n = 0;
+        if interest.is_readable() {
+            changes[n] = kevent(fd, libc::EVFILT_READ, flags, 0, token);
+            n += 1;
+        }
+        if interest.is_writable() {
+            changes[n] = kevent(fd, libc::EVFILT_WRITE, flags, 0, token);
+            n += 1;
+        }
+        // Registering the write filter on a pipe whose read end is closed fails with `EPIPE`,
+        // but the event is still delivered, like mio we ignore it.
+        apply(self.kq.as_raw_fd(), &mut changes[..n], &[libc::EPIPE])
+    }
+
+    pub(crate) fn deregister(&self, source: &mut impl Source) -> io::Result<()> {
+        let fd = source.raw_fd() as usize;
+        let flags = libc::EV_DELETE | libc::EV_RECEIPT;
+        let mut changes = [
+            kevent(fd, libc::EVFILT_READ, flags, 0, Token(0)),
+            kevent(fd, libc::EVFILT_WRITE, flags, 0, Token(0)),
+        ];
+        // The fd may only be registered with one of the filters.
+        apply(self.kq.as_raw_fd(), &mut changes, &[libc::ENOENT])
+    }
+}
+
+/// Apply the changes, and check the receipts written back to them.
+fn apply(kq: RawFd, changes: &mut [libc::kevent], ignored: &[libc::c_int]) -> io::Result<()> {
+    syscall!(kevent(
+        kq,
+        changes.as_ptr(),
+        changes.len() as libc::c_int,
+        changes.as_mut_ptr(),
+        changes.len() as libc::c_int,
+        ptr::null(),
+    ))
+    .or_else(|e| {
+        // EINTR means the changes are applied, and the receipts are written to the list.
+        if e.kind() == io::ErrorKind::Interrupted {
+            Ok(0)
+        } else {
+            Err(e)
+        }
+    })?;
+
+    for change in changes.iter() {
+        if change.flags & libc::EV_ERROR != 0
+            && change.data != 0
+            && !ignored.contains(&(change.data as libc::c_int))
+        {
+            return Err(io::Error::from_raw_os_error(change.data as libc::c_int));
+        }
+    }
+    Ok(())
+}
+
+/// Event list for [`Poller::poll`].
+pub(crate) struct Events {
+    list: Vec<libc::kevent>,
+}
+
+impl Events {
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Self {
+            list: Vec::with_capacity(capacity),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &Event> {
+        self.list
+            .iter()
+            .map(|kev| unsafe { &*(kev as *const libc::kevent as *const Event) })
+    }
+}
+
+#[repr(transparent)]
+pub(crate) struct Event {
+    inner: libc::kevent,
+}
+
+impl Event {
+    #[inline]
+    pub(crate) fn token(&self) -> Token {
+        Token(self.inner.udata as usize)
+    }
+
+    #[inline]
+    pub(crate) fn is_readable(&self) -> bool {
+        self.inner.filter == libc::EVFILT_READ || self.inner.filter == libc::EVFILT_USER
+    }
+
+    #[inline]
+    pub(crate) fn is_writable(&self) -> bool {
+        self.inner.filter == libc::EVFILT_WRITE
+    }
+
+    #[inline]
+    pub(crate) fn is_read_closed(&self) -> bool {
+        self.inner.filter == libc::EVFILT_READ && self.inner.flags & libc::EV_EOF != 0
+    }
+
+    #[inline]
+    pub(crate) fn is_write_closed(&self) -> bool {
+        self.inner.filter == libc::EVFILT_WRITE && self.inner.flags & libc::EV_EOF != 0
+    }
+}
+
+/// Wake up the [`Poller`] from other threads.
+#[cfg(feature = "sync")]
+pub(crate) struct Waker {
+    kq: OwnedFd,
+    token: Token,
+}
+
+#[cfg(feature = "sync")]
+impl Waker {
+    pub(crate) fn new(registry: &Registry, token: Token) -> io::Result<Self> {
+        let kq = registry.kq.try_clone()?;
+        let mut changes = [kevent(
+            token.0,
+            libc::EVFILT_USER,
+            libc::EV_ADD | libc::EV_CLEAR | libc::EV_RECEIPT,
+            0,
+            token,
+        )];
+        apply(kq.as_raw_fd(), &mut changes, &[])?;
+        Ok(Self { kq, token })
+    }
+
+    pub(crate) fn wake(&self) -> io::Result<()> {
+        let mut changes = [kevent(
+            self.token.0,
+            libc::EVFILT_USER,
+            libc::EV_ADD | libc::EV_RECEIPT,
+            libc::NOTE_TRIGGER,
+            self.token,
+        )];
+        apply(self.kq.as_raw_fd(), &mut changes, &[])
+    }
+}
@@ -20,17 +20,36 @@ use crate::utils::slab::Slab;
 #[cfg(windows)]
 pub(super) mod iocp;
 
+#[cfg(any(target_os = "macos", target_os = "freebsd"))]
+pub(super) mod kqueue;
+#[cfg(any(target_os = "macos", target_os = "freebsd"))]
+use kqueue::{Events, Poller, Source};
+
+#[cfg(all(target_os = "linux", feature = "legacy-epoll"))]
+pub(super) mod epoll;
+#[cfg(all(target_os = "linux", feature = "legacy-epoll"))]
+use epoll::{Events, Poller, Source};
+#[cfg(all(
+    unix,
+    not(any(
+        target_os = "macos",
+        target_os = "freebsd",
+        all(target_os = "linux", feature = "legacy-epoll")
+    ))
+))]
+use mio::{event::Source, Events, Poll as Poller};
+
 #[cfg(feature = "sync")]
-mod waker;
+pub(super) mod waker;
 #[cfg(feature = "sync")]
 pub(crate) use waker::UnparkHandle;
 
 pub(crate) struct LegacyInner {
     pub(crate) io_dispatch: Slab<ScheduledIo>,
     #[cfg(unix)]
-    events: mio::Events,
+    events: Events,
     #[cfg(unix)]
-    poll: mio::Poll,
+    poll: Poller,
     #[cfg(windows)]
     events: iocp::Events,
     #[cfg(windows)]
@@ -67,12 +86,12 @@ impl LegacyDriver {
 
     pub(crate) fn new_with_entries(entries: u32) -> io::Result<Self> {
         #[cfg(unix)]
-        let poll = mio::Poll::new()?;
+        let poll = Poller::new()?;
         #[cfg(windows)]
         let poll = iocp::Poller::new()?;
 
         #[cfg(all(unix, feature = "sync"))]
-        let shared_waker = std::sync::Arc::new(waker::EventWaker::new(mio::Waker::new(
+        let shared_waker = std::sync::Arc::new(waker::EventWaker::new(waker::Waker::new(
             poll.registry(),
             TOKEN_WAKEUP,
         )?));
@@ -89,7 +108,7 @@ impl LegacyDriver {
         let inner = LegacyInner {
             io_dispatch: Slab::new(),
             #[cfg(unix)]
-            events: mio::Events::with_capacity(entries as usize),
+            events: Events::with_capacity(entries as usize),
             #[cfg(unix)]
             poll,
             #[cfg(windows)]
@@ -126,8 +145,10 @@ impl LegacyDriver {
         #[cfg(feature = "sync")]
         {
             // Process foreign wakers
+            let mut foreign = 0;
             while let Ok(w) = inner.waker_receiver.try_recv() {
                 w.wake();
+                foreign += 1;
                 need_wait = false;
             }
 
@@ -142,8 +163,10 @@ impl LegacyDriver {
             // Process foreign wakers left
             while let Ok(w) = inner.waker_receiver.try_recv() {
                 w.wake();
+                foreign += 1;
                 need_wait = false;
             }
+            crate::runtime::metrics::foreign_wakeups(foreign);
         }
 
         if !need_wait {
@@ -161,16 +184,26 @@ impl LegacyDriver {
         let iter = events.iter();
         #[cfg(windows)]
         let iter = events.events.iter();
+        #[cfg(any(target_os = "macos", target_os = "freebsd"))]
+        let from_event = Ready::from_kqueue;
+        #[cfg(all(target_os = "linux", feature = "legacy-epoll"))]
+        let from_event = Ready::from_epoll;
+        #[cfg(not(any(
+            target_os = "macos",
+            target_os = "freebsd",
+            all(target_os = "linux", feature = "legacy-epoll")
+        )))]
+        let from_event = Ready::from_mio;
         for event in iter {
             let token = event.token();
 
             #[cfg(feature = "sync")]
             if token != TOKEN_WAKEUP {
-                inner.dispatch(token, Ready::from_mio(event));
+                inner.dispatch(token, from_event(event));
             }
 
             #[cfg(not(feature = "sync"))]
-            inner.dispatch(token, Ready::from_mio(event));
+            inner.dispatch(token, from_event(event));
         }
         Ok(())
     }
@@ -215,7 +248,7 @@ impl LegacyDriver {
     #[cfg(unix)]
     pub(crate) fn register(
         this: &Rc<UnsafeCell<LegacyInner>>,
-        source: &mut impl mio::event::Source,
+        source: &mut impl Source,
         interest: mio::Interest,
     ) -> io::Result<usize> {
         let inner = unsafe { &mut *this.get() };
@@ -235,7 +268,7 @@ impl LegacyDriver {
     pub(crate) fn deregister(
         this: &Rc<UnsafeCell<LegacyInner>>,
         token: usize,
-        source: &mut impl mio::event::Source,
+        source: &mut impl Source,
     ) -> io::Result<()> {
         let inner = unsafe { &mut *this.get() };
 
@@ -274,10 +307,9 @@ impl LegacyInner {
             None => {
                 // if there is no index provided, it means the action does not rely on fd
                 // readiness. do syscall right now.
-                return Poll::Ready(CompletionMeta {
-                    result: OpAble::legacy_call(data),
-                    flags: 0,
-                });
+                let result = OpAble::legacy_call(data);
+                trace_completed(None, &result);
+                return Poll::Ready(CompletionMeta { result, flags: 0 });
             }
         };
 
@@ -298,19 +330,15 @@ impl LegacyInner {
         }
 
         match OpAble::legacy_call(data) {
-            Ok(n) => Poll::Ready(CompletionMeta {
-                result: Ok(n),
-                flags: 0,
-            }),
             Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => {
                 ref_mut.clear_readiness(direction.mask());
                 ref_mut.set_waker(cx, direction);
                 Poll::Pending
             }
-            Err(e) => Poll::Ready(CompletionMeta {
-                result: Err(e),
-                flags: 0,
-            }),
+            result => {
+                trace_completed(Some(index), &result);
+                Poll::Ready(CompletionMeta { result, flags: 0 })
+            }
         }
     }
 
@@ -334,6 +362,10 @@ impl LegacyInner {
     where
         T: OpAble,
     {
+        trace_event!(
+            token = data.legacy_interest().map(|(_, token)| token),
+            "op submitted"
+        );
         Ok(Op {
             driver: Inner::Legacy(this.clone()),
             // useless for legacy
@@ -376,6 +408,14 @@ impl Driver for LegacyDriver {
     fn unpark(&self) -> Self::Unpark {
         LegacyInner::unpark(&self.inner)
     }
+
+    /// The epoll or kqueue fd, which is reset by polling it.
+    #[cfg(unix)]
+    fn pollable_fd(&self) -> io::Result<std::os::unix::io::RawFd> {
+        use std::os::unix::io::AsRawFd;
+        let inner = unsafe { &*self.inner.get() };
+        Ok(inner.poll.as_raw_fd())
+    }
 }
 
 impl Drop for LegacyDriver {
@@ -389,3 +429,15 @@ impl Drop for LegacyDriver {
         }
     }
 }
+
+/// Emit the completion event of the op on the io source `token`.
+#[inline]
+#[allow(unused_variables)]
+fn trace_completed(token: Option<usize>, result: &io::Result<super::op::MaybeFd>) {
+    trace_event!(
+        token,
+        bytes = result.as_ref().ok().map(|n| n.fd()),
+        errno = result.as_ref().err().and_then(io::Error::raw_os_error),
+        "op completed"
+    );
+}
@@ -1,3 +1,17 @@
+#[cfg(all(
+    unix,
+    not(any(
+        target_os = "macos",
+        target_os = "freebsd",
+        all(target_os = "linux", feature = "legacy-epoll")
+    ))
+))]
+pub(crate) use mio::Waker;
+
+#[cfg(all(target_os = "linux", feature = "legacy-epoll"))]
+pub(crate) use super::epoll::Waker;
+#[cfg(any(target_os = "macos", target_os = "freebsd"))]
+pub(crate) use super::kqueue::Waker;
 use crate::driver::unpark::Unpark;
 
 pub(crate) struct EventWaker {
@@ -5,14 +19,14 @@ pub(crate) struct EventWaker {
     #[cfg(windows)]
     waker: super::iocp::Waker,
     #[cfg(unix)]
-    waker: mio::Waker,
+    waker: Waker,
     // Atomic awake status
     pub(crate) awake: std::sync::atomic::AtomicBool,
 }
 
 impl EventWaker {
     #[cfg(unix)]
-    pub(crate) fn new(waker: mio::Waker) -> Self {
+    pub(crate) fn new(waker: Waker) -> Self {
         Self {
             waker,
             awake: std::sync::atomic::AtomicBool::new(true),
@@ -42,6 +56,7 @@ pub struct UnparkHandle(pub(crate) std::sync::Weak<EventWaker>);
 impl Unpark for UnparkHandle {
     fn unpark(&self) -> std::io::Result<()> {
         if let Some(w) = self.0.upgrade() {
+            trace_event!("runtime unpark");
             w.wake()
         } else {
             Ok(())
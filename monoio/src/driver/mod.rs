@@ -1,4 +1,5 @@
-/// Monoio Driver.
+//! Monoio Driver.
+
 #[allow(dead_code)]
 pub(crate) mod op;
 #[cfg(all(feature = "poll-io", unix))]
@@ -12,6 +13,8 @@ pub(crate) mod shared_fd;
 #[cfg(feature = "sync")]
 pub(crate) mod thread;
 
+#[cfg(all(windows, feature = "iocp"))]
+mod iocp;
 #[cfg(feature = "legacy")]
 mod legacy;
 #[cfg(all(target_os = "linux", feature = "iouring"))]
@@ -25,19 +28,32 @@ use std::{
     time::Duration,
 };
 
+#[cfg(all(windows, feature = "iocp"))]
+pub use self::iocp::IocpDriver;
+#[cfg(all(windows, feature = "iocp"))]
+use self::iocp::IocpInner;
 #[allow(unreachable_pub)]
 #[cfg(feature = "legacy")]
 pub use self::legacy::LegacyDriver;
 #[cfg(feature = "legacy")]
 use self::legacy::LegacyInner;
-use self::op::{CompletionMeta, Op, OpAble};
 #[cfg(all(target_os = "linux", feature = "iouring"))]
-pub use self::uring::IoUringDriver;
+pub use self::op::link;
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+pub use self::op::raw::{submit_raw, RawCompletion, RawOp};
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+pub use self::op::uring_cmd::{
+    NvmeUringCmd, UblkCtrlCmd, UringCmd, UringCmdCompletion, UringCmdOp,
+};
+use self::op::{CompletionMeta, Op, OpAble};
 #[cfg(all(target_os = "linux", feature = "iouring"))]
 use self::uring::UringInner;
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+pub use self::uring::{CqOverflowPolicy, IoUringDriver, Restriction, SubmitPolicy};
 
 /// Unpark a runtime of another thread.
 pub(crate) mod unpark {
+    /// Unpark a parked driver.
     #[allow(unreachable_pub)]
     pub trait Unpark: Sync + Send + 'static {
         /// Unblocks a thread that is blocked by the associated `Park` handle.
@@ -67,6 +83,18 @@ impl unpark::Unpark for std::sync::Arc<dyn unpark::Unpark> {
 }
 
 /// Core driver trait.
+///
+/// Besides [`IoUringDriver`], [`LegacyDriver`] and `IocpDriver`(on windows), it can be implemented
+/// outside monoio to drive other event sources(e.g. SPDK, DPDK or RDMA completion queues), and the
+/// runtime is built
+/// with [`RuntimeBuilder::build_with_driver`](crate::RuntimeBuilder::build_with_driver).
+///
+/// A custom driver is responsible for its own IO objects: the IO types in monoio(`net`, `fs`,
+/// ...) submit operations to the builtin drivers and panic under a custom one. Tasks, timers
+/// and cross-thread wakers work with any driver as long as:
+/// - `park` and `park_timeout` wake the tasks whose events are ready before returning;
+/// - `park` returns after [`Unpark::unpark`](crate::Unpark::unpark) is called on the handle
+///   returned by `unpark`, even if it is called before `park`.
 pub trait Driver {
     /// Run with driver TLS.
     fn with<R>(&self, f: impl FnOnce() -> R) -> R;
@@ -84,16 +112,45 @@ pub trait Driver {
     /// Get Unpark.
     #[cfg(feature = "sync")]
     fn unpark(&self) -> Self::Unpark;
+
+    /// Get an eventfd which is notified when completions arrive, for embedding the runtime in
+    /// another event loop: wait for it to be readable there, then drive the runtime. The eventfd
+    /// is owned by the driver, and it is a counter which should be read to reset.
+    ///
+    /// Returns `ErrorKind::Unsupported` if the driver does not support it.
+    #[cfg(unix)]
+    fn completion_eventfd(&self) -> io::Result<std::os::unix::io::RawFd> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+
+    /// Get an fd which becomes readable when the driver has events to process, for embedding the
+    /// runtime in another event loop, see [`Runtime::pollable_fd`](crate::Runtime::pollable_fd).
+    /// It is the [`completion_eventfd`](Driver::completion_eventfd) by default.
+    #[cfg(unix)]
+    fn pollable_fd(&self) -> io::Result<std::os::unix::io::RawFd> {
+        self.completion_eventfd()
+    }
 }
 
 scoped_thread_local!(pub(crate) static CURRENT: Inner);
 
+/// Metrics of the builtin drivers, see [`RuntimeMetrics`](crate::runtime::RuntimeMetrics).
+#[derive(Debug, Default, Clone, Copy)]
+pub(crate) struct DriverMetrics {
+    pub(crate) in_flight: usize,
+    pub(crate) slab_size: usize,
+    pub(crate) sq_high_water: usize,
+    pub(crate) cq_high_water: usize,
+}
+
 #[derive(Clone)]
 pub(crate) enum Inner {
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     Uring(std::rc::Rc<std::cell::UnsafeCell<UringInner>>),
     #[cfg(feature = "legacy")]
     Legacy(std::rc::Rc<std::cell::UnsafeCell<LegacyInner>>),
+    #[cfg(all(windows, feature = "iocp"))]
+    Iocp(std::rc::Rc<std::cell::UnsafeCell<IocpInner>>),
 }
 
 impl Inner {
@@ -103,6 +160,8 @@ impl Inner {
             Inner::Uring(this) => UringInner::submit_with_data(this, data),
             #[cfg(feature = "legacy")]
             Inner::Legacy(this) => LegacyInner::submit_with_data(this, data),
+            #[cfg(all(windows, feature = "iocp"))]
+            Inner::Iocp(this) => IocpInner::submit_with_data(this, data),
             #[cfg(all(
                 not(feature = "legacy"),
                 not(all(target_os = "linux", feature = "iouring"))
@@ -113,6 +172,66 @@ impl Inner {
         }
     }
 
+    /// If the operations of a chain have to be executed one by one with syscalls.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fn link_polled<T: OpAble>(&self, _data: &T) -> bool {
+        match self {
+            #[cfg(any(feature = "legacy", feature = "poll-io"))]
+            Inner::Uring(this) => UringInner::link_polled(this, _data),
+            #[cfg(not(any(feature = "legacy", feature = "poll-io")))]
+            Inner::Uring(_) => false,
+            #[cfg(feature = "legacy")]
+            Inner::Legacy(_) => true,
+        }
+    }
+
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fn reserve_link(&self, len: usize, polled: bool) -> io::Result<()> {
+        match self {
+            Inner::Uring(this) if !polled => UringInner::reserve_link(this, len),
+            _ => Ok(()),
+        }
+    }
+
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fn link_op<T: OpAble>(
+        &self,
+        data: T,
+        polled: bool,
+        chain: &mut Vec<io_uring::squeue::Entry>,
+    ) -> Op<T> {
+        match self {
+            Inner::Uring(this) => {
+                #[cfg(any(feature = "legacy", feature = "poll-io"))]
+                if polled {
+                    return Op {
+                        driver: self.clone(),
+                        index: uring::POLL_INDEX,
+                        data: Some(data),
+                    };
+                }
+                #[cfg(not(any(feature = "legacy", feature = "poll-io")))]
+                let _ = polled;
+                UringInner::link_op(this, data, chain)
+            }
+            #[cfg(feature = "legacy")]
+            Inner::Legacy(_) => Op {
+                driver: self.clone(),
+                // useless for legacy
+                index: 0,
+                data: Some(data),
+            },
+        }
+    }
+
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fn push_link(&self, chain: &[io_uring::squeue::Entry]) {
+        match self {
+            Inner::Uring(this) if !chain.is_empty() => UringInner::push_link(this, chain),
+            _ => {}
+        }
+    }
+
     #[allow(unused)]
     fn poll_op<T: OpAble>(
         &self,
@@ -122,9 +241,82 @@ impl Inner {
     ) -> Poll<CompletionMeta> {
         match self {
             #[cfg(all(target_os = "linux", feature = "iouring"))]
-            Inner::Uring(this) => UringInner::poll_op(this, index, cx),
+            Inner::Uring(this) => {
+                #[cfg(any(feature = "legacy", feature = "poll-io"))]
+                if index == uring::POLL_INDEX {
+                    return UringInner::poll_legacy_op(this, data, cx);
+                }
+                UringInner::poll_op(this, index, cx)
+            }
             #[cfg(feature = "legacy")]
             Inner::Legacy(this) => LegacyInner::poll_op::<T>(this, data, cx),
+            #[cfg(all(windows, feature = "iocp"))]
+            Inner::Iocp(this) => IocpInner::poll_op(this, data, index, cx),
+            #[cfg(all(
+                not(feature = "legacy"),
+                not(all(target_os = "linux", feature = "iouring"))
+            ))]
+            _ => {
+                util::feature_panic();
+            }
+        }
+    }
+
+    /// Poll the op with the big part of its CQE, which is zero if the ring does not have big
+    /// entries.
+    #[allow(unused)]
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fn poll_big_op<T: OpAble>(
+        &self,
+        data: &mut T,
+        index: usize,
+        cx: &mut Context<'_>,
+    ) -> Poll<(CompletionMeta, [u64; 2])> {
+        match self {
+            Inner::Uring(this) => {
+                #[cfg(any(feature = "legacy", feature = "poll-io"))]
+                if index == uring::POLL_INDEX {
+                    return UringInner::poll_legacy_op(this, data, cx).map(|meta| (meta, [0; 2]));
+                }
+                UringInner::poll_big_op(this, index, cx)
+            }
+            #[cfg(feature = "legacy")]
+            Inner::Legacy(this) => {
+                LegacyInner::poll_op::<T>(this, data, cx).map(|meta| (meta, [0; 2]))
+            }
+        }
+    }
+
+    /// Poll the next completion of a multishot op. The returned bool indicates if there may be
+    /// more completions. For legacy driver every completion is done by a syscall, so the op never
+    /// terminates by itself.
+    #[allow(unused)]
+    fn poll_multishot_op<T: OpAble>(
+        &self,
+        data: &mut T,
+        index: usize,
+        cx: &mut Context<'_>,
+    ) -> Poll<(CompletionMeta, bool)> {
+        match self {
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            Inner::Uring(this) => {
+                #[cfg(any(feature = "legacy", feature = "poll-io"))]
+                if index == uring::POLL_INDEX {
+                    return UringInner::poll_legacy_op(this, data, cx).map(|meta| (meta, true));
+                }
+                UringInner::poll_multishot_op(this, index, cx).map(|meta| {
+                    let more = io_uring::cqueue::more(meta.flags);
+                    (meta, more)
+                })
+            }
+            #[cfg(feature = "legacy")]
+            Inner::Legacy(this) => {
+                LegacyInner::poll_op::<T>(this, data, cx).map(|meta| (meta, true))
+            }
+            // Only the ops executed with syscalls may be multishot.
+            #[cfg(all(windows, feature = "iocp"))]
+            Inner::Iocp(this) => IocpInner::poll_op(this, data, index, cx)
+                .map(|meta| (meta, index == iocp::SYNC_INDEX)),
             #[cfg(all(
                 not(feature = "legacy"),
                 not(all(target_os = "linux", feature = "iouring"))
@@ -146,6 +338,8 @@ impl Inner {
             Inner::Uring(this) => UringInner::poll_legacy_op(this, data, cx),
             #[cfg(feature = "legacy")]
             Inner::Legacy(this) => LegacyInner::poll_op::<T>(this, data, cx),
+            #[cfg(all(windows, feature = "iocp"))]
+            Inner::Iocp(this) => IocpInner::poll_op(this, data, iocp::SYNC_INDEX, cx),
             #[cfg(all(
                 not(feature = "legacy"),
                 not(all(target_os = "linux", feature = "iouring"))
@@ -156,13 +350,19 @@ impl Inner {
         }
     }
 
-    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[cfg(any(
+        all(target_os = "linux", feature = "iouring"),
+        all(windows, feature = "iocp")
+    ))]
     #[inline]
-    fn drop_op<T: 'static>(&self, index: usize, data: &mut Option<T>, skip_cancel: bool) {
+    fn drop_op<T: 'static>(&self, index: usize, data: &mut Option<T>, _skip_cancel: bool) {
         match self {
-            Inner::Uring(this) => UringInner::drop_op(this, index, data, skip_cancel),
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            Inner::Uring(this) => UringInner::drop_op(this, index, data, _skip_cancel),
             #[cfg(feature = "legacy")]
             Inner::Legacy(_) => {}
+            #[cfg(all(windows, feature = "iocp"))]
+            Inner::Iocp(this) => IocpInner::drop_op(this, index, data),
         }
     }
 
@@ -170,13 +370,21 @@ impl Inner {
     pub(super) unsafe fn cancel_op(&self, op_canceller: &op::OpCanceller) {
         match self {
             #[cfg(all(target_os = "linux", feature = "iouring"))]
-            Inner::Uring(this) => UringInner::cancel_op(this, op_canceller.index),
+            Inner::Uring(this) => {
+                #[cfg(feature = "poll-io")]
+                if let Some(direction) = op_canceller.direction {
+                    return UringInner::cancel_poll_op(this, op_canceller.index, direction);
+                }
+                UringInner::cancel_op(this, op_canceller.index)
+            }
             #[cfg(feature = "legacy")]
             Inner::Legacy(this) => {
                 if let Some(direction) = op_canceller.direction {
                     LegacyInner::cancel_op(this, op_canceller.index, direction)
                 }
             }
+            #[cfg(all(windows, feature = "iocp"))]
+            Inner::Iocp(this) => IocpInner::cancel_op(this, op_canceller.index),
             #[cfg(all(
                 not(feature = "legacy"),
                 not(all(target_os = "linux", feature = "iouring"))
@@ -187,7 +395,10 @@ impl Inner {
         }
     }
 
-    #[cfg(all(target_os = "linux", feature = "iouring", feature = "legacy"))]
+    #[cfg(any(
+        all(target_os = "linux", feature = "iouring", feature = "legacy"),
+        all(windows, feature = "iocp")
+    ))]
     fn is_legacy(&self) -> bool {
         matches!(self, Inner::Legacy(..))
     }
@@ -197,13 +408,196 @@ impl Inner {
         false
     }
 
+    /// Submit the queued operations now.
+    pub(crate) fn flush_submissions(&self) -> io::Result<()> {
+        match self {
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            Inner::Uring(this) => UringInner::flush_submissions(this),
+            #[cfg(feature = "legacy")]
+            Inner::Legacy(_) => Ok(()),
+            #[cfg(all(windows, feature = "iocp"))]
+            Inner::Iocp(_) => Ok(()),
+        }
+    }
+
+    /// Metrics of the builtin driver.
+    pub(crate) fn metrics(&self) -> DriverMetrics {
+        match self {
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            Inner::Uring(this) => UringInner::metrics(this),
+            #[cfg(feature = "legacy")]
+            Inner::Legacy(this) => DriverMetrics {
+                slab_size: unsafe { (*this.get()).io_dispatch.len() },
+                ..Default::default()
+            },
+            #[cfg(all(windows, feature = "iocp"))]
+            Inner::Iocp(this) => IocpInner::metrics(this),
+        }
+    }
+
+    /// Cancel all the in-flight ops for the runtime shutdown, a no-op for the legacy driver
+    /// whose ops are not in the kernel.
+    pub(crate) fn cancel_all(&self) {
+        match self {
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            Inner::Uring(this) => UringInner::cancel_all(this),
+            #[cfg(feature = "legacy")]
+            Inner::Legacy(_) => {}
+            #[cfg(all(windows, feature = "iocp"))]
+            Inner::Iocp(this) => IocpInner::cancel_all(this),
+        }
+    }
+
+    /// Release the resources of the driver for the runtime shutdown.
+    pub(crate) fn shutdown(&self) {
+        match self {
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            Inner::Uring(this) => UringInner::shutdown(this),
+            #[cfg(feature = "legacy")]
+            Inner::Legacy(_) => {}
+            #[cfg(all(windows, feature = "iocp"))]
+            Inner::Iocp(this) => IocpInner::shutdown(this),
+        }
+    }
+
+    /// Reset the pollable fd before processing the events, which is the completion eventfd of
+    /// the uring driver. The legacy poller fd is reset by polling it.
+    #[cfg(unix)]
+    pub(crate) fn reset_pollable_fd(&self) {
+        match self {
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            Inner::Uring(this) => UringInner::reset_completion_eventfd(this),
+            #[cfg(feature = "legacy")]
+            Inner::Legacy(_) => {}
+        }
+    }
+
+    /// Latency histograms of the uring ops, empty for the legacy driver.
+    pub(crate) fn op_latency(&self) -> Vec<(u8, crate::runtime::LatencyHistogram)> {
+        match self {
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            Inner::Uring(this) => UringInner::op_latency(this),
+            #[cfg(feature = "legacy")]
+            Inner::Legacy(_) => Vec::new(),
+            #[cfg(all(windows, feature = "iocp"))]
+            Inner::Iocp(_) => Vec::new(),
+        }
+    }
+
+    /// If it is a uring driver in hybrid mode, where sockets are driven by readiness.
+    #[cfg(all(target_os = "linux", feature = "iouring", feature = "poll-io"))]
+    pub(crate) fn is_hybrid(&self) -> bool {
+        match self {
+            Inner::Uring(this) => UringInner::is_hybrid(this),
+            #[cfg(feature = "legacy")]
+            Inner::Legacy(_) => false,
+        }
+    }
+
     #[allow(unused)]
-    #[cfg(not(all(target_os = "linux", feature = "iouring")))]
+    #[cfg(not(any(
+        all(target_os = "linux", feature = "iouring"),
+        all(windows, feature = "iocp")
+    )))]
     fn is_legacy(&self) -> bool {
         true
     }
 }
 
+/// Run `f` with the submitter of current uring driver. Returns `None` if there is no current
+/// driver or it is not a uring driver.
+#[allow(unused)]
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+pub(crate) fn with_uring_submitter<R>(f: impl FnOnce(io_uring::Submitter<'_>) -> R) -> Option<R> {
+    if !CURRENT.is_set() {
+        return None;
+    }
+    CURRENT.with(|inner| match inner {
+        Inner::Uring(this) => Some(UringInner::with_submitter(this, f)),
+        #[cfg(feature = "legacy")]
+        Inner::Legacy(_) => None,
+    })
+}
+
+/// If timers can be armed in the kernel, which requires a uring driver not set up with
+/// `IORING_SETUP_IOPOLL`.
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+pub(crate) fn kernel_timer_available() -> bool {
+    if !CURRENT.is_set() {
+        return false;
+    }
+    CURRENT.with(|inner| match inner {
+        Inner::Uring(this) => !UringInner::is_iopoll(this),
+        #[cfg(feature = "legacy")]
+        Inner::Legacy(_) => false,
+    })
+}
+
+/// Capabilities of the driver of current runtime, see [`capabilities`].
+#[derive(Debug, Clone)]
+pub struct Capabilities {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    opcodes: Option<uring::Opcodes>,
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    big_entries: bool,
+}
+
+impl Capabilities {
+    /// If the driver is io_uring.
+    #[inline]
+    pub fn is_uring(&self) -> bool {
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        return self.opcodes.is_some();
+        #[cfg(not(all(target_os = "linux", feature = "iouring")))]
+        false
+    }
+
+    /// If the io_uring opcode(the `CODE` of types in [`io_uring::opcode`]) is supported by the
+    /// kernel. It is always false with legacy driver.
+    ///
+    /// The opcodes are probed when the driver is created. If the kernel does not support
+    /// probing(before 5.6), all opcodes are considered supported.
+    ///
+    /// The operations whose opcode is not supported are executed with syscalls on the runtime
+    /// thread instead, which block the thread if the fd is not ready.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[inline]
+    pub fn is_opcode_supported(&self, opcode: u8) -> bool {
+        self.opcodes
+            .is_some_and(|opcodes| opcodes.is_supported(opcode))
+    }
+
+    /// If the ring is built with big entries(`IORING_SETUP_SQE128` and `IORING_SETUP_CQE32`),
+    /// see [`uring_big_entries`](crate::RuntimeBuilder::uring_big_entries).
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[inline]
+    pub fn is_big_entries(&self) -> bool {
+        self.big_entries
+    }
+}
+
+/// Get the capabilities of the driver of current runtime.
+///
+/// # Panics
+///
+/// Panics if called outside a runtime.
+pub fn capabilities() -> Capabilities {
+    CURRENT.with(|_inner| Capabilities {
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        opcodes: match _inner {
+            Inner::Uring(this) => Some(UringInner::opcodes(this)),
+            #[cfg(feature = "legacy")]
+            Inner::Legacy(_) => None,
+        },
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        big_entries: match _inner {
+            Inner::Uring(this) => UringInner::is_big(this),
+            #[cfg(feature = "legacy")]
+            Inner::Legacy(_) => false,
+        },
+    })
+}
+
 /// The unified UnparkHandle.
 #[cfg(feature = "sync")]
 #[derive(Clone)]
@@ -212,6 +606,8 @@ pub(crate) enum UnparkHandle {
     Uring(self::uring::UnparkHandle),
     #[cfg(feature = "legacy")]
     Legacy(self::legacy::UnparkHandle),
+    /// Unpark of a driver implemented outside monoio.
+    Custom(std::sync::Arc<dyn unpark::Unpark>),
 }
 
 #[cfg(feature = "sync")]
@@ -222,13 +618,7 @@ impl unpark::Unpark for UnparkHandle {
             UnparkHandle::Uring(inner) => inner.unpark(),
             #[cfg(feature = "legacy")]
             UnparkHandle::Legacy(inner) => inner.unpark(),
-            #[cfg(all(
-                not(feature = "legacy"),
-                not(all(target_os = "linux", feature = "iouring"))
-            ))]
-            _ => {
-                util::feature_panic();
-            }
+            UnparkHandle::Custom(inner) => inner.unpark(),
         }
     }
 }
@@ -256,6 +646,8 @@ impl UnparkHandle {
             Inner::Uring(this) => UringInner::unpark(this).into(),
             #[cfg(feature = "legacy")]
             Inner::Legacy(this) => LegacyInner::unpark(this).into(),
+            #[cfg(all(windows, feature = "iocp"))]
+            Inner::Iocp(this) => IocpInner::unpark(this).into(),
         })
     }
 }
@@ -11,12 +11,14 @@ pub(crate) mod close;
 pub(crate) mod read;
 pub(crate) mod write;
 
-mod accept;
+pub(crate) mod accept;
 mod connect;
 mod fsync;
+#[cfg(all(target_os = "linux", feature = "legacy"))]
+mod mmsg;
 mod open;
 mod poll;
-mod recv;
+pub(crate) mod recv;
 mod send;
 #[cfg(unix)]
 mod statx;
@@ -33,9 +35,39 @@ mod rename;
 #[cfg(all(unix, feature = "symlinkat"))]
 mod symlink;
 
-#[cfg(all(target_os = "linux", feature = "splice"))]
+#[cfg(all(unix, feature = "linkat"))]
+mod hard_link;
+
+#[cfg(target_os = "linux")]
 mod splice;
 
+#[cfg(target_os = "linux")]
+mod fallocate;
+
+#[cfg(target_os = "linux")]
+mod advise;
+
+#[cfg(target_os = "linux")]
+pub(crate) mod xattr;
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+pub(crate) mod provide_buf;
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+mod files_update;
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+pub mod link;
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+pub(crate) mod raw;
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+pub(crate) mod uring_cmd;
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+pub(crate) mod timeout;
+
 /// In-flight operation
 pub(crate) struct Op<T: 'static + OpAble> {
     // Driver running the operation
@@ -145,11 +177,57 @@ pub(crate) trait OpAble {
     const SKIP_CANCEL: bool = false;
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     fn uring_op(&mut self) -> io_uring::squeue::Entry;
+    /// The SQE pushed to the ring set up with `IORING_SETUP_SQE128`, which is the padded
+    /// `uring_op` by default.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[inline]
+    fn uring_op128(&mut self) -> io_uring::squeue::Entry128 {
+        self.uring_op().into()
+    }
 
     #[cfg(any(feature = "legacy", feature = "poll-io"))]
     fn legacy_interest(&self) -> Option<(super::ready::Direction, usize)>;
     #[cfg(any(feature = "legacy", feature = "poll-io"))]
     fn legacy_call(&mut self) -> io::Result<MaybeFd>;
+
+    /// The opcode to check before submitting to io_uring. If the kernel does not support it, the
+    /// op is executed with `legacy_call` instead(when `legacy_call` is available).
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    const URING_OPCODE: Option<u8> = None;
+
+    /// If the op waits for readiness instead of being submitted to io_uring, when the uring
+    /// driver runs in hybrid mode. It is for the ops on fds registered to the poller.
+    #[cfg(all(target_os = "linux", feature = "iouring", feature = "poll-io"))]
+    #[inline]
+    fn uring_poll(&self) -> bool {
+        self.legacy_interest().is_some()
+    }
+
+    /// The socket to start the overlapped operation on with the IOCP driver. The op is executed
+    /// with `legacy_call` instead if it is `None`.
+    #[cfg(all(windows, feature = "iocp"))]
+    #[inline]
+    fn iocp_socket(&self) -> Option<std::os::windows::io::RawSocket> {
+        None
+    }
+    /// Start the overlapped operation.
+    ///
+    /// # Safety
+    /// The `overlapped` and the memory passed to the kernel must be valid until the completion
+    /// is posted, so the op must only pass the memory on heap, since the op itself may move.
+    #[cfg(all(windows, feature = "iocp"))]
+    unsafe fn iocp_call(
+        &mut self,
+        _overlapped: *mut windows_sys::Win32::System::IO::OVERLAPPED,
+    ) -> io::Result<()> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+    /// Finish the overlapped operation with the number of bytes transferred.
+    #[cfg(all(windows, feature = "iocp"))]
+    #[inline]
+    fn iocp_complete(&mut self, result: io::Result<u32>) -> io::Result<MaybeFd> {
+        MaybeFd::new_non_fd_result(result)
+    }
 }
 
 /// If legacy is enabled and iouring is not, we can expose io interface in a poll-like way.
@@ -208,9 +286,20 @@ impl<T: OpAble> Op<T> {
         }
     }
 
+    /// If the op is executed with syscalls, with legacy driver or not submitted to the ring by
+    /// uring driver.
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    fn is_polled(&self) -> bool {
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        if self.index == driver::uring::POLL_INDEX {
+            return true;
+        }
+        is_legacy()
+    }
+
     pub(crate) fn op_canceller(&self) -> OpCanceller {
-        #[cfg(feature = "legacy")]
-        if is_legacy() {
+        #[cfg(any(feature = "legacy", feature = "poll-io"))]
+        if self.is_polled() {
             return if let Some((dir, id)) = self.data.as_ref().unwrap().legacy_interest() {
                 OpCanceller {
                     index: id,
@@ -225,12 +314,49 @@ impl<T: OpAble> Op<T> {
         }
         OpCanceller {
             index: self.index,
-            #[cfg(feature = "legacy")]
+            #[cfg(any(feature = "legacy", feature = "poll-io"))]
             direction: None,
         }
     }
 }
 
+impl<T> Op<T>
+where
+    T: Unpin + OpAble + 'static,
+{
+    /// Poll the next completion of a multishot operation. Returns `None` once the operation has
+    /// terminated.
+    #[allow(unused)]
+    pub(crate) fn poll_next_multishot(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<CompletionMeta>> {
+        if self.index == usize::MAX {
+            return Poll::Ready(None);
+        }
+        let coop = ready!(crate::task::coop::poll_proceed(cx));
+        let data_mut = self.data.as_mut().expect("unexpected operation state");
+        let (meta, more) = ready!(self.driver.poll_multishot_op::<T>(data_mut, self.index, cx));
+        coop.made_progress();
+        if !more {
+            self.index = usize::MAX;
+        }
+        Poll::Ready(Some(meta))
+    }
+
+    /// Poll the completion with the big part of the CQE, see
+    /// [`uring_big_entries`](crate::RuntimeBuilder::uring_big_entries).
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    pub(crate) fn poll_big(&mut self, cx: &mut Context<'_>) -> Poll<(Completion<T>, [u64; 2])> {
+        let data_mut = self.data.as_mut().expect("unexpected operation state");
+        let (meta, big_cqe) = ready!(self.driver.poll_big_op::<T>(data_mut, self.index, cx));
+
+        self.index = usize::MAX;
+        let data = self.data.take().expect("unexpected operation state");
+        Poll::Ready((Completion { data, meta }, big_cqe))
+    }
+}
+
 impl<T> Future for Op<T>
 where
     T: Unpin + OpAble + 'static,
@@ -238,9 +364,11 @@ where
     type Output = Completion<T>;
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let coop = ready!(crate::task::coop::poll_proceed(cx));
         let me = &mut *self;
         let data_mut = me.data.as_mut().expect("unexpected operation state");
         let meta = ready!(me.driver.poll_op::<T>(data_mut, me.index, cx));
+        coop.made_progress();
 
         me.index = usize::MAX;
         let data = me.data.take().expect("unexpected operation state");
@@ -257,16 +385,24 @@ impl<T: OpAble> Drop for Op<T> {
     }
 }
 
+#[cfg(all(windows, feature = "iocp"))]
+impl<T: OpAble> Drop for Op<T> {
+    #[inline]
+    fn drop(&mut self) {
+        self.driver.drop_op(self.index, &mut self.data, false);
+    }
+}
+
 /// Check if current driver is legacy.
 #[allow(unused)]
-#[cfg(not(target_os = "linux"))]
+#[cfg(not(any(target_os = "linux", all(windows, feature = "iocp"))))]
 #[inline]
 pub const fn is_legacy() -> bool {
     true
 }
 
 /// Check if current driver is legacy.
-#[cfg(target_os = "linux")]
+#[cfg(any(target_os = "linux", all(windows, feature = "iocp")))]
 #[inline]
 pub fn is_legacy() -> bool {
     super::CURRENT.with(|inner| inner.is_legacy())
@@ -275,7 +411,7 @@ pub fn is_legacy() -> bool {
 #[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub(crate) struct OpCanceller {
     pub(super) index: usize,
-    #[cfg(feature = "legacy")]
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
     pub(super) direction: Option<super::ready::Direction>,
 }
 
@@ -6,7 +6,19 @@ use std::{
 };
 
 #[cfg(all(target_os = "linux", feature = "iouring"))]
-use io_uring::{opcode, types};
+use io_uring::opcode;
+#[cfg(all(windows, feature = "iocp"))]
+use {
+    std::os::windows::io::{BorrowedSocket, IntoRawSocket, RawSocket},
+    windows_sys::Win32::{
+        Foundation::FALSE,
+        Networking::WinSock::{
+            setsockopt, AcceptEx, GetAcceptExSockaddrs, SOCKADDR, SOCKET, SOCKET_ERROR, SOL_SOCKET,
+            SO_UPDATE_ACCEPT_CONTEXT,
+        },
+        System::IO::OVERLAPPED,
+    },
+};
 #[cfg(windows)]
 use {
     std::os::windows::prelude::AsRawSocket,
@@ -26,6 +38,19 @@ pub(crate) struct Accept {
     pub(crate) addr: Box<(MaybeUninit<libc::sockaddr_storage>, libc::socklen_t)>,
     #[cfg(windows)]
     pub(crate) addr: Box<(MaybeUninit<SOCKADDR_STORAGE>, socklen_t)>,
+    /// The socket to accept the connection with `AcceptEx`, and the buffer of its addresses.
+    #[cfg(all(windows, feature = "iocp"))]
+    accept_ex: Option<Box<AcceptExState>>,
+}
+
+/// Each address written by `AcceptEx` takes 16 bytes more than the max address length.
+#[cfg(all(windows, feature = "iocp"))]
+const ACCEPT_EX_ADDR_LEN: usize = size_of::<SOCKADDR_STORAGE>() + 16;
+
+#[cfg(all(windows, feature = "iocp"))]
+struct AcceptExState {
+    socket: socket2::Socket,
+    buf: [u8; ACCEPT_EX_ADDR_LEN * 2],
 }
 
 impl Op<Accept> {
@@ -46,22 +71,24 @@ impl Op<Accept> {
         Op::submit_with(Accept {
             fd: fd.clone(),
             addr,
+            #[cfg(all(windows, feature = "iocp"))]
+            accept_ex: None,
         })
     }
 }
 
 impl OpAble for Accept {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    const URING_OPCODE: Option<u8> = Some(opcode::Accept::CODE);
+
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     const RET_IS_FD: bool = true;
 
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
-        opcode::Accept::new(
-            types::Fd(self.fd.raw_fd()),
-            self.addr.0.as_mut_ptr() as *mut _,
-            &mut self.addr.1,
-        )
-        .build()
+        self.fd.uring_entry(|fd| {
+            opcode::Accept::new(fd, self.addr.0.as_mut_ptr() as *mut _, &mut self.addr.1).build()
+        })
     }
 
     #[cfg(any(feature = "legacy", feature = "poll-io"))]
@@ -79,49 +106,181 @@ impl OpAble for Accept {
         crate::syscall!(accept@FD(fd as _, addr, len), PartialEq::eq, INVALID_SOCKET)
     }
 
+    #[cfg(all(windows, feature = "iocp"))]
+    #[inline]
+    fn iocp_socket(&self) -> Option<RawSocket> {
+        self.fd.iocp_socket()
+    }
+
+    #[cfg(all(windows, feature = "iocp"))]
+    unsafe fn iocp_call(&mut self, overlapped: *mut OVERLAPPED) -> io::Result<()> {
+        // The accepted socket must be created before, with the family of the listener.
+        let listener = BorrowedSocket::borrow_raw(self.fd.raw_socket());
+        let domain = socket2::SockRef::from(&listener).local_addr()?.domain();
+        let socket = socket2::Socket::new(domain, socket2::Type::STREAM, None)?;
+        let state = self.accept_ex.insert(Box::new(AcceptExState {
+            socket,
+            buf: [0; ACCEPT_EX_ADDR_LEN * 2],
+        }));
+
+        let mut received = 0;
+        let ok = AcceptEx(
+            self.fd.raw_socket() as _,
+            state.socket.as_raw_socket() as _,
+            state.buf.as_mut_ptr().cast(),
+            0,
+            ACCEPT_EX_ADDR_LEN as _,
+            ACCEPT_EX_ADDR_LEN as _,
+            &mut received,
+            overlapped,
+        );
+        crate::driver::iocp::check_started(ok == FALSE)
+    }
+
+    #[cfg(all(windows, feature = "iocp"))]
+    fn iocp_complete(&mut self, result: io::Result<u32>) -> io::Result<MaybeFd> {
+        result?;
+        let state = self.accept_ex.take().expect("unexpected operation state");
+        let listener = self.fd.raw_socket() as SOCKET;
+        let ret = unsafe {
+            setsockopt(
+                state.socket.as_raw_socket() as _,
+                SOL_SOCKET,
+                SO_UPDATE_ACCEPT_CONTEXT,
+                &listener as *const SOCKET as *const u8,
+                size_of::<SOCKET>() as _,
+            )
+        };
+        if ret == SOCKET_ERROR {
+            return Err(io::Error::last_os_error());
+        }
+
+        let mut local: *mut SOCKADDR = std::ptr::null_mut();
+        let mut local_len = 0;
+        let mut remote: *mut SOCKADDR = std::ptr::null_mut();
+        let mut remote_len = 0;
+        unsafe {
+            GetAcceptExSockaddrs(
+                state.buf.as_ptr().cast(),
+                0,
+                ACCEPT_EX_ADDR_LEN as _,
+                ACCEPT_EX_ADDR_LEN as _,
+                &mut local,
+                &mut local_len,
+                &mut remote,
+                &mut remote_len,
+            );
+            let len = (remote_len as usize).min(size_of::<SOCKADDR_STORAGE>());
+            std::ptr::copy_nonoverlapping(
+                remote.cast::<u8>(),
+                self.addr.0.as_mut_ptr().cast::<u8>(),
+                len,
+            );
+            self.addr.1 = len as _;
+            Ok(MaybeFd::new_fd(state.socket.into_raw_socket() as _))
+        }
+    }
+
     #[cfg(all(any(feature = "legacy", feature = "poll-io"), unix))]
     fn legacy_call(&mut self) -> io::Result<MaybeFd> {
         let fd = self.fd.as_raw_fd();
         let addr = self.addr.0.as_mut_ptr() as *mut _;
         let len = &mut self.addr.1;
-        // Here I use copied some code from mio because I don't want the conversion.
-
-        // On platforms that support it we can use `accept4(2)` to set `NONBLOCK`
-        // and `CLOEXEC` in the call to accept the connection.
-        #[cfg(any(
-            // Android x86's seccomp profile forbids calls to `accept4(2)`
-            // See https://github.com/tokio-rs/mio/issues/1445 for details
-            all(
-                not(target_arch="x86"),
-                target_os = "android"
-            ),
-            target_os = "dragonfly",
-            target_os = "freebsd",
-            target_os = "illumos",
-            target_os = "linux",
-            target_os = "netbsd",
-            target_os = "openbsd"
-        ))]
-        return {
-            let flag = libc::SOCK_CLOEXEC | libc::SOCK_NONBLOCK;
-            crate::syscall!(accept4@FD(fd, addr, len, flag))
-        };
+        legacy_accept(fd, addr, len)
+    }
+}
 
-        // But not all platforms have the `accept4(2)` call. Luckily BSD (derived)
-        // OSes inherit the non-blocking flag from the listener, so we just have to
-        // set `CLOEXEC`.
-        #[cfg(any(
-            all(target_arch = "x86", target_os = "android"),
-            target_os = "ios",
-            target_os = "macos",
-            target_os = "redox"
-        ))]
-        return {
-            let stream_fd = crate::syscall!(accept@FD(fd, addr, len))?;
-            let fd = stream_fd.fd() as libc::c_int;
-            crate::syscall!(fcntl@RAW(fd, libc::F_SETFD, libc::FD_CLOEXEC))?;
-            crate::syscall!(fcntl@RAW(fd, libc::F_SETFL, libc::O_NONBLOCK))?;
-            Ok(stream_fd)
-        };
+#[cfg(all(any(feature = "legacy", feature = "poll-io"), unix))]
+fn legacy_accept(
+    fd: std::os::unix::io::RawFd,
+    addr: *mut libc::sockaddr,
+    len: *mut libc::socklen_t,
+) -> io::Result<MaybeFd> {
+    // Here I use copied some code from mio because I don't want the conversion.
+
+    // On platforms that support it we can use `accept4(2)` to set `NONBLOCK`
+    // and `CLOEXEC` in the call to accept the connection.
+    #[cfg(any(
+        // Android x86's seccomp profile forbids calls to `accept4(2)`
+        // See https://github.com/tokio-rs/mio/issues/1445 for details
+        all(
+            not(target_arch="x86"),
+            target_os = "android"
+        ),
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "illumos",
+        target_os = "linux",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    return {
+        let flag = libc::SOCK_CLOEXEC | libc::SOCK_NONBLOCK;
+        crate::syscall!(accept4@FD(fd, addr, len, flag))
+    };
+
+    // But not all platforms have the `accept4(2)` call. Luckily BSD (derived)
+    // OSes inherit the non-blocking flag from the listener, so we just have to
+    // set `CLOEXEC`.
+    #[cfg(any(
+        all(target_arch = "x86", target_os = "android"),
+        target_os = "ios",
+        target_os = "macos",
+        target_os = "redox"
+    ))]
+    return {
+        let stream_fd = crate::syscall!(accept@FD(fd, addr, len))?;
+        let fd = stream_fd.fd() as libc::c_int;
+        crate::syscall!(fcntl@RAW(fd, libc::F_SETFD, libc::FD_CLOEXEC))?;
+        crate::syscall!(fcntl@RAW(fd, libc::F_SETFL, libc::O_NONBLOCK))?;
+        Ok(stream_fd)
+    };
+}
+
+/// Multishot accept. One submission yields accepted fds until it is canceled or failed.
+pub(crate) struct AcceptMulti {
+    pub(crate) fd: SharedFd,
+}
+
+impl Op<AcceptMulti> {
+    /// Accept connections with multishot mode(requires kernel 5.19+ when using uring)
+    pub(crate) fn accept_multi(fd: &SharedFd) -> io::Result<Self> {
+        Op::submit_with(AcceptMulti { fd: fd.clone() })
+    }
+}
+
+impl OpAble for AcceptMulti {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    const RET_IS_FD: bool = true;
+
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        self.fd
+            .uring_entry(|fd| opcode::AcceptMulti::new(fd).build())
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    #[inline]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        self.fd.registered_index().map(|idx| (Direction::Read, idx))
+    }
+
+    #[cfg(all(any(feature = "legacy", feature = "poll-io"), windows))]
+    fn legacy_call(&mut self) -> io::Result<MaybeFd> {
+        let fd = self.fd.as_raw_socket();
+        crate::syscall!(
+            accept@FD(fd as _, std::ptr::null_mut(), std::ptr::null_mut()),
+            PartialEq::eq,
+            INVALID_SOCKET
+        )
+    }
+
+    #[cfg(all(any(feature = "legacy", feature = "poll-io"), unix))]
+    fn legacy_call(&mut self) -> io::Result<MaybeFd> {
+        legacy_accept(
+            self.fd.as_raw_fd(),
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
     }
 }
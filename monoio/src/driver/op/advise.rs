@@ -0,0 +1,109 @@
+//! This module works only on linux.
+
+use std::io;
+
+#[cfg(feature = "iouring")]
+use io_uring::opcode;
+
+use super::{super::shared_fd::SharedFd, Op, OpAble};
+#[cfg(any(feature = "legacy", feature = "poll-io"))]
+use super::{driver::ready::Direction, MaybeFd};
+
+pub(crate) struct Fadvise {
+    fd: SharedFd,
+    offset: u64,
+    len: u32,
+    advice: i32,
+}
+
+impl Op<Fadvise> {
+    pub(crate) fn fadvise(
+        fd: &SharedFd,
+        offset: u64,
+        len: u32,
+        advice: i32,
+    ) -> io::Result<Op<Fadvise>> {
+        Op::submit_with(Fadvise {
+            fd: fd.clone(),
+            offset,
+            len,
+            advice,
+        })
+    }
+}
+
+impl OpAble for Fadvise {
+    #[cfg(feature = "iouring")]
+    const URING_OPCODE: Option<u8> = Some(opcode::Fadvise::CODE);
+
+    #[cfg(feature = "iouring")]
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        let (offset, len, advice) = (self.offset, self.len, self.advice);
+        self.fd.uring_entry(|fd| {
+            opcode::Fadvise::new(fd, len as libc::off_t, advice)
+                .offset(offset)
+                .build()
+        })
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    #[inline]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        None
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    fn legacy_call(&mut self) -> io::Result<MaybeFd> {
+        // `posix_fadvise` returns the error number instead of setting `errno`.
+        match unsafe {
+            libc::posix_fadvise(
+                self.fd.raw_fd(),
+                self.offset as libc::off_t,
+                self.len as libc::off_t,
+                self.advice,
+            )
+        } {
+            0 => Ok(MaybeFd::new_non_fd(0)),
+            errno => Err(io::Error::from_raw_os_error(errno)),
+        }
+    }
+}
+
+pub(crate) struct Madvise {
+    addr: *mut libc::c_void,
+    len: u32,
+    advice: i32,
+}
+
+impl Op<Madvise> {
+    /// # Safety
+    /// The memory range must stay valid until the operation completes.
+    pub(crate) unsafe fn madvise(
+        addr: *mut libc::c_void,
+        len: u32,
+        advice: i32,
+    ) -> io::Result<Op<Madvise>> {
+        Op::submit_with(Madvise { addr, len, advice })
+    }
+}
+
+impl OpAble for Madvise {
+    #[cfg(feature = "iouring")]
+    const URING_OPCODE: Option<u8> = Some(opcode::Madvise::CODE);
+
+    #[cfg(feature = "iouring")]
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        opcode::Madvise::new(self.addr, self.len as libc::off_t, self.advice).build()
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    #[inline]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        None
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    fn legacy_call(&mut self) -> io::Result<MaybeFd> {
+        crate::syscall!(madvise@NON_FD(self.addr, self.len as usize, self.advice))
+    }
+}
@@ -22,6 +22,15 @@ impl Op<Close> {
     #[allow(unused)]
     #[cfg(unix)]
     pub(crate) fn close(fd: RawFd) -> io::Result<Op<Close>> {
+        // The op is not polled, so it can not fall back to syscall like the others. Let the
+        // caller close it if io_uring can not(e.g. it is restricted).
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        if crate::driver::CURRENT.is_set() {
+            let caps = crate::driver::capabilities();
+            if caps.is_uring() && !caps.is_opcode_supported(opcode::Close::CODE) {
+                return Err(io::ErrorKind::Unsupported.into());
+            }
+        }
         Op::try_submit_with(Close { fd })
     }
 
@@ -1,12 +1,30 @@
 use std::{io, net::SocketAddr};
 
 #[cfg(all(target_os = "linux", feature = "iouring"))]
-use io_uring::{opcode, types};
+use io_uring::opcode;
 #[cfg(windows)]
 use windows_sys::Win32::Networking::WinSock::{
     connect, socklen_t, AF_INET, AF_INET6, IN6_ADDR, IN6_ADDR_0, IN_ADDR, IN_ADDR_0, SOCKADDR_IN,
     SOCKADDR_IN6, SOCKADDR_IN6_0, SOCKET_ERROR,
 };
+#[cfg(all(windows, feature = "iocp"))]
+use {
+    std::{
+        net::{Ipv4Addr, Ipv6Addr},
+        os::windows::io::{BorrowedSocket, RawSocket},
+    },
+    windows_sys::{
+        core::GUID,
+        Win32::{
+            Foundation::FALSE,
+            Networking::WinSock::{
+                setsockopt, WSAIoctl, LPFN_CONNECTEX, SIO_GET_EXTENSION_FUNCTION_POINTER,
+                SOL_SOCKET, SO_UPDATE_CONNECT_CONTEXT, WSAID_CONNECTEX,
+            },
+            System::IO::OVERLAPPED,
+        },
+    },
+};
 
 use super::{super::shared_fd::SharedFd, Op, OpAble};
 #[cfg(any(feature = "legacy", feature = "poll-io"))]
@@ -42,14 +60,14 @@ impl Op<Connect> {
 }
 
 impl OpAble for Connect {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    const URING_OPCODE: Option<u8> = Some(opcode::Connect::CODE);
+
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
-        opcode::Connect::new(
-            types::Fd(self.fd.raw_fd()),
-            self.socket_addr.as_ptr(),
-            self.socket_addr_len,
-        )
-        .build()
+        self.fd.uring_entry(|fd| {
+            opcode::Connect::new(fd, self.socket_addr.as_ptr(), self.socket_addr_len).build()
+        })
     }
 
     #[cfg(any(feature = "legacy", feature = "poll-io"))]
@@ -58,6 +76,12 @@ impl OpAble for Connect {
         None
     }
 
+    #[cfg(all(target_os = "linux", feature = "iouring", feature = "poll-io"))]
+    #[inline]
+    fn uring_poll(&self) -> bool {
+        self.fd.registered_index().is_some()
+    }
+
     #[cfg(any(feature = "legacy", feature = "poll-io"))]
     fn legacy_call(&mut self) -> io::Result<MaybeFd> {
         // For ios/macos, if tfo is enabled, we will
@@ -113,8 +137,82 @@ impl OpAble for Connect {
             Ok(MaybeFd::zero())
         }
     }
+
+    #[cfg(all(windows, feature = "iocp"))]
+    #[inline]
+    fn iocp_socket(&self) -> Option<RawSocket> {
+        self.fd.iocp_socket()
+    }
+
+    #[cfg(all(windows, feature = "iocp"))]
+    unsafe fn iocp_call(&mut self, overlapped: *mut OVERLAPPED) -> io::Result<()> {
+        let fd = self.fd.raw_socket();
+        let connect_ex = CONNECT_EX
+            .get_or_init(|| {
+                let mut connect_ex: LPFN_CONNECTEX = None;
+                let mut dw_bytes = 0;
+                WSAIoctl(
+                    fd as _,
+                    SIO_GET_EXTENSION_FUNCTION_POINTER,
+                    &WSAID_CONNECTEX as *const _ as *const std::ffi::c_void,
+                    std::mem::size_of::<GUID>() as _,
+                    &mut connect_ex as *mut _ as *mut std::ffi::c_void,
+                    std::mem::size_of::<LPFN_CONNECTEX>() as _,
+                    &mut dw_bytes,
+                    std::ptr::null_mut(),
+                    None,
+                );
+                connect_ex
+            })
+            .ok_or_else(|| io::Error::new(io::ErrorKind::Unsupported, "ConnectEx not found"))?;
+
+        // ConnectEx requires the socket to be bound.
+        let socket = BorrowedSocket::borrow_raw(fd);
+        let socket = socket2::SockRef::from(&socket);
+        if socket.local_addr().is_err() {
+            let unspecified: SocketAddr = if self.socket_addr.v4.sin_family == AF_INET {
+                (Ipv4Addr::UNSPECIFIED, 0).into()
+            } else {
+                (Ipv6Addr::UNSPECIFIED, 0).into()
+            };
+            socket.bind(&unspecified.into())?;
+        }
+
+        let ok = connect_ex(
+            fd as _,
+            self.socket_addr.as_ptr().cast(),
+            self.socket_addr_len,
+            std::ptr::null(),
+            0,
+            std::ptr::null_mut(),
+            overlapped,
+        );
+        crate::driver::iocp::check_started(ok == FALSE)
+    }
+
+    #[cfg(all(windows, feature = "iocp"))]
+    fn iocp_complete(&mut self, result: io::Result<u32>) -> io::Result<MaybeFd> {
+        result?;
+        // Update the context so the functions like getpeername work with the socket.
+        let ret = unsafe {
+            setsockopt(
+                self.fd.raw_socket() as _,
+                SOL_SOCKET,
+                SO_UPDATE_CONNECT_CONTEXT,
+                std::ptr::null(),
+                0,
+            )
+        };
+        if ret == SOCKET_ERROR {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(MaybeFd::zero())
+    }
 }
 
+#[cfg(all(windows, feature = "iocp"))]
+static CONNECT_EX: std::sync::OnceLock<LPFN_CONNECTEX> = std::sync::OnceLock::new();
+
 #[cfg(unix)]
 pub(crate) struct ConnectUnix {
     /// Holds a strong ref to the FD, preventing the file from being closed
@@ -140,14 +238,19 @@ impl Op<ConnectUnix> {
 
 #[cfg(unix)]
 impl OpAble for ConnectUnix {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    const URING_OPCODE: Option<u8> = Some(opcode::Connect::CODE);
+
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
-        opcode::Connect::new(
-            types::Fd(self.fd.raw_fd()),
-            &self.socket_addr.0 as *const _ as *const _,
-            self.socket_addr.1,
-        )
-        .build()
+        self.fd.uring_entry(|fd| {
+            opcode::Connect::new(
+                fd,
+                &self.socket_addr.0 as *const _ as *const _,
+                self.socket_addr.1,
+            )
+            .build()
+        })
     }
 
     #[cfg(any(feature = "legacy", feature = "poll-io"))]
@@ -156,6 +259,12 @@ impl OpAble for ConnectUnix {
         None
     }
 
+    #[cfg(all(target_os = "linux", feature = "iouring", feature = "poll-io"))]
+    #[inline]
+    fn uring_poll(&self) -> bool {
+        self.fd.registered_index().is_some()
+    }
+
     #[cfg(any(feature = "legacy", feature = "poll-io"))]
     fn legacy_call(&mut self) -> io::Result<MaybeFd> {
         match crate::syscall!(connect@RAW(
@@ -169,6 +278,70 @@ impl OpAble for ConnectUnix {
     }
 }
 
+#[cfg(target_os = "linux")]
+pub(crate) struct ConnectVsock {
+    /// Holds a strong ref to the FD, preventing the file from being closed
+    /// while the operation is in-flight.
+    pub(crate) fd: SharedFd,
+    socket_addr: Box<libc::sockaddr_vm>,
+}
+
+#[cfg(target_os = "linux")]
+impl Op<ConnectVsock> {
+    /// Submit a request to connect.
+    pub(crate) fn connect_vsock(
+        socket: SharedFd,
+        socket_addr: libc::sockaddr_vm,
+    ) -> io::Result<Op<ConnectVsock>> {
+        Op::submit_with(ConnectVsock {
+            fd: socket,
+            socket_addr: Box::new(socket_addr),
+        })
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl OpAble for ConnectVsock {
+    #[cfg(feature = "iouring")]
+    const URING_OPCODE: Option<u8> = Some(opcode::Connect::CODE);
+
+    #[cfg(feature = "iouring")]
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        self.fd.uring_entry(|fd| {
+            opcode::Connect::new(
+                fd,
+                &*self.socket_addr as *const _ as *const _,
+                std::mem::size_of::<libc::sockaddr_vm>() as _,
+            )
+            .build()
+        })
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    #[inline]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        None
+    }
+
+    #[cfg(all(feature = "iouring", feature = "poll-io"))]
+    #[inline]
+    fn uring_poll(&self) -> bool {
+        self.fd.registered_index().is_some()
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    fn legacy_call(&mut self) -> io::Result<MaybeFd> {
+        match crate::syscall!(connect@RAW(
+            self.fd.raw_fd(),
+            &*self.socket_addr as *const _ as *const _,
+            std::mem::size_of::<libc::sockaddr_vm>() as _
+        )) {
+            Err(err) if err.raw_os_error() != Some(libc::EINPROGRESS) => Err(err),
+            _ => Ok(MaybeFd::zero()),
+        }
+    }
+}
+
 /// A type with the same memory layout as `libc::sockaddr`. Used in converting Rust level
 /// SocketAddr* types into their system representation. The benefit of this specific
 /// type over using `libc::sockaddr_storage` is that this type is exactly as large as it
@@ -0,0 +1,112 @@
+//! This module works only on linux.
+
+use std::io;
+
+#[cfg(feature = "iouring")]
+use io_uring::opcode;
+
+use super::{super::shared_fd::SharedFd, Op, OpAble};
+#[cfg(any(feature = "legacy", feature = "poll-io"))]
+use super::{driver::ready::Direction, MaybeFd};
+
+/// `IORING_OP_FTRUNCATE`(since 6.9), which is not covered by `io_uring::opcode` yet.
+#[cfg(feature = "iouring")]
+const FTRUNCATE_CODE: u8 = 55;
+
+pub(crate) struct Fallocate {
+    fd: SharedFd,
+    offset: u64,
+    len: u64,
+    mode: i32,
+}
+
+impl Op<Fallocate> {
+    pub(crate) fn fallocate(
+        fd: &SharedFd,
+        offset: u64,
+        len: u64,
+        mode: i32,
+    ) -> io::Result<Op<Fallocate>> {
+        Op::submit_with(Fallocate {
+            fd: fd.clone(),
+            offset,
+            len,
+            mode,
+        })
+    }
+}
+
+impl OpAble for Fallocate {
+    #[cfg(feature = "iouring")]
+    const URING_OPCODE: Option<u8> = Some(opcode::Fallocate::CODE);
+
+    #[cfg(feature = "iouring")]
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        let (offset, len, mode) = (self.offset, self.len, self.mode);
+        self.fd.uring_entry(|fd| {
+            opcode::Fallocate::new(fd, len)
+                .offset(offset)
+                .mode(mode)
+                .build()
+        })
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    #[inline]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        None
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    fn legacy_call(&mut self) -> io::Result<MaybeFd> {
+        crate::syscall!(fallocate@NON_FD(
+            self.fd.raw_fd(),
+            self.mode,
+            self.offset as libc::off_t,
+            self.len as libc::off_t
+        ))
+    }
+}
+
+pub(crate) struct Ftruncate {
+    fd: SharedFd,
+    len: u64,
+}
+
+impl Op<Ftruncate> {
+    pub(crate) fn ftruncate(fd: &SharedFd, len: u64) -> io::Result<Op<Ftruncate>> {
+        Op::submit_with(Ftruncate {
+            fd: fd.clone(),
+            len,
+        })
+    }
+}
+
+impl OpAble for Ftruncate {
+    #[cfg(feature = "iouring")]
+    const URING_OPCODE: Option<u8> = Some(FTRUNCATE_CODE);
+
+    #[cfg(feature = "iouring")]
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        let len = self.len;
+        // FTRUNCATE takes the fd and the length in `off` only, which is the layout of a
+        // FALLOCATE with zero `len` and `mode`, so build that and replace the opcode.
+        let entry = self
+            .fd
+            .uring_entry(|fd| opcode::Fallocate::new(fd, 0).offset(len).build());
+        let mut sqe: [u8; 64] = unsafe { std::mem::transmute(entry) };
+        sqe[0] = FTRUNCATE_CODE;
+        unsafe { std::mem::transmute(sqe) }
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    #[inline]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        None
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    fn legacy_call(&mut self) -> io::Result<MaybeFd> {
+        crate::syscall!(ftruncate@NON_FD(self.fd.raw_fd(), self.len as libc::off_t))
+    }
+}
@@ -0,0 +1,51 @@
+use std::{io, os::unix::io::RawFd};
+
+use io_uring::opcode;
+
+#[cfg(any(feature = "legacy", feature = "poll-io"))]
+use super::MaybeFd;
+use super::{Op, OpAble};
+
+/// Install a fd into the fixed file table, letting the kernel pick a free slot.
+pub(crate) struct FilesUpdate {
+    /// The kernel writes the allocated slot back, so it must not move.
+    fds: Box<[RawFd; 1]>,
+}
+
+impl Op<FilesUpdate> {
+    pub(crate) fn register_file(fd: RawFd) -> io::Result<Op<FilesUpdate>> {
+        Op::submit_with(FilesUpdate {
+            fds: Box::new([fd]),
+        })
+    }
+
+    pub(crate) async fn result(self) -> io::Result<u32> {
+        let complete = self.await;
+        match complete.meta.result?.into_inner() {
+            1 => Ok(complete.data.fds[0] as u32),
+            _ => Err(io::Error::other("no slot allocated in fixed file table")),
+        }
+    }
+}
+
+impl OpAble for FilesUpdate {
+    const SKIP_CANCEL: bool = true;
+
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        // `IORING_FILE_INDEX_ALLOC`
+        opcode::FilesUpdate::new(self.fds.as_ptr(), 1)
+            .offset(-1)
+            .build()
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    #[inline]
+    fn legacy_interest(&self) -> Option<(crate::driver::ready::Direction, usize)> {
+        None
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    fn legacy_call(&mut self) -> io::Result<MaybeFd> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
@@ -14,6 +14,16 @@ pub(crate) struct Fsync {
     data_sync: bool,
 }
 
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+impl Fsync {
+    pub(crate) fn new(fd: &SharedFd, data_sync: bool) -> Self {
+        Fsync {
+            fd: fd.clone(),
+            data_sync,
+        }
+    }
+}
+
 impl Op<Fsync> {
     pub(crate) fn fsync(fd: &SharedFd) -> io::Result<Op<Fsync>> {
         Op::submit_with(Fsync {
@@ -35,11 +45,14 @@ impl Op<Fsync> {
 impl OpAble for Fsync {
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
-        let mut opc = opcode::Fsync::new(types::Fd(self.fd.raw_fd()));
-        if self.data_sync {
-            opc = opc.flags(types::FsyncFlags::DATASYNC)
-        }
-        opc.build()
+        let data_sync = self.data_sync;
+        self.fd.uring_entry(|fd| {
+            let mut opc = opcode::Fsync::new(fd);
+            if data_sync {
+                opc = opc.flags(types::FsyncFlags::DATASYNC)
+            }
+            opc.build()
+        })
     }
 
     #[cfg(any(feature = "legacy", feature = "poll-io"))]
@@ -73,3 +86,62 @@ impl OpAble for Fsync {
         crate::syscall!(fsync@NON_FD(self.fd.raw_fd()))
     }
 }
+
+/// `sync_file_range(2)`, which starts or waits for the writeback of a range of the file.
+#[cfg(target_os = "linux")]
+pub(crate) struct SyncFileRange {
+    fd: SharedFd,
+    offset: u64,
+    len: u32,
+    flags: u32,
+}
+
+#[cfg(target_os = "linux")]
+impl Op<SyncFileRange> {
+    pub(crate) fn sync_file_range(
+        fd: &SharedFd,
+        offset: u64,
+        len: u32,
+        flags: u32,
+    ) -> io::Result<Op<SyncFileRange>> {
+        Op::submit_with(SyncFileRange {
+            fd: fd.clone(),
+            offset,
+            len,
+            flags,
+        })
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl OpAble for SyncFileRange {
+    #[cfg(feature = "iouring")]
+    const URING_OPCODE: Option<u8> = Some(opcode::SyncFileRange::CODE);
+
+    #[cfg(feature = "iouring")]
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        let (offset, len, flags) = (self.offset, self.len, self.flags);
+        self.fd.uring_entry(|fd| {
+            opcode::SyncFileRange::new(fd, len)
+                .offset(offset)
+                .flags(flags)
+                .build()
+        })
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    #[inline]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        None
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    fn legacy_call(&mut self) -> io::Result<MaybeFd> {
+        crate::syscall!(sync_file_range@NON_FD(
+            self.fd.raw_fd(),
+            self.offset as libc::off64_t,
+            self.len as libc::off64_t,
+            self.flags
+        ))
+    }
+}
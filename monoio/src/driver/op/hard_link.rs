@@ -0,0 +1,55 @@
+use std::{ffi::CString, io, path::Path};
+
+#[cfg(any(feature = "legacy", feature = "poll-io"))]
+use super::{driver::ready::Direction, MaybeFd};
+use super::{Op, OpAble};
+use crate::driver::util::cstr;
+
+pub(crate) struct HardLink {
+    pub(crate) original: CString,
+    pub(crate) link: CString,
+}
+
+impl Op<HardLink> {
+    pub(crate) fn hard_link<P: AsRef<Path>, Q: AsRef<Path>>(
+        original: P,
+        link: Q,
+    ) -> io::Result<Op<HardLink>> {
+        let original = cstr(original.as_ref())?;
+        let link = cstr(link.as_ref())?;
+        Op::submit_with(HardLink { original, link })
+    }
+}
+
+impl OpAble for HardLink {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    const URING_OPCODE: Option<u8> = Some(io_uring::opcode::LinkAt::CODE);
+
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        use io_uring::{opcode, types};
+        opcode::LinkAt::new(
+            types::Fd(libc::AT_FDCWD),
+            self.original.as_ptr(),
+            types::Fd(libc::AT_FDCWD),
+            self.link.as_ptr(),
+        )
+        .build()
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        None
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    fn legacy_call(&mut self) -> io::Result<MaybeFd> {
+        crate::syscall!(linkat@NON_FD(
+            libc::AT_FDCWD,
+            self.original.as_ptr(),
+            libc::AT_FDCWD,
+            self.link.as_ptr(),
+            0
+        ))
+    }
+}
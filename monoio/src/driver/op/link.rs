@@ -0,0 +1,264 @@
+//! Operations linked with `IOSQE_IO_LINK`.
+//!
+//! Each operation of a chain is started by the kernel only after the previous one completes
+//! successfully, so the ordering needs no round trip to userspace. If one fails (or a read or
+//! write of it is short), the rest of the chain completes with `ECANCELED`.
+//!
+//! ```no_run
+//! use monoio::{driver::link, fs::File};
+//!
+//! #[monoio::main]
+//! async fn main() -> std::io::Result<()> {
+//!     let file = File::create("foo.txt").await?;
+//!     let (write, sync) = link::submit((
+//!         link::write_at(&file, b"hello".to_vec(), 0),
+//!         link::sync_data(&file),
+//!     ))
+//!     .await;
+//!     write.0?;
+//!     sync?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! With legacy driver, or if one of the operations is not submitted to the ring, the operations
+//! are executed one by one with the same semantic.
+
+use std::{
+    future::Future,
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use super::{fsync::Fsync, read::ReadAt, write::WriteAt, Completion, CompletionMeta, Op, OpAble};
+use crate::{
+    buf::{IoBuf, IoBufMut},
+    driver,
+    fs::File,
+    BufResult,
+};
+
+// The sealed traits refer to the crate-private operations, which never leave the crate.
+#[allow(private_bounds, private_interfaces)]
+mod sealed {
+    use std::{future::Future, io, mem, pin::Pin};
+
+    use super::{Completion, CompletionMeta, Context, Op, OpAble, Poll};
+
+    pub trait LinkOpPriv: OpAble + Unpin + Sized + 'static {
+        fn output(completion: Completion<Self>) -> <Self as super::LinkOp>::Output
+        where
+            Self: super::LinkOp;
+    }
+
+    pub trait LinkChainPriv: Sized {
+        type Slots: Unpin;
+
+        fn submit(self) -> Self::Slots;
+
+        fn poll(
+            slots: &mut Self::Slots,
+            cx: &mut Context<'_>,
+        ) -> Poll<<Self as super::LinkChain>::Output>
+        where
+            Self: super::LinkChain;
+    }
+
+    /// An operation of the submitted chain.
+    pub enum Slot<T: OpAble + 'static> {
+        Pending(Op<T>),
+        Done(Completion<T>),
+        Taken,
+    }
+
+    impl<T: OpAble + Unpin + 'static> Slot<T> {
+        pub(super) fn failed(data: T, err: Option<io::Error>) -> Self {
+            Slot::Done(Completion {
+                data,
+                meta: CompletionMeta {
+                    result: Err(
+                        err.unwrap_or_else(|| io::Error::from_raw_os_error(libc::ECANCELED))
+                    ),
+                    flags: 0,
+                },
+            })
+        }
+
+        /// Poll the operation after the previous ones complete, `failed` is set if it fails.
+        pub(super) fn poll(&mut self, cx: &mut Context<'_>, failed: &mut bool) -> Poll<()> {
+            // The kernel cancels the rest of the chain, we do the same when they are executed with
+            // syscalls.
+            #[cfg(any(feature = "legacy", feature = "poll-io"))]
+            if let Slot::Pending(op) = self {
+                if *failed && op.is_polled() {
+                    op.index = usize::MAX;
+                    let data = op.data.take().expect("unexpected operation state");
+                    *self = Slot::failed(data, None);
+                }
+            }
+            if let Slot::Pending(op) = self {
+                *self = Slot::Done(ready!(Pin::new(op).poll(cx)));
+            }
+            if let Slot::Done(completion) = self {
+                *failed |= completion.meta.result.is_err();
+            }
+            Poll::Ready(())
+        }
+
+        pub(super) fn take(&mut self) -> Completion<T> {
+            match mem::replace(self, Slot::Taken) {
+                Slot::Done(completion) => completion,
+                _ => panic!("unexpected operation state"),
+            }
+        }
+    }
+}
+
+use sealed::{LinkChainPriv, LinkOpPriv, Slot};
+
+/// An operation which can be linked with the others by [`submit`].
+pub trait LinkOp: LinkOpPriv {
+    /// The result of the operation.
+    type Output;
+}
+
+/// A chain of [`LinkOp`]s, which is a tuple of up to 8 operations.
+pub trait LinkChain: LinkChainPriv {
+    /// The results of the operations, in the order of the chain.
+    type Output;
+}
+
+/// Submit the operations as a chain, and return the results of each of them.
+///
+/// If the chain can not be submitted, e.g. it is longer than the submission queue, the first
+/// operation fails with the error and the rest are cancelled.
+///
+/// # Panics
+///
+/// Panics if called outside of a monoio runtime.
+pub fn submit<L: LinkChain>(ops: L) -> Link<L> {
+    Link {
+        slots: ops.submit(),
+    }
+}
+
+/// Future of the chain submitted with [`submit`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Link<L: LinkChain> {
+    slots: L::Slots,
+}
+
+impl<L: LinkChain> Future for Link<L> {
+    type Output = <L as LinkChain>::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        L::poll(&mut self.slots, cx)
+    }
+}
+
+/// Write the buffer at the position of the file, like [`File::write_at`].
+pub fn write_at<T: IoBuf>(
+    file: &File,
+    buf: T,
+    pos: u64,
+) -> impl LinkOp<Output = BufResult<usize, T>> {
+    WriteAt::new(file.shared_fd().clone(), buf, pos)
+}
+
+/// Read into the buffer from the position of the file, like [`File::read_at`].
+pub fn read_at<T: IoBufMut>(
+    file: &File,
+    buf: T,
+    pos: u64,
+) -> impl LinkOp<Output = BufResult<usize, T>> {
+    ReadAt::new(file.shared_fd().clone(), buf, pos)
+}
+
+/// Sync the data and metadata of the file, like [`File::sync_all`].
+pub fn sync_all(file: &File) -> impl LinkOp<Output = io::Result<()>> {
+    Fsync::new(file.shared_fd(), false)
+}
+
+/// Sync the data of the file, like [`File::sync_data`].
+pub fn sync_data(file: &File) -> impl LinkOp<Output = io::Result<()>> {
+    Fsync::new(file.shared_fd(), true)
+}
+
+impl<T: IoBuf> LinkOpPriv for WriteAt<T> {
+    fn output(completion: Completion<Self>) -> <Self as LinkOp>::Output {
+        let res = completion.meta.result.map(|v| v.into_inner() as usize);
+        (res, completion.data.buf)
+    }
+}
+
+impl<T: IoBuf> LinkOp for WriteAt<T> {
+    type Output = BufResult<usize, T>;
+}
+
+impl<T: IoBufMut> LinkOpPriv for ReadAt<T> {
+    fn output(completion: Completion<Self>) -> <Self as LinkOp>::Output {
+        let res = completion.meta.result.map(|v| v.into_inner() as usize);
+        let mut buf = completion.data.buf;
+        if let Ok(n) = res {
+            // Safety: the kernel wrote `n` bytes to the buffer
+            unsafe { buf.set_init(n) };
+        }
+        (res, buf)
+    }
+}
+
+impl<T: IoBufMut> LinkOp for ReadAt<T> {
+    type Output = BufResult<usize, T>;
+}
+
+impl LinkOpPriv for Fsync {
+    fn output(completion: Completion<Self>) -> <Self as LinkOp>::Output {
+        completion.meta.result.map(|_| ())
+    }
+}
+
+impl LinkOp for Fsync {
+    type Output = io::Result<()>;
+}
+
+macro_rules! link_chain {
+    ($($T:ident $idx:tt),+) => {
+        impl<$($T: LinkOp),+> LinkChainPriv for ($($T,)+) {
+            type Slots = ($(Slot<$T>,)+);
+
+            fn submit(self) -> Self::Slots {
+                driver::CURRENT.with(|driver| {
+                    let polled = false $(|| driver.link_polled(&self.$idx))+;
+                    if let Err(e) = driver.reserve_link([$($idx),+].len(), polled) {
+                        // The chain fails as a whole, as if its head fails.
+                        let mut err = Some(e);
+                        return ($(Slot::failed(self.$idx, err.take()),)+);
+                    }
+                    let mut chain = Vec::new();
+                    let slots = ($(Slot::Pending(driver.link_op(self.$idx, polled, &mut chain)),)+);
+                    driver.push_link(&chain);
+                    slots
+                })
+            }
+
+            fn poll(slots: &mut Self::Slots, cx: &mut Context<'_>) -> Poll<<Self as LinkChain>::Output> {
+                let mut failed = false;
+                $(ready!(slots.$idx.poll(cx, &mut failed));)+
+                Poll::Ready(($(<$T as LinkOpPriv>::output(slots.$idx.take()),)+))
+            }
+        }
+
+        impl<$($T: LinkOp),+> LinkChain for ($($T,)+) {
+            type Output = ($($T::Output,)+);
+        }
+    };
+}
+
+link_chain!(A 0, B 1);
+link_chain!(A 0, B 1, C 2);
+link_chain!(A 0, B 1, C 2, D 3);
+link_chain!(A 0, B 1, C 2, D 3, E 4);
+link_chain!(A 0, B 1, C 2, D 3, E 4, F 5);
+link_chain!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+link_chain!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
@@ -5,26 +5,50 @@ use libc::mode_t;
 #[cfg(any(feature = "legacy", feature = "poll-io"))]
 use super::MaybeFd;
 use super::{Op, OpAble};
-use crate::driver::util::cstr;
+use crate::driver::{shared_fd::SharedFd, util::cstr};
 
 pub(crate) struct MkDir {
+    /// The directory which `path` is relative to, or the current directory if `None`.
+    dir: Option<SharedFd>,
     path: CString,
     mode: mode_t,
 }
 
 impl Op<MkDir> {
     pub(crate) fn mkdir<P: AsRef<Path>>(path: P, mode: mode_t) -> std::io::Result<Op<MkDir>> {
+        Self::mkdir_at(None, path, mode)
+    }
+
+    pub(crate) fn mkdir_at<P: AsRef<Path>>(
+        dir: Option<&SharedFd>,
+        path: P,
+        mode: mode_t,
+    ) -> std::io::Result<Op<MkDir>> {
         let path = cstr(path.as_ref())?;
-        Op::submit_with(MkDir { path, mode })
+        Op::submit_with(MkDir {
+            dir: dir.cloned(),
+            path,
+            mode,
+        })
+    }
+}
+
+impl MkDir {
+    #[inline]
+    fn dirfd(&self) -> libc::c_int {
+        self.dir.as_ref().map_or(libc::AT_FDCWD, |dir| dir.raw_fd())
     }
 }
 
 impl OpAble for MkDir {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    const URING_OPCODE: Option<u8> = Some(io_uring::opcode::MkDirAt::CODE);
+
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
         use io_uring::{opcode, types};
 
-        opcode::MkDirAt::new(types::Fd(libc::AT_FDCWD), self.path.as_ptr())
+        opcode::MkDirAt::new(types::Fd(self.dirfd()), self.path.as_ptr())
             .mode(self.mode)
             .build()
     }
@@ -38,7 +62,7 @@ impl OpAble for MkDir {
     #[cfg(all(any(feature = "legacy", feature = "poll-io"), unix))]
     fn legacy_call(&mut self) -> std::io::Result<MaybeFd> {
         crate::syscall!(mkdirat@NON_FD(
-            libc::AT_FDCWD,
+            self.dirfd(),
             self.path.as_ptr(),
             self.mode
         ))
@@ -0,0 +1,175 @@
+//! `sendmmsg`/`recvmmsg` ops, only submitted by the legacy driver.
+//!
+//! io_uring has no equivalent opcode, where the batches are submitted as separate SQEs with a
+//! single `io_uring_enter` instead.
+
+use std::{io, mem::MaybeUninit, net::SocketAddr, os::unix::prelude::AsRawFd};
+
+use libc::{sockaddr_storage, socklen_t};
+use socket2::SockAddr;
+
+use super::{super::shared_fd::SharedFd, driver::ready::Direction, MaybeFd, Op, OpAble};
+use crate::buf::{IoBuf, IoBufMut, IoVecBufMut, IoVecMeta};
+
+pub(crate) struct SendMmsg<T> {
+    /// Holds a strong ref to the FD, preventing the file from being closed
+    /// while the operation is in-flight.
+    fd: SharedFd,
+    pub(crate) bufs: Vec<T>,
+    // The headers point to the addresses and iovecs, which must not move.
+    #[allow(unused)]
+    addrs: Vec<Option<SockAddr>>,
+    #[allow(unused)]
+    iovecs: Vec<IoVecMeta>,
+    hdrs: Vec<libc::mmsghdr>,
+}
+
+impl<T: IoBuf> Op<SendMmsg<T>> {
+    pub(crate) fn send_mmsg(fd: SharedFd, msgs: Vec<(T, Option<SocketAddr>)>) -> io::Result<Self> {
+        let (bufs, addrs): (Vec<T>, Vec<Option<SockAddr>>) = msgs
+            .into_iter()
+            .map(|(buf, addr)| (buf, addr.map(Into::into)))
+            .unzip();
+        let mut iovecs: Vec<IoVecMeta> = bufs.iter().map(IoVecMeta::from).collect();
+        let hdrs = addrs
+            .iter()
+            .zip(iovecs.iter_mut())
+            .map(|(addr, iovec)| {
+                let mut hdr: libc::mmsghdr = unsafe { std::mem::zeroed() };
+                hdr.msg_hdr.msg_iov = iovec.write_iovec_ptr();
+                hdr.msg_hdr.msg_iovlen = iovec.write_iovec_len() as _;
+                if let Some(addr) = addr {
+                    hdr.msg_hdr.msg_name = addr.as_ptr() as *mut libc::c_void;
+                    hdr.msg_hdr.msg_namelen = addr.len();
+                }
+                hdr
+            })
+            .collect();
+        Op::submit_with(SendMmsg {
+            fd,
+            bufs,
+            addrs,
+            iovecs,
+            hdrs,
+        })
+    }
+
+    /// Returns the number of the messages sent, and the bytes sent of each message.
+    pub(crate) async fn wait(self) -> (io::Result<Vec<usize>>, Vec<T>) {
+        let complete = self.await;
+        let data = complete.data;
+        let res = complete.meta.result.map(|n| {
+            data.hdrs[..n.into_inner() as usize]
+                .iter()
+                .map(|hdr| hdr.msg_len as usize)
+                .collect()
+        });
+        (res, data.bufs)
+    }
+}
+
+impl<T: IoBuf> OpAble for SendMmsg<T> {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        unreachable!("sendmmsg is only submitted to the legacy driver")
+    }
+
+    #[inline]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        self.fd
+            .registered_index()
+            .map(|idx| (Direction::Write, idx))
+    }
+
+    fn legacy_call(&mut self) -> io::Result<MaybeFd> {
+        let fd = self.fd.as_raw_fd();
+        crate::syscall!(sendmmsg@NON_FD(
+            fd,
+            self.hdrs.as_mut_ptr(),
+            self.hdrs.len() as _,
+            libc::MSG_NOSIGNAL as _
+        ))
+    }
+}
+
+pub(crate) struct RecvMmsg<T> {
+    /// Holds a strong ref to the FD, preventing the file from being closed
+    /// while the operation is in-flight.
+    fd: SharedFd,
+    pub(crate) bufs: Vec<T>,
+    // The headers point to the addresses and iovecs, which must not move.
+    addrs: Vec<MaybeUninit<sockaddr_storage>>,
+    #[allow(unused)]
+    iovecs: Vec<IoVecMeta>,
+    hdrs: Vec<libc::mmsghdr>,
+}
+
+impl<T: IoBufMut> Op<RecvMmsg<T>> {
+    pub(crate) fn recv_mmsg(fd: SharedFd, mut bufs: Vec<T>) -> io::Result<Self> {
+        let mut addrs = vec![MaybeUninit::<sockaddr_storage>::uninit(); bufs.len()];
+        let mut iovecs: Vec<IoVecMeta> = bufs.iter_mut().map(IoVecMeta::from).collect();
+        let hdrs = addrs
+            .iter_mut()
+            .zip(iovecs.iter_mut())
+            .map(|(addr, iovec)| {
+                let mut hdr: libc::mmsghdr = unsafe { std::mem::zeroed() };
+                hdr.msg_hdr.msg_iov = iovec.write_iovec_ptr();
+                hdr.msg_hdr.msg_iovlen = iovec.write_iovec_len() as _;
+                hdr.msg_hdr.msg_name = addr.as_mut_ptr() as *mut libc::c_void;
+                hdr.msg_hdr.msg_namelen = std::mem::size_of::<sockaddr_storage>() as socklen_t;
+                hdr
+            })
+            .collect();
+        Op::submit_with(RecvMmsg {
+            fd,
+            bufs,
+            addrs,
+            iovecs,
+            hdrs,
+        })
+    }
+
+    /// Returns the length and the source address of each received message, in the order of
+    /// the buffers.
+    pub(crate) async fn wait(self) -> (io::Result<Vec<(usize, SocketAddr)>>, Vec<T>) {
+        let complete = self.await;
+        let mut data = complete.data;
+        let res = complete.meta.result.map(|n| {
+            let n = n.into_inner() as usize;
+            (0..n)
+                .map(|i| {
+                    let len = data.hdrs[i].msg_len as usize;
+                    // Safety: the kernel wrote the address and `len` bytes of the buffer.
+                    let addr =
+                        unsafe { super::recv::decode_socket_addr(data.addrs[i].assume_init_ref()) };
+                    unsafe { data.bufs[i].set_init(len) };
+                    (len, addr)
+                })
+                .collect()
+        });
+        (res, data.bufs)
+    }
+}
+
+impl<T: IoBufMut> OpAble for RecvMmsg<T> {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        unreachable!("recvmmsg is only submitted to the legacy driver")
+    }
+
+    #[inline]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        self.fd.registered_index().map(|idx| (Direction::Read, idx))
+    }
+
+    fn legacy_call(&mut self) -> io::Result<MaybeFd> {
+        let fd = self.fd.as_raw_fd();
+        crate::syscall!(recvmmsg@NON_FD(
+            fd,
+            self.hdrs.as_mut_ptr(),
+            self.hdrs.len() as _,
+            0,
+            std::ptr::null_mut()
+        ))
+    }
+}
@@ -6,6 +6,8 @@ use io_uring::{opcode, types};
 #[cfg(any(feature = "legacy", feature = "poll-io"))]
 use super::{driver::ready::Direction, MaybeFd};
 use super::{Op, OpAble};
+#[cfg(target_os = "linux")]
+use crate::driver::shared_fd::SharedFd;
 use crate::{driver::util::cstr, fs::OpenOptions};
 
 /// Open a file
@@ -15,25 +17,62 @@ pub(crate) struct Open {
     flags: i32,
     #[cfg(unix)]
     mode: libc::mode_t,
+    /// The directory which `path` is relative to, or the current directory if `None`.
+    #[cfg(target_os = "linux")]
+    dir: Option<SharedFd>,
+    /// `RESOLVE_*` flags of `openat2(2)`, which is used instead of `openat(2)` if not zero.
+    #[cfg(target_os = "linux")]
+    how: libc::open_how,
     #[cfg(windows)]
     opts: OpenOptions,
 }
 
 impl Op<Open> {
-    #[cfg(unix)]
+    #[cfg(all(unix, not(target_os = "linux")))]
     /// Submit a request to open a file.
     pub(crate) fn open<P: AsRef<Path>>(path: P, options: &OpenOptions) -> io::Result<Op<Open>> {
         // Here the path will be copied, so its safe.
         let path = cstr(path.as_ref())?;
-        let flags = libc::O_CLOEXEC
-            | options.access_mode()?
-            | options.creation_mode()?
-            | (options.custom_flags & !libc::O_ACCMODE);
+        let flags = open_flags(options)?;
         let mode = options.mode;
 
         Op::submit_with(Open { path, flags, mode })
     }
 
+    #[cfg(target_os = "linux")]
+    /// Submit a request to open a file.
+    pub(crate) fn open<P: AsRef<Path>>(path: P, options: &OpenOptions) -> io::Result<Op<Open>> {
+        Self::open_at(None, path, options)
+    }
+
+    #[cfg(target_os = "linux")]
+    /// Submit a request to open a file relative to the directory `dir`.
+    pub(crate) fn open_at<P: AsRef<Path>>(
+        dir: Option<&SharedFd>,
+        path: P,
+        options: &OpenOptions,
+    ) -> io::Result<Op<Open>> {
+        // Here the path will be copied, so its safe.
+        let path = cstr(path.as_ref())?;
+        let flags = open_flags(options)?;
+        let mode = options.mode;
+        let mut how: libc::open_how = unsafe { std::mem::zeroed() };
+        how.flags = flags as u64;
+        // `openat2(2)` rejects the mode without `O_CREAT` or `O_TMPFILE`.
+        if flags & libc::O_CREAT != 0 || flags & libc::O_TMPFILE == libc::O_TMPFILE {
+            how.mode = mode as u64;
+        }
+        how.resolve = options.resolve;
+
+        Op::submit_with(Open {
+            path,
+            flags,
+            mode,
+            dir: dir.cloned(),
+            how,
+        })
+    }
+
     #[cfg(windows)]
     /// Submit a request to open a file.
     pub(crate) fn open<P: AsRef<Path>>(path: P, options: &OpenOptions) -> io::Result<Op<Open>> {
@@ -47,13 +86,44 @@ impl Op<Open> {
     }
 }
 
+#[cfg(unix)]
+fn open_flags(options: &OpenOptions) -> io::Result<libc::c_int> {
+    #[allow(unused_mut)]
+    let mut flags = libc::O_CLOEXEC
+        | options.access_mode()?
+        | options.creation_mode()?
+        | (options.custom_flags & !libc::O_ACCMODE);
+    #[cfg(target_os = "linux")]
+    if options.direct {
+        flags |= libc::O_DIRECT;
+    }
+    Ok(flags)
+}
+
+#[cfg(target_os = "linux")]
+impl Open {
+    #[inline]
+    fn dirfd(&self) -> libc::c_int {
+        self.dir.as_ref().map_or(libc::AT_FDCWD, |dir| dir.raw_fd())
+    }
+}
+
 impl OpAble for Open {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    const URING_OPCODE: Option<u8> = Some(opcode::OpenAt::CODE);
+
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     const RET_IS_FD: bool = true;
 
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
-        opcode::OpenAt::new(types::Fd(libc::AT_FDCWD), self.path.as_c_str().as_ptr())
+        let dirfd = types::Fd(self.dirfd());
+        if self.how.resolve != 0 {
+            // `types::OpenHow` is a transparent wrapper of `open_how`.
+            let how = &self.how as *const libc::open_how as *const types::OpenHow;
+            return opcode::OpenAt2::new(dirfd, self.path.as_c_str().as_ptr(), how).build();
+        }
+        opcode::OpenAt::new(dirfd, self.path.as_c_str().as_ptr())
             .flags(self.flags)
             .mode(self.mode)
             .build()
@@ -65,7 +135,30 @@ impl OpAble for Open {
         None
     }
 
-    #[cfg(all(any(feature = "legacy", feature = "poll-io"), not(windows)))]
+    #[cfg(all(any(feature = "legacy", feature = "poll-io"), target_os = "linux"))]
+    fn legacy_call(&mut self) -> io::Result<MaybeFd> {
+        if self.how.resolve != 0 {
+            return crate::syscall!(syscall@FD(
+                libc::SYS_openat2,
+                self.dirfd(),
+                self.path.as_c_str().as_ptr(),
+                &self.how as *const libc::open_how,
+                std::mem::size_of::<libc::open_how>()
+            ));
+        }
+        crate::syscall!(openat@FD(
+            self.dirfd(),
+            self.path.as_c_str().as_ptr(),
+            self.flags,
+            self.mode as libc::c_int
+        ))
+    }
+
+    #[cfg(all(
+        any(feature = "legacy", feature = "poll-io"),
+        unix,
+        not(target_os = "linux")
+    ))]
     fn legacy_call(&mut self) -> io::Result<MaybeFd> {
         crate::syscall!(open@FD(
             self.path.as_c_str().as_ptr(),
@@ -43,17 +43,19 @@ impl Op<PollAdd> {
 impl OpAble for PollAdd {
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
-        use io_uring::{opcode, types};
-
-        opcode::PollAdd::new(
-            types::Fd(self.fd.raw_fd()),
-            if self.is_read {
-                libc::POLLIN as _
-            } else {
-                libc::POLLOUT as _
-            },
-        )
-        .build()
+        use io_uring::opcode;
+
+        self.fd.uring_entry(|fd| {
+            opcode::PollAdd::new(
+                fd,
+                if self.is_read {
+                    libc::POLLIN as _
+                } else {
+                    libc::POLLOUT as _
+                },
+            )
+            .build()
+        })
     }
 
     #[cfg(any(feature = "legacy", feature = "poll-io"))]
@@ -127,3 +129,57 @@ impl OpAble for PollAdd {
         Ok(MaybeFd::new_non_fd(1))
     }
 }
+
+/// Wait for the pending error of the fd, e.g. the notifications in the error queue.
+#[cfg(target_os = "linux")]
+pub(crate) struct PollError {
+    fd: SharedFd,
+}
+
+#[cfg(target_os = "linux")]
+impl Op<PollError> {
+    pub(crate) fn poll_error(fd: &SharedFd) -> io::Result<Op<PollError>> {
+        Op::submit_with(PollError { fd: fd.clone() })
+    }
+
+    pub(crate) async fn wait(self) -> io::Result<()> {
+        let complete = self.await;
+        complete.meta.result.map(|_| ())
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl OpAble for PollError {
+    #[cfg(feature = "iouring")]
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        use io_uring::opcode;
+
+        self.fd
+            .uring_entry(|fd| opcode::PollAdd::new(fd, libc::POLLERR as _).build())
+    }
+
+    /// A lone `EPOLLERR` is reported as write closed, so it waits for the write readiness.
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    #[inline]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        self.fd
+            .registered_index()
+            .map(|idx| (Direction::Write, idx))
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    fn legacy_call(&mut self) -> io::Result<MaybeFd> {
+        use std::os::fd::AsRawFd;
+
+        let mut pollfd = libc::pollfd {
+            fd: self.fd.as_raw_fd(),
+            events: 0,
+            revents: 0,
+        };
+        crate::syscall!(poll@RAW(&mut pollfd as *mut _, 1, 0))?;
+        if pollfd.revents & libc::POLLERR == 0 {
+            return Err(io::ErrorKind::WouldBlock.into());
+        }
+        Ok(MaybeFd::new_non_fd(1))
+    }
+}
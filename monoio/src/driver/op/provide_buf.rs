@@ -0,0 +1,86 @@
+use std::io;
+
+use io_uring::opcode;
+
+#[cfg(any(feature = "legacy", feature = "poll-io"))]
+use super::{driver::ready::Direction, MaybeFd};
+use super::{Op, OpAble};
+
+/// Provide buffers to the kernel. It is only available with uring driver.
+pub(crate) struct ProvideBuffers {
+    addr: *mut u8,
+    len: i32,
+    nbufs: u16,
+    bgid: u16,
+    bid: u16,
+}
+
+impl Op<ProvideBuffers> {
+    /// Provide `nbufs` buffers of `len` bytes starting at `addr`, with ids starting from `bid`.
+    ///
+    /// # Safety
+    /// The memory must be valid until the buffers are consumed or removed.
+    pub(crate) unsafe fn provide_buffers(
+        addr: *mut u8,
+        len: i32,
+        nbufs: u16,
+        bgid: u16,
+        bid: u16,
+    ) -> io::Result<Self> {
+        Op::try_submit_with(ProvideBuffers {
+            addr,
+            len,
+            nbufs,
+            bgid,
+            bid,
+        })
+    }
+}
+
+impl OpAble for ProvideBuffers {
+    const SKIP_CANCEL: bool = true;
+
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        opcode::ProvideBuffers::new(self.addr, self.len, self.nbufs, self.bgid, self.bid).build()
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        None
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    fn legacy_call(&mut self) -> io::Result<MaybeFd> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
+
+/// Remove buffers from a buffer group. It is only available with uring driver.
+pub(crate) struct RemoveBuffers {
+    nbufs: u16,
+    bgid: u16,
+}
+
+impl Op<RemoveBuffers> {
+    pub(crate) fn remove_buffers(nbufs: u16, bgid: u16) -> io::Result<Self> {
+        Op::try_submit_with(RemoveBuffers { nbufs, bgid })
+    }
+}
+
+impl OpAble for RemoveBuffers {
+    const SKIP_CANCEL: bool = true;
+
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        opcode::RemoveBuffers::new(self.nbufs, self.bgid).build()
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        None
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    fn legacy_call(&mut self) -> io::Result<MaybeFd> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
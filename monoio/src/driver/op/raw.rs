@@ -0,0 +1,95 @@
+use std::{
+    future::Future,
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+#[cfg(any(feature = "legacy", feature = "poll-io"))]
+use super::{driver::ready::Direction, MaybeFd};
+use super::{Op, OpAble};
+
+/// An operation submitted with a custom SQE.
+pub(crate) struct Raw<T> {
+    entry: Option<io_uring::squeue::Entry>,
+    data: T,
+}
+
+impl<T> OpAble for Raw<T> {
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        self.entry.take().expect("raw entry is built twice")
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    #[inline]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        None
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    fn legacy_call(&mut self) -> io::Result<MaybeFd> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
+
+/// Submit a custom SQE to the io_uring driver of current runtime, for the opcodes monoio has not
+/// wrapped(e.g. `URING_CMD` for ublk or NVMe passthrough).
+///
+/// `data` is held by the operation until it completes, even if the returned future is dropped
+/// before, so it can own the resources referred by the SQE, like buffers. It is returned back
+/// with the completion. The `user_data` of the SQE is overwritten by the driver.
+///
+/// Returns `ErrorKind::Unsupported` if current runtime is not on io_uring driver.
+///
+/// # Safety
+///
+/// The SQE must be valid, and the memory it refers to must stay valid until the operation
+/// completes. `data` is moved, so the SQE must only refer to the memory which does not move with
+/// it(e.g. the heap memory of a `Vec` or `Box`). The SQE must not be linked with
+/// `IOSQE_IO_LINK`, and must produce exactly one completion.
+///
+/// # Panics
+///
+/// Panics if called outside of a monoio runtime.
+pub unsafe fn submit_raw<T: Unpin + 'static>(
+    entry: io_uring::squeue::Entry,
+    data: T,
+) -> io::Result<RawOp<T>> {
+    if super::is_legacy() {
+        return Err(io::ErrorKind::Unsupported.into());
+    }
+    let op = Op::submit_with(Raw {
+        entry: Some(entry),
+        data,
+    })?;
+    Ok(RawOp { op })
+}
+
+/// Future of the operation submitted with [`submit_raw`].
+pub struct RawOp<T: 'static> {
+    op: Op<Raw<T>>,
+}
+
+/// Completion of the operation submitted with [`submit_raw`].
+#[derive(Debug)]
+pub struct RawCompletion<T> {
+    /// The data passed to [`submit_raw`].
+    pub data: T,
+    /// The result of the CQE, as an error if it is negative.
+    pub result: io::Result<u32>,
+    /// The flags of the CQE.
+    pub flags: u32,
+}
+
+impl<T: Unpin + 'static> Future for RawOp<T> {
+    type Output = RawCompletion<T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let complete = ready!(Pin::new(&mut self.op).poll(cx));
+        Poll::Ready(RawCompletion {
+            data: complete.data.data,
+            result: complete.meta.result.map(|n| n.into_inner()),
+            flags: complete.meta.flags,
+        })
+    }
+}
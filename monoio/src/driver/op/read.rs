@@ -5,13 +5,13 @@ use std::os::unix::prelude::AsRawFd;
 #[cfg(any(feature = "legacy", feature = "poll-io"))]
 pub(crate) use impls::*;
 #[cfg(all(target_os = "linux", feature = "iouring"))]
-use io_uring::{opcode, types};
+use io_uring::opcode;
 
 use super::{super::shared_fd::SharedFd, Op, OpAble};
 #[cfg(any(feature = "legacy", feature = "poll-io"))]
 use super::{driver::ready::Direction, MaybeFd};
 use crate::{
-    buf::{IoBufMut, IoVecBufMut},
+    buf::{FixedBuf, IoBufMut, IoVecBufMut},
     BufResult,
 };
 
@@ -65,18 +65,19 @@ impl<T: IoBufMut> Op<Read<T>> {
 }
 
 impl<T: IoBufMut> OpAble for Read<T> {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    const URING_OPCODE: Option<u8> = Some(opcode::Read::CODE);
+
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
         // Refers to https://docs.rs/io-uring/latest/io_uring/opcode/struct.Read.html.
         // If `offset` is set to `-1`, the offset will use (and advance) the file position, like
         // the read(2) syscall.
-        opcode::Read::new(
-            types::Fd(self.fd.raw_fd()),
-            self.buf.write_ptr(),
-            self.buf.bytes_total() as _,
-        )
-        .offset(-1i64 as u64)
-        .build()
+        self.fd.uring_entry(|fd| {
+            opcode::Read::new(fd, self.buf.write_ptr(), self.buf.bytes_total() as _)
+                .offset(-1i64 as u64)
+                .build()
+        })
     }
 
     #[cfg(any(feature = "legacy", feature = "poll-io"))]
@@ -106,6 +107,13 @@ pub(crate) struct ReadAt<T> {
     offset: u64,
 }
 
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+impl<T> ReadAt<T> {
+    pub(crate) fn new(fd: SharedFd, buf: T, offset: u64) -> Self {
+        ReadAt { fd, buf, offset }
+    }
+}
+
 impl<T: IoBufMut> Op<ReadAt<T>> {
     pub(crate) fn read_at(fd: SharedFd, buf: T, offset: u64) -> io::Result<Op<ReadAt<T>>> {
         Op::submit_with(ReadAt { fd, offset, buf })
@@ -113,15 +121,16 @@ impl<T: IoBufMut> Op<ReadAt<T>> {
 }
 
 impl<T: IoBufMut> OpAble for ReadAt<T> {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    const URING_OPCODE: Option<u8> = Some(opcode::Read::CODE);
+
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
-        opcode::Read::new(
-            types::Fd(self.fd.raw_fd()),
-            self.buf.write_ptr(),
-            self.buf.bytes_total() as _,
-        )
-        .offset(self.offset)
-        .build()
+        self.fd.uring_entry(|fd| {
+            opcode::Read::new(fd, self.buf.write_ptr(), self.buf.bytes_total() as _)
+                .offset(self.offset)
+                .build()
+        })
     }
 
     #[cfg(any(feature = "legacy", feature = "poll-io"))]
@@ -167,9 +176,11 @@ impl<T: IoVecBufMut> OpAble for ReadVec<T> {
         // Refersto https://docs.rs/io-uring/latest/io_uring/opcode/struct.Readv.html.
         // If `offset` is set to `-1`, the offset will use (and advance) the file position, like
         // the readv(2) syscall.
-        opcode::Readv::new(types::Fd(self.fd.raw_fd()), ptr, len)
-            .offset(-1i64 as u64)
-            .build()
+        self.fd.uring_entry(|fd| {
+            opcode::Readv::new(fd, ptr, len)
+                .offset(-1i64 as u64)
+                .build()
+        })
     }
 
     #[cfg(any(feature = "legacy", feature = "poll-io"))]
@@ -245,9 +256,8 @@ impl<T: IoVecBufMut> OpAble for ReadVecAt<T> {
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
         let ptr = self.buf_vec.write_iovec_ptr() as _;
         let len = self.buf_vec.write_iovec_len() as _;
-        opcode::Readv::new(types::Fd(self.fd.raw_fd()), ptr, len)
-            .offset(self.offset)
-            .build()
+        self.fd
+            .uring_entry(|fd| opcode::Readv::new(fd, ptr, len).offset(self.offset).build())
     }
 
     #[cfg(any(feature = "legacy", feature = "poll-io"))]
@@ -308,6 +318,74 @@ impl<T: IoVecBufMut> OpAble for ReadVecAt<T> {
     }
 }
 
+pub(crate) struct ReadFixed {
+    /// Holds a strong ref to the FD, preventing the file from being closed
+    /// while the operation is in-flight.
+    fd: SharedFd,
+    /// Reference to the in-flight buffer.
+    pub(crate) buf: FixedBuf,
+    /// If `offset` is set to `-1`, the offset will use (and advance) the file position.
+    offset: u64,
+}
+
+impl Op<ReadFixed> {
+    pub(crate) fn read_fixed(fd: SharedFd, buf: FixedBuf, offset: u64) -> io::Result<Self> {
+        Op::submit_with(ReadFixed { fd, buf, offset })
+    }
+
+    pub(crate) async fn result(self) -> BufResult<usize, FixedBuf> {
+        let complete = self.await;
+        let res = complete.meta.result.map(|v| v.into_inner() as usize);
+        let mut buf = complete.data.buf;
+        if let Ok(read_len) = res {
+            // Safety: the kernel wrote `n` bytes to the buffer
+            unsafe { buf.set_init(read_len) };
+        }
+        (res, buf)
+    }
+}
+
+impl OpAble for ReadFixed {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        let ptr = self.buf.write_ptr();
+        let len = self.buf.bytes_total() as _;
+        let (offset, index) = (self.offset, self.buf.buf_index());
+        if self.buf.is_registered() {
+            self.fd.uring_entry(|fd| {
+                opcode::ReadFixed::new(fd, ptr, len, index)
+                    .offset(offset)
+                    .build()
+            })
+        } else {
+            self.fd
+                .uring_entry(|fd| opcode::Read::new(fd, ptr, len).offset(offset).build())
+        }
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    #[inline]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        self.fd.registered_index().map(|idx| (Direction::Read, idx))
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    fn legacy_call(&mut self) -> io::Result<MaybeFd> {
+        #[cfg(unix)]
+        let fd = self.fd.as_raw_fd();
+        #[cfg(windows)]
+        let fd = self.fd.raw_handle() as _;
+
+        let buf = self.buf.write_ptr();
+        let len = self.buf.bytes_total();
+        if self.offset == u64::MAX {
+            read(fd, buf, len)
+        } else {
+            read_at(fd, buf, len, self.offset)
+        }
+    }
+}
+
 #[cfg(all(any(feature = "legacy", feature = "poll-io"), unix))]
 pub(crate) mod impls {
     use libc::iovec;
@@ -321,8 +399,8 @@ pub(crate) mod impls {
 
     /// A wrapper of [`libc::pread`]
     pub(crate) fn read_at(fd: i32, buf: *mut u8, len: usize, offset: u64) -> io::Result<MaybeFd> {
-        let offset = libc::off_t::try_from(offset)
-            .map_err(|_| io::Error::new(io::ErrorKind::Other, "offset too big"))?;
+        let offset =
+            libc::off_t::try_from(offset).map_err(|_| io::Error::other("offset too big"))?;
 
         crate::syscall!(pread@NON_FD(fd, buf as _, len, offset))
     }
@@ -339,8 +417,8 @@ pub(crate) mod impls {
         len: usize,
         offset: u64,
     ) -> io::Result<MaybeFd> {
-        let offset = libc::off_t::try_from(offset)
-            .map_err(|_| io::Error::new(io::ErrorKind::Other, "offset too big"))?;
+        let offset =
+            libc::off_t::try_from(offset).map_err(|_| io::Error::other("offset too big"))?;
 
         crate::syscall!(preadv@NON_FD(fd, buf_vec as _, len as _, offset))
     }
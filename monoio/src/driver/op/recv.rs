@@ -7,10 +7,10 @@ use std::{
 };
 
 #[cfg(all(target_os = "linux", feature = "iouring"))]
-use io_uring::{opcode, types};
+use io_uring::opcode;
 #[cfg(unix)]
 use {
-    crate::net::unix::SocketAddr as UnixSocketAddr,
+    crate::net::{cmsg::CmsgBuf, unix::SocketAddr as UnixSocketAddr},
     libc::{sockaddr_in, sockaddr_in6, sockaddr_storage, socklen_t, AF_INET, AF_INET6},
 };
 #[cfg(all(windows, any(feature = "legacy", feature = "poll-io")))]
@@ -31,12 +31,17 @@ use {
         },
     },
 };
+#[cfg(all(windows, feature = "iocp"))]
+use {
+    std::os::windows::io::RawSocket,
+    windows_sys::Win32::Networking::WinSock::{WSARecv, SOCKET_ERROR as WSA_ERROR, WSABUF},
+};
 
 use super::{super::shared_fd::SharedFd, Op, OpAble};
 #[cfg(any(feature = "legacy", feature = "poll-io"))]
 use super::{driver::ready::Direction, MaybeFd};
 use crate::{
-    buf::{IoBufMut, IoVecBufMut, IoVecMeta, MsgMeta},
+    buf::{BufGroup, IoBufMut, IoVecBufMut, IoVecMeta, MsgMeta, ProvidedBuf},
     BufResult,
 };
 
@@ -48,11 +53,17 @@ pub(crate) struct Recv<T> {
 
     /// Reference to the in-flight buffer.
     pub(crate) buf: T,
+    flags: i32,
 }
 
 impl<T: IoBufMut> Op<Recv<T>> {
     pub(crate) fn recv(fd: SharedFd, buf: T) -> io::Result<Self> {
-        Op::submit_with(Recv { fd, buf })
+        Self::recv_with_flags(fd, buf, 0)
+    }
+
+    /// Receive with the flags like `MSG_PEEK`.
+    pub(crate) fn recv_with_flags(fd: SharedFd, buf: T, flags: i32) -> io::Result<Self> {
+        Op::submit_with(Recv { fd, buf, flags })
     }
 
     #[allow(unused)]
@@ -60,6 +71,7 @@ impl<T: IoBufMut> Op<Recv<T>> {
         Recv {
             fd: fd.clone(),
             buf,
+            flags: 0,
         }
     }
 
@@ -79,14 +91,16 @@ impl<T: IoBufMut> Op<Recv<T>> {
 }
 
 impl<T: IoBufMut> OpAble for Recv<T> {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    const URING_OPCODE: Option<u8> = Some(opcode::Recv::CODE);
+
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
-        opcode::Recv::new(
-            types::Fd(self.fd.raw_fd()),
-            self.buf.write_ptr(),
-            self.buf.bytes_total() as _,
-        )
-        .build()
+        self.fd.uring_entry(|fd| {
+            opcode::Recv::new(fd, self.buf.write_ptr(), self.buf.bytes_total() as _)
+                .flags(self.flags)
+                .build()
+        })
     }
 
     #[cfg(any(feature = "legacy", feature = "poll-io"))]
@@ -102,7 +116,7 @@ impl<T: IoBufMut> OpAble for Recv<T> {
             fd,
             self.buf.write_ptr() as _,
             self.buf.bytes_total().min(u32::MAX as usize),
-            0
+            self.flags
         ))
     }
 
@@ -114,12 +128,38 @@ impl<T: IoBufMut> OpAble for Recv<T> {
                 fd as _,
                 self.buf.write_ptr(),
                 self.buf.bytes_total().min(i32::MAX as usize) as _,
-                0
+                self.flags as _
             ),
             PartialOrd::lt,
             0
         )
     }
+
+    #[cfg(all(windows, feature = "iocp"))]
+    #[inline]
+    fn iocp_socket(&self) -> Option<RawSocket> {
+        self.fd.iocp_socket()
+    }
+
+    #[cfg(all(windows, feature = "iocp"))]
+    unsafe fn iocp_call(&mut self, overlapped: *mut OVERLAPPED) -> io::Result<()> {
+        // The WSABUF is only read when the operation starts.
+        let buf = WSABUF {
+            len: self.buf.bytes_total().min(u32::MAX as usize) as _,
+            buf: self.buf.write_ptr(),
+        };
+        let mut flags = self.flags as u32;
+        let ret = WSARecv(
+            self.fd.raw_socket() as _,
+            &buf,
+            1,
+            std::ptr::null_mut(),
+            &mut flags,
+            overlapped,
+            None,
+        );
+        crate::driver::iocp::check_started(ret == WSA_ERROR)
+    }
 }
 
 pub(crate) struct RecvMsg<T> {
@@ -132,10 +172,76 @@ pub(crate) struct RecvMsg<T> {
     pub(crate) buf: T,
     /// For multiple message recv in the future
     pub(crate) info: Box<(MaybeUninit<sockaddr_storage>, IoVecMeta, MsgMeta)>,
+    /// Buffer of the ancillary data, empty if it is not received.
+    #[cfg(unix)]
+    control: CmsgBuf,
+    flags: i32,
 }
 
 impl<T: IoBufMut> Op<RecvMsg<T>> {
-    pub(crate) fn recv_msg(fd: SharedFd, mut buf: T) -> io::Result<Self> {
+    pub(crate) fn recv_msg(fd: SharedFd, buf: T) -> io::Result<Self> {
+        Self::recv_msg_with_flags(fd, buf, 0)
+    }
+
+    /// Receive a message with the flags like `MSG_PEEK`.
+    pub(crate) fn recv_msg_with_flags(fd: SharedFd, buf: T, flags: i32) -> io::Result<Self> {
+        Op::submit_with(RecvMsg::new(
+            fd,
+            buf,
+            #[cfg(unix)]
+            CmsgBuf::default(),
+            flags,
+        ))
+    }
+
+    /// Receive a message with the ancillary data into `control`, whose length is the
+    /// capacity of the ancillary data.
+    #[cfg(unix)]
+    pub(crate) fn recv_msg_with_control(
+        fd: SharedFd,
+        buf: T,
+        control: CmsgBuf,
+    ) -> io::Result<Self> {
+        Op::submit_with(RecvMsg::new(fd, buf, control, 0))
+    }
+
+    pub(crate) async fn wait(self) -> BufResult<(usize, SocketAddr), T> {
+        let complete = self.await;
+        let res = complete.meta.result.map(|v| v.into_inner() as _);
+        let mut buf = complete.data.buf;
+
+        let res = res.map(|n| {
+            let addr = unsafe { decode_socket_addr(complete.data.info.0.assume_init_ref()) };
+            // Safety: the kernel wrote `n` bytes to the buffer.
+            unsafe { buf.set_init(n) };
+            (n, addr)
+        });
+        (res, buf)
+    }
+
+    /// Wait for the message, returning the ancillary data and the flags of the message too.
+    #[cfg(unix)]
+    pub(crate) async fn wait_with_control(
+        self,
+    ) -> BufResult<(usize, SocketAddr, CmsgBuf, libc::c_int), T> {
+        let complete = self.await;
+        let res = complete.meta.result.map(|v| v.into_inner() as _);
+        let mut data = complete.data;
+
+        let res = res.map(|n| {
+            let addr = unsafe { decode_socket_addr(data.info.0.assume_init_ref()) };
+            // Safety: the kernel wrote `n` bytes to the buffer.
+            unsafe { data.buf.set_init(n) };
+            let mut control = std::mem::take(&mut data.control);
+            control.truncate(data.info.2.msg_controllen as _);
+            (n, addr, control, data.info.2.msg_flags)
+        });
+        (res, data.buf)
+    }
+}
+
+impl<T: IoBufMut> RecvMsg<T> {
+    fn new(fd: SharedFd, mut buf: T, #[cfg(unix)] mut control: CmsgBuf, flags: i32) -> Self {
         let mut info: Box<(MaybeUninit<sockaddr_storage>, IoVecMeta, MsgMeta)> =
             Box::new((MaybeUninit::uninit(), IoVecMeta::from(&mut buf), unsafe {
                 std::mem::zeroed()
@@ -147,6 +253,10 @@ impl<T: IoBufMut> Op<RecvMsg<T>> {
             info.2.msg_iovlen = info.1.write_iovec_len() as _;
             info.2.msg_name = &mut info.0 as *mut _ as *mut libc::c_void;
             info.2.msg_namelen = std::mem::size_of::<sockaddr_storage>() as socklen_t;
+            if !control.is_empty() {
+                info.2.msg_control = control.as_mut_ptr();
+                info.2.msg_controllen = control.len() as _;
+            }
         }
         #[cfg(windows)]
         {
@@ -154,59 +264,55 @@ impl<T: IoBufMut> Op<RecvMsg<T>> {
             info.2.dwBufferCount = info.1.write_wsabuf_len() as _;
             info.2.name = &mut info.0 as *mut _ as *mut SOCKADDR;
             info.2.namelen = std::mem::size_of::<sockaddr_storage>() as _;
+            info.2.dwFlags = flags as _;
         }
 
-        Op::submit_with(RecvMsg { fd, buf, info })
+        RecvMsg {
+            fd,
+            buf,
+            info,
+            #[cfg(unix)]
+            control,
+            flags,
+        }
     }
+}
 
-    pub(crate) async fn wait(self) -> BufResult<(usize, SocketAddr), T> {
-        let complete = self.await;
-        let res = complete.meta.result.map(|v| v.into_inner() as _);
-        let mut buf = complete.data.buf;
-
-        let res = res.map(|n| {
-            let storage = unsafe { complete.data.info.0.assume_init() };
-
-            let addr = unsafe {
-                match storage.ss_family as _ {
-                    AF_INET => {
-                        // Safety: if the ss_family field is AF_INET then storage must be a
-                        // sockaddr_in.
-                        let addr: &sockaddr_in = transmute(&storage);
-                        #[cfg(unix)]
-                        let ip = Ipv4Addr::from(addr.sin_addr.s_addr.to_ne_bytes());
-                        #[cfg(windows)]
-                        let ip = Ipv4Addr::from(addr.sin_addr.S_un.S_addr.to_ne_bytes());
-                        let port = u16::from_be(addr.sin_port);
-                        SocketAddr::V4(SocketAddrV4::new(ip, port))
-                    }
-                    AF_INET6 => {
-                        // Safety: if the ss_family field is AF_INET6 then storage must be a
-                        // sockaddr_in6.
-                        let addr: &sockaddr_in6 = transmute(&storage);
-                        #[cfg(unix)]
-                        let ip = Ipv6Addr::from(addr.sin6_addr.s6_addr);
-                        #[cfg(windows)]
-                        let ip = Ipv6Addr::from(addr.sin6_addr.u.Byte);
-                        let port = u16::from_be(addr.sin6_port);
-                        #[cfg(unix)]
-                        let scope_id = addr.sin6_scope_id;
-                        #[cfg(windows)]
-                        let scope_id = addr.Anonymous.sin6_scope_id;
-                        SocketAddr::V6(SocketAddrV6::new(ip, port, addr.sin6_flowinfo, scope_id))
-                    }
-                    _ => {
-                        unreachable!()
-                    }
-                }
-            };
-
-            // Safety: the kernel wrote `n` bytes to the buffer.
-            unsafe { buf.set_init(n) };
-
-            (n, addr)
-        });
-        (res, buf)
+/// Decode the address written by the kernel.
+///
+/// # Safety
+/// The storage must hold a `sockaddr_in` or `sockaddr_in6`.
+pub(super) unsafe fn decode_socket_addr(storage: &sockaddr_storage) -> SocketAddr {
+    match storage.ss_family as _ {
+        AF_INET => {
+            // Safety: if the ss_family field is AF_INET then storage must be a
+            // sockaddr_in.
+            let addr: &sockaddr_in = transmute(storage);
+            #[cfg(unix)]
+            let ip = Ipv4Addr::from(addr.sin_addr.s_addr.to_ne_bytes());
+            #[cfg(windows)]
+            let ip = Ipv4Addr::from(addr.sin_addr.S_un.S_addr.to_ne_bytes());
+            let port = u16::from_be(addr.sin_port);
+            SocketAddr::V4(SocketAddrV4::new(ip, port))
+        }
+        AF_INET6 => {
+            // Safety: if the ss_family field is AF_INET6 then storage must be a
+            // sockaddr_in6.
+            let addr: &sockaddr_in6 = transmute(storage);
+            #[cfg(unix)]
+            let ip = Ipv6Addr::from(addr.sin6_addr.s6_addr);
+            #[cfg(windows)]
+            let ip = Ipv6Addr::from(addr.sin6_addr.u.Byte);
+            let port = u16::from_be(addr.sin6_port);
+            #[cfg(unix)]
+            let scope_id = addr.sin6_scope_id;
+            #[cfg(windows)]
+            let scope_id = addr.Anonymous.sin6_scope_id;
+            SocketAddr::V6(SocketAddrV6::new(ip, port, addr.sin6_flowinfo, scope_id))
+        }
+        _ => {
+            unreachable!()
+        }
     }
 }
 
@@ -223,9 +329,16 @@ static WSA_RECV_MSG: std::sync::OnceLock<
 > = std::sync::OnceLock::new();
 
 impl<T: IoBufMut> OpAble for RecvMsg<T> {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    const URING_OPCODE: Option<u8> = Some(opcode::RecvMsg::CODE);
+
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
-        opcode::RecvMsg::new(types::Fd(self.fd.raw_fd()), &mut *self.info.2).build()
+        self.fd.uring_entry(|fd| {
+            opcode::RecvMsg::new(fd, &mut *self.info.2)
+                .flags(self.flags as _)
+                .build()
+        })
     }
 
     #[cfg(any(feature = "legacy", feature = "poll-io"))]
@@ -237,7 +350,7 @@ impl<T: IoBufMut> OpAble for RecvMsg<T> {
     #[cfg(all(any(feature = "legacy", feature = "poll-io"), unix))]
     fn legacy_call(&mut self) -> io::Result<MaybeFd> {
         let fd = self.fd.as_raw_fd();
-        crate::syscall!(recvmsg@NON_FD(fd, &mut *self.info.2, 0))
+        crate::syscall!(recvmsg@NON_FD(fd, &mut *self.info.2, self.flags))
     }
 
     #[cfg(all(any(feature = "legacy", feature = "poll-io"), windows))]
@@ -296,11 +409,25 @@ pub(crate) struct RecvMsgUnix<T> {
     pub(crate) buf: T,
     /// For multiple message recv in the future
     pub(crate) info: Box<(MaybeUninit<sockaddr_storage>, IoVecMeta, libc::msghdr)>,
+    /// Buffer of the ancillary data, empty if it is not received.
+    control: CmsgBuf,
+    flags: i32,
 }
 
 #[cfg(unix)]
 impl<T: IoBufMut> Op<RecvMsgUnix<T>> {
-    pub(crate) fn recv_msg_unix(fd: SharedFd, mut buf: T) -> io::Result<Self> {
+    pub(crate) fn recv_msg_unix(fd: SharedFd, buf: T) -> io::Result<Self> {
+        Self::recv_msg_unix_with_control(fd, buf, CmsgBuf::default())
+    }
+
+    /// Receive a message with the ancillary data into `control`, whose length is the
+    /// capacity of the ancillary data. The received file descriptors are close-on-exec
+    /// where it is supported.
+    pub(crate) fn recv_msg_unix_with_control(
+        fd: SharedFd,
+        mut buf: T,
+        mut control: CmsgBuf,
+    ) -> io::Result<Self> {
         let mut info: Box<(MaybeUninit<sockaddr_storage>, IoVecMeta, libc::msghdr)> =
             Box::new((MaybeUninit::uninit(), IoVecMeta::from(&mut buf), unsafe {
                 std::mem::zeroed()
@@ -310,8 +437,26 @@ impl<T: IoBufMut> Op<RecvMsgUnix<T>> {
         info.2.msg_iovlen = info.1.write_iovec_len() as _;
         info.2.msg_name = &mut info.0 as *mut _ as *mut libc::c_void;
         info.2.msg_namelen = std::mem::size_of::<sockaddr_storage>() as socklen_t;
+        if !control.is_empty() {
+            info.2.msg_control = control.as_mut_ptr();
+            info.2.msg_controllen = control.len() as _;
+        }
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        let flags = if control.is_empty() {
+            0
+        } else {
+            libc::MSG_CMSG_CLOEXEC
+        };
+        #[cfg(not(any(target_os = "linux", target_os = "android")))]
+        let flags = 0;
 
-        Op::submit_with(RecvMsgUnix { fd, buf, info })
+        Op::submit_with(RecvMsgUnix {
+            fd,
+            buf,
+            info,
+            control,
+            flags,
+        })
     }
 
     pub(crate) async fn wait(self) -> BufResult<(usize, UnixSocketAddr), T> {
@@ -337,13 +482,42 @@ impl<T: IoBufMut> Op<RecvMsgUnix<T>> {
         });
         (res, buf)
     }
+
+    /// Wait for the message, returning the ancillary data and the flags of the message too.
+    pub(crate) async fn wait_with_control(
+        self,
+    ) -> BufResult<(usize, UnixSocketAddr, CmsgBuf, libc::c_int), T> {
+        let complete = self.await;
+        let res = complete.meta.result.map(|v| v.into_inner() as _);
+        let mut data = complete.data;
+
+        let res = res.map(|n| {
+            let addr = unsafe {
+                let addr: &libc::sockaddr_un = transmute(data.info.0.assume_init_ref());
+                UnixSocketAddr::from_parts(*addr, data.info.2.msg_namelen)
+            };
+            // Safety: the kernel wrote `n` bytes to the buffer.
+            unsafe { data.buf.set_init(n) };
+            let mut control = std::mem::take(&mut data.control);
+            control.truncate(data.info.2.msg_controllen as _);
+            (n, addr, control, data.info.2.msg_flags)
+        });
+        (res, data.buf)
+    }
 }
 
 #[cfg(unix)]
 impl<T: IoBufMut> OpAble for RecvMsgUnix<T> {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    const URING_OPCODE: Option<u8> = Some(opcode::RecvMsg::CODE);
+
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
-        opcode::RecvMsg::new(types::Fd(self.fd.raw_fd()), &mut self.info.2 as *mut _).build()
+        self.fd.uring_entry(|fd| {
+            opcode::RecvMsg::new(fd, &mut self.info.2 as *mut _)
+                .flags(self.flags as _)
+                .build()
+        })
     }
 
     #[cfg(any(feature = "legacy", feature = "poll-io"))]
@@ -355,6 +529,117 @@ impl<T: IoBufMut> OpAble for RecvMsgUnix<T> {
     #[cfg(any(feature = "legacy", feature = "poll-io"))]
     fn legacy_call(&mut self) -> io::Result<MaybeFd> {
         let fd = self.fd.as_raw_fd();
-        crate::syscall!(recvmsg@NON_FD(fd, &mut self.info.2 as *mut _, 0))
+        crate::syscall!(recvmsg@NON_FD(fd, &mut self.info.2 as *mut _, self.flags))
+    }
+}
+
+/// Multishot recv with provided buffers. One submission keeps receiving data into buffers
+/// selected from the group until it is canceled, failed, or reaches EOF.
+pub(crate) struct RecvMulti {
+    /// Holds a strong ref to the FD, preventing the file from being closed
+    /// while the operation is in-flight.
+    #[allow(unused)]
+    fd: SharedFd,
+
+    /// Buffer group which the buffers are selected from. Holding it also makes sure the memory
+    /// is valid while the operation is in-flight.
+    group: BufGroup,
+
+    /// Buffer selected by legacy driver for the last successful recv.
+    #[allow(unused)]
+    bid: Option<u16>,
+}
+
+impl Op<RecvMulti> {
+    pub(crate) fn recv_multi(fd: SharedFd, group: BufGroup) -> io::Result<Self> {
+        Op::submit_with(RecvMulti {
+            fd,
+            group,
+            bid: None,
+        })
+    }
+
+    /// Poll the next filled buffer. Returns `None` on EOF or when the operation has terminated.
+    pub(crate) fn poll_next_buf(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<io::Result<ProvidedBuf>>> {
+        let meta = match ready!(self.poll_next_multishot(cx)) {
+            Some(meta) => meta,
+            None => return std::task::Poll::Ready(None),
+        };
+        let data = self.data.as_mut().expect("unexpected operation state");
+        #[allow(unused_mut)]
+        let mut bid = data.bid.take();
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        if bid.is_none() {
+            bid = io_uring::cqueue::buffer_select(meta.flags);
+        }
+
+        let n = match meta.result {
+            Ok(n) => n.into_inner() as usize,
+            Err(e) => return std::task::Poll::Ready(Some(Err(e))),
+        };
+        // Safety: the buffer is selected from the group and filled with `n` bytes.
+        let buf = bid.map(|bid| unsafe { data.group.selected(bid, n) });
+        if n == 0 {
+            // Reach EOF, the buffer(if any) is given back when dropped.
+            return std::task::Poll::Ready(None);
+        }
+        match buf {
+            Some(buf) => std::task::Poll::Ready(Some(Ok(buf))),
+            None => std::task::Poll::Ready(Some(Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "no buffer selected",
+            )))),
+        }
+    }
+}
+
+impl OpAble for RecvMulti {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        self.fd
+            .uring_entry(|fd| opcode::RecvMulti::new(fd, self.group.bgid()).build())
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    #[inline]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        self.fd.registered_index().map(|idx| (Direction::Read, idx))
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    fn legacy_call(&mut self) -> io::Result<MaybeFd> {
+        let (bid, ptr) = self
+            .group
+            .take_free()
+            .ok_or_else(|| io::Error::from_raw_os_error(libc::ENOBUFS))?;
+        let len = self.group.buf_len();
+
+        #[cfg(unix)]
+        let res = crate::syscall!(recv@NON_FD(self.fd.as_raw_fd(), ptr as _, len, 0));
+        #[cfg(windows)]
+        let res = crate::syscall!(
+            recv@NON_FD(
+                self.fd.as_raw_socket() as _,
+                ptr,
+                len.min(i32::MAX as usize) as _,
+                0
+            ),
+            PartialOrd::lt,
+            0
+        );
+
+        match res {
+            Ok(n) => {
+                self.bid = Some(bid);
+                Ok(n)
+            }
+            Err(e) => {
+                self.group.put_free(bid);
+                Err(e)
+            }
+        }
     }
 }
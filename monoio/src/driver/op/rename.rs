@@ -20,6 +20,9 @@ impl Op<Rename> {
 }
 
 impl OpAble for Rename {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    const URING_OPCODE: Option<u8> = Some(io_uring::opcode::RenameAt::CODE);
+
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
         use io_uring::{opcode::RenameAt, types};
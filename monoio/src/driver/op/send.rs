@@ -3,19 +3,27 @@ use std::os::unix::prelude::AsRawFd;
 use std::{io, net::SocketAddr};
 
 #[cfg(all(target_os = "linux", feature = "iouring"))]
-use io_uring::{opcode, types};
+use io_uring::opcode;
 use socket2::SockAddr;
 #[cfg(all(windows, any(feature = "legacy", feature = "poll-io")))]
 use {
     std::os::windows::io::AsRawSocket,
     windows_sys::Win32::Networking::WinSock::{send, WSASendMsg, SOCKET_ERROR},
 };
+#[cfg(all(windows, feature = "iocp"))]
+use {
+    std::os::windows::io::RawSocket,
+    windows_sys::Win32::{
+        Networking::WinSock::{WSASend, SOCKET_ERROR as WSA_ERROR, WSABUF},
+        System::IO::OVERLAPPED,
+    },
+};
 
 use super::{super::shared_fd::SharedFd, Op, OpAble};
 #[cfg(any(feature = "legacy", feature = "poll-io"))]
 use super::{driver::ready::Direction, MaybeFd};
 #[cfg(unix)]
-use crate::net::unix::SocketAddr as UnixSocketAddr;
+use crate::net::{cmsg::CmsgBuf, unix::SocketAddr as UnixSocketAddr};
 use crate::{
     buf::{IoBuf, IoVecBufMut, IoVecMeta, MsgMeta},
     BufResult,
@@ -28,19 +36,25 @@ pub(crate) struct Send<T> {
     fd: SharedFd,
 
     pub(crate) buf: T,
+    flags: i32,
 }
 
 impl<T: IoBuf> Op<Send<T>> {
     pub(crate) fn send(fd: SharedFd, buf: T) -> io::Result<Self> {
-        Op::submit_with(Send { fd, buf })
+        Op::submit_with(Send::new(fd, buf))
+    }
+
+    /// Send with the flags like `MSG_ZEROCOPY`, in addition to the default `MSG_NOSIGNAL`.
+    #[cfg(target_os = "linux")]
+    pub(crate) fn send_with_flags(fd: SharedFd, buf: T, flags: i32) -> io::Result<Self> {
+        let mut data = Send::new(fd, buf);
+        data.flags |= flags;
+        Op::submit_with(data)
     }
 
     #[allow(unused)]
     pub(crate) fn send_raw(fd: &SharedFd, buf: T) -> Send<T> {
-        Send {
-            fd: fd.clone(),
-            buf,
-        }
+        Send::new(fd.clone(), buf)
     }
 
     pub(crate) async fn result(self) -> BufResult<usize, T> {
@@ -52,38 +66,28 @@ impl<T: IoBuf> Op<Send<T>> {
     }
 }
 
-impl<T: IoBuf> OpAble for Send<T> {
-    #[cfg(all(target_os = "linux", feature = "iouring"))]
-    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+impl<T> Send<T> {
+    fn new(fd: SharedFd, buf: T) -> Self {
+        #[cfg(target_os = "linux")]
         #[allow(deprecated)]
-        #[cfg(feature = "zero-copy")]
-        fn zero_copy_flag_guard<T: IoBuf>(buf: &T) -> libc::c_int {
-            // TODO: use libc const after supported.
-            const MSG_ZEROCOPY: libc::c_int = 0x4000000;
-            // According to Linux's documentation, zero copy introduces extra overhead and
-            // is only considered effective for at writes over around 10 KB.
-            // see also: https://www.kernel.org/doc/html/v4.16/networking/msg_zerocopy.html
-            const MSG_ZEROCOPY_THRESHOLD: usize = 10 * 1024 * 1024;
-            if buf.bytes_init() >= MSG_ZEROCOPY_THRESHOLD {
-                libc::MSG_NOSIGNAL as libc::c_int | MSG_ZEROCOPY
-            } else {
-                libc::MSG_NOSIGNAL as libc::c_int
-            }
-        }
+        let flags = libc::MSG_NOSIGNAL as _;
+        #[cfg(not(target_os = "linux"))]
+        let flags = 0;
+        Send { fd, buf, flags }
+    }
+}
 
-        #[cfg(feature = "zero-copy")]
-        let flags = zero_copy_flag_guard(&self.buf);
-        #[cfg(not(feature = "zero-copy"))]
-        #[allow(deprecated)]
-        let flags = libc::MSG_NOSIGNAL as libc::c_int;
+impl<T: IoBuf> OpAble for Send<T> {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    const URING_OPCODE: Option<u8> = Some(opcode::Send::CODE);
 
-        opcode::Send::new(
-            types::Fd(self.fd.raw_fd()),
-            self.buf.read_ptr(),
-            self.buf.bytes_init() as _,
-        )
-        .flags(flags)
-        .build()
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        self.fd.uring_entry(|fd| {
+            opcode::Send::new(fd, self.buf.read_ptr(), self.buf.bytes_init() as _)
+                .flags(self.flags)
+                .build()
+        })
     }
 
     #[cfg(any(feature = "legacy", feature = "poll-io"))]
@@ -97,17 +101,11 @@ impl<T: IoBuf> OpAble for Send<T> {
     #[cfg(all(any(feature = "legacy", feature = "poll-io"), unix))]
     fn legacy_call(&mut self) -> io::Result<MaybeFd> {
         let fd = self.fd.as_raw_fd();
-        #[cfg(target_os = "linux")]
-        #[allow(deprecated)]
-        let flags = libc::MSG_NOSIGNAL as _;
-        #[cfg(not(target_os = "linux"))]
-        let flags = 0;
-
         crate::syscall!(send@NON_FD(
             fd,
             self.buf.read_ptr() as _,
             self.buf.bytes_init(),
-            flags
+            self.flags
         ))
     }
 
@@ -115,11 +113,101 @@ impl<T: IoBuf> OpAble for Send<T> {
     fn legacy_call(&mut self) -> io::Result<MaybeFd> {
         let fd = self.fd.as_raw_socket();
         crate::syscall!(
-            send@NON_FD(fd as _, self.buf.read_ptr(), self.buf.bytes_init() as _, 0),
+            send@NON_FD(
+                fd as _,
+                self.buf.read_ptr(),
+                self.buf.bytes_init() as _,
+                self.flags
+            ),
             PartialOrd::lt,
             0
         )
     }
+
+    #[cfg(all(windows, feature = "iocp"))]
+    #[inline]
+    fn iocp_socket(&self) -> Option<RawSocket> {
+        self.fd.iocp_socket()
+    }
+
+    #[cfg(all(windows, feature = "iocp"))]
+    unsafe fn iocp_call(&mut self, overlapped: *mut OVERLAPPED) -> io::Result<()> {
+        // The WSABUF is only read when the operation starts.
+        let buf = WSABUF {
+            len: self.buf.bytes_init().min(u32::MAX as usize) as _,
+            buf: self.buf.read_ptr() as _,
+        };
+        let ret = WSASend(
+            self.fd.raw_socket() as _,
+            &buf,
+            1,
+            std::ptr::null_mut(),
+            self.flags as _,
+            overlapped,
+            None,
+        );
+        crate::driver::iocp::check_started(ret == WSA_ERROR)
+    }
+}
+
+/// Zero-copy send(requires kernel 6.0+). The kernel posts the result first, then a notification
+/// once it no longer reads the buffer; the buffer is only given back after the notification.
+pub(crate) struct SendZc<T>(Send<T>);
+
+impl<T: IoBuf> Op<SendZc<T>> {
+    pub(crate) fn send_zc(fd: SharedFd, buf: T) -> io::Result<Self> {
+        Op::submit_with(SendZc(Send::new(fd, buf)))
+    }
+
+    #[allow(unused_mut)]
+    pub(crate) async fn result(mut self) -> BufResult<usize, T> {
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        if !super::is_legacy() {
+            let meta = std::future::poll_fn(|cx| self.poll_next_multishot(cx))
+                .await
+                .expect("unexpected operation state");
+            if io_uring::cqueue::more(meta.flags) {
+                // Wait for the notification.
+                let _ = std::future::poll_fn(|cx| self.poll_next_multishot(cx)).await;
+            }
+            let buf = self.data.take().expect("unexpected operation state").0.buf;
+            return (meta.result.map(|v| v.into_inner() as _), buf);
+        }
+
+        let complete = self.await;
+        (
+            complete.meta.result.map(|v| v.into_inner() as _),
+            complete.data.0.buf,
+        )
+    }
+}
+
+impl<T: IoBuf> OpAble for SendZc<T> {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    const URING_OPCODE: Option<u8> = Some(opcode::SendZc::CODE);
+
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        #[allow(deprecated)]
+        const FLAGS: i32 = libc::MSG_NOSIGNAL;
+        let (ptr, len) = (self.0.buf.read_ptr(), self.0.buf.bytes_init() as _);
+        self.0
+            .fd
+            .uring_entry(|fd| opcode::SendZc::new(fd, ptr, len).flags(FLAGS).build())
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    #[inline]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        self.0.legacy_interest()
+    }
+
+    /// Legacy driver has no zero-copy send; it works the same as `send`.
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    #[inline]
+    fn legacy_call(&mut self) -> io::Result<MaybeFd> {
+        self.0.legacy_call()
+    }
 }
 
 pub(crate) struct SendMsg<T> {
@@ -132,6 +220,9 @@ pub(crate) struct SendMsg<T> {
     pub(crate) buf: T,
     /// For multiple message send in the future
     pub(crate) info: Box<(Option<SockAddr>, IoVecMeta, MsgMeta)>,
+    /// The encoded ancillary data, empty if there is none.
+    #[cfg(unix)]
+    control: CmsgBuf,
 }
 
 impl<T: IoBuf> Op<SendMsg<T>> {
@@ -139,6 +230,32 @@ impl<T: IoBuf> Op<SendMsg<T>> {
         fd: SharedFd,
         buf: T,
         socket_addr: Option<SocketAddr>,
+    ) -> io::Result<Self> {
+        Self::send_msg_inner(
+            fd,
+            buf,
+            socket_addr,
+            #[cfg(unix)]
+            CmsgBuf::default(),
+        )
+    }
+
+    /// Send a message with the encoded ancillary data.
+    #[cfg(unix)]
+    pub(crate) fn send_msg_with_control(
+        fd: SharedFd,
+        buf: T,
+        socket_addr: Option<SocketAddr>,
+        control: CmsgBuf,
+    ) -> io::Result<Self> {
+        Self::send_msg_inner(fd, buf, socket_addr, control)
+    }
+
+    fn send_msg_inner(
+        fd: SharedFd,
+        buf: T,
+        socket_addr: Option<SocketAddr>,
+        #[cfg(unix)] mut control: CmsgBuf,
     ) -> io::Result<Self> {
         let mut info: Box<(Option<SockAddr>, IoVecMeta, MsgMeta)> = Box::new((
             socket_addr.map(Into::into),
@@ -160,6 +277,10 @@ impl<T: IoBuf> Op<SendMsg<T>> {
                     info.2.msg_namelen = 0;
                 }
             }
+            if !control.is_empty() {
+                info.2.msg_control = control.as_mut_ptr();
+                info.2.msg_controllen = control.len() as _;
+            }
         }
         #[cfg(windows)]
         {
@@ -177,7 +298,13 @@ impl<T: IoBuf> Op<SendMsg<T>> {
             }
         }
 
-        Op::submit_with(SendMsg { fd, buf, info })
+        Op::submit_with(SendMsg {
+            fd,
+            buf,
+            info,
+            #[cfg(unix)]
+            control,
+        })
     }
 
     pub(crate) async fn wait(self) -> BufResult<usize, T> {
@@ -189,13 +316,15 @@ impl<T: IoBuf> Op<SendMsg<T>> {
 }
 
 impl<T: IoBuf> OpAble for SendMsg<T> {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    const URING_OPCODE: Option<u8> = Some(opcode::SendMsg::CODE);
+
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
         #[allow(deprecated)]
         const FLAGS: u32 = libc::MSG_NOSIGNAL as u32;
-        opcode::SendMsg::new(types::Fd(self.fd.raw_fd()), &*self.info.2)
-            .flags(FLAGS)
-            .build()
+        self.fd
+            .uring_entry(|fd| opcode::SendMsg::new(fd, &*self.info.2).flags(FLAGS).build())
     }
 
     #[cfg(any(feature = "legacy", feature = "poll-io"))]
@@ -250,6 +379,9 @@ pub(crate) struct SendMsgUnix<T> {
     pub(crate) buf: T,
     /// For multiple message send in the future
     pub(crate) info: Box<(Option<UnixSocketAddr>, IoVecMeta, libc::msghdr)>,
+    /// The encoded ancillary data, empty if there is none.
+    #[allow(unused)]
+    control: CmsgBuf,
 }
 
 #[cfg(unix)]
@@ -259,11 +391,20 @@ impl<T: IoBuf> Op<SendMsgUnix<T>> {
         buf: T,
         socket_addr: Option<UnixSocketAddr>,
     ) -> io::Result<Self> {
-        let mut info: Box<(Option<UnixSocketAddr>, IoVecMeta, libc::msghdr)> = Box::new((
-            socket_addr.map(Into::into),
-            IoVecMeta::from(&buf),
-            unsafe { std::mem::zeroed() },
-        ));
+        Self::send_msg_unix_with_control(fd, buf, socket_addr, CmsgBuf::default())
+    }
+
+    /// Send a message with the encoded ancillary data.
+    pub(crate) fn send_msg_unix_with_control(
+        fd: SharedFd,
+        buf: T,
+        socket_addr: Option<UnixSocketAddr>,
+        mut control: CmsgBuf,
+    ) -> io::Result<Self> {
+        let mut info: Box<(Option<UnixSocketAddr>, IoVecMeta, libc::msghdr)> =
+            Box::new((socket_addr, IoVecMeta::from(&buf), unsafe {
+                std::mem::zeroed()
+            }));
 
         info.2.msg_iov = info.1.write_iovec_ptr();
         info.2.msg_iovlen = info.1.write_iovec_len() as _;
@@ -278,8 +419,17 @@ impl<T: IoBuf> Op<SendMsgUnix<T>> {
                 info.2.msg_namelen = 0;
             }
         }
+        if !control.is_empty() {
+            info.2.msg_control = control.as_mut_ptr();
+            info.2.msg_controllen = control.len() as _;
+        }
 
-        Op::submit_with(SendMsgUnix { fd, buf, info })
+        Op::submit_with(SendMsgUnix {
+            fd,
+            buf,
+            info,
+            control,
+        })
     }
 
     pub(crate) async fn wait(self) -> BufResult<usize, T> {
@@ -292,13 +442,18 @@ impl<T: IoBuf> Op<SendMsgUnix<T>> {
 
 #[cfg(unix)]
 impl<T: IoBuf> OpAble for SendMsgUnix<T> {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    const URING_OPCODE: Option<u8> = Some(opcode::SendMsg::CODE);
+
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
         #[allow(deprecated)]
         const FLAGS: u32 = libc::MSG_NOSIGNAL as u32;
-        opcode::SendMsg::new(types::Fd(self.fd.raw_fd()), &mut self.info.2 as *mut _)
-            .flags(FLAGS)
-            .build()
+        self.fd.uring_entry(|fd| {
+            opcode::SendMsg::new(fd, &mut self.info.2 as *mut _)
+                .flags(FLAGS)
+                .build()
+        })
     }
 
     #[cfg(any(feature = "legacy", feature = "poll-io"))]
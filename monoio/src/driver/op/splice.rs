@@ -4,7 +4,7 @@ use std::io;
 
 #[cfg(all(target_os = "linux", feature = "iouring"))]
 use io_uring::{opcode, types};
-#[cfg(all(unix, feature = "legacy"))]
+#[cfg(any(feature = "legacy", feature = "poll-io"))]
 use {
     crate::driver::{op::MaybeFd, ready::Direction},
     std::os::unix::prelude::AsRawFd,
@@ -12,11 +12,13 @@ use {
 
 use super::{super::shared_fd::SharedFd, Op, OpAble};
 
-// Currently our Splice does not support setting offset.
 pub(crate) struct Splice {
     fd_in: SharedFd,
     fd_out: SharedFd,
+    // Offset of `fd_in`, -1 to use and update the file position.
+    off_in: i64,
     len: u32,
+    #[allow(unused)]
     direction: SpliceDirection,
 }
 enum SpliceDirection {
@@ -25,14 +27,26 @@ enum SpliceDirection {
 }
 
 impl Op<Splice> {
+    #[cfg(feature = "splice")]
     pub(crate) fn splice_to_pipe(
         fd_in: &SharedFd,
         fd_out: &SharedFd,
         len: u32,
+    ) -> io::Result<Op<Splice>> {
+        Self::splice_to_pipe_at(fd_in, fd_out, -1, len)
+    }
+
+    /// Splice from `fd_in` at `off_in`, which does not change the file position.
+    pub(crate) fn splice_to_pipe_at(
+        fd_in: &SharedFd,
+        fd_out: &SharedFd,
+        off_in: i64,
+        len: u32,
     ) -> io::Result<Op<Splice>> {
         Op::submit_with(Splice {
             fd_in: fd_in.clone(),
             fd_out: fd_out.clone(),
+            off_in,
             len,
             direction: SpliceDirection::ToPipe,
         })
@@ -46,6 +60,7 @@ impl Op<Splice> {
         Op::submit_with(Splice {
             fd_in: fd_in.clone(),
             fd_out: fd_out.clone(),
+            off_in: -1,
             len,
             direction: SpliceDirection::FromPipe,
         })
@@ -58,21 +73,29 @@ impl Op<Splice> {
 }
 
 impl OpAble for Splice {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    const URING_OPCODE: Option<u8> = Some(opcode::Splice::CODE);
+
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
         const FLAG: u32 = libc::SPLICE_F_MOVE;
-        opcode::Splice::new(
-            types::Fd(self.fd_in.raw_fd()),
-            -1,
-            types::Fd(self.fd_out.raw_fd()),
-            -1,
-            self.len,
-        )
-        .flags(FLAG)
-        .build()
+        let (off_in, len) = (self.off_in, self.len);
+        // `fd_out` is referred by the entry, while a fixed `fd_in` is marked by
+        // `SPLICE_F_FD_IN_FIXED`.
+        self.fd_out
+            .uring_entry(|fd_out| match self.fd_in.fixed_index() {
+                Some(idx) => opcode::Splice::new(types::Fixed(idx), off_in, fd_out, -1, len)
+                    .flags(FLAG)
+                    .build(),
+                None => {
+                    opcode::Splice::new(types::Fd(self.fd_in.raw_fd()), off_in, fd_out, -1, len)
+                        .flags(FLAG)
+                        .build()
+                }
+            })
     }
 
-    #[cfg(all(unix, feature = "legacy"))]
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
     #[inline]
     fn legacy_interest(&self) -> Option<(Direction, usize)> {
         match self.direction {
@@ -87,12 +110,17 @@ impl OpAble for Splice {
         }
     }
 
-    #[cfg(all(unix, feature = "legacy"))]
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
     fn legacy_call(&mut self) -> io::Result<MaybeFd> {
         const FLAG: u32 = libc::SPLICE_F_MOVE | libc::SPLICE_F_NONBLOCK;
         let fd_in = self.fd_in.as_raw_fd();
         let fd_out = self.fd_out.as_raw_fd();
-        let off_in = std::ptr::null_mut::<libc::loff_t>();
+        let mut off = self.off_in;
+        let off_in = if off < 0 {
+            std::ptr::null_mut::<libc::loff_t>()
+        } else {
+            &mut off as *mut libc::loff_t
+        };
         let off_out = std::ptr::null_mut::<libc::loff_t>();
         crate::syscall!(splice@NON_FD(
             fd_in,
@@ -104,3 +132,61 @@ impl OpAble for Splice {
         ))
     }
 }
+
+/// Duplicate the data of a pipe to another pipe without consuming it.
+#[cfg(feature = "splice")]
+pub(crate) struct Tee {
+    fd_in: SharedFd,
+    fd_out: SharedFd,
+    len: u32,
+}
+
+#[cfg(feature = "splice")]
+impl Op<Tee> {
+    pub(crate) fn tee(fd_in: &SharedFd, fd_out: &SharedFd, len: u32) -> io::Result<Op<Tee>> {
+        Op::submit_with(Tee {
+            fd_in: fd_in.clone(),
+            fd_out: fd_out.clone(),
+            len,
+        })
+    }
+
+    pub(crate) async fn result(self) -> io::Result<u32> {
+        let complete = self.await;
+        complete.meta.result.map(|v| v.into_inner())
+    }
+}
+
+#[cfg(feature = "splice")]
+impl OpAble for Tee {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    const URING_OPCODE: Option<u8> = Some(opcode::Tee::CODE);
+
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        let len = self.len;
+        self.fd_out
+            .uring_entry(|fd_out| match self.fd_in.fixed_index() {
+                Some(idx) => opcode::Tee::new(types::Fixed(idx), fd_out, len).build(),
+                None => opcode::Tee::new(types::Fd(self.fd_in.raw_fd()), fd_out, len).build(),
+            })
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    #[inline]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        self.fd_in
+            .registered_index()
+            .map(|idx| (Direction::Read, idx))
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    fn legacy_call(&mut self) -> io::Result<MaybeFd> {
+        crate::syscall!(tee@NON_FD(
+            self.fd_in.as_raw_fd(),
+            self.fd_out.as_raw_fd(),
+            self.len as usize,
+            libc::SPLICE_F_NONBLOCK
+        ))
+    }
+}
@@ -63,6 +63,9 @@ impl Op<FdStatx> {
 }
 
 impl OpAble for FdStatx {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    const URING_OPCODE: Option<u8> = Some(opcode::Statx::CODE);
+
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
         use std::os::fd::AsRawFd;
@@ -70,7 +73,7 @@ impl OpAble for FdStatx {
         let statxbuf = self.statx_buf.as_mut_ptr() as *mut _;
 
         opcode::Statx::new(types::Fd(self.inner.as_raw_fd()), c"".as_ptr(), statxbuf)
-            .flags(libc::AT_EMPTY_PATH | libc::AT_STATX_SYNC_AS_STAT)
+            .flags(self.flags | libc::AT_EMPTY_PATH)
             .mask(libc::STATX_ALL)
             .build()
     }
@@ -89,7 +92,7 @@ impl OpAble for FdStatx {
         crate::syscall!(statx@NON_FD(
             self.inner.as_raw_fd(),
             c"".as_ptr(),
-            libc::AT_EMPTY_PATH,
+            self.flags | libc::AT_EMPTY_PATH,
             libc::STATX_ALL,
             self.statx_buf.as_mut_ptr() as *mut _
         ))
@@ -111,15 +114,26 @@ impl OpAble for FdStatx {
     }
 }
 
-type PathStatx = Statx<CString>;
+/// The path and the directory which it is relative to, or the current directory if `None`.
+type PathStatx = Statx<(Option<SharedFd>, CString)>;
 
 impl Op<PathStatx> {
     /// submit a statx operation
     #[cfg(target_os = "linux")]
     pub(crate) fn statx_using_path<P: AsRef<Path>>(path: P, flags: i32) -> std::io::Result<Self> {
+        Self::statx_using_path_at(None, path, flags)
+    }
+
+    /// submit a statx operation with the path relative to `dir`
+    #[cfg(target_os = "linux")]
+    pub(crate) fn statx_using_path_at<P: AsRef<Path>>(
+        dir: Option<&SharedFd>,
+        path: P,
+        flags: i32,
+    ) -> std::io::Result<Self> {
         let path = cstr(path.as_ref())?;
         Op::submit_with(Statx {
-            inner: path,
+            inner: (dir.cloned(), path),
             flags,
             statx_buf: Box::new(MaybeUninit::uninit()),
         })
@@ -140,7 +154,7 @@ impl Op<PathStatx> {
     ) -> std::io::Result<Self> {
         let path = cstr(path.as_ref())?;
         Op::submit_with(Statx {
-            inner: path,
+            inner: (None, path),
             follow_symlinks,
             stat_buf: Box::new(MaybeUninit::uninit()),
         })
@@ -155,12 +169,26 @@ impl Op<PathStatx> {
     }
 }
 
+impl PathStatx {
+    #[inline]
+    #[allow(unused)]
+    fn dirfd(&self) -> libc::c_int {
+        self.inner
+            .0
+            .as_ref()
+            .map_or(libc::AT_FDCWD, |dir| dir.raw_fd())
+    }
+}
+
 impl OpAble for PathStatx {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    const URING_OPCODE: Option<u8> = Some(opcode::Statx::CODE);
+
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
         let statxbuf = self.statx_buf.as_mut_ptr() as *mut _;
 
-        opcode::Statx::new(types::Fd(libc::AT_FDCWD), self.inner.as_ptr(), statxbuf)
+        opcode::Statx::new(types::Fd(self.dirfd()), self.inner.1.as_ptr(), statxbuf)
             .flags(self.flags)
             .mask(libc::STATX_ALL)
             .build()
@@ -174,8 +202,8 @@ impl OpAble for PathStatx {
     #[cfg(all(any(feature = "legacy", feature = "poll-io"), target_os = "linux"))]
     fn legacy_call(&mut self) -> std::io::Result<MaybeFd> {
         crate::syscall!(statx@NON_FD(
-            libc::AT_FDCWD,
-            self.inner.as_ptr(),
+            self.dirfd(),
+            self.inner.1.as_ptr(),
             self.flags,
             libc::STATX_ALL,
             self.statx_buf.as_mut_ptr() as *mut _
@@ -191,12 +219,12 @@ impl OpAble for PathStatx {
     fn legacy_call(&mut self) -> std::io::Result<MaybeFd> {
         if self.follow_symlinks {
             crate::syscall!(stat@NON_FD(
-                self.inner.as_ptr(),
+                self.inner.1.as_ptr(),
                 self.stat_buf.as_mut_ptr() as *mut _
             ))
         } else {
             crate::syscall!(lstat@NON_FD(
-                self.inner.as_ptr(),
+                self.inner.1.as_ptr(),
                 self.stat_buf.as_mut_ptr() as *mut _
             ))
         }
@@ -21,6 +21,9 @@ impl Op<Symlink> {
 }
 
 impl OpAble for Symlink {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    const URING_OPCODE: Option<u8> = Some(io_uring::opcode::SymlinkAt::CODE);
+
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
         use io_uring::{opcode, types};
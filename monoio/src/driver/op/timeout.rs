@@ -0,0 +1,68 @@
+use std::{io, time::Duration};
+
+use io_uring::{opcode, types};
+
+#[cfg(any(feature = "legacy", feature = "poll-io"))]
+use super::MaybeFd;
+use super::{Op, OpAble};
+
+/// `IORING_TIMEOUT_MULTISHOT`, since 6.4.
+const TIMEOUT_MULTISHOT: u32 = 1 << 6;
+
+/// A timer armed in the kernel.
+pub(crate) struct Timeout {
+    /// The kernel reads it when the SQE is submitted, which may be after the op is moved.
+    ts: Box<types::Timespec>,
+    multishot: bool,
+}
+
+impl Op<Timeout> {
+    /// Expire once after the duration.
+    pub(crate) fn timeout(duration: Duration) -> io::Result<Op<Timeout>> {
+        Op::submit_with(Timeout {
+            ts: Box::new(duration.into()),
+            multishot: false,
+        })
+    }
+
+    /// Expire every period until canceled, with a completion for each expiration.
+    pub(crate) fn timeout_multishot(period: Duration) -> io::Result<Op<Timeout>> {
+        Op::submit_with(Timeout {
+            ts: Box::new(period.into()),
+            multishot: true,
+        })
+    }
+}
+
+impl Timeout {
+    /// The expiration completes with `ETIME`, which is not an error.
+    pub(crate) fn expired(result: io::Result<super::MaybeFd>) -> io::Result<()> {
+        match result {
+            Ok(_) => Ok(()),
+            Err(e) if e.raw_os_error() == Some(libc::ETIME) => Ok(()),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl OpAble for Timeout {
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        let mut op = opcode::Timeout::new(&*self.ts);
+        if self.multishot {
+            // Safety: the flag is defined by the kernel, which rejects it if not supported.
+            op = op.flags(unsafe { types::TimeoutFlags::from_bits_unchecked(TIMEOUT_MULTISHOT) });
+        }
+        op.build()
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    #[inline]
+    fn legacy_interest(&self) -> Option<(crate::driver::ready::Direction, usize)> {
+        None
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    fn legacy_call(&mut self) -> io::Result<MaybeFd> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
@@ -2,42 +2,66 @@ use std::{ffi::CString, io, path::Path};
 
 #[cfg(all(target_os = "linux", feature = "iouring"))]
 use io_uring::{opcode, squeue::Entry, types::Fd};
-#[cfg(all(target_os = "linux", feature = "iouring"))]
-use libc::{AT_FDCWD, AT_REMOVEDIR};
 
 use super::{Op, OpAble};
-use crate::driver::util::cstr;
 #[cfg(any(feature = "legacy", feature = "poll-io"))]
 use crate::driver::{op::MaybeFd, ready::Direction};
+use crate::driver::{shared_fd::SharedFd, util::cstr};
 
 pub(crate) struct Unlink {
+    /// The directory which `path` is relative to, or the current directory if `None`.
+    dir: Option<SharedFd>,
     path: CString,
     remove_dir: bool,
 }
 
 impl Op<Unlink> {
     pub(crate) fn unlink<P: AsRef<Path>>(path: P) -> io::Result<Op<Unlink>> {
-        let path = cstr(path.as_ref())?;
-        Op::submit_with(Unlink {
-            path,
-            remove_dir: false,
-        })
+        Self::unlink_at(None, path, false)
     }
 
     pub(crate) fn rmdir<P: AsRef<Path>>(path: P) -> io::Result<Op<Unlink>> {
+        Self::unlink_at(None, path, true)
+    }
+
+    pub(crate) fn unlink_at<P: AsRef<Path>>(
+        dir: Option<&SharedFd>,
+        path: P,
+        remove_dir: bool,
+    ) -> io::Result<Op<Unlink>> {
         let path = cstr(path.as_ref())?;
         Op::submit_with(Unlink {
+            dir: dir.cloned(),
             path,
-            remove_dir: true,
+            remove_dir,
         })
     }
 }
 
+impl Unlink {
+    #[inline]
+    fn dirfd(&self) -> libc::c_int {
+        self.dir.as_ref().map_or(libc::AT_FDCWD, |dir| dir.raw_fd())
+    }
+
+    #[inline]
+    fn flags(&self) -> libc::c_int {
+        if self.remove_dir {
+            libc::AT_REMOVEDIR
+        } else {
+            0
+        }
+    }
+}
+
 impl OpAble for Unlink {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    const URING_OPCODE: Option<u8> = Some(opcode::UnlinkAt::CODE);
+
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     fn uring_op(&mut self) -> Entry {
-        opcode::UnlinkAt::new(Fd(AT_FDCWD), self.path.as_c_str().as_ptr())
-            .flags(if self.remove_dir { AT_REMOVEDIR } else { 0 })
+        opcode::UnlinkAt::new(Fd(self.dirfd()), self.path.as_c_str().as_ptr())
+            .flags(self.flags())
             .build()
     }
 
@@ -48,10 +72,10 @@ impl OpAble for Unlink {
 
     #[cfg(any(feature = "legacy", feature = "poll-io"))]
     fn legacy_call(&mut self) -> io::Result<MaybeFd> {
-        if self.remove_dir {
-            crate::syscall!(rmdir@NON_FD(self.path.as_c_str().as_ptr()))
-        } else {
-            crate::syscall!(unlink@NON_FD(self.path.as_c_str().as_ptr()))
-        }
+        crate::syscall!(unlinkat@NON_FD(
+            self.dirfd(),
+            self.path.as_c_str().as_ptr(),
+            self.flags()
+        ))
     }
 }
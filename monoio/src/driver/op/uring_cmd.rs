@@ -0,0 +1,260 @@
+use std::{
+    future::Future,
+    io,
+    os::unix::prelude::RawFd,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use io_uring::{opcode, types};
+
+#[cfg(any(feature = "legacy", feature = "poll-io"))]
+use super::{driver::ready::Direction, MaybeFd};
+use super::{Op, OpAble};
+
+/// Size of the command in the 64-byte SQE.
+const CMD_LEN: usize = 16;
+/// Size of the command in the 128-byte SQE.
+const BIG_CMD_LEN: usize = 80;
+
+/// Builder of an `IORING_OP_URING_CMD` operation, which passes a command to the driver of the
+/// file, like NVMe passthrough([`NvmeUringCmd`]) and ublk control([`UblkCtrlCmd`]).
+///
+/// Commands longer than 16 bytes require the ring to be built with
+/// [`uring_big_entries`](crate::RuntimeBuilder::uring_big_entries), which also makes the big
+/// part of the CQE available in [`UringCmdCompletion::big_cqe`].
+#[derive(Debug, Clone)]
+pub struct UringCmd {
+    fd: RawFd,
+    cmd_op: u32,
+    cmd: [u8; BIG_CMD_LEN],
+    cmd_len: usize,
+    buf_index: Option<u16>,
+}
+
+impl UringCmd {
+    /// Create a command `cmd_op` on the fd, with an empty payload.
+    pub fn new(fd: RawFd, cmd_op: u32) -> Self {
+        Self {
+            fd,
+            cmd_op,
+            cmd: [0; BIG_CMD_LEN],
+            cmd_len: 0,
+            buf_index: None,
+        }
+    }
+
+    /// Create an NVMe passthrough command on the char device(e.g. `/dev/ng0n1`). `cmd_op` is
+    /// one of [`NvmeUringCmd::IO`], [`NvmeUringCmd::IO_VEC`], [`NvmeUringCmd::ADMIN`] and
+    /// [`NvmeUringCmd::ADMIN_VEC`].
+    pub fn nvme(fd: RawFd, cmd_op: u32, cmd: &NvmeUringCmd) -> Self {
+        Self::new(fd, cmd_op).cmd(as_bytes(cmd))
+    }
+
+    /// Create a ublk control command on `/dev/ublk-control`. `cmd_op` is one of the `UBLK_U_CMD_*`
+    /// constants of [`UblkCtrlCmd`].
+    pub fn ublk_ctrl(fd: RawFd, cmd_op: u32, cmd: &UblkCtrlCmd) -> Self {
+        Self::new(fd, cmd_op).cmd(as_bytes(cmd))
+    }
+
+    /// Set the payload of the command.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the payload is longer than 80 bytes.
+    #[must_use]
+    pub fn cmd(mut self, cmd: &[u8]) -> Self {
+        assert!(
+            cmd.len() <= BIG_CMD_LEN,
+            "uring cmd is longer than {BIG_CMD_LEN} bytes"
+        );
+        self.cmd = [0; BIG_CMD_LEN];
+        self.cmd[..cmd.len()].copy_from_slice(cmd);
+        self.cmd_len = cmd.len();
+        self
+    }
+
+    /// Use the registered buffer at `buf_index`(`IORING_URING_CMD_FIXED`).
+    #[must_use]
+    pub fn fixed_buffer(mut self, buf_index: u16) -> Self {
+        self.buf_index = Some(buf_index);
+        self
+    }
+
+    /// Submit the command to the io_uring driver of current runtime.
+    ///
+    /// `data` is held by the operation until it completes, even if the returned future is
+    /// dropped before, so it can own the resources referred by the command, like buffers. It is
+    /// returned back with the completion.
+    ///
+    /// Returns `ErrorKind::Unsupported` if current runtime is not on io_uring driver or the
+    /// kernel does not support `URING_CMD`, and `ErrorKind::InvalidInput` if the command is
+    /// longer than 16 bytes and the ring does not have big entries.
+    ///
+    /// # Safety
+    ///
+    /// The command must be valid for the file, and the memory it refers to must stay valid until
+    /// the operation completes. `data` is moved, so the command must only refer to the memory
+    /// which does not move with it(e.g. the heap memory of a `Vec` or `Box`).
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a monoio runtime.
+    pub unsafe fn submit<T: Unpin + 'static>(self, data: T) -> io::Result<UringCmdOp<T>> {
+        let caps = super::super::capabilities();
+        if !caps.is_uring() || !caps.is_opcode_supported(opcode::UringCmd16::CODE) {
+            return Err(io::ErrorKind::Unsupported.into());
+        }
+        if self.cmd_len > CMD_LEN && !caps.is_big_entries() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "uring cmd longer than 16 bytes requires big entries",
+            ));
+        }
+        let op = Op::submit_with(Cmd { cmd: self, data })?;
+        Ok(UringCmdOp { op })
+    }
+}
+
+fn as_bytes<T: Copy>(v: &T) -> &[u8] {
+    unsafe { std::slice::from_raw_parts(v as *const T as *const u8, std::mem::size_of::<T>()) }
+}
+
+/// `struct nvme_uring_cmd` of `linux/nvme_ioctl.h`.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+#[allow(missing_docs)]
+pub struct NvmeUringCmd {
+    pub opcode: u8,
+    pub flags: u8,
+    pub rsvd1: u16,
+    pub nsid: u32,
+    pub cdw2: u32,
+    pub cdw3: u32,
+    pub metadata: u64,
+    pub addr: u64,
+    pub metadata_len: u32,
+    pub data_len: u32,
+    pub cdw10: u32,
+    pub cdw11: u32,
+    pub cdw12: u32,
+    pub cdw13: u32,
+    pub cdw14: u32,
+    pub cdw15: u32,
+    pub timeout_ms: u32,
+    pub rsvd2: u32,
+}
+
+impl NvmeUringCmd {
+    /// `NVME_URING_CMD_IO`
+    pub const IO: u32 = 0xC048_4E80;
+    /// `NVME_URING_CMD_IO_VEC`
+    pub const IO_VEC: u32 = 0xC048_4E81;
+    /// `NVME_URING_CMD_ADMIN`
+    pub const ADMIN: u32 = 0xC048_4E82;
+    /// `NVME_URING_CMD_ADMIN_VEC`
+    pub const ADMIN_VEC: u32 = 0xC048_4E83;
+}
+
+/// `struct ublksrv_ctrl_cmd` of `linux/ublk_cmd.h`.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy)]
+#[allow(missing_docs)]
+pub struct UblkCtrlCmd {
+    pub dev_id: u32,
+    pub queue_id: u16,
+    pub len: u16,
+    pub addr: u64,
+    pub data: u64,
+    pub dev_path_len: u16,
+    pub pad: u16,
+    pub reserved: u32,
+}
+
+impl UblkCtrlCmd {
+    /// `UBLK_U_CMD_GET_QUEUE_AFFINITY`
+    pub const GET_QUEUE_AFFINITY: u32 = 0x8020_7501;
+    /// `UBLK_U_CMD_GET_DEV_INFO`
+    pub const GET_DEV_INFO: u32 = 0x8020_7502;
+    /// `UBLK_U_CMD_ADD_DEV`
+    pub const ADD_DEV: u32 = 0xC020_7504;
+    /// `UBLK_U_CMD_DEL_DEV`
+    pub const DEL_DEV: u32 = 0xC020_7505;
+    /// `UBLK_U_CMD_START_DEV`
+    pub const START_DEV: u32 = 0xC020_7506;
+    /// `UBLK_U_CMD_STOP_DEV`
+    pub const STOP_DEV: u32 = 0xC020_7507;
+    /// `UBLK_U_CMD_SET_PARAMS`
+    pub const SET_PARAMS: u32 = 0xC020_7508;
+    /// `UBLK_U_CMD_GET_PARAMS`
+    pub const GET_PARAMS: u32 = 0x8020_7509;
+    /// `UBLK_U_CMD_GET_FEATURES`
+    pub const GET_FEATURES: u32 = 0x8020_7513;
+}
+
+/// The `URING_CMD` operation.
+pub(crate) struct Cmd<T> {
+    cmd: UringCmd,
+    data: T,
+}
+
+impl<T> OpAble for Cmd<T> {
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        let c = &self.cmd;
+        let mut cmd = [0; CMD_LEN];
+        cmd.copy_from_slice(&c.cmd[..CMD_LEN]);
+        opcode::UringCmd16::new(types::Fd(c.fd), c.cmd_op)
+            .cmd(cmd)
+            .buf_index(c.buf_index)
+            .build()
+    }
+
+    fn uring_op128(&mut self) -> io_uring::squeue::Entry128 {
+        let c = &self.cmd;
+        opcode::UringCmd80::new(types::Fd(c.fd), c.cmd_op)
+            .cmd(c.cmd)
+            .buf_index(c.buf_index)
+            .build()
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    #[inline]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        None
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    fn legacy_call(&mut self) -> io::Result<MaybeFd> {
+        Err(io::ErrorKind::Unsupported.into())
+    }
+}
+
+/// Future of the command submitted with [`UringCmd::submit`].
+pub struct UringCmdOp<T: 'static> {
+    op: Op<Cmd<T>>,
+}
+
+/// Completion of the command submitted with [`UringCmd::submit`].
+#[derive(Debug)]
+pub struct UringCmdCompletion<T> {
+    /// The data passed to [`UringCmd::submit`].
+    pub data: T,
+    /// The result of the CQE, as an error if it is negative.
+    pub result: io::Result<u32>,
+    /// The big part of the CQE(e.g. the NVMe completion result), which is zero if the ring does
+    /// not have big entries.
+    pub big_cqe: [u64; 2],
+}
+
+impl<T: Unpin + 'static> Future for UringCmdOp<T> {
+    type Output = UringCmdCompletion<T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let (complete, big_cqe) = ready!(self.op.poll_big(cx));
+        Poll::Ready(UringCmdCompletion {
+            data: complete.data.data,
+            result: complete.meta.result.map(|n| n.into_inner()),
+            big_cqe,
+        })
+    }
+}
@@ -1,5 +1,5 @@
 use std::io;
-#[cfg(unix)]
+#[cfg(all(unix, any(feature = "legacy", feature = "poll-io")))]
 use std::os::unix::prelude::AsRawFd;
 #[cfg(windows)]
 use std::os::windows::io::AsRawHandle;
@@ -15,7 +15,7 @@ use super::{super::shared_fd::SharedFd, Op, OpAble};
 #[cfg(any(feature = "legacy", feature = "poll-io"))]
 use super::{driver::ready::Direction, MaybeFd};
 use crate::{
-    buf::{IoBuf, IoVecBuf},
+    buf::{FixedBuf, IoBuf, IoVecBuf},
     BufResult,
 };
 
@@ -55,19 +55,20 @@ impl<T: IoBuf> Op<Write<T>> {
 }
 
 impl<T: IoBuf> OpAble for Write<T> {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    const URING_OPCODE: Option<u8> = Some(opcode::Write::CODE);
+
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
         // Refers to https://docs.rs/io-uring/latest/io_uring/opcode/struct.Write.html.
         //
         // If `offset` is set to `-1`, the offset will use (and advance) the file position, like
         // the write(2) system calls
-        opcode::Write::new(
-            types::Fd(self.fd.as_raw_fd()),
-            self.buf.read_ptr(),
-            self.buf.bytes_init() as _,
-        )
-        .offset(-1i64 as _)
-        .build()
+        self.fd.uring_entry(|fd| {
+            opcode::Write::new(fd, self.buf.read_ptr(), self.buf.bytes_init() as _)
+                .offset(-1i64 as _)
+                .build()
+        })
     }
 
     #[cfg(any(feature = "legacy", feature = "poll-io"))]
@@ -101,6 +102,13 @@ pub(crate) struct WriteAt<T> {
     pub(crate) buf: T,
 }
 
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+impl<T> WriteAt<T> {
+    pub(crate) fn new(fd: SharedFd, buf: T, offset: u64) -> Self {
+        WriteAt { fd, offset, buf }
+    }
+}
+
 impl<T: IoBuf> Op<WriteAt<T>> {
     pub(crate) fn write_at(fd: SharedFd, buf: T, offset: u64) -> io::Result<Op<WriteAt<T>>> {
         Op::submit_with(WriteAt { fd, offset, buf })
@@ -108,15 +116,16 @@ impl<T: IoBuf> Op<WriteAt<T>> {
 }
 
 impl<T: IoBuf> OpAble for WriteAt<T> {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    const URING_OPCODE: Option<u8> = Some(opcode::Write::CODE);
+
     #[cfg(all(target_os = "linux", feature = "iouring"))]
     fn uring_op(&mut self) -> io_uring::squeue::Entry {
-        opcode::Write::new(
-            types::Fd(self.fd.raw_fd()),
-            self.buf.read_ptr(),
-            self.buf.bytes_init() as _,
-        )
-        .offset(self.offset)
-        .build()
+        self.fd.uring_entry(|fd| {
+            opcode::Write::new(fd, self.buf.read_ptr(), self.buf.bytes_init() as _)
+                .offset(self.offset)
+                .build()
+        })
     }
 
     #[cfg(any(feature = "legacy", feature = "poll-io"))]
@@ -167,9 +176,11 @@ impl<T: IoVecBuf> OpAble for WriteVec<T> {
         //
         // If `offset` is set to `-1`, the offset will use (and advance) the file position, like
         // the writev(2) system calls
-        opcode::Writev::new(types::Fd(self.fd.raw_fd()), ptr, len)
-            .offset(-1i64 as u64)
-            .build()
+        self.fd.uring_entry(|fd| {
+            opcode::Writev::new(fd, ptr, len)
+                .offset(-1i64 as u64)
+                .build()
+        })
     }
 
     #[cfg(any(feature = "legacy", feature = "poll-io"))]
@@ -247,6 +258,70 @@ impl<T: IoVecBuf> OpAble for WriteVecAt<T> {
     }
 }
 
+pub(crate) struct WriteFixed {
+    /// Holds a strong ref to the FD, preventing the file from being closed
+    /// while the operation is in-flight.
+    fd: SharedFd,
+    pub(crate) buf: FixedBuf,
+    /// If `offset` is set to `-1`, the offset will use (and advance) the file position.
+    offset: u64,
+}
+
+impl Op<WriteFixed> {
+    pub(crate) fn write_fixed(fd: SharedFd, buf: FixedBuf, offset: u64) -> io::Result<Self> {
+        Op::submit_with(WriteFixed { fd, buf, offset })
+    }
+
+    pub(crate) async fn result(self) -> BufResult<usize, FixedBuf> {
+        let complete = self.await;
+        (
+            complete.meta.result.map(|v| v.into_inner() as _),
+            complete.data.buf,
+        )
+    }
+}
+
+impl OpAble for WriteFixed {
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        let ptr = self.buf.read_ptr();
+        let len = self.buf.bytes_init() as _;
+        let (offset, index) = (self.offset, self.buf.buf_index());
+        if self.buf.is_registered() {
+            self.fd.uring_entry(|fd| {
+                opcode::WriteFixed::new(fd, ptr, len, index)
+                    .offset(offset)
+                    .build()
+            })
+        } else {
+            self.fd
+                .uring_entry(|fd| opcode::Write::new(fd, ptr, len).offset(offset).build())
+        }
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    #[inline]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        self.fd
+            .registered_index()
+            .map(|idx| (Direction::Write, idx))
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    fn legacy_call(&mut self) -> io::Result<MaybeFd> {
+        #[cfg(windows)]
+        let fd = self.fd.as_raw_handle() as _;
+        #[cfg(unix)]
+        let fd = self.fd.as_raw_fd();
+
+        if self.offset == u64::MAX {
+            write(fd, self.buf.read_ptr(), self.buf.bytes_init())
+        } else {
+            write_at(fd, self.buf.read_ptr(), self.buf.bytes_init(), self.offset)
+        }
+    }
+}
+
 #[cfg(all(any(feature = "legacy", feature = "poll-io"), unix))]
 pub(crate) mod impls {
     use libc::iovec;
@@ -265,8 +340,8 @@ pub(crate) mod impls {
         len: usize,
         offset: u64,
     ) -> io::Result<MaybeFd> {
-        let offset = libc::off_t::try_from(offset)
-            .map_err(|_| io::Error::new(io::ErrorKind::Other, "offset too big"))?;
+        let offset =
+            libc::off_t::try_from(offset).map_err(|_| io::Error::other("offset too big"))?;
 
         crate::syscall!(pwrite@NON_FD(fd, buf as _, len, offset))
     }
@@ -287,8 +362,8 @@ pub(crate) mod impls {
         len: usize,
         offset: u64,
     ) -> io::Result<MaybeFd> {
-        let offset = libc::off_t::try_from(offset)
-            .map_err(|_| io::Error::new(io::ErrorKind::Other, "offset too big"))?;
+        let offset =
+            libc::off_t::try_from(offset).map_err(|_| io::Error::other("offset too big"))?;
 
         crate::syscall!(pwritev@NON_FD(fd, buf_vec as _, len as _, offset))
     }
@@ -0,0 +1,198 @@
+//! This module works only on linux.
+
+use std::{ffi::CString, io};
+
+use super::{super::shared_fd::SharedFd, Op, OpAble};
+#[cfg(any(feature = "legacy", feature = "poll-io"))]
+use super::{driver::ready::Direction, MaybeFd};
+
+// The xattr opcodes(since 5.19), which are not covered by `io_uring::opcode` yet.
+#[cfg(feature = "iouring")]
+const FSETXATTR_CODE: u8 = 41;
+#[cfg(feature = "iouring")]
+const SETXATTR_CODE: u8 = 42;
+#[cfg(feature = "iouring")]
+const FGETXATTR_CODE: u8 = 43;
+#[cfg(feature = "iouring")]
+const GETXATTR_CODE: u8 = 44;
+
+/// The file of the xattr operations.
+pub(crate) enum XattrTarget {
+    Fd(SharedFd),
+    /// The path, which is resolved with the symlinks followed.
+    Path(CString),
+}
+
+/// Build the SQE of the xattr opcodes, where `name` is in `addr`, `value` in `addr2`, the size
+/// in `len`, the flags in `xattr_flags`, and the path in `addr3`.
+#[cfg(feature = "iouring")]
+fn xattr_entry(
+    code: u8,
+    target: &XattrTarget,
+    name: &CString,
+    value: u64,
+    len: u32,
+    flags: i32,
+) -> io_uring::squeue::Entry {
+    let build = |fd: i32, path: u64| {
+        let mut sqe = [0u8; 64];
+        sqe[0] = code;
+        sqe[4..8].copy_from_slice(&fd.to_ne_bytes());
+        sqe[8..16].copy_from_slice(&value.to_ne_bytes());
+        sqe[16..24].copy_from_slice(&(name.as_ptr() as u64).to_ne_bytes());
+        sqe[24..28].copy_from_slice(&len.to_ne_bytes());
+        sqe[28..32].copy_from_slice(&flags.to_ne_bytes());
+        sqe[48..56].copy_from_slice(&path.to_ne_bytes());
+        unsafe { std::mem::transmute::<[u8; 64], io_uring::squeue::Entry>(sqe) }
+    };
+    match target {
+        XattrTarget::Fd(fd) => fd.uring_entry(|fd| build(fd.0, 0)),
+        XattrTarget::Path(path) => build(0, path.as_ptr() as u64),
+    }
+}
+
+pub(crate) struct GetXattr {
+    target: XattrTarget,
+    name: CString,
+    value: Vec<u8>,
+}
+
+impl Op<GetXattr> {
+    /// Get the value into a buffer of `size` bytes. With `size` of 0 it returns the size of
+    /// the value.
+    pub(crate) fn get_xattr(
+        target: XattrTarget,
+        name: CString,
+        size: usize,
+    ) -> io::Result<Op<GetXattr>> {
+        Op::submit_with(GetXattr {
+            target,
+            name,
+            value: Vec::with_capacity(size),
+        })
+    }
+
+    /// Returns the value read, or only its size if the buffer is empty.
+    pub(crate) async fn result(self) -> io::Result<(usize, Vec<u8>)> {
+        let complete = self.await;
+        let n = complete.meta.result?.into_inner() as usize;
+        let mut value = complete.data.value;
+        if value.capacity() != 0 {
+            // Safety: the kernel wrote `n` bytes to the buffer
+            unsafe { value.set_len(n) };
+        }
+        Ok((n, value))
+    }
+}
+
+impl OpAble for GetXattr {
+    #[cfg(feature = "iouring")]
+    const URING_OPCODE: Option<u8> = Some(FGETXATTR_CODE);
+
+    #[cfg(feature = "iouring")]
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        let code = match self.target {
+            XattrTarget::Fd(_) => FGETXATTR_CODE,
+            XattrTarget::Path(_) => GETXATTR_CODE,
+        };
+        xattr_entry(
+            code,
+            &self.target,
+            &self.name,
+            self.value.as_mut_ptr() as u64,
+            self.value.capacity() as u32,
+            0,
+        )
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    #[inline]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        None
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    fn legacy_call(&mut self) -> io::Result<MaybeFd> {
+        let (name, value, size) = (
+            self.name.as_ptr(),
+            self.value.as_mut_ptr().cast(),
+            self.value.capacity(),
+        );
+        match &self.target {
+            XattrTarget::Fd(fd) => {
+                crate::syscall!(fgetxattr@NON_FD(fd.raw_fd(), name, value, size))
+            }
+            XattrTarget::Path(path) => {
+                crate::syscall!(getxattr@NON_FD(path.as_ptr(), name, value, size))
+            }
+        }
+    }
+}
+
+pub(crate) struct SetXattr {
+    target: XattrTarget,
+    name: CString,
+    value: Vec<u8>,
+    flags: i32,
+}
+
+impl Op<SetXattr> {
+    pub(crate) fn set_xattr(
+        target: XattrTarget,
+        name: CString,
+        value: Vec<u8>,
+        flags: i32,
+    ) -> io::Result<Op<SetXattr>> {
+        Op::submit_with(SetXattr {
+            target,
+            name,
+            value,
+            flags,
+        })
+    }
+}
+
+impl OpAble for SetXattr {
+    #[cfg(feature = "iouring")]
+    const URING_OPCODE: Option<u8> = Some(FSETXATTR_CODE);
+
+    #[cfg(feature = "iouring")]
+    fn uring_op(&mut self) -> io_uring::squeue::Entry {
+        let code = match self.target {
+            XattrTarget::Fd(_) => FSETXATTR_CODE,
+            XattrTarget::Path(_) => SETXATTR_CODE,
+        };
+        xattr_entry(
+            code,
+            &self.target,
+            &self.name,
+            self.value.as_ptr() as u64,
+            self.value.len() as u32,
+            self.flags,
+        )
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    #[inline]
+    fn legacy_interest(&self) -> Option<(Direction, usize)> {
+        None
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    fn legacy_call(&mut self) -> io::Result<MaybeFd> {
+        let (name, value, size, flags) = (
+            self.name.as_ptr(),
+            self.value.as_ptr().cast(),
+            self.value.len(),
+            self.flags,
+        );
+        match &self.target {
+            XattrTarget::Fd(fd) => {
+                crate::syscall!(fsetxattr@NON_FD(fd.raw_fd(), name, value, size, flags))
+            }
+            XattrTarget::Path(path) => {
+                crate::syscall!(setxattr@NON_FD(path.as_ptr(), name, value, size, flags))
+            }
+        }
+    }
+}
@@ -1,6 +1,10 @@
 use std::{io, task::Context, time::Duration};
 
-use super::{op::MaybeFd, ready::Direction, scheduled_io::ScheduledIo};
+use super::{
+    op::MaybeFd,
+    ready::{Direction, Ready},
+    scheduled_io::ScheduledIo,
+};
 use crate::{driver::op::CompletionMeta, utils::slab::Slab};
 
 /// Poller with io dispatch.
@@ -71,6 +75,19 @@ impl Poll {
         }
     }
 
+    /// Wake the op waiting on the direction with the canceled readiness.
+    pub(crate) fn cancel(&mut self, token: usize, direction: Direction) {
+        let ready = match direction {
+            Direction::Read => Ready::READ_CANCELED,
+            Direction::Write => Ready::WRITE_CANCELED,
+        };
+        if let Some(mut sio) = self.io_dispatch.get(token) {
+            let ref_mut = sio.as_mut();
+            ref_mut.set_readiness(|curr| curr | ready);
+            ref_mut.wake(ready);
+        }
+    }
+
     #[inline]
     pub(crate) fn poll_syscall(
         &mut self,
@@ -81,7 +98,18 @@ impl Poll {
     ) -> std::task::Poll<CompletionMeta> {
         let mut scheduled_io = self.io_dispatch.get(token).expect("scheduled_io lost");
         let ref_mut = scheduled_io.as_mut();
-        ready!(ref_mut.poll_readiness(cx, direction));
+        let readiness = ready!(ref_mut.poll_readiness(cx, direction));
+
+        // check if canceled
+        if readiness.is_canceled() {
+            // clear CANCELED part only
+            ref_mut.clear_readiness(readiness & Ready::CANCELED);
+            return std::task::Poll::Ready(CompletionMeta {
+                result: Err(io::Error::from_raw_os_error(libc::ECANCELED)),
+                flags: 0,
+            });
+        }
+
         match syscall() {
             Ok(n) => std::task::Poll::Ready(CompletionMeta {
                 result: Ok(n),
@@ -68,7 +68,63 @@ impl Ready {
         ready
     }
 
-    #[cfg(unix)]
+    #[cfg(all(feature = "legacy", any(target_os = "macos", target_os = "freebsd")))]
+    pub(crate) fn from_kqueue(event: &super::legacy::kqueue::Event) -> Ready {
+        let mut ready = Ready::EMPTY;
+
+        if event.is_readable() {
+            ready |= Ready::READABLE;
+        }
+
+        if event.is_writable() {
+            ready |= Ready::WRITABLE;
+        }
+
+        if event.is_read_closed() {
+            ready |= Ready::READ_CLOSED;
+        }
+
+        if event.is_write_closed() {
+            ready |= Ready::WRITE_CLOSED;
+        }
+
+        ready
+    }
+
+    #[cfg(all(target_os = "linux", feature = "legacy-epoll"))]
+    pub(crate) fn from_epoll(event: &super::legacy::epoll::Event) -> Ready {
+        let mut ready = Ready::EMPTY;
+
+        if event.is_readable() {
+            ready |= Ready::READABLE;
+        }
+
+        if event.is_writable() {
+            ready |= Ready::WRITABLE;
+        }
+
+        if event.is_read_closed() {
+            ready |= Ready::READ_CLOSED;
+        }
+
+        if event.is_write_closed() {
+            ready |= Ready::WRITE_CLOSED;
+        }
+
+        ready
+    }
+
+    #[cfg(all(
+        unix,
+        any(
+            feature = "poll-io",
+            not(any(
+                target_os = "macos",
+                target_os = "freebsd",
+                all(target_os = "linux", feature = "legacy-epoll")
+            ))
+        )
+    ))]
     // Must remain crate-private to avoid adding a public dependency on Mio.
     pub(crate) fn from_mio(event: &mio::event::Event) -> Ready {
         let mut ready = Ready::EMPTY;
@@ -22,6 +22,10 @@ struct Inner {
     #[cfg(any(unix, windows))]
     fd: RawFd,
 
+    // Slot in the fixed file table of io_uring, if registered.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fixed: std::cell::Cell<Option<u32>>,
+
     // Waker to notify when the close operation completes.
     state: UnsafeCell<State>,
 }
@@ -31,6 +35,9 @@ enum State {
     Uring(UringState),
     #[cfg(feature = "legacy")]
     Legacy(Option<usize>),
+    /// Associated with the completion port
+    #[cfg(all(windows, feature = "iocp"))]
+    Iocp,
 }
 
 #[cfg(feature = "poll-io")]
@@ -62,10 +69,7 @@ impl State {
                 })?;
             *state = UringState::Legacy(Some(reg));
         } else {
-            return Err(io::Error::new(
-                io::ErrorKind::Other,
-                "not clear uring state",
-            ));
+            return Err(io::Error::other("not clear uring state"));
         }
         Ok(())
     }
@@ -83,7 +87,7 @@ impl State {
             _ => return Ok(()),
         };
         let Some(token) = inner else {
-            return Err(io::Error::new(io::ErrorKind::Other, "empty token"));
+            return Err(io::Error::other("empty token"));
         };
         let mut source = mio::unix::SourceFd(&fd);
         crate::syscall!(fcntl@RAW(fd, libc::F_SETFL, 0))?;
@@ -167,9 +171,11 @@ impl SharedFd {
             Legacy(io::Result<usize>),
         }
 
+        #[cfg(all(target_os = "linux", feature = "iouring", feature = "legacy"))]
+        let force_legacy = FORCE_LEGACY || Self::hybrid_register(fd)?;
         #[cfg(all(target_os = "linux", feature = "iouring", feature = "legacy"))]
         let state = match CURRENT.with(|inner| match inner {
-            super::Inner::Uring(inner) => match FORCE_LEGACY {
+            super::Inner::Uring(inner) => match force_legacy {
                 false => Reg::Uring,
                 true => {
                     #[cfg(feature = "poll-io")]
@@ -200,9 +206,35 @@ impl SharedFd {
             Reg::Legacy(idx) => State::Legacy(Some(idx?)),
         };
 
-        #[cfg(all(not(feature = "legacy"), target_os = "linux", feature = "iouring"))]
+        #[cfg(all(
+            not(feature = "legacy"),
+            target_os = "linux",
+            feature = "iouring",
+            not(feature = "poll-io")
+        ))]
         let state = State::Uring(UringState::Init);
 
+        #[cfg(all(
+            not(feature = "legacy"),
+            target_os = "linux",
+            feature = "iouring",
+            feature = "poll-io"
+        ))]
+        let state = match FORCE_LEGACY || Self::hybrid_register(fd)? {
+            false => State::Uring(UringState::Init),
+            true => {
+                let mut source = mio::unix::SourceFd(&fd);
+                let idx = CURRENT.with(|inner| match inner {
+                    super::Inner::Uring(inner) => super::IoUringDriver::register_poll_io(
+                        inner,
+                        &mut source,
+                        super::ready::RW_INTERESTS,
+                    ),
+                })?;
+                State::Uring(UringState::Legacy(Some(idx)))
+            }
+        };
+
         #[cfg(all(
             unix,
             feature = "legacy",
@@ -234,26 +266,42 @@ impl SharedFd {
         Ok(SharedFd {
             inner: Rc::new(Inner {
                 fd,
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                fixed: std::cell::Cell::new(None),
                 state: UnsafeCell::new(state),
             }),
         })
     }
 
+    /// In hybrid mode the fd is registered to the poller of uring driver, so set it to
+    /// non-blocking. Returns if the fd should be registered.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[inline]
+    fn hybrid_register(_fd: RawFd) -> io::Result<bool> {
+        #[cfg(feature = "poll-io")]
+        if CURRENT.with(|inner| inner.is_hybrid()) {
+            crate::syscall!(fcntl@RAW(_fd, libc::F_SETFL, libc::O_NONBLOCK))?;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
     #[cfg(windows)]
     pub(crate) fn new<const FORCE_LEGACY: bool>(fd: RawSocket) -> io::Result<SharedFd> {
         const RW_INTERESTS: mio::Interest = mio::Interest::READABLE.add(mio::Interest::WRITABLE);
 
         let mut fd = RawFd::new(fd);
 
-        let state = {
-            let reg = CURRENT.with(|inner| match inner {
-                super::Inner::Legacy(inner) => {
-                    super::legacy::LegacyDriver::register(inner, &mut fd, RW_INTERESTS)
-                }
-            });
-
-            State::Legacy(Some(reg?))
-        };
+        let state = CURRENT.with(|inner| match inner {
+            super::Inner::Legacy(inner) => {
+                super::legacy::LegacyDriver::register(inner, &mut fd, RW_INTERESTS)
+                    .map(|idx| State::Legacy(Some(idx)))
+            }
+            #[cfg(feature = "iocp")]
+            super::Inner::Iocp(inner) => {
+                super::IocpDriver::register(inner, fd.socket).map(|_| State::Iocp)
+            }
+        })?;
 
         #[allow(unreachable_code)]
         Ok(SharedFd {
@@ -284,6 +332,8 @@ impl SharedFd {
         SharedFd {
             inner: Rc::new(Inner {
                 fd,
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                fixed: std::cell::Cell::new(None),
                 state: UnsafeCell::new(state),
             }),
         }
@@ -294,6 +344,10 @@ impl SharedFd {
     pub(crate) fn new_without_register(fd: RawSocket) -> SharedFd {
         let state = CURRENT.with(|inner| match inner {
             super::Inner::Legacy(_) => State::Legacy(None),
+            // The overlapped operations can not be started on it, so they are executed with
+            // syscalls.
+            #[cfg(feature = "iocp")]
+            super::Inner::Iocp(_) => State::Legacy(None),
         });
 
         SharedFd {
@@ -332,6 +386,10 @@ impl SharedFd {
             Ok(inner) => {
                 // Only drop Inner's state, skip its drop impl.
                 let mut inner_skip_drop = ManuallyDrop::new(inner);
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                if let Some(idx) = inner_skip_drop.fixed.get() {
+                    release_fixed(idx);
+                }
                 #[allow(invalid_value)]
                 #[allow(clippy::uninit_assumed_init)]
                 let mut state = unsafe { MaybeUninit::uninit().assume_init() };
@@ -377,7 +435,9 @@ impl SharedFd {
     pub(crate) fn try_unwrap(self) -> Result<RawSocket, Self> {
         match Rc::try_unwrap(self.inner) {
             Ok(_inner) => {
-                let mut fd = _inner.fd;
+                // Only take Inner's fd, skip its drop impl.
+                let _inner = std::mem::ManuallyDrop::new(_inner);
+                let mut fd = unsafe { std::ptr::read(&_inner.fd) };
                 let state = unsafe { &*_inner.state.get() };
 
                 #[allow(irrefutable_let_patterns)]
@@ -393,6 +453,8 @@ impl SharedFd {
                                         );
                                     }
                                 }
+                                #[cfg(feature = "iocp")]
+                                super::Inner::Iocp(_) => {}
                             }
                         })
                     }
@@ -413,6 +475,8 @@ impl SharedFd {
             State::Uring(_) => None,
             #[cfg(feature = "legacy")]
             State::Legacy(s) => *s,
+            #[cfg(all(windows, feature = "iocp"))]
+            State::Iocp => None,
             #[cfg(all(
                 not(feature = "legacy"),
                 not(all(target_os = "linux", feature = "iouring"))
@@ -423,6 +487,64 @@ impl SharedFd {
         }
     }
 
+    /// The socket to start the overlapped operations on, if it is associated with the completion
+    /// port.
+    #[cfg(all(windows, feature = "iocp"))]
+    #[inline]
+    pub(crate) fn iocp_socket(&self) -> Option<RawSocket> {
+        let state = unsafe { &*self.inner.state.get() };
+        matches!(state, State::Iocp).then_some(self.inner.fd.socket)
+    }
+
+    /// Slot in the fixed file table, if the fd is registered.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[inline]
+    pub(crate) fn fixed_index(&self) -> Option<u32> {
+        self.inner.fixed.get()
+    }
+
+    /// Register the fd to the fixed file table of io_uring and return the slot.
+    /// The slot is released when the fd is closed.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    pub(crate) async fn register_fixed(&self) -> io::Result<u32> {
+        if let Some(idx) = self.inner.fixed.get() {
+            return Ok(idx);
+        }
+        if super::op::is_legacy() {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "fixed file table is only supported by io_uring driver",
+            ));
+        }
+        let idx = super::op::Op::register_file(self.inner.fd)?
+            .result()
+            .await?;
+        // Registered concurrently, keep the first one.
+        if let Some(prev) = self.inner.fixed.get() {
+            release_fixed(idx);
+            return Ok(prev);
+        }
+        self.inner.fixed.set(Some(idx));
+        Ok(idx)
+    }
+
+    /// Build an io_uring entry on the fd. If the fd is registered, the entry refers to the
+    /// slot with `IOSQE_FIXED_FILE` instead.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    #[inline]
+    pub(crate) fn uring_entry(
+        &self,
+        f: impl FnOnce(io_uring::types::Fd) -> io_uring::squeue::Entry,
+    ) -> io_uring::squeue::Entry {
+        use io_uring::{squeue::Flags, types::Fd};
+
+        match self.inner.fixed.get() {
+            // A fixed target is the slot index in the fd field with the flag set.
+            Some(idx) => f(Fd(idx as _)).flags(Flags::FIXED_FILE),
+            None => f(Fd(self.inner.fd)),
+        }
+    }
+
     /// An FD cannot be closed until all in-flight operation have completed.
     /// This prevents bugs where in-flight reads could operate on the incorrect
     /// file descriptor.
@@ -440,6 +562,7 @@ impl SharedFd {
                         Ok(op) => UringState::Closing(op),
                         Err(_) => {
                             let _ = unsafe { std::fs::File::from_raw_fd(fd) };
+                            *uring_state = UringState::Closed;
                             return;
                         }
                     };
@@ -523,14 +646,18 @@ impl Inner {
 impl Drop for Inner {
     fn drop(&mut self) {
         let fd = self.fd;
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        if let Some(idx) = self.fixed.get() {
+            release_fixed(idx);
+        }
         let state = unsafe { &mut *self.state.get() };
         #[allow(unreachable_patterns)]
         match state {
             #[cfg(all(target_os = "linux", feature = "iouring"))]
-            State::Uring(UringState::Init) | State::Uring(UringState::Waiting(..)) => {
-                if super::op::Op::close(fd).is_err() {
-                    let _ = unsafe { std::fs::File::from_raw_fd(fd) };
-                };
+            State::Uring(UringState::Init) | State::Uring(UringState::Waiting(..))
+                if super::op::Op::close(fd).is_err() =>
+            {
+                let _ = unsafe { std::fs::File::from_raw_fd(fd) };
             }
             #[cfg(feature = "legacy")]
             State::Legacy(idx) => drop_legacy(fd, *idx),
@@ -541,6 +668,22 @@ impl Drop for Inner {
     }
 }
 
+#[cfg(all(windows, feature = "iocp"))]
+impl Drop for Inner {
+    fn drop(&mut self) {
+        if let State::Iocp = unsafe { &*self.state.get() } {
+            let _ = unsafe { OwnedSocket::from_raw_socket(self.fd.socket) };
+        }
+    }
+}
+
+/// Remove the fd from the fixed file table. If the runtime is gone, the table has been
+/// released with the ring.
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+fn release_fixed(idx: u32) {
+    let _ = super::with_uring_submitter(|s| s.register_files_update(idx, &[-1]));
+}
+
 #[allow(unused_mut)]
 #[cfg(feature = "legacy")]
 fn drop_legacy(mut fd: RawFd, idx: Option<usize>) {
@@ -564,6 +707,9 @@ fn drop_legacy(mut fd: RawFd, idx: Option<usize>) {
                         let _ = super::legacy::LegacyDriver::deregister(inner, idx, &mut fd);
                     }
                 }
+                // Not registered to the poller, see `SharedFd::new_without_register`.
+                #[cfg(all(windows, feature = "iocp"))]
+                super::Inner::Iocp(_) => {}
             }
         })
     }
@@ -0,0 +1,106 @@
+//! Latency recording of the uring operations.
+
+use std::{
+    collections::HashMap,
+    os::unix::prelude::RawFd,
+    time::{Duration, Instant},
+};
+
+use io_uring::squeue;
+
+use crate::{
+    builder::UringOpts,
+    runtime::{
+        metrics::{SlowOp, SlowOpCallback},
+        LatencyHistogram,
+    },
+};
+
+/// Records the time from pushing the SQE to reaping its CQE, per opcode.
+pub(crate) struct OpLatency {
+    pending: HashMap<usize, Pending>,
+    histograms: HashMap<u8, LatencyHistogram>,
+    slow_threshold: Option<Duration>,
+    slow_callback: Option<SlowOpCallback>,
+}
+
+struct Pending {
+    start: Instant,
+    opcode: u8,
+    fd: RawFd,
+}
+
+impl OpLatency {
+    pub(crate) fn new(opts: &UringOpts) -> Self {
+        OpLatency {
+            pending: HashMap::new(),
+            histograms: HashMap::new(),
+            slow_threshold: opts.slow_op_threshold,
+            slow_callback: opts.slow_op_callback.clone(),
+        }
+    }
+
+    /// Start timing the SQE pushed with the user_data `index`.
+    pub(crate) fn submitted<E: squeue::EntryMarker>(&mut self, index: usize, sqe: &E) {
+        let (opcode, fd) = super::sqe_info(sqe);
+        self.pending.insert(
+            index,
+            Pending {
+                start: Instant::now(),
+                opcode,
+                fd,
+            },
+        );
+    }
+
+    /// Record the completion of the operation. For the multishot operations each completion is
+    /// recorded, timed from the previous one.
+    pub(crate) fn completed(&mut self, index: usize, more: bool) {
+        let now = Instant::now();
+        let pending = if more {
+            match self.pending.get_mut(&index) {
+                Some(pending) => {
+                    let start = std::mem::replace(&mut pending.start, now);
+                    Pending {
+                        start,
+                        opcode: pending.opcode,
+                        fd: pending.fd,
+                    }
+                }
+                None => return,
+            }
+        } else {
+            match self.pending.remove(&index) {
+                Some(pending) => pending,
+                None => return,
+            }
+        };
+        let latency = now.saturating_duration_since(pending.start);
+        self.histograms
+            .entry(pending.opcode)
+            .or_insert_with(LatencyHistogram::new)
+            .record(latency);
+        if self.slow_threshold.is_some_and(|t| latency >= t) {
+            let op = SlowOp {
+                opcode: pending.opcode,
+                fd: pending.fd,
+                latency,
+            };
+            #[cfg(feature = "tracing")]
+            tracing::warn!(op.opcode, op.fd, ?op.latency, "slow io_uring op");
+            if let Some(callback) = self.slow_callback.as_ref() {
+                (callback.0)(&op);
+            }
+        }
+    }
+
+    pub(crate) fn histograms(&self) -> Vec<(u8, LatencyHistogram)> {
+        let mut histograms: Vec<_> = self
+            .histograms
+            .iter()
+            .map(|(opcode, histogram)| (*opcode, histogram.clone()))
+            .collect();
+        histograms.sort_unstable_by_key(|(opcode, _)| *opcode);
+        histograms
+    }
+}
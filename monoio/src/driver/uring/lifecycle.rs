@@ -2,10 +2,13 @@
 //! Partly borrow from tokio-uring.
 
 use std::{
+    collections::VecDeque,
     io,
     task::{Context, Poll, Waker},
 };
 
+use io_uring::cqueue;
+
 use crate::{
     driver::op::{CompletionMeta, MaybeFd},
     utils::slab::Ref,
@@ -25,10 +28,19 @@ enum Lifecycle {
 
     /// The operation has completed.
     Completed(io::Result<MaybeFd>, u32),
+
+    /// The multishot operation has produced completions which are not consumed yet. Only the
+    /// last one may come without `IORING_CQE_F_MORE`, which means the operation is terminated.
+    CompletionList(VecDeque<(io::Result<MaybeFd>, u32)>),
 }
 
 pub(crate) struct MaybeFdLifecycle {
     is_fd: bool,
+    /// The big part of the last CQE, which is only set on the ring with `IORING_SETUP_CQE32`.
+    pub(crate) big_cqe: [u64; 2],
+    /// The opcode of the op, kept for the completion event.
+    #[cfg(feature = "tracing")]
+    pub(crate) opcode: u8,
     lifecycle: Lifecycle,
 }
 
@@ -37,6 +49,9 @@ impl MaybeFdLifecycle {
     pub(crate) const fn new(is_fd: bool) -> Self {
         Self {
             is_fd,
+            big_cqe: [0; 2],
+            #[cfg(feature = "tracing")]
+            opcode: 0,
             lifecycle: Lifecycle::Submitted,
         }
     }
@@ -47,20 +62,34 @@ impl Ref<'_, MaybeFdLifecycle> {
     // Caller must make sure the result is valid since it may contain fd or a length hint.
     pub(crate) unsafe fn complete(mut self, result: io::Result<u32>, flags: u32) {
         let result = MaybeFd::new_result(result, self.is_fd);
+        let more = cqueue::more(flags);
         let ref_mut = &mut self.lifecycle;
         match ref_mut {
+            Lifecycle::Submitted if more => {
+                *ref_mut = Lifecycle::CompletionList(VecDeque::from([(result, flags)]));
+            }
             Lifecycle::Submitted => {
                 *ref_mut = Lifecycle::Completed(result, flags);
             }
             Lifecycle::Waiting(_) => {
-                let old = std::mem::replace(ref_mut, Lifecycle::Completed(result, flags));
-                match old {
+                let new = if more {
+                    Lifecycle::CompletionList(VecDeque::from([(result, flags)]))
+                } else {
+                    Lifecycle::Completed(result, flags)
+                };
+                match std::mem::replace(ref_mut, new) {
                     Lifecycle::Waiting(waker) => {
                         waker.wake();
                     }
                     _ => std::hint::unreachable_unchecked(),
                 }
             }
+            Lifecycle::CompletionList(list) => {
+                list.push_back((result, flags));
+            }
+            // The multishot operation is still alive, drop the result(the fd will be closed)
+            // and wait for the terminating completion.
+            Lifecycle::Ignored(..) if more => {}
             Lifecycle::Ignored(..) => {
                 self.remove();
             }
@@ -91,6 +120,40 @@ impl Ref<'_, MaybeFdLifecycle> {
         }
     }
 
+    /// Poll the next completion of a multishot operation. The slab entry is only removed when
+    /// the terminating completion(without `IORING_CQE_F_MORE`) is taken.
+    pub(crate) fn poll_multishot_op(mut self, cx: &mut Context<'_>) -> Poll<CompletionMeta> {
+        let ref_mut = &mut self.lifecycle;
+        match ref_mut {
+            Lifecycle::Submitted => {
+                *ref_mut = Lifecycle::Waiting(cx.waker().clone());
+                Poll::Pending
+            }
+            Lifecycle::Waiting(waker) => {
+                if !waker.will_wake(cx.waker()) {
+                    *ref_mut = Lifecycle::Waiting(cx.waker().clone());
+                }
+                Poll::Pending
+            }
+            Lifecycle::CompletionList(list) => {
+                let (result, flags) = unsafe { list.pop_front().unwrap_unchecked() };
+                if !cqueue::more(flags) {
+                    self.remove();
+                } else if list.is_empty() {
+                    *ref_mut = Lifecycle::Submitted;
+                }
+                Poll::Ready(CompletionMeta { result, flags })
+            }
+            Lifecycle::Completed(..) => match self.remove().lifecycle {
+                Lifecycle::Completed(result, flags) => {
+                    Poll::Ready(CompletionMeta { result, flags })
+                }
+                _ => unsafe { std::hint::unreachable_unchecked() },
+            },
+            Lifecycle::Ignored(..) => unsafe { std::hint::unreachable_unchecked() },
+        }
+    }
+
     // return if the op must has been finished
     pub(crate) fn drop_op<T: 'static>(mut self, data: &mut Option<T>) -> bool {
         let ref_mut = &mut self.lifecycle;
@@ -104,6 +167,14 @@ impl Ref<'_, MaybeFdLifecycle> {
                 };
                 return false;
             }
+            Lifecycle::CompletionList(list) => {
+                // The terminating completion is always the last one.
+                if list.back().is_some_and(|(_, flags)| cqueue::more(*flags)) {
+                    *ref_mut = Lifecycle::Ignored(Box::new(data.take()));
+                    return false;
+                }
+                self.remove();
+            }
             Lifecycle::Completed(..) => {
                 self.remove();
             }
@@ -4,14 +4,15 @@ use std::{
     cell::UnsafeCell,
     io,
     mem::ManuallyDrop,
-    os::unix::prelude::{AsRawFd, RawFd},
+    os::unix::prelude::{AsRawFd, FromRawFd, OwnedFd, RawFd},
     rc::Rc,
     task::{Context, Poll},
     time::Duration,
 };
 
-use io_uring::{cqueue, opcode, types::Timespec, IoUring};
+use io_uring::{cqueue, opcode, squeue, types::Timespec, IoUring};
 use lifecycle::MaybeFdLifecycle;
+use ring::Ring;
 
 use super::{
     op::{CompletionMeta, Op, OpAble},
@@ -22,9 +23,11 @@ use super::{
     Inner,
     CURRENT,
 };
-use crate::utils::slab::Slab;
+use crate::{builder::UringOpts, runtime::LatencyHistogram, utils::slab::Slab};
 
+mod latency;
 mod lifecycle;
+mod ring;
 #[cfg(feature = "sync")]
 mod waker;
 #[cfg(feature = "sync")]
@@ -37,8 +40,134 @@ pub(crate) const TIMEOUT_USERDATA: u64 = u64::MAX - 1;
 pub(crate) const EVENTFD_USERDATA: u64 = u64::MAX - 2;
 #[cfg(feature = "poll-io")]
 pub(crate) const POLLER_USERDATA: u64 = u64::MAX - 3;
+#[cfg(feature = "sync")]
+pub(crate) const MSG_RING_USERDATA: u64 = u64::MAX - 4;
+
+pub(crate) const MIN_REVERSED_USERDATA: u64 = u64::MAX - 4;
+
+/// Set on the user_data of the MSG_RING completions carrying a waker(see
+/// `UnparkHandle::send_waker`), the other bits are the pointer of the message.
+#[cfg(feature = "sync")]
+pub(crate) const MSG_WAKER_FLAG: u64 = 1 << 63;
+/// Set on the user_data of the completion of the MSG_RING sent by us.
+#[cfg(feature = "sync")]
+pub(crate) const MSG_SENT_FLAG: u64 = 1 << 62;
+
+/// Index of the ops not submitted to the ring(see `UringInner::skip_ring`), which are executed
+/// with syscalls on `poll_op` instead.
+#[cfg(any(feature = "legacy", feature = "poll-io"))]
+pub(crate) const POLL_INDEX: usize = usize::MAX - 1;
+
+/// What to do when the in-flight operations may overflow the completion queue.
+///
+/// The kernel(5.5+, `IORING_FEAT_NODROP`) keeps the completions overflowed in a backlog, which
+/// is flushed to the CQ when the runtime asks for completions, so no completion is lost. But the
+/// backlog is slow to post to and flush, which degrades the latency. The CQ can not grow once the
+/// ring is created, set a larger one with
+/// [`RuntimeBuilder::uring_cq_entries`](crate::RuntimeBuilder::uring_cq_entries) instead.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CqOverflowPolicy {
+    /// Submit without limit and flush the overflowed completions when detected.
+    #[default]
+    Flush,
+    /// Wait for completions before submitting when the in-flight operations may overflow the
+    /// CQ. The thread blocks while waiting, so it must not be used if the in-flight operations
+    /// depend on the operations being submitted(e.g. accepting a connection made by the same
+    /// thread).
+    Backpressure,
+    /// Fail the operations with `EBUSY` when the in-flight operations may overflow the CQ.
+    Error,
+}
+
+/// When the uring driver submits the queued SQEs to the kernel. Besides the policy, the SQEs are
+/// always submitted when the SQ is full and when the runtime parks, and can be submitted early
+/// with [`flush_submissions`](crate::flush_submissions).
+///
+/// Batching more SQEs into one `io_uring_enter` saves syscalls, at the cost of the latency of
+/// the SQEs waiting in the queue.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SubmitPolicy {
+    /// Submit when the runtime polls the driver between rounds of tasks, and when it parks.
+    #[default]
+    Default,
+    /// Submit once the number of queued SQEs reaches the value.
+    Batch(u32),
+    /// Submit once the SQ is the percent full(1 to 100).
+    Ratio(u8),
+    /// Submit only when the runtime parks.
+    Park,
+}
 
-pub(crate) const MIN_REVERSED_USERDATA: u64 = u64::MAX - 3;
+impl SubmitPolicy {
+    fn threshold(self, entries: u32) -> Option<usize> {
+        let threshold = match self {
+            SubmitPolicy::Batch(n) => n.min(entries),
+            SubmitPolicy::Ratio(percent) => (entries * percent.min(100) as u32).div_ceil(100),
+            SubmitPolicy::Default | SubmitPolicy::Park => return None,
+        };
+        Some(threshold.max(1) as usize)
+    }
+}
+
+/// A restriction registered to the ring with `IORING_REGISTER_RESTRICTIONS`, see
+/// [`RuntimeBuilder::uring_restrictions`](crate::RuntimeBuilder::uring_restrictions).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Restriction {
+    /// Allow the opcode(the `CODE` of types in [`io_uring::opcode`]).
+    Opcode(u8),
+    /// Allow the `io_uring_register` opcode(`IORING_REGISTER_*`).
+    Register(u8),
+    /// Allow the SQE flags(the bits of [`io_uring::squeue::Flags`]).
+    SqeFlagsAllowed(u8),
+    /// Require the SQE flags on all SQEs.
+    SqeFlagsRequired(u8),
+}
+
+/// CQ entries kept for the completions not counted as in-flight operations(e.g. eventfd,
+/// poller, timeout and cancellation).
+const CQ_RESERVED: usize = 8;
+
+/// Opcodes supported by the kernel, probed when the driver is created.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Opcodes(Option<[u64; 4]>);
+
+impl Opcodes {
+    fn probe(uring: &Ring) -> Self {
+        let mut probe = io_uring::Probe::new();
+        match uring.submitter().register_probe(&mut probe) {
+            Ok(_) => {
+                let mut bits = [0; 4];
+                for opcode in 0..=u8::MAX {
+                    if probe.is_supported(opcode) {
+                        bits[opcode as usize / 64] |= 1 << (opcode % 64);
+                    }
+                }
+                Self(Some(bits))
+            }
+            // Probing requires kernel 5.6+, assume everything is supported.
+            Err(_) => Self(None),
+        }
+    }
+
+    /// Keep only the opcodes allowed.
+    fn restrict(&mut self, allowed: &[u8]) {
+        let mut bits = [0; 4];
+        for &opcode in allowed {
+            if self.is_supported(opcode) {
+                bits[opcode as usize / 64] |= 1 << (opcode % 64);
+            }
+        }
+        self.0 = Some(bits);
+    }
+
+    #[inline]
+    pub(crate) fn is_supported(&self, opcode: u8) -> bool {
+        match self.0 {
+            Some(bits) => bits[opcode as usize / 64] & (1 << (opcode % 64)) != 0,
+            None => true,
+        }
+    }
+}
 
 /// Driver with uring.
 pub struct IoUringDriver {
@@ -64,9 +193,12 @@ pub(crate) struct UringInner {
     poll: super::poll::Poll,
     #[cfg(feature = "poll-io")]
     poller_installed: bool,
+    // Drive the ops on fds registered to the poller by readiness
+    #[cfg(feature = "poll-io")]
+    hybrid: bool,
 
     /// IoUring bindings
-    uring: ManuallyDrop<IoUring>,
+    uring: ManuallyDrop<Ring>,
 
     /// Shared waker
     #[cfg(feature = "sync")]
@@ -82,6 +214,190 @@ pub(crate) struct UringInner {
 
     // Uring support ext_arg
     ext_arg: bool,
+
+    // Index of the ring fd registered with `IORING_REGISTER_RING_FDS`
+    ring_index: Option<u32>,
+
+    // Eventfd registered with `IORING_REGISTER_EVENTFD`, created on demand
+    completion_eventfd: Option<OwnedFd>,
+
+    // Opcodes supported by the kernel
+    opcodes: Opcodes,
+
+    // What to do when the CQ may overflow
+    cq_overflow: CqOverflowPolicy,
+
+    // Number of operations submitted and not completed
+    in_flight: usize,
+    // Most entries observed in the SQ and CQ
+    sq_high_water: usize,
+    cq_high_water: usize,
+
+    // Number of queued SQEs to submit at, None if they are not submitted on pushing
+    submit_threshold: Option<usize>,
+    // If `Driver::submit` submits the queued SQEs
+    submit_on_poll: bool,
+
+    // How the completion work is run
+    taskrun: TaskRun,
+
+    // Latency of the ops, recorded when enabled
+    op_latency: Option<Box<latency::OpLatency>>,
+
+    // Leak the ops on drop, set if they are still in flight when the runtime shuts down
+    leak_ops: bool,
+}
+
+/// How the kernel runs the completion work.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TaskRun {
+    /// Run with task work, which interrupts the thread.
+    Default,
+    /// `IORING_SETUP_COOP_TASKRUN` with `IORING_SETUP_TASKRUN_FLAG`: run at the next
+    /// transition into the kernel; `IORING_SQ_TASKRUN` is set when there is work pending.
+    Coop,
+    /// `IORING_SETUP_DEFER_TASKRUN`: run only when asking for completions with
+    /// `IORING_ENTER_GETEVENTS`.
+    Defer,
+}
+
+/// `IORING_ENTER_GETEVENTS`
+const ENTER_GETEVENTS: u32 = 1;
+/// `IORING_ENTER_SQ_WAKEUP`
+const ENTER_SQ_WAKEUP: u32 = 1 << 1;
+/// `IORING_ENTER_EXT_ARG`
+const ENTER_EXT_ARG: u32 = 1 << 3;
+/// `IORING_ENTER_REGISTERED_RING`
+const ENTER_REGISTERED_RING: u32 = 1 << 4;
+/// `IORING_REGISTER_RING_FDS`
+const REGISTER_RING_FDS: libc::c_uint = 20;
+/// `IORING_UNREGISTER_RING_FDS`
+const UNREGISTER_RING_FDS: libc::c_uint = 21;
+
+/// `struct io_uring_rsrc_update`
+#[repr(C)]
+struct RsrcUpdate {
+    offset: u32,
+    resv: u32,
+    data: u64,
+}
+
+/// `struct io_uring_getevents_arg`
+#[repr(C)]
+struct GetEventsArg {
+    sigmask: u64,
+    sigmask_sz: u32,
+    pad: u32,
+    ts: u64,
+}
+
+/// Register the ring fd to the thread(requires kernel 5.18+), returns the registered index.
+fn register_ring_fd(fd: RawFd) -> Option<u32> {
+    let mut update = RsrcUpdate {
+        offset: u32::MAX,
+        resv: 0,
+        data: fd as u64,
+    };
+    let ret = unsafe {
+        libc::syscall(
+            libc::SYS_io_uring_register,
+            fd,
+            REGISTER_RING_FDS,
+            &mut update as *mut RsrcUpdate,
+            1,
+        )
+    };
+    (ret == 1).then_some(update.offset)
+}
+
+fn unregister_ring_fd(fd: RawFd, index: u32) {
+    let mut update = RsrcUpdate {
+        offset: index,
+        resv: 0,
+        data: 0,
+    };
+    unsafe {
+        libc::syscall(
+            libc::SYS_io_uring_register,
+            fd,
+            UNREGISTER_RING_FDS,
+            &mut update as *mut RsrcUpdate,
+            1,
+        )
+    };
+}
+
+/// Build the ring with the optional setup flags in `opts`. If the kernel rejects them with
+/// `EINVAL`, the flags are dropped one by one from the newest and the build is retried.
+///
+/// The big ring is built from a new builder, since `urb` is typed for the normal entries.
+fn build_uring(
+    urb: &io_uring::Builder,
+    entries: u32,
+    opts: &UringOpts,
+) -> io::Result<(Ring, TaskRun)> {
+    fn setup<S: squeue::EntryMarker, C: cqueue::EntryMarker>(
+        b: &mut io_uring::Builder<S, C>,
+        opts: &UringOpts,
+        (coop, single, defer): (bool, bool, bool),
+    ) {
+        if coop {
+            b.setup_coop_taskrun().setup_taskrun_flag();
+        }
+        if single || defer {
+            b.setup_single_issuer();
+        }
+        if opts.restrictions.is_some() {
+            b.setup_r_disabled();
+        }
+        if defer {
+            b.setup_defer_taskrun();
+        }
+        if opts.iopoll {
+            b.setup_iopoll();
+        }
+    }
+
+    let (mut coop, mut single, mut defer) =
+        (opts.coop_taskrun, opts.single_issuer, opts.defer_taskrun);
+    loop {
+        let flags = (coop, single, defer);
+        let ring = if opts.big_entries {
+            let mut b = IoUring::<squeue::Entry128, cqueue::Entry32>::builder();
+            if let Some((idle_ms, cpu)) = opts.sqpoll {
+                b.setup_sqpoll(idle_ms);
+                if let Some(cpu) = cpu {
+                    b.setup_sqpoll_cpu(cpu);
+                }
+            }
+            if let Some(cq_entries) = opts.cq_entries {
+                b.setup_cqsize(cq_entries);
+            }
+            setup(&mut b, opts, flags);
+            b.build(entries).map(Ring::Big)
+        } else {
+            let mut b = urb.clone();
+            setup(&mut b, opts, flags);
+            b.build(entries).map(Ring::Normal)
+        };
+        match ring {
+            Ok(uring) => {
+                let taskrun = match (defer, coop) {
+                    (true, _) => TaskRun::Defer,
+                    (false, true) => TaskRun::Coop,
+                    (false, false) => TaskRun::Default,
+                };
+                return Ok((uring, taskrun));
+            }
+            // Since 6.1
+            Err(e) if e.raw_os_error() == Some(libc::EINVAL) && defer => defer = false,
+            // Since 6.0
+            Err(e) if e.raw_os_error() == Some(libc::EINVAL) && single => single = false,
+            // Since 5.19
+            Err(e) if e.raw_os_error() == Some(libc::EINVAL) && coop => coop = false,
+            Err(e) => return Err(e),
+        }
+    }
 }
 
 // When dropping the driver, all in-flight operations must have completed. This
@@ -93,25 +409,75 @@ struct Ops {
 impl IoUringDriver {
     const DEFAULT_ENTRIES: u32 = 1024;
 
-    pub(crate) fn new(b: &io_uring::Builder) -> io::Result<IoUringDriver> {
-        Self::new_with_entries(b, Self::DEFAULT_ENTRIES)
+    pub(crate) fn new(b: &io_uring::Builder, opts: &UringOpts) -> io::Result<IoUringDriver> {
+        Self::new_with_entries(b, Self::DEFAULT_ENTRIES, opts)
+    }
+
+    /// Apply the settings which need a created ring.
+    pub(crate) fn apply_opts(&self, opts: &UringOpts) -> io::Result<()> {
+        let inner = unsafe { &mut *self.inner.get() };
+        if let Some(nr) = opts.fixed_files {
+            inner.uring.submitter().register_files_sparse(nr)?;
+        }
+        if let Some(mut max) = opts.iowq_max_workers {
+            inner
+                .uring
+                .submitter()
+                .register_iowq_max_workers(&mut max)?;
+        }
+        if let Some(cpus) = opts.iowq_affinity.as_ref() {
+            let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+            for &cpu in cpus {
+                if cpu >= libc::CPU_SETSIZE as usize {
+                    return Err(io::Error::from_raw_os_error(libc::EINVAL));
+                }
+                unsafe { libc::CPU_SET(cpu, &mut set) };
+            }
+            inner.uring.submitter().register_iowq_aff(&set)?;
+        }
+        if opts.register_ring_fd {
+            inner.ring_index = register_ring_fd(inner.uring.as_raw_fd());
+        }
+        // The ring is created disabled, restrict it and enable it at last.
+        if let Some(restrictions) = opts.restrictions.as_ref() {
+            inner.restrict(restrictions)?;
+        }
+        Ok(())
     }
 
     #[cfg(not(feature = "sync"))]
     pub(crate) fn new_with_entries(
         urb: &io_uring::Builder,
         entries: u32,
+        opts: &UringOpts,
     ) -> io::Result<IoUringDriver> {
-        let uring = ManuallyDrop::new(urb.build(entries)?);
+        let (uring, taskrun) = build_uring(urb, entries, opts)?;
+        let uring = ManuallyDrop::new(uring);
 
         let inner = Rc::new(UnsafeCell::new(UringInner {
             #[cfg(feature = "poll-io")]
             poll: super::poll::Poll::with_capacity(entries as usize)?,
             #[cfg(feature = "poll-io")]
             poller_installed: false,
+            #[cfg(feature = "poll-io")]
+            hybrid: opts.hybrid,
             ops: Ops::new(),
             ext_arg: uring.params().is_feature_ext_arg(),
+            ring_index: None,
+            completion_eventfd: None,
+            opcodes: Opcodes::probe(&uring),
+            cq_overflow: opts.cq_overflow,
+            in_flight: 0,
+            sq_high_water: 0,
+            cq_high_water: 0,
+            submit_threshold: opts.submit_policy.threshold(uring.params().sq_entries()),
+            submit_on_poll: opts.submit_policy == SubmitPolicy::Default,
+            taskrun,
             uring,
+            op_latency: opts
+                .op_latency
+                .then(|| Box::new(latency::OpLatency::new(opts))),
+            leak_ops: false,
         }));
 
         Ok(IoUringDriver {
@@ -124,8 +490,10 @@ impl IoUringDriver {
     pub(crate) fn new_with_entries(
         urb: &io_uring::Builder,
         entries: u32,
+        opts: &UringOpts,
     ) -> io::Result<IoUringDriver> {
-        let uring = ManuallyDrop::new(urb.build(entries)?);
+        let (uring, taskrun) = build_uring(urb, entries, opts)?;
+        let uring = ManuallyDrop::new(uring);
 
         // Create eventfd and register it to the ring.
         let waker = {
@@ -137,16 +505,37 @@ impl IoUringDriver {
         };
 
         let (waker_sender, waker_receiver) = flume::unbounded::<std::task::Waker>();
+        let opcodes = Opcodes::probe(&uring);
+        // Wake the ring with MSG_RING instead of eventfd when we can.
+        let ring_fd = opcodes
+            .is_supported(opcode::MsgRingData::CODE)
+            .then(|| uring.as_raw_fd());
 
         let inner = Rc::new(UnsafeCell::new(UringInner {
             #[cfg(feature = "poll-io")]
             poller_installed: false,
             #[cfg(feature = "poll-io")]
             poll: super::poll::Poll::with_capacity(entries as usize)?,
+            #[cfg(feature = "poll-io")]
+            hybrid: opts.hybrid,
             ops: Ops::new(),
             ext_arg: uring.params().is_feature_ext_arg(),
+            ring_index: None,
+            completion_eventfd: None,
+            opcodes,
+            cq_overflow: opts.cq_overflow,
+            in_flight: 0,
+            sq_high_water: 0,
+            cq_high_water: 0,
+            submit_threshold: opts.submit_policy.threshold(uring.params().sq_entries()),
+            submit_on_poll: opts.submit_policy == SubmitPolicy::Default,
+            taskrun,
             uring,
-            shared_waker: std::sync::Arc::new(waker::EventWaker::new(waker)),
+            op_latency: opts
+                .op_latency
+                .then(|| Box::new(latency::OpLatency::new(opts))),
+            leak_ops: false,
+            shared_waker: std::sync::Arc::new(waker::EventWaker::new(waker, ring_fd)),
             eventfd_installed: false,
             waker_receiver,
         }));
@@ -226,8 +615,10 @@ impl IoUringDriver {
         #[cfg(feature = "sync")]
         {
             // Process foreign wakers
+            let mut foreign = 0;
             while let Ok(w) = inner.waker_receiver.try_recv() {
                 w.wake();
+                foreign += 1;
                 need_wait = false;
             }
 
@@ -242,11 +633,17 @@ impl IoUringDriver {
             // Process foreign wakers left
             while let Ok(w) = inner.waker_receiver.try_recv() {
                 w.wake();
+                foreign += 1;
                 need_wait = false;
             }
+            crate::runtime::metrics::foreign_wakeups(foreign);
         }
 
-        if need_wait {
+        // The polled IO completes only when the ring is polled, and the eventfd, poller and
+        // timeout can not be installed, so busy poll instead of waiting.
+        if need_wait && inner.uring.params().is_setup_iopoll() {
+            inner.submit_and_wait(0)?;
+        } else if need_wait {
             // Install timeout and eventfd for unpark if sync is enabled
 
             // 1. alloc spaces
@@ -279,20 +676,20 @@ impl IoUringDriver {
             }
 
             // 2.3 install timeout and submit_and_wait with timeout
+            inner.sqpoll_fence();
             if let Some(duration) = timeout {
                 match inner.ext_arg {
                     // Submit and Wait with timeout in an TimeoutOp way.
                     // Better compatibility(5.4+).
                     false => {
                         self.install_timeout(inner, duration);
-                        inner.uring.submit_and_wait(1)?;
+                        inner.submit_and_wait(1)?;
                     }
                     // Submit and Wait with enter args.
                     // Better performance(5.11+).
                     true => {
                         let timespec = timespec(duration);
-                        let args = io_uring::types::SubmitArgs::new().timespec(&timespec);
-                        if let Err(e) = inner.uring.submitter().submit_with_args(1, &args) {
+                        if let Err(e) = inner.submit_and_wait_timeout(1, &timespec) {
                             if e.raw_os_error() != Some(libc::ETIME) {
                                 return Err(e);
                             }
@@ -301,11 +698,11 @@ impl IoUringDriver {
                 }
             } else {
                 // Submit and Wait without timeout
-                inner.uring.submit_and_wait(1)?;
+                inner.submit_and_wait(1)?;
             }
         } else {
             // Submit only
-            inner.uring.submit()?;
+            inner.submit()?;
         }
 
         // Set status as awake
@@ -354,7 +751,9 @@ impl Driver for IoUringDriver {
 
     fn submit(&self) -> io::Result<()> {
         let inner = unsafe { &mut *self.inner.get() };
-        inner.submit()?;
+        if inner.submit_on_poll {
+            inner.submit()?;
+        }
         inner.tick()?;
         Ok(())
     }
@@ -374,34 +773,167 @@ impl Driver for IoUringDriver {
     fn unpark(&self) -> Self::Unpark {
         UringInner::unpark(&self.inner)
     }
+
+    /// The eventfd is created and registered with `IORING_REGISTER_EVENTFD` on the first call.
+    fn completion_eventfd(&self) -> io::Result<RawFd> {
+        let inner = unsafe { &mut *self.inner.get() };
+        if let Some(fd) = inner.completion_eventfd.as_ref() {
+            return Ok(fd.as_raw_fd());
+        }
+        let fd = crate::syscall!(eventfd@RAW(0, libc::EFD_CLOEXEC | libc::EFD_NONBLOCK))?;
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+        inner.uring.submitter().register_eventfd(fd.as_raw_fd())?;
+        Ok(inner.completion_eventfd.insert(fd).as_raw_fd())
+    }
 }
 
 impl UringInner {
     fn tick(&mut self) -> io::Result<()> {
-        let cq = self.uring.completion();
-
-        for cqe in cq {
-            let index = cqe.user_data();
-            match index {
-                #[cfg(feature = "sync")]
-                EVENTFD_USERDATA => self.eventfd_installed = false,
-                #[cfg(feature = "poll-io")]
-                POLLER_USERDATA => {
-                    self.poller_installed = false;
-                    self.poll.tick(Some(Duration::ZERO))?;
+        loop {
+            let cq = self.uring.completion();
+
+            let mut reaped = 0;
+            for cqe in cq {
+                reaped += 1;
+                let index = cqe.user_data;
+                match index {
+                    #[cfg(feature = "sync")]
+                    EVENTFD_USERDATA => self.eventfd_installed = false,
+                    #[cfg(feature = "poll-io")]
+                    POLLER_USERDATA => {
+                        self.poller_installed = false;
+                        self.poll.tick(Some(Duration::ZERO))?;
+                    }
+                    _ if index >= MIN_REVERSED_USERDATA => (),
+                    #[cfg(feature = "sync")]
+                    _ if index & MSG_WAKER_FLAG != 0 => Self::recv_msg(index, cqe.result),
+                    _ => {
+                        let more = cqueue::more(cqe.flags);
+                        if !more {
+                            self.in_flight -= 1;
+                        }
+                        if let Some(latency) = self.op_latency.as_mut() {
+                            latency.completed(index as _, more);
+                        }
+                        // # Safety
+                        // Here we can make sure the result is valid.
+                        unsafe {
+                            self.ops
+                                .complete(index as _, resultify(cqe.result), cqe.flags, cqe.big)
+                        }
+                    }
                 }
-                _ if index >= MIN_REVERSED_USERDATA => (),
-                // # Safety
-                // Here we can make sure the result is valid.
-                _ => unsafe { self.ops.complete(index as _, resultify(&cqe), cqe.flags()) },
             }
+            self.cq_high_water = self.cq_high_water.max(reaped);
+
+            // The CQ is drained, flush the completions overflowed to it.
+            if !self.uring.submission().cq_overflow() {
+                return Ok(());
+            }
+            trace!("MONOIO DEBUG[IoUringDriver]: flush overflowed completions");
+            unsafe { self.sys_enter(0, 0, ENTER_GETEVENTS, std::ptr::null(), 0)? };
+        }
+    }
+
+    /// Submit if the queued SQEs reach the threshold of the submit policy. The error is ignored
+    /// since the SQEs will be submitted later.
+    #[inline]
+    fn submit_batch(&mut self) {
+        if let Some(threshold) = self.submit_threshold {
+            if self.uring.submission().len() >= threshold {
+                let _ = self.submit();
+            }
+        }
+    }
+
+    /// Submit the queued SQEs now.
+    pub(crate) fn flush_submissions(this: &Rc<UnsafeCell<UringInner>>) -> io::Result<()> {
+        let inner = unsafe { &mut *this.get() };
+        inner.submit()
+    }
+
+    pub(crate) fn metrics(this: &Rc<UnsafeCell<UringInner>>) -> super::DriverMetrics {
+        let inner = unsafe { &*this.get() };
+        super::DriverMetrics {
+            in_flight: inner.in_flight,
+            slab_size: inner.ops.slab.len(),
+            sq_high_water: inner.sq_high_water,
+            cq_high_water: inner.cq_high_water,
         }
+    }
+
+    pub(crate) fn op_latency(this: &Rc<UnsafeCell<UringInner>>) -> Vec<(u8, LatencyHistogram)> {
+        let inner = unsafe { &*this.get() };
+        inner
+            .op_latency
+            .as_ref()
+            .map(|latency| latency.histograms())
+            .unwrap_or_default()
+    }
+
+    /// Register the restrictions and enable the ring. The opcodes used by the runtime itself are
+    /// always allowed, and the ops of the opcodes not allowed are executed with syscalls if
+    /// possible.
+    fn restrict(&mut self, restrictions: &[Restriction]) -> io::Result<()> {
+        use io_uring::register::Restriction as Res;
+
+        let mut allowed = vec![opcode::AsyncCancel::CODE];
+        if !self.ext_arg {
+            allowed.push(opcode::Timeout::CODE);
+        }
+        #[cfg(feature = "sync")]
+        allowed.extend([opcode::Read::CODE, opcode::MsgRingData::CODE]);
+        #[cfg(feature = "poll-io")]
+        allowed.push(opcode::PollAdd::CODE);
+
+        let mut res: Vec<_> = allowed.iter().map(|&op| Res::sqe_op(op)).collect();
+        for r in restrictions {
+            res.push(match *r {
+                Restriction::Opcode(op) => {
+                    allowed.push(op);
+                    Res::sqe_op(op)
+                }
+                Restriction::Register(op) => Res::register_op(op),
+                Restriction::SqeFlagsAllowed(flags) => Res::sqe_flags_allowed(flags),
+                Restriction::SqeFlagsRequired(flags) => Res::sqe_flags_required(flags),
+            });
+        }
+        // The registered ring fd is unregistered on drop.
+        if self.ring_index.is_some() {
+            res.push(Res::register_op(UNREGISTER_RING_FDS as u8));
+        }
+
+        let submitter = self.uring.submitter();
+        submitter.register_restrictions(&mut res)?;
+        submitter.register_enable_rings()?;
+        self.opcodes.restrict(&allowed);
         Ok(())
     }
 
+    /// Make sure there is room in the CQ for `n` more operations according to the overflow
+    /// policy.
+    fn reserve_cq(&mut self, n: usize) -> io::Result<()> {
+        let limit = (self.uring.params().cq_entries() as usize).saturating_sub(CQ_RESERVED);
+        match self.cq_overflow {
+            CqOverflowPolicy::Flush => Ok(()),
+            CqOverflowPolicy::Backpressure => {
+                while self.in_flight != 0 && self.in_flight + n > limit {
+                    self.submit_and_wait(1)?;
+                    self.tick()?;
+                }
+                Ok(())
+            }
+            CqOverflowPolicy::Error if self.in_flight + n > limit => {
+                Err(io::Error::from_raw_os_error(libc::EBUSY))
+            }
+            CqOverflowPolicy::Error => Ok(()),
+        }
+    }
+
     fn submit(&mut self) -> io::Result<()> {
         loop {
-            match self.uring.submit() {
+            self.sqpoll_fence();
+            match self.enter() {
                 #[cfg(feature = "unstable")]
                 Err(ref e)
                     if matches!(e.kind(), io::ErrorKind::Other | io::ErrorKind::ResourceBusy) =>
@@ -418,12 +950,141 @@ impl UringInner {
                     // to get the raw error code.
                     self.tick()?;
                 }
-                e => return e.map(|_| ()),
+                Ok(_) => break,
+                Err(e) => return Err(e),
+            }
+        }
+        // The SQ thread consumes entries asynchronously. If the queue is still full, wait for
+        // it to make room, since the caller is going to push.
+        if self.uring.params().is_setup_sqpoll() && self.uring.submission().is_full() {
+            self.uring.submitter().squeue_wait()?;
+        }
+        Ok(())
+    }
+
+    /// Submit the SQ. If the completion work is deferred or pending, ask the kernel to run it
+    /// so the completions can be reaped without waiting.
+    fn enter(&mut self) -> io::Result<usize> {
+        let get_events = match self.taskrun {
+            TaskRun::Default => false,
+            TaskRun::Coop => self.uring.submission().taskrun(),
+            TaskRun::Defer => true,
+        };
+        if !get_events {
+            return self.submit_and_wait(0);
+        }
+        let to_submit = self.uring.submission().len() as u32;
+        self.record_submit(to_submit);
+        unsafe { self.sys_enter(to_submit, 0, ENTER_GETEVENTS, std::ptr::null(), 0) }
+    }
+
+    /// Submit the SQ and wait for `want` completions, like [`io_uring::Submitter::submit_and_wait`]
+    /// but entering with the registered ring fd if there is one.
+    fn submit_and_wait(&mut self, want: u32) -> io::Result<usize> {
+        let (to_submit, cq_overflow, need_wakeup) = {
+            let sq = self.uring.submission();
+            (sq.len() as u32, sq.cq_overflow(), sq.need_wakeup())
+        };
+        self.record_submit(to_submit);
+        let mut flags = 0;
+        if want > 0 || self.uring.params().is_setup_iopoll() || cq_overflow {
+            flags |= ENTER_GETEVENTS;
+        }
+        if self.uring.params().is_setup_sqpoll() {
+            if need_wakeup {
+                flags |= ENTER_SQ_WAKEUP;
+            } else if want == 0 {
+                // The SQ thread is polling, no need to enter.
+                return Ok(to_submit as usize);
+            }
+        }
+        unsafe { self.sys_enter(to_submit, want, flags, std::ptr::null(), 0) }
+    }
+
+    /// Submit the SQ and wait for `want` completions with timeout(requires
+    /// `IORING_FEAT_EXT_ARG`). Returns `ETIME` on timeout.
+    fn submit_and_wait_timeout(
+        &mut self,
+        want: u32,
+        timespec: &io_uring::types::Timespec,
+    ) -> io::Result<usize> {
+        let (to_submit, need_wakeup) = {
+            let sq = self.uring.submission();
+            (sq.len() as u32, sq.need_wakeup())
+        };
+        self.record_submit(to_submit);
+        let mut flags = ENTER_GETEVENTS | ENTER_EXT_ARG;
+        if self.uring.params().is_setup_sqpoll() && need_wakeup {
+            flags |= ENTER_SQ_WAKEUP;
+        }
+        let arg = GetEventsArg {
+            sigmask: 0,
+            sigmask_sz: 0,
+            pad: 0,
+            // `Timespec` is a transparent wrapper of `struct __kernel_timespec`.
+            ts: timespec as *const io_uring::types::Timespec as u64,
+        };
+        unsafe {
+            self.sys_enter(
+                to_submit,
+                want,
+                flags,
+                (&arg as *const GetEventsArg).cast(),
+                std::mem::size_of::<GetEventsArg>(),
+            )
+        }
+    }
+
+    /// Record the entries about to be submitted for the SQ high-water metric.
+    #[inline]
+    fn record_submit(&mut self, to_submit: u32) {
+        self.sq_high_water = self.sq_high_water.max(to_submit as usize);
+    }
+
+    /// `io_uring_enter` with the registered ring fd if there is one.
+    unsafe fn sys_enter(
+        &self,
+        to_submit: u32,
+        min_complete: u32,
+        mut flags: u32,
+        arg: *const libc::c_void,
+        size: usize,
+    ) -> io::Result<usize> {
+        let fd = match self.ring_index {
+            Some(index) => {
+                flags |= ENTER_REGISTERED_RING;
+                index as RawFd
             }
+            None => self.uring.as_raw_fd(),
+        };
+        let ret = libc::syscall(
+            libc::SYS_io_uring_enter,
+            fd,
+            to_submit,
+            min_complete,
+            flags,
+            arg,
+            size,
+        );
+        if ret < 0 {
+            Err(io::Error::last_os_error())
+        } else {
+            Ok(ret as usize)
+        }
+    }
+
+    /// With SQPOLL, `io_uring_enter` is only issued when the SQ thread has to be woken up
+    /// (`IORING_SQ_NEED_WAKEUP`). The tail update must be visible before the flag is read,
+    /// otherwise we may miss the wakeup while the thread is going to sleep.
+    #[inline]
+    fn sqpoll_fence(&self) {
+        if self.uring.params().is_setup_sqpoll() {
+            std::sync::atomic::fence(std::sync::atomic::Ordering::SeqCst);
         }
     }
 
     fn new_op<T: OpAble>(data: T, inner: &mut UringInner, driver: Inner) -> Op<T> {
+        inner.in_flight += 1;
         Op {
             driver,
             index: inner.ops.insert(T::RET_IS_FD),
@@ -439,6 +1100,17 @@ impl UringInner {
         T: OpAble,
     {
         let inner = unsafe { &mut *this.get() };
+        #[cfg(any(feature = "legacy", feature = "poll-io"))]
+        if inner.skip_ring(&data) {
+            return Ok(Op {
+                driver: Inner::Uring(this.clone()),
+                index: POLL_INDEX,
+                data: Some(data),
+            });
+        }
+
+        inner.reserve_cq(1)?;
+
         // If the submission queue is full, flush it to the kernel
         if inner.uring.submission().is_full() {
             inner.submit()?;
@@ -449,15 +1121,27 @@ impl UringInner {
 
         // Configure the SQE
         let data_mut = unsafe { op.data.as_mut().unwrap_unchecked() };
-        let sqe = OpAble::uring_op(data_mut).user_data(op.index as _);
-
-        {
-            let mut sq = inner.uring.submission();
-
-            // Push the new operation
-            if unsafe { sq.push(&sqe).is_err() } {
-                unimplemented!("when is this hit?");
+        let pushed = if inner.uring.is_big() {
+            let sqe = OpAble::uring_op128(data_mut).user_data(op.index as _);
+            if let Some(latency) = inner.op_latency.as_mut() {
+                latency.submitted(op.index, &sqe);
             }
+            #[cfg(feature = "tracing")]
+            inner.ops.trace_submitted(op.index, &sqe);
+            unsafe { inner.uring.submission().push_big(&sqe) }
+        } else {
+            let sqe = OpAble::uring_op(data_mut).user_data(op.index as _);
+            if let Some(latency) = inner.op_latency.as_mut() {
+                latency.submitted(op.index, &sqe);
+            }
+            #[cfg(feature = "tracing")]
+            inner.ops.trace_submitted(op.index, &sqe);
+            unsafe { inner.uring.submission().push(&sqe) }
+        };
+
+        // Push the new operation
+        if pushed.is_err() {
+            unimplemented!("when is this hit?");
         }
 
         // Submit the new operation. At this point, the operation has been
@@ -469,9 +1153,74 @@ impl UringInner {
         // CHIHAI: We are not going to do syscall now. If we are waiting
         // for IO, we will submit on `park`.
         // let _ = inner.submit();
+        inner.submit_batch();
         Ok(op)
     }
 
+    /// Make room for a chain of `len` operations, which must be pushed together, otherwise the
+    /// kernel may see a dangling link.
+    pub(crate) fn reserve_link(this: &Rc<UnsafeCell<UringInner>>, len: usize) -> io::Result<()> {
+        let inner = unsafe { &mut *this.get() };
+        inner.reserve_cq(len)?;
+
+        let room = |inner: &mut UringInner| {
+            let sq = inner.uring.submission();
+            sq.capacity() - sq.len()
+        };
+        if room(inner) < len {
+            inner.submit()?;
+        }
+        if room(inner) < len {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the linked operations do not fit in the submission queue",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Create an operation of the chain and append its SQE, linking the previous one to it. The
+    /// room must be made with [`reserve_link`](Self::reserve_link) before.
+    pub(crate) fn link_op<T: OpAble>(
+        this: &Rc<UnsafeCell<UringInner>>,
+        data: T,
+        chain: &mut Vec<io_uring::squeue::Entry>,
+    ) -> Op<T> {
+        let inner = unsafe { &mut *this.get() };
+        let mut op = Self::new_op(data, inner, Inner::Uring(this.clone()));
+        let data_mut = unsafe { op.data.as_mut().unwrap_unchecked() };
+        let sqe = OpAble::uring_op(data_mut).user_data(op.index as _);
+        if let Some(latency) = inner.op_latency.as_mut() {
+            latency.submitted(op.index, &sqe);
+        }
+        #[cfg(feature = "tracing")]
+        inner.ops.trace_submitted(op.index, &sqe);
+
+        if let Some(prev) = chain.pop() {
+            chain.push(prev.flags(io_uring::squeue::Flags::IO_LINK));
+        }
+        chain.push(sqe);
+        op
+    }
+
+    /// Push the chain built with [`link_op`](Self::link_op).
+    pub(crate) fn push_link(this: &Rc<UnsafeCell<UringInner>>, chain: &[io_uring::squeue::Entry]) {
+        let inner = unsafe { &mut *this.get() };
+        let pushed = unsafe { inner.uring.submission().push_multiple(chain) };
+        if pushed.is_err() {
+            unreachable!("the room of the linked operations is not reserved");
+        }
+        inner.submit_batch();
+    }
+
+    /// If the operation of a chain has to be executed with syscalls, in which case the whole
+    /// chain is, to keep the order.
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    pub(crate) fn link_polled<T: OpAble>(this: &Rc<UnsafeCell<UringInner>>, data: &T) -> bool {
+        let inner = unsafe { &*this.get() };
+        inner.skip_ring(data)
+    }
+
     pub(crate) fn poll_op(
         this: &Rc<UnsafeCell<UringInner>>,
         index: usize,
@@ -482,29 +1231,93 @@ impl UringInner {
         lifecycle.poll_op(cx)
     }
 
-    #[cfg(feature = "poll-io")]
+    /// Poll the op with the big part of its CQE, which is zero if the ring is not set up with
+    /// `IORING_SETUP_CQE32`.
+    pub(crate) fn poll_big_op(
+        this: &Rc<UnsafeCell<UringInner>>,
+        index: usize,
+        cx: &mut Context<'_>,
+    ) -> Poll<(CompletionMeta, [u64; 2])> {
+        let inner = unsafe { &mut *this.get() };
+        let lifecycle = unsafe { inner.ops.slab.get(index).unwrap_unchecked() };
+        let big_cqe = lifecycle.big_cqe;
+        lifecycle.poll_op(cx).map(|meta| (meta, big_cqe))
+    }
+
+    /// If the ring is set up with `IORING_SETUP_SQE128` and `IORING_SETUP_CQE32`.
+    pub(crate) fn is_big(this: &Rc<UnsafeCell<UringInner>>) -> bool {
+        let inner = unsafe { &*this.get() };
+        inner.uring.is_big()
+    }
+
+    pub(crate) fn poll_multishot_op(
+        this: &Rc<UnsafeCell<UringInner>>,
+        index: usize,
+        cx: &mut Context<'_>,
+    ) -> Poll<CompletionMeta> {
+        let inner = unsafe { &mut *this.get() };
+        let lifecycle = unsafe { inner.ops.slab.get(index).unwrap_unchecked() };
+        lifecycle.poll_multishot_op(cx)
+    }
+
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
     pub(crate) fn poll_legacy_op<T: OpAble>(
         this: &Rc<UnsafeCell<Self>>,
         data: &mut T,
         cx: &mut Context<'_>,
     ) -> Poll<CompletionMeta> {
+        #[cfg(feature = "poll-io")]
+        if let Some((direction, index)) = data.legacy_interest() {
+            // wait io ready and do syscall
+            let inner = unsafe { &mut *this.get() };
+            return inner
+                .poll
+                .poll_syscall(cx, index, direction, || OpAble::legacy_call(data));
+        }
+        #[cfg(not(feature = "poll-io"))]
+        let _ = (this, cx);
+
+        // if there is no index provided, it means the action does not rely on fd
+        // readiness. do syscall right now.
+        Poll::Ready(CompletionMeta {
+            result: OpAble::legacy_call(data),
+            flags: 0,
+        })
+    }
+
+    /// If the op is executed with syscalls instead of being submitted to the ring: the fd is
+    /// registered to the poller in hybrid mode, or the opcode is not supported by the kernel.
+    #[cfg(any(feature = "legacy", feature = "poll-io"))]
+    #[inline]
+    fn skip_ring<T: OpAble>(&self, data: &T) -> bool {
+        #[cfg(feature = "poll-io")]
+        if self.hybrid && data.uring_poll() {
+            return true;
+        }
+        #[cfg(not(feature = "poll-io"))]
+        let _ = data;
+        T::URING_OPCODE.is_some_and(|opcode| !self.opcodes.is_supported(opcode))
+    }
+
+    #[inline]
+    pub(crate) fn opcodes(this: &Rc<UnsafeCell<UringInner>>) -> Opcodes {
+        unsafe { &*this.get() }.opcodes
+    }
+
+    #[cfg(feature = "poll-io")]
+    pub(crate) fn cancel_poll_op(
+        this: &Rc<UnsafeCell<UringInner>>,
+        token: usize,
+        direction: super::ready::Direction,
+    ) {
         let inner = unsafe { &mut *this.get() };
-        let (direction, index) = match data.legacy_interest() {
-            Some(x) => x,
-            None => {
-                // if there is no index provided, it means the action does not rely on fd
-                // readiness. do syscall right now.
-                return Poll::Ready(CompletionMeta {
-                    result: OpAble::legacy_call(data),
-                    flags: 0,
-                });
-            }
-        };
+        inner.poll.cancel(token, direction);
+    }
 
-        // wait io ready and do syscall
-        inner
-            .poll
-            .poll_syscall(cx, index, direction, || OpAble::legacy_call(data))
+    #[cfg(feature = "poll-io")]
+    #[inline]
+    pub(crate) fn is_hybrid(this: &Rc<UnsafeCell<UringInner>>) -> bool {
+        unsafe { &*this.get() }.hybrid
     }
 
     pub(crate) fn drop_op<T: 'static>(
@@ -518,6 +1331,11 @@ impl UringInner {
             // already finished
             return;
         }
+        #[cfg(any(feature = "legacy", feature = "poll-io"))]
+        if index == POLL_INDEX {
+            // nothing in the kernel
+            return;
+        }
         if let Some(lifecycle) = inner.ops.slab.get(index) {
             let _must_finished = lifecycle.drop_op(data);
             #[cfg(feature = "async-cancel")]
@@ -538,6 +1356,10 @@ impl UringInner {
     }
 
     pub(crate) unsafe fn cancel_op(this: &Rc<UnsafeCell<UringInner>>, index: usize) {
+        #[cfg(any(feature = "legacy", feature = "poll-io"))]
+        if index == POLL_INDEX {
+            return;
+        }
         let inner = &mut *this.get();
         let cancel = opcode::AsyncCancel::new(index as u64)
             .build()
@@ -548,6 +1370,116 @@ impl UringInner {
         }
     }
 
+    /// Read the completion eventfd if it is created, so it is not readable until new completions
+    /// arrive.
+    pub(crate) fn reset_completion_eventfd(this: &Rc<UnsafeCell<UringInner>>) {
+        let inner = unsafe { &*this.get() };
+        if let Some(fd) = inner.completion_eventfd.as_ref() {
+            let mut count = 0u64;
+            // It is nonblocking, and fails with EAGAIN if it is not readable.
+            let _ = unsafe { libc::read(fd.as_raw_fd(), &mut count as *mut u64 as *mut _, 8) };
+        }
+    }
+
+    /// Cancel all the in-flight ops for the runtime shutdown. It needs linux 5.19, and does
+    /// nothing on the older kernels.
+    pub(crate) fn cancel_all(this: &Rc<UnsafeCell<UringInner>>) {
+        let inner = unsafe { &mut *this.get() };
+        if inner.in_flight == 0 {
+            return;
+        }
+        let cancel = opcode::AsyncCancel2::new(io_uring::types::CancelBuilder::any())
+            .build()
+            .user_data(u64::MAX);
+        unsafe {
+            if inner.uring.submission().push(&cancel).is_err() {
+                let _ = inner.submit();
+                let _ = inner.uring.submission().push(&cancel);
+            }
+        }
+        let _ = inner.submit();
+    }
+
+    /// Release the registered resources for the runtime shutdown. The buffers of the ops still
+    /// in flight are leaked rather than freed when the driver is dropped, since the kernel may
+    /// still write to them.
+    pub(crate) fn shutdown(this: &Rc<UnsafeCell<UringInner>>) {
+        let inner = unsafe { &mut *this.get() };
+        inner.leak_ops = inner.in_flight != 0;
+        let submitter = inner.uring.submitter();
+        // They fail if nothing is registered.
+        let _ = submitter.unregister_files();
+        if inner.completion_eventfd.is_some() {
+            let _ = submitter.unregister_eventfd();
+        }
+    }
+
+    pub(crate) fn is_iopoll(this: &Rc<UnsafeCell<UringInner>>) -> bool {
+        let inner = unsafe { &*this.get() };
+        inner.uring.params().is_setup_iopoll()
+    }
+
+    pub(crate) fn with_submitter<R>(
+        this: &Rc<UnsafeCell<UringInner>>,
+        f: impl FnOnce(io_uring::Submitter<'_>) -> R,
+    ) -> R {
+        let inner = unsafe { &*this.get() };
+        f(inner.uring.submitter())
+    }
+
+    /// Post a completion with `user_data` to another ring with current uring driver. Returns
+    /// false if there is no current uring driver, MSG_RING is not supported or the submission
+    /// queue is full.
+    #[cfg(feature = "sync")]
+    pub(crate) fn msg_ring(ring: RawFd, user_data: u64, sender_user_data: u64) -> bool {
+        if !CURRENT.is_set() {
+            return false;
+        }
+        CURRENT.with(|inner| match inner {
+            Inner::Uring(this) => {
+                let inner = unsafe { &mut *this.get() };
+                if !inner.opcodes.is_supported(opcode::MsgRingData::CODE) {
+                    return false;
+                }
+                let entry = opcode::MsgRingData::new(io_uring::types::Fd(ring), 0, user_data, None)
+                    .build()
+                    .user_data(sender_user_data);
+                if unsafe { inner.uring.submission().push(&entry) }.is_err() {
+                    return false;
+                }
+                // Submit now for the latency, the entry will be submitted later if it fails.
+                let _ = inner.submit();
+                true
+            }
+            #[cfg(feature = "legacy")]
+            Inner::Legacy(_) => false,
+        })
+    }
+
+    #[cfg(feature = "sync")]
+    fn recv_msg(user_data: u64, result: i32) {
+        let msg = (user_data & !(MSG_WAKER_FLAG | MSG_SENT_FLAG)) as *mut waker::RingMessage;
+        if user_data & MSG_SENT_FLAG == 0 {
+            // # Safety
+            // The message is leaked by the sender and only received once.
+            let msg = unsafe { Box::from_raw(msg) };
+            msg.waker.wake();
+            crate::runtime::metrics::foreign_wakeups(1);
+        } else if result < 0 {
+            // The message is not delivered, send it with channel instead.
+            let msg = unsafe { Box::from_raw(msg) };
+            if let Some(sender) = super::thread::get_waker_sender(msg.target) {
+                let _ = sender.send(msg.waker);
+            }
+            // We are in `tick`, do not push to the ring again.
+            if let Some(super::UnparkHandle::Uring(handle)) =
+                super::thread::get_unpark_handle(msg.target)
+            {
+                handle.unpark_eventfd();
+            }
+        }
+    }
+
     #[cfg(feature = "sync")]
     pub(crate) fn unpark(this: &Rc<UnsafeCell<UringInner>>) -> waker::UnparkHandle {
         let inner = unsafe { &*this.get() };
@@ -587,7 +1519,14 @@ impl Drop for IoUringDriver {
 impl Drop for UringInner {
     fn drop(&mut self) {
         // no need to wait for completion, as the kernel will clean up the ring asynchronically.
-        let _ = self.uring.submitter().submit();
+        let _ = self.submit_and_wait(0);
+        if self.leak_ops {
+            std::mem::forget(std::mem::replace(&mut self.ops, Ops::new()));
+        }
+        // The registered ring fd holds a reference of the ring.
+        if let Some(index) = self.ring_index.take() {
+            unregister_ring_fd(self.uring.as_raw_fd(), index);
+        }
         unsafe {
             ManuallyDrop::drop(&mut self.uring);
         }
@@ -609,16 +1548,51 @@ impl Ops {
     // # Safety
     // Caller must make sure the result is valid.
     #[inline]
-    unsafe fn complete(&mut self, index: usize, result: io::Result<u32>, flags: u32) {
-        let lifecycle = unsafe { self.slab.get(index).unwrap_unchecked() };
+    unsafe fn complete(
+        &mut self,
+        index: usize,
+        result: io::Result<u32>,
+        flags: u32,
+        big: [u64; 2],
+    ) {
+        let mut lifecycle = unsafe { self.slab.get(index).unwrap_unchecked() };
+        lifecycle.big_cqe = big;
+        trace_event!(
+            index,
+            opcode = lifecycle.opcode,
+            bytes = result.as_ref().ok(),
+            errno = result.as_ref().err().and_then(io::Error::raw_os_error),
+            more = cqueue::more(flags),
+            "op completed"
+        );
         lifecycle.complete(result, flags);
     }
+
+    /// Emit the submission event, and keep the opcode for the completion event.
+    #[cfg(feature = "tracing")]
+    fn trace_submitted<E: squeue::EntryMarker>(&mut self, index: usize, sqe: &E) {
+        let (opcode, fd) = sqe_info(sqe);
+        if let Some(mut lifecycle) = self.slab.get(index) {
+            lifecycle.opcode = opcode;
+        }
+        tracing::trace!(index, opcode, fd, "op submitted");
+    }
 }
 
+/// The opcode and fd of the SQE.
 #[inline]
-fn resultify(cqe: &cqueue::Entry) -> io::Result<u32> {
-    let res = cqe.result();
+fn sqe_info<E: squeue::EntryMarker>(sqe: &E) -> (u8, RawFd) {
+    // # Safety
+    // Both entries are `#[repr(C)]` and start with an `io_uring_sqe`, whose first byte is the
+    // opcode and whose aligned `i32` at offset 4 is the fd.
+    unsafe {
+        let ptr = sqe as *const E as *const u8;
+        (*ptr, *(ptr.add(4) as *const i32))
+    }
+}
 
+#[inline]
+fn resultify(res: i32) -> io::Result<u32> {
     if res >= 0 {
         Ok(res as u32)
     } else {
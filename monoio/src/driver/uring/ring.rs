@@ -0,0 +1,161 @@
+//! The io_uring instance of the driver, with 64-byte or big(128-byte SQE and 32-byte CQE)
+//! entries.
+
+use std::os::unix::prelude::{AsRawFd, RawFd};
+
+use io_uring::{
+    cqueue, squeue, squeue::PushError, IoUring, Parameters, SubmissionQueue, Submitter,
+};
+
+pub(crate) enum Ring {
+    Normal(IoUring),
+    /// `IORING_SETUP_SQE128` and `IORING_SETUP_CQE32`
+    Big(IoUring<squeue::Entry128, cqueue::Entry32>),
+}
+
+/// The submission queue of the ring.
+pub(crate) enum Sq<'a> {
+    Normal(SubmissionQueue<'a>),
+    Big(SubmissionQueue<'a, squeue::Entry128>),
+}
+
+/// A completion of the ring. `big` is always zero for the normal ring.
+pub(crate) struct Cqe {
+    pub(crate) user_data: u64,
+    pub(crate) result: i32,
+    pub(crate) flags: u32,
+    pub(crate) big: [u64; 2],
+}
+
+macro_rules! each {
+    ($self: expr, $inner: ident => $e: expr) => {
+        match $self {
+            Self::Normal($inner) => $e,
+            Self::Big($inner) => $e,
+        }
+    };
+}
+
+impl Ring {
+    #[inline]
+    pub(crate) fn is_big(&self) -> bool {
+        matches!(self, Ring::Big(_))
+    }
+
+    #[inline]
+    pub(crate) fn submitter(&self) -> Submitter<'_> {
+        each!(self, r => r.submitter())
+    }
+
+    #[inline]
+    pub(crate) fn params(&self) -> &Parameters {
+        each!(self, r => r.params())
+    }
+
+    #[inline]
+    pub(crate) fn submission(&mut self) -> Sq<'_> {
+        match self {
+            Ring::Normal(r) => Sq::Normal(r.submission()),
+            Ring::Big(r) => Sq::Big(r.submission()),
+        }
+    }
+
+    /// Drain the completion queue.
+    #[inline]
+    pub(crate) fn completion(&mut self) -> impl Iterator<Item = Cqe> + '_ {
+        let (normal, big) = match self {
+            Ring::Normal(r) => (Some(r.completion()), None),
+            Ring::Big(r) => (None, Some(r.completion())),
+        };
+        let normal = normal.into_iter().flatten().map(|cqe| Cqe {
+            user_data: cqe.user_data(),
+            result: cqe.result(),
+            flags: cqe.flags(),
+            big: [0; 2],
+        });
+        let big = big.into_iter().flatten().map(|cqe| Cqe {
+            user_data: cqe.user_data(),
+            result: cqe.result(),
+            flags: cqe.flags(),
+            big: *cqe.big_cqe(),
+        });
+        normal.chain(big)
+    }
+}
+
+impl AsRawFd for Ring {
+    fn as_raw_fd(&self) -> RawFd {
+        each!(self, r => r.as_raw_fd())
+    }
+}
+
+impl Sq<'_> {
+    #[inline]
+    pub(crate) fn len(&self) -> usize {
+        each!(self, sq => sq.len())
+    }
+
+    #[inline]
+    pub(crate) fn capacity(&self) -> usize {
+        each!(self, sq => sq.capacity())
+    }
+
+    #[inline]
+    pub(crate) fn is_full(&self) -> bool {
+        each!(self, sq => sq.is_full())
+    }
+
+    #[inline]
+    pub(crate) fn cq_overflow(&self) -> bool {
+        each!(self, sq => sq.cq_overflow())
+    }
+
+    #[inline]
+    pub(crate) fn need_wakeup(&self) -> bool {
+        each!(self, sq => sq.need_wakeup())
+    }
+
+    #[inline]
+    pub(crate) fn taskrun(&self) -> bool {
+        each!(self, sq => sq.taskrun())
+    }
+
+    /// # Safety
+    /// See [`SubmissionQueue::push`].
+    #[inline]
+    pub(crate) unsafe fn push(&mut self, entry: &squeue::Entry) -> Result<(), PushError> {
+        match self {
+            Sq::Normal(sq) => sq.push(entry),
+            Sq::Big(sq) => sq.push(&entry.clone().into()),
+        }
+    }
+
+    /// # Safety
+    /// See [`SubmissionQueue::push_multiple`].
+    #[inline]
+    pub(crate) unsafe fn push_multiple(
+        &mut self,
+        entries: &[squeue::Entry],
+    ) -> Result<(), PushError> {
+        match self {
+            Sq::Normal(sq) => sq.push_multiple(entries),
+            Sq::Big(sq) => {
+                let entries: Vec<squeue::Entry128> =
+                    entries.iter().map(|e| e.clone().into()).collect();
+                sq.push_multiple(&entries)
+            }
+        }
+    }
+
+    /// Push a 128-byte entry, which is only possible on the big ring.
+    ///
+    /// # Safety
+    /// See [`SubmissionQueue::push`].
+    #[inline]
+    pub(crate) unsafe fn push_big(&mut self, entry: &squeue::Entry128) -> Result<(), PushError> {
+        match self {
+            Sq::Normal(_) => unreachable!("128-byte SQE is pushed to the normal ring"),
+            Sq::Big(sq) => sq.push(entry),
+        }
+    }
+}
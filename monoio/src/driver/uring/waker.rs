@@ -1,6 +1,9 @@
-//! Custom thread waker based on eventfd.
+//! Custom thread waker based on eventfd and `IORING_OP_MSG_RING`.
 
-use std::os::unix::prelude::{AsRawFd, RawFd};
+use std::{
+    os::unix::prelude::{AsRawFd, FromRawFd, OwnedFd, RawFd},
+    task::Waker,
+};
 
 use crate::driver::unpark::Unpark;
 
@@ -9,15 +12,30 @@ pub(crate) struct EventWaker {
     raw: RawFd,
     // File hold the ownership of fd, only useful when drop
     _file: std::fs::File,
+    // A duplicate of the ring fd, which keeps the ring valid for MSG_RING while the waker is
+    // alive. It is None if the kernel does not support MSG_RING.
+    ring: Option<OwnedFd>,
     // Atomic awake status
     pub(crate) awake: std::sync::atomic::AtomicBool,
 }
 
+/// Waker sent to another ring with MSG_RING.
+pub(crate) struct RingMessage {
+    pub(crate) waker: Waker,
+    // Thread id of the target runtime, used to send the waker with channel if MSG_RING failed.
+    pub(crate) target: usize,
+}
+
 impl EventWaker {
-    pub(crate) fn new(file: std::fs::File) -> Self {
+    pub(crate) fn new(file: std::fs::File, ring: Option<RawFd>) -> Self {
+        let ring = ring.and_then(|fd| {
+            let fd = unsafe { libc::fcntl(fd, libc::F_DUPFD_CLOEXEC, 0) };
+            (fd >= 0).then(|| unsafe { OwnedFd::from_raw_fd(fd) })
+        });
         Self {
             raw: file.as_raw_fd(),
             _file: file,
+            ring,
             awake: std::sync::atomic::AtomicBool::new(true),
         }
     }
@@ -27,12 +45,26 @@ impl EventWaker {
         if self.awake.load(std::sync::atomic::Ordering::Acquire) {
             return Ok(());
         }
+        // Post a completion to the ring directly if we are on a uring runtime.
+        if let Some(ring) = self.ring.as_ref() {
+            if super::UringInner::msg_ring(
+                ring.as_raw_fd(),
+                super::MSG_RING_USERDATA,
+                super::CANCEL_USERDATA,
+            ) {
+                return Ok(());
+            }
+        }
+        self.wake_eventfd();
+        Ok(())
+    }
+
+    fn wake_eventfd(&self) {
         // Write data into EventFd to wake the executor.
         let buf = 0x1u64.to_ne_bytes();
         unsafe {
             // SAFETY: Writing number to eventfd is thread safe.
             libc::write(self.raw, buf.as_ptr().cast(), buf.len());
-            Ok(())
         }
     }
 }
@@ -46,9 +78,42 @@ impl AsRawFd for EventWaker {
 #[derive(Clone)]
 pub struct UnparkHandle(pub(crate) std::sync::Weak<EventWaker>);
 
+impl UnparkHandle {
+    /// Send the waker to the ring with MSG_RING, which wakes the ring as well. Returns the waker
+    /// back if current thread is not on a uring runtime or MSG_RING is not supported.
+    pub(crate) fn send_waker(&self, waker: Waker, target: usize) -> Result<(), Waker> {
+        let Some(w) = self.0.upgrade() else {
+            return Err(waker);
+        };
+        let Some(ring) = w.ring.as_ref() else {
+            return Err(waker);
+        };
+        let msg = Box::into_raw(Box::new(RingMessage { waker, target }));
+        let user_data = msg as u64 | super::MSG_WAKER_FLAG;
+        if super::UringInner::msg_ring(
+            ring.as_raw_fd(),
+            user_data,
+            user_data | super::MSG_SENT_FLAG,
+        ) {
+            Ok(())
+        } else {
+            // The message is not pushed, we still own it.
+            Err(unsafe { Box::from_raw(msg) }.waker)
+        }
+    }
+
+    /// Wake the ring with eventfd only.
+    pub(crate) fn unpark_eventfd(&self) {
+        if let Some(w) = self.0.upgrade() {
+            w.wake_eventfd();
+        }
+    }
+}
+
 impl Unpark for UnparkHandle {
     fn unpark(&self) -> std::io::Result<()> {
         if let Some(w) = self.0.upgrade() {
+            trace_event!("runtime unpark");
             w.wake()
         } else {
             Ok(())
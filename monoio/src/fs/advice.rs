@@ -0,0 +1,94 @@
+use std::io;
+
+use crate::driver::op::Op;
+
+/// Advice about the access pattern of a file range or memory, for [`File::advise`] and
+/// [`madvise`].
+///
+/// [`File::advise`]: super::File::advise
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Advice {
+    /// No special treatment, which is the default.
+    Normal,
+    /// Expect sequential access, so the kernel reads ahead more aggressively.
+    Sequential,
+    /// Expect random access, so the kernel disables the read ahead.
+    Random,
+    /// Expect access in the near future, so the kernel starts reading the data into the cache.
+    WillNeed,
+    /// Do not expect access in the near future, so the kernel drops the clean cached data.
+    ///
+    /// With [`madvise`] on private anonymous memory, the following access gets zero-filled
+    /// pages.
+    DontNeed,
+    /// Expect the data to be accessed only once. It is a no-op for files since Linux 2.6.18,
+    /// and not supported by [`madvise`].
+    NoReuse,
+}
+
+impl Advice {
+    #[inline]
+    fn fadvise(self) -> i32 {
+        match self {
+            Advice::Normal => libc::POSIX_FADV_NORMAL,
+            Advice::Sequential => libc::POSIX_FADV_SEQUENTIAL,
+            Advice::Random => libc::POSIX_FADV_RANDOM,
+            Advice::WillNeed => libc::POSIX_FADV_WILLNEED,
+            Advice::DontNeed => libc::POSIX_FADV_DONTNEED,
+            Advice::NoReuse => libc::POSIX_FADV_NOREUSE,
+        }
+    }
+
+    #[inline]
+    fn madvise(self) -> io::Result<i32> {
+        Ok(match self {
+            Advice::Normal => libc::MADV_NORMAL,
+            Advice::Sequential => libc::MADV_SEQUENTIAL,
+            Advice::Random => libc::MADV_RANDOM,
+            Advice::WillNeed => libc::MADV_WILLNEED,
+            Advice::DontNeed => libc::MADV_DONTNEED,
+            Advice::NoReuse => return Err(io::Error::from_raw_os_error(libc::EINVAL)),
+        })
+    }
+}
+
+pub(crate) async fn fadvise(
+    fd: &crate::driver::shared_fd::SharedFd,
+    offset: u64,
+    len: u32,
+    advice: Advice,
+) -> io::Result<()> {
+    let op = Op::fadvise(fd, offset, len, advice.fadvise())?;
+    op.await.meta.result.map(|_| ())
+}
+
+/// Give advice about the use of the memory range with `madvise(2)`, like hinting the pages of
+/// a memory-mapped file with [`Advice::WillNeed`] or [`Advice::DontNeed`].
+///
+/// With io_uring driver it uses `IORING_OP_MADVISE`(requires kernel 5.6+), so it does not block
+/// the thread even if the kernel needs to do some work, like reading the pages ahead.
+///
+/// # Errors
+///
+/// Returns an error if `addr` is not page-aligned, the range is not mapped, or the advice is
+/// [`Advice::NoReuse`].
+///
+/// # Safety
+///
+/// The memory range must stay mapped until the returned future completes. [`Advice::DontNeed`]
+/// discards the content of private anonymous memory, which must not be referred after that.
+pub async unsafe fn madvise(addr: *mut u8, len: usize, advice: Advice) -> io::Result<()> {
+    let advice = advice.madvise()?;
+    // The length is 32-bit in the SQE, so advise the large range in chunks.
+    const CHUNK: usize = 1 << 30;
+    let mut offset = 0;
+    loop {
+        let chunk = (len - offset).min(CHUNK);
+        let op = Op::madvise(addr.add(offset).cast(), chunk as u32, advice)?;
+        op.await.meta.result?;
+        offset += chunk;
+        if offset >= len {
+            return Ok(());
+        }
+    }
+}
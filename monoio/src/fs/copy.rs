@@ -0,0 +1,201 @@
+use std::{
+    io,
+    os::unix::{
+        fs::{OpenOptionsExt, PermissionsExt},
+        prelude::{AsRawFd, RawFd},
+    },
+    path::Path,
+};
+
+use super::{File, OpenOptions};
+use crate::buf::{FixedBufPool, IoBuf};
+
+/// Max bytes copied by one `copy_file_range(2)`.
+const COPY_CHUNK: usize = 16 * 1024 * 1024;
+/// Size of the buffer when falling back to read and write.
+const BUF_SIZE: usize = 64 * 1024;
+
+/// Copies the contents of one file to another. This function will also copy the permission bits
+/// of the original file to the destination file.
+///
+/// This function will overwrite the contents of `to`. On success, the total number of bytes
+/// copied is returned, and it is equal to the length of the `to` file as reported by
+/// `metadata`.
+///
+/// It is an async version of [`std::fs::copy`], which uses `copy_file_range(2)` so that the data
+/// is copied in the kernel, or shared by reflink on the file systems supporting it(e.g. Btrfs
+/// and XFS). It falls back to a read and write loop if `copy_file_range(2)` is not supported,
+/// or the files are on different file systems before Linux 5.19(`EXDEV`).
+///
+/// `copy_file_range(2)` has no io_uring operation. It runs on the blocking thread pool with the
+/// `sync` feature when io_uring is not enabled, or in place otherwise, which copies at most 16MiB
+/// at once.
+///
+/// # Errors
+///
+/// This function will return an error in the following situations, but is not limited to just
+/// these cases:
+///
+/// * `from` is neither a regular file nor a symlink to a regular file.
+/// * `from` does not exist.
+/// * The current process does not have the permission rights to read `from` or write `to`.
+///
+/// # Examples
+///
+/// ```no_run
+/// #[monoio::main]
+/// async fn main() -> std::io::Result<()> {
+///     monoio::fs::copy("foo.txt", "bar.txt").await?; // Copy foo.txt to bar.txt
+///     Ok(())
+/// }
+/// ```
+pub async fn copy<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> io::Result<u64> {
+    copy_inner(from.as_ref(), to.as_ref(), None).await
+}
+
+/// Copies the contents of one file to another like [`copy`], but the read and write loop of the
+/// fallback uses a registered buffer of `pool` if there is one available.
+///
+/// # Examples
+///
+/// ```no_run
+/// use monoio::buf::FixedBufPool;
+///
+/// #[monoio::main]
+/// async fn main() -> std::io::Result<()> {
+///     let pool = FixedBufPool::new(4, 64 * 1024)?;
+///     monoio::fs::copy_with_pool("foo.txt", "bar.txt", &pool).await?;
+///     Ok(())
+/// }
+/// ```
+pub async fn copy_with_pool<P: AsRef<Path>, Q: AsRef<Path>>(
+    from: P,
+    to: Q,
+    pool: &FixedBufPool,
+) -> io::Result<u64> {
+    copy_inner(from.as_ref(), to.as_ref(), Some(pool)).await
+}
+
+async fn copy_inner(from: &Path, to: &Path, pool: Option<&FixedBufPool>) -> io::Result<u64> {
+    let src = File::open(from).await?;
+    let meta = src.metadata().await?;
+    if !meta.is_file() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the source path is neither a regular file nor a symlink to a regular file",
+        ));
+    }
+    let mode = meta.permissions().mode();
+    let dst = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(mode)
+        .open(to)
+        .await?;
+    // The mode of `open` is masked by the umask, and ignored if the file exists.
+    crate::syscall!(fchmod@RAW(dst.as_raw_fd(), mode as libc::mode_t))?;
+
+    let res = match copy_file_range(&src, &dst).await? {
+        Some(n) => Ok(n),
+        None => copy_by_buf(&src, &dst, pool).await,
+    };
+    dst.close().await?;
+    res
+}
+
+/// Copy with `copy_file_range(2)`, returns `None` if it is not supported for the files.
+#[cfg(any(feature = "iouring", not(feature = "sync")))]
+async fn copy_file_range(src: &File, dst: &File) -> io::Result<Option<u64>> {
+    copy_file_range_blocking(src.as_raw_fd(), dst.as_raw_fd())
+}
+
+/// Copy with `copy_file_range(2)`, returns `None` if it is not supported for the files.
+#[cfg(all(feature = "sync", not(feature = "iouring")))]
+async fn copy_file_range(src: &File, dst: &File) -> io::Result<Option<u64>> {
+    let (fd_in, fd_out) = (src.as_raw_fd(), dst.as_raw_fd());
+    crate::fs::asyncify(move || copy_file_range_blocking(fd_in, fd_out)).await
+}
+
+fn copy_file_range_blocking(fd_in: RawFd, fd_out: RawFd) -> io::Result<Option<u64>> {
+    let mut off_in: libc::loff_t = 0;
+    let mut off_out: libc::loff_t = 0;
+    loop {
+        match crate::syscall!(copy_file_range@RAW(
+            fd_in,
+            &mut off_in,
+            fd_out,
+            &mut off_out,
+            COPY_CHUNK,
+            0
+        )) {
+            // Some files report the length of zero(e.g. procfs), try to read them.
+            Ok(0) if off_in == 0 => return Ok(None),
+            Ok(0) => return Ok(Some(off_in as u64)),
+            Ok(_) => {}
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            // Fall back only if nothing has been copied, like std does.
+            Err(e)
+                if off_in == 0
+                    && matches!(
+                        e.raw_os_error(),
+                        Some(
+                            libc::ENOSYS
+                                | libc::EXDEV
+                                | libc::EINVAL
+                                | libc::EPERM
+                                | libc::EOPNOTSUPP
+                                | libc::EBADF
+                        )
+                    ) =>
+            {
+                return Ok(None)
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+async fn copy_by_buf(src: &File, dst: &File, pool: Option<&FixedBufPool>) -> io::Result<u64> {
+    let mut pos = 0;
+    if let Some(mut buf) = pool.and_then(FixedBufPool::try_next) {
+        loop {
+            let (res, b) = src.read_fixed_at(buf, pos).await;
+            buf = b;
+            let n = match res {
+                Ok(0) => return Ok(pos),
+                Ok(n) => n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            let (res, b) = dst.write_fixed_at(buf, pos).await;
+            buf = b;
+            let written = res?;
+            if written < n {
+                // Write the rest of a short write with the normal operation.
+                let (res, slice) = dst
+                    .write_all_at(buf.slice(written..n), pos + written as u64)
+                    .await;
+                res?;
+                buf = slice.into_inner();
+            }
+            pos += n as u64;
+        }
+    }
+
+    let mut buf = Vec::with_capacity(BUF_SIZE);
+    loop {
+        let (res, b) = src.read_at(buf, pos).await;
+        buf = b;
+        let n = match res {
+            Ok(0) => return Ok(pos),
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        let (res, b) = dst.write_all_at(buf, pos).await;
+        buf = b;
+        res?;
+        pos += n as u64;
+    }
+}
@@ -0,0 +1,471 @@
+use std::{
+    ffi::{CStr, OsStr, OsString},
+    io,
+    os::unix::{
+        ffi::OsStrExt,
+        fs::OpenOptionsExt,
+        prelude::{AsRawFd, RawFd},
+    },
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use super::{file_type::FileType, File, Metadata, OpenOptions};
+#[cfg(any(
+    feature = "iouring",
+    not(feature = "sync"),
+    feature = "mkdirat",
+    feature = "unlinkat"
+))]
+use crate::driver::op::Op;
+
+/// Size of the buffer to read the directory entries with `getdents64(2)`.
+const DIRENT_BUF_SIZE: usize = 8 * 1024;
+
+/// A handle of an opened directory.
+///
+/// The operations on the paths relative to the directory, like [`Dir::open_file`] and
+/// [`Dir::remove_file`], reuse the fd of the directory(the `*at` syscalls) instead of resolving
+/// the whole path again, and they keep working when the directory is renamed.
+///
+/// # Examples
+///
+/// ```no_run
+/// use monoio::fs::{Dir, OpenOptions};
+///
+/// #[monoio::main]
+/// async fn main() -> std::io::Result<()> {
+///     let dir = Dir::open("/tmp").await?;
+///     let mut entries = dir.read_dir().await?;
+///     while let Some(entry) = entries.next_entry().await? {
+///         println!("{:?}", entry.file_name());
+///     }
+///     let file = dir
+///         .open_file("foo.txt", OpenOptions::new().read(true))
+///         .await?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Dir {
+    file: File,
+    path: Arc<Path>,
+}
+
+impl Dir {
+    /// Opens the directory at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `path` does not exist or is not a directory.
+    pub async fn open(path: impl AsRef<Path>) -> io::Result<Dir> {
+        let path = path.as_ref();
+        let file = dir_options(false).open(path).await?;
+        Ok(Dir {
+            file,
+            path: Arc::from(path),
+        })
+    }
+
+    /// Returns the path the directory was opened with.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Opens the directory at `path` relative to this directory.
+    pub async fn open_dir(&self, path: impl AsRef<Path>) -> io::Result<Dir> {
+        self.open_dir_inner(path.as_ref(), false).await
+    }
+
+    async fn open_dir_inner(&self, path: &Path, no_follow: bool) -> io::Result<Dir> {
+        let file = dir_options(no_follow)
+            .open_at_fd(self.file.shared_fd(), path)
+            .await?;
+        Ok(Dir {
+            file,
+            path: Arc::from(self.path.join(path)),
+        })
+    }
+
+    /// Opens the file at `path` relative to this directory with `options`.
+    ///
+    /// It is the same as [`OpenOptions::open_at`].
+    pub async fn open_file(
+        &self,
+        path: impl AsRef<Path>,
+        options: &OpenOptions,
+    ) -> io::Result<File> {
+        options
+            .open_at_fd(self.file.shared_fd(), path.as_ref())
+            .await
+    }
+
+    /// Returns a stream over the entries within the directory.
+    ///
+    /// The entries of `.` and `..` are skipped. The stream has its own fd, so that it reads the
+    /// directory from the beginning, and more than one of them can be used at the same time.
+    pub async fn read_dir(&self) -> io::Result<ReadDir> {
+        let file = dir_options(false)
+            .open_at_fd(self.file.shared_fd(), Path::new("."))
+            .await?;
+        Ok(ReadDir::new(file, self.path.clone()))
+    }
+
+    /// Queries the metadata of the file at `path` relative to this directory, which traverses
+    /// the symbolic links.
+    pub async fn metadata(&self, path: impl AsRef<Path>) -> io::Result<Metadata> {
+        self.metadata_inner(path.as_ref(), true).await
+    }
+
+    /// Queries the metadata of the file at `path` relative to this directory, without following
+    /// the symbolic links.
+    pub async fn symlink_metadata(&self, path: impl AsRef<Path>) -> io::Result<Metadata> {
+        self.metadata_inner(path.as_ref(), false).await
+    }
+
+    #[cfg(any(feature = "iouring", not(feature = "sync")))]
+    async fn metadata_inner(&self, path: &Path, follow_symlinks: bool) -> io::Result<Metadata> {
+        let mut flags = libc::AT_STATX_SYNC_AS_STAT;
+        if !follow_symlinks {
+            flags |= libc::AT_SYMLINK_NOFOLLOW;
+        }
+        let op = Op::statx_using_path_at(Some(self.file.shared_fd()), path, flags)?;
+        op.result()
+            .await
+            .map(super::metadata::FileAttr::from)
+            .map(Metadata)
+    }
+
+    #[cfg(all(feature = "sync", not(feature = "iouring")))]
+    async fn metadata_inner(&self, path: &Path, follow_symlinks: bool) -> io::Result<Metadata> {
+        let fd = self.file.as_raw_fd();
+        let path = std::ffi::CString::new(path.as_os_str().as_bytes())?;
+        crate::fs::asyncify(move || super::metadata::stat(Some(fd), &path, follow_symlinks))
+            .await
+            .map(Metadata)
+    }
+
+    /// Creates a new, empty directory at `path` relative to this directory, with the
+    /// permission bits `mode`(masked by the umask).
+    #[cfg(feature = "mkdirat")]
+    pub async fn create_dir(&self, path: impl AsRef<Path>, mode: u32) -> io::Result<()> {
+        let op = Op::mkdir_at(Some(self.file.shared_fd()), path, mode as libc::mode_t)?;
+        op.await.meta.result.map(|_| ())
+    }
+
+    /// Removes the file at `path` relative to this directory.
+    #[cfg(feature = "unlinkat")]
+    pub async fn remove_file(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let op = Op::unlink_at(Some(self.file.shared_fd()), path, false)?;
+        op.await.meta.result.map(|_| ())
+    }
+
+    /// Removes the empty directory at `path` relative to this directory.
+    #[cfg(feature = "unlinkat")]
+    pub async fn remove_dir(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let op = Op::unlink_at(Some(self.file.shared_fd()), path, true)?;
+        op.await.meta.result.map(|_| ())
+    }
+
+    /// Closes the directory.
+    pub async fn close(self) -> io::Result<()> {
+        self.file.close().await
+    }
+}
+
+impl AsRawFd for Dir {
+    fn as_raw_fd(&self) -> RawFd {
+        self.file.as_raw_fd()
+    }
+}
+
+fn dir_options(no_follow: bool) -> OpenOptions {
+    let mut options = OpenOptions::new();
+    let mut flags = libc::O_DIRECTORY;
+    if no_follow {
+        flags |= libc::O_NOFOLLOW;
+    }
+    options.read(true).custom_flags(flags);
+    options
+}
+
+/// Returns a stream over the entries within the directory at `path`.
+///
+/// The entries of `.` and `..` are skipped, and the order of the entries is not specified.
+///
+/// The entries are read with `getdents64(2)`, which has no io_uring operation. It runs on the
+/// blocking thread pool with the `sync` feature when io_uring is not enabled, or in place
+/// otherwise, which reads at most 8KiB of entries at once.
+///
+/// # Errors
+///
+/// Returns an error if `path` does not exist, is not a directory, or the user lacks the
+/// permission to read it.
+///
+/// # Examples
+///
+/// ```no_run
+/// #[monoio::main]
+/// async fn main() -> std::io::Result<()> {
+///     let mut entries = monoio::fs::read_dir(".").await?;
+///     while let Some(entry) = entries.next_entry().await? {
+///         println!("{}", entry.path().display());
+///     }
+///     Ok(())
+/// }
+/// ```
+pub async fn read_dir(path: impl AsRef<Path>) -> io::Result<ReadDir> {
+    let path = path.as_ref();
+    let file = dir_options(false).open(path).await?;
+    Ok(ReadDir::new(file, Arc::from(path)))
+}
+
+/// Stream of the entries in a directory, returned by [`read_dir`] and [`Dir::read_dir`].
+///
+/// It also implements [`Stream`](crate::io::stream::Stream) of `io::Result<DirEntry>`.
+pub struct ReadDir {
+    file: File,
+    root: Arc<Path>,
+    buf: Vec<u8>,
+    pos: usize,
+    end: bool,
+}
+
+impl ReadDir {
+    fn new(file: File, root: Arc<Path>) -> ReadDir {
+        ReadDir {
+            file,
+            root,
+            buf: Vec::new(),
+            pos: 0,
+            end: false,
+        }
+    }
+
+    /// Returns the next entry in the directory, or `None` if all the entries are returned.
+    pub async fn next_entry(&mut self) -> io::Result<Option<DirEntry>> {
+        loop {
+            while self.pos < self.buf.len() {
+                let dirent = &self.buf[self.pos..];
+                // struct linux_dirent64 {
+                //     ino64_t        d_ino;
+                //     off64_t        d_off;
+                //     unsigned short d_reclen;
+                //     unsigned char  d_type;
+                //     char           d_name[];
+                // };
+                let ino = u64::from_ne_bytes(dirent[0..8].try_into().unwrap());
+                let reclen = u16::from_ne_bytes(dirent[16..18].try_into().unwrap()) as usize;
+                let d_type = dirent[18];
+                let name = CStr::from_bytes_until_nul(&dirent[19..reclen])
+                    .map_err(|_| io::Error::from(io::ErrorKind::InvalidData))?;
+                self.pos += reclen;
+
+                let name = name.to_bytes();
+                if name == b"." || name == b".." {
+                    continue;
+                }
+                return Ok(Some(DirEntry {
+                    root: self.root.clone(),
+                    name: OsStr::from_bytes(name).to_os_string(),
+                    ino,
+                    d_type,
+                }));
+            }
+            if self.end {
+                return Ok(None);
+            }
+            self.fill().await?;
+        }
+    }
+
+    #[cfg(any(feature = "iouring", not(feature = "sync")))]
+    async fn fill(&mut self) -> io::Result<()> {
+        let mut buf = std::mem::take(&mut self.buf);
+        let res = getdents(self.file.as_raw_fd(), &mut buf);
+        self.on_filled(buf, res)
+    }
+
+    #[cfg(all(feature = "sync", not(feature = "iouring")))]
+    async fn fill(&mut self) -> io::Result<()> {
+        let fd = self.file.as_raw_fd();
+        let mut buf = std::mem::take(&mut self.buf);
+        let (buf, res) = crate::fs::asyncify(move || {
+            let res = getdents(fd, &mut buf);
+            Ok((buf, res))
+        })
+        .await?;
+        self.on_filled(buf, res)
+    }
+
+    fn on_filled(&mut self, buf: Vec<u8>, res: io::Result<()>) -> io::Result<()> {
+        self.buf = buf;
+        self.pos = 0;
+        if res.is_err() {
+            self.buf.clear();
+        }
+        self.end = self.buf.is_empty();
+        res
+    }
+}
+
+impl std::fmt::Debug for ReadDir {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReadDir").field("root", &self.root).finish()
+    }
+}
+
+/// Read the entries of the directory `fd` into `buf`, which is empty at the end of the directory.
+fn getdents(fd: RawFd, buf: &mut Vec<u8>) -> io::Result<()> {
+    buf.clear();
+    buf.reserve(DIRENT_BUF_SIZE);
+    let n = crate::syscall!(syscall@RAW(
+        libc::SYS_getdents64,
+        fd,
+        buf.as_mut_ptr(),
+        buf.capacity()
+    ))?;
+    unsafe { buf.set_len(n as usize) };
+    Ok(())
+}
+
+impl crate::io::stream::Stream for ReadDir {
+    type Item = io::Result<DirEntry>;
+
+    async fn next(&mut self) -> Option<Self::Item> {
+        self.next_entry().await.transpose()
+    }
+}
+
+/// An entry of the directory, returned by [`ReadDir`].
+#[derive(Debug, Clone)]
+pub struct DirEntry {
+    root: Arc<Path>,
+    name: OsString,
+    ino: u64,
+    d_type: u8,
+}
+
+impl DirEntry {
+    /// Returns the full path of the entry, which is the path of the directory joined with the
+    /// file name of the entry.
+    pub fn path(&self) -> PathBuf {
+        self.root.join(&self.name)
+    }
+
+    /// Returns the file name of the entry, without any leading path component.
+    pub fn file_name(&self) -> OsString {
+        self.name.clone()
+    }
+
+    /// Returns the inode number of the entry.
+    pub fn ino(&self) -> u64 {
+        self.ino
+    }
+
+    /// Returns the file type of the entry, without following the symbolic links.
+    ///
+    /// It comes from the directory entry on most of the file systems, or queries the metadata
+    /// if the file system does not provide it.
+    pub async fn file_type(&self) -> io::Result<FileType> {
+        match dirent_mode(self.d_type) {
+            Some(mode) => Ok(FileType { mode }),
+            None => self.metadata().await.map(|m| m.file_type()),
+        }
+    }
+
+    /// Queries the metadata of the entry, without following the symbolic links.
+    pub async fn metadata(&self) -> io::Result<Metadata> {
+        super::symlink_metadata(self.path()).await
+    }
+}
+
+fn dirent_mode(d_type: u8) -> Option<libc::mode_t> {
+    Some(match d_type {
+        libc::DT_REG => libc::S_IFREG,
+        libc::DT_DIR => libc::S_IFDIR,
+        libc::DT_LNK => libc::S_IFLNK,
+        libc::DT_FIFO => libc::S_IFIFO,
+        libc::DT_SOCK => libc::S_IFSOCK,
+        libc::DT_CHR => libc::S_IFCHR,
+        libc::DT_BLK => libc::S_IFBLK,
+        _ => return None,
+    })
+}
+
+/// Removes the directory at `path` after removing all its contents, without following the
+/// symbolic links. If `path` is a symbolic link, the link itself is removed.
+///
+/// The directories are walked with [`Dir`], so the entries are removed relative to the fds of
+/// their parents.
+///
+/// # Errors
+///
+/// Returns an error if `path` does not exist, or any entry can not be removed. The entries
+/// removed concurrently by others are ignored.
+///
+/// # Examples
+///
+/// ```no_run
+/// #[monoio::main]
+/// async fn main() -> std::io::Result<()> {
+///     monoio::fs::remove_dir_all("/some/dir").await?;
+///     Ok(())
+/// }
+/// ```
+#[cfg(feature = "unlinkat")]
+pub async fn remove_dir_all(path: impl AsRef<Path>) -> io::Result<()> {
+    let path = path.as_ref();
+    if super::symlink_metadata(path).await?.is_symlink() {
+        return super::remove_file(path).await;
+    }
+    let dir = Dir::open_nofollow(path).await?;
+    remove_dir_contents(&dir).await?;
+    dir.close().await?;
+    super::remove_dir(path).await
+}
+
+#[cfg(feature = "unlinkat")]
+impl Dir {
+    async fn open_nofollow(path: &Path) -> io::Result<Dir> {
+        let file = dir_options(true).open(path).await?;
+        Ok(Dir {
+            file,
+            path: Arc::from(path),
+        })
+    }
+}
+
+#[cfg(feature = "unlinkat")]
+fn remove_dir_contents(
+    dir: &Dir,
+) -> std::pin::Pin<Box<dyn std::future::Future<Output = io::Result<()>> + '_>> {
+    Box::pin(async move {
+        let mut entries = dir.read_dir().await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name();
+            let is_dir = match entry.file_type().await {
+                Ok(file_type) => file_type.is_dir(),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e),
+            };
+            let res = if is_dir {
+                match dir.open_dir_inner(Path::new(&name), true).await {
+                    Ok(child) => {
+                        remove_dir_contents(&child).await?;
+                        child.close().await?;
+                        dir.remove_dir(&name).await
+                    }
+                    Err(e) => Err(e),
+                }
+            } else {
+                dir.remove_file(&name).await
+            };
+            match res {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+        }
+        Ok(())
+    })
+}
@@ -1,10 +1,15 @@
 use std::{future::Future, io, path::Path};
 
+#[cfg(any(windows, feature = "iouring", not(feature = "sync")))]
+use crate::driver::op::Op;
 use crate::{
-    buf::{IoBuf, IoBufMut, IoVecBuf, IoVecBufMut},
-    driver::{op::Op, shared_fd::SharedFd},
+    buf::{FixedBuf, IoBuf, IoBufMut, IoVecBuf, IoVecBufMut},
+    driver::shared_fd::SharedFd,
     fs::OpenOptions,
-    io::{AsyncReadRent, AsyncWriteRent},
+    io::{
+        operation_canceled, AsyncReadRent, AsyncWriteRent, CancelHandle, CancelableAsyncReadRent,
+        CancelableAsyncWriteRent,
+    },
     BufResult,
 };
 
@@ -69,6 +74,11 @@ pub struct File {
 }
 
 impl File {
+    #[allow(unused)]
+    pub(crate) fn shared_fd(&self) -> &SharedFd {
+        &self.fd
+    }
+
     /// Attempts to open a file in read-only mode.
     ///
     /// See the [`OpenOptions::open`] method for more details.
@@ -209,6 +219,45 @@ impl File {
         file_impl::read_at(self.fd.clone(), buf, pos).await
     }
 
+    /// Read some bytes at the specified offset from the file into the registered buffer.
+    ///
+    /// It works like [`read_at`], but uses `IORING_OP_READ_FIXED` so the kernel does not
+    /// need to map the buffer for every read. If the buffer pool is not registered (e.g. with
+    /// legacy driver), it falls back to [`read_at`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use monoio::{buf::FixedBufPool, fs::File};
+    ///
+    /// #[monoio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let pool = FixedBufPool::new(4, 4096)?;
+    ///     let f = File::open("foo.txt").await?;
+    ///
+    ///     let buf = pool.try_next().unwrap();
+    ///     let (res, buf) = f.read_fixed_at(buf, 0).await;
+    ///     let n = res?;
+    ///
+    ///     println!("The bytes: {:?}", &buf[..n]);
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [`read_at`]: File::read_at
+    pub async fn read_fixed_at(
+        &self,
+        buf: FixedBuf,
+        pos: u64,
+    ) -> crate::BufResult<usize, FixedBuf> {
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        if buf.is_registered() {
+            let op = Op::read_fixed(self.fd.clone(), buf, pos).unwrap();
+            return op.result().await;
+        }
+        self.read_at(buf, pos).await
+    }
+
     /// Read the exact number of bytes required to fill `buf` at the specified
     /// offset from the file.
     ///
@@ -361,6 +410,26 @@ impl File {
         file_impl::write_at(self.fd.clone(), buf, pos).await
     }
 
+    /// Write the registered buffer into this file at the specified offset.
+    ///
+    /// It works like [`write_at`], but uses `IORING_OP_WRITE_FIXED` so the kernel does not
+    /// need to map the buffer for every write. If the buffer pool is not registered (e.g. with
+    /// legacy driver), it falls back to [`write_at`].
+    ///
+    /// [`write_at`]: File::write_at
+    pub async fn write_fixed_at(
+        &self,
+        buf: FixedBuf,
+        pos: u64,
+    ) -> crate::BufResult<usize, FixedBuf> {
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        if buf.is_registered() {
+            let op = Op::write_fixed(self.fd.clone(), buf, pos).unwrap();
+            return op.result().await;
+        }
+        self.write_at(buf, pos).await
+    }
+
     /// Attempts to write an entire buffer into this file at the specified
     /// offset.
     ///
@@ -458,11 +527,18 @@ impl File {
     /// }
     /// ```
     pub async fn sync_all(&self) -> io::Result<()> {
-        let op = Op::fsync(&self.fd).unwrap();
-        let completion = op.await;
+        #[cfg(unix)]
+        {
+            file_impl::fsync(self.fd.clone(), false).await
+        }
+        #[cfg(windows)]
+        {
+            let op = Op::fsync(&self.fd).unwrap();
+            let completion = op.await;
 
-        completion.meta.result?;
-        Ok(())
+            completion.meta.result?;
+            Ok(())
+        }
     }
 
     /// Attempts to sync file data to disk.
@@ -498,11 +574,211 @@ impl File {
     /// }
     /// ```
     pub async fn sync_data(&self) -> io::Result<()> {
-        let op = Op::datasync(&self.fd).unwrap();
-        let completion = op.await;
+        #[cfg(unix)]
+        {
+            file_impl::fsync(self.fd.clone(), true).await
+        }
+        #[cfg(windows)]
+        {
+            let op = Op::datasync(&self.fd).unwrap();
+            let completion = op.await;
 
-        completion.meta.result?;
-        Ok(())
+            completion.meta.result?;
+            Ok(())
+        }
+    }
+
+    /// Sync a range of the file data with `sync_file_range(2)`, which does not flush the file
+    /// metadata nor the disk write cache, so it does not guarantee durability by itself. It is
+    /// useful for starting the writeback early(`SYNC_FILE_RANGE_WRITE`) before a later
+    /// [`sync_data`].
+    ///
+    /// `len` of 0 means to the end of the file. `flags` is a combination of
+    /// `libc::SYNC_FILE_RANGE_WAIT_BEFORE`, `libc::SYNC_FILE_RANGE_WRITE` and
+    /// `libc::SYNC_FILE_RANGE_WAIT_AFTER`.
+    ///
+    /// With io_uring driver it uses `IORING_OP_SYNC_FILE_RANGE`(requires kernel 5.2+).
+    ///
+    /// [`sync_data`]: File::sync_data
+    #[cfg(target_os = "linux")]
+    pub async fn sync_range(&self, offset: u64, len: u32, flags: u32) -> io::Result<()> {
+        file_impl::sync_range(self.fd.clone(), offset, len, flags).await
+    }
+
+    /// Manipulate the allocated disk space of the range with `fallocate(2)`.
+    ///
+    /// With `mode` of 0 the space is allocated and the file size is extended if the range goes
+    /// beyond it. `mode` can be a combination of the `libc::FALLOC_FL_*` flags, like
+    /// `FALLOC_FL_KEEP_SIZE` to pre-allocate without changing the size, and
+    /// `FALLOC_FL_PUNCH_HOLE | FALLOC_FL_KEEP_SIZE` to deallocate the range.
+    ///
+    /// With io_uring driver it uses `IORING_OP_FALLOCATE`(requires kernel 5.6+).
+    #[cfg(target_os = "linux")]
+    pub async fn allocate(&self, offset: u64, len: u64, mode: i32) -> io::Result<()> {
+        file_impl::allocate(self.fd.clone(), offset, len, mode).await
+    }
+
+    /// Give advice about the access pattern of the file range with `posix_fadvise(2)`, like
+    /// dropping the cached data with [`Advice::DontNeed`] after a large sequential scan.
+    ///
+    /// `len` of 0 means to the end of the file.
+    ///
+    /// With io_uring driver it uses `IORING_OP_FADVISE`(requires kernel 5.6+), so it does not
+    /// block the thread even if the kernel needs to do some work, like writing back the dirty
+    /// pages for [`Advice::DontNeed`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use monoio::fs::{Advice, File};
+    ///
+    /// #[monoio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let file = File::open("foo.txt").await?;
+    ///     file.advise(0, 0, Advice::Sequential).await?;
+    ///     // scan the file ...
+    ///     file.advise(0, 0, Advice::DontNeed).await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [`Advice::DontNeed`]: crate::fs::Advice::DontNeed
+    #[cfg(target_os = "linux")]
+    pub async fn advise(&self, offset: u64, len: u32, advice: crate::fs::Advice) -> io::Result<()> {
+        crate::fs::advice::fadvise(&self.fd, offset, len, advice).await
+    }
+
+    /// Retrieve the value of the extended attribute `name` of the file, or `None` if the file
+    /// has no such attribute.
+    ///
+    /// With io_uring driver it uses `IORING_OP_FGETXATTR`(requires kernel 5.19+), or
+    /// `fgetxattr(2)` if the kernel does not support it.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use monoio::fs::File;
+    ///
+    /// #[monoio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let file = File::open("foo.txt").await?;
+    ///     file.set_xattr("user.origin", b"bar", 0).await?;
+    ///     assert_eq!(
+    ///         file.get_xattr("user.origin").await?.as_deref(),
+    ///         Some(&b"bar"[..])
+    ///     );
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(target_os = "linux")]
+    pub async fn get_xattr<N: AsRef<std::ffi::OsStr>>(
+        &self,
+        name: N,
+    ) -> io::Result<Option<Vec<u8>>> {
+        crate::fs::xattr::fget(&self.fd, name.as_ref()).await
+    }
+
+    /// Set the value of the extended attribute `name` of the file.
+    ///
+    /// `flags` of 0 creates or replaces the attribute, or use [`XATTR_CREATE`] or
+    /// [`XATTR_REPLACE`] to fail if it exists or not.
+    ///
+    /// With io_uring driver it uses `IORING_OP_FSETXATTR`(requires kernel 5.19+), or
+    /// `fsetxattr(2)` if the kernel does not support it.
+    ///
+    /// [`XATTR_CREATE`]: crate::fs::XATTR_CREATE
+    /// [`XATTR_REPLACE`]: crate::fs::XATTR_REPLACE
+    #[cfg(target_os = "linux")]
+    pub async fn set_xattr<N: AsRef<std::ffi::OsStr>>(
+        &self,
+        name: N,
+        value: &[u8],
+        flags: i32,
+    ) -> io::Result<()> {
+        crate::fs::xattr::fset(&self.fd, name.as_ref(), value, flags).await
+    }
+
+    /// List the names of the extended attributes of the file with `flistxattr(2)`.
+    #[cfg(target_os = "linux")]
+    pub async fn list_xattr(&self) -> io::Result<Vec<std::ffi::OsString>> {
+        crate::fs::xattr::flist(&self.fd).await
+    }
+
+    /// Remove the extended attribute `name` of the file with `fremovexattr(2)`.
+    #[cfg(target_os = "linux")]
+    pub async fn remove_xattr<N: AsRef<std::ffi::OsStr>>(&self, name: N) -> io::Result<()> {
+        crate::fs::xattr::fremove(&self.fd, name.as_ref()).await
+    }
+
+    /// Truncate or extend the file to `len` bytes, like [`std::fs::File::set_len`]. The extended
+    /// part is filled with zeros.
+    ///
+    /// With io_uring driver it uses `IORING_OP_FTRUNCATE`(requires kernel 6.9+), or
+    /// `ftruncate(2)` if the kernel does not support it.
+    #[cfg(target_os = "linux")]
+    pub async fn set_len(&self, len: u64) -> io::Result<()> {
+        file_impl::set_len(self.fd.clone(), len).await
+    }
+
+    /// Write a buffer into this file at the specified offset, and then sync the file data to
+    /// disk like [`sync_data`].
+    ///
+    /// With io_uring driver the write and the sync are linked(`IOSQE_IO_LINK`), so they are
+    /// submitted together and the kernel starts the sync only after the write succeeds. If the
+    /// write is short, the sync is issued again on its own, so the written bytes are always
+    /// synced when it returns `Ok`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use monoio::fs::File;
+    ///
+    /// #[monoio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let file = File::create("foo.txt").await?;
+    ///
+    ///     // Write and sync some bytes
+    ///     let (res, buf) = file.write_at_sync(&b"some bytes"[..], 0).await;
+    ///     let n = res?;
+    ///
+    ///     println!("wrote and synced {} bytes", n);
+    ///
+    ///     // Close the file
+    ///     file.close().await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [`sync_data`]: File::sync_data
+    pub async fn write_at_sync<T: IoBuf>(&self, buf: T, pos: u64) -> crate::BufResult<usize, T> {
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        {
+            use crate::driver::link;
+
+            let ((res, buf), sync) =
+                link::submit((link::write_at(self, buf, pos), link::sync_data(self))).await;
+            let n = match res {
+                Ok(n) => n,
+                Err(e) => return (Err(e), buf),
+            };
+            match sync {
+                Ok(_) => (Ok(n), buf),
+                // A short write breaks the link.
+                Err(e) if e.raw_os_error() == Some(libc::ECANCELED) => {
+                    (self.sync_data().await.map(|_| n), buf)
+                }
+                Err(e) => (Err(e), buf),
+            }
+        }
+
+        #[cfg(not(all(target_os = "linux", feature = "iouring")))]
+        {
+            let (res, buf) = file_impl::write_at(self.fd.clone(), buf, pos).await;
+            match res {
+                Ok(n) => (self.sync_data().await.map(|_| n), buf),
+                Err(e) => (Err(e), buf),
+            }
+        }
     }
 
     #[inline]
@@ -510,6 +786,17 @@ impl File {
         std::future::ready(Ok(()))
     }
 
+    /// Install the file into the fixed file table of io_uring and return the slot.
+    ///
+    /// After that, the io_uring operations on it refer to the slot instead of the fd. The table
+    /// must be registered with
+    /// [`RuntimeBuilder::with_fixed_files`](crate::RuntimeBuilder::with_fixed_files), and the
+    /// slot is released when the file is closed. It fails with legacy driver.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    pub async fn register_fixed(&self) -> io::Result<u32> {
+        self.fd.register_fixed().await
+    }
+
     /// Closes the file.
     ///
     /// The method completes once the close operation has completed,
@@ -777,3 +1064,112 @@ impl AsyncReadRentAt for File {
         File::read_at(self, buf, pos as u64)
     }
 }
+
+/// Cancelable read and write on the file.
+///
+/// With io_uring driver the in-flight operation is canceled with `IORING_OP_ASYNC_CANCEL`,
+/// which is useful on files that may block for long, like pipes or character devices. When
+/// the operation is offloaded to the blocking thread pool, it can only be canceled before it
+/// starts.
+impl CancelableAsyncReadRent for File {
+    async fn cancelable_read<T: IoBufMut>(
+        &mut self,
+        buf: T,
+        c: CancelHandle,
+    ) -> crate::BufResult<usize, T> {
+        if c.canceled() {
+            return (Err(operation_canceled()), buf);
+        }
+
+        #[cfg(any(feature = "iouring", not(feature = "sync")))]
+        {
+            let op = Op::read(self.fd.clone(), buf).unwrap();
+            let _guard = c.associate_op(op.op_canceller());
+            op.result().await
+        }
+        #[cfg(all(not(feature = "iouring"), feature = "sync"))]
+        file_impl::read(self.fd.clone(), buf).await
+    }
+
+    async fn cancelable_readv<T: IoVecBufMut>(
+        &mut self,
+        buf_vec: T,
+        c: CancelHandle,
+    ) -> crate::BufResult<usize, T> {
+        if c.canceled() {
+            return (Err(operation_canceled()), buf_vec);
+        }
+
+        #[cfg(all(unix, any(feature = "iouring", not(feature = "sync"))))]
+        {
+            let op = Op::readv(self.fd.clone(), buf_vec).unwrap();
+            let _guard = c.associate_op(op.op_canceller());
+            op.result().await
+        }
+        #[cfg(not(all(unix, any(feature = "iouring", not(feature = "sync")))))]
+        file_impl::read_vectored(self.fd.clone(), buf_vec).await
+    }
+}
+
+impl crate::io::as_fd::AsReadFd for File {
+    #[inline]
+    fn as_reader_fd(&mut self) -> &crate::io::as_fd::SharedFdWrapper {
+        crate::io::as_fd::SharedFdWrapper::new(&self.fd)
+    }
+}
+
+impl crate::io::as_fd::AsWriteFd for File {
+    #[inline]
+    fn as_writer_fd(&mut self) -> &crate::io::as_fd::SharedFdWrapper {
+        crate::io::as_fd::SharedFdWrapper::new(&self.fd)
+    }
+}
+
+impl CancelableAsyncWriteRent for File {
+    async fn cancelable_write<T: IoBuf>(
+        &mut self,
+        buf: T,
+        c: CancelHandle,
+    ) -> crate::BufResult<usize, T> {
+        if c.canceled() {
+            return (Err(operation_canceled()), buf);
+        }
+
+        #[cfg(any(feature = "iouring", not(feature = "sync")))]
+        {
+            let op = Op::write(self.fd.clone(), buf).unwrap();
+            let _guard = c.associate_op(op.op_canceller());
+            op.result().await
+        }
+        #[cfg(all(not(feature = "iouring"), feature = "sync"))]
+        file_impl::write(self.fd.clone(), buf).await
+    }
+
+    async fn cancelable_writev<T: IoVecBuf>(
+        &mut self,
+        buf_vec: T,
+        c: CancelHandle,
+    ) -> crate::BufResult<usize, T> {
+        if c.canceled() {
+            return (Err(operation_canceled()), buf_vec);
+        }
+
+        #[cfg(all(unix, any(feature = "iouring", not(feature = "sync"))))]
+        {
+            let op = Op::writev(self.fd.clone(), buf_vec).unwrap();
+            let _guard = c.associate_op(op.op_canceller());
+            op.result().await
+        }
+        #[cfg(not(all(unix, any(feature = "iouring", not(feature = "sync")))))]
+        file_impl::write_vectored(self.fd.clone(), buf_vec).await
+    }
+
+    async fn cancelable_flush(&mut self, _c: CancelHandle) -> io::Result<()> {
+        // File does not need flush.
+        Ok(())
+    }
+
+    async fn cancelable_shutdown(&mut self, _c: CancelHandle) -> io::Result<()> {
+        Ok(())
+    }
+}
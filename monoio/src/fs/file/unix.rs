@@ -12,8 +12,8 @@ pub(crate) use iouring::*;
 use super::File;
 use crate::{
     buf::{IoBuf, IoBufMut, IoVecBuf, IoVecBufMut},
-    driver::{op::Op, shared_fd::SharedFd},
-    fs::{metadata::FileAttr, Metadata},
+    driver::shared_fd::SharedFd,
+    fs::Metadata,
 };
 
 impl File {
@@ -58,21 +58,10 @@ impl AsRawFd for File {
     }
 }
 
-pub(crate) async fn metadata(fd: SharedFd) -> std::io::Result<Metadata> {
-    #[cfg(target_os = "linux")]
-    let flags = libc::AT_STATX_SYNC_AS_STAT | libc::AT_EMPTY_PATH;
-    #[cfg(target_os = "linux")]
-    let op = Op::statx_using_fd(fd, flags)?;
-    #[cfg(target_os = "macos")]
-    let op = Op::statx_using_fd(fd, true)?;
-
-    op.result().await.map(FileAttr::from).map(Metadata)
-}
-
 #[cfg(any(feature = "iouring", not(feature = "sync")))]
 mod iouring {
     use super::*;
-    use crate::uring_op;
+    use crate::{driver::op::Op, fs::metadata::FileAttr, uring_op};
 
     uring_op!(read<IoBufMut>(read, buf));
     uring_op!(read_at<IoBufMut>(read_at, buf, pos: u64));
@@ -81,6 +70,49 @@ mod iouring {
     uring_op!(write<IoBuf>(write, buf));
     uring_op!(write_at<IoBuf>(write_at, buf, pos: u64));
     uring_op!(write_vectored<IoVecBuf>(writev, buf_vec));
+
+    pub(crate) async fn metadata(fd: SharedFd) -> io::Result<Metadata> {
+        #[cfg(target_os = "linux")]
+        let flags = libc::AT_STATX_SYNC_AS_STAT | libc::AT_EMPTY_PATH;
+        #[cfg(target_os = "linux")]
+        let op = Op::statx_using_fd(fd, flags)?;
+        #[cfg(target_os = "macos")]
+        let op = Op::statx_using_fd(fd, true)?;
+
+        op.result().await.map(FileAttr::from).map(Metadata)
+    }
+
+    pub(crate) async fn fsync(fd: SharedFd, data_sync: bool) -> io::Result<()> {
+        let op = if data_sync {
+            Op::datasync(&fd)?
+        } else {
+            Op::fsync(&fd)?
+        };
+        op.await.meta.result.map(|_| ())
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(crate) async fn sync_range(
+        fd: SharedFd,
+        offset: u64,
+        len: u32,
+        flags: u32,
+    ) -> io::Result<()> {
+        let op = Op::sync_file_range(&fd, offset, len, flags)?;
+        op.await.meta.result.map(|_| ())
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(crate) async fn allocate(fd: SharedFd, offset: u64, len: u64, mode: i32) -> io::Result<()> {
+        let op = Op::fallocate(&fd, offset, len, mode)?;
+        op.await.meta.result.map(|_| ())
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(crate) async fn set_len(fd: SharedFd, len: u64) -> io::Result<()> {
+        let op = Op::ftruncate(&fd, len)?;
+        op.await.meta.result.map(|_| ())
+    }
 }
 
 #[cfg(all(not(feature = "iouring"), feature = "sync"))]
@@ -98,4 +130,70 @@ mod asyncified {
     asyncify_op!(W, write<IoBuf>(write::write, IoBuf::read_ptr, IoBuf::bytes_init));
     asyncify_op!(W, write_at<IoBuf>(write::write_at, IoBuf::read_ptr, IoBuf::bytes_init, pos: u64));
     asyncify_op!(W, write_vectored<IoVecBuf>(write::write_vectored, IoVecBuf::read_iovec_ptr, IoVecBuf::read_iovec_len));
+
+    pub(crate) async fn metadata(fd: SharedFd) -> io::Result<Metadata> {
+        let fd = fd.as_raw_fd();
+        crate::fs::asyncify(move || crate::fs::metadata::stat(Some(fd), c"", true))
+            .await
+            .map(Metadata)
+    }
+
+    pub(crate) async fn fsync(
+        fd: SharedFd,
+        #[cfg_attr(not(target_os = "linux"), allow(unused))] data_sync: bool,
+    ) -> io::Result<()> {
+        let fd = fd.as_raw_fd();
+        crate::fs::asyncify(move || {
+            #[cfg(target_os = "linux")]
+            if data_sync {
+                return crate::syscall!(fdatasync@RAW(fd));
+            }
+            crate::syscall!(fsync@RAW(fd))
+        })
+        .await
+        .map(|_| ())
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(crate) async fn sync_range(
+        fd: SharedFd,
+        offset: u64,
+        len: u32,
+        flags: u32,
+    ) -> io::Result<()> {
+        let fd = fd.as_raw_fd();
+        crate::fs::asyncify(move || {
+            crate::syscall!(sync_file_range@RAW(
+                fd,
+                offset as libc::off64_t,
+                len as libc::off64_t,
+                flags
+            ))
+        })
+        .await
+        .map(|_| ())
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(crate) async fn allocate(fd: SharedFd, offset: u64, len: u64, mode: i32) -> io::Result<()> {
+        let fd = fd.as_raw_fd();
+        crate::fs::asyncify(move || {
+            crate::syscall!(fallocate@RAW(
+                fd,
+                mode,
+                offset as libc::off_t,
+                len as libc::off_t
+            ))
+        })
+        .await
+        .map(|_| ())
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(crate) async fn set_len(fd: SharedFd, len: u64) -> io::Result<()> {
+        let fd = fd.as_raw_fd();
+        crate::fs::asyncify(move || crate::syscall!(ftruncate@RAW(fd, len as libc::off_t)))
+            .await
+            .map(|_| ())
+    }
 }
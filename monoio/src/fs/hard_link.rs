@@ -0,0 +1,33 @@
+use std::{io, path::Path};
+
+use crate::driver::op::Op;
+
+/// Creates a new hard link on the filesystem.
+///
+/// The `link` path will be a link pointing to the `original` path. Note that systems often
+/// require these two paths to both be located on the same filesystem. If `original` is a
+/// symbolic link, the link is created to the symbolic link itself.
+///
+/// This is an async version of [`std::fs::hard_link`].
+///
+/// # Errors
+///
+/// This function will return an error in the following situations, but is not limited to just
+/// these cases:
+///
+/// * The `original` path is not a file or doesn't exist.
+/// * The `link` path already exists.
+///
+/// # Examples
+///
+/// ```no_run
+/// #[monoio::main]
+/// async fn main() -> std::io::Result<()> {
+///     monoio::fs::hard_link("a.txt", "b.txt").await?; // Hard link a.txt to b.txt
+///     Ok(())
+/// }
+/// ```
+pub async fn hard_link<P: AsRef<Path>, Q: AsRef<Path>>(original: P, link: Q) -> io::Result<()> {
+    Op::hard_link(original, link)?.await.meta.result?;
+    Ok(())
+}
@@ -4,6 +4,7 @@ mod windows;
 use std::{os::unix::fs::MetadataExt, path::Path, time::SystemTime};
 
 use super::{file_type::FileType, permissions::Permissions};
+#[cfg(any(feature = "iouring", not(feature = "sync")))]
 use crate::driver::op::Op;
 
 /// Given a path, query the file system to get information about a file,
@@ -39,16 +40,7 @@ use crate::driver::op::Op;
 /// }
 /// ```
 pub async fn metadata<P: AsRef<Path>>(path: P) -> std::io::Result<Metadata> {
-    #[cfg(target_os = "linux")]
-    let flags = libc::AT_STATX_SYNC_AS_STAT;
-
-    #[cfg(target_os = "linux")]
-    let op = Op::statx_using_path(path, flags)?;
-
-    #[cfg(target_os = "macos")]
-    let op = Op::statx_using_path(path, true)?;
-
-    op.result().await.map(FileAttr::from).map(Metadata)
+    path_metadata(path.as_ref(), true).await
 }
 
 /// Query the metadata about a file without following symlinks.
@@ -79,18 +71,38 @@ pub async fn metadata<P: AsRef<Path>>(path: P) -> std::io::Result<Metadata> {
 /// }
 /// ```
 pub async fn symlink_metadata<P: AsRef<Path>>(path: P) -> std::io::Result<Metadata> {
-    #[cfg(target_os = "linux")]
-    let flags = libc::AT_STATX_SYNC_AS_STAT | libc::AT_SYMLINK_NOFOLLOW;
+    path_metadata(path.as_ref(), false).await
+}
 
+#[cfg(any(feature = "iouring", not(feature = "sync")))]
+async fn path_metadata(path: &Path, follow_symlinks: bool) -> std::io::Result<Metadata> {
     #[cfg(target_os = "linux")]
-    let op = Op::statx_using_path(path, flags)?;
+    let op = {
+        let mut flags = libc::AT_STATX_SYNC_AS_STAT;
+        if !follow_symlinks {
+            flags |= libc::AT_SYMLINK_NOFOLLOW;
+        }
+        Op::statx_using_path(path, flags)?
+    };
 
     #[cfg(target_os = "macos")]
-    let op = Op::statx_using_path(path, false)?;
+    let op = Op::statx_using_path(path, follow_symlinks)?;
 
     op.result().await.map(FileAttr::from).map(Metadata)
 }
 
+#[cfg(all(feature = "sync", not(feature = "iouring")))]
+async fn path_metadata(path: &Path, follow_symlinks: bool) -> std::io::Result<Metadata> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = std::ffi::CString::new(path.as_os_str().as_bytes())?;
+    crate::fs::asyncify(move || stat(None, &path, follow_symlinks))
+        .await
+        .map(Metadata)
+}
+
+#[cfg(all(unix, feature = "sync", not(feature = "iouring")))]
+pub(crate) use unix::stat;
 #[cfg(unix)]
 pub(crate) use unix::FileAttr;
 
@@ -101,6 +113,7 @@ pub(crate) use unix::FileAttr;
 /// metadata about a file such as its permissions, size, modification
 /// times, etc.
 #[cfg(unix)]
+#[derive(Clone)]
 pub struct Metadata(pub(crate) FileAttr);
 
 impl Metadata {
@@ -248,17 +261,11 @@ impl Metadata {
 
                 Ok(SystemTime::UNIX_EPOCH + std::time::Duration::new(btime as u64, btime_nsec))
             } else {
-                Err(std::io::Error::new(
-                    std::io::ErrorKind::Other,
-                    "Creation time is not available",
-                ))
+                Err(std::io::Error::other("Creation time is not available"))
             };
         }
 
-        Err(std::io::Error::new(
-            std::io::ErrorKind::Other,
-            "Creation time is not available",
-        ))
+        Err(std::io::Error::other("Creation time is not available"))
     }
 
     /// Returns the permissions of the file this metadata is for.
@@ -307,7 +314,7 @@ impl Metadata {
 impl std::fmt::Debug for Metadata {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut debug = f.debug_struct("Metadata");
-        // debug.field("file_type", &self.file_type());
+        debug.field("file_type", &self.file_type());
         debug.field("permissions", &self.permissions());
         debug.field("len", &self.len());
         if let Ok(modified) = self.modified() {
@@ -2,6 +2,7 @@ use libc::mode_t;
 
 use crate::fs::{file_type::FileType, permissions::FilePermissions};
 
+#[derive(Clone)]
 pub(crate) struct FileAttr {
     #[cfg(target_os = "linux")]
     pub(crate) stat: libc::stat64,
@@ -32,6 +33,7 @@ impl FileAttr {
 
 /// Extra fields that are available in `statx` struct.
 #[cfg(target_os = "linux")]
+#[derive(Clone)]
 pub(crate) struct StatxExtraFields {
     pub(crate) stx_mask: u32,
     pub(crate) stx_btime: libc::statx_timestamp,
@@ -80,3 +82,43 @@ impl From<libc::stat> for FileAttr {
         Self { stat }
     }
 }
+
+/// Query the metadata of the file `fd`, or `path` if `fd` is `None`, in place, which blocks the
+/// thread. It is used on the blocking pool when io_uring is not available.
+#[cfg(all(feature = "sync", not(feature = "iouring")))]
+pub(crate) fn stat(
+    fd: Option<std::os::fd::RawFd>,
+    path: &std::ffi::CStr,
+    follow_symlinks: bool,
+) -> std::io::Result<FileAttr> {
+    #[cfg(target_os = "linux")]
+    {
+        let mut flags = libc::AT_STATX_SYNC_AS_STAT;
+        if fd.is_some() {
+            flags |= libc::AT_EMPTY_PATH;
+        }
+        if !follow_symlinks {
+            flags |= libc::AT_SYMLINK_NOFOLLOW;
+        }
+        let mut buf = std::mem::MaybeUninit::<libc::statx>::uninit();
+        crate::syscall!(statx@RAW(
+            fd.unwrap_or(libc::AT_FDCWD),
+            path.as_ptr(),
+            flags,
+            libc::STATX_ALL,
+            buf.as_mut_ptr()
+        ))?;
+        Ok(FileAttr::from(unsafe { buf.assume_init() }))
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let mut buf = std::mem::MaybeUninit::<libc::stat>::uninit();
+        match fd {
+            Some(fd) => crate::syscall!(fstat@RAW(fd, buf.as_mut_ptr()))?,
+            None if follow_symlinks => crate::syscall!(stat@RAW(path.as_ptr(), buf.as_mut_ptr()))?,
+            None => crate::syscall!(lstat@RAW(path.as_ptr(), buf.as_mut_ptr()))?,
+        };
+        Ok(FileAttr::from(unsafe { buf.assume_init() }))
+    }
+}
@@ -20,9 +20,41 @@ mod symlink;
 #[cfg(all(unix, feature = "symlinkat"))]
 pub use symlink::symlink;
 
+#[cfg(all(unix, feature = "linkat"))]
+mod hard_link;
+#[cfg(all(unix, feature = "linkat"))]
+pub use hard_link::hard_link;
+
 mod open_options;
 pub use open_options::OpenOptions;
 
+#[cfg(target_os = "linux")]
+mod advice;
+#[cfg(target_os = "linux")]
+pub use advice::{madvise, Advice};
+
+#[cfg(target_os = "linux")]
+mod copy;
+#[cfg(target_os = "linux")]
+pub use copy::{copy, copy_with_pool};
+
+#[cfg(target_os = "linux")]
+mod dir;
+#[cfg(all(target_os = "linux", feature = "unlinkat"))]
+pub use dir::remove_dir_all;
+#[cfg(target_os = "linux")]
+pub use dir::{read_dir, Dir, DirEntry, ReadDir};
+
+#[cfg(target_os = "linux")]
+mod xattr;
+#[cfg(target_os = "linux")]
+pub use xattr::{get_xattr, list_xattr, remove_xattr, set_xattr, XATTR_CREATE, XATTR_REPLACE};
+
+#[cfg(target_os = "linux")]
+mod watcher;
+#[cfg(target_os = "linux")]
+pub use watcher::{Event, EventKind, WatchDescriptor, Watcher};
+
 #[cfg(unix)]
 mod metadata;
 #[cfg(unix)]
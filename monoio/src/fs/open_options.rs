@@ -74,6 +74,10 @@ pub struct OpenOptions {
     pub(crate) mode: libc::mode_t,
     #[cfg(unix)]
     pub(crate) custom_flags: libc::c_int,
+    #[cfg(target_os = "linux")]
+    pub(crate) resolve: u64,
+    #[cfg(target_os = "linux")]
+    pub(crate) direct: bool,
     #[cfg(windows)]
     pub(crate) custom_flags: u32,
     #[cfg(windows)]
@@ -118,6 +122,10 @@ impl OpenOptions {
             mode: 0o666,
             #[cfg(unix)]
             custom_flags: 0,
+            #[cfg(target_os = "linux")]
+            resolve: 0,
+            #[cfg(target_os = "linux")]
+            direct: false,
             #[cfg(windows)]
             custom_flags: 0,
             #[cfg(windows)]
@@ -301,6 +309,112 @@ impl OpenOptions {
         self
     }
 
+    /// Sets the option to fail the open if the path escapes the directory it is resolved
+    /// relative to, by `..` components, absolute paths or symlinks
+    /// (`RESOLVE_BENEATH` of `openat2(2)`).
+    ///
+    /// The directory is the current directory with [`open`], or the one passed to [`open_at`].
+    /// It is useful to open the user-controlled paths under a directory safely.
+    ///
+    /// Setting any of the resolve options makes the file opened by `openat2(2)`, which is
+    /// available since Linux 5.6.
+    ///
+    /// [`open`]: OpenOptions::open
+    /// [`open_at`]: OpenOptions::open_at
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use monoio::fs::{File, OpenOptions};
+    ///
+    /// #[monoio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let root = File::open("/srv/www").await?;
+    ///     // Fails with `EXDEV` instead of opening `/etc/passwd`.
+    ///     let file = OpenOptions::new()
+    ///         .read(true)
+    ///         .open_beneath(true)
+    ///         .open_at(&root, "../../etc/passwd")
+    ///         .await;
+    ///     assert!(file.is_err());
+    ///     Ok(())
+    /// }
+    /// ```
+    #[cfg(target_os = "linux")]
+    pub fn open_beneath(&mut self, open_beneath: bool) -> &mut OpenOptions {
+        self.set_resolve(libc::RESOLVE_BENEATH, open_beneath)
+    }
+
+    /// Sets the option to resolve the path as if the directory it is resolved relative to was
+    /// the root, so `..` and absolute symlinks can not escape from it (`RESOLVE_IN_ROOT` of
+    /// `openat2(2)`).
+    #[cfg(target_os = "linux")]
+    pub fn in_root(&mut self, in_root: bool) -> &mut OpenOptions {
+        self.set_resolve(libc::RESOLVE_IN_ROOT, in_root)
+    }
+
+    /// Sets the option to fail the open if any component of the path is a symlink, including
+    /// the magic links (`RESOLVE_NO_SYMLINKS` of `openat2(2)`).
+    #[cfg(target_os = "linux")]
+    pub fn no_symlinks(&mut self, no_symlinks: bool) -> &mut OpenOptions {
+        self.set_resolve(libc::RESOLVE_NO_SYMLINKS, no_symlinks)
+    }
+
+    /// Sets the option to fail the open if any component of the path is a magic link, like
+    /// `/proc/[pid]/fd/*` (`RESOLVE_NO_MAGICLINKS` of `openat2(2)`).
+    #[cfg(target_os = "linux")]
+    pub fn no_magiclinks(&mut self, no_magiclinks: bool) -> &mut OpenOptions {
+        self.set_resolve(libc::RESOLVE_NO_MAGICLINKS, no_magiclinks)
+    }
+
+    /// Sets the option to fail the open if the path crosses a mount point
+    /// (`RESOLVE_NO_XDEV` of `openat2(2)`).
+    #[cfg(target_os = "linux")]
+    pub fn no_xdev(&mut self, no_xdev: bool) -> &mut OpenOptions {
+        self.set_resolve(libc::RESOLVE_NO_XDEV, no_xdev)
+    }
+
+    /// Sets the option to open the file with `O_DIRECT`, which bypasses the page cache.
+    ///
+    /// The buffers, the lengths and the offsets of the IO on the file must usually be aligned to
+    /// the logical block size of the device, see [`AlignedBuf`]. Some file systems do not support
+    /// it, like tmpfs, and the open fails with `EINVAL`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use monoio::{buf::AlignedBuf, fs::OpenOptions};
+    ///
+    /// #[monoio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let file = OpenOptions::new()
+    ///         .read(true)
+    ///         .direct(true)
+    ///         .open("foo.db")
+    ///         .await?;
+    ///     let (res, buf) = file.read_at(AlignedBuf::with_capacity(4096), 0).await;
+    ///     println!("read {} bytes", res?);
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [`AlignedBuf`]: crate::buf::AlignedBuf
+    #[cfg(target_os = "linux")]
+    pub fn direct(&mut self, direct: bool) -> &mut OpenOptions {
+        self.direct = direct;
+        self
+    }
+
+    #[cfg(target_os = "linux")]
+    fn set_resolve(&mut self, flag: u64, enable: bool) -> &mut OpenOptions {
+        if enable {
+            self.resolve |= flag;
+        } else {
+            self.resolve &= !flag;
+        }
+        self
+    }
+
     /// Opens a file at `path` with the options specified by `self`.
     ///
     /// # Errors
@@ -356,6 +470,33 @@ impl OpenOptions {
         )))
     }
 
+    /// Opens a file at `path` relative to the directory `dir` with the options specified by
+    /// `self`, like `openat(2)`. An absolute `path` ignores `dir` unless [`open_beneath`] or
+    /// [`in_root`] is set.
+    ///
+    /// # Errors
+    ///
+    /// Besides the errors of [`open`], it fails if `dir` is not a directory, or the path
+    /// violates the resolve options.
+    ///
+    /// [`open`]: OpenOptions::open
+    /// [`open_beneath`]: OpenOptions::open_beneath
+    /// [`in_root`]: OpenOptions::in_root
+    #[cfg(target_os = "linux")]
+    pub async fn open_at(&self, dir: &File, path: impl AsRef<Path>) -> io::Result<File> {
+        self.open_at_fd(dir.shared_fd(), path.as_ref()).await
+    }
+
+    #[cfg(target_os = "linux")]
+    pub(crate) async fn open_at_fd(&self, dir: &SharedFd, path: &Path) -> io::Result<File> {
+        let op = Op::open_at(Some(dir), path, self)?;
+        let completion = op.await;
+
+        Ok(File::from_shared_fd(SharedFd::new_without_register(
+            completion.meta.result?.into_inner() as _,
+        )))
+    }
+
     #[cfg(unix)]
     pub(crate) fn access_mode(&self) -> io::Result<libc::c_int> {
         match (self.read, self.write, self.append) {
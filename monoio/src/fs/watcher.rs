@@ -0,0 +1,335 @@
+//! Filesystem events based on inotify(Linux only).
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    ffi::{CString, OsStr},
+    io,
+    os::unix::{
+        ffi::OsStrExt,
+        prelude::{AsRawFd, RawFd},
+    },
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    driver::{op::Op, shared_fd::SharedFd},
+    io::stream::Stream,
+};
+
+/// The events watched by [`Watcher::watch`].
+const DEFAULT_MASK: u32 = libc::IN_CREATE
+    | libc::IN_MODIFY
+    | libc::IN_DELETE
+    | libc::IN_DELETE_SELF
+    | libc::IN_MOVED_FROM
+    | libc::IN_MOVED_TO;
+
+/// The identifier of a watch, returned by [`Watcher::watch`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WatchDescriptor(libc::c_int);
+
+/// What happened to a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventKind {
+    /// The file was created in a watched directory.
+    Created,
+    /// The file was written.
+    Modified,
+    /// The file was removed from a watched directory, or the watched file itself was
+    /// removed.
+    Removed,
+    /// The file was renamed within the watched directories, from the path.
+    Moved {
+        /// The path before renamed.
+        from: PathBuf,
+    },
+    /// The file was moved out of the watched directories.
+    MovedOut,
+    /// The file was moved into a watched directory from an unwatched one.
+    MovedIn,
+    /// Some events were dropped because the queue overflowed, and the path is empty.
+    Overflow,
+    /// The other events watched by [`Watcher::watch_with_mask`], with the inotify mask.
+    Other(u32),
+}
+
+/// A filesystem event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    kind: EventKind,
+    path: PathBuf,
+    is_dir: bool,
+}
+
+impl Event {
+    /// Returns what happened.
+    #[inline]
+    pub fn kind(&self) -> &EventKind {
+        &self.kind
+    }
+
+    /// Returns the path of the file, which is the watched path joined with the file name.
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns true if the file is a directory.
+    #[inline]
+    pub fn is_dir(&self) -> bool {
+        self.is_dir
+    }
+}
+
+/// A watcher of files and directories, yielding the events of them.
+///
+/// ```no_run
+/// use monoio::fs::{EventKind, Watcher};
+///
+/// #[monoio::main]
+/// async fn main() -> std::io::Result<()> {
+///     let mut watcher = Watcher::new()?;
+///     watcher.watch_recursive("/etc/myapp")?;
+///     loop {
+///         let event = watcher.next_event().await?;
+///         if let EventKind::Modified = event.kind() {
+///             println!("reload {}", event.path().display());
+///         }
+///     }
+/// }
+/// ```
+pub struct Watcher {
+    fd: SharedFd,
+    watches: HashMap<libc::c_int, PathBuf>,
+    /// The watches added by `watch_recursive`, whose new subdirectories are watched too.
+    recursive: HashSet<libc::c_int>,
+    events: VecDeque<Event>,
+    /// The `IN_MOVED_FROM` event waiting for the `IN_MOVED_TO` event of the same cookie.
+    moved_from: Option<(u32, Event)>,
+    buf: Vec<u8>,
+}
+
+impl Watcher {
+    /// Create a watcher with an inotify instance.
+    pub fn new() -> io::Result<Self> {
+        let fd = crate::syscall!(inotify_init1@RAW(libc::IN_NONBLOCK | libc::IN_CLOEXEC))?;
+        Ok(Self {
+            fd: SharedFd::new::<false>(fd)?,
+            watches: HashMap::new(),
+            recursive: HashSet::new(),
+            events: VecDeque::new(),
+            moved_from: None,
+            buf: vec![0; 16 * 1024],
+        })
+    }
+
+    /// Watch the file or directory for creation, modification, removal and moves. For a
+    /// directory, the events of its entries are reported, but not of its subdirectories.
+    pub fn watch<P: AsRef<Path>>(&mut self, path: P) -> io::Result<WatchDescriptor> {
+        self.watch_with_mask(path, DEFAULT_MASK)
+    }
+
+    /// Watch the file or directory for the events of the inotify mask, like
+    /// `IN_CLOSE_WRITE | IN_ATTRIB`. The events other than the ones of
+    /// [`watch`](Self::watch) are reported as [`EventKind::Other`].
+    ///
+    /// Watching a path again replaces the mask of the watch.
+    pub fn watch_with_mask<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        mask: u32,
+    ) -> io::Result<WatchDescriptor> {
+        let path = path.as_ref();
+        let c_path = CString::new(path.as_os_str().as_bytes())?;
+        let wd = crate::syscall!(inotify_add_watch@RAW(self.fd.raw_fd(), c_path.as_ptr(), mask))?;
+        self.watches.insert(wd, path.to_path_buf());
+        Ok(WatchDescriptor(wd))
+    }
+
+    /// Watch the directory and all its subdirectories, including the ones created later.
+    ///
+    /// The directory tree is walked synchronously. The files created in a new subdirectory
+    /// before it is watched are not reported.
+    pub fn watch_recursive<P: AsRef<Path>>(&mut self, path: P) -> io::Result<()> {
+        let wd = self.watch_with_mask(&path, DEFAULT_MASK | libc::IN_ONLYDIR)?;
+        self.recursive.insert(wd.0);
+        for entry in std::fs::read_dir(path)? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() {
+                match self.watch_recursive(entry.path()) {
+                    // It may have been removed or replaced after listed.
+                    Err(e) if matches!(e.raw_os_error(), Some(libc::ENOENT | libc::ENOTDIR)) => {}
+                    res => res?,
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Stop watching. The events of it which are not received yet are still yielded.
+    pub fn unwatch(&mut self, wd: WatchDescriptor) -> io::Result<()> {
+        self.recursive.remove(&wd.0);
+        crate::syscall!(inotify_rm_watch@RAW(self.fd.raw_fd(), wd.0))?;
+        Ok(())
+    }
+
+    /// Wait for the next event.
+    pub async fn next_event(&mut self) -> io::Result<Event> {
+        loop {
+            if let Some(event) = self.events.pop_front() {
+                return Ok(event);
+            }
+            if self.read()? {
+                continue;
+            }
+            // The pair of the `IN_MOVED_FROM` is queued together, so it is moved out.
+            if let Some((_, mut event)) = self.moved_from.take() {
+                event.kind = EventKind::MovedOut;
+                return Ok(event);
+            }
+            Op::poll_read(&self.fd, false)?.wait().await?;
+        }
+    }
+
+    /// Read and parse the events available. Returns false if there is none.
+    fn read(&mut self) -> io::Result<bool> {
+        let n = match crate::syscall!(read@RAW(
+            self.fd.raw_fd(),
+            self.buf.as_mut_ptr() as *mut libc::c_void,
+            self.buf.len()
+        )) {
+            Ok(n) => n as usize,
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(false),
+            Err(e) => return Err(e),
+        };
+        const HEADER_LEN: usize = std::mem::size_of::<libc::inotify_event>();
+        let buf = std::mem::take(&mut self.buf);
+        let mut offset = 0;
+        while offset + HEADER_LEN <= n {
+            // Safety: the kernel writes whole events.
+            let header = unsafe {
+                std::ptr::read_unaligned(buf[offset..].as_ptr() as *const libc::inotify_event)
+            };
+            let name = &buf[offset + HEADER_LEN..offset + HEADER_LEN + header.len as usize];
+            // The name is padded with nul.
+            let name = name.split(|b| *b == 0).next().unwrap_or_default();
+            self.handle(
+                header.wd,
+                header.mask,
+                header.cookie,
+                OsStr::from_bytes(name),
+            );
+            offset += HEADER_LEN + header.len as usize;
+        }
+        self.buf = buf;
+        Ok(true)
+    }
+
+    fn handle(&mut self, wd: libc::c_int, mask: u32, cookie: u32, name: &OsStr) {
+        if mask & libc::IN_Q_OVERFLOW != 0 {
+            self.push(Event {
+                kind: EventKind::Overflow,
+                path: PathBuf::new(),
+                is_dir: false,
+            });
+            return;
+        }
+        if mask & libc::IN_IGNORED != 0 {
+            self.watches.remove(&wd);
+            self.recursive.remove(&wd);
+            return;
+        }
+        let Some(dir) = self.watches.get(&wd) else {
+            return;
+        };
+        let path = if name.is_empty() {
+            dir.clone()
+        } else {
+            dir.join(name)
+        };
+        let is_dir = mask & libc::IN_ISDIR != 0;
+        let kind = if mask & libc::IN_CREATE != 0 {
+            EventKind::Created
+        } else if mask & libc::IN_MODIFY != 0 {
+            EventKind::Modified
+        } else if mask & (libc::IN_DELETE | libc::IN_DELETE_SELF) != 0 {
+            EventKind::Removed
+        } else if mask & libc::IN_MOVED_FROM != 0 {
+            let event = Event {
+                kind: EventKind::MovedOut,
+                path,
+                is_dir,
+            };
+            if let Some((_, event)) = self.moved_from.replace((cookie, event)) {
+                self.events.push_back(event);
+            }
+            return;
+        } else if mask & libc::IN_MOVED_TO != 0 {
+            match self.moved_from.take() {
+                Some((from_cookie, from)) if from_cookie == cookie => {
+                    if is_dir {
+                        self.rename_watches(&from.path, &path);
+                    }
+                    EventKind::Moved { from: from.path }
+                }
+                other => {
+                    self.moved_from = other;
+                    EventKind::MovedIn
+                }
+            }
+        } else {
+            EventKind::Other(mask)
+        };
+        if is_dir
+            && self.recursive.contains(&wd)
+            && matches!(kind, EventKind::Created | EventKind::MovedIn)
+        {
+            let _ = self.watch_recursive(&path);
+        }
+        self.push(Event { kind, path, is_dir });
+    }
+
+    /// Push the event after the pending `IN_MOVED_FROM` event, which is moved out since
+    /// its pair does not follow.
+    fn push(&mut self, event: Event) {
+        if let Some((_, moved_out)) = self.moved_from.take() {
+            self.events.push_back(moved_out);
+        }
+        self.events.push_back(event);
+    }
+
+    /// The watches of a renamed directory and its subdirectories follow them, so update
+    /// their paths.
+    fn rename_watches(&mut self, from: &Path, to: &Path) {
+        for path in self.watches.values_mut() {
+            if let Ok(suffix) = path.strip_prefix(from) {
+                *path = to.join(suffix);
+            }
+        }
+    }
+}
+
+impl Stream for Watcher {
+    type Item = io::Result<Event>;
+
+    #[inline]
+    async fn next(&mut self) -> Option<Self::Item> {
+        Some(self.next_event().await)
+    }
+}
+
+impl AsRawFd for Watcher {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.raw_fd()
+    }
+}
+
+impl std::fmt::Debug for Watcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Watcher")
+            .field("fd", &self.fd)
+            .field("watches", &self.watches)
+            .finish()
+    }
+}
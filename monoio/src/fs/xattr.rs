@@ -0,0 +1,191 @@
+use std::{
+    ffi::{CString, OsStr, OsString},
+    io,
+    os::unix::{
+        ffi::{OsStrExt, OsStringExt},
+        prelude::RawFd,
+    },
+    path::Path,
+};
+
+use crate::driver::{
+    op::{xattr::XattrTarget, Op},
+    shared_fd::SharedFd,
+};
+
+/// Create the attribute, and fail with `EEXIST` if it exists.
+pub const XATTR_CREATE: i32 = libc::XATTR_CREATE;
+/// Replace the attribute, and fail with `ENODATA` if it does not exist.
+pub const XATTR_REPLACE: i32 = libc::XATTR_REPLACE;
+
+/// Retrieve the value of the extended attribute `name` of the file at `path`, or `None` if the
+/// file has no such attribute. The symlinks are followed.
+///
+/// With io_uring driver it uses `IORING_OP_GETXATTR`(requires kernel 5.19+), or `getxattr(2)`
+/// if the kernel does not support it.
+///
+/// # Examples
+///
+/// ```no_run
+/// #[monoio::main]
+/// async fn main() -> std::io::Result<()> {
+///     if let Some(value) = monoio::fs::get_xattr("foo.txt", "user.origin").await? {
+///         println!("{}", String::from_utf8_lossy(&value));
+///     }
+///     Ok(())
+/// }
+/// ```
+pub async fn get_xattr<P: AsRef<Path>, N: AsRef<OsStr>>(
+    path: P,
+    name: N,
+) -> io::Result<Option<Vec<u8>>> {
+    let path = cstr(path.as_ref().as_os_str())?;
+    get(|| XattrTarget::Path(path.clone()), name.as_ref()).await
+}
+
+/// Set the value of the extended attribute `name` of the file at `path`. The symlinks are
+/// followed.
+///
+/// `flags` of 0 creates or replaces the attribute, or use [`XATTR_CREATE`] or
+/// [`XATTR_REPLACE`] to fail if it exists or not.
+///
+/// With io_uring driver it uses `IORING_OP_SETXATTR`(requires kernel 5.19+), or `setxattr(2)`
+/// if the kernel does not support it.
+///
+/// # Examples
+///
+/// ```no_run
+/// #[monoio::main]
+/// async fn main() -> std::io::Result<()> {
+///     monoio::fs::set_xattr("foo.txt", "user.origin", b"bar", 0).await
+/// }
+/// ```
+pub async fn set_xattr<P: AsRef<Path>, N: AsRef<OsStr>>(
+    path: P,
+    name: N,
+    value: &[u8],
+    flags: i32,
+) -> io::Result<()> {
+    let path = cstr(path.as_ref().as_os_str())?;
+    set(XattrTarget::Path(path), name.as_ref(), value, flags).await
+}
+
+/// List the names of the extended attributes of the file at `path`. The symlinks are followed.
+///
+/// There is no io_uring operation for it, so `listxattr(2)` runs on the blocking thread pool
+/// with the `sync` feature when io_uring is not enabled, or in place otherwise.
+pub async fn list_xattr<P: AsRef<Path>>(path: P) -> io::Result<Vec<OsString>> {
+    let path = cstr(path.as_ref().as_os_str())?;
+    blocking(move || {
+        list_blocking(|buf, size| {
+            crate::syscall!(listxattr@RAW(path.as_ptr(), buf, size)).map(|n| n as usize)
+        })
+    })
+    .await
+}
+
+/// Remove the extended attribute `name` of the file at `path`. The symlinks are followed.
+///
+/// There is no io_uring operation for it, so `removexattr(2)` runs on the blocking thread pool
+/// with the `sync` feature when io_uring is not enabled, or in place otherwise.
+pub async fn remove_xattr<P: AsRef<Path>, N: AsRef<OsStr>>(path: P, name: N) -> io::Result<()> {
+    let path = cstr(path.as_ref().as_os_str())?;
+    let name = cstr(name.as_ref())?;
+    blocking(move || crate::syscall!(removexattr@RAW(path.as_ptr(), name.as_ptr())).map(|_| ()))
+        .await
+}
+
+pub(crate) async fn fget(fd: &SharedFd, name: &OsStr) -> io::Result<Option<Vec<u8>>> {
+    get(|| XattrTarget::Fd(fd.clone()), name).await
+}
+
+pub(crate) async fn fset(fd: &SharedFd, name: &OsStr, value: &[u8], flags: i32) -> io::Result<()> {
+    set(XattrTarget::Fd(fd.clone()), name, value, flags).await
+}
+
+pub(crate) async fn flist(fd: &SharedFd) -> io::Result<Vec<OsString>> {
+    let fd: RawFd = fd.raw_fd();
+    blocking(move || {
+        list_blocking(|buf, size| {
+            crate::syscall!(flistxattr@RAW(fd, buf, size)).map(|n| n as usize)
+        })
+    })
+    .await
+}
+
+pub(crate) async fn fremove(fd: &SharedFd, name: &OsStr) -> io::Result<()> {
+    let fd: RawFd = fd.raw_fd();
+    let name = cstr(name)?;
+    blocking(move || crate::syscall!(fremovexattr@RAW(fd, name.as_ptr())).map(|_| ())).await
+}
+
+async fn get(target: impl Fn() -> XattrTarget, name: &OsStr) -> io::Result<Option<Vec<u8>>> {
+    let name = cstr(name)?;
+    loop {
+        // Query the size first, the value may be changed before reading it, so retry on ERANGE.
+        let size = match Op::get_xattr(target(), name.clone(), 0)?.result().await {
+            Ok((size, _)) => size,
+            Err(e) if e.raw_os_error() == Some(libc::ENODATA) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        if size == 0 {
+            return Ok(Some(Vec::new()));
+        }
+        match Op::get_xattr(target(), name.clone(), size)?.result().await {
+            Ok((_, value)) => return Ok(Some(value)),
+            Err(e) if e.raw_os_error() == Some(libc::ERANGE) => {}
+            Err(e) if e.raw_os_error() == Some(libc::ENODATA) => return Ok(None),
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+async fn set(target: XattrTarget, name: &OsStr, value: &[u8], flags: i32) -> io::Result<()> {
+    let op = Op::set_xattr(target, cstr(name)?, value.to_vec(), flags)?;
+    op.await.meta.result.map(|_| ())
+}
+
+/// Read the list of names with `list`, which is called with an empty buffer for the size first.
+fn list_blocking(
+    list: impl Fn(*mut libc::c_char, usize) -> io::Result<usize>,
+) -> io::Result<Vec<OsString>> {
+    let mut buf: Vec<u8> = Vec::new();
+    loop {
+        let size = list(std::ptr::null_mut(), 0)?;
+        if size == 0 {
+            return Ok(Vec::new());
+        }
+        buf.reserve(size);
+        match list(buf.as_mut_ptr().cast(), buf.capacity()) {
+            Ok(n) => {
+                // Safety: the kernel wrote `n` bytes to the buffer
+                unsafe { buf.set_len(n) };
+                break;
+            }
+            // The list grew after querying the size.
+            Err(e) if e.raw_os_error() == Some(libc::ERANGE) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(buf
+        .split(|b| *b == 0)
+        .filter(|name| !name.is_empty())
+        .map(|name| OsString::from_vec(name.to_vec()))
+        .collect())
+}
+
+fn cstr(s: &OsStr) -> io::Result<CString> {
+    Ok(CString::new(s.as_bytes())?)
+}
+
+#[cfg(any(feature = "iouring", not(feature = "sync")))]
+async fn blocking<T>(f: impl FnOnce() -> io::Result<T>) -> io::Result<T> {
+    f()
+}
+
+#[cfg(all(feature = "sync", not(feature = "iouring")))]
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> io::Result<T> + Send + 'static,
+) -> io::Result<T> {
+    crate::fs::asyncify(f).await
+}
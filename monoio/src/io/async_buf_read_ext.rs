@@ -6,7 +6,7 @@ use std::{
 
 use memchr::memchr;
 
-use crate::io::AsyncBufRead;
+use crate::io::{AsyncBufRead, Lines};
 
 struct Guard<'a> {
     buf: &'a mut Vec<u8>,
@@ -83,6 +83,31 @@ pub trait AsyncBufReadExt {
     /// the read bytes are not valid UTF-8. If an I/O error is encountered then buf may contain some
     /// bytes already read in the event that all data read so far was valid UTF-8.
     fn read_line<'a>(&'a mut self, buf: &'a mut String) -> impl Future<Output = Result<usize>>;
+
+    /// Returns the lines of the reader, which are read by [`read_line`](Self::read_line) and
+    /// yielded without the newline(`\n` or `\r\n`).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use monoio::{
+    ///     fs::File,
+    ///     io::{AsyncBufReadExt, BufReader},
+    /// };
+    ///
+    /// #[monoio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let file = File::open("foo.log").await?;
+    ///     let mut lines = BufReader::new(file).lines();
+    ///     while let Some(line) = lines.next_line().await? {
+    ///         println!("{line}");
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    fn lines(self) -> Lines<Self>
+    where
+        Self: Sized;
 }
 
 impl<A> AsyncBufReadExt for A
@@ -118,4 +143,12 @@ where
             }
         }
     }
+
+    #[inline]
+    fn lines(self) -> Lines<Self>
+    where
+        Self: Sized,
+    {
+        Lines::new(self)
+    }
 }
@@ -0,0 +1,165 @@
+//! Readiness based io for arbitrary file descriptors.
+
+use std::{
+    fmt, io,
+    os::unix::prelude::{AsRawFd, RawFd},
+};
+
+use crate::driver::{op::Op, shared_fd::SharedFd};
+
+/// Associates an fd which supports `epoll`(or `poll` for io_uring), e.g. a tun device, a
+/// serial port or an inotify fd, with the driver, so that its readiness can be awaited.
+///
+/// The io itself is done by the caller with the syscalls or the library owning the fd, in
+/// the closures passed to [`AsyncFdReadyGuard::try_io`] or [`AsyncFd::async_io`]. The fd
+/// should be in the non-blocking mode, or a spurious readiness may block the thread.
+///
+/// The fd is not closed when `AsyncFd` is dropped, it is closed with the inner object.
+pub struct AsyncFd<T: AsRawFd> {
+    inner: Option<T>,
+    fd: Option<SharedFd>,
+}
+
+/// The readiness of an [`AsyncFd`], returned by [`AsyncFd::readable`] and
+/// [`AsyncFd::writable`].
+pub struct AsyncFdReadyGuard<'a, T: AsRawFd> {
+    async_fd: &'a AsyncFd<T>,
+}
+
+/// The error of [`AsyncFdReadyGuard::try_io`], returned if the io would block, which means
+/// the readiness has gone and should be awaited again.
+#[derive(Debug)]
+pub struct TryIoError(());
+
+/// The direction of the readiness to wait for in [`AsyncFd::async_io`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interest {
+    /// Wait for read readiness.
+    Readable,
+    /// Wait for write readiness.
+    Writable,
+}
+
+impl<T: AsRawFd> AsyncFd<T> {
+    /// Register the fd of the inner object with the driver of the current thread.
+    pub fn new(inner: T) -> io::Result<Self> {
+        let fd = SharedFd::new::<false>(inner.as_raw_fd())?;
+        Ok(Self {
+            inner: Some(inner),
+            fd: Some(fd),
+        })
+    }
+
+    /// Returns a shared reference to the inner object.
+    #[inline]
+    pub fn get_ref(&self) -> &T {
+        self.inner.as_ref().unwrap()
+    }
+
+    /// Returns a mutable reference to the inner object.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut T {
+        self.inner.as_mut().unwrap()
+    }
+
+    /// Deregister the fd from the driver and return the inner object.
+    pub fn into_inner(mut self) -> T {
+        self.release();
+        self.inner.take().unwrap()
+    }
+
+    /// Wait for read readiness, and returns a guard to do the io.
+    pub async fn readable(&self) -> io::Result<AsyncFdReadyGuard<'_, T>> {
+        Op::poll_read(self.shared_fd(), false)?.wait().await?;
+        Ok(AsyncFdReadyGuard { async_fd: self })
+    }
+
+    /// Wait for write readiness, and returns a guard to do the io.
+    pub async fn writable(&self) -> io::Result<AsyncFdReadyGuard<'_, T>> {
+        Op::poll_write(self.shared_fd(), false)?.wait().await?;
+        Ok(AsyncFdReadyGuard { async_fd: self })
+    }
+
+    /// Call the closure each time the fd is ready in the direction, until it returns other
+    /// than a `WouldBlock` error.
+    pub async fn async_io<R>(
+        &self,
+        interest: Interest,
+        mut f: impl FnMut(&T) -> io::Result<R>,
+    ) -> io::Result<R> {
+        loop {
+            let mut guard = match interest {
+                Interest::Readable => self.readable().await?,
+                Interest::Writable => self.writable().await?,
+            };
+            if let Ok(res) = guard.try_io(&mut f) {
+                return res;
+            }
+        }
+    }
+
+    #[inline]
+    fn shared_fd(&self) -> &SharedFd {
+        self.fd.as_ref().unwrap()
+    }
+
+    /// Deregister the fd without closing it.
+    fn release(&mut self) {
+        if let Some(fd) = self.fd.take() {
+            // An op forgotten without being awaited may still hold the fd, leak it rather
+            // than closing the fd of the inner object.
+            if let Err(fd) = fd.try_unwrap() {
+                std::mem::forget(fd);
+            }
+        }
+    }
+}
+
+impl<T: AsRawFd> AsyncFdReadyGuard<'_, T> {
+    /// Returns a shared reference to the inner object.
+    #[inline]
+    pub fn get_inner(&self) -> &T {
+        self.async_fd.get_ref()
+    }
+
+    /// Do the io with the inner object. If the closure returns a `WouldBlock` error, the
+    /// readiness has gone, and [`TryIoError`] is returned to wait for it again.
+    pub fn try_io<R>(
+        &mut self,
+        f: impl FnOnce(&T) -> io::Result<R>,
+    ) -> Result<io::Result<R>, TryIoError> {
+        match f(self.async_fd.get_ref()) {
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => Err(TryIoError(())),
+            res => Ok(res),
+        }
+    }
+}
+
+impl<T: AsRawFd> AsRawFd for AsyncFd<T> {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.get_ref().as_raw_fd()
+    }
+}
+
+impl<T: AsRawFd> Drop for AsyncFd<T> {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+impl<T: AsRawFd + fmt::Debug> fmt::Debug for AsyncFd<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AsyncFd")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl<T: AsRawFd + fmt::Debug> fmt::Debug for AsyncFdReadyGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AsyncFdReadyGuard")
+            .field("async_fd", self.async_fd)
+            .finish()
+    }
+}
@@ -13,29 +13,31 @@ macro_rules! reader_trait {
     };
 }
 
-macro_rules! reader_be_impl {
-    ($future: ident, $n_ty: ty, $f: ident) => {
+macro_rules! reader_impl {
+    ($n_ty: ty, $f: ident, $from_bytes: ident) => {
         async fn $f(&mut self) -> std::io::Result<$n_ty> {
-            let (res, buf) = self
-                .read_exact(std::boxed::Box::new([0; std::mem::size_of::<$n_ty>()]))
-                .await;
-            res?;
-            use crate::utils::box_into_inner::IntoInner;
-            Ok(<$n_ty>::from_be_bytes(Box::consume(buf)))
+            use crate::io::util::buf_pool;
+
+            const N: usize = std::mem::size_of::<$n_ty>();
+            let buf = buf_pool::take_small().slice_mut(..N);
+            let (res, buf) = self.read_exact(buf).await;
+            let buf = buf.into_inner();
+            let n = <$n_ty>::$from_bytes(buf[..N].try_into().unwrap());
+            buf_pool::give_small(buf);
+            res.map(|_| n)
         }
     };
 }
 
+macro_rules! reader_be_impl {
+    ($future: ident, $n_ty: ty, $f: ident) => {
+        reader_impl!($n_ty, $f, from_be_bytes);
+    };
+}
+
 macro_rules! reader_le_impl {
     ($future: ident, $n_ty: ty, $f: ident) => {
-        async fn $f(&mut self) -> std::io::Result<$n_ty> {
-            let (res, buf) = self
-                .read_exact(std::boxed::Box::new([0; std::mem::size_of::<$n_ty>()]))
-                .await;
-            res?;
-            use crate::utils::box_into_inner::IntoInner;
-            Ok(<$n_ty>::from_le_bytes(Box::consume(buf)))
-        }
+        reader_impl!($n_ty, $f, from_le_bytes);
     };
 }
 
@@ -57,7 +59,7 @@ pub trait AsyncReadRentExt {
     reader_trait!(ReadU16Future, u16, read_u16);
     reader_trait!(ReadU32Future, u32, read_u32);
     reader_trait!(ReadU64Future, u64, read_u64);
-    reader_trait!(ReadU128Future, u16, read_u128);
+    reader_trait!(ReadU128Future, u128, read_u128);
     reader_trait!(ReadI8Future, i8, read_i8);
     reader_trait!(ReadI16Future, i16, read_i16);
     reader_trait!(ReadI32Future, i32, read_i32);
@@ -125,19 +127,25 @@ where
             meta = meta_;
             match res {
                 Ok(0) => {
+                    unsafe { buf.set_init(read) };
                     return (
                         Err(std::io::Error::new(
                             std::io::ErrorKind::UnexpectedEof,
                             "failed to fill whole buffer",
                         )),
                         buf,
-                    )
+                    );
                 }
                 Ok(n) => read += n,
                 Err(ref e) if e.kind() == std::io::ErrorKind::Interrupted => {}
-                Err(e) => return (Err(e), buf),
+                Err(e) => {
+                    unsafe { buf.set_init(read) };
+                    return (Err(e), buf);
+                }
             }
         }
+        // Safety: `read` bytes are filled in order.
+        unsafe { buf.set_init(read) };
         (Ok(read), buf)
     }
 
@@ -145,7 +153,7 @@ where
     reader_be_impl!(ReadU16Future, u16, read_u16);
     reader_be_impl!(ReadU32Future, u32, read_u32);
     reader_be_impl!(ReadU64Future, u64, read_u64);
-    reader_be_impl!(ReadU128Future, u16, read_u128);
+    reader_be_impl!(ReadU128Future, u128, read_u128);
     reader_be_impl!(ReadI8Future, i8, read_i8);
     reader_be_impl!(ReadI16Future, i16, read_i16);
     reader_be_impl!(ReadI32Future, i32, read_i32);
@@ -164,6 +172,6 @@ where
     reader_le_impl!(ReadI32LEFuture, i32, read_i32_le);
     reader_le_impl!(ReadI64LEFuture, i64, read_i64_le);
     reader_le_impl!(ReadI128LEFuture, i128, read_i128_le);
-    reader_be_impl!(ReadF32LEFuture, f32, read_f32_le);
-    reader_be_impl!(ReadF64LEFuture, f64, read_f64_le);
+    reader_le_impl!(ReadF32LEFuture, f32, read_f32_le);
+    reader_le_impl!(ReadF64LEFuture, f64, read_f64_le);
 }
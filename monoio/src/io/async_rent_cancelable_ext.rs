@@ -13,29 +13,31 @@ macro_rules! reader_trait {
     };
 }
 
-macro_rules! reader_be_impl {
-    ($future: ident, $n_ty: ty, $f: ident) => {
+macro_rules! reader_impl {
+    ($n_ty: ty, $f: ident, $from_bytes: ident) => {
         async fn $f(&mut self, c: CancelHandle) -> std::io::Result<$n_ty> {
-            let (res, buf) = self
-                .cancelable_read_exact(std::boxed::Box::new([0; std::mem::size_of::<$n_ty>()]), c)
-                .await;
-            res?;
-            use crate::utils::box_into_inner::IntoInner;
-            Ok(<$n_ty>::from_be_bytes(Box::consume(buf)))
+            use crate::io::util::buf_pool;
+
+            const N: usize = std::mem::size_of::<$n_ty>();
+            let buf = buf_pool::take_small().slice_mut(..N);
+            let (res, buf) = self.cancelable_read_exact(buf, c).await;
+            let buf = buf.into_inner();
+            let n = <$n_ty>::$from_bytes(buf[..N].try_into().unwrap());
+            buf_pool::give_small(buf);
+            res.map(|_| n)
         }
     };
 }
 
+macro_rules! reader_be_impl {
+    ($future: ident, $n_ty: ty, $f: ident) => {
+        reader_impl!($n_ty, $f, from_be_bytes);
+    };
+}
+
 macro_rules! reader_le_impl {
     ($future: ident, $n_ty: ty, $f: ident) => {
-        async fn $f(&mut self, c: CancelHandle) -> std::io::Result<$n_ty> {
-            let (res, buf) = self
-                .cancelable_read_exact(std::boxed::Box::new([0; std::mem::size_of::<$n_ty>()]), c)
-                .await;
-            res?;
-            use crate::utils::box_into_inner::IntoInner;
-            Ok(<$n_ty>::from_le_bytes(Box::consume(buf)))
-        }
+        reader_impl!($n_ty, $f, from_le_bytes);
     };
 }
 
@@ -132,19 +134,25 @@ where
             meta = meta_;
             match res {
                 Ok(0) => {
+                    unsafe { buf.set_init(read) };
                     return (
                         Err(std::io::Error::new(
                             std::io::ErrorKind::UnexpectedEof,
                             "failed to fill whole buffer",
                         )),
                         buf,
-                    )
+                    );
                 }
                 Ok(n) => read += n,
                 Err(ref e) if e.kind() == std::io::ErrorKind::Interrupted => {}
-                Err(e) => return (Err(e), buf),
+                Err(e) => {
+                    unsafe { buf.set_init(read) };
+                    return (Err(e), buf);
+                }
             }
         }
+        // Safety: `read` bytes are filled in order.
+        unsafe { buf.set_init(read) };
         (Ok(read), buf)
     }
 
@@ -171,8 +179,8 @@ where
     reader_le_impl!(ReadI32LEFuture, i32, cancelable_read_i32_le);
     reader_le_impl!(ReadI64LEFuture, i64, cancelable_read_i64_le);
     reader_le_impl!(ReadI128LEFuture, i128, cancelable_read_i128_le);
-    reader_be_impl!(ReadF32LEFuture, f32, cancelable_read_f32_le);
-    reader_be_impl!(ReadF64LEFuture, f64, cancelable_read_f64_le);
+    reader_le_impl!(ReadF32LEFuture, f32, cancelable_read_f32_le);
+    reader_le_impl!(ReadF64LEFuture, f64, cancelable_read_f64_le);
 }
 
 /// CancelableAsyncWriteRentExt
@@ -6,6 +6,28 @@ use crate::{
     BufResult,
 };
 
+macro_rules! writer_trait {
+    ($n_ty: ty, $f: ident) => {
+        /// Write number in async way
+        fn $f(&mut self, n: $n_ty) -> impl Future<Output = std::io::Result<()>>;
+    };
+}
+
+macro_rules! writer_impl {
+    ($n_ty: ty, $f: ident, $to_bytes: ident) => {
+        async fn $f(&mut self, n: $n_ty) -> std::io::Result<()> {
+            use crate::io::util::buf_pool;
+
+            const N: usize = std::mem::size_of::<$n_ty>();
+            let mut buf = buf_pool::take_small();
+            buf[..N].copy_from_slice(&n.$to_bytes());
+            let (res, buf) = self.write_all(buf.slice(..N)).await;
+            buf_pool::give_small(buf.into_inner());
+            res.map(|_| ())
+        }
+    };
+}
+
 /// AsyncWriteRentExt
 pub trait AsyncWriteRentExt {
     /// Write all
@@ -19,6 +41,32 @@ pub trait AsyncWriteRentExt {
         &mut self,
         buf: T,
     ) -> impl Future<Output = BufResult<usize, T>>;
+
+    writer_trait!(u8, write_u8);
+    writer_trait!(u16, write_u16);
+    writer_trait!(u32, write_u32);
+    writer_trait!(u64, write_u64);
+    writer_trait!(u128, write_u128);
+    writer_trait!(i8, write_i8);
+    writer_trait!(i16, write_i16);
+    writer_trait!(i32, write_i32);
+    writer_trait!(i64, write_i64);
+    writer_trait!(i128, write_i128);
+    writer_trait!(f32, write_f32);
+    writer_trait!(f64, write_f64);
+
+    writer_trait!(u8, write_u8_le);
+    writer_trait!(u16, write_u16_le);
+    writer_trait!(u32, write_u32_le);
+    writer_trait!(u64, write_u64_le);
+    writer_trait!(u128, write_u128_le);
+    writer_trait!(i8, write_i8_le);
+    writer_trait!(i16, write_i16_le);
+    writer_trait!(i32, write_i32_le);
+    writer_trait!(i64, write_i64_le);
+    writer_trait!(i128, write_i128_le);
+    writer_trait!(f32, write_f32_le);
+    writer_trait!(f64, write_f64_le);
 }
 
 impl<A> AsyncWriteRentExt for A
@@ -78,4 +126,30 @@ where
         }
         (Ok(written), buf)
     }
+
+    writer_impl!(u8, write_u8, to_be_bytes);
+    writer_impl!(u16, write_u16, to_be_bytes);
+    writer_impl!(u32, write_u32, to_be_bytes);
+    writer_impl!(u64, write_u64, to_be_bytes);
+    writer_impl!(u128, write_u128, to_be_bytes);
+    writer_impl!(i8, write_i8, to_be_bytes);
+    writer_impl!(i16, write_i16, to_be_bytes);
+    writer_impl!(i32, write_i32, to_be_bytes);
+    writer_impl!(i64, write_i64, to_be_bytes);
+    writer_impl!(i128, write_i128, to_be_bytes);
+    writer_impl!(f32, write_f32, to_be_bytes);
+    writer_impl!(f64, write_f64, to_be_bytes);
+
+    writer_impl!(u8, write_u8_le, to_le_bytes);
+    writer_impl!(u16, write_u16_le, to_le_bytes);
+    writer_impl!(u32, write_u32_le, to_le_bytes);
+    writer_impl!(u64, write_u64_le, to_le_bytes);
+    writer_impl!(u128, write_u128_le, to_le_bytes);
+    writer_impl!(i8, write_i8_le, to_le_bytes);
+    writer_impl!(i16, write_i16_le, to_le_bytes);
+    writer_impl!(i32, write_i32_le, to_le_bytes);
+    writer_impl!(i64, write_i64_le, to_le_bytes);
+    writer_impl!(i128, write_i128_le, to_le_bytes);
+    writer_impl!(f32, write_f32_le, to_le_bytes);
+    writer_impl!(f64, write_f64_le, to_le_bytes);
 }
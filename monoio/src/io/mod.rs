@@ -2,6 +2,8 @@
 
 mod async_buf_read;
 mod async_buf_read_ext;
+#[cfg(unix)]
+mod async_fd;
 mod async_read_rent;
 mod async_read_rent_ext;
 mod async_rent_cancelable;
@@ -18,6 +20,8 @@ pub mod splice;
 
 pub use async_buf_read::AsyncBufRead;
 pub use async_buf_read_ext::AsyncBufReadExt;
+#[cfg(unix)]
+pub use async_fd::{AsyncFd, AsyncFdReadyGuard, Interest, TryIoError};
 pub use async_read_rent::{AsyncReadRent, AsyncReadRentAt};
 pub use async_read_rent_ext::AsyncReadRentExt;
 pub use async_rent_cancelable::{CancelableAsyncReadRent, CancelableAsyncWriteRent};
@@ -25,7 +29,7 @@ pub use async_rent_cancelable_ext::{CancelableAsyncReadRentExt, CancelableAsyncW
 pub use async_write_rent::{AsyncWriteRent, AsyncWriteRentAt};
 pub use async_write_rent_ext::AsyncWriteRentExt;
 
-mod util;
+pub(crate) mod util;
 
 #[cfg(feature = "poll-io")]
 pub use tokio::io as poll_io;
@@ -33,7 +37,7 @@ pub(crate) use util::operation_canceled;
 #[cfg(all(target_os = "linux", feature = "splice"))]
 pub use util::zero_copy;
 pub use util::{
-    copy, BufReader, BufWriter, CancelHandle, Canceller, OwnedReadHalf, OwnedWriteHalf,
+    copy, BufReader, BufWriter, CancelHandle, Canceller, Lines, OwnedReadHalf, OwnedWriteHalf,
     PrefixedReadIo, Split, Splitable,
 };
 #[cfg(feature = "poll-io")]
@@ -25,6 +25,13 @@ pub trait SpliceDestination {
     ) -> impl Future<Output = std::io::Result<u32>>;
 }
 
+/// Duplicate up to `len` bytes of data from pipe `src` to pipe `dst` without consuming it, so
+/// it can still be read or spliced from `src`. Returns the bytes duplicated, 0 if `src` is
+/// closed by the writer and empty.
+pub async fn tee(src: &mut Pipe, dst: &mut Pipe, len: u32) -> std::io::Result<u32> {
+    Op::tee(&src.fd, &dst.fd, len)?.result().await
+}
+
 impl<T: AsReadFd> SpliceSource for T {
     #[inline]
     async fn splice_to_pipe<'a>(
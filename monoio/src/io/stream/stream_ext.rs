@@ -1,6 +1,7 @@
 use std::future::Future;
 
 use super::{assert_stream, Stream};
+use crate::time::{error::Elapsed, Duration};
 
 /// Stream extensions.
 pub trait StreamExt: Stream {
@@ -38,6 +39,19 @@ pub trait StreamExt: Stream {
             }
         }
     }
+
+    /// Requires each item of the stream to be yielded before the duration has
+    /// elapsed since it is polled, or an [`Elapsed`] error is yielded instead.
+    ///
+    /// The future of the item timed out is dropped, which cancels the IO
+    /// operation it is waiting for. The stream ends when the underlying stream
+    /// ends.
+    fn timeout(self, duration: Duration) -> Timeout<Self>
+    where
+        Self: Sized,
+    {
+        assert_stream::<Result<Self::Item, Elapsed>, _>(Timeout::new(self, duration))
+    }
 }
 
 impl<T> StreamExt for T where T: Stream {}
@@ -94,3 +108,29 @@ where
         Some((self.f)(item).await)
     }
 }
+
+#[must_use = "streams do nothing unless polled"]
+pub struct Timeout<St> {
+    stream: St,
+    duration: Duration,
+}
+
+impl<St> Timeout<St> {
+    pub(super) fn new(stream: St, duration: Duration) -> Self {
+        Self { stream, duration }
+    }
+}
+
+impl<St> Stream for Timeout<St>
+where
+    St: Stream,
+{
+    type Item = Result<St::Item, Elapsed>;
+
+    async fn next(&mut self) -> Option<Self::Item> {
+        match crate::time::timeout(self.duration, self.stream.next()).await {
+            Ok(item) => item.map(Ok),
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! A per-thread pool of the internal buffers of [`BufReader`](super::BufReader) and
+//! [`BufWriter`](super::BufWriter), so that short-lived wrappers(e.g. one for each connection)
+//! do not allocate and zero a new buffer every time. Small buffers for reading and writing
+//! numbers are pooled too.
+
+use std::cell::RefCell;
+
+/// Size of the buffers created by `new`, which are the only ones pooled.
+pub(crate) const DEFAULT_BUF_SIZE: usize = 8 * 1024;
+/// Max buffers kept by each thread.
+const MAX_POOLED: usize = 64;
+
+/// Size of the small buffers, which hold any number.
+pub(crate) const SMALL_BUF_SIZE: usize = 16;
+
+thread_local! {
+    static POOL: RefCell<Vec<Box<[u8]>>> = const { RefCell::new(Vec::new()) };
+    // The buffers are handed out as boxes, so they are kept boxed.
+    #[allow(clippy::vec_box)]
+    static SMALL_POOL: RefCell<Vec<Box<[u8; SMALL_BUF_SIZE]>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Take a small buffer from the pool, or allocate a new one.
+pub(crate) fn take_small() -> Box<[u8; SMALL_BUF_SIZE]> {
+    SMALL_POOL
+        .try_with(|pool| pool.borrow_mut().pop())
+        .ok()
+        .flatten()
+        .unwrap_or_else(|| Box::new([0; SMALL_BUF_SIZE]))
+}
+
+/// Give a small buffer back to the pool.
+pub(crate) fn give_small(buf: Box<[u8; SMALL_BUF_SIZE]>) {
+    let _ = SMALL_POOL.try_with(|pool| {
+        let mut pool = pool.borrow_mut();
+        if pool.len() < MAX_POOLED {
+            pool.push(buf);
+        }
+    });
+}
+
+/// Take a buffer of `capacity` bytes from the pool, or allocate a new one.
+pub(crate) fn take(capacity: usize) -> Box<[u8]> {
+    if capacity == DEFAULT_BUF_SIZE {
+        if let Some(buf) = POOL.try_with(|pool| pool.borrow_mut().pop()).ok().flatten() {
+            return buf;
+        }
+    }
+    vec![0; capacity].into_boxed_slice()
+}
+
+/// Take an empty `Vec` with the capacity of [`DEFAULT_BUF_SIZE`] from the pool.
+pub(crate) fn take_vec() -> Vec<u8> {
+    let mut buf = Vec::from(take(DEFAULT_BUF_SIZE));
+    buf.clear();
+    buf
+}
+
+/// Give a `Vec` taken by [`take_vec`] back to the pool.
+pub(crate) fn give_vec(mut buf: Vec<u8>) {
+    if buf.capacity() == DEFAULT_BUF_SIZE {
+        // Safety: the whole buffer is initialized since it comes from a boxed slice.
+        unsafe { buf.set_len(DEFAULT_BUF_SIZE) };
+        give(buf.into_boxed_slice());
+    }
+}
+
+/// Give a buffer back to the pool, it is freed if the pool is full or not for its size.
+pub(crate) fn give(buf: Box<[u8]>) {
+    if buf.len() != DEFAULT_BUF_SIZE {
+        return;
+    }
+    let _ = POOL.try_with(|pool| {
+        let mut pool = pool.borrow_mut();
+        if pool.len() < MAX_POOLED {
+            pool.push(buf);
+        }
+    });
+}
@@ -1,5 +1,6 @@
-use std::future::Future;
+use std::{future::Future, mem::ManuallyDrop};
 
+use super::buf_pool::{self, DEFAULT_BUF_SIZE};
 use crate::{
     buf::{IoBuf, IoBufMut, IoVecBuf, IoVecBufMut, IoVecWrapperMut},
     io::{AsyncBufRead, AsyncReadRent, AsyncWriteRent},
@@ -16,10 +17,11 @@ pub struct BufReader<R> {
     cap: usize,
 }
 
-const DEFAULT_BUF_SIZE: usize = 8 * 1024;
-
 impl<R> BufReader<R> {
-    /// Create BufReader with default buffer size
+    /// Create BufReader with default buffer size(8KiB)
+    ///
+    /// The buffers of the default size are reused in the current thread after the BufReader is
+    /// dropped, so it is cheap to create one for each short-lived connection.
     #[inline]
     pub fn new(inner: R) -> Self {
         Self::with_capacity(DEFAULT_BUF_SIZE, inner)
@@ -28,15 +30,20 @@ impl<R> BufReader<R> {
     /// Create BufReader with given buffer size
     #[inline]
     pub fn with_capacity(capacity: usize, inner: R) -> Self {
-        let buffer = vec![0; capacity];
         Self {
             inner,
-            buf: Some(buffer.into_boxed_slice()),
+            buf: Some(buf_pool::take(capacity)),
             pos: 0,
             cap: 0,
         }
     }
 
+    /// Returns the size of the internal buffer.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buf.as_ref().map_or(0, |buf| buf.len())
+    }
+
     /// Gets a reference to the underlying reader.
     ///
     /// It is inadvisable to directly read from the underlying reader.
@@ -56,7 +63,12 @@ impl<R> BufReader<R> {
     /// Note that any leftover data in the internal buffer is lost.
     #[inline]
     pub fn into_inner(self) -> R {
-        self.inner
+        let mut this = ManuallyDrop::new(self);
+        if let Some(buf) = this.buf.take() {
+            buf_pool::give(buf);
+        }
+        // Safety: `this` is not dropped, and `inner` is not used again.
+        unsafe { std::ptr::read(&this.inner) }
     }
 
     /// Returns a reference to the internally buffered data.
@@ -76,6 +88,15 @@ impl<R> BufReader<R> {
     }
 }
 
+impl<R> Drop for BufReader<R> {
+    fn drop(&mut self) {
+        // The buffer is lost if a read or write future is dropped before completing.
+        if let Some(buf) = self.buf.take() {
+            buf_pool::give(buf);
+        }
+    }
+}
+
 impl<R: AsyncReadRent> AsyncReadRent for BufReader<R> {
     async fn read<T: IoBufMut>(&mut self, mut buf: T) -> BufResult<usize, T> {
         // If we don't have any buffered data and we're doing a massive read
@@ -1,5 +1,6 @@
-use std::{future::Future, io};
+use std::{future::Future, io, mem::ManuallyDrop};
 
+use super::buf_pool::{self, DEFAULT_BUF_SIZE};
 use crate::{
     buf::{IoBuf, IoBufMut, IoVecBuf, IoVecBufMut, IoVecWrapper, Slice},
     io::{AsyncBufRead, AsyncReadRent, AsyncWriteRent, AsyncWriteRentExt},
@@ -16,10 +17,11 @@ pub struct BufWriter<W> {
     cap: usize,
 }
 
-const DEFAULT_BUF_SIZE: usize = 8 * 1024;
-
 impl<W> BufWriter<W> {
-    /// Create BufWriter with default buffer size
+    /// Create BufWriter with default buffer size(8KiB)
+    ///
+    /// The buffers of the default size are reused in the current thread after the BufWriter is
+    /// dropped, so it is cheap to create one for each short-lived connection.
     #[inline]
     pub fn new(inner: W) -> Self {
         Self::with_capacity(DEFAULT_BUF_SIZE, inner)
@@ -28,15 +30,20 @@ impl<W> BufWriter<W> {
     /// Create BufWriter with given buffer size
     #[inline]
     pub fn with_capacity(capacity: usize, inner: W) -> Self {
-        let buffer = vec![0; capacity];
         Self {
             inner,
-            buf: Some(buffer.into_boxed_slice()),
+            buf: Some(buf_pool::take(capacity)),
             pos: 0,
             cap: 0,
         }
     }
 
+    /// Returns the size of the internal buffer.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.buf.as_ref().map_or(0, |buf| buf.len())
+    }
+
     /// Gets a reference to the underlying writer.
     #[inline]
     pub fn get_ref(&self) -> &W {
@@ -54,7 +61,12 @@ impl<W> BufWriter<W> {
     /// Note that any leftover data in the internal buffer is lost.
     #[inline]
     pub fn into_inner(self) -> W {
-        self.inner
+        let mut this = ManuallyDrop::new(self);
+        if let Some(buf) = this.buf.take() {
+            buf_pool::give(buf);
+        }
+        // Safety: `this` is not dropped, and `inner` is not used again.
+        unsafe { std::ptr::read(&this.inner) }
     }
 
     /// Returns a reference to the internally buffered data.
@@ -71,6 +83,15 @@ impl<W> BufWriter<W> {
     }
 }
 
+impl<W> Drop for BufWriter<W> {
+    fn drop(&mut self) {
+        // The buffer is lost if a read or write future is dropped before completing.
+        if let Some(buf) = self.buf.take() {
+            buf_pool::give(buf);
+        }
+    }
+}
+
 impl<W: AsyncWriteRent> BufWriter<W> {
     async fn flush_buf(&mut self) -> io::Result<()> {
         if self.pos != self.cap {
@@ -97,7 +118,7 @@ impl<W: AsyncWriteRent> AsyncWriteRent for BufWriter<W> {
         let owned_len = owned_buf.len();
         let amt = buf.bytes_init();
 
-        if self.pos + amt > owned_len {
+        if self.cap + amt > owned_len {
             // Buf can not be copied directly into OwnedBuf,
             // we must flush OwnedBuf first.
             match self.flush_buf().await {
@@ -109,7 +130,7 @@ impl<W: AsyncWriteRent> AsyncWriteRent for BufWriter<W> {
         }
 
         // Now there are two situations here:
-        // 1. OwnedBuf has data, and self.pos + amt <= owned_len,
+        // 1. OwnedBuf has data, and self.cap + amt <= owned_len,
         // which means the data can be copied into OwnedBuf.
         // 2. OwnedBuf is empty. If we can copy buf into OwnedBuf,
         // we will copy it, otherwise we will send it directly(in
@@ -55,6 +55,19 @@ impl Canceller {
         }
     }
 
+    /// Cancel all related operations, and the ones associated later. Unlike `cancel`, the
+    /// canceller is not reset.
+    pub(crate) fn cancel_in_place(&self) {
+        let slot = {
+            let mut shared = self.shared.borrow_mut();
+            shared.canceled = true;
+            std::mem::take(&mut shared.slot_ref)
+        };
+        for op_canceller in slot.iter() {
+            unsafe { op_canceller.cancel() };
+        }
+    }
+
     /// Create a CancelHandle which can be used to pass to io operation.
     #[inline]
     pub fn handle(&self) -> CancelHandle {
@@ -2,19 +2,51 @@
 
 use std::io;
 
+use super::buf_pool;
 use crate::io::{AsyncReadRent, AsyncWriteRent, AsyncWriteRentExt};
 #[cfg(unix)]
 use crate::net::unix::new_pipe;
 
 const BUF_SIZE: usize = 4 * 1024;
 
-/// Copy data from reader to writer.
+/// Copy data from reader to writer until EOF of the reader, and returns the bytes copied.
+///
+/// The data is copied with a buffer of 8KiB, which is taken from a per-thread pool and recycled
+/// after copying, so it is cheap to copy for each short-lived connection(e.g. in a proxy). Use
+/// [`zero_copy`] to move the data between fds with `splice(2)` instead.
+///
+/// # Examples
+///
+/// ```no_run
+/// use monoio::net::TcpStream;
+///
+/// #[monoio::main]
+/// async fn main() -> std::io::Result<()> {
+///     let mut upstream = TcpStream::connect("127.0.0.1:8080").await?;
+///     let mut file = monoio::fs::File::open("foo.txt").await?;
+///     monoio::io::copy(&mut file, &mut upstream).await?;
+///     Ok(())
+/// }
+/// ```
 pub async fn copy<'a, R, W>(reader: &'a mut R, writer: &'a mut W) -> io::Result<u64>
 where
     R: AsyncReadRent + ?Sized,
     W: AsyncWriteRent + ?Sized,
 {
-    let mut buf: Vec<u8> = Vec::with_capacity(BUF_SIZE);
+    let (res, buf) = copy_with_buf(reader, writer, buf_pool::take_vec()).await;
+    buf_pool::give_vec(buf);
+    res
+}
+
+async fn copy_with_buf<R, W>(
+    reader: &mut R,
+    writer: &mut W,
+    mut buf: Vec<u8>,
+) -> (io::Result<u64>, Vec<u8>)
+where
+    R: AsyncReadRent + ?Sized,
+    W: AsyncWriteRent + ?Sized,
+{
     let mut transferred: u64 = 0;
 
     'r: loop {
@@ -22,6 +54,7 @@ where
         match read_res {
             Ok(0) => {
                 // read closed
+                buf = buf_read;
                 break;
             }
             Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {
@@ -31,7 +64,7 @@ where
             }
             Err(e) => {
                 // should return error
-                return Err(e);
+                return (Err(e), buf_read);
             }
             Ok(_) => {
                 // go write data
@@ -43,10 +76,13 @@ where
             match write_res {
                 Ok(0) => {
                     // write closed
-                    return Err(io::Error::new(
-                        io::ErrorKind::WriteZero,
-                        "write zero byte into writer",
-                    ));
+                    return (
+                        Err(io::Error::new(
+                            io::ErrorKind::WriteZero,
+                            "write zero byte into writer",
+                        )),
+                        buf_,
+                    );
                 }
                 Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {
                     // retry
@@ -55,7 +91,7 @@ where
                 }
                 Err(e) => {
                     // should return error
-                    return Err(e);
+                    return (Err(e), buf_);
                 }
                 Ok(n) => {
                     // go read data
@@ -67,10 +103,13 @@ where
         }
     }
 
-    Ok(transferred)
+    (Ok(transferred), buf)
 }
 
-/// Copy with splice.
+/// Copy with splice: the data is moved from `reader` to `writer`(e.g. socket to socket, or file
+/// to socket) through a pipe, without being copied into userspace.
+///
+/// With legacy driver, the data is copied with read and write instead.
 #[cfg(all(target_os = "linux", feature = "splice"))]
 pub async fn zero_copy<SRC: crate::io::as_fd::AsReadFd, DST: crate::io::as_fd::AsWriteFd>(
     reader: &mut SRC,
@@ -81,6 +120,14 @@ pub async fn zero_copy<SRC: crate::io::as_fd::AsReadFd, DST: crate::io::as_fd::A
         io::splice::{SpliceDestination, SpliceSource},
     };
 
+    if crate::driver::op::is_legacy() {
+        return copy_fd(
+            reader.as_reader_fd().as_ref(),
+            writer.as_writer_fd().as_ref(),
+        )
+        .await;
+    }
+
     let (mut pr, mut pw) = new_pipe()?;
     let mut transferred: u64 = 0;
     loop {
@@ -96,3 +143,41 @@ pub async fn zero_copy<SRC: crate::io::as_fd::AsReadFd, DST: crate::io::as_fd::A
     }
     Ok(transferred)
 }
+
+/// Copy between the fds with read and write.
+#[cfg(all(target_os = "linux", feature = "splice"))]
+async fn copy_fd(
+    reader: &crate::driver::shared_fd::SharedFd,
+    writer: &crate::driver::shared_fd::SharedFd,
+) -> io::Result<u64> {
+    use crate::{buf::IoBuf, driver::op::Op};
+
+    let mut buf = buf_pool::take_vec();
+    let mut transferred: u64 = 0;
+    loop {
+        let (res, mut b) = Op::read(reader.clone(), buf)?.result().await;
+        let n = res?;
+        if n == 0 {
+            buf_pool::give_vec(b);
+            return Ok(transferred);
+        }
+        let mut written = 0;
+        while written < n {
+            let (res, slice) = Op::write(writer.clone(), b.slice(written..n))?
+                .result()
+                .await;
+            b = slice.into_inner();
+            match res? {
+                0 => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::WriteZero,
+                        "write zero byte into writer",
+                    ))
+                }
+                w => written += w,
+            }
+        }
+        transferred += n as u64;
+        buf = b;
+    }
+}
@@ -0,0 +1,66 @@
+use std::io;
+
+use crate::io::{stream::Stream, AsyncBufRead, AsyncBufReadExt};
+
+/// Lines of a reader, returned by [`AsyncBufReadExt::lines`].
+///
+/// It also implements [`Stream`] of `io::Result<String>`.
+#[derive(Debug)]
+pub struct Lines<R> {
+    reader: R,
+    buf: String,
+}
+
+impl<R> Lines<R> {
+    pub(crate) fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buf: String::new(),
+        }
+    }
+
+    /// Gets a reference to the underlying reader.
+    #[inline]
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Gets a mutable reference to the underlying reader.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
+    /// Consumes the `Lines`, returning the underlying reader.
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: AsyncBufRead> Lines<R> {
+    /// Returns the next line without the newline(`\n` or `\r\n`), or `None` at EOF.
+    ///
+    /// The last line is returned even if it does not end with a newline.
+    pub async fn next_line(&mut self) -> io::Result<Option<String>> {
+        self.buf.clear();
+        if self.reader.read_line(&mut self.buf).await? == 0 {
+            return Ok(None);
+        }
+        if self.buf.ends_with('\n') {
+            self.buf.pop();
+            if self.buf.ends_with('\r') {
+                self.buf.pop();
+            }
+        }
+        Ok(Some(std::mem::take(&mut self.buf)))
+    }
+}
+
+impl<R: AsyncBufRead> Stream for Lines<R> {
+    type Item = io::Result<String>;
+
+    async fn next(&mut self) -> Option<Self::Item> {
+        self.next_line().await.transpose()
+    }
+}
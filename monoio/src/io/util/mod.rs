@@ -1,9 +1,11 @@
 //! IO utils
 
+pub(crate) mod buf_pool;
 mod buf_reader;
 mod buf_writer;
 mod cancel;
 mod copy;
+mod lines;
 mod prefixed_io;
 mod split;
 
@@ -14,5 +16,6 @@ pub use cancel::{CancelHandle, Canceller};
 pub use copy::copy;
 #[cfg(all(target_os = "linux", feature = "splice"))]
 pub use copy::zero_copy;
+pub use lines::Lines;
 pub use prefixed_io::PrefixedReadIo;
 pub use split::{OwnedReadHalf, OwnedWriteHalf, Split, Splitable};
@@ -14,10 +14,10 @@ pub mod macros;
 #[doc(hidden)]
 pub use monoio_macros::select_priv_declare_output_enum;
 #[macro_use]
-mod driver;
+pub mod driver;
 pub(crate) mod builder;
 #[allow(dead_code)]
-pub(crate) mod runtime;
+pub mod runtime;
 mod scheduler;
 pub mod time;
 
@@ -30,6 +30,11 @@ pub mod buf;
 pub mod fs;
 pub mod io;
 pub mod net;
+#[cfg(target_os = "linux")]
+pub mod process;
+#[cfg(target_os = "linux")]
+pub mod signal;
+pub mod sync;
 pub mod task;
 pub mod utils;
 
@@ -38,14 +43,18 @@ use std::future::Future;
 #[cfg(feature = "sync")]
 pub use blocking::spawn_blocking;
 pub use builder::{Buildable, RuntimeBuilder};
+#[cfg(feature = "sync")]
+pub use driver::unpark::Unpark;
 pub use driver::Driver;
 #[cfg(all(target_os = "linux", feature = "iouring"))]
 pub use driver::IoUringDriver;
+#[cfg(all(windows, feature = "iocp"))]
+pub use driver::IocpDriver;
 #[cfg(feature = "legacy")]
 pub use driver::LegacyDriver;
 #[cfg(feature = "macros")]
 pub use monoio_macros::{main, test, test_all};
-pub use runtime::{spawn, Runtime};
+pub use runtime::{flush_submissions, spawn, spawn_with_priority, Runtime};
 #[cfg(any(all(target_os = "linux", feature = "iouring"), feature = "legacy"))]
 pub use {builder::FusionDriver, runtime::FusionRuntime};
 
@@ -19,3 +19,13 @@ macro_rules! info {
 macro_rules! info {
     ($( $args:expr ),*) => {};
 }
+
+#[cfg(feature = "tracing")]
+macro_rules! trace_event {
+    ($( $args:tt )*) => { tracing::trace!( $( $args )* ); }
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! trace_event {
+    ($( $args:tt )*) => {};
+}
@@ -0,0 +1,392 @@
+//! Encoding and decoding of the ancillary data of messages.
+
+use std::marker::PhantomData;
+#[cfg(target_os = "linux")]
+use std::{
+    io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    os::fd::RawFd,
+    time::{Duration, SystemTime},
+};
+
+/// The buffer of the ancillary data, which is aligned for `cmsghdr`.
+#[derive(Debug, Default)]
+pub(crate) struct CmsgBuf {
+    buf: Vec<u64>,
+    len: usize,
+}
+
+impl CmsgBuf {
+    /// Create a zeroed buffer of `len` bytes, to receive the ancillary data with.
+    pub(crate) fn new(len: usize) -> Self {
+        Self {
+            buf: vec![0; len.div_ceil(std::mem::size_of::<u64>())],
+            len,
+        }
+    }
+
+    /// Encode the items of `(level, type, data)`.
+    pub(crate) fn encode(items: &[(libc::c_int, libc::c_int, &[u8])]) -> Self {
+        // Safety: CMSG_SPACE only computes the length.
+        let space = |data: &[u8]| unsafe { libc::CMSG_SPACE(data.len() as _) } as usize;
+        let mut control = Self::new(items.iter().map(|(_, _, data)| space(data)).sum());
+        let mut offset = 0;
+        for (level, ty, data) in items {
+            // Safety: each item takes CMSG_SPACE bytes of the buffer, so the header is
+            // aligned and the data is in bounds.
+            unsafe {
+                let cmsg = control.as_mut_ptr().cast::<u8>().add(offset) as *mut libc::cmsghdr;
+                (*cmsg).cmsg_level = *level;
+                (*cmsg).cmsg_type = *ty;
+                (*cmsg).cmsg_len = libc::CMSG_LEN(data.len() as _) as _;
+                std::ptr::copy_nonoverlapping(data.as_ptr(), libc::CMSG_DATA(cmsg), data.len());
+            }
+            offset += space(data);
+        }
+        control
+    }
+
+    #[inline]
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline]
+    pub(crate) fn as_mut_ptr(&mut self) -> *mut libc::c_void {
+        self.buf.as_mut_ptr().cast()
+    }
+
+    /// Shorten the buffer to the length of the ancillary data written by the kernel.
+    #[inline]
+    pub(crate) fn truncate(&mut self, len: usize) {
+        self.len = self.len.min(len);
+    }
+
+    /// Iterate over the items as `(level, type, data)`.
+    pub(crate) fn iter(&self) -> CmsgIter<'_> {
+        // Safety: the header only describes the buffer for the CMSG_* macros.
+        let mut msg: libc::msghdr = unsafe { std::mem::zeroed() };
+        msg.msg_control = self.buf.as_ptr() as *mut libc::c_void;
+        msg.msg_controllen = self.len as _;
+        let cmsg = if self.is_empty() {
+            std::ptr::null()
+        } else {
+            unsafe { libc::CMSG_FIRSTHDR(&msg) }
+        };
+        CmsgIter {
+            msg,
+            cmsg,
+            _buf: PhantomData,
+        }
+    }
+}
+
+/// Iterator over the items of a [`CmsgBuf`].
+pub(crate) struct CmsgIter<'a> {
+    msg: libc::msghdr,
+    cmsg: *const libc::cmsghdr,
+    _buf: PhantomData<&'a CmsgBuf>,
+}
+
+impl<'a> Iterator for CmsgIter<'a> {
+    type Item = (libc::c_int, libc::c_int, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.cmsg.is_null() {
+            return None;
+        }
+        // Safety: the pointers are derived from the CMSG_* macros, which stay in the buffer.
+        unsafe {
+            let cmsg = &*self.cmsg;
+            // The type of cmsg_len differs between the platforms.
+            #[allow(clippy::unnecessary_cast)]
+            let data_len = (cmsg.cmsg_len as usize).saturating_sub(libc::CMSG_LEN(0) as usize);
+            let data = std::slice::from_raw_parts(libc::CMSG_DATA(cmsg), data_len);
+            self.cmsg = libc::CMSG_NXTHDR(&self.msg, cmsg);
+            Some((cmsg.cmsg_level, cmsg.cmsg_type, data))
+        }
+    }
+}
+
+/// Read the data of an item, `None` if it is truncated.
+///
+/// # Safety
+/// `T` must be a plain C struct or integer.
+#[inline]
+pub(crate) unsafe fn read<T>(data: &[u8]) -> Option<T> {
+    (data.len() >= std::mem::size_of::<T>())
+        .then(|| std::ptr::read_unaligned(data.as_ptr() as *const T))
+}
+
+/// The buffer size for the ancillary data of a received message, large enough for the
+/// packet info, TOS, TTL and timestamp of both families.
+#[cfg(target_os = "linux")]
+pub(crate) const RECV_CONTROL_LEN: usize = 256;
+
+/// The metadata of a received UDP message.
+///
+/// The fields other than the source address are only reported if the corresponding option
+/// is enabled, e.g. with [`UdpSocket::set_recv_pktinfo`](super::udp::UdpSocket::set_recv_pktinfo).
+#[cfg(target_os = "linux")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecvMeta {
+    addr: SocketAddr,
+    dst_ip: Option<IpAddr>,
+    ifindex: Option<u32>,
+    tos: Option<u8>,
+    ttl: Option<u8>,
+    timestamp: Option<SystemTime>,
+    truncated: bool,
+}
+
+#[cfg(target_os = "linux")]
+impl RecvMeta {
+    /// Returns the source address of the message.
+    #[inline]
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Returns the destination address in the IP header, which tells the local address the
+    /// message was sent to when the socket is bound to a wildcard address.
+    #[inline]
+    pub fn dst_ip(&self) -> Option<IpAddr> {
+        self.dst_ip
+    }
+
+    /// Returns the index of the interface the message was received on.
+    #[inline]
+    pub fn ifindex(&self) -> Option<u32> {
+        self.ifindex
+    }
+
+    /// Returns the TOS field(or traffic class for IPv6) of the message.
+    #[inline]
+    pub fn tos(&self) -> Option<u8> {
+        self.tos
+    }
+
+    /// Returns the TTL(or hop limit for IPv6) of the message.
+    #[inline]
+    pub fn ttl(&self) -> Option<u8> {
+        self.ttl
+    }
+
+    /// Returns the time the message was received by the kernel.
+    #[inline]
+    pub fn timestamp(&self) -> Option<SystemTime> {
+        self.timestamp
+    }
+
+    /// Returns true if the message was larger than the buffer and the rest was discarded.
+    #[inline]
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+}
+
+/// The ancillary data of a UDP message to send.
+///
+/// By default nothing is set, and the message is sent like with
+/// [`send_to`](super::udp::UdpSocket::send_to).
+#[cfg(target_os = "linux")]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SendMeta {
+    /// The source address of the message, which must be a local address. It is usually
+    /// the destination address of the request being replied.
+    pub src_ip: Option<IpAddr>,
+    /// The index of the interface to send the message on.
+    pub ifindex: Option<u32>,
+    /// The TOS field(or traffic class for IPv6) of the message.
+    pub tos: Option<u8>,
+    /// The TTL(or hop limit for IPv6) of the message.
+    pub ttl: Option<u8>,
+}
+
+#[cfg(target_os = "linux")]
+impl SendMeta {
+    /// Create a `SendMeta` with nothing set.
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the source address.
+    #[must_use]
+    #[inline]
+    pub fn src_ip(mut self, src_ip: IpAddr) -> Self {
+        self.src_ip = Some(src_ip);
+        self
+    }
+
+    /// Set the outgoing interface.
+    #[must_use]
+    #[inline]
+    pub fn ifindex(mut self, ifindex: u32) -> Self {
+        self.ifindex = Some(ifindex);
+        self
+    }
+
+    /// Set the TOS field.
+    #[must_use]
+    #[inline]
+    pub fn tos(mut self, tos: u8) -> Self {
+        self.tos = Some(tos);
+        self
+    }
+
+    /// Set the TTL.
+    #[must_use]
+    #[inline]
+    pub fn ttl(mut self, ttl: u8) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    #[inline]
+    pub(crate) fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn set_int_opt(
+    fd: RawFd,
+    level: libc::c_int,
+    name: libc::c_int,
+    value: libc::c_int,
+) -> io::Result<()> {
+    crate::syscall!(setsockopt@RAW(
+        fd,
+        level,
+        name,
+        &value as *const _ as *const libc::c_void,
+        std::mem::size_of::<libc::c_int>() as libc::socklen_t
+    ))?;
+    Ok(())
+}
+
+/// Encode the ancillary data for a socket of the given family.
+#[cfg(target_os = "linux")]
+pub(crate) fn encode(meta: &SendMeta, ipv6: bool) -> CmsgBuf {
+    // A v4 source address on an IPv6 socket is sent with IPV6_PKTINFO as a mapped address.
+    let pktinfo = meta.src_ip.is_some() || meta.ifindex.is_some();
+    let mut items: Vec<(libc::c_int, libc::c_int, Vec<u8>)> = Vec::with_capacity(3);
+    if ipv6 {
+        if pktinfo {
+            let ip = match meta.src_ip {
+                Some(IpAddr::V6(ip)) => ip,
+                Some(IpAddr::V4(ip)) => ip.to_ipv6_mapped(),
+                None => Ipv6Addr::UNSPECIFIED,
+            };
+            let info = libc::in6_pktinfo {
+                ipi6_addr: libc::in6_addr {
+                    s6_addr: ip.octets(),
+                },
+                ipi6_ifindex: meta.ifindex.unwrap_or(0),
+            };
+            items.push((libc::IPPROTO_IPV6, libc::IPV6_PKTINFO, as_bytes(&info)));
+        }
+        if let Some(tos) = meta.tos {
+            let v = tos as libc::c_int;
+            items.push((libc::IPPROTO_IPV6, libc::IPV6_TCLASS, as_bytes(&v)));
+        }
+        if let Some(ttl) = meta.ttl {
+            let v = ttl as libc::c_int;
+            items.push((libc::IPPROTO_IPV6, libc::IPV6_HOPLIMIT, as_bytes(&v)));
+        }
+    } else {
+        if pktinfo {
+            let ip = match meta.src_ip {
+                Some(IpAddr::V4(ip)) => ip,
+                Some(IpAddr::V6(ip)) => ip.to_ipv4_mapped().unwrap_or(Ipv4Addr::UNSPECIFIED),
+                None => Ipv4Addr::UNSPECIFIED,
+            };
+            let info = libc::in_pktinfo {
+                ipi_ifindex: meta.ifindex.unwrap_or(0) as _,
+                ipi_spec_dst: libc::in_addr {
+                    s_addr: u32::from_ne_bytes(ip.octets()),
+                },
+                ipi_addr: libc::in_addr { s_addr: 0 },
+            };
+            items.push((libc::IPPROTO_IP, libc::IP_PKTINFO, as_bytes(&info)));
+        }
+        if let Some(tos) = meta.tos {
+            let v = tos as libc::c_int;
+            items.push((libc::IPPROTO_IP, libc::IP_TOS, as_bytes(&v)));
+        }
+        if let Some(ttl) = meta.ttl {
+            let v = ttl as libc::c_int;
+            items.push((libc::IPPROTO_IP, libc::IP_TTL, as_bytes(&v)));
+        }
+    }
+    let items: Vec<_> = items
+        .iter()
+        .map(|(level, ty, data)| (*level, *ty, data.as_slice()))
+        .collect();
+    CmsgBuf::encode(&items)
+}
+
+/// Decode the ancillary data of a received message.
+#[cfg(target_os = "linux")]
+pub(crate) fn decode(addr: SocketAddr, control: &CmsgBuf, flags: libc::c_int) -> RecvMeta {
+    let mut meta = RecvMeta {
+        addr,
+        dst_ip: None,
+        ifindex: None,
+        tos: None,
+        ttl: None,
+        timestamp: None,
+        truncated: flags & libc::MSG_TRUNC != 0,
+    };
+    for (level, ty, data) in control.iter() {
+        // Safety: the items are the C structs and integers of their types.
+        unsafe {
+            match (level, ty) {
+                (libc::IPPROTO_IP, libc::IP_PKTINFO) => {
+                    if let Some(info) = read::<libc::in_pktinfo>(data) {
+                        meta.dst_ip =
+                            Some(Ipv4Addr::from(info.ipi_addr.s_addr.to_ne_bytes()).into());
+                        meta.ifindex = Some(info.ipi_ifindex as u32);
+                    }
+                }
+                (libc::IPPROTO_IPV6, libc::IPV6_PKTINFO) => {
+                    if let Some(info) = read::<libc::in6_pktinfo>(data) {
+                        meta.dst_ip = Some(Ipv6Addr::from(info.ipi6_addr.s6_addr).into());
+                        meta.ifindex = Some(info.ipi6_ifindex);
+                    }
+                }
+                // The TOS of IPv4 is reported as a single byte.
+                (libc::IPPROTO_IP, libc::IP_TOS) => meta.tos = data.first().copied(),
+                (libc::IPPROTO_IPV6, libc::IPV6_TCLASS) => {
+                    meta.tos = read::<libc::c_int>(data).map(|v| v as u8)
+                }
+                (libc::IPPROTO_IP, libc::IP_TTL) | (libc::IPPROTO_IPV6, libc::IPV6_HOPLIMIT) => {
+                    meta.ttl = read::<libc::c_int>(data).map(|v| v as u8)
+                }
+                (libc::SOL_SOCKET, libc::SCM_TIMESTAMPNS) => {
+                    meta.timestamp = read::<libc::timespec>(data).and_then(|ts| {
+                        SystemTime::UNIX_EPOCH
+                            .checked_add(Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32))
+                    });
+                }
+                _ => (),
+            }
+        }
+    }
+    meta
+}
+
+#[cfg(target_os = "linux")]
+pub(crate) fn as_bytes<T>(v: &T) -> Vec<u8> {
+    // Safety: only used for the plain C structs and integers.
+    unsafe { std::slice::from_raw_parts(v as *const T as *const u8, std::mem::size_of::<T>()) }
+        .to_vec()
+}
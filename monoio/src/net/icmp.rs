@@ -0,0 +1,229 @@
+//! ICMP sockets without privileges, a.k.a. ping sockets.
+
+use std::{
+    io,
+    net::{IpAddr, SocketAddr},
+    os::{
+        fd::BorrowedFd,
+        unix::prelude::{AsRawFd, IntoRawFd, RawFd},
+    },
+};
+
+use crate::{
+    buf::{IoBuf, IoBufMut},
+    driver::{op::Op, shared_fd::SharedFd},
+    net::new_socket_with_protocol,
+    BufResult,
+};
+
+/// The length of the header of an echo message, which the payload follows.
+pub const ECHO_HEADER_LEN: usize = 8;
+
+const ICMP_ECHO_REQUEST: u8 = 8;
+const ICMP_ECHO_REPLY: u8 = 0;
+const ICMPV6_ECHO_REQUEST: u8 = 128;
+const ICMPV6_ECHO_REPLY: u8 = 129;
+
+/// An ICMP socket of `SOCK_DGRAM`, which sends echo requests and receives the replies
+/// without `CAP_NET_RAW`.
+///
+/// The kernel fills the identifier of the sent requests with the [`ident`](Self::ident)
+/// of the socket, computes the checksums, and only delivers the replies to the requests of
+/// this socket. The group of the process must be allowed by the
+/// `net.ipv4.ping_group_range` sysctl, which covers IPv6 too.
+pub struct IcmpSocket {
+    fd: SharedFd,
+    ipv6: bool,
+}
+
+/// The information of a received echo reply. The whole ICMP message is in the buffer, with
+/// the payload starting at [`ECHO_HEADER_LEN`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EchoReply {
+    addr: IpAddr,
+    ident: u16,
+    seq: u16,
+    len: usize,
+}
+
+impl EchoReply {
+    /// Returns the address of the host replied.
+    #[inline]
+    pub fn addr(&self) -> IpAddr {
+        self.addr
+    }
+
+    /// Returns the identifier, which is the [`ident`](IcmpSocket::ident) of the socket.
+    #[inline]
+    pub fn ident(&self) -> u16 {
+        self.ident
+    }
+
+    /// Returns the sequence number of the request replied.
+    #[inline]
+    pub fn seq(&self) -> u16 {
+        self.seq
+    }
+
+    /// Returns the length of the payload.
+    #[inline]
+    pub fn payload_len(&self) -> usize {
+        self.len
+    }
+}
+
+impl IcmpSocket {
+    /// Create an ICMP socket bound to the address, whose family decides whether it is ICMP
+    /// or ICMPv6. The port of the address is the identifier of the echo requests, and the
+    /// kernel picks a free one if it is 0.
+    pub fn bind(addr: SocketAddr) -> io::Result<Self> {
+        let (domain, protocol) = match addr {
+            SocketAddr::V4(_) => (libc::AF_INET, libc::IPPROTO_ICMP),
+            SocketAddr::V6(_) => (libc::AF_INET6, libc::IPPROTO_ICMPV6),
+        };
+        let socket = new_socket_with_protocol(domain, libc::SOCK_DGRAM, protocol)?;
+        let this = Self {
+            fd: SharedFd::new::<false>(socket)?,
+            ipv6: addr.is_ipv6(),
+        };
+        this.with_socket(|s| s.bind(&addr.into()))?;
+        Ok(this)
+    }
+
+    /// Returns the identifier the kernel puts in the echo requests.
+    pub fn ident(&self) -> io::Result<u16> {
+        self.local_addr().map(|addr| addr.port())
+    }
+
+    /// Returns the local address of the socket, whose port is the identifier.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.with_socket(|s| s.local_addr())?
+            .as_socket()
+            .ok_or_else(|| io::ErrorKind::InvalidInput.into())
+    }
+
+    /// Sends an echo request with the sequence number and the payload to the host.
+    /// On success, returns the number of bytes of the payload sent.
+    pub async fn send_echo<T: IoBuf>(
+        &self,
+        addr: IpAddr,
+        seq: u16,
+        payload: T,
+    ) -> BufResult<usize, T> {
+        let ty = if self.ipv6 {
+            ICMPV6_ECHO_REQUEST
+        } else {
+            ICMP_ECHO_REQUEST
+        };
+        // Safety: the buffer is initialized to `bytes_init`.
+        let data = unsafe { std::slice::from_raw_parts(payload.read_ptr(), payload.bytes_init()) };
+        let mut msg = Vec::with_capacity(ECHO_HEADER_LEN + data.len());
+        // The checksum and the identifier are filled by the kernel.
+        msg.extend_from_slice(&[ty, 0, 0, 0, 0, 0]);
+        msg.extend_from_slice(&seq.to_be_bytes());
+        msg.extend_from_slice(data);
+        let (res, _) = self.send_to(msg, SocketAddr::new(addr, 0)).await;
+        (res.map(|n| n.saturating_sub(ECHO_HEADER_LEN)), payload)
+    }
+
+    /// Receives an echo reply into the buffer, skipping the other ICMP messages.
+    pub async fn recv_echo<T: IoBufMut>(&self, mut buf: T) -> BufResult<EchoReply, T> {
+        let reply = if self.ipv6 {
+            ICMPV6_ECHO_REPLY
+        } else {
+            ICMP_ECHO_REPLY
+        };
+        loop {
+            let (res, b) = self.recv_from(buf).await;
+            buf = b;
+            let (n, addr) = match res {
+                Ok(v) => v,
+                Err(e) => return (Err(e), buf),
+            };
+            // Safety: the kernel wrote `n` bytes to the buffer.
+            let msg = unsafe { std::slice::from_raw_parts(buf.write_ptr(), n) };
+            if n < ECHO_HEADER_LEN || msg[0] != reply {
+                continue;
+            }
+            let echo = EchoReply {
+                addr: addr.ip(),
+                ident: u16::from_be_bytes([msg[4], msg[5]]),
+                seq: u16::from_be_bytes([msg[6], msg[7]]),
+                len: n - ECHO_HEADER_LEN,
+            };
+            return (Ok(echo), buf);
+        }
+    }
+
+    /// Sends an ICMP message, whose checksum and identifier are filled by the kernel. The
+    /// port of the address is ignored.
+    pub async fn send_to<T: IoBuf>(&self, buf: T, addr: SocketAddr) -> BufResult<usize, T> {
+        let op = Op::send_msg(self.fd.clone(), buf, Some(addr)).unwrap();
+        op.wait().await
+    }
+
+    /// Receives an ICMP message without the IP header, and returns the source address with
+    /// port 0.
+    pub async fn recv_from<T: IoBufMut>(&self, buf: T) -> BufResult<(usize, SocketAddr), T> {
+        let op = Op::recv_msg(self.fd.clone(), buf).unwrap();
+        op.wait().await
+    }
+
+    /// Wait for read readiness.
+    /// Note: Do not use it before every io. It is different from other runtimes!
+    ///
+    /// Everytime call to this method may pay a syscall cost.
+    /// In uring impl, it will push a PollAdd op; in epoll impl, it will use use
+    /// inner readiness state; if !relaxed, it will call syscall poll after that.
+    ///
+    /// If relaxed, on legacy driver it may return false positive result.
+    /// If you want to do io by your own, you must maintain io readiness and wait
+    /// for io ready with relaxed=false.
+    pub async fn readable(&self, relaxed: bool) -> io::Result<()> {
+        let op = Op::poll_read(&self.fd, relaxed).unwrap();
+        op.wait().await
+    }
+
+    /// Wait for write readiness.
+    /// Note: Do not use it before every io. It is different from other runtimes!
+    ///
+    /// Everytime call to this method may pay a syscall cost.
+    /// In uring impl, it will push a PollAdd op; in epoll impl, it will use use
+    /// inner readiness state; if !relaxed, it will call syscall poll after that.
+    ///
+    /// If relaxed, on legacy driver it may return false positive result.
+    /// If you want to do io by your own, you must maintain io readiness and wait
+    /// for io ready with relaxed=false.
+    pub async fn writable(&self, relaxed: bool) -> io::Result<()> {
+        let op = Op::poll_write(&self.fd, relaxed).unwrap();
+        op.wait().await
+    }
+
+    #[inline]
+    fn with_socket<R>(&self, f: impl FnOnce(&socket2::SockRef<'_>) -> R) -> R {
+        let fd = unsafe { BorrowedFd::borrow_raw(self.fd.raw_fd()) };
+        f(&socket2::SockRef::from(&fd))
+    }
+}
+
+impl AsRawFd for IcmpSocket {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.raw_fd()
+    }
+}
+
+impl IntoRawFd for IcmpSocket {
+    #[inline]
+    fn into_raw_fd(self) -> RawFd {
+        self.fd
+            .try_unwrap()
+            .expect("unexpected multiple reference to rawfd")
+    }
+}
+
+impl std::fmt::Debug for IcmpSocket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IcmpSocket").field("fd", &self.fd).finish()
+    }
+}
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 /// Custom listener options
 #[derive(Debug, Clone, Copy)]
 #[non_exhaustive]
@@ -14,6 +16,14 @@ pub struct ListenerOpts {
     pub recv_buf_size: Option<usize>,
     /// TCP fast open.
     pub tcp_fast_open: bool,
+    /// Queue length of the TCP fast open requests, or None to use the backlog size.
+    pub tcp_fast_open_queue: Option<i32>,
+    /// Whether an IPv6 listener accepts IPv6 only(IPV6_V6ONLY), or None to use the system
+    /// default.
+    pub ipv6_only: Option<bool>,
+    /// How long to wait for the data before accepting a connection(TCP_DEFER_ACCEPT), or
+    /// None to accept connections as soon as they are established.
+    pub defer_accept: Option<Duration>,
 }
 
 impl Default for ListenerOpts {
@@ -34,6 +44,9 @@ impl ListenerOpts {
             send_buf_size: None,
             recv_buf_size: None,
             tcp_fast_open: false,
+            tcp_fast_open_queue: None,
+            ipv6_only: None,
+            defer_accept: None,
         }
     }
 
@@ -88,4 +101,32 @@ impl ListenerOpts {
         self.tcp_fast_open = fast_open;
         self
     }
+
+    /// Enable FastOpen with the given queue length of the pending requests.
+    /// Note: The queue length is only used on linux and android.
+    #[must_use]
+    #[inline]
+    pub fn tcp_fast_open_queue(mut self, queue_len: i32) -> Self {
+        self.tcp_fast_open = true;
+        self.tcp_fast_open_queue = Some(queue_len);
+        self
+    }
+
+    /// Specify IPV6_V6ONLY. It only applies to IPv6 addresses.
+    #[must_use]
+    #[inline]
+    pub fn ipv6_only(mut self, ipv6_only: bool) -> Self {
+        self.ipv6_only = Some(ipv6_only);
+        self
+    }
+
+    /// Specify TCP_DEFER_ACCEPT, so the connections are not accepted until data arrives
+    /// or the timeout expires. The timeout is rounded to seconds.
+    /// Note: This option only works for linux and android.
+    #[must_use]
+    #[inline]
+    pub fn defer_accept(mut self, timeout: Duration) -> Self {
+        self.defer_accept = Some(timeout);
+        self
+    }
 }
@@ -1,18 +1,46 @@
 //! Network related
-//! Currently, TCP/UnixStream/UnixDatagram are implemented.
+//! Currently, TCP/UDP/SCTP/UnixStream/UnixDatagram/Vsock, raw sockets, ICMP sockets and
+//! netlink sockets are implemented.
 
+#[cfg(unix)]
+pub(crate) mod cmsg;
+#[cfg(target_os = "linux")]
+pub mod icmp;
 mod listener_config;
+#[cfg(target_os = "linux")]
+pub mod netlink;
+#[cfg(target_os = "linux")]
+mod raw;
+mod recv_multi;
+pub mod resolver;
+#[cfg(target_os = "linux")]
+pub mod sctp;
 pub mod tcp;
 pub mod udp;
 #[cfg(unix)]
 pub mod unix;
+#[cfg(target_os = "linux")]
+pub mod vsock;
 
+#[cfg(target_os = "linux")]
+pub use icmp::IcmpSocket;
 pub use listener_config::ListenerOpts;
 #[deprecated(since = "0.2.0", note = "use ListenerOpts")]
 pub use listener_config::ListenerOpts as ListenerConfig;
-pub use tcp::{TcpConnectOpts, TcpListener, TcpStream};
+#[cfg(target_os = "linux")]
+pub use netlink::NetlinkSocket;
+#[cfg(target_os = "linux")]
+pub use raw::RawSocket;
+pub use recv_multi::RecvMulti;
+#[cfg(target_os = "linux")]
+pub use sctp::{SctpListener, SctpStream};
+#[cfg(target_os = "linux")]
+pub use tcp::TcpInfo;
+pub use tcp::{TcpConnectOpts, TcpListener, TcpSocket, TcpStream};
 #[cfg(unix)]
 pub use unix::{Pipe, UnixDatagram, UnixListener, UnixStream};
+#[cfg(target_os = "linux")]
+pub use vsock::{VsockAddr, VsockListener, VsockStream};
 #[cfg(windows)]
 use {
     std::os::windows::prelude::RawSocket,
@@ -25,11 +53,21 @@ use {
     },
 };
 
-// Copied from mio.
 #[cfg(unix)]
+#[inline]
 pub(crate) fn new_socket(
     domain: libc::c_int,
     socket_type: libc::c_int,
+) -> std::io::Result<libc::c_int> {
+    new_socket_with_protocol(domain, socket_type, 0)
+}
+
+// Copied from mio.
+#[cfg(unix)]
+pub(crate) fn new_socket_with_protocol(
+    domain: libc::c_int,
+    socket_type: libc::c_int,
+    protocol: libc::c_int,
 ) -> std::io::Result<libc::c_int> {
     #[cfg(any(
         target_os = "android",
@@ -53,7 +91,7 @@ pub(crate) fn new_socket(
     // Gives a warning for platforms without SOCK_NONBLOCK.
     #[allow(clippy::let_and_return)]
     #[cfg(unix)]
-    let socket = crate::syscall!(socket@RAW(domain, socket_type, 0));
+    let socket = crate::syscall!(socket@RAW(domain, socket_type, protocol));
 
     // Mimic `libstd` and set `SO_NOSIGPIPE` on apple systems.
     #[cfg(target_vendor = "apple")]
@@ -109,6 +147,11 @@ pub(crate) fn new_socket(
         PartialEq::eq,
         INVALID_SOCKET
     )?;
+    // The ops without overlapped version are executed with blocking syscalls by the IOCP driver.
+    #[cfg(feature = "iocp")]
+    if !crate::driver::op::is_legacy() {
+        return Ok(socket as RawSocket);
+    }
     crate::syscall!(
         ioctlsocket@RAW(socket, FIONBIO, &mut 1),
         PartialEq::ne,
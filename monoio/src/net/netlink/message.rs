@@ -0,0 +1,166 @@
+//! The framing of netlink messages, each of which is a `nlmsghdr` followed by the payload,
+//! padded to 4 bytes.
+
+use std::io;
+
+/// The length of `nlmsghdr`.
+pub const HEADER_LEN: usize = std::mem::size_of::<libc::nlmsghdr>();
+
+#[inline]
+const fn align(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+/// A netlink message borrowing its payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetlinkMessage<'a> {
+    ty: u16,
+    flags: u16,
+    seq: u32,
+    port_id: u32,
+    payload: &'a [u8],
+}
+
+impl<'a> NetlinkMessage<'a> {
+    /// Create a message to send. The flags are the `NLM_F_*` flags, and requests to the
+    /// kernel must include `NLM_F_REQUEST`.
+    #[inline]
+    pub fn new(ty: u16, flags: u16, seq: u32, payload: &'a [u8]) -> Self {
+        Self {
+            ty,
+            flags,
+            seq,
+            port_id: 0,
+            payload,
+        }
+    }
+
+    /// Returns the type, e.g. `RTM_NEWLINK` or `NLMSG_DONE`.
+    #[inline]
+    pub fn ty(&self) -> u16 {
+        self.ty
+    }
+
+    /// Returns the `NLM_F_*` flags.
+    #[inline]
+    pub fn flags(&self) -> u16 {
+        self.flags
+    }
+
+    /// Returns the sequence number, which the replies of a request copy from it.
+    #[inline]
+    pub fn seq(&self) -> u32 {
+        self.seq
+    }
+
+    /// Returns the port id of the sender, which is 0 for the kernel.
+    #[inline]
+    pub fn port_id(&self) -> u32 {
+        self.port_id
+    }
+
+    /// Returns the payload without the header and the padding.
+    #[inline]
+    pub fn payload(&self) -> &'a [u8] {
+        self.payload
+    }
+
+    /// Returns true if it is `NLMSG_DONE`, the end of a multipart reply.
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.ty == libc::NLMSG_DONE as u16
+    }
+
+    /// Returns the result carried by a `NLMSG_ERROR` message, which is `Ok(())` for an
+    /// acknowledgement, or `None` for other messages.
+    pub fn error(&self) -> Option<io::Result<()>> {
+        if self.ty != libc::NLMSG_ERROR as u16 {
+            return None;
+        }
+        let code = self
+            .payload
+            .get(..4)
+            .map(|b| i32::from_ne_bytes([b[0], b[1], b[2], b[3]]));
+        Some(match code {
+            Some(0) => Ok(()),
+            Some(code) => Err(io::Error::from_raw_os_error(-code)),
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "truncated netlink error message",
+            )),
+        })
+    }
+
+    /// Returns the length of the encoded message including the padding.
+    #[inline]
+    pub fn encoded_len(&self) -> usize {
+        align(HEADER_LEN + self.payload.len())
+    }
+
+    /// Append the encoded message to the buffer, so that several messages can be sent at
+    /// once.
+    pub fn encode(&self, buf: &mut Vec<u8>) {
+        let len = HEADER_LEN + self.payload.len();
+        buf.reserve(align(len));
+        buf.extend_from_slice(&(len as u32).to_ne_bytes());
+        buf.extend_from_slice(&self.ty.to_ne_bytes());
+        buf.extend_from_slice(&self.flags.to_ne_bytes());
+        buf.extend_from_slice(&self.seq.to_ne_bytes());
+        buf.extend_from_slice(&self.port_id.to_ne_bytes());
+        buf.extend_from_slice(self.payload);
+        buf.resize(buf.len() + align(len) - len, 0);
+    }
+
+    /// Returns the encoded message.
+    pub fn to_vec(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(self.encoded_len());
+        self.encode(&mut buf);
+        buf
+    }
+}
+
+/// Returns an iterator over the messages in a received datagram.
+#[inline]
+pub fn messages(buf: &[u8]) -> Messages<'_> {
+    Messages { buf }
+}
+
+/// An iterator over the messages in a buffer, created by [`messages`]. It yields an error
+/// and stops if a message is malformed.
+#[derive(Debug, Clone)]
+pub struct Messages<'a> {
+    buf: &'a [u8],
+}
+
+impl<'a> Iterator for Messages<'a> {
+    type Item = io::Result<NetlinkMessage<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buf.is_empty() {
+            return None;
+        }
+        let buf = std::mem::take(&mut self.buf);
+        if buf.len() < HEADER_LEN {
+            return Some(Err(malformed()));
+        }
+        let u32_at = |i: usize| u32::from_ne_bytes([buf[i], buf[i + 1], buf[i + 2], buf[i + 3]]);
+        let u16_at = |i: usize| u16::from_ne_bytes([buf[i], buf[i + 1]]);
+        let len = u32_at(0) as usize;
+        if len < HEADER_LEN || len > buf.len() {
+            return Some(Err(malformed()));
+        }
+        let msg = NetlinkMessage {
+            ty: u16_at(4),
+            flags: u16_at(6),
+            seq: u32_at(8),
+            port_id: u32_at(12),
+            payload: &buf[HEADER_LEN..len],
+        };
+        self.buf = buf.get(align(len)..).unwrap_or_default();
+        Some(Ok(msg))
+    }
+}
+
+fn malformed() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "malformed netlink message")
+}
@@ -0,0 +1,164 @@
+//! Netlink(`AF_NETLINK`) sockets, for the communication with the kernel subsystems like
+//! routing and conntrack.
+
+pub mod message;
+
+use std::{
+    io,
+    os::unix::prelude::{AsRawFd, IntoRawFd, RawFd},
+};
+
+pub use message::{messages, NetlinkMessage};
+
+use crate::{
+    buf::{IoBuf, IoBufMut},
+    driver::{op::Op, shared_fd::SharedFd},
+    net::new_socket_with_protocol,
+    BufResult,
+};
+
+/// A netlink socket, sending requests to the kernel and receiving the replies and the
+/// notifications of the subscribed multicast groups.
+///
+/// A datagram may contain several messages, which can be iterated with [`messages`].
+pub struct NetlinkSocket {
+    fd: SharedFd,
+}
+
+impl NetlinkSocket {
+    /// Create a netlink socket of the protocol, e.g. `NETLINK_ROUTE`. The kernel assigns
+    /// the port id, and the sent messages are destined for the kernel.
+    pub fn new(protocol: libc::c_int) -> io::Result<Self> {
+        let socket = new_socket_with_protocol(libc::AF_NETLINK, libc::SOCK_RAW, protocol)?;
+        let this = Self {
+            fd: SharedFd::new::<false>(socket)?,
+        };
+        // Safety: all zero is a valid `sockaddr_nl`.
+        let mut addr: libc::sockaddr_nl = unsafe { std::mem::zeroed() };
+        addr.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+        let len = std::mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t;
+        crate::syscall!(bind@RAW(socket, &addr as *const _ as *const libc::sockaddr, len))?;
+        crate::syscall!(connect@RAW(socket, &addr as *const _ as *const libc::sockaddr, len))?;
+        Ok(this)
+    }
+
+    /// Returns the port id assigned by the kernel, which is the destination of the
+    /// unicast replies.
+    pub fn port_id(&self) -> io::Result<u32> {
+        // Safety: all zero is a valid `sockaddr_nl`.
+        let mut addr: libc::sockaddr_nl = unsafe { std::mem::zeroed() };
+        let mut len = std::mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t;
+        crate::syscall!(getsockname@RAW(
+            self.fd.raw_fd(),
+            &mut addr as *mut _ as *mut libc::sockaddr,
+            &mut len
+        ))?;
+        Ok(addr.nl_pid)
+    }
+
+    /// Subscribe to the multicast group of the number, e.g. `RTNLGRP_LINK`, so that its
+    /// notifications are received.
+    pub fn add_membership(&self, group: u32) -> io::Result<()> {
+        crate::net::cmsg::set_int_opt(
+            self.fd.raw_fd(),
+            libc::SOL_NETLINK,
+            libc::NETLINK_ADD_MEMBERSHIP,
+            group as libc::c_int,
+        )
+    }
+
+    /// Unsubscribe from the multicast group of the number.
+    pub fn drop_membership(&self, group: u32) -> io::Result<()> {
+        crate::net::cmsg::set_int_opt(
+            self.fd.raw_fd(),
+            libc::SOL_NETLINK,
+            libc::NETLINK_DROP_MEMBERSHIP,
+            group as libc::c_int,
+        )
+    }
+
+    /// Sets the value of the `NETLINK_NO_ENOBUFS` option. If enabled, the receive no longer
+    /// fails with `ENOBUFS` when the notifications overflow the receive buffer, and they are
+    /// dropped silently.
+    pub fn set_no_enobufs(&self, enabled: bool) -> io::Result<()> {
+        crate::net::cmsg::set_int_opt(
+            self.fd.raw_fd(),
+            libc::SOL_NETLINK,
+            libc::NETLINK_NO_ENOBUFS,
+            enabled as libc::c_int,
+        )
+    }
+
+    /// Sends a datagram of one or more encoded messages to the kernel.
+    pub async fn send<T: IoBuf>(&self, buf: T) -> BufResult<usize, T> {
+        let op = Op::send(self.fd.clone(), buf).unwrap();
+        op.result().await
+    }
+
+    /// Encodes and sends a message to the kernel.
+    pub async fn send_message(&self, msg: &NetlinkMessage<'_>) -> io::Result<()> {
+        let (res, _) = self.send(msg.to_vec()).await;
+        res.map(|_| ())
+    }
+
+    /// Receives a datagram of one or more messages. If the buffer is smaller than the
+    /// datagram, the rest is discarded, so it should be at least 8KiB for dumps.
+    pub async fn recv<T: IoBufMut>(&self, buf: T) -> BufResult<usize, T> {
+        let op = Op::recv(self.fd.clone(), buf).unwrap();
+        op.result().await
+    }
+
+    /// Wait for read readiness.
+    /// Note: Do not use it before every io. It is different from other runtimes!
+    ///
+    /// Everytime call to this method may pay a syscall cost.
+    /// In uring impl, it will push a PollAdd op; in epoll impl, it will use use
+    /// inner readiness state; if !relaxed, it will call syscall poll after that.
+    ///
+    /// If relaxed, on legacy driver it may return false positive result.
+    /// If you want to do io by your own, you must maintain io readiness and wait
+    /// for io ready with relaxed=false.
+    pub async fn readable(&self, relaxed: bool) -> io::Result<()> {
+        let op = Op::poll_read(&self.fd, relaxed).unwrap();
+        op.wait().await
+    }
+
+    /// Wait for write readiness.
+    /// Note: Do not use it before every io. It is different from other runtimes!
+    ///
+    /// Everytime call to this method may pay a syscall cost.
+    /// In uring impl, it will push a PollAdd op; in epoll impl, it will use use
+    /// inner readiness state; if !relaxed, it will call syscall poll after that.
+    ///
+    /// If relaxed, on legacy driver it may return false positive result.
+    /// If you want to do io by your own, you must maintain io readiness and wait
+    /// for io ready with relaxed=false.
+    pub async fn writable(&self, relaxed: bool) -> io::Result<()> {
+        let op = Op::poll_write(&self.fd, relaxed).unwrap();
+        op.wait().await
+    }
+}
+
+impl AsRawFd for NetlinkSocket {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.raw_fd()
+    }
+}
+
+impl IntoRawFd for NetlinkSocket {
+    #[inline]
+    fn into_raw_fd(self) -> RawFd {
+        self.fd
+            .try_unwrap()
+            .expect("unexpected multiple reference to rawfd")
+    }
+}
+
+impl std::fmt::Debug for NetlinkSocket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NetlinkSocket")
+            .field("fd", &self.fd)
+            .finish()
+    }
+}
@@ -0,0 +1,274 @@
+//! Raw IP sockets and packet(`AF_PACKET`) sockets.
+
+use std::{
+    io,
+    net::SocketAddr,
+    os::{
+        fd::BorrowedFd,
+        unix::prelude::{AsRawFd, IntoRawFd, RawFd},
+    },
+};
+
+use crate::{
+    buf::{IoBuf, IoBufMut},
+    driver::{op::Op, shared_fd::SharedFd},
+    net::new_socket_with_protocol,
+    BufResult,
+};
+
+// Not in libc yet, see linux/if_packet.h.
+const PACKET_ADD_MEMBERSHIP: libc::c_int = 1;
+const PACKET_DROP_MEMBERSHIP: libc::c_int = 2;
+const PACKET_MR_PROMISC: libc::c_ushort = 1;
+
+#[repr(C)]
+#[allow(non_camel_case_types)]
+struct packet_mreq {
+    mr_ifindex: libc::c_int,
+    mr_type: libc::c_ushort,
+    mr_alen: libc::c_ushort,
+    mr_address: [libc::c_uchar; 8],
+}
+
+/// A raw socket, which sends and receives the packets below the transport layer.
+///
+/// Packet sockets created with [`RawSocket::packet`] see the whole link layer frames of an
+/// interface, and raw IP sockets created with [`RawSocket::ipv4`] or [`RawSocket::ipv6`]
+/// see the IP packets of a protocol. Both require `CAP_NET_RAW`.
+pub struct RawSocket {
+    fd: SharedFd,
+    /// The protocol of a packet socket in the host byte order, used when binding.
+    packet_protocol: Option<u16>,
+}
+
+impl RawSocket {
+    /// Create a raw socket with the given domain, type and protocol, which are passed to
+    /// `socket(2)` as is.
+    pub fn new(domain: libc::c_int, ty: libc::c_int, protocol: libc::c_int) -> io::Result<Self> {
+        let socket = new_socket_with_protocol(domain, ty, protocol)?;
+        Ok(Self {
+            fd: SharedFd::new::<false>(socket)?,
+            packet_protocol: None,
+        })
+    }
+
+    /// Create a packet socket receiving and sending the whole frames including the link
+    /// layer header. The protocol is an ethernet type like `ETH_P_IP`, or `ETH_P_ALL` to
+    /// capture all the frames.
+    ///
+    /// The socket captures the frames of all the interfaces until it is bound to one with
+    /// [`bind_interface`](Self::bind_interface), which is required before sending.
+    pub fn packet(protocol: u16) -> io::Result<Self> {
+        let mut socket = Self::new(
+            libc::AF_PACKET,
+            libc::SOCK_RAW,
+            protocol.to_be() as libc::c_int,
+        )?;
+        socket.packet_protocol = Some(protocol);
+        Ok(socket)
+    }
+
+    /// Create a raw IPv4 socket of the protocol, e.g. `IPPROTO_ICMP`. The received packets
+    /// include the IP header.
+    ///
+    /// A socket of `IPPROTO_RAW` can only send, and the IP header must be included in the
+    /// sent packets.
+    pub fn ipv4(protocol: libc::c_int) -> io::Result<Self> {
+        Self::new(libc::AF_INET, libc::SOCK_RAW, protocol)
+    }
+
+    /// Create a raw IPv6 socket of the protocol, e.g. `IPPROTO_ICMPV6`. Unlike IPv4, the
+    /// received packets do not include the IP header.
+    pub fn ipv6(protocol: libc::c_int) -> io::Result<Self> {
+        Self::new(libc::AF_INET6, libc::SOCK_RAW, protocol)
+    }
+
+    /// Bind a packet socket to the interface of the index, so that only its frames are
+    /// received, and the frames are sent through it.
+    pub fn bind_interface(&self, ifindex: u32) -> io::Result<()> {
+        let protocol = self
+            .packet_protocol
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "not a packet socket"))?;
+        // Safety: all zero is a valid `sockaddr_ll`.
+        let mut addr: libc::sockaddr_ll = unsafe { std::mem::zeroed() };
+        addr.sll_family = libc::AF_PACKET as libc::c_ushort;
+        addr.sll_protocol = protocol.to_be();
+        addr.sll_ifindex = ifindex as libc::c_int;
+        crate::syscall!(bind@RAW(
+            self.fd.raw_fd(),
+            &addr as *const _ as *const libc::sockaddr,
+            std::mem::size_of::<libc::sockaddr_ll>() as libc::socklen_t
+        ))?;
+        Ok(())
+    }
+
+    /// Enable or disable the promiscuous mode of the interface for a packet socket, so
+    /// that the frames destined for the other hosts are captured too. It is disabled when
+    /// the socket is closed.
+    pub fn set_promiscuous(&self, ifindex: u32, promiscuous: bool) -> io::Result<()> {
+        let mreq = packet_mreq {
+            mr_ifindex: ifindex as libc::c_int,
+            mr_type: PACKET_MR_PROMISC,
+            mr_alen: 0,
+            mr_address: [0; 8],
+        };
+        let name = if promiscuous {
+            PACKET_ADD_MEMBERSHIP
+        } else {
+            PACKET_DROP_MEMBERSHIP
+        };
+        crate::syscall!(setsockopt@RAW(
+            self.fd.raw_fd(),
+            libc::SOL_PACKET,
+            name,
+            &mreq as *const _ as *const libc::c_void,
+            std::mem::size_of::<packet_mreq>() as libc::socklen_t
+        ))?;
+        Ok(())
+    }
+
+    /// Attach a classic BPF program with `SO_ATTACH_FILTER`, which drops the packets the
+    /// program returns 0 for, and truncates the others to the returned length. It replaces
+    /// the filter attached before.
+    ///
+    /// The packets received before the filter is attached are not filtered.
+    pub fn attach_filter(&self, filter: &[libc::sock_filter]) -> io::Result<()> {
+        let len = libc::c_ushort::try_from(filter.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "filter is too long"))?;
+        let prog = libc::sock_fprog {
+            len,
+            filter: filter.as_ptr() as *mut libc::sock_filter,
+        };
+        crate::syscall!(setsockopt@RAW(
+            self.fd.raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_ATTACH_FILTER,
+            &prog as *const _ as *const libc::c_void,
+            std::mem::size_of::<libc::sock_fprog>() as libc::socklen_t
+        ))?;
+        Ok(())
+    }
+
+    /// Attach an eBPF program of the type `BPF_PROG_TYPE_SOCKET_FILTER`, which is loaded
+    /// with `bpf(2)`, with `SO_ATTACH_BPF`.
+    pub fn attach_bpf(&self, prog: BorrowedFd<'_>) -> io::Result<()> {
+        crate::net::cmsg::set_int_opt(
+            self.fd.raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_ATTACH_BPF,
+            prog.as_raw_fd(),
+        )
+    }
+
+    /// Remove the filter attached with [`attach_filter`](Self::attach_filter) or
+    /// [`attach_bpf`](Self::attach_bpf).
+    pub fn detach_filter(&self) -> io::Result<()> {
+        crate::net::cmsg::set_int_opt(
+            self.fd.raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_DETACH_FILTER,
+            0,
+        )
+    }
+
+    /// Sets the value of the `IP_HDRINCL` option of a raw IPv4 socket. If enabled, the IP
+    /// header is included in the sent packets instead of being built by the kernel.
+    pub fn set_header_included(&self, included: bool) -> io::Result<()> {
+        crate::net::cmsg::set_int_opt(
+            self.fd.raw_fd(),
+            libc::IPPROTO_IP,
+            libc::IP_HDRINCL,
+            included as libc::c_int,
+        )
+    }
+
+    /// Sends a packet. A packet socket must be bound to an interface first, and the frame
+    /// must include the link layer header.
+    pub async fn send<T: IoBuf>(&self, buf: T) -> BufResult<usize, T> {
+        let op = Op::send(self.fd.clone(), buf).unwrap();
+        op.result().await
+    }
+
+    /// Receives a packet. If the buffer is smaller than the packet, the rest is
+    /// discarded.
+    pub async fn recv<T: IoBufMut>(&self, buf: T) -> BufResult<usize, T> {
+        let op = Op::recv(self.fd.clone(), buf).unwrap();
+        op.result().await
+    }
+
+    /// Sends a packet to the address with a raw IP socket. The port of the address is
+    /// ignored.
+    pub async fn send_to<T: IoBuf>(&self, buf: T, addr: SocketAddr) -> BufResult<usize, T> {
+        let op = Op::send_msg(self.fd.clone(), buf, Some(addr)).unwrap();
+        op.wait().await
+    }
+
+    /// Receives a packet with a raw IP socket, and returns the source address with port 0.
+    ///
+    /// It can not be used with packet sockets, whose addresses are not IP addresses.
+    pub async fn recv_from<T: IoBufMut>(&self, buf: T) -> BufResult<(usize, SocketAddr), T> {
+        if self.packet_protocol.is_some() {
+            return (
+                Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "recv_from is not supported by packet sockets",
+                )),
+                buf,
+            );
+        }
+        let op = Op::recv_msg(self.fd.clone(), buf).unwrap();
+        op.wait().await
+    }
+
+    /// Wait for read readiness.
+    /// Note: Do not use it before every io. It is different from other runtimes!
+    ///
+    /// Everytime call to this method may pay a syscall cost.
+    /// In uring impl, it will push a PollAdd op; in epoll impl, it will use use
+    /// inner readiness state; if !relaxed, it will call syscall poll after that.
+    ///
+    /// If relaxed, on legacy driver it may return false positive result.
+    /// If you want to do io by your own, you must maintain io readiness and wait
+    /// for io ready with relaxed=false.
+    pub async fn readable(&self, relaxed: bool) -> io::Result<()> {
+        let op = Op::poll_read(&self.fd, relaxed).unwrap();
+        op.wait().await
+    }
+
+    /// Wait for write readiness.
+    /// Note: Do not use it before every io. It is different from other runtimes!
+    ///
+    /// Everytime call to this method may pay a syscall cost.
+    /// In uring impl, it will push a PollAdd op; in epoll impl, it will use use
+    /// inner readiness state; if !relaxed, it will call syscall poll after that.
+    ///
+    /// If relaxed, on legacy driver it may return false positive result.
+    /// If you want to do io by your own, you must maintain io readiness and wait
+    /// for io ready with relaxed=false.
+    pub async fn writable(&self, relaxed: bool) -> io::Result<()> {
+        let op = Op::poll_write(&self.fd, relaxed).unwrap();
+        op.wait().await
+    }
+}
+
+impl AsRawFd for RawSocket {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.raw_fd()
+    }
+}
+
+impl IntoRawFd for RawSocket {
+    #[inline]
+    fn into_raw_fd(self) -> RawFd {
+        self.fd
+            .try_unwrap()
+            .expect("unexpected multiple reference to rawfd")
+    }
+}
+
+impl std::fmt::Debug for RawSocket {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RawSocket").field("fd", &self.fd).finish()
+    }
+}
@@ -0,0 +1,46 @@
+use std::io;
+
+use crate::{
+    buf::ProvidedBuf,
+    driver::op::{recv, Op},
+    io::stream::Stream,
+};
+
+/// Stream of buffers filled by one multishot recv operation.
+///
+/// It is created by [`TcpStream::recv_multi`](super::TcpStream::recv_multi) or
+/// [`UdpSocket::recv_multi`](super::udp::UdpSocket::recv_multi). Each item is a buffer selected
+/// from the given [`BufGroup`](crate::buf::BufGroup), which will be given back to the group when
+/// dropped. The stream ends on EOF or when the operation is terminated by the kernel(for
+/// example, when the group runs out of buffers, an `ENOBUFS` error is yielded first); create a
+/// new one to continue receiving. Dropping the stream cancels the operation.
+pub struct RecvMulti {
+    op: Option<Op<recv::RecvMulti>>,
+}
+
+impl RecvMulti {
+    pub(crate) fn new(op: Op<recv::RecvMulti>) -> Self {
+        Self { op: Some(op) }
+    }
+}
+
+impl Stream for RecvMulti {
+    type Item = io::Result<ProvidedBuf>;
+
+    async fn next(&mut self) -> Option<Self::Item> {
+        let op = self.op.as_mut()?;
+        let item = std::future::poll_fn(|cx| op.poll_next_buf(cx)).await;
+        if item.is_none() {
+            self.op = None;
+        }
+        item
+    }
+}
+
+impl std::fmt::Debug for RecvMulti {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RecvMulti")
+            .field("finished", &self.op.is_none())
+            .finish()
+    }
+}
@@ -0,0 +1,199 @@
+//! Host name resolution.
+//!
+//! Resolving a host name with `getaddrinfo` blocks the current thread, which stalls all the
+//! other tasks of the runtime. [`GaiResolver`] offloads it to the blocking thread pool when
+//! the `sync` feature is enabled, and the `stub-resolver` feature adds a [`StubResolver`]
+//! which queries the name servers asynchronously. The resolved addresses can be connected to
+//! with [`TcpStream::connect_with_resolver`](crate::net::TcpStream::connect_with_resolver).
+
+use std::{
+    future::Future,
+    io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
+};
+
+#[cfg(feature = "stub-resolver")]
+mod stub;
+#[cfg(feature = "stub-resolver")]
+pub use stub::StubResolver;
+
+/// Resolve host names to socket addresses.
+pub trait Resolve {
+    /// Resolve the `host` to the addresses with the `port` filled in.
+    fn resolve(&self, host: &str, port: u16) -> impl Future<Output = io::Result<Vec<SocketAddr>>>;
+}
+
+/// The resolver with the `getaddrinfo` of the system.
+///
+/// With the `sync` feature, the lookup runs on the blocking thread pool attached to the
+/// runtime(or the current thread with [`BlockingStrategy::ExecuteLocal`]). Without it, or if
+/// the runtime has no thread pool and uses [`BlockingStrategy::Panic`], the lookup blocks the
+/// current thread instead of panicking.
+///
+/// [`BlockingStrategy::ExecuteLocal`]: crate::blocking::BlockingStrategy::ExecuteLocal
+/// [`BlockingStrategy::Panic`]: crate::blocking::BlockingStrategy::Panic
+#[derive(Debug, Default, Clone, Copy)]
+pub struct GaiResolver;
+
+impl Resolve for GaiResolver {
+    async fn resolve(&self, host: &str, port: u16) -> io::Result<Vec<SocketAddr>> {
+        #[cfg(feature = "sync")]
+        if crate::blocking::can_spawn_blocking() {
+            let host = host.to_owned();
+            return crate::spawn_blocking(move || {
+                std::net::ToSocketAddrs::to_socket_addrs(&(host.as_str(), port))
+                    .map(Iterator::collect)
+            })
+            .await
+            .map_err(|_| io::Error::other("background task failed"))?;
+        }
+        std::net::ToSocketAddrs::to_socket_addrs(&(host, port)).map(Iterator::collect)
+    }
+}
+
+/// Types which can be resolved to socket addresses.
+///
+/// It is like [`std::net::ToSocketAddrs`], but the host names are resolved asynchronously by
+/// a [`Resolve`] implementation instead of blocking the current thread.
+///
+/// This trait is sealed and implemented for the same types as the std one, except the
+/// iterators of `std::net::ToSocketAddrs` impls. The types implementing only the std trait can
+/// be resolved with `std::net::ToSocketAddrs::to_socket_addrs` first, and the addresses passed
+/// as a `&[SocketAddr]`.
+pub trait ToSocketAddrs: sealed::ToSocketAddrsPriv {}
+
+mod sealed {
+    use std::{io, net::SocketAddr};
+
+    /// The resolved addresses, or the host name to resolve.
+    pub enum Target<'a> {
+        Addrs(Vec<SocketAddr>),
+        Host(&'a str, u16),
+    }
+
+    pub trait ToSocketAddrsPriv {
+        fn to_target(&self) -> io::Result<Target<'_>>;
+    }
+}
+
+use sealed::{Target, ToSocketAddrsPriv};
+
+macro_rules! to_socket_addrs_impl {
+    ($($ty:ty => |$this:ident| $addr:expr),* $(,)?) => {
+        $(
+            impl ToSocketAddrs for $ty {}
+
+            impl ToSocketAddrsPriv for $ty {
+                #[inline]
+                fn to_target(&self) -> io::Result<Target<'_>> {
+                    let $this = self;
+                    Ok(Target::Addrs(vec![SocketAddr::from($addr)]))
+                }
+            }
+        )*
+    };
+}
+
+to_socket_addrs_impl! {
+    SocketAddr => |this| *this,
+    SocketAddrV4 => |this| *this,
+    SocketAddrV6 => |this| *this,
+    (IpAddr, u16) => |this| *this,
+    (Ipv4Addr, u16) => |this| *this,
+    (Ipv6Addr, u16) => |this| *this,
+}
+
+impl ToSocketAddrs for [SocketAddr] {}
+
+impl ToSocketAddrsPriv for [SocketAddr] {
+    #[inline]
+    fn to_target(&self) -> io::Result<Target<'_>> {
+        Ok(Target::Addrs(self.to_vec()))
+    }
+}
+
+impl ToSocketAddrs for str {}
+
+impl ToSocketAddrsPriv for str {
+    fn to_target(&self) -> io::Result<Target<'_>> {
+        if let Ok(addr) = self.parse::<SocketAddr>() {
+            return Ok(Target::Addrs(vec![addr]));
+        }
+        let invalid = || io::Error::new(io::ErrorKind::InvalidInput, "invalid socket address");
+        let (host, port) = self.rsplit_once(':').ok_or_else(invalid)?;
+        let port = port.parse().map_err(|_| invalid())?;
+        Ok(Target::Host(host, port))
+    }
+}
+
+impl ToSocketAddrs for String {}
+
+impl ToSocketAddrsPriv for String {
+    #[inline]
+    fn to_target(&self) -> io::Result<Target<'_>> {
+        self.as_str().to_target()
+    }
+}
+
+impl ToSocketAddrs for (&str, u16) {}
+
+impl ToSocketAddrsPriv for (&str, u16) {
+    #[inline]
+    fn to_target(&self) -> io::Result<Target<'_>> {
+        Ok(host_target(self.0, self.1))
+    }
+}
+
+impl ToSocketAddrs for (String, u16) {}
+
+impl ToSocketAddrsPriv for (String, u16) {
+    #[inline]
+    fn to_target(&self) -> io::Result<Target<'_>> {
+        Ok(host_target(&self.0, self.1))
+    }
+}
+
+fn host_target(host: &str, port: u16) -> Target<'_> {
+    match host.parse::<IpAddr>() {
+        Ok(ip) => Target::Addrs(vec![SocketAddr::new(ip, port)]),
+        Err(_) => Target::Host(host, port),
+    }
+}
+
+impl<T: ToSocketAddrs + ?Sized> ToSocketAddrs for &T {}
+
+impl<T: ToSocketAddrsPriv + ?Sized> ToSocketAddrsPriv for &T {
+    #[inline]
+    fn to_target(&self) -> io::Result<Target<'_>> {
+        (**self).to_target()
+    }
+}
+
+/// Resolve the address with the [`GaiResolver`].
+///
+/// # Examples
+///
+/// ```no_run
+/// #[monoio::main]
+/// async fn main() -> std::io::Result<()> {
+///     let addrs = monoio::net::resolver::lookup_host("example.com:443").await?;
+///     println!("{addrs:?}");
+///     Ok(())
+/// }
+/// ```
+pub async fn lookup_host<A: ToSocketAddrs>(addr: A) -> io::Result<Vec<SocketAddr>> {
+    lookup_host_with(&GaiResolver, addr).await
+}
+
+/// Resolve the address with the given resolver.
+///
+/// The addresses which do not need a lookup, like `"127.0.0.1:80"`, are returned directly.
+pub async fn lookup_host_with<R: Resolve, A: ToSocketAddrs>(
+    resolver: &R,
+    addr: A,
+) -> io::Result<Vec<SocketAddr>> {
+    match addr.to_target()? {
+        Target::Addrs(addrs) => Ok(addrs),
+        Target::Host(host, port) => resolver.resolve(host, port).await,
+    }
+}
@@ -0,0 +1,308 @@
+use std::{
+    io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::Duration,
+};
+
+use super::Resolve;
+use crate::{
+    io::{AsyncReadRentExt, AsyncWriteRentExt},
+    net::{udp::UdpSocket, TcpStream},
+};
+
+const DNS_PORT: u16 = 53;
+const TYPE_A: u16 = 1;
+const TYPE_AAAA: u16 = 28;
+const CLASS_IN: u16 = 1;
+const FLAG_RESPONSE: u16 = 0x8000;
+const FLAG_TRUNCATED: u16 = 0x0200;
+const FLAG_RECURSION_DESIRED: u16 = 0x0100;
+const RCODE_NXDOMAIN: u16 = 3;
+// Without EDNS, a response over UDP is at most 512 bytes.
+const UDP_MSG_SIZE: usize = 512;
+
+/// A stub resolver which queries the name servers over UDP, and retries over TCP if the
+/// response is truncated.
+///
+/// The `A` and `AAAA` queries are sent concurrently to the name servers in order until one
+/// answers. There is no cache, search domain or hosts file lookup; `localhost` is resolved to
+/// the loopback addresses.
+///
+/// Note: The timer must be enabled on the runtime.
+///
+/// # Examples
+///
+/// ```no_run
+/// use monoio::net::resolver::{lookup_host_with, StubResolver};
+///
+/// #[monoio::main(timer_enabled = true)]
+/// async fn main() -> std::io::Result<()> {
+///     let resolver = StubResolver::new(vec!["8.8.8.8:53".parse().unwrap()]);
+///     let addrs = lookup_host_with(&resolver, "example.com:443").await?;
+///     println!("{addrs:?}");
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct StubResolver {
+    nameservers: Vec<SocketAddr>,
+    timeout: Duration,
+    attempts: usize,
+}
+
+impl StubResolver {
+    /// Create a resolver querying the given name servers.
+    pub fn new(nameservers: Vec<SocketAddr>) -> Self {
+        Self {
+            nameservers,
+            timeout: Duration::from_secs(5),
+            attempts: 2,
+        }
+    }
+
+    /// Create a resolver with the name servers in `/etc/resolv.conf`.
+    ///
+    /// The file is read synchronously, so the resolver is expected to be created once and
+    /// reused.
+    pub fn from_system() -> io::Result<Self> {
+        std::fs::read_to_string("/etc/resolv.conf").map(|conf| Self::from_resolv_conf(&conf))
+    }
+
+    /// Create a resolver with the name servers in the content of a `resolv.conf`.
+    ///
+    /// The local name server is used if there is no one.
+    pub fn from_resolv_conf(conf: &str) -> Self {
+        let mut nameservers: Vec<SocketAddr> = conf
+            .lines()
+            .filter_map(|line| {
+                let mut words = line.split_whitespace();
+                match words.next() {
+                    Some("nameserver") => words.next()?.parse::<IpAddr>().ok(),
+                    _ => None,
+                }
+            })
+            .map(|ip| SocketAddr::new(ip, DNS_PORT))
+            .collect();
+        if nameservers.is_empty() {
+            nameservers.push(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), DNS_PORT));
+        }
+        Self::new(nameservers)
+    }
+
+    /// Set the timeout of each query, 5 seconds by default.
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Set how many rounds the name servers are tried, 2 by default.
+    #[must_use]
+    pub fn attempts(mut self, attempts: usize) -> Self {
+        self.attempts = attempts.max(1);
+        self
+    }
+
+    /// Returns the name servers.
+    pub fn nameservers(&self) -> &[SocketAddr] {
+        &self.nameservers
+    }
+
+    async fn query(&self, host: &str, qtype: u16) -> io::Result<Vec<IpAddr>> {
+        let id = crate::utils::thread_rng_n(u16::MAX as u32 + 1) as u16;
+        let msg = encode_query(id, host, qtype)?;
+        let mut last_err = io::Error::other("no name server");
+        for _ in 0..self.attempts {
+            for &ns in &self.nameservers {
+                let res = match crate::time::timeout(self.timeout, query_udp(ns, &msg, qtype)).await
+                {
+                    Ok(Ok(None)) => crate::time::timeout(self.timeout, query_tcp(ns, &msg, qtype))
+                        .await
+                        .map_err(io::Error::from)
+                        .and_then(|res| res),
+                    Ok(Ok(Some(addrs))) => Ok(addrs),
+                    Ok(Err(e)) => Err(e),
+                    Err(elapsed) => Err(elapsed.into()),
+                };
+                match res {
+                    Ok(addrs) => return Ok(addrs),
+                    Err(e) => last_err = e,
+                }
+            }
+        }
+        Err(last_err)
+    }
+}
+
+impl Resolve for StubResolver {
+    async fn resolve(&self, host: &str, port: u16) -> io::Result<Vec<SocketAddr>> {
+        if let Ok(ip) = host.parse::<IpAddr>() {
+            return Ok(vec![SocketAddr::new(ip, port)]);
+        }
+        if host.eq_ignore_ascii_case("localhost") {
+            return Ok(vec![
+                SocketAddr::new(Ipv4Addr::LOCALHOST.into(), port),
+                SocketAddr::new(Ipv6Addr::LOCALHOST.into(), port),
+            ]);
+        }
+
+        let (v4, v6) = crate::join!(self.query(host, TYPE_A), self.query(host, TYPE_AAAA));
+        let addrs: Vec<_> = match (v4, v6) {
+            (Err(e), Err(_)) => return Err(e),
+            (v4, v6) => v4
+                .unwrap_or_default()
+                .into_iter()
+                .chain(v6.unwrap_or_default())
+                .map(|ip| SocketAddr::new(ip, port))
+                .collect(),
+        };
+        if addrs.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no address found for {host}"),
+            ));
+        }
+        Ok(addrs)
+    }
+}
+
+/// Send the query over UDP. Returns None if the response is truncated.
+async fn query_udp(ns: SocketAddr, msg: &[u8], qtype: u16) -> io::Result<Option<Vec<IpAddr>>> {
+    let local: SocketAddr = match ns {
+        SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+        SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+    };
+    let socket = UdpSocket::bind(local)?;
+    socket.connect(ns).await?;
+    let (res, _) = socket.send(msg.to_vec()).await;
+    res?;
+
+    let mut buf = vec![0; UDP_MSG_SIZE];
+    loop {
+        let (res, b) = socket.recv(buf).await;
+        buf = b;
+        let n = res?;
+        // Ignore the stale responses.
+        if n >= 2 && buf[..2] == msg[..2] {
+            return parse_response(&buf[..n], qtype);
+        }
+    }
+}
+
+async fn query_tcp(ns: SocketAddr, msg: &[u8], qtype: u16) -> io::Result<Vec<IpAddr>> {
+    let mut stream = TcpStream::connect_addr(ns).await?;
+    let mut out = Vec::with_capacity(2 + msg.len());
+    out.extend_from_slice(&(msg.len() as u16).to_be_bytes());
+    out.extend_from_slice(msg);
+    let (res, _) = stream.write_all(out).await;
+    res?;
+
+    let (res, len) = stream.read_exact(Vec::with_capacity(2)).await;
+    res?;
+    let len = u16::from_be_bytes([len[0], len[1]]) as usize;
+    let (res, buf) = stream.read_exact(Vec::with_capacity(len)).await;
+    res?;
+    if buf.len() < 2 || buf[..2] != msg[..2] {
+        return Err(malformed());
+    }
+    parse_response(&buf, qtype)?.ok_or_else(malformed)
+}
+
+fn malformed() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "malformed DNS response")
+}
+
+fn encode_query(id: u16, host: &str, qtype: u16) -> io::Result<Vec<u8>> {
+    let host = host.strip_suffix('.').unwrap_or(host);
+    if host.is_empty() || host.len() > 253 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "invalid host name",
+        ));
+    }
+
+    let mut msg = Vec::with_capacity(18 + host.len());
+    msg.extend_from_slice(&id.to_be_bytes());
+    msg.extend_from_slice(&FLAG_RECURSION_DESIRED.to_be_bytes());
+    // 1 question, no answer, authority or additional records.
+    msg.extend_from_slice(&[0, 1, 0, 0, 0, 0, 0, 0]);
+    for label in host.split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid host name",
+            ));
+        }
+        msg.push(label.len() as u8);
+        msg.extend_from_slice(label.as_bytes());
+    }
+    msg.push(0);
+    msg.extend_from_slice(&qtype.to_be_bytes());
+    msg.extend_from_slice(&CLASS_IN.to_be_bytes());
+    Ok(msg)
+}
+
+/// Parse the addresses in the answers. Returns None if the response is truncated.
+fn parse_response(msg: &[u8], qtype: u16) -> io::Result<Option<Vec<IpAddr>>> {
+    let read_u16 = |pos: usize| -> io::Result<u16> {
+        msg.get(pos..pos + 2)
+            .map(|b| u16::from_be_bytes([b[0], b[1]]))
+            .ok_or_else(malformed)
+    };
+
+    let flags = read_u16(2)?;
+    if flags & FLAG_RESPONSE == 0 {
+        return Err(malformed());
+    }
+    if flags & FLAG_TRUNCATED != 0 {
+        return Ok(None);
+    }
+    match flags & 0xf {
+        0 => (),
+        RCODE_NXDOMAIN => return Ok(Some(Vec::new())),
+        rcode => {
+            return Err(io::Error::other(format!(
+                "name server returned error code {rcode}"
+            )))
+        }
+    }
+
+    let questions = read_u16(4)?;
+    let answers = read_u16(6)?;
+    let mut pos = 12;
+    for _ in 0..questions {
+        pos = skip_name(msg, pos)? + 4;
+    }
+    let mut addrs = Vec::new();
+    for _ in 0..answers {
+        pos = skip_name(msg, pos)?;
+        let rtype = read_u16(pos)?;
+        let class = read_u16(pos + 2)?;
+        let len = read_u16(pos + 8)? as usize;
+        pos += 10;
+        let data = msg.get(pos..pos + len).ok_or_else(malformed)?;
+        pos += len;
+        if rtype != qtype || class != CLASS_IN {
+            // e.g. CNAME
+            continue;
+        }
+        if let Ok(octets) = <[u8; 4]>::try_from(data) {
+            addrs.push(IpAddr::from(octets));
+        } else if let Ok(octets) = <[u8; 16]>::try_from(data) {
+            addrs.push(IpAddr::from(octets));
+        }
+    }
+    Ok(Some(addrs))
+}
+
+fn skip_name(msg: &[u8], mut pos: usize) -> io::Result<usize> {
+    loop {
+        let len = *msg.get(pos).ok_or_else(malformed)? as usize;
+        match len {
+            0 => return Ok(pos + 1),
+            // A pointer to the name elsewhere.
+            l if l & 0xc0 == 0xc0 => return Ok(pos + 2),
+            l => pos += 1 + l,
+        }
+    }
+}
@@ -0,0 +1,203 @@
+//! The ancillary data of SCTP messages, which is what `sctp_sendv`/`sctp_recvv` of
+//! libsctp pass to `sendmsg`/`recvmsg`.
+
+use std::{io, os::fd::RawFd};
+
+use crate::net::cmsg::{self, CmsgBuf};
+
+// Not in libc yet, see linux/sctp.h.
+pub(crate) const SOL_SCTP: libc::c_int = 132;
+pub(crate) const SCTP_NODELAY: libc::c_int = 3;
+const SCTP_RECVRCVINFO: libc::c_int = 32;
+const SCTP_SNDINFO: libc::c_int = 2;
+const SCTP_RCVINFO: libc::c_int = 3;
+const SCTP_UNORDERED: u16 = 1;
+const MSG_NOTIFICATION: libc::c_int = 0x8000;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+#[allow(non_camel_case_types, unused)]
+struct sctp_sndinfo {
+    snd_sid: u16,
+    snd_flags: u16,
+    snd_ppid: u32,
+    snd_context: u32,
+    snd_assoc_id: i32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+#[allow(non_camel_case_types, unused)]
+struct sctp_rcvinfo {
+    rcv_sid: u16,
+    rcv_ssn: u16,
+    rcv_flags: u16,
+    rcv_ppid: u32,
+    rcv_tsn: u32,
+    rcv_cumtsn: u32,
+    rcv_context: u32,
+    rcv_assoc_id: i32,
+}
+
+/// The buffer size for the ancillary data of a received message.
+pub(crate) const RECV_CONTROL_LEN: usize = 64;
+
+/// How to send an SCTP message.
+///
+/// By default the message is sent ordered on stream 0 with no payload protocol identifier.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SendInfo {
+    /// The stream to send the message on, which must be less than the number of outbound
+    /// streams negotiated for the association.
+    pub stream: u16,
+    /// The payload protocol identifier, which is passed to the peer as is.
+    pub ppid: u32,
+    /// Deliver the message as soon as it arrives, regardless of the order in the stream.
+    pub unordered: bool,
+    /// An opaque value reported back if the message fails to be sent.
+    pub context: u32,
+}
+
+impl SendInfo {
+    /// Create a `SendInfo` for stream 0.
+    #[must_use]
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the stream.
+    #[must_use]
+    #[inline]
+    pub fn stream(mut self, stream: u16) -> Self {
+        self.stream = stream;
+        self
+    }
+
+    /// Set the payload protocol identifier.
+    #[must_use]
+    #[inline]
+    pub fn ppid(mut self, ppid: u32) -> Self {
+        self.ppid = ppid;
+        self
+    }
+
+    /// Set whether the message is unordered.
+    #[must_use]
+    #[inline]
+    pub fn unordered(mut self, unordered: bool) -> Self {
+        self.unordered = unordered;
+        self
+    }
+
+    /// Set the context.
+    #[must_use]
+    #[inline]
+    pub fn context(mut self, context: u32) -> Self {
+        self.context = context;
+        self
+    }
+}
+
+/// The information of a received SCTP message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecvInfo {
+    stream: u16,
+    ssn: u16,
+    ppid: u32,
+    tsn: u32,
+    unordered: bool,
+    complete: bool,
+    notification: bool,
+}
+
+impl RecvInfo {
+    /// Returns the stream the message was received on.
+    #[inline]
+    pub fn stream(&self) -> u16 {
+        self.stream
+    }
+
+    /// Returns the stream sequence number of the message.
+    #[inline]
+    pub fn ssn(&self) -> u16 {
+        self.ssn
+    }
+
+    /// Returns the payload protocol identifier set by the sender.
+    #[inline]
+    pub fn ppid(&self) -> u32 {
+        self.ppid
+    }
+
+    /// Returns the transmission sequence number of the message.
+    #[inline]
+    pub fn tsn(&self) -> u32 {
+        self.tsn
+    }
+
+    /// Returns true if the message was sent unordered.
+    #[inline]
+    pub fn is_unordered(&self) -> bool {
+        self.unordered
+    }
+
+    /// Returns true if the end of the message is received. A message larger than the
+    /// buffer is received in parts, and only the last part is complete.
+    #[inline]
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    /// Returns true if the data is an event notification instead of a message.
+    #[inline]
+    pub fn is_notification(&self) -> bool {
+        self.notification
+    }
+}
+
+/// Report `SCTP_RCVINFO` with each received message.
+pub(crate) fn enable_rcvinfo(fd: RawFd) -> io::Result<()> {
+    cmsg::set_int_opt(fd, SOL_SCTP, SCTP_RECVRCVINFO, 1)
+}
+
+/// Encode the `SCTP_SNDINFO` ancillary data.
+pub(crate) fn encode(info: &SendInfo) -> CmsgBuf {
+    let sndinfo = sctp_sndinfo {
+        snd_sid: info.stream,
+        snd_flags: if info.unordered { SCTP_UNORDERED } else { 0 },
+        // It is in the network byte order on the wire, but passed as is.
+        snd_ppid: info.ppid,
+        snd_context: info.context,
+        snd_assoc_id: 0,
+    };
+    CmsgBuf::encode(&[(SOL_SCTP, SCTP_SNDINFO, &cmsg::as_bytes(&sndinfo))])
+}
+
+/// Decode the ancillary data and the flags of a received message.
+pub(crate) fn decode(control: &CmsgBuf, flags: libc::c_int) -> RecvInfo {
+    let mut info = RecvInfo {
+        stream: 0,
+        ssn: 0,
+        ppid: 0,
+        tsn: 0,
+        unordered: false,
+        complete: flags & libc::MSG_EOR != 0,
+        notification: flags & MSG_NOTIFICATION != 0,
+    };
+    for (level, ty, data) in control.iter() {
+        if (level, ty) != (SOL_SCTP, SCTP_RCVINFO) {
+            continue;
+        }
+        // Safety: the item is a sctp_rcvinfo.
+        if let Some(rcvinfo) = unsafe { cmsg::read::<sctp_rcvinfo>(data) } {
+            info.stream = rcvinfo.rcv_sid;
+            info.ssn = rcvinfo.rcv_ssn;
+            info.ppid = rcvinfo.rcv_ppid;
+            info.tsn = rcvinfo.rcv_tsn;
+            info.unordered = rcvinfo.rcv_flags & SCTP_UNORDERED != 0;
+        }
+    }
+    info
+}
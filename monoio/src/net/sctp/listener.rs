@@ -0,0 +1,154 @@
+use std::{
+    io,
+    net::SocketAddr,
+    os::{
+        fd::BorrowedFd,
+        unix::prelude::{AsRawFd, IntoRawFd, RawFd},
+    },
+};
+
+use super::SctpStream;
+use crate::{
+    driver::{
+        op::{accept::Accept, Op},
+        shared_fd::SharedFd,
+    },
+    io::{stream::Stream, CancelHandle},
+    net::{new_socket_with_protocol, ListenerOpts},
+};
+
+/// An SCTP listener of the one-to-one style, accepting an [`SctpStream`] for each
+/// association.
+pub struct SctpListener {
+    fd: SharedFd,
+}
+
+impl SctpListener {
+    /// Creates a new `SctpListener` bound to the specified address with custom config.
+    ///
+    /// The `reuse_addr`, `ipv6_only`, buffer sizes and backlog of the config are used.
+    pub fn bind_with_config(addr: SocketAddr, config: &ListenerOpts) -> io::Result<SctpListener> {
+        let domain = match addr {
+            SocketAddr::V4(_) => libc::AF_INET,
+            SocketAddr::V6(_) => libc::AF_INET6,
+        };
+        let socket = new_socket_with_protocol(domain, libc::SOCK_STREAM, libc::IPPROTO_SCTP)?;
+        let fd = SharedFd::new::<false>(socket)?;
+        let raw = unsafe { BorrowedFd::borrow_raw(socket) };
+        let sys_listener = socket2::SockRef::from(&raw);
+
+        if let (true, Some(ipv6_only)) = (addr.is_ipv6(), config.ipv6_only) {
+            sys_listener.set_only_v6(ipv6_only)?;
+        }
+        if config.reuse_addr {
+            sys_listener.set_reuse_address(true)?;
+        }
+        if let Some(send_buf_size) = config.send_buf_size {
+            sys_listener.set_send_buffer_size(send_buf_size)?;
+        }
+        if let Some(recv_buf_size) = config.recv_buf_size {
+            sys_listener.set_recv_buffer_size(recv_buf_size)?;
+        }
+        sys_listener.bind(&addr.into())?;
+        sys_listener.listen(config.backlog)?;
+        Ok(Self { fd })
+    }
+
+    /// Creates a new `SctpListener` bound to the specified address with default config.
+    pub fn bind(addr: SocketAddr) -> io::Result<SctpListener> {
+        Self::bind_with_config(addr, &ListenerOpts::default())
+    }
+
+    /// Accept
+    pub async fn accept(&self) -> io::Result<(SctpStream, SocketAddr)> {
+        let op = Op::accept(&self.fd)?;
+
+        // Await the completion of the event
+        let completion = op.await;
+        Self::accepted(completion.meta.result?.into_inner(), completion.data)
+    }
+
+    /// Cancelable accept
+    pub async fn cancelable_accept(&self, c: CancelHandle) -> io::Result<(SctpStream, SocketAddr)> {
+        use crate::io::operation_canceled;
+
+        if c.canceled() {
+            return Err(operation_canceled());
+        }
+        let op = Op::accept(&self.fd)?;
+        let _guard = c.associate_op(op.op_canceller());
+
+        // Await the completion of the event
+        let completion = op.await;
+        Self::accepted(completion.meta.result?.into_inner(), completion.data)
+    }
+
+    fn accepted(fd: u32, data: Accept) -> io::Result<(SctpStream, SocketAddr)> {
+        // Construct stream
+        let stream = SctpStream::from_shared_fd(SharedFd::new::<false>(fd as _)?)?;
+
+        // Construct SocketAddr
+        let addr = unsafe { socket2::SockAddr::new(data.addr.0.assume_init(), data.addr.1) };
+        let addr = addr
+            .as_socket()
+            .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidInput))?;
+        Ok((stream, addr))
+    }
+
+    /// Returns the local address that this listener is bound to.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        let raw = unsafe { BorrowedFd::borrow_raw(self.fd.raw_fd()) };
+        socket2::SockRef::from(&raw)
+            .local_addr()?
+            .as_socket()
+            .ok_or_else(|| io::ErrorKind::InvalidInput.into())
+    }
+
+    /// Wait for read readiness.
+    /// Note: Do not use it before every io. It is different from other runtimes!
+    ///
+    /// Everytime call to this method may pay a syscall cost.
+    /// In uring impl, it will push a PollAdd op; in epoll impl, it will use use
+    /// inner readiness state; if !relaxed, it will call syscall poll after that.
+    ///
+    /// If relaxed, on legacy driver it may return false positive result.
+    /// If you want to do io by your own, you must maintain io readiness and wait
+    /// for io ready with relaxed=false.
+    pub async fn readable(&self, relaxed: bool) -> io::Result<()> {
+        let op = Op::poll_read(&self.fd, relaxed).unwrap();
+        op.wait().await
+    }
+}
+
+impl Stream for SctpListener {
+    type Item = io::Result<(SctpStream, SocketAddr)>;
+
+    #[inline]
+    async fn next(&mut self) -> Option<Self::Item> {
+        Some(self.accept().await)
+    }
+}
+
+impl std::fmt::Debug for SctpListener {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SctpListener")
+            .field("fd", &self.fd)
+            .finish()
+    }
+}
+
+impl IntoRawFd for SctpListener {
+    #[inline]
+    fn into_raw_fd(self) -> RawFd {
+        self.fd
+            .try_unwrap()
+            .expect("unexpected multiple reference to rawfd")
+    }
+}
+
+impl AsRawFd for SctpListener {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.raw_fd()
+    }
+}
@@ -0,0 +1,9 @@
+//! SCTP sockets of the one-to-one style(Linux only).
+
+mod info;
+mod listener;
+mod stream;
+
+pub use info::{RecvInfo, SendInfo};
+pub use listener::SctpListener;
+pub use stream::SctpStream;
@@ -0,0 +1,319 @@
+use std::{
+    future::Future,
+    io,
+    net::SocketAddr,
+    os::{
+        fd::BorrowedFd,
+        unix::prelude::{AsRawFd, FromRawFd, IntoRawFd, RawFd},
+    },
+};
+
+use super::info::{self, RecvInfo, SendInfo};
+use crate::{
+    buf::{IoBuf, IoBufMut, IoVecBuf, IoVecBufMut},
+    driver::{op::Op, shared_fd::SharedFd},
+    io::{
+        as_fd::{AsReadFd, AsWriteFd, SharedFdWrapper},
+        operation_canceled, AsyncReadRent, AsyncWriteRent, CancelHandle, CancelableAsyncReadRent,
+        CancelableAsyncWriteRent, Split,
+    },
+    net::{cmsg::CmsgBuf, new_socket_with_protocol},
+    BufResult,
+};
+
+/// An SCTP association of the one-to-one style, which is like a TCP connection carrying
+/// messages on multiple streams.
+///
+/// The [`AsyncReadRent`] and [`AsyncWriteRent`] implementations receive from any stream and
+/// send on stream 0. Use [`send_with_info`](Self::send_with_info) and
+/// [`recv_with_info`](Self::recv_with_info) to choose the stream and see the message
+/// boundaries.
+pub struct SctpStream {
+    fd: SharedFd,
+}
+
+/// SctpStream is safe to split to two parts
+unsafe impl Split for SctpStream {}
+
+impl SctpStream {
+    pub(crate) fn from_shared_fd(fd: SharedFd) -> io::Result<Self> {
+        info::enable_rcvinfo(fd.raw_fd())?;
+        Ok(Self { fd })
+    }
+
+    /// Opens an SCTP association to a remote host.
+    pub async fn connect(addr: SocketAddr) -> io::Result<Self> {
+        let domain = match addr {
+            SocketAddr::V4(_) => libc::AF_INET,
+            SocketAddr::V6(_) => libc::AF_INET6,
+        };
+        let socket = new_socket_with_protocol(domain, libc::SOCK_STREAM, libc::IPPROTO_SCTP)?;
+        let fd = SharedFd::new::<false>(socket)?;
+        info::enable_rcvinfo(socket)?;
+        let completion = Op::connect(fd, addr, false)?.await;
+        completion.meta.result?;
+
+        let stream = Self {
+            fd: completion.data.fd,
+        };
+        // wait write ready on epoll branch(legacy driver or uring driver in hybrid mode)
+        if stream.fd.registered_index().is_some() {
+            stream.writable(true).await?;
+        }
+        // getsockopt
+        let sys_socket = unsafe { socket2::Socket::from_raw_fd(stream.fd.raw_fd()) };
+        let err = sys_socket.take_error();
+        let _ = sys_socket.into_raw_fd();
+        if let Some(e) = err? {
+            return Err(e);
+        }
+        Ok(stream)
+    }
+
+    /// Sends a message on the stream and with the payload protocol identifier of `info`.
+    /// The data is sent as one message, or fails with `EMSGSIZE` if it is too large.
+    pub async fn send_with_info<T: IoBuf>(&self, buf: T, info: &SendInfo) -> BufResult<usize, T> {
+        let op = Op::send_msg_with_control(self.fd.clone(), buf, None, info::encode(info)).unwrap();
+        op.wait().await
+    }
+
+    /// Receives a message, or a part of it if the buffer is too small, with the stream and
+    /// the payload protocol identifier it was sent with.
+    pub async fn recv_with_info<T: IoBufMut>(&self, buf: T) -> BufResult<(usize, RecvInfo), T> {
+        let control = CmsgBuf::new(info::RECV_CONTROL_LEN);
+        let op = Op::recv_msg_with_control(self.fd.clone(), buf, control).unwrap();
+        let (res, buf) = op.wait_with_control().await;
+        let res = res.map(|(n, _, control, flags)| (n, info::decode(&control, flags)));
+        (res, buf)
+    }
+
+    /// Returns the local address that this stream is bound to.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.with_socket(|s| s.local_addr())?
+            .as_socket()
+            .ok_or_else(|| io::ErrorKind::InvalidInput.into())
+    }
+
+    /// Returns the primary address of the peer.
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.with_socket(|s| s.peer_addr())?
+            .as_socket()
+            .ok_or_else(|| io::ErrorKind::InvalidInput.into())
+    }
+
+    /// Sets the value of the `SCTP_NODELAY` option, which disables the Nagle-like
+    /// algorithm bundling the small messages.
+    pub fn set_nodelay(&self, nodelay: bool) -> io::Result<()> {
+        crate::net::cmsg::set_int_opt(
+            self.fd.raw_fd(),
+            info::SOL_SCTP,
+            info::SCTP_NODELAY,
+            nodelay as libc::c_int,
+        )
+    }
+
+    #[inline]
+    fn with_socket<R>(&self, f: impl FnOnce(&socket2::SockRef<'_>) -> R) -> R {
+        let fd = unsafe { BorrowedFd::borrow_raw(self.fd.raw_fd()) };
+        f(&socket2::SockRef::from(&fd))
+    }
+
+    /// Wait for read readiness.
+    /// Note: Do not use it before every io. It is different from other runtimes!
+    ///
+    /// Everytime call to this method may pay a syscall cost.
+    /// In uring impl, it will push a PollAdd op; in epoll impl, it will use use
+    /// inner readiness state; if !relaxed, it will call syscall poll after that.
+    ///
+    /// If relaxed, on legacy driver it may return false positive result.
+    /// If you want to do io by your own, you must maintain io readiness and wait
+    /// for io ready with relaxed=false.
+    pub async fn readable(&self, relaxed: bool) -> io::Result<()> {
+        let op = Op::poll_read(&self.fd, relaxed).unwrap();
+        op.wait().await
+    }
+
+    /// Wait for write readiness.
+    /// Note: Do not use it before every io. It is different from other runtimes!
+    ///
+    /// Everytime call to this method may pay a syscall cost.
+    /// In uring impl, it will push a PollAdd op; in epoll impl, it will use use
+    /// inner readiness state; if !relaxed, it will call syscall poll after that.
+    ///
+    /// If relaxed, on legacy driver it may return false positive result.
+    /// If you want to do io by your own, you must maintain io readiness and wait
+    /// for io ready with relaxed=false.
+    pub async fn writable(&self, relaxed: bool) -> io::Result<()> {
+        let op = Op::poll_write(&self.fd, relaxed).unwrap();
+        op.wait().await
+    }
+}
+
+impl AsReadFd for SctpStream {
+    #[inline]
+    fn as_reader_fd(&mut self) -> &SharedFdWrapper {
+        SharedFdWrapper::new(&self.fd)
+    }
+}
+
+impl AsWriteFd for SctpStream {
+    #[inline]
+    fn as_writer_fd(&mut self) -> &SharedFdWrapper {
+        SharedFdWrapper::new(&self.fd)
+    }
+}
+
+impl IntoRawFd for SctpStream {
+    #[inline]
+    fn into_raw_fd(self) -> RawFd {
+        self.fd
+            .try_unwrap()
+            .expect("unexpected multiple reference to rawfd")
+    }
+}
+
+impl AsRawFd for SctpStream {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.raw_fd()
+    }
+}
+
+impl std::fmt::Debug for SctpStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SctpStream").field("fd", &self.fd).finish()
+    }
+}
+
+impl AsyncWriteRent for SctpStream {
+    #[inline]
+    fn write<T: IoBuf>(&mut self, buf: T) -> impl Future<Output = BufResult<usize, T>> {
+        // Submit the write operation
+        let op = Op::send(self.fd.clone(), buf).unwrap();
+        op.result()
+    }
+
+    #[inline]
+    fn writev<T: IoVecBuf>(&mut self, buf_vec: T) -> impl Future<Output = BufResult<usize, T>> {
+        let op = Op::writev(self.fd.clone(), buf_vec).unwrap();
+        op.result()
+    }
+
+    #[inline]
+    async fn flush(&mut self) -> std::io::Result<()> {
+        // SCTP stream does not need flush.
+        Ok(())
+    }
+
+    fn shutdown(&mut self) -> impl Future<Output = std::io::Result<()>> {
+        let fd = self.as_raw_fd();
+        async move {
+            match unsafe { libc::shutdown(fd, libc::SHUT_WR) } {
+                -1 => Err(io::Error::last_os_error()),
+                _ => Ok(()),
+            }
+        }
+    }
+}
+
+impl CancelableAsyncWriteRent for SctpStream {
+    #[inline]
+    async fn cancelable_write<T: IoBuf>(
+        &mut self,
+        buf: T,
+        c: CancelHandle,
+    ) -> crate::BufResult<usize, T> {
+        let fd = self.fd.clone();
+
+        if c.canceled() {
+            return (Err(operation_canceled()), buf);
+        }
+
+        let op = Op::send(fd, buf).unwrap();
+        let _guard = c.associate_op(op.op_canceller());
+        op.result().await
+    }
+
+    #[inline]
+    async fn cancelable_writev<T: IoVecBuf>(
+        &mut self,
+        buf_vec: T,
+        c: CancelHandle,
+    ) -> crate::BufResult<usize, T> {
+        let fd = self.fd.clone();
+
+        if c.canceled() {
+            return (Err(operation_canceled()), buf_vec);
+        }
+
+        let op = Op::writev(fd.clone(), buf_vec).unwrap();
+        let _guard = c.associate_op(op.op_canceller());
+        op.result().await
+    }
+
+    #[inline]
+    async fn cancelable_flush(&mut self, _c: CancelHandle) -> io::Result<()> {
+        // SCTP stream does not need flush.
+        Ok(())
+    }
+
+    async fn cancelable_shutdown(&mut self, _c: CancelHandle) -> io::Result<()> {
+        let fd = self.as_raw_fd();
+        match unsafe { libc::shutdown(fd, libc::SHUT_WR) } {
+            -1 => Err(io::Error::last_os_error()),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl AsyncReadRent for SctpStream {
+    #[inline]
+    fn read<T: IoBufMut>(&mut self, buf: T) -> impl Future<Output = BufResult<usize, T>> {
+        // Submit the read operation
+        let op = Op::recv(self.fd.clone(), buf).unwrap();
+        op.result()
+    }
+
+    #[inline]
+    fn readv<T: IoVecBufMut>(&mut self, buf: T) -> impl Future<Output = BufResult<usize, T>> {
+        // Submit the read operation
+        let op = Op::readv(self.fd.clone(), buf).unwrap();
+        op.result()
+    }
+}
+
+impl CancelableAsyncReadRent for SctpStream {
+    #[inline]
+    async fn cancelable_read<T: IoBufMut>(
+        &mut self,
+        buf: T,
+        c: CancelHandle,
+    ) -> crate::BufResult<usize, T> {
+        let fd = self.fd.clone();
+
+        if c.canceled() {
+            return (Err(operation_canceled()), buf);
+        }
+
+        let op = Op::recv(fd, buf).unwrap();
+        let _guard = c.associate_op(op.op_canceller());
+        op.result().await
+    }
+
+    #[inline]
+    async fn cancelable_readv<T: IoVecBufMut>(
+        &mut self,
+        buf: T,
+        c: CancelHandle,
+    ) -> crate::BufResult<usize, T> {
+        let fd = self.fd.clone();
+
+        if c.canceled() {
+            return (Err(operation_canceled()), buf);
+        }
+
+        let op = Op::readv(fd, buf).unwrap();
+        let _guard = c.associate_op(op.op_canceller());
+        op.result().await
+    }
+}
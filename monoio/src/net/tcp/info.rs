@@ -0,0 +1,115 @@
+use std::{fmt, io, mem, os::fd::RawFd, time::Duration};
+
+/// A snapshot of the kernel statistics of a TCP connection(`TCP_INFO`).
+///
+/// Returned by [`TcpStream::tcp_info`](super::TcpStream::tcp_info).
+#[derive(Clone, Copy)]
+pub struct TcpInfo {
+    raw: libc::tcp_info,
+}
+
+impl TcpInfo {
+    pub(crate) fn get(fd: RawFd) -> io::Result<Self> {
+        let mut raw: libc::tcp_info = unsafe { mem::zeroed() };
+        let mut len = mem::size_of::<libc::tcp_info>() as libc::socklen_t;
+        crate::syscall!(getsockopt@RAW(
+            fd,
+            libc::IPPROTO_TCP,
+            libc::TCP_INFO,
+            &mut raw as *mut _ as *mut libc::c_void,
+            &mut len
+        ))?;
+        Ok(Self { raw })
+    }
+
+    /// The smoothed round trip time.
+    #[inline]
+    pub fn rtt(&self) -> Duration {
+        Duration::from_micros(self.raw.tcpi_rtt as u64)
+    }
+
+    /// The variance of the round trip time.
+    #[inline]
+    pub fn rtt_var(&self) -> Duration {
+        Duration::from_micros(self.raw.tcpi_rttvar as u64)
+    }
+
+    /// The retransmission timeout.
+    #[inline]
+    pub fn rto(&self) -> Duration {
+        Duration::from_micros(self.raw.tcpi_rto as u64)
+    }
+
+    /// The number of retransmissions of the unacknowledged data since the last
+    /// acknowledgment.
+    #[inline]
+    pub fn retransmits(&self) -> u8 {
+        self.raw.tcpi_retransmits
+    }
+
+    /// The total number of retransmitted segments of the connection.
+    #[inline]
+    pub fn total_retrans(&self) -> u32 {
+        self.raw.tcpi_total_retrans
+    }
+
+    /// The number of segments considered lost.
+    #[inline]
+    pub fn lost(&self) -> u32 {
+        self.raw.tcpi_lost
+    }
+
+    /// The number of segments sent but not yet acknowledged.
+    #[inline]
+    pub fn unacked(&self) -> u32 {
+        self.raw.tcpi_unacked
+    }
+
+    /// The congestion window in segments.
+    #[inline]
+    pub fn snd_cwnd(&self) -> u32 {
+        self.raw.tcpi_snd_cwnd
+    }
+
+    /// The maximum segment size for sending.
+    #[inline]
+    pub fn snd_mss(&self) -> u32 {
+        self.raw.tcpi_snd_mss
+    }
+
+    /// The maximum segment size for receiving.
+    #[inline]
+    pub fn rcv_mss(&self) -> u32 {
+        self.raw.tcpi_rcv_mss
+    }
+
+    /// The path MTU.
+    #[inline]
+    pub fn pmtu(&self) -> u32 {
+        self.raw.tcpi_pmtu
+    }
+
+    /// The raw `tcp_info` returned by the kernel.
+    #[inline]
+    pub fn as_raw(&self) -> &libc::tcp_info {
+        &self.raw
+    }
+}
+
+impl fmt::Debug for TcpInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TcpInfo")
+            .field("rtt", &self.rtt())
+            .field("rtt_var", &self.rtt_var())
+            .field("rto", &self.rto())
+            .field("retransmits", &self.retransmits())
+            .field("total_retrans", &self.total_retrans())
+            .field("lost", &self.lost())
+            .field("unacked", &self.unacked())
+            .field("snd_cwnd", &self.snd_cwnd())
+            .field("snd_mss", &self.snd_mss())
+            .field("rcv_mss", &self.rcv_mss())
+            .field("pmtu", &self.pmtu())
+            .finish()
+    }
+}
@@ -19,7 +19,10 @@ use {
 
 use super::stream::TcpStream;
 use crate::{
-    driver::{op::Op, shared_fd::SharedFd},
+    driver::{
+        op::{accept, Op},
+        shared_fd::SharedFd,
+    },
     io::{stream::Stream, CancelHandle},
     net::ListenerOpts,
 };
@@ -50,7 +53,7 @@ impl TcpListener {
         let addr = addr
             .to_socket_addrs()?
             .next()
-            .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "empty address"))?;
+            .ok_or_else(|| io::Error::other("empty address"))?;
 
         let domain = if addr.is_ipv6() {
             socket2::Domain::IPV6
@@ -63,6 +66,9 @@ impl TcpListener {
         #[cfg(feature = "legacy")]
         Self::set_non_blocking(&sys_listener)?;
 
+        if let (true, Some(ipv6_only)) = (addr.is_ipv6(), opts.ipv6_only) {
+            sys_listener.set_only_v6(ipv6_only)?;
+        }
         let addr = socket2::SockAddr::from(addr);
         #[cfg(unix)]
         if opts.reuse_port {
@@ -79,11 +85,25 @@ impl TcpListener {
         }
         if opts.tcp_fast_open {
             #[cfg(any(target_os = "linux", target_os = "android"))]
-            super::tfo::set_tcp_fastopen(&sys_listener, opts.backlog)?;
+            super::tfo::set_tcp_fastopen(
+                &sys_listener,
+                opts.tcp_fast_open_queue.unwrap_or(opts.backlog),
+            )?;
             #[cfg(any(target_os = "ios", target_os = "macos"))]
             let _ = super::tfo::set_tcp_fastopen_force_enable(&sys_listener);
         }
         sys_listener.bind(&addr)?;
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        if let Some(timeout) = opts.defer_accept {
+            let secs = timeout.as_secs().min(libc::c_int::MAX as u64) as libc::c_int;
+            crate::syscall!(setsockopt@RAW(
+                sys_listener.as_raw_fd(),
+                libc::IPPROTO_TCP,
+                libc::TCP_DEFER_ACCEPT,
+                &secs as *const _ as *const libc::c_void,
+                std::mem::size_of::<libc::c_int>() as libc::socklen_t
+            ))?;
+        }
         sys_listener.listen(opts.backlog)?;
 
         #[cfg(any(target_os = "ios", target_os = "macos"))]
@@ -214,6 +234,32 @@ impl TcpListener {
         Ok((stream, addr))
     }
 
+    /// Accept connections with a single multishot operation.
+    ///
+    /// With io_uring driver(requires kernel 5.19+), one persistent submission keeps yielding
+    /// accepted connections, which saves the per-accept submission cost. With legacy driver it
+    /// works the same as calling [`accept`](Self::accept) repeatedly.
+    ///
+    /// Unlike `accept`, the peer address is not returned; use [`TcpStream::peer_addr`] if needed.
+    /// The returned stream ends after the kernel terminates the operation(for example on
+    /// error); call this method again to re-arm it. Dropping the stream cancels the operation.
+    pub fn accept_multi(&self) -> io::Result<AcceptMulti> {
+        Ok(AcceptMulti {
+            op: Op::accept_multi(&self.fd)?,
+        })
+    }
+
+    /// Install the listener into the fixed file table of io_uring and return the slot.
+    ///
+    /// After that, the io_uring operations on it refer to the slot instead of the fd. The table
+    /// must be registered with
+    /// [`RuntimeBuilder::with_fixed_files`](crate::RuntimeBuilder::with_fixed_files), and the
+    /// slot is released when the listener is closed. It fails with legacy driver.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    pub async fn register_fixed(&self) -> io::Result<u32> {
+        self.fd.register_fixed().await
+    }
+
     /// Returns the local address that this listener is bound to.
     pub fn local_addr(&self) -> io::Result<SocketAddr> {
         let meta = self.meta.get();
@@ -230,12 +276,15 @@ impl TcpListener {
     }
 
     #[cfg(feature = "legacy")]
-    fn set_non_blocking(_socket: &socket2::Socket) -> io::Result<()> {
+    pub(super) fn set_non_blocking(_socket: &socket2::Socket) -> io::Result<()> {
         crate::driver::CURRENT.with(|x| match x {
             // TODO: windows ioring support
             #[cfg(all(target_os = "linux", feature = "iouring"))]
             crate::driver::Inner::Uring(_) => Ok(()),
             crate::driver::Inner::Legacy(_) => _socket.set_nonblocking(true),
+            // The ops without overlapped version are executed with blocking syscalls.
+            #[cfg(all(windows, feature = "iocp"))]
+            crate::driver::Inner::Iocp(_) => Ok(()),
         })
     }
 
@@ -315,6 +364,32 @@ impl Drop for TcpListener {
     }
 }
 
+/// Stream of connections accepted by one multishot accept operation.
+///
+/// It is created by [`TcpListener::accept_multi`].
+pub struct AcceptMulti {
+    op: Op<accept::AcceptMulti>,
+}
+
+impl Stream for AcceptMulti {
+    type Item = io::Result<TcpStream>;
+
+    async fn next(&mut self) -> Option<Self::Item> {
+        let meta = std::future::poll_fn(|cx| self.op.poll_next_multishot(cx)).await?;
+        let fd = match meta.result {
+            Ok(fd) => fd,
+            Err(e) => return Some(Err(e)),
+        };
+        Some(SharedFd::new::<false>(fd.into_inner() as _).map(TcpStream::from_shared_fd))
+    }
+}
+
+impl std::fmt::Debug for AcceptMulti {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AcceptMulti").finish()
+    }
+}
+
 #[derive(Debug, Default, Clone)]
 struct ListenerMeta {
     local_addr: Option<SocketAddr>,
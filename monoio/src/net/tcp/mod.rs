@@ -1,12 +1,20 @@
 #![allow(unreachable_pub)]
 //! TCP related.
 
+#[cfg(target_os = "linux")]
+mod info;
 mod listener;
+mod socket;
 mod split;
 mod stream;
 mod tfo;
+#[cfg(all(target_os = "linux", feature = "zero-copy"))]
+mod zerocopy;
 
-pub use listener::TcpListener;
+#[cfg(target_os = "linux")]
+pub use info::TcpInfo;
+pub use listener::{AcceptMulti, TcpListener};
+pub use socket::TcpSocket;
 pub use split::{TcpOwnedReadHalf, TcpOwnedWriteHalf};
 pub use stream::{TcpConnectOpts, TcpStream};
 
@@ -0,0 +1,187 @@
+#[cfg(unix)]
+use std::os::unix::prelude::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+#[cfg(windows)]
+use std::os::windows::prelude::{AsRawSocket, FromRawSocket, IntoRawSocket, RawSocket};
+use std::{io, net::SocketAddr};
+
+use super::{listener::TcpListener, stream::TcpStream};
+use crate::driver::shared_fd::SharedFd;
+
+/// A TCP socket that has not yet been converted to a [`TcpStream`] or [`TcpListener`].
+///
+/// It is used to set the socket options which must be set before binding, connecting or
+/// listening, like `SO_REUSEPORT`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use monoio::net::TcpSocket;
+///
+/// #[monoio::main]
+/// async fn main() -> std::io::Result<()> {
+///     let socket = TcpSocket::new_v4()?;
+///     socket.set_reuseaddr(true)?;
+///     socket.bind("127.0.0.1:8080".parse().unwrap())?;
+///     let _listener = socket.listen(1024)?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct TcpSocket {
+    inner: socket2::Socket,
+}
+
+impl TcpSocket {
+    /// Create a new IPv4 TCP socket.
+    pub fn new_v4() -> io::Result<Self> {
+        Self::new(socket2::Domain::IPV4)
+    }
+
+    /// Create a new IPv6 TCP socket.
+    pub fn new_v6() -> io::Result<Self> {
+        Self::new(socket2::Domain::IPV6)
+    }
+
+    fn new(domain: socket2::Domain) -> io::Result<Self> {
+        let inner =
+            socket2::Socket::new(domain, socket2::Type::STREAM, Some(socket2::Protocol::TCP))?;
+        Ok(Self { inner })
+    }
+
+    /// Allow the socket to bind to an in-use address(`SO_REUSEADDR`).
+    pub fn set_reuseaddr(&self, reuseaddr: bool) -> io::Result<()> {
+        self.inner.set_reuse_address(reuseaddr)
+    }
+
+    /// Get the value of the `SO_REUSEADDR` option.
+    pub fn reuseaddr(&self) -> io::Result<bool> {
+        self.inner.reuse_address()
+    }
+
+    /// Allow the socket to bind to an in-use port(`SO_REUSEPORT`), so the connections can be
+    /// balanced between the listeners of multiple threads.
+    #[cfg(unix)]
+    pub fn set_reuseport(&self, reuseport: bool) -> io::Result<()> {
+        self.inner.set_reuse_port(reuseport)
+    }
+
+    /// Get the value of the `SO_REUSEPORT` option.
+    #[cfg(unix)]
+    pub fn reuseport(&self) -> io::Result<bool> {
+        self.inner.reuse_port()
+    }
+
+    /// Set the size of the send buffer(`SO_SNDBUF`).
+    pub fn set_send_buffer_size(&self, size: u32) -> io::Result<()> {
+        self.inner.set_send_buffer_size(size as usize)
+    }
+
+    /// Get the size of the send buffer.
+    pub fn send_buffer_size(&self) -> io::Result<u32> {
+        self.inner.send_buffer_size().map(|size| size as u32)
+    }
+
+    /// Set the size of the receive buffer(`SO_RCVBUF`).
+    pub fn set_recv_buffer_size(&self, size: u32) -> io::Result<()> {
+        self.inner.set_recv_buffer_size(size as usize)
+    }
+
+    /// Get the size of the receive buffer.
+    pub fn recv_buffer_size(&self) -> io::Result<u32> {
+        self.inner.recv_buffer_size().map(|size| size as u32)
+    }
+
+    /// Set the value of the `TCP_NODELAY` option.
+    pub fn set_nodelay(&self, nodelay: bool) -> io::Result<()> {
+        self.inner.set_nodelay(nodelay)
+    }
+
+    /// Bind the socket to the given address.
+    pub fn bind(&self, addr: SocketAddr) -> io::Result<()> {
+        self.inner.bind(&addr.into())
+    }
+
+    /// Get the local address of the socket.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.inner
+            .local_addr()?
+            .as_socket()
+            .ok_or_else(|| io::Error::other("not an inet address"))
+    }
+
+    /// Establish a TCP connection with the peer at `addr`, consuming the socket.
+    pub async fn connect(self, addr: SocketAddr) -> io::Result<TcpStream> {
+        #[cfg(feature = "legacy")]
+        TcpListener::set_non_blocking(&self.inner)?;
+        let fd = self.into_shared_fd()?;
+        TcpStream::connect_shared_fd(fd, addr, false).await
+    }
+
+    /// Listen for the connections, consuming the socket.
+    pub fn listen(self, backlog: i32) -> io::Result<TcpListener> {
+        #[cfg(feature = "legacy")]
+        TcpListener::set_non_blocking(&self.inner)?;
+        self.inner.listen(backlog)?;
+        Ok(TcpListener::from_shared_fd(self.into_shared_fd()?))
+    }
+
+    fn into_shared_fd(self) -> io::Result<SharedFd> {
+        #[cfg(unix)]
+        let fd = self.inner.into_raw_fd();
+        #[cfg(windows)]
+        let fd = self.inner.into_raw_socket();
+        SharedFd::new::<false>(fd)
+    }
+}
+
+#[cfg(unix)]
+impl AsRawFd for TcpSocket {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+#[cfg(unix)]
+impl FromRawFd for TcpSocket {
+    #[inline]
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        Self {
+            inner: socket2::Socket::from_raw_fd(fd),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl IntoRawFd for TcpSocket {
+    #[inline]
+    fn into_raw_fd(self) -> RawFd {
+        self.inner.into_raw_fd()
+    }
+}
+
+#[cfg(windows)]
+impl AsRawSocket for TcpSocket {
+    #[inline]
+    fn as_raw_socket(&self) -> RawSocket {
+        self.inner.as_raw_socket()
+    }
+}
+
+#[cfg(windows)]
+impl FromRawSocket for TcpSocket {
+    #[inline]
+    unsafe fn from_raw_socket(socket: RawSocket) -> Self {
+        Self {
+            inner: socket2::Socket::from_raw_socket(socket),
+        }
+    }
+}
+
+#[cfg(windows)]
+impl IntoRawSocket for TcpSocket {
+    #[inline]
+    fn into_raw_socket(self) -> RawSocket {
+        self.inner.into_raw_socket()
+    }
+}
@@ -3,6 +3,7 @@ use std::{
     future::Future,
     io,
     net::{SocketAddr, ToSocketAddrs},
+    task::Poll,
     time::Duration,
 };
 
@@ -20,13 +21,14 @@ use {
 };
 
 use crate::{
-    buf::{IoBuf, IoBufMut, IoVecBuf, IoVecBufMut},
+    buf::{BufGroup, FixedBuf, IoBuf, IoBufMut, IoVecBuf, IoVecBufMut},
     driver::{op::Op, shared_fd::SharedFd},
     io::{
         as_fd::{AsReadFd, AsWriteFd, SharedFdWrapper},
         operation_canceled, AsyncReadRent, AsyncWriteRent, CancelHandle, CancelableAsyncReadRent,
         CancelableAsyncWriteRent, Split,
     },
+    net::{resolver, RecvMulti},
     BufResult,
 };
 
@@ -36,8 +38,17 @@ use crate::{
 pub struct TcpConnectOpts {
     /// TCP fast open.
     pub tcp_fast_open: bool,
+    /// Local address to bind before connecting, or None to let the system choose.
+    pub local_addr: Option<SocketAddr>,
+    /// Timeout of establishing the connection, or None to wait until the system gives up.
+    pub timeout: Option<Duration>,
+    // Interface name padded with NUL.
+    bind_device: Option<[u8; DEVICE_NAME_CAP]>,
 }
 
+// IFNAMSIZ
+const DEVICE_NAME_CAP: usize = 16;
+
 impl Default for TcpConnectOpts {
     #[inline]
     fn default() -> Self {
@@ -51,6 +62,9 @@ impl TcpConnectOpts {
     pub const fn new() -> Self {
         Self {
             tcp_fast_open: false,
+            local_addr: None,
+            timeout: None,
+            bind_device: None,
         }
     }
 
@@ -65,6 +79,43 @@ impl TcpConnectOpts {
         self.tcp_fast_open = fast_open;
         self
     }
+
+    /// Bind the socket to the local address before connecting.
+    #[must_use]
+    #[inline]
+    pub fn local_addr(mut self, addr: SocketAddr) -> Self {
+        self.local_addr = Some(addr);
+        self
+    }
+
+    /// Bind the socket to the network interface(SO_BINDTODEVICE) before connecting.
+    /// Note: This option only works for linux and android, and may require
+    /// `CAP_NET_RAW`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the name is longer than 15 bytes.
+    #[must_use]
+    #[inline]
+    pub fn bind_device(mut self, interface: &str) -> Self {
+        assert!(
+            interface.len() < DEVICE_NAME_CAP,
+            "interface name is too long"
+        );
+        let mut name = [0; DEVICE_NAME_CAP];
+        name[..interface.len()].copy_from_slice(interface.as_bytes());
+        self.bind_device = Some(name);
+        self
+    }
+
+    /// Fail with `TimedOut` if the connection is not established within `timeout`.
+    /// Note: The timer must be enabled on the runtime.
+    #[must_use]
+    #[inline]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
 }
 /// TcpStream
 pub struct TcpStream {
@@ -81,32 +132,128 @@ impl TcpStream {
         let meta = StreamMeta::new(fd.raw_fd());
         #[cfg(windows)]
         let meta = StreamMeta::new(fd.raw_socket());
-        #[cfg(feature = "zero-copy")]
-        // enable SOCK_ZEROCOPY
-        meta.set_zero_copy();
 
         Self { fd, meta }
     }
 
     /// Open a TCP connection to a remote host.
     /// Note: This function may block the current thread while resolution is
-    /// performed.
-    // TODO(chihai): Fix it, maybe spawn_blocking like tokio.
+    /// performed, use [`connect_with_resolver`](Self::connect_with_resolver) to resolve the
+    /// host name asynchronously.
     pub async fn connect<A: ToSocketAddrs>(addr: A) -> io::Result<Self> {
         // TODO(chihai): loop for all addrs
         let addr = addr
             .to_socket_addrs()?
             .next()
-            .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "empty address"))?;
+            .ok_or_else(|| io::Error::other("empty address"))?;
 
         Self::connect_addr(addr).await
     }
 
+    /// Open a TCP connection to a remote host with given options.
+    /// Note: This function may block the current thread while resolution is
+    /// performed.
+    pub async fn connect_with<A: ToSocketAddrs>(addr: A, opts: TcpConnectOpts) -> io::Result<Self> {
+        let addr = addr
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| io::Error::other("empty address"))?;
+
+        Self::connect_addr_with_config(addr, &opts).await
+    }
+
+    /// Open a TCP connection to a remote host, whose name is resolved with the `resolver`
+    /// without blocking the current thread.
+    ///
+    /// ```no_run
+    /// use monoio::net::{resolver::GaiResolver, TcpStream};
+    ///
+    /// #[monoio::main]
+    /// async fn main() -> std::io::Result<()> {
+    ///     let stream = TcpStream::connect_with_resolver(&GaiResolver, "example.com:80").await?;
+    ///     println!("{:?}", stream.peer_addr()?);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn connect_with_resolver<R: resolver::Resolve, A: resolver::ToSocketAddrs>(
+        resolver: &R,
+        addr: A,
+    ) -> io::Result<Self> {
+        let addr = resolver::lookup_host_with(resolver, addr)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| io::Error::other("empty address"))?;
+
+        Self::connect_addr(addr).await
+    }
+
+    /// Open a TCP connection to a dual-stack host with Happy Eyeballs(RFC 8305).
+    ///
+    /// The resolved addresses are tried alternating between IPv6 and IPv4, starting with
+    /// IPv6. A new attempt is started every 250ms or as soon as the previous one fails, and
+    /// the first established connection wins while the others are canceled. So a broken
+    /// IPv6 path costs a short delay instead of a connect timeout.
+    ///
+    /// Note: The timer must be enabled on the runtime, and this function may block the
+    /// current thread while resolution is performed. The addresses resolved with
+    /// [`resolver::lookup_host`](crate::net::resolver::lookup_host) can be passed as a
+    /// `&[SocketAddr]` to avoid it.
+    pub async fn connect_happy<A: ToSocketAddrs>(addrs: A) -> io::Result<Self> {
+        const ATTEMPT_DELAY: Duration = Duration::from_millis(250);
+
+        let mut addrs = interleave_families(addrs.to_socket_addrs()?.collect()).into_iter();
+        let mut attempts = Vec::new();
+        let mut last_err = None;
+        let mut delay = Box::pin(crate::time::sleep(ATTEMPT_DELAY));
+        let mut start_next = true;
+
+        std::future::poll_fn(|cx| loop {
+            if start_next {
+                start_next = false;
+                if let Some(addr) = addrs.next() {
+                    attempts.push(Box::pin(Self::connect_addr(addr)));
+                    delay
+                        .as_mut()
+                        .reset(crate::time::Instant::now() + ATTEMPT_DELAY);
+                }
+            }
+
+            let mut i = 0;
+            while i < attempts.len() {
+                match attempts[i].as_mut().poll(cx) {
+                    Poll::Ready(Ok(stream)) => return Poll::Ready(Ok(stream)),
+                    Poll::Ready(Err(e)) => {
+                        drop(attempts.swap_remove(i));
+                        last_err = Some(e);
+                        start_next = true;
+                    }
+                    Poll::Pending => i += 1,
+                }
+            }
+            if !start_next && addrs.len() != 0 && delay.as_mut().poll(cx).is_ready() {
+                start_next = true;
+            }
+            if start_next && addrs.len() != 0 {
+                continue;
+            }
+            if attempts.is_empty() {
+                return Poll::Ready(Err(last_err.take().unwrap_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "could not resolve to any address",
+                    )
+                })));
+            }
+            start_next = false;
+            return Poll::Pending;
+        })
+        .await
+    }
+
     /// Establish a connection to the specified `addr`.
     pub async fn connect_addr(addr: SocketAddr) -> io::Result<Self> {
-        const DEFAULT_OPTS: TcpConnectOpts = TcpConnectOpts {
-            tcp_fast_open: false,
-        };
+        const DEFAULT_OPTS: TcpConnectOpts = TcpConnectOpts::new();
         Self::connect_addr_with_config(addr, &DEFAULT_OPTS).await
     }
 
@@ -120,6 +267,22 @@ impl TcpStream {
             SocketAddr::V6(_) => AF_INET6,
         };
         let socket = crate::net::new_socket(domain, SOCK_STREAM)?;
+        let fd = SharedFd::new::<false>(socket)?;
+        if opts.local_addr.is_some() || opts.bind_device.is_some() {
+            #[cfg(unix)]
+            let raw = unsafe { std::os::fd::BorrowedFd::borrow_raw(fd.raw_fd()) };
+            #[cfg(windows)]
+            let raw = unsafe { std::os::windows::io::BorrowedSocket::borrow_raw(fd.raw_socket()) };
+            let sock = socket2::SockRef::from(&raw);
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            if let Some(name) = &opts.bind_device {
+                let len = name.iter().position(|&b| b == 0).unwrap_or(name.len());
+                sock.bind_device(Some(&name[..len]))?;
+            }
+            if let Some(local_addr) = opts.local_addr {
+                sock.bind(&local_addr.into())?;
+            }
+        }
         #[allow(unused_mut)]
         let mut tfo = opts.tcp_fast_open;
 
@@ -132,12 +295,26 @@ impl TcpStream {
                 tfo = false;
             }
         }
-        let completion = Op::connect(SharedFd::new::<false>(socket)?, addr, tfo)?.await;
+        match opts.timeout {
+            Some(timeout) => {
+                crate::time::timeout(timeout, Self::connect_shared_fd(fd, addr, tfo)).await?
+            }
+            None => Self::connect_shared_fd(fd, addr, tfo).await,
+        }
+    }
+
+    /// Connect the created socket to `addr`.
+    pub(super) async fn connect_shared_fd(
+        fd: SharedFd,
+        addr: SocketAddr,
+        tfo: bool,
+    ) -> io::Result<Self> {
+        let completion = Op::connect(fd, addr, tfo)?.await;
         completion.meta.result?;
 
         let stream = TcpStream::from_shared_fd(completion.data.fd);
-        // wait write ready on epoll branch
-        if crate::driver::op::is_legacy() {
+        // wait write ready on epoll branch(legacy driver or uring driver in hybrid mode)
+        if stream.fd.registered_index().is_some() {
             #[cfg(all(any(target_os = "ios", target_os = "macos"), feature = "legacy"))]
             if !tfo {
                 stream.writable(true).await?;
@@ -212,6 +389,124 @@ impl TcpStream {
         self.meta.set_tcp_keepalive(time, interval, retries)
     }
 
+    /// Get the value of the `SO_KEEPALIVE` option on this socket.
+    #[inline]
+    pub fn keepalive(&self) -> io::Result<bool> {
+        self.meta.socket().keepalive()
+    }
+
+    /// Set the value of the `SO_KEEPALIVE` option on this socket, with the system default
+    /// parameters.
+    #[inline]
+    pub fn set_keepalive(&self, keepalive: bool) -> io::Result<()> {
+        self.meta.socket().set_keepalive(keepalive)
+    }
+
+    /// Get the idle time before the keepalive probes are sent(`TCP_KEEPIDLE`).
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "macos"
+    ))]
+    #[inline]
+    pub fn keepalive_time(&self) -> io::Result<Duration> {
+        self.meta.socket().keepalive_time()
+    }
+
+    /// Get the interval between the keepalive probes(`TCP_KEEPINTVL`).
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "macos"
+    ))]
+    #[inline]
+    pub fn keepalive_interval(&self) -> io::Result<Duration> {
+        self.meta.socket().keepalive_interval()
+    }
+
+    /// Get the number of the keepalive probes before dropping the connection(`TCP_KEEPCNT`).
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "macos"
+    ))]
+    #[inline]
+    pub fn keepalive_retries(&self) -> io::Result<u32> {
+        self.meta.socket().keepalive_retries()
+    }
+
+    /// Get the value of the `TCP_QUICKACK` option on this socket.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[inline]
+    pub fn quickack(&self) -> io::Result<bool> {
+        self.meta.socket().quickack()
+    }
+
+    /// Set the value of the `TCP_QUICKACK` option on this socket.
+    /// Note: The kernel may leave the quickack mode later, so it is usually set again after
+    /// each read.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[inline]
+    pub fn set_quickack(&self, quickack: bool) -> io::Result<()> {
+        self.meta.socket().set_quickack(quickack)
+    }
+
+    /// Get the value of the `SO_LINGER` option on this socket.
+    #[inline]
+    pub fn linger(&self) -> io::Result<Option<Duration>> {
+        self.meta.socket().linger()
+    }
+
+    /// Set the value of the `SO_LINGER` option on this socket.
+    ///
+    /// With `Some(Duration::ZERO)`, the connection is reset on close instead of being shut
+    /// down gracefully.
+    #[inline]
+    pub fn set_linger(&self, linger: Option<Duration>) -> io::Result<()> {
+        self.meta.socket().set_linger(linger)
+    }
+
+    /// Get the value of the `IP_TTL` option on this socket.
+    #[inline]
+    pub fn ttl(&self) -> io::Result<u32> {
+        self.meta.socket().ttl()
+    }
+
+    /// Set the value of the `IP_TTL` option on this socket.
+    #[inline]
+    pub fn set_ttl(&self, ttl: u32) -> io::Result<()> {
+        self.meta.socket().set_ttl(ttl)
+    }
+
+    /// Get the value of the `TCP_USER_TIMEOUT` option on this socket.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[inline]
+    pub fn user_timeout(&self) -> io::Result<Option<Duration>> {
+        self.meta.socket().tcp_user_timeout()
+    }
+
+    /// Set the value of the `TCP_USER_TIMEOUT` option on this socket, which is the maximum
+    /// time the transmitted data may remain unacknowledged before the connection is closed.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    #[inline]
+    pub fn set_user_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.meta.socket().set_tcp_user_timeout(timeout)
+    }
+
+    /// Get a snapshot of the kernel statistics of the connection(`TCP_INFO`), like the RTT
+    /// and the retransmissions.
+    #[cfg(target_os = "linux")]
+    #[inline]
+    pub fn tcp_info(&self) -> io::Result<super::TcpInfo> {
+        super::TcpInfo::get(self.fd.raw_fd())
+    }
+
     /// Creates new `TcpStream` from a `std::net::TcpStream`.
     pub fn from_std(stream: std::net::TcpStream) -> io::Result<Self> {
         #[cfg(unix)]
@@ -245,6 +540,110 @@ impl TcpStream {
         op.wait().await
     }
 
+    /// Receive data with a single multishot operation and buffers provided by `group`,
+    /// which can be a [`BufGroup`] or a [`BufRing`](crate::buf::BufRing).
+    ///
+    /// With io_uring driver(requires kernel 6.0+), one persistent submission keeps receiving
+    /// without resubmitting an op per read. With legacy driver it works the same as receiving
+    /// repeatedly with buffers taken from the group.
+    ///
+    /// The stream should not be used with other read operations at the same time, otherwise the
+    /// order of data is not guaranteed.
+    pub fn recv_multi(&self, group: impl AsRef<BufGroup>) -> io::Result<RecvMulti> {
+        Op::recv_multi(self.fd.clone(), group.as_ref().clone()).map(RecvMulti::new)
+    }
+
+    /// Read some bytes into the registered buffer.
+    ///
+    /// With io_uring driver it uses `IORING_OP_READ_FIXED`, which saves the kernel from mapping
+    /// the buffer for every read. If the buffer pool is not registered, it works like
+    /// [`read`](AsyncReadRent::read).
+    pub async fn read_fixed(&mut self, buf: FixedBuf) -> BufResult<usize, FixedBuf> {
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        if buf.is_registered() {
+            let op = Op::read_fixed(self.fd.clone(), buf, u64::MAX).unwrap();
+            return op.result().await;
+        }
+        self.read(buf).await
+    }
+
+    /// Write the registered buffer.
+    ///
+    /// With io_uring driver it uses `IORING_OP_WRITE_FIXED`, which saves the kernel from mapping
+    /// the buffer for every write. If the buffer pool is not registered, it works like
+    /// [`write`](AsyncWriteRent::write).
+    pub async fn write_fixed(&mut self, buf: FixedBuf) -> BufResult<usize, FixedBuf> {
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        if buf.is_registered() {
+            let op = Op::write_fixed(self.fd.clone(), buf, u64::MAX).unwrap();
+            return op.result().await;
+        }
+        self.write(buf).await
+    }
+
+    /// Install the socket into the fixed file table of io_uring and return the slot.
+    ///
+    /// After that, the io_uring operations on it refer to the slot instead of the fd. The table
+    /// must be registered with
+    /// [`RuntimeBuilder::with_fixed_files`](crate::RuntimeBuilder::with_fixed_files), and the
+    /// slot is released when the socket is closed. It fails with legacy driver.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    pub async fn register_fixed(&self) -> io::Result<u32> {
+        self.fd.register_fixed().await
+    }
+
+    /// Send data with zero-copy(`IORING_OP_SEND_ZC`, requires kernel 6.0+).
+    ///
+    /// The kernel sends the data from the buffer directly without copying it. The returned
+    /// future completes only after the kernel has released the buffer, so the buffer can be
+    /// reused safely. It is worth it for large buffers; for small ones the extra notification
+    /// costs more than the copy.
+    ///
+    /// With the `zero-copy` feature, it is sent with `MSG_ZEROCOPY`(kernel 4.14+) with legacy
+    /// driver or if the kernel does not support `IORING_OP_SEND_ZC`, and the future completes
+    /// after the kernel reports the buffer is released in the socket error queue. Otherwise
+    /// it works like [`write`](AsyncWriteRent::write) in these cases.
+    pub async fn send_zc<T: IoBuf>(&mut self, buf: T) -> BufResult<usize, T> {
+        #[cfg(all(target_os = "linux", feature = "zero-copy"))]
+        if let Some(zero_copy) = self.meta.zero_copy() {
+            #[cfg(feature = "iouring")]
+            let send_zc =
+                crate::driver::capabilities().is_opcode_supported(io_uring::opcode::SendZc::CODE);
+            #[cfg(not(feature = "iouring"))]
+            let send_zc = false;
+            if !send_zc {
+                return zero_copy.send(&self.fd, buf).await;
+            }
+        }
+        let op = Op::send_zc(self.fd.clone(), buf).unwrap();
+        op.result().await
+    }
+
+    /// Write the buffer with zero-copy.
+    ///
+    /// It is the same as [`send_zc`](Self::send_zc), as a counterpart of
+    /// [`write`](AsyncWriteRent::write) like [`write_fixed`](Self::write_fixed).
+    #[inline]
+    pub async fn write_zc<T: IoBuf>(&mut self, buf: T) -> BufResult<usize, T> {
+        self.send_zc(buf).await
+    }
+
+    /// Send `len` bytes of the file from `offset` to the stream, without copying the data into
+    /// userspace. It is the primitive for serving static files.
+    ///
+    /// With io_uring driver the data is spliced through a pipe, otherwise it is sent with
+    /// `sendfile(2)`. The data is sent in chunks until `len` bytes are sent or the end of the
+    /// file is reached, and the bytes sent are returned. The file position is not changed.
+    #[cfg(target_os = "linux")]
+    pub async fn send_file(
+        &mut self,
+        file: &crate::fs::File,
+        offset: u64,
+        len: usize,
+    ) -> io::Result<u64> {
+        send_file(&self.fd, file.shared_fd(), offset, len).await
+    }
+
     /// Wait for write readiness.
     /// Note: Do not use it before every io. It is different from other runtimes!
     ///
@@ -261,6 +660,54 @@ impl TcpStream {
     }
 }
 
+/// Size of a chunk of `send_file`, which is the default capacity of a pipe.
+#[cfg(target_os = "linux")]
+const SEND_FILE_CHUNK: usize = 64 * 1024;
+
+#[cfg(target_os = "linux")]
+async fn send_file(sock: &SharedFd, file: &SharedFd, offset: u64, len: usize) -> io::Result<u64> {
+    let mut sent = 0;
+    #[cfg(feature = "iouring")]
+    if !crate::driver::op::is_legacy() {
+        let (pr, pw) = crate::net::unix::new_pipe()?;
+        while sent < len {
+            let chunk = (len - sent).min(SEND_FILE_CHUNK) as u32;
+            let off = (offset + sent as u64) as i64;
+            let n = Op::splice_to_pipe_at(file, &pw.fd, off, chunk)?
+                .splice()
+                .await?;
+            if n == 0 {
+                break;
+            }
+            let mut left = n;
+            while left > 0 {
+                let written = Op::splice_from_pipe(&pr.fd, sock, left)?.splice().await?;
+                if written == 0 {
+                    return Err(io::ErrorKind::WriteZero.into());
+                }
+                left -= written;
+            }
+            sent += n as usize;
+        }
+        return Ok(sent as u64);
+    }
+
+    while sent < len {
+        let chunk = (len - sent).min(SEND_FILE_CHUNK);
+        let mut off = (offset + sent as u64) as libc::off_t;
+        match crate::syscall!(sendfile@RAW(sock.raw_fd(), file.raw_fd(), &mut off, chunk)) {
+            Ok(0) => break,
+            Ok(n) => sent += n as usize,
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                Op::poll_write(sock, false)?.wait().await?;
+            }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(sent as u64)
+}
+
 impl AsReadFd for TcpStream {
     #[inline]
     fn as_reader_fd(&mut self) -> &SharedFdWrapper {
@@ -319,9 +766,27 @@ impl std::fmt::Debug for TcpStream {
 impl AsyncWriteRent for TcpStream {
     #[inline]
     fn write<T: IoBuf>(&mut self, buf: T) -> impl Future<Output = BufResult<usize, T>> {
+        // Large writes are sent with MSG_ZEROCOPY.
+        #[cfg(all(target_os = "linux", feature = "zero-copy"))]
+        {
+            let this = &*self;
+            let op = match this.meta.zero_copy() {
+                Some(zero_copy) if buf.bytes_init() >= super::zerocopy::THRESHOLD => {
+                    Err((zero_copy, buf))
+                }
+                _ => Ok(Op::send(this.fd.clone(), buf).unwrap()),
+            };
+            async move {
+                match op {
+                    Ok(op) => op.result().await,
+                    Err((zero_copy, buf)) => zero_copy.send(&this.fd, buf).await,
+                }
+            }
+        }
+
         // Submit the write operation
-        let op = Op::send(self.fd.clone(), buf).unwrap();
-        op.result()
+        #[cfg(not(all(target_os = "linux", feature = "zero-copy")))]
+        Op::send(self.fd.clone(), buf).unwrap().result()
     }
 
     #[inline]
@@ -539,6 +1004,8 @@ impl tokio::io::AsyncWrite for TcpStream {
 struct StreamMeta {
     socket: Option<socket2::Socket>,
     meta: UnsafeCell<Meta>,
+    #[cfg(all(target_os = "linux", feature = "zero-copy"))]
+    zero_copy: Option<Box<super::zerocopy::ZeroCopy>>,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -553,9 +1020,20 @@ impl StreamMeta {
         Self {
             socket: unsafe { Some(socket2::Socket::from_raw_fd(fd)) },
             meta: Default::default(),
+            // enable SOCK_ZEROCOPY
+            #[cfg(all(target_os = "linux", feature = "zero-copy"))]
+            zero_copy: super::zerocopy::ZeroCopy::new(fd),
         }
     }
 
+    /// The zero copy state if `SO_ZEROCOPY` is set and the sends are tracked.
+    #[cfg(all(target_os = "linux", feature = "zero-copy"))]
+    fn zero_copy(&self) -> Option<&super::zerocopy::ZeroCopy> {
+        self.zero_copy
+            .as_deref()
+            .filter(|zero_copy| zero_copy.enabled())
+    }
+
     /// When operating files, we should use RawHandle;
     /// When operating sockets, we should use RawSocket;
     #[cfg(windows)]
@@ -602,6 +1080,11 @@ impl StreamMeta {
         ret
     }
 
+    #[inline]
+    fn socket(&self) -> &socket2::Socket {
+        self.socket.as_ref().unwrap()
+    }
+
     fn no_delay(&self) -> io::Result<bool> {
         self.socket.as_ref().unwrap().nodelay()
     }
@@ -630,20 +1113,17 @@ impl StreamMeta {
         }
         self.socket.as_ref().unwrap().set_tcp_keepalive(&t)
     }
+}
 
-    #[cfg(feature = "zero-copy")]
-    fn set_zero_copy(&self) {
-        #[cfg(target_os = "linux")]
-        unsafe {
-            let fd = self.socket.as_ref().unwrap().as_raw_fd();
-            let v: libc::c_int = 1;
-            libc::setsockopt(
-                fd,
-                libc::SOL_SOCKET,
-                libc::SO_ZEROCOPY,
-                &v as *const _ as *const _,
-                std::mem::size_of::<libc::c_int>() as _,
-            );
+/// Reorder the addresses to alternate between IPv6 and IPv4, starting with IPv6.
+fn interleave_families(addrs: Vec<SocketAddr>) -> Vec<SocketAddr> {
+    let (v6, v4): (Vec<_>, Vec<_>) = addrs.into_iter().partition(SocketAddr::is_ipv6);
+    let mut out = Vec::with_capacity(v6.len() + v4.len());
+    let (mut v6, mut v4) = (v6.into_iter(), v4.into_iter());
+    loop {
+        match (v6.next(), v4.next()) {
+            (None, None) => return out,
+            (a, b) => out.extend(a.into_iter().chain(b)),
         }
     }
 }
@@ -0,0 +1,226 @@
+//! `MSG_ZEROCOPY` sends, for the kernels without `IORING_OP_SEND_ZC` and the legacy driver.
+//!
+//! The kernel numbers the zero-copy sends of a socket from 0, and reports the ranges of the
+//! numbers whose pages are released in the socket error queue. A send completes only after
+//! its number is reported, so the owned buffer is not reused while the kernel reads it. If the
+//! send is dropped before, its buffer is kept by the socket until the number is reported.
+//! See also: <https://www.kernel.org/doc/html/latest/networking/msg_zerocopy.html>
+
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    io,
+    os::fd::{AsRawFd, RawFd},
+};
+
+use crate::{
+    buf::{IoBuf, RawBuf},
+    driver::{op::Op, shared_fd::SharedFd},
+    net::cmsg::{self, CmsgBuf},
+    BufResult,
+};
+
+/// According to Linux's documentation, zero copy introduces extra overhead and is only
+/// considered effective for large writes.
+pub(crate) const THRESHOLD: usize = 10 * 1024 * 1024;
+
+// Not in libc yet.
+const SO_EE_ORIGIN_ZEROCOPY: u8 = 5;
+
+pub(crate) struct ZeroCopy {
+    /// If the numbers are in sync with the kernel.
+    enabled: Cell<bool>,
+    next_id: Cell<u32>,
+    /// All the numbers before it are released.
+    released_below: Cell<u32>,
+    /// The released ranges after `released_below`, inclusive.
+    released: RefCell<Vec<(u32, u32)>>,
+    /// The buffers of the dropped sends with their numbers, which may still be read by the
+    /// kernel. The number is `None` if it is unknown whether the send is done.
+    orphans: RefCell<Vec<Orphan>>,
+}
+
+type Orphan = (Option<u32>, Box<dyn Any>);
+
+impl ZeroCopy {
+    /// Set `SO_ZEROCOPY` on the socket, it is `None` if the kernel does not support it. It is
+    /// boxed to keep the streams without zero copy small.
+    pub(crate) fn new(fd: RawFd) -> Option<Box<Self>> {
+        let v: libc::c_int = 1;
+        let enabled = crate::syscall!(setsockopt@RAW(
+            fd,
+            libc::SOL_SOCKET,
+            libc::SO_ZEROCOPY,
+            &v as *const _ as *const libc::c_void,
+            std::mem::size_of::<libc::c_int>() as libc::socklen_t
+        ))
+        .is_ok();
+        enabled.then(|| {
+            Box::new(Self {
+                enabled: Cell::new(true),
+                next_id: Cell::new(0),
+                released_below: Cell::new(0),
+                released: RefCell::new(Vec::new()),
+                orphans: RefCell::new(Vec::new()),
+            })
+        })
+    }
+
+    #[inline]
+    pub(crate) fn enabled(&self) -> bool {
+        self.enabled.get()
+    }
+
+    /// Send with `MSG_ZEROCOPY`, and wait until the kernel releases the buffer.
+    ///
+    /// If the kernel runs out of the memory to pin the pages(`ENOBUFS`), the buffer is sent
+    /// with copying instead.
+    pub(crate) async fn send<T: IoBuf>(&self, fd: &SharedFd, buf: T) -> BufResult<usize, T> {
+        // The buffer is boxed so it does not move when it is handed to the socket.
+        let mut lease = Lease {
+            zero_copy: self,
+            buf: Some(Box::new(buf)),
+            id: None,
+        };
+        let raw = {
+            let buf = lease.buf.as_ref().unwrap();
+            // Safety: the buffer is kept until the kernel releases it.
+            unsafe { RawBuf::new(buf.read_ptr(), buf.bytes_init()) }
+        };
+        let res = Op::send_with_flags(fd.clone(), raw, libc::MSG_ZEROCOPY)
+            .unwrap()
+            .result()
+            .await
+            .0;
+        let n = match res {
+            // A failed send does not take a number.
+            Ok(n) if n > 0 => n,
+            Err(e) if e.raw_os_error() == Some(libc::ENOBUFS) => {
+                let buf = lease.take();
+                return Op::send(fd.clone(), buf).unwrap().result().await;
+            }
+            res => return (res, lease.take()),
+        };
+
+        let id = self.next_id.get();
+        self.next_id.set(id.wrapping_add(1));
+        lease.id = Some(id);
+        loop {
+            if let Err(e) = self.read_error_queue(fd.as_raw_fd()) {
+                self.enabled.set(false);
+                return (Err(e), lease.take());
+            }
+            if self.is_released(id) {
+                return (Ok(n), lease.take());
+            }
+            if let Err(e) = Op::poll_error(fd).unwrap().wait().await {
+                self.enabled.set(false);
+                return (Err(e), lease.take());
+            }
+        }
+    }
+
+    /// Drop the buffers of the dropped sends which are released.
+    fn drop_orphans(&self) {
+        self.orphans
+            .borrow_mut()
+            .retain(|(id, _)| !id.is_some_and(|id| self.is_released(id)));
+    }
+
+    #[inline]
+    fn is_released(&self, id: u32) -> bool {
+        (id.wrapping_sub(self.released_below.get()) as i32) < 0
+    }
+
+    fn release(&self, lo: u32, hi: u32) {
+        let mut released = self.released.borrow_mut();
+        released.push((lo, hi));
+        loop {
+            let below = self.released_below.get();
+            let Some(i) = released
+                .iter()
+                .position(|&(lo, _)| lo.wrapping_sub(below) as i32 <= 0)
+            else {
+                break;
+            };
+            let (_, hi) = released.swap_remove(i);
+            if hi.wrapping_sub(below) as i32 >= 0 {
+                self.released_below.set(hi.wrapping_add(1));
+            }
+        }
+    }
+
+    /// Read the notifications until the error queue is empty.
+    fn read_error_queue(&self, fd: RawFd) -> io::Result<()> {
+        loop {
+            let mut control = CmsgBuf::new(128);
+            let mut msg: libc::msghdr = unsafe { std::mem::zeroed() };
+            msg.msg_control = control.as_mut_ptr();
+            msg.msg_controllen = control.len() as _;
+            match crate::syscall!(recvmsg@RAW(
+                fd,
+                &mut msg,
+                libc::MSG_ERRQUEUE | libc::MSG_DONTWAIT
+            )) {
+                Ok(_) => (),
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                    self.drop_orphans();
+                    return Ok(());
+                }
+                Err(e) => return Err(e),
+            }
+
+            control.truncate(msg.msg_controllen as _);
+            for (level, ty, data) in control.iter() {
+                if !matches!(
+                    (level, ty),
+                    (libc::SOL_IP, libc::IP_RECVERR) | (libc::SOL_IPV6, libc::IPV6_RECVERR)
+                ) {
+                    continue;
+                }
+                // Safety: the item is a sock_extended_err.
+                if let Some(err) = unsafe { cmsg::read::<libc::sock_extended_err>(data) } {
+                    if err.ee_errno == 0 && err.ee_origin == SO_EE_ORIGIN_ZEROCOPY {
+                        self.release(err.ee_info, err.ee_data);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl Drop for ZeroCopy {
+    fn drop(&mut self) {
+        // The kernel may still read them after the socket is closed, until the data is acked.
+        for (_, buf) in self.orphans.take() {
+            std::mem::forget(buf);
+        }
+    }
+}
+
+/// The buffer of a send, which is handed to the socket if the send is dropped.
+struct Lease<'a, T: 'static> {
+    zero_copy: &'a ZeroCopy,
+    buf: Option<Box<T>>,
+    id: Option<u32>,
+}
+
+impl<T: 'static> Lease<'_, T> {
+    fn take(&mut self) -> T {
+        *self.buf.take().expect("the buffer is taken twice")
+    }
+}
+
+impl<T: 'static> Drop for Lease<'_, T> {
+    fn drop(&mut self) {
+        let Some(buf) = self.buf.take() else {
+            return;
+        };
+        if self.id.is_none() {
+            // If the future is dropped before the send completes, it is unknown whether a
+            // number is taken, so the later sends can not be tracked.
+            self.zero_copy.enabled.set(false);
+        }
+        self.zero_copy.orphans.borrow_mut().push((self.id, buf));
+    }
+}
@@ -6,13 +6,28 @@ use std::os::unix::prelude::{AsRawFd, FromRawFd, IntoRawFd};
 use std::os::windows::prelude::{AsRawSocket, FromRawSocket, IntoRawSocket, RawSocket};
 use std::{
     io,
-    net::{SocketAddr, ToSocketAddrs},
+    mem::ManuallyDrop,
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs},
 };
 
+#[cfg(target_os = "linux")]
+pub use crate::net::cmsg::{RecvMeta, SendMeta};
+
+#[cfg(unix)]
+const MSG_PEEK: i32 = libc::MSG_PEEK;
+#[cfg(windows)]
+const MSG_PEEK: i32 = windows_sys::Win32::Networking::WinSock::MSG_PEEK as _;
+// The sockets are blocking with io_uring driver.
+#[cfg(unix)]
+const MSG_DONTWAIT: i32 = libc::MSG_DONTWAIT;
+// The sockets are always non-blocking with legacy driver.
+#[cfg(windows)]
+const MSG_DONTWAIT: i32 = 0;
 use crate::{
-    buf::{IoBuf, IoBufMut},
+    buf::{BufGroup, IoBuf, IoBufMut},
     driver::{op::Op, shared_fd::SharedFd},
     io::{operation_canceled, CancelHandle, Split},
+    net::RecvMulti,
 };
 
 /// A UDP socket.
@@ -44,6 +59,9 @@ impl UdpSocket {
             #[cfg(all(target_os = "linux", feature = "iouring"))]
             crate::driver::Inner::Uring(_) => Ok(()),
             crate::driver::Inner::Legacy(_) => _socket.set_nonblocking(true),
+            // The ops without overlapped version are executed with blocking syscalls.
+            #[cfg(all(windows, feature = "iocp"))]
+            crate::driver::Inner::Iocp(_) => Ok(()),
         })
     }
 
@@ -52,7 +70,7 @@ impl UdpSocket {
         let addr = addr
             .to_socket_addrs()?
             .next()
-            .ok_or_else(|| io::Error::new(io::ErrorKind::Other, "empty address"))?;
+            .ok_or_else(|| io::Error::other("empty address"))?;
         let domain = if addr.is_ipv6() {
             socket2::Domain::IPV6
         } else {
@@ -92,6 +110,254 @@ impl UdpSocket {
         op.wait().await
     }
 
+    /// Receives a single datagram message with its metadata, like the destination address
+    /// and the interface it was received on.
+    ///
+    /// The metadata other than the source address is reported only if enabled by the
+    /// `set_recv_*` methods.
+    #[cfg(target_os = "linux")]
+    pub async fn recv_msg<T: IoBufMut>(&self, buf: T) -> crate::BufResult<(usize, RecvMeta), T> {
+        let control = crate::net::cmsg::CmsgBuf::new(crate::net::cmsg::RECV_CONTROL_LEN);
+        let op = Op::recv_msg_with_control(self.fd.clone(), buf, control).unwrap();
+        let (res, buf) = op.wait_with_control().await;
+        let res = res
+            .map(|(n, addr, control, flags)| (n, crate::net::cmsg::decode(addr, &control, flags)));
+        (res, buf)
+    }
+
+    /// Sends data on the socket with the ancillary data in `meta`, e.g. to reply from the
+    /// address a request was received on. If `socket_addr` is None, the data is sent to the
+    /// connected address.
+    #[cfg(target_os = "linux")]
+    pub async fn send_msg<T: IoBuf>(
+        &self,
+        buf: T,
+        socket_addr: Option<SocketAddr>,
+        meta: &SendMeta,
+    ) -> crate::BufResult<usize, T> {
+        let control = if meta.is_empty() {
+            crate::net::cmsg::CmsgBuf::default()
+        } else {
+            let ipv6 = match self.local_addr() {
+                Ok(addr) => addr.is_ipv6(),
+                Err(e) => return (Err(e), buf),
+            };
+            crate::net::cmsg::encode(meta, ipv6)
+        };
+        let op = Op::send_msg_with_control(self.fd.clone(), buf, socket_addr, control).unwrap();
+        op.wait().await
+    }
+
+    /// Set whether the destination address and the interface of the received messages are
+    /// reported by [`recv_msg`](Self::recv_msg), with `IP_PKTINFO` or `IPV6_RECVPKTINFO`.
+    #[cfg(target_os = "linux")]
+    pub fn set_recv_pktinfo(&self, on: bool) -> io::Result<()> {
+        self.set_recv_opt(
+            (libc::IPPROTO_IP, libc::IP_PKTINFO),
+            (libc::IPPROTO_IPV6, libc::IPV6_RECVPKTINFO),
+            on,
+        )
+    }
+
+    /// Set whether the TOS(or traffic class) of the received messages is reported by
+    /// [`recv_msg`](Self::recv_msg).
+    #[cfg(target_os = "linux")]
+    pub fn set_recv_tos(&self, on: bool) -> io::Result<()> {
+        self.set_recv_opt(
+            (libc::IPPROTO_IP, libc::IP_RECVTOS),
+            (libc::IPPROTO_IPV6, libc::IPV6_RECVTCLASS),
+            on,
+        )
+    }
+
+    /// Set whether the TTL(or hop limit) of the received messages is reported by
+    /// [`recv_msg`](Self::recv_msg).
+    #[cfg(target_os = "linux")]
+    pub fn set_recv_ttl(&self, on: bool) -> io::Result<()> {
+        self.set_recv_opt(
+            (libc::IPPROTO_IP, libc::IP_RECVTTL),
+            (libc::IPPROTO_IPV6, libc::IPV6_RECVHOPLIMIT),
+            on,
+        )
+    }
+
+    /// Set whether the receive timestamp of the messages is reported by
+    /// [`recv_msg`](Self::recv_msg), with `SO_TIMESTAMPNS`.
+    #[cfg(target_os = "linux")]
+    pub fn set_recv_timestamp(&self, on: bool) -> io::Result<()> {
+        crate::net::cmsg::set_int_opt(
+            self.fd.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_TIMESTAMPNS,
+            on as _,
+        )
+    }
+
+    /// Set the option of the socket family. For IPv6 sockets, the IPv4 option is set too
+    /// for the messages from the IPv4-mapped addresses.
+    #[cfg(target_os = "linux")]
+    fn set_recv_opt(
+        &self,
+        v4: (libc::c_int, libc::c_int),
+        v6: (libc::c_int, libc::c_int),
+        on: bool,
+    ) -> io::Result<()> {
+        use crate::net::cmsg::set_int_opt;
+
+        let fd = self.fd.as_raw_fd();
+        if self.local_addr()?.is_ipv6() {
+            set_int_opt(fd, v6.0, v6.1, on as _)?;
+            // It fails if the socket is IPv6 only.
+            let _ = set_int_opt(fd, v4.0, v4.1, on as _);
+            Ok(())
+        } else {
+            set_int_opt(fd, v4.0, v4.1, on as _)
+        }
+    }
+
+    /// Sends a batch of datagrams, returning the result of each one in order.
+    ///
+    /// With io_uring driver, the messages are submitted together as separate SQEs; with
+    /// legacy driver on Linux, they are sent with `sendmmsg`. The other platforms send them
+    /// one by one.
+    pub async fn send_batch<T: IoBuf>(
+        &self,
+        msgs: Vec<(T, SocketAddr)>,
+    ) -> Vec<crate::BufResult<usize, T>> {
+        #[cfg(all(target_os = "linux", feature = "legacy"))]
+        if crate::driver::op::is_legacy() {
+            return self.send_mmsg(msgs).await;
+        }
+
+        let ops: Vec<_> = msgs
+            .into_iter()
+            .map(|(buf, addr)| Op::send_msg(self.fd.clone(), buf, Some(addr)).unwrap())
+            .collect();
+        let mut results = Vec::with_capacity(ops.len());
+        for op in ops {
+            results.push(op.wait().await);
+        }
+        results
+    }
+
+    #[cfg(all(target_os = "linux", feature = "legacy"))]
+    async fn send_mmsg<T: IoBuf>(
+        &self,
+        msgs: Vec<(T, SocketAddr)>,
+    ) -> Vec<crate::BufResult<usize, T>> {
+        let mut results = Vec::with_capacity(msgs.len());
+        let mut pending: Vec<_> = msgs
+            .into_iter()
+            .map(|(buf, addr)| (buf, Some(addr)))
+            .collect();
+        // sendmmsg may send a part of the messages, or fail with the first one.
+        while !pending.is_empty() {
+            let addrs: Vec<_> = pending.iter().map(|(_, addr)| *addr).collect();
+            let (res, bufs) = Op::send_mmsg(self.fd.clone(), pending)
+                .unwrap()
+                .wait()
+                .await;
+            let mut bufs = bufs.into_iter();
+            let done = match res {
+                Ok(lens) if !lens.is_empty() => {
+                    let n = lens.len();
+                    for len in lens {
+                        results.push((Ok(len), bufs.next().unwrap()));
+                    }
+                    n
+                }
+                // The first message is skipped if it fails.
+                Ok(_) => {
+                    let err = io::ErrorKind::WriteZero.into();
+                    results.push((Err(err), bufs.next().unwrap()));
+                    1
+                }
+                Err(e) => {
+                    results.push((Err(e), bufs.next().unwrap()));
+                    1
+                }
+            };
+            pending = bufs.zip(addrs.into_iter().skip(done)).collect();
+        }
+        results
+    }
+
+    /// Receives a batch of datagrams into the buffers. It waits for at least one datagram,
+    /// and returns the length and the source address of each received one. The first `n`
+    /// buffers returned hold the `n` datagrams received.
+    ///
+    /// With io_uring driver, a receive is submitted for each buffer and the ones not
+    /// completed with the first are canceled; with legacy driver on Linux, they are received
+    /// with `recvmmsg`. The other platforms receive one datagram.
+    pub async fn recv_batch<T: IoBufMut>(
+        &self,
+        mut bufs: Vec<T>,
+    ) -> crate::BufResult<Vec<(usize, SocketAddr)>, Vec<T>> {
+        if bufs.is_empty() {
+            return (Ok(Vec::new()), bufs);
+        }
+        #[cfg(all(target_os = "linux", feature = "legacy"))]
+        if crate::driver::op::is_legacy() {
+            return Op::recv_mmsg(self.fd.clone(), bufs).unwrap().wait().await;
+        }
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        if !crate::driver::op::is_legacy() {
+            return self.recv_batch_uring(bufs).await;
+        }
+
+        let rest = bufs.split_off(1);
+        let (res, buf) = self.recv_from(bufs.pop().unwrap()).await;
+        bufs.push(buf);
+        bufs.extend(rest);
+        (res.map(|received| vec![received]), bufs)
+    }
+
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    async fn recv_batch_uring<T: IoBufMut>(
+        &self,
+        bufs: Vec<T>,
+    ) -> crate::BufResult<Vec<(usize, SocketAddr)>, Vec<T>> {
+        let mut ops: Vec<_> = bufs
+            .into_iter()
+            .map(|buf| Op::recv_msg(self.fd.clone(), buf).unwrap())
+            .collect();
+        let rest = ops.split_off(1);
+        let first = ops.pop().unwrap().wait().await;
+        // Cancel the receives still in the kernel, the completed ones keep their results.
+        for op in &rest {
+            unsafe { op.op_canceller().cancel() };
+        }
+
+        let mut received = Vec::with_capacity(rest.len() + 1);
+        let mut filled = Vec::with_capacity(rest.len() + 1);
+        let mut empty = Vec::new();
+        let err = match first {
+            (Ok(r), buf) => {
+                received.push(r);
+                filled.push(buf);
+                None
+            }
+            (Err(e), buf) => {
+                empty.push(buf);
+                Some(e)
+            }
+        };
+        for op in rest {
+            match op.wait().await {
+                (Ok(r), buf) => {
+                    received.push(r);
+                    filled.push(buf);
+                }
+                (Err(_), buf) => empty.push(buf),
+            }
+        }
+        filled.extend(empty);
+        match err {
+            Some(e) if received.is_empty() => (Err(e), filled),
+            _ => (Ok(received), filled),
+        }
+    }
+
     /// Returns the socket address of the remote peer this socket was connected to.
     pub fn peer_addr(&self) -> io::Result<SocketAddr> {
         #[cfg(unix)]
@@ -134,6 +400,76 @@ impl UdpSocket {
         Ok(())
     }
 
+    /// Dissolves the association set by [`connect`](Self::connect), so the socket can send
+    /// to and receive from any address again.
+    #[cfg(unix)]
+    pub fn disconnect(&self) -> io::Result<()> {
+        let mut addr: libc::sockaddr = unsafe { std::mem::zeroed() };
+        addr.sa_family = libc::AF_UNSPEC as _;
+        let res = crate::syscall!(connect@RAW(
+            self.fd.as_raw_fd(),
+            &addr,
+            std::mem::size_of::<libc::sockaddr>() as libc::socklen_t
+        ));
+        match res {
+            // BSDs report it after the socket is disconnected.
+            Err(e) if e.raw_os_error() == Some(libc::EAFNOSUPPORT) => Ok(()),
+            res => res.map(|_| ()),
+        }
+    }
+
+    /// Receives a single datagram from the connected address without removing it from the
+    /// queue. On success, returns the number of bytes peeked.
+    pub async fn peek<T: IoBufMut>(&self, buf: T) -> crate::BufResult<usize, T> {
+        let op = Op::recv_with_flags(self.fd.clone(), buf, MSG_PEEK).unwrap();
+        op.result().await
+    }
+
+    /// Receives a single datagram without removing it from the queue. On success, returns
+    /// the number of bytes peeked and the origin.
+    pub async fn peek_from<T: IoBufMut>(&self, buf: T) -> crate::BufResult<(usize, SocketAddr), T> {
+        let op = Op::recv_msg_with_flags(self.fd.clone(), buf, MSG_PEEK).unwrap();
+        op.wait().await
+    }
+
+    /// Tries to receive a single datagram from the connected address without waiting.
+    ///
+    /// It returns [`io::ErrorKind::WouldBlock`] if there is no datagram; use it with
+    /// [`readable`](Self::readable) to receive without submitting an op per datagram.
+    pub fn try_recv(&self, buf: &mut [u8]) -> io::Result<usize> {
+        // Safety: the initialized bytes are never de-initialized.
+        let buf = unsafe { &mut *(buf as *mut [u8] as *mut [std::mem::MaybeUninit<u8>]) };
+        self.with_socket(|s| s.recv_with_flags(buf, MSG_DONTWAIT))
+    }
+
+    /// Tries to receive a single datagram without waiting. On success, returns the number of
+    /// bytes read and the origin.
+    ///
+    /// It returns [`io::ErrorKind::WouldBlock`] if there is no datagram.
+    pub fn try_recv_from(&self, buf: &mut [u8]) -> io::Result<(usize, SocketAddr)> {
+        // Safety: the initialized bytes are never de-initialized.
+        let buf = unsafe { &mut *(buf as *mut [u8] as *mut [std::mem::MaybeUninit<u8>]) };
+        let (n, addr) = self.with_socket(|s| s.recv_from_with_flags(buf, MSG_DONTWAIT))?;
+        let addr = addr
+            .as_socket()
+            .ok_or_else(|| io::Error::from(io::ErrorKind::InvalidInput))?;
+        Ok((n, addr))
+    }
+
+    /// Tries to send a datagram to the connected address without waiting.
+    ///
+    /// It returns [`io::ErrorKind::WouldBlock`] if the send buffer is full.
+    pub fn try_send(&self, buf: &[u8]) -> io::Result<usize> {
+        self.with_socket(|s| s.send_with_flags(buf, MSG_DONTWAIT))
+    }
+
+    /// Tries to send a datagram to the given address without waiting.
+    ///
+    /// It returns [`io::ErrorKind::WouldBlock`] if the send buffer is full.
+    pub fn try_send_to(&self, buf: &[u8], target: SocketAddr) -> io::Result<usize> {
+        self.with_socket(|s| s.send_to_with_flags(buf, &target.into(), MSG_DONTWAIT))
+    }
+
     /// Sends data on the socket to the remote address to which it is connected.
     pub async fn send<T: IoBuf>(&self, buf: T) -> crate::BufResult<usize, T> {
         let op = Op::send_msg(self.fd.clone(), buf, None).unwrap();
@@ -147,6 +483,18 @@ impl UdpSocket {
         op.result().await
     }
 
+    /// Receive datagrams with a single multishot operation and buffers provided by `group`,
+    /// which can be a [`BufGroup`] or a [`BufRing`](crate::buf::BufRing).
+    /// Each datagram is received into one buffer, so the buffer length of the group should be
+    /// large enough to hold a datagram.
+    ///
+    /// With io_uring driver(requires kernel 6.0+), one persistent submission keeps receiving
+    /// without resubmitting an op per datagram. The source addresses are not reported, so it is
+    /// mostly useful for connected sockets.
+    pub fn recv_multi(&self, group: impl AsRef<BufGroup>) -> io::Result<RecvMulti> {
+        Op::recv_multi(self.fd.clone(), group.as_ref().clone()).map(RecvMulti::new)
+    }
+
     /// Creates new `UdpSocket` from a `std::net::UdpSocket`.
     pub fn from_std(socket: std::net::UdpSocket) -> io::Result<Self> {
         #[cfg(unix)]
@@ -203,6 +551,122 @@ impl UdpSocket {
         r
     }
 
+    /// Run `f` with the socket borrowed as a `socket2::Socket`.
+    fn with_socket<R>(&self, f: impl FnOnce(&socket2::Socket) -> io::Result<R>) -> io::Result<R> {
+        #[cfg(unix)]
+        let socket = unsafe { socket2::Socket::from_raw_fd(self.fd.as_raw_fd()) };
+        #[cfg(windows)]
+        let socket = unsafe { socket2::Socket::from_raw_socket(self.fd.as_raw_socket()) };
+        f(&ManuallyDrop::new(socket))
+    }
+
+    /// Executes an operation of the `IP_ADD_MEMBERSHIP` type.
+    ///
+    /// This function specifies a new multicast group for this socket to join. The address
+    /// must be a valid multicast address, and `interface` is the address of the local
+    /// interface with which the system should join the multicast group. If it's equal to
+    /// `INADDR_ANY` then an appropriate interface is chosen by the system.
+    pub fn join_multicast_v4(&self, multiaddr: Ipv4Addr, interface: Ipv4Addr) -> io::Result<()> {
+        self.with_socket(|s| s.join_multicast_v4(&multiaddr, &interface))
+    }
+
+    /// Executes an operation of the `IPV6_ADD_MEMBERSHIP` type.
+    ///
+    /// This function specifies a new multicast group for this socket to join. The address
+    /// must be a valid multicast address, and `interface` is the index of the interface to
+    /// join/leave (or 0 to indicate any interface).
+    pub fn join_multicast_v6(&self, multiaddr: &Ipv6Addr, interface: u32) -> io::Result<()> {
+        self.with_socket(|s| s.join_multicast_v6(multiaddr, interface))
+    }
+
+    /// Executes an operation of the `IP_DROP_MEMBERSHIP` type.
+    ///
+    /// For more information about this option, see
+    /// [`join_multicast_v4`](Self::join_multicast_v4).
+    pub fn leave_multicast_v4(&self, multiaddr: Ipv4Addr, interface: Ipv4Addr) -> io::Result<()> {
+        self.with_socket(|s| s.leave_multicast_v4(&multiaddr, &interface))
+    }
+
+    /// Executes an operation of the `IPV6_DROP_MEMBERSHIP` type.
+    ///
+    /// For more information about this option, see
+    /// [`join_multicast_v6`](Self::join_multicast_v6).
+    pub fn leave_multicast_v6(&self, multiaddr: &Ipv6Addr, interface: u32) -> io::Result<()> {
+        self.with_socket(|s| s.leave_multicast_v6(multiaddr, interface))
+    }
+
+    /// Sets the value of the `IP_MULTICAST_LOOP` option for this socket.
+    ///
+    /// If enabled, multicast packets will be looped back to the local socket.
+    pub fn set_multicast_loop_v4(&self, on: bool) -> io::Result<()> {
+        self.with_socket(|s| s.set_multicast_loop_v4(on))
+    }
+
+    /// Gets the value of the `IP_MULTICAST_LOOP` option for this socket.
+    pub fn multicast_loop_v4(&self) -> io::Result<bool> {
+        self.with_socket(|s| s.multicast_loop_v4())
+    }
+
+    /// Sets the value of the `IPV6_MULTICAST_LOOP` option for this socket.
+    ///
+    /// Controls whether this socket sees the multicast packets it sends itself.
+    pub fn set_multicast_loop_v6(&self, on: bool) -> io::Result<()> {
+        self.with_socket(|s| s.set_multicast_loop_v6(on))
+    }
+
+    /// Gets the value of the `IPV6_MULTICAST_LOOP` option for this socket.
+    pub fn multicast_loop_v6(&self) -> io::Result<bool> {
+        self.with_socket(|s| s.multicast_loop_v6())
+    }
+
+    /// Sets the value of the `IP_MULTICAST_TTL` option for this socket.
+    ///
+    /// Indicates the time-to-live value of outgoing multicast packets for this socket. The
+    /// default value is 1 which means that multicast packets don't leave the local network
+    /// unless explicitly requested.
+    pub fn set_multicast_ttl_v4(&self, ttl: u32) -> io::Result<()> {
+        self.with_socket(|s| s.set_multicast_ttl_v4(ttl))
+    }
+
+    /// Gets the value of the `IP_MULTICAST_TTL` option for this socket.
+    pub fn multicast_ttl_v4(&self) -> io::Result<u32> {
+        self.with_socket(|s| s.multicast_ttl_v4())
+    }
+
+    /// Sets the value of the `IP_MULTICAST_IF` option for this socket, which is the local
+    /// address of the interface to send the multicast packets on.
+    pub fn set_multicast_if_v4(&self, interface: Ipv4Addr) -> io::Result<()> {
+        self.with_socket(|s| s.set_multicast_if_v4(&interface))
+    }
+
+    /// Gets the value of the `IP_MULTICAST_IF` option for this socket.
+    pub fn multicast_if_v4(&self) -> io::Result<Ipv4Addr> {
+        self.with_socket(|s| s.multicast_if_v4())
+    }
+
+    /// Sets the value of the `IPV6_MULTICAST_IF` option for this socket, which is the index
+    /// of the interface to send the multicast packets on(0 for the default one).
+    pub fn set_multicast_if_v6(&self, interface: u32) -> io::Result<()> {
+        self.with_socket(|s| s.set_multicast_if_v6(interface))
+    }
+
+    /// Gets the value of the `IPV6_MULTICAST_IF` option for this socket.
+    pub fn multicast_if_v6(&self) -> io::Result<u32> {
+        self.with_socket(|s| s.multicast_if_v6())
+    }
+
+    /// Sets the value of the `SO_BROADCAST` option for this socket.
+    ///
+    /// When enabled, this socket is allowed to send packets to a broadcast address.
+    pub fn set_broadcast(&self, on: bool) -> io::Result<()> {
+        self.with_socket(|s| s.set_broadcast(on))
+    }
+
+    /// Gets the value of the `SO_BROADCAST` option for this socket.
+    pub fn broadcast(&self) -> io::Result<bool> {
+        self.with_socket(|s| s.broadcast())
+    }
+
     /// Wait for read readiness.
     /// Note: Do not use it before every io. It is different from other runtimes!
     ///
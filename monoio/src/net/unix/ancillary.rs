@@ -0,0 +1,101 @@
+//! File descriptor and credential passing with the ancillary data of Unix sockets.
+
+use std::os::fd::{AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd};
+
+use super::SocketAddr;
+#[cfg(target_os = "linux")]
+use super::UCred;
+use crate::{
+    buf::{IoBuf, IoBufMut},
+    driver::{op::Op, shared_fd::SharedFd},
+    net::cmsg::{self, CmsgBuf},
+    BufResult,
+};
+
+/// The ancillary data received with a message.
+pub(crate) struct Received {
+    pub(crate) addr: SocketAddr,
+    pub(crate) fds: Vec<OwnedFd>,
+    #[cfg(target_os = "linux")]
+    pub(crate) cred: Option<UCred>,
+}
+
+/// Send `buf` with the file descriptors attached as `SCM_RIGHTS`.
+pub(crate) async fn send_with_fds<T: IoBuf>(
+    fd: &SharedFd,
+    buf: T,
+    fds: &[BorrowedFd<'_>],
+    addr: Option<SocketAddr>,
+) -> BufResult<usize, T> {
+    let control = encode_fds(fds);
+    let op = Op::send_msg_unix_with_control(fd.clone(), buf, addr, control).unwrap();
+    op.wait().await
+}
+
+/// Receive a message with at most `max_fds` file descriptors, and the credentials of the
+/// sender if `cred` is set.
+///
+/// The descriptors beyond `max_fds` are closed by the kernel.
+pub(crate) async fn recv<T: IoBufMut>(
+    fd: &SharedFd,
+    buf: T,
+    max_fds: usize,
+    #[cfg_attr(not(target_os = "linux"), allow(unused))] cred: bool,
+) -> BufResult<(usize, Received), T> {
+    #[cfg_attr(not(target_os = "linux"), allow(unused_mut))]
+    // Safety: CMSG_SPACE only computes the length.
+    let mut len = if max_fds == 0 {
+        0
+    } else {
+        unsafe { libc::CMSG_SPACE((max_fds * std::mem::size_of::<RawFd>()) as _) as usize }
+    };
+    #[cfg(target_os = "linux")]
+    if cred {
+        len += unsafe { libc::CMSG_SPACE(std::mem::size_of::<libc::ucred>() as _) as usize };
+    }
+    let op = Op::recv_msg_unix_with_control(fd.clone(), buf, CmsgBuf::new(len)).unwrap();
+    let (res, buf) = op.wait_with_control().await;
+    let res = res.map(|(n, addr, control, _)| (n, decode(addr, &control)));
+    (res, buf)
+}
+
+fn encode_fds(fds: &[BorrowedFd<'_>]) -> CmsgBuf {
+    if fds.is_empty() {
+        return CmsgBuf::default();
+    }
+    let data: Vec<u8> = fds
+        .iter()
+        .flat_map(|fd| fd.as_raw_fd().to_ne_bytes())
+        .collect();
+    CmsgBuf::encode(&[(libc::SOL_SOCKET, libc::SCM_RIGHTS, &data)])
+}
+
+fn decode(addr: SocketAddr, control: &CmsgBuf) -> Received {
+    let mut received = Received {
+        addr,
+        fds: Vec::new(),
+        #[cfg(target_os = "linux")]
+        cred: None,
+    };
+    for (level, ty, data) in control.iter() {
+        match (level, ty) {
+            (libc::SOL_SOCKET, libc::SCM_RIGHTS) => {
+                // Safety: the received descriptors are owned by this process now.
+                received.fds.extend(
+                    data.chunks_exact(std::mem::size_of::<RawFd>())
+                        .filter_map(|fd| unsafe { cmsg::read::<RawFd>(fd) })
+                        .map(|fd| unsafe { OwnedFd::from_raw_fd(fd) }),
+                );
+            }
+            #[cfg(target_os = "linux")]
+            (libc::SOL_SOCKET, libc::SCM_CREDENTIALS) => {
+                // Safety: the item is a ucred.
+                if let Some(cred) = unsafe { cmsg::read::<libc::ucred>(data) } {
+                    received.cred = Some(UCred::new(cred.uid, cred.gid, Some(cred.pid)));
+                }
+            }
+            _ => (),
+        }
+    }
+    received
+}
@@ -2,14 +2,20 @@
 
 use std::{
     io,
-    os::unix::{
-        net::UnixDatagram as StdUnixDatagram,
-        prelude::{AsRawFd, IntoRawFd, RawFd},
+    os::{
+        fd::{BorrowedFd, OwnedFd},
+        unix::{
+            net::UnixDatagram as StdUnixDatagram,
+            prelude::{AsRawFd, IntoRawFd, RawFd},
+        },
     },
     path::Path,
 };
 
+#[cfg(target_os = "linux")]
+use super::UCred;
 use super::{
+    ancillary,
     socket_addr::{local_addr, pair, peer_addr, socket_addr},
     SocketAddr,
 };
@@ -30,8 +36,26 @@ impl UnixDatagram {
     }
 
     /// Creates a Unix datagram socket bound to the given path.
+    ///
+    /// On Linux, a path starting with a null byte is an address in the abstract namespace.
     pub fn bind<P: AsRef<Path>>(path: P) -> io::Result<Self> {
-        StdUnixDatagram::bind(path).and_then(Self::from_std)
+        let (addr, addr_len) = socket_addr(path.as_ref())?;
+        Self::bind_addr(&SocketAddr::from_parts(addr, addr_len))
+    }
+
+    /// Creates a Unix datagram socket bound to an address, e.g. one in the abstract
+    /// namespace created with [`SocketAddr::from_abstract_name`]. On Linux, binding to an
+    /// unnamed address autobinds the socket to a unique abstract name.
+    pub fn bind_addr(addr: &SocketAddr) -> io::Result<Self> {
+        let socket = new_socket(libc::AF_UNIX, libc::SOCK_DGRAM)?;
+        let fd = SharedFd::new::<false>(socket)?;
+        let (addr, addr_len) = addr.clone().into_parts();
+        crate::syscall!(bind@RAW(
+            socket,
+            &addr as *const _ as *const libc::sockaddr,
+            addr_len
+        ))?;
+        Ok(Self::from_shared_fd(fd))
     }
 
     /// Creates a new `UnixDatagram` which is not bound to any address.
@@ -141,6 +165,17 @@ impl UnixDatagram {
         op.wait().await
     }
 
+    /// Sends data on the socket to the given address, e.g. the origin returned by
+    /// [`recv_from`](Self::recv_from). On success, returns the number of bytes written.
+    pub async fn send_to_addr<T: IoBuf>(
+        &self,
+        buf: T,
+        addr: &SocketAddr,
+    ) -> crate::BufResult<usize, T> {
+        let op = Op::send_msg_unix(self.fd.clone(), buf, Some(addr.clone())).unwrap();
+        op.wait().await
+    }
+
     /// Receives a single datagram message on the socket. On success, returns the number
     /// of bytes read and the origin.
     pub async fn recv_from<T: IoBufMut>(&self, buf: T) -> crate::BufResult<(usize, SocketAddr), T> {
@@ -160,6 +195,97 @@ impl UnixDatagram {
         let op = Op::recv(self.fd.clone(), buf).unwrap();
         op.result().await
     }
+
+    /// Sends data with a file descriptor attached as `SCM_RIGHTS`. The receiver gets a
+    /// duplicate of the descriptor, which refers to the same open file.
+    pub async fn send_with_fd<T: IoBuf>(
+        &self,
+        buf: T,
+        fd: BorrowedFd<'_>,
+    ) -> crate::BufResult<usize, T> {
+        self.send_with_fds(buf, &[fd]).await
+    }
+
+    /// Sends data with the file descriptors attached as `SCM_RIGHTS`. At least one byte of
+    /// data must be sent with the descriptors.
+    pub async fn send_with_fds<T: IoBuf>(
+        &self,
+        buf: T,
+        fds: &[BorrowedFd<'_>],
+    ) -> crate::BufResult<usize, T> {
+        ancillary::send_with_fds(&self.fd, buf, fds, None).await
+    }
+
+    /// Receives data from the connected address with at most one file descriptor attached.
+    pub async fn recv_with_fd<T: IoBufMut>(
+        &self,
+        buf: T,
+    ) -> crate::BufResult<(usize, Option<OwnedFd>), T> {
+        let (res, buf) = self.recv_with_fds(buf, 1).await;
+        (res.map(|(n, fds)| (n, fds.into_iter().next())), buf)
+    }
+
+    /// Receives data from the connected address with at most `max_fds` file descriptors attached.
+    /// The received descriptors are close-on-exec on Linux, and the ones beyond `max_fds` are
+    /// closed.
+    pub async fn recv_with_fds<T: IoBufMut>(
+        &self,
+        buf: T,
+        max_fds: usize,
+    ) -> crate::BufResult<(usize, Vec<OwnedFd>), T> {
+        let (res, buf) = ancillary::recv(&self.fd, buf, max_fds, false).await;
+        (res.map(|(n, received)| (n, received.fds)), buf)
+    }
+
+    /// Sends data to the given address with the file descriptors attached as
+    /// `SCM_RIGHTS`.
+    pub async fn send_to_addr_with_fds<T: IoBuf>(
+        &self,
+        buf: T,
+        fds: &[BorrowedFd<'_>],
+        addr: &SocketAddr,
+    ) -> crate::BufResult<usize, T> {
+        ancillary::send_with_fds(&self.fd, buf, fds, Some(addr.clone())).await
+    }
+
+    /// Receives a single datagram message with at most `max_fds` file descriptors
+    /// attached. On success, returns the number of bytes read, the origin and the
+    /// descriptors.
+    pub async fn recv_from_with_fds<T: IoBufMut>(
+        &self,
+        buf: T,
+        max_fds: usize,
+    ) -> crate::BufResult<(usize, SocketAddr, Vec<OwnedFd>), T> {
+        let (res, buf) = ancillary::recv(&self.fd, buf, max_fds, false).await;
+        (
+            res.map(|(n, received)| (n, received.addr, received.fds)),
+            buf,
+        )
+    }
+
+    /// Sets the value of the `SO_PASSCRED` option. If enabled, the credentials of the
+    /// sender are received with [`recv_with_cred`](Self::recv_with_cred).
+    #[cfg(target_os = "linux")]
+    pub fn set_passcred(&self, passcred: bool) -> io::Result<()> {
+        crate::net::cmsg::set_int_opt(
+            self.fd.raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_PASSCRED,
+            passcred as libc::c_int,
+        )
+    }
+
+    /// Receives data from the connected address with the `SCM_CREDENTIALS` of the sender, which are
+    /// verified by the kernel. They are only reported if `SO_PASSCRED` is enabled with
+    /// [`set_passcred`](Self::set_passcred).
+    #[cfg(target_os = "linux")]
+    pub async fn recv_with_cred<T: IoBufMut>(
+        &self,
+        buf: T,
+    ) -> crate::BufResult<(usize, Option<UCred>), T> {
+        let (res, buf) = ancillary::recv(&self.fd, buf, 0, true).await;
+        (res.map(|(n, received)| (n, received.cred)), buf)
+    }
 }
 
 impl AsRawFd for UnixDatagram {
@@ -5,7 +5,10 @@ use std::{
     path::Path,
 };
 
-use super::{socket_addr::SocketAddr, UnixStream};
+use super::{
+    socket_addr::{socket_addr, SocketAddr},
+    UnixStream,
+};
 use crate::{
     driver::{op::Op, shared_fd::SharedFd},
     io::{stream::Stream, CancelHandle},
@@ -29,16 +32,35 @@ impl UnixListener {
 
     /// Creates a new `UnixListener` bound to the specified socket with custom
     /// config.
+    ///
+    /// On Linux, a path starting with a null byte is an address in the abstract namespace.
     pub fn bind_with_config<P: AsRef<Path>>(
         path: P,
         config: &ListenerOpts,
+    ) -> io::Result<UnixListener> {
+        let (addr, addr_len) = socket_addr(path.as_ref())?;
+        Self::bind_addr_with_config(&SocketAddr::from_parts(addr, addr_len), config)
+    }
+
+    /// Creates a new `UnixListener` bound to the specified address with custom config.
+    ///
+    /// The address can be one in the abstract namespace created with
+    /// [`SocketAddr::from_abstract_name`]. On Linux, binding to an unnamed address
+    /// autobinds the listener to a unique abstract name, which is reported by
+    /// [`local_addr`](Self::local_addr).
+    pub fn bind_addr_with_config(
+        addr: &SocketAddr,
+        config: &ListenerOpts,
     ) -> io::Result<UnixListener> {
         let sys_listener =
             socket2::Socket::new(socket2::Domain::UNIX, socket2::Type::STREAM, None)?;
-        let addr = socket2::SockAddr::unix(path)?;
 
         if config.reuse_port {
-            sys_listener.set_reuse_port(true)?;
+            // Newer kernels reject SO_REUSEPORT on Unix sockets, where it never had effect.
+            match sys_listener.set_reuse_port(true) {
+                Err(e) if e.raw_os_error() == Some(libc::EOPNOTSUPP) => (),
+                res => res?,
+            }
         }
         if config.reuse_addr {
             sys_listener.set_reuse_address(true)?;
@@ -50,7 +72,11 @@ impl UnixListener {
             sys_listener.set_recv_buffer_size(recv_buf_size)?;
         }
 
-        sys_listener.bind(&addr)?;
+        crate::syscall!(bind@RAW(
+            sys_listener.as_raw_fd(),
+            addr.as_ptr() as *const libc::sockaddr,
+            addr.len()
+        ))?;
         sys_listener.listen(config.backlog)?;
 
         let fd = SharedFd::new::<false>(sys_listener.into_raw_fd())?;
@@ -64,6 +90,16 @@ impl UnixListener {
         Self::bind_with_config(path, &ListenerOpts::default())
     }
 
+    /// Creates a new `UnixListener` bound to the specified address with default config.
+    pub fn bind_addr(addr: &SocketAddr) -> io::Result<UnixListener> {
+        Self::bind_addr_with_config(addr, &ListenerOpts::default())
+    }
+
+    /// Returns the local socket address of this listener.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        super::socket_addr::local_addr(self.fd.raw_fd())
+    }
+
     /// Accept
     pub async fn accept(&self) -> io::Result<(UnixStream, SocketAddr)> {
         let op = Op::accept(&self.fd)?;
@@ -1,6 +1,7 @@
 #![allow(unreachable_pub)]
 //! Unix related.
 
+mod ancillary;
 mod datagram;
 mod listener;
 mod pipe;
@@ -19,6 +20,7 @@ pub use seq_packet::{UnixSeqpacket, UnixSeqpacketListener};
 pub use socket_addr::SocketAddr;
 pub use split::{UnixOwnedReadHalf, UnixOwnedWriteHalf};
 pub use stream::UnixStream;
+pub use ucred::UCred;
 
 #[cfg(feature = "poll-io")]
 pub mod stream_poll;
@@ -91,6 +91,45 @@ impl SocketAddr {
         (self.sockaddr, self.socklen)
     }
 
+    /// Constructs a `SocketAddr` with the family `AF_UNIX` and the provided path.
+    ///
+    /// Returns an error if the path is longer than `SUN_LEN` or if it contains NULL bytes.
+    pub fn from_pathname<P: AsRef<Path>>(path: P) -> io::Result<SocketAddr> {
+        let bytes = path.as_ref().as_os_str().as_bytes();
+        if bytes.contains(&0) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "paths must not contain interior null bytes",
+            ));
+        }
+        let (sockaddr, socklen) = socket_addr(path.as_ref())?;
+        Ok(SocketAddr::from_parts(sockaddr, socklen))
+    }
+
+    /// Creates a Unix socket address in the abstract namespace, which is a Linux-specific
+    /// extension. The name does not include the leading null byte, and can contain any
+    /// bytes.
+    ///
+    /// Returns an error if the name is longer than `SUN_LEN - 1`.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn from_abstract_name<N: AsRef<[u8]>>(name: N) -> io::Result<SocketAddr> {
+        let name = name.as_ref();
+        let mut sockaddr: libc::sockaddr_un = unsafe { mem::zeroed() };
+        sockaddr.sun_family = libc::AF_UNIX as libc::sa_family_t;
+        if name.len() + 1 > sockaddr.sun_path.len() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "abstract socket name must be shorter than SUN_LEN",
+            ));
+        }
+        // The first byte is left zero.
+        for (dst, src) in sockaddr.sun_path[1..].iter_mut().zip(name) {
+            *dst = *src as libc::c_char;
+        }
+        let socklen = path_offset(&sockaddr) + 1 + name.len();
+        Ok(SocketAddr::from_parts(sockaddr, socklen as libc::socklen_t))
+    }
+
     /// Returns `true` if the address is unnamed.
     ///
     /// Documentation reflected in [`SocketAddr`]
@@ -1,11 +1,15 @@
 use std::{
     future::Future,
     io::{self},
-    os::unix::prelude::{AsRawFd, FromRawFd, IntoRawFd, RawFd},
+    os::{
+        fd::{BorrowedFd, OwnedFd},
+        unix::prelude::{AsRawFd, FromRawFd, IntoRawFd, RawFd},
+    },
     path::Path,
 };
 
 use super::{
+    ancillary,
     socket_addr::{local_addr, pair, peer_addr, socket_addr, SocketAddr},
     ucred::UCred,
 };
@@ -57,7 +61,7 @@ impl UnixStream {
         completion.meta.result?;
 
         let stream = Self::from_shared_fd(completion.data.fd);
-        if crate::driver::op::is_legacy() {
+        if stream.fd.registered_index().is_some() {
             stream.writable(true).await?;
         }
         // getsockopt
@@ -95,6 +99,66 @@ impl UnixStream {
         }
     }
 
+    /// Sends data with a file descriptor attached as `SCM_RIGHTS`. The receiver gets a
+    /// duplicate of the descriptor, which refers to the same open file.
+    pub async fn send_with_fd<T: IoBuf>(&self, buf: T, fd: BorrowedFd<'_>) -> BufResult<usize, T> {
+        self.send_with_fds(buf, &[fd]).await
+    }
+
+    /// Sends data with the file descriptors attached as `SCM_RIGHTS`. At least one byte of
+    /// data must be sent with the descriptors.
+    pub async fn send_with_fds<T: IoBuf>(
+        &self,
+        buf: T,
+        fds: &[BorrowedFd<'_>],
+    ) -> BufResult<usize, T> {
+        ancillary::send_with_fds(&self.fd, buf, fds, None).await
+    }
+
+    /// Receives data with at most one file descriptor attached.
+    pub async fn recv_with_fd<T: IoBufMut>(
+        &self,
+        buf: T,
+    ) -> BufResult<(usize, Option<OwnedFd>), T> {
+        let (res, buf) = self.recv_with_fds(buf, 1).await;
+        (res.map(|(n, fds)| (n, fds.into_iter().next())), buf)
+    }
+
+    /// Receives data with at most `max_fds` file descriptors attached. The received
+    /// descriptors are close-on-exec on Linux, and the ones beyond `max_fds` are closed.
+    pub async fn recv_with_fds<T: IoBufMut>(
+        &self,
+        buf: T,
+        max_fds: usize,
+    ) -> BufResult<(usize, Vec<OwnedFd>), T> {
+        let (res, buf) = ancillary::recv(&self.fd, buf, max_fds, false).await;
+        (res.map(|(n, received)| (n, received.fds)), buf)
+    }
+
+    /// Sets the value of the `SO_PASSCRED` option. If enabled, the credentials of the
+    /// sender are received with [`recv_with_cred`](Self::recv_with_cred).
+    #[cfg(target_os = "linux")]
+    pub fn set_passcred(&self, passcred: bool) -> io::Result<()> {
+        crate::net::cmsg::set_int_opt(
+            self.fd.raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_PASSCRED,
+            passcred as libc::c_int,
+        )
+    }
+
+    /// Receives data with the `SCM_CREDENTIALS` of the sender, which are verified by
+    /// the kernel. They are only reported if `SO_PASSCRED` is enabled with
+    /// [`set_passcred`](Self::set_passcred).
+    #[cfg(target_os = "linux")]
+    pub async fn recv_with_cred<T: IoBufMut>(
+        &self,
+        buf: T,
+    ) -> BufResult<(usize, Option<UCred>), T> {
+        let (res, buf) = ancillary::recv(&self.fd, buf, 0, true).await;
+        (res.map(|(n, received)| (n, received.cred)), buf)
+    }
+
     /// Returns the socket address of the local half of this connection.
     pub fn local_addr(&self) -> io::Result<SocketAddr> {
         local_addr(self.as_raw_fd())
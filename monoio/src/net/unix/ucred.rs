@@ -13,6 +13,11 @@ pub struct UCred {
 }
 
 impl UCred {
+    #[cfg(target_os = "linux")]
+    pub(crate) fn new(uid: uid_t, gid: gid_t, pid: Option<pid_t>) -> Self {
+        Self { pid, uid, gid }
+    }
+
     /// Gets UID (user ID) of the process.
     #[inline]
     pub fn uid(&self) -> uid_t {
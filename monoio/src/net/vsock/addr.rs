@@ -0,0 +1,79 @@
+use std::{fmt, io, mem, os::fd::RawFd};
+
+/// An address of a vsock socket, which is a pair of a context identifier(CID) and a port.
+///
+/// The CID identifies the VM or the host, e.g. [`VsockAddr::CID_HOST`] is the host seen
+/// from a guest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct VsockAddr {
+    cid: u32,
+    port: u32,
+}
+
+impl VsockAddr {
+    /// Binds to any CID of the local machine.
+    pub const CID_ANY: u32 = libc::VMADDR_CID_ANY;
+    /// The local machine, for the communication between the processes on the same host.
+    /// It requires the `vsock_loopback` transport.
+    pub const CID_LOCAL: u32 = libc::VMADDR_CID_LOCAL;
+    /// The host, usually used by the guests to connect to the host.
+    pub const CID_HOST: u32 = libc::VMADDR_CID_HOST;
+    /// Binds to a free port picked by the kernel.
+    pub const PORT_ANY: u32 = libc::VMADDR_PORT_ANY;
+
+    /// Create a vsock address from the CID and the port.
+    #[inline]
+    pub const fn new(cid: u32, port: u32) -> Self {
+        Self { cid, port }
+    }
+
+    /// Returns the context identifier.
+    #[inline]
+    pub const fn cid(&self) -> u32 {
+        self.cid
+    }
+
+    /// Returns the port.
+    #[inline]
+    pub const fn port(&self) -> u32 {
+        self.port
+    }
+
+    pub(crate) fn to_raw(self) -> libc::sockaddr_vm {
+        // Safety: all zero is a valid `sockaddr_vm`.
+        let mut addr: libc::sockaddr_vm = unsafe { mem::zeroed() };
+        addr.svm_family = libc::AF_VSOCK as libc::sa_family_t;
+        addr.svm_cid = self.cid;
+        addr.svm_port = self.port;
+        addr
+    }
+
+    pub(crate) fn from_raw(addr: &libc::sockaddr_vm) -> Self {
+        Self::new(addr.svm_cid, addr.svm_port)
+    }
+}
+
+impl fmt::Display for VsockAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.cid, self.port)
+    }
+}
+
+pub(crate) fn local_addr(fd: RawFd) -> io::Result<VsockAddr> {
+    sock_name(|addr, len| crate::syscall!(getsockname@RAW(fd, addr, len)))
+}
+
+pub(crate) fn peer_addr(fd: RawFd) -> io::Result<VsockAddr> {
+    sock_name(|addr, len| crate::syscall!(getpeername@RAW(fd, addr, len)))
+}
+
+fn sock_name<F>(f: F) -> io::Result<VsockAddr>
+where
+    F: FnOnce(*mut libc::sockaddr, &mut libc::socklen_t) -> io::Result<libc::c_int>,
+{
+    // Safety: all zero is a valid `sockaddr_vm`.
+    let mut addr: libc::sockaddr_vm = unsafe { mem::zeroed() };
+    let mut len = mem::size_of::<libc::sockaddr_vm>() as libc::socklen_t;
+    f(&mut addr as *mut _ as *mut libc::sockaddr, &mut len)?;
+    Ok(VsockAddr::from_raw(&addr))
+}
@@ -0,0 +1,146 @@
+use std::{
+    io,
+    os::unix::prelude::{AsRawFd, IntoRawFd, RawFd},
+};
+
+use super::{
+    addr::{local_addr, VsockAddr},
+    VsockStream,
+};
+use crate::{
+    driver::{op::Op, shared_fd::SharedFd},
+    io::{stream::Stream, CancelHandle},
+    net::{new_socket, ListenerOpts},
+};
+
+/// A vsock listener.
+pub struct VsockListener {
+    fd: SharedFd,
+}
+
+impl VsockListener {
+    /// Creates a new `VsockListener` bound to the specified address with custom config.
+    ///
+    /// Only the backlog of the config is used, the other options do not apply to vsock.
+    pub fn bind_with_config(addr: VsockAddr, config: &ListenerOpts) -> io::Result<VsockListener> {
+        let socket = new_socket(libc::AF_VSOCK, libc::SOCK_STREAM)?;
+        let fd = SharedFd::new::<false>(socket)?;
+        let addr = addr.to_raw();
+        crate::syscall!(bind@RAW(
+            socket,
+            &addr as *const _ as *const libc::sockaddr,
+            std::mem::size_of::<libc::sockaddr_vm>() as libc::socklen_t
+        ))?;
+        crate::syscall!(listen@RAW(socket, config.backlog))?;
+        Ok(Self { fd })
+    }
+
+    /// Creates a new `VsockListener` bound to the specified address with default config.
+    ///
+    /// Use [`VsockAddr::CID_ANY`] to accept connections to any CID of the local machine,
+    /// and [`VsockAddr::PORT_ANY`] to let the kernel pick a port.
+    pub fn bind(addr: VsockAddr) -> io::Result<VsockListener> {
+        Self::bind_with_config(addr, &ListenerOpts::default())
+    }
+
+    /// Accept
+    pub async fn accept(&self) -> io::Result<(VsockStream, VsockAddr)> {
+        let op = Op::accept(&self.fd)?;
+
+        // Await the completion of the event
+        let completion = op.await;
+
+        // Convert fd
+        let fd = completion.meta.result?;
+
+        // Construct stream
+        let stream = VsockStream::from_shared_fd(SharedFd::new::<false>(fd.into_inner() as _)?);
+
+        // Construct VsockAddr
+        let storage = unsafe { completion.data.addr.0.assume_init_ref() };
+        let addr =
+            VsockAddr::from_raw(unsafe { &*(storage as *const _ as *const libc::sockaddr_vm) });
+
+        Ok((stream, addr))
+    }
+
+    /// Cancelable accept
+    pub async fn cancelable_accept(&self, c: CancelHandle) -> io::Result<(VsockStream, VsockAddr)> {
+        use crate::io::operation_canceled;
+
+        if c.canceled() {
+            return Err(operation_canceled());
+        }
+        let op = Op::accept(&self.fd)?;
+        let _guard = c.associate_op(op.op_canceller());
+
+        // Await the completion of the event
+        let completion = op.await;
+
+        // Convert fd
+        let fd = completion.meta.result?;
+
+        // Construct stream
+        let stream = VsockStream::from_shared_fd(SharedFd::new::<false>(fd.into_inner() as _)?);
+
+        // Construct VsockAddr
+        let storage = unsafe { completion.data.addr.0.assume_init_ref() };
+        let addr =
+            VsockAddr::from_raw(unsafe { &*(storage as *const _ as *const libc::sockaddr_vm) });
+
+        Ok((stream, addr))
+    }
+
+    /// Returns the local address that this listener is bound to.
+    pub fn local_addr(&self) -> io::Result<VsockAddr> {
+        local_addr(self.fd.raw_fd())
+    }
+
+    /// Wait for read readiness.
+    /// Note: Do not use it before every io. It is different from other runtimes!
+    ///
+    /// Everytime call to this method may pay a syscall cost.
+    /// In uring impl, it will push a PollAdd op; in epoll impl, it will use use
+    /// inner readiness state; if !relaxed, it will call syscall poll after that.
+    ///
+    /// If relaxed, on legacy driver it may return false positive result.
+    /// If you want to do io by your own, you must maintain io readiness and wait
+    /// for io ready with relaxed=false.
+    pub async fn readable(&self, relaxed: bool) -> io::Result<()> {
+        let op = Op::poll_read(&self.fd, relaxed).unwrap();
+        op.wait().await
+    }
+}
+
+impl Stream for VsockListener {
+    type Item = io::Result<(VsockStream, VsockAddr)>;
+
+    #[inline]
+    async fn next(&mut self) -> Option<Self::Item> {
+        Some(self.accept().await)
+    }
+}
+
+impl std::fmt::Debug for VsockListener {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VsockListener")
+            .field("fd", &self.fd)
+            .finish()
+    }
+}
+
+impl IntoRawFd for VsockListener {
+    #[inline]
+    fn into_raw_fd(self) -> RawFd {
+        self.fd
+            .try_unwrap()
+            .expect("unexpected multiple reference to rawfd")
+    }
+}
+
+impl AsRawFd for VsockListener {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.raw_fd()
+    }
+}
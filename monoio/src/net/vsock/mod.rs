@@ -0,0 +1,9 @@
+//! Vsock(`AF_VSOCK`) related, for the communication between VMs and their host.
+
+mod addr;
+mod listener;
+mod stream;
+
+pub use addr::VsockAddr;
+pub use listener::VsockListener;
+pub use stream::VsockStream;
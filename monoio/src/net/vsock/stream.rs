@@ -0,0 +1,262 @@
+use std::{
+    future::Future,
+    io,
+    os::unix::prelude::{AsRawFd, FromRawFd, IntoRawFd, RawFd},
+};
+
+use super::addr::{local_addr, peer_addr, VsockAddr};
+use crate::{
+    buf::{IoBuf, IoBufMut, IoVecBuf, IoVecBufMut},
+    driver::{op::Op, shared_fd::SharedFd},
+    io::{
+        as_fd::{AsReadFd, AsWriteFd, SharedFdWrapper},
+        operation_canceled, AsyncReadRent, AsyncWriteRent, CancelHandle, CancelableAsyncReadRent,
+        CancelableAsyncWriteRent, Split,
+    },
+    net::new_socket,
+    BufResult,
+};
+
+/// A vsock stream between a VM and its host, or the VMs on the same host.
+pub struct VsockStream {
+    fd: SharedFd,
+}
+
+/// VsockStream is safe to split to two parts
+unsafe impl Split for VsockStream {}
+
+impl VsockStream {
+    pub(crate) fn from_shared_fd(fd: SharedFd) -> Self {
+        Self { fd }
+    }
+
+    /// Opens a vsock connection to a remote address.
+    pub async fn connect(addr: VsockAddr) -> io::Result<Self> {
+        let socket = new_socket(libc::AF_VSOCK, libc::SOCK_STREAM)?;
+        let op = Op::connect_vsock(SharedFd::new::<false>(socket)?, addr.to_raw())?;
+        let completion = op.await;
+        completion.meta.result?;
+
+        let stream = Self::from_shared_fd(completion.data.fd);
+        if stream.fd.registered_index().is_some() {
+            stream.writable(true).await?;
+        }
+        // getsockopt
+        let sys_socket = unsafe { socket2::Socket::from_raw_fd(stream.fd.raw_fd()) };
+        let err = sys_socket.take_error();
+        let _ = sys_socket.into_raw_fd();
+        if let Some(e) = err? {
+            return Err(e);
+        }
+        Ok(stream)
+    }
+
+    /// Returns the socket address of the local half of this connection.
+    pub fn local_addr(&self) -> io::Result<VsockAddr> {
+        local_addr(self.as_raw_fd())
+    }
+
+    /// Returns the socket address of the remote half of this connection.
+    pub fn peer_addr(&self) -> io::Result<VsockAddr> {
+        peer_addr(self.as_raw_fd())
+    }
+
+    /// Wait for read readiness.
+    /// Note: Do not use it before every io. It is different from other runtimes!
+    ///
+    /// Everytime call to this method may pay a syscall cost.
+    /// In uring impl, it will push a PollAdd op; in epoll impl, it will use use
+    /// inner readiness state; if !relaxed, it will call syscall poll after that.
+    ///
+    /// If relaxed, on legacy driver it may return false positive result.
+    /// If you want to do io by your own, you must maintain io readiness and wait
+    /// for io ready with relaxed=false.
+    pub async fn readable(&self, relaxed: bool) -> io::Result<()> {
+        let op = Op::poll_read(&self.fd, relaxed).unwrap();
+        op.wait().await
+    }
+
+    /// Wait for write readiness.
+    /// Note: Do not use it before every io. It is different from other runtimes!
+    ///
+    /// Everytime call to this method may pay a syscall cost.
+    /// In uring impl, it will push a PollAdd op; in epoll impl, it will use use
+    /// inner readiness state; if !relaxed, it will call syscall poll after that.
+    ///
+    /// If relaxed, on legacy driver it may return false positive result.
+    /// If you want to do io by your own, you must maintain io readiness and wait
+    /// for io ready with relaxed=false.
+    pub async fn writable(&self, relaxed: bool) -> io::Result<()> {
+        let op = Op::poll_write(&self.fd, relaxed).unwrap();
+        op.wait().await
+    }
+}
+
+impl AsReadFd for VsockStream {
+    #[inline]
+    fn as_reader_fd(&mut self) -> &SharedFdWrapper {
+        SharedFdWrapper::new(&self.fd)
+    }
+}
+
+impl AsWriteFd for VsockStream {
+    #[inline]
+    fn as_writer_fd(&mut self) -> &SharedFdWrapper {
+        SharedFdWrapper::new(&self.fd)
+    }
+}
+
+impl IntoRawFd for VsockStream {
+    #[inline]
+    fn into_raw_fd(self) -> RawFd {
+        self.fd
+            .try_unwrap()
+            .expect("unexpected multiple reference to rawfd")
+    }
+}
+
+impl AsRawFd for VsockStream {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.raw_fd()
+    }
+}
+
+impl std::fmt::Debug for VsockStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VsockStream").field("fd", &self.fd).finish()
+    }
+}
+
+impl AsyncWriteRent for VsockStream {
+    #[inline]
+    fn write<T: IoBuf>(&mut self, buf: T) -> impl Future<Output = BufResult<usize, T>> {
+        // Submit the write operation
+        let op = Op::send(self.fd.clone(), buf).unwrap();
+        op.result()
+    }
+
+    #[inline]
+    fn writev<T: IoVecBuf>(&mut self, buf_vec: T) -> impl Future<Output = BufResult<usize, T>> {
+        let op = Op::writev(self.fd.clone(), buf_vec).unwrap();
+        op.result()
+    }
+
+    #[inline]
+    async fn flush(&mut self) -> std::io::Result<()> {
+        // Vsock stream does not need flush.
+        Ok(())
+    }
+
+    fn shutdown(&mut self) -> impl Future<Output = std::io::Result<()>> {
+        let fd = self.as_raw_fd();
+        async move {
+            match unsafe { libc::shutdown(fd, libc::SHUT_WR) } {
+                -1 => Err(io::Error::last_os_error()),
+                _ => Ok(()),
+            }
+        }
+    }
+}
+
+impl CancelableAsyncWriteRent for VsockStream {
+    #[inline]
+    async fn cancelable_write<T: IoBuf>(
+        &mut self,
+        buf: T,
+        c: CancelHandle,
+    ) -> crate::BufResult<usize, T> {
+        let fd = self.fd.clone();
+
+        if c.canceled() {
+            return (Err(operation_canceled()), buf);
+        }
+
+        let op = Op::send(fd, buf).unwrap();
+        let _guard = c.associate_op(op.op_canceller());
+        op.result().await
+    }
+
+    #[inline]
+    async fn cancelable_writev<T: IoVecBuf>(
+        &mut self,
+        buf_vec: T,
+        c: CancelHandle,
+    ) -> crate::BufResult<usize, T> {
+        let fd = self.fd.clone();
+
+        if c.canceled() {
+            return (Err(operation_canceled()), buf_vec);
+        }
+
+        let op = Op::writev(fd.clone(), buf_vec).unwrap();
+        let _guard = c.associate_op(op.op_canceller());
+        op.result().await
+    }
+
+    #[inline]
+    async fn cancelable_flush(&mut self, _c: CancelHandle) -> io::Result<()> {
+        // Vsock stream does not need flush.
+        Ok(())
+    }
+
+    async fn cancelable_shutdown(&mut self, _c: CancelHandle) -> io::Result<()> {
+        let fd = self.as_raw_fd();
+        match unsafe { libc::shutdown(fd, libc::SHUT_WR) } {
+            -1 => Err(io::Error::last_os_error()),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl AsyncReadRent for VsockStream {
+    #[inline]
+    fn read<T: IoBufMut>(&mut self, buf: T) -> impl Future<Output = BufResult<usize, T>> {
+        // Submit the read operation
+        let op = Op::recv(self.fd.clone(), buf).unwrap();
+        op.result()
+    }
+
+    #[inline]
+    fn readv<T: IoVecBufMut>(&mut self, buf: T) -> impl Future<Output = BufResult<usize, T>> {
+        // Submit the read operation
+        let op = Op::readv(self.fd.clone(), buf).unwrap();
+        op.result()
+    }
+}
+
+impl CancelableAsyncReadRent for VsockStream {
+    #[inline]
+    async fn cancelable_read<T: IoBufMut>(
+        &mut self,
+        buf: T,
+        c: CancelHandle,
+    ) -> crate::BufResult<usize, T> {
+        let fd = self.fd.clone();
+
+        if c.canceled() {
+            return (Err(operation_canceled()), buf);
+        }
+
+        let op = Op::recv(fd, buf).unwrap();
+        let _guard = c.associate_op(op.op_canceller());
+        op.result().await
+    }
+
+    #[inline]
+    async fn cancelable_readv<T: IoVecBufMut>(
+        &mut self,
+        buf: T,
+        c: CancelHandle,
+    ) -> crate::BufResult<usize, T> {
+        let fd = self.fd.clone();
+
+        if c.canceled() {
+            return (Err(operation_canceled()), buf);
+        }
+
+        let op = Op::readv(fd, buf).unwrap();
+        let _guard = c.associate_op(op.op_canceller());
+        op.result().await
+    }
+}
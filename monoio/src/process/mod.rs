@@ -0,0 +1,247 @@
+//! Child processes(Linux only).
+//!
+//! A wrapper of [`std::process::Command`], whose stdio pipes do async io on the driver, and
+//! whose exit is awaited with a pidfd(requires kernel 5.3+), so shelling out does not block
+//! the thread.
+
+mod pidfd;
+mod stdio;
+
+use std::{
+    ffi::OsStr,
+    io,
+    path::Path,
+    process::{ExitStatus, Output, Stdio},
+};
+
+pub use pidfd::PidFd;
+pub use stdio::{ChildStderr, ChildStdin, ChildStdout};
+
+/// A process builder, mirroring [`std::process::Command`].
+#[derive(Debug)]
+pub struct Command {
+    std: std::process::Command,
+    stdin_set: bool,
+    stdout_set: bool,
+    stderr_set: bool,
+}
+
+impl Command {
+    /// Constructs a new `Command` for launching the program, see
+    /// [`std::process::Command::new`].
+    pub fn new<S: AsRef<OsStr>>(program: S) -> Command {
+        std::process::Command::new(program).into()
+    }
+
+    /// Adds an argument to pass to the program.
+    pub fn arg<S: AsRef<OsStr>>(&mut self, arg: S) -> &mut Command {
+        self.std.arg(arg);
+        self
+    }
+
+    /// Adds multiple arguments to pass to the program.
+    pub fn args<I, S>(&mut self, args: I) -> &mut Command
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        self.std.args(args);
+        self
+    }
+
+    /// Inserts or updates an environment variable of the child process.
+    pub fn env<K, V>(&mut self, key: K, val: V) -> &mut Command
+    where
+        K: AsRef<OsStr>,
+        V: AsRef<OsStr>,
+    {
+        self.std.env(key, val);
+        self
+    }
+
+    /// Inserts or updates multiple environment variables of the child process.
+    pub fn envs<I, K, V>(&mut self, vars: I) -> &mut Command
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<OsStr>,
+        V: AsRef<OsStr>,
+    {
+        self.std.envs(vars);
+        self
+    }
+
+    /// Removes an environment variable of the child process.
+    pub fn env_remove<K: AsRef<OsStr>>(&mut self, key: K) -> &mut Command {
+        self.std.env_remove(key);
+        self
+    }
+
+    /// Clears all the environment variables of the child process.
+    pub fn env_clear(&mut self) -> &mut Command {
+        self.std.env_clear();
+        self
+    }
+
+    /// Sets the working directory of the child process.
+    pub fn current_dir<P: AsRef<Path>>(&mut self, dir: P) -> &mut Command {
+        self.std.current_dir(dir);
+        self
+    }
+
+    /// Sets the stdin of the child process. Use [`Stdio::piped`] to write to it with
+    /// [`Child::stdin`].
+    pub fn stdin<T: Into<Stdio>>(&mut self, cfg: T) -> &mut Command {
+        self.std.stdin(cfg);
+        self.stdin_set = true;
+        self
+    }
+
+    /// Sets the stdout of the child process. Use [`Stdio::piped`] to read from it with
+    /// [`Child::stdout`].
+    pub fn stdout<T: Into<Stdio>>(&mut self, cfg: T) -> &mut Command {
+        self.std.stdout(cfg);
+        self.stdout_set = true;
+        self
+    }
+
+    /// Sets the stderr of the child process. Use [`Stdio::piped`] to read from it with
+    /// [`Child::stderr`].
+    pub fn stderr<T: Into<Stdio>>(&mut self, cfg: T) -> &mut Command {
+        self.std.stderr(cfg);
+        self.stderr_set = true;
+        self
+    }
+
+    /// Returns a shared reference to the inner std `Command`.
+    #[inline]
+    pub fn as_std(&self) -> &std::process::Command {
+        &self.std
+    }
+
+    /// Returns a mutable reference to the inner std `Command`, to configure it with the
+    /// extension traits like [`std::os::unix::process::CommandExt`].
+    #[inline]
+    pub fn as_std_mut(&mut self) -> &mut std::process::Command {
+        &mut self.std
+    }
+
+    /// Executes the command as a child process, returning a handle to it. The stdio is
+    /// inherited by default.
+    pub fn spawn(&mut self) -> io::Result<Child> {
+        let mut child = self.std.spawn()?;
+        let stdin = child.stdin.take().map(stdio::register).transpose();
+        let stdout = child.stdout.take().map(stdio::register).transpose();
+        let stderr = child.stderr.take().map(stdio::register).transpose();
+        let (stdin, stdout, stderr) = match (stdin, stdout, stderr) {
+            (Ok(stdin), Ok(stdout), Ok(stderr)) => (stdin, stdout, stderr),
+            (Err(e), ..) | (_, Err(e), _) | (.., Err(e)) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(e);
+            }
+        };
+        Ok(Child {
+            stdin: stdin.map(ChildStdin::from_shared_fd),
+            stdout: stdout.map(ChildStdout::from_shared_fd),
+            stderr: stderr.map(ChildStderr::from_shared_fd),
+            std: child,
+            pidfd: None,
+        })
+    }
+
+    /// Executes the command as a child process, waiting for it to finish and collecting
+    /// its output. Unless configured, the stdin is null, and the stdout and stderr are
+    /// captured.
+    pub async fn output(&mut self) -> io::Result<Output> {
+        if !self.stdin_set {
+            self.std.stdin(Stdio::null());
+        }
+        if !self.stdout_set {
+            self.std.stdout(Stdio::piped());
+        }
+        if !self.stderr_set {
+            self.std.stderr(Stdio::piped());
+        }
+        self.spawn()?.wait_with_output().await
+    }
+
+    /// Executes the command as a child process, waiting for it to finish and returning its
+    /// exit status. The stdio is inherited by default.
+    pub async fn status(&mut self) -> io::Result<ExitStatus> {
+        self.spawn()?.wait().await
+    }
+}
+
+impl From<std::process::Command> for Command {
+    fn from(std: std::process::Command) -> Self {
+        Self {
+            std,
+            stdin_set: false,
+            stdout_set: false,
+            stderr_set: false,
+        }
+    }
+}
+
+/// A handle to a child process, mirroring [`std::process::Child`].
+///
+/// Like std, the child is not killed or waited when the handle is dropped.
+#[derive(Debug)]
+pub struct Child {
+    /// The handle writing to the stdin of the child, if it is piped.
+    pub stdin: Option<ChildStdin>,
+    /// The handle reading from the stdout of the child, if it is piped.
+    pub stdout: Option<ChildStdout>,
+    /// The handle reading from the stderr of the child, if it is piped.
+    pub stderr: Option<ChildStderr>,
+    std: std::process::Child,
+    pidfd: Option<PidFd>,
+}
+
+impl Child {
+    /// Returns the OS-assigned process identifier.
+    #[inline]
+    pub fn id(&self) -> u32 {
+        self.std.id()
+    }
+
+    /// Sends `SIGKILL` to the child without waiting for it to exit.
+    pub fn kill(&mut self) -> io::Result<()> {
+        self.std.kill()
+    }
+
+    /// Returns the exit status if the child has exited, without blocking.
+    pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
+        self.std.try_wait()
+    }
+
+    /// Waits for the child to exit, and returns its exit status. The stdin is closed before
+    /// waiting to avoid the deadlock of a child reading it.
+    pub async fn wait(&mut self) -> io::Result<ExitStatus> {
+        drop(self.stdin.take());
+        loop {
+            if let Some(status) = self.std.try_wait()? {
+                return Ok(status);
+            }
+            if self.pidfd.is_none() {
+                self.pidfd = Some(PidFd::open(self.std.id())?);
+            }
+            self.pidfd.as_ref().unwrap().exited().await?;
+        }
+    }
+
+    /// Waits for the child to exit while collecting the piped stdout and stderr.
+    pub async fn wait_with_output(mut self) -> io::Result<Output> {
+        drop(self.stdin.take());
+        let (stdout, stderr) = crate::join!(
+            stdio::read_to_end(self.stdout.take()),
+            stdio::read_to_end(self.stderr.take())
+        );
+        let status = self.wait().await?;
+        Ok(Output {
+            status,
+            stdout: stdout?,
+            stderr: stderr?,
+        })
+    }
+}
@@ -0,0 +1,121 @@
+use std::{
+    io,
+    os::unix::{
+        prelude::{AsRawFd, IntoRawFd, RawFd},
+        process::ExitStatusExt,
+    },
+    process::ExitStatus,
+};
+
+use crate::driver::{op::Op, shared_fd::SharedFd};
+
+/// A file descriptor referring to a process(requires kernel 5.3+).
+///
+/// Unlike a pid, it always refers to the same process even after the process exits and the
+/// pid is reused, so it is safe to signal. It becomes readable when the process exits,
+/// which can be awaited for any number of processes without a thread per process.
+pub struct PidFd {
+    fd: SharedFd,
+    pid: u32,
+}
+
+impl PidFd {
+    /// Open a pidfd for the process of the pid with `pidfd_open(2)`.
+    ///
+    /// To avoid referring to a reused pid, the process should be a child not waited yet,
+    /// or be checked to be the expected one after opening.
+    pub fn open(pid: u32) -> io::Result<PidFd> {
+        let fd = crate::syscall!(syscall@RAW(libc::SYS_pidfd_open, pid, 0))?;
+        Ok(Self {
+            fd: SharedFd::new::<false>(fd as _)?,
+            pid,
+        })
+    }
+
+    /// Returns the pid of the process.
+    #[inline]
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// Waits for the process to exit, without reaping it. It works for the processes other
+    /// than the children too.
+    pub async fn exited(&self) -> io::Result<()> {
+        Op::poll_read(&self.fd, false)?.wait().await
+    }
+
+    /// Waits for the child process to exit, and reaps it to return its exit status.
+    ///
+    /// It fails with `ECHILD` if the process is not a child of the current process, or has
+    /// been reaped.
+    pub async fn wait(&self) -> io::Result<ExitStatus> {
+        loop {
+            if let Some(status) = self.try_wait()? {
+                return Ok(status);
+            }
+            self.exited().await?;
+        }
+    }
+
+    /// Reaps the child process and returns its exit status if it has exited, without
+    /// blocking.
+    pub fn try_wait(&self) -> io::Result<Option<ExitStatus>> {
+        // Safety: all zero is a valid `siginfo_t`.
+        let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+        crate::syscall!(waitid@RAW(
+            libc::P_PIDFD,
+            self.fd.raw_fd() as libc::id_t,
+            &mut info,
+            libc::WEXITED | libc::WNOHANG
+        ))?;
+        // Safety: the pid and status are filled for the children reaped.
+        let (pid, status) = unsafe { (info.si_pid(), info.si_status()) };
+        if pid == 0 {
+            return Ok(None);
+        }
+        // Encode it like the status of `waitpid(2)`.
+        let raw = match info.si_code {
+            libc::CLD_EXITED => (status & 0xff) << 8,
+            libc::CLD_DUMPED => status | 0x80,
+            _ => status,
+        };
+        Ok(Some(ExitStatus::from_raw(raw)))
+    }
+
+    /// Sends the signal to the process with `pidfd_send_signal(2)`.
+    pub fn send_signal(&self, signal: libc::c_int) -> io::Result<()> {
+        crate::syscall!(syscall@RAW(
+            libc::SYS_pidfd_send_signal,
+            self.fd.raw_fd(),
+            signal,
+            std::ptr::null::<libc::siginfo_t>(),
+            0
+        ))?;
+        Ok(())
+    }
+}
+
+impl AsRawFd for PidFd {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.fd.raw_fd()
+    }
+}
+
+impl IntoRawFd for PidFd {
+    #[inline]
+    fn into_raw_fd(self) -> RawFd {
+        self.fd
+            .try_unwrap()
+            .expect("unexpected multiple reference to rawfd")
+    }
+}
+
+impl std::fmt::Debug for PidFd {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PidFd")
+            .field("fd", &self.fd)
+            .field("pid", &self.pid)
+            .finish()
+    }
+}
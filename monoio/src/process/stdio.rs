@@ -0,0 +1,146 @@
+use std::{
+    future::Future,
+    io,
+    os::unix::prelude::{AsRawFd, IntoRawFd, RawFd},
+};
+
+use crate::{
+    buf::{IoBuf, IoBufMut, IoVecBuf, IoVecBufMut},
+    driver::{op::Op, shared_fd::SharedFd},
+    io::{AsyncReadRent, AsyncWriteRent},
+    BufResult,
+};
+
+/// Register our end of a pipe of the child with the driver. The legacy driver requires it to
+/// be non-blocking.
+pub(super) fn register(fd: impl IntoRawFd) -> io::Result<SharedFd> {
+    let fd = fd.into_raw_fd();
+    if crate::driver::op::is_legacy() {
+        if let Err(e) = crate::syscall!(fcntl@RAW(fd, libc::F_SETFL, libc::O_NONBLOCK)) {
+            let _ = crate::syscall!(close@RAW(fd));
+            return Err(e);
+        }
+    }
+    SharedFd::new::<false>(fd)
+}
+
+macro_rules! child_pipe {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        pub struct $name {
+            fd: SharedFd,
+        }
+
+        impl $name {
+            pub(super) fn from_shared_fd(fd: SharedFd) -> Self {
+                Self { fd }
+            }
+        }
+
+        impl AsRawFd for $name {
+            #[inline]
+            fn as_raw_fd(&self) -> RawFd {
+                self.fd.raw_fd()
+            }
+        }
+
+        impl IntoRawFd for $name {
+            #[inline]
+            fn into_raw_fd(self) -> RawFd {
+                self.fd
+                    .try_unwrap()
+                    .expect("unexpected multiple reference to rawfd")
+            }
+        }
+
+        impl std::fmt::Debug for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct(stringify!($name))
+                    .field("fd", &self.fd)
+                    .finish()
+            }
+        }
+    };
+}
+
+macro_rules! impl_read {
+    ($name:ident) => {
+        impl AsyncReadRent for $name {
+            #[inline]
+            fn read<T: IoBufMut>(&mut self, buf: T) -> impl Future<Output = BufResult<usize, T>> {
+                let op = Op::read(self.fd.clone(), buf).unwrap();
+                op.result()
+            }
+
+            #[inline]
+            fn readv<T: IoVecBufMut>(
+                &mut self,
+                buf: T,
+            ) -> impl Future<Output = BufResult<usize, T>> {
+                let op = Op::readv(self.fd.clone(), buf).unwrap();
+                op.result()
+            }
+        }
+    };
+}
+
+child_pipe!(
+    /// The handle writing to the stdin of a child process, which is closed when it is
+    /// dropped.
+    ChildStdin
+);
+child_pipe!(
+    /// The handle reading from the stdout of a child process.
+    ChildStdout
+);
+child_pipe!(
+    /// The handle reading from the stderr of a child process.
+    ChildStderr
+);
+
+impl_read!(ChildStdout);
+impl_read!(ChildStderr);
+
+impl AsyncWriteRent for ChildStdin {
+    #[inline]
+    fn write<T: IoBuf>(&mut self, buf: T) -> impl Future<Output = BufResult<usize, T>> {
+        let op = Op::write(self.fd.clone(), buf).unwrap();
+        op.result()
+    }
+
+    #[inline]
+    fn writev<T: IoVecBuf>(&mut self, buf_vec: T) -> impl Future<Output = BufResult<usize, T>> {
+        let op = Op::writev(self.fd.clone(), buf_vec).unwrap();
+        op.result()
+    }
+
+    #[inline]
+    async fn flush(&mut self) -> io::Result<()> {
+        // Pipe does not need flush.
+        Ok(())
+    }
+
+    async fn shutdown(&mut self) -> io::Result<()> {
+        // The pipe is closed when the handle is dropped.
+        Ok(())
+    }
+}
+
+/// Read the pipe to the end.
+pub(super) async fn read_to_end<R: AsyncReadRent>(reader: Option<R>) -> io::Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    let Some(mut reader) = reader else {
+        return Ok(buf);
+    };
+    loop {
+        if buf.len() == buf.capacity() {
+            buf.reserve(4096);
+        }
+        let len = buf.len();
+        let (res, slice) = reader.read(buf.slice_mut(len..)).await;
+        buf = slice.into_inner();
+        if res? == 0 {
+            return Ok(buf);
+        }
+    }
+}
@@ -1,4 +1,9 @@
-use std::future::Future;
+//! Monoio runtime, task spawning and metrics.
+
+use std::{
+    future::Future,
+    time::{Duration, Instant},
+};
 
 #[cfg(any(all(target_os = "linux", feature = "iouring"), feature = "legacy"))]
 use crate::time::TimeDriver;
@@ -8,10 +13,10 @@ use crate::IoUringDriver;
 use crate::LegacyDriver;
 use crate::{
     driver::Driver,
-    scheduler::{LocalScheduler, TaskQueue},
+    scheduler::{LocalScheduler, Priority, SchedulerOpts, TaskQueue},
     task::{
         new_task,
-        waker_fn::{dummy_waker, set_poll, should_poll},
+        waker_fn::{dummy_waker, is_poll_set, set_poll, should_poll},
         JoinHandle,
     },
     time::driver::Handle as TimeHandle,
@@ -26,9 +31,38 @@ thread_local! {
         tasks: Default::default(),
         time_handle: None,
         blocking_handle: crate::blocking::BlockingHandle::Empty(crate::blocking::BlockingStrategy::Panic),
+        remote_wakers: None,
+        recent: Default::default(),
+        clock: Default::default(),
+        coop_budget: None,
+        max_tasks_per_tick: None,
+        metrics: Default::default(),
+        panic_policy: PanicPolicy::Propagate,
+        owned: Default::default(),
+        blocking_queued: Default::default(),
+        #[cfg(feature = "watchdog")]
+        watchdog: None,
     };
 }
 
+#[cfg(feature = "sync")]
+mod handle;
+#[cfg(feature = "tracing")]
+mod instrument;
+pub(crate) mod metrics;
+pub(crate) mod panic;
+mod shutdown;
+#[cfg(feature = "watchdog")]
+pub(crate) mod watchdog;
+#[cfg(feature = "sync")]
+pub use handle::{Handle, RemoteJoinHandle};
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+pub use metrics::SlowOp;
+pub use metrics::{metrics, op_latency, LatencyHistogram, RuntimeMetrics};
+pub use panic::{PanicPolicy, TaskPanic};
+#[cfg(feature = "watchdog")]
+pub use watchdog::{BlockedReactor, BlockedTask};
+
 scoped_thread_local!(pub(crate) static CURRENT: Context);
 
 pub(crate) struct Context {
@@ -54,6 +88,40 @@ pub(crate) struct Context {
     /// Blocking Handle
     #[cfg(feature = "sync")]
     pub(crate) blocking_handle: crate::blocking::BlockingHandle,
+
+    /// Wakers sent from other threads, only for drivers implemented outside monoio. The
+    /// builtin drivers receive them by themselves.
+    #[cfg(feature = "sync")]
+    pub(crate) remote_wakers: Option<flume::Receiver<std::task::Waker>>,
+
+    /// Timestamp cached for `Instant::recent`
+    pub(crate) recent: crate::time::Recent,
+
+    /// Clock of the runtime
+    pub(crate) clock: crate::time::ClockHandle,
+
+    /// Cooperative scheduling budget of a task poll, `None` means unconstrained
+    pub(crate) coop_budget: Option<u8>,
+
+    /// Max tasks to run before checking the driver
+    pub(crate) max_tasks_per_tick: Option<usize>,
+
+    /// Counters of the runtime
+    pub(crate) metrics: metrics::Metrics,
+
+    /// What to do when a spawned task panics
+    pub(crate) panic_policy: PanicPolicy,
+
+    /// Tasks spawned and not finished
+    pub(crate) owned: std::rc::Rc<shutdown::OwnedTasks>,
+
+    /// Blocking tasks scheduled to the thread pool and not started
+    #[cfg(feature = "sync")]
+    pub(crate) blocking_queued: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+
+    /// State shared with the watchdog thread
+    #[cfg(feature = "watchdog")]
+    pub(crate) watchdog: Option<std::sync::Arc<watchdog::Shared>>,
 }
 
 impl Context {
@@ -68,6 +136,17 @@ impl Context {
             tasks: TaskQueue::default(),
             time_handle: None,
             blocking_handle,
+            remote_wakers: None,
+            recent: Default::default(),
+            clock: Default::default(),
+            coop_budget: Some(crate::task::coop::DEFAULT_BUDGET),
+            max_tasks_per_tick: None,
+            metrics: Default::default(),
+            panic_policy: Default::default(),
+            owned: Default::default(),
+            #[cfg(feature = "watchdog")]
+            watchdog: None,
+            blocking_queued: Default::default(),
         }
     }
 
@@ -79,24 +158,91 @@ impl Context {
             thread_id,
             tasks: TaskQueue::default(),
             time_handle: None,
+            recent: Default::default(),
+            clock: Default::default(),
+            coop_budget: Some(crate::task::coop::DEFAULT_BUDGET),
+            max_tasks_per_tick: None,
+            metrics: Default::default(),
+            panic_policy: Default::default(),
+            owned: Default::default(),
+            #[cfg(feature = "watchdog")]
+            watchdog: None,
         }
     }
 
     #[allow(unused)]
     #[cfg(feature = "sync")]
     pub(crate) fn unpark_thread(&self, id: usize) {
-        use crate::driver::{thread::get_unpark_handle, unpark::Unpark};
-        if let Some(handle) = self.unpark_cache.borrow().get(&id) {
+        use crate::driver::unpark::Unpark;
+        if let Some(handle) = self.unpark_handle(id) {
             handle.unpark();
-            return;
         }
+    }
 
-        if let Some(v) = get_unpark_handle(id) {
-            // Write back to local cache
-            let w = v.clone();
-            self.unpark_cache.borrow_mut().insert(id, w);
-            v.unpark();
+    #[cfg(feature = "sync")]
+    fn unpark_handle(&self, id: usize) -> Option<crate::driver::UnparkHandle> {
+        use crate::driver::thread::get_unpark_handle;
+        if let Some(handle) = self.unpark_cache.borrow().get(&id) {
+            return Some(handle.clone());
         }
+
+        let v = get_unpark_handle(id)?;
+        // Write back to local cache
+        self.unpark_cache.borrow_mut().insert(id, v.clone());
+        Some(v)
+    }
+
+    /// Send the waker to the thread and unpark it.
+    #[cfg(feature = "sync")]
+    pub(crate) fn wake_thread(&self, id: usize, w: std::task::Waker) {
+        // Between uring runtimes, the waker is sent with MSG_RING which wakes the ring as well.
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        let w = match self.unpark_handle(id) {
+            Some(crate::driver::UnparkHandle::Uring(handle)) => match handle.send_waker(w, id) {
+                Ok(()) => return,
+                Err(w) => w,
+            },
+            _ => w,
+        };
+        self.send_waker(id, w);
+        self.unpark_thread(id);
+    }
+
+    /// Use the custom clock, or read `CLOCK_MONOTONIC_COARSE` for `Instant::recent` if
+    /// there is none.
+    pub(crate) fn with_clock(
+        mut self,
+        clock: Option<Box<dyn crate::time::Clock + Send>>,
+        coarse: bool,
+    ) -> Self {
+        let clock = clock.map(std::rc::Rc::<dyn crate::time::Clock + Send>::from);
+        self.recent = crate::time::Recent::new(coarse && clock.is_none());
+        self.clock = crate::time::ClockHandle::new(clock.map(|clock| clock as _));
+        self
+    }
+
+    /// Set the cooperative scheduling budget of a task poll.
+    pub(crate) fn with_coop_budget(mut self, budget: Option<u8>) -> Self {
+        self.coop_budget = budget;
+        self
+    }
+
+    /// Build the run queue with the settings.
+    pub(crate) fn with_scheduler_opts(mut self, opts: &SchedulerOpts) -> Self {
+        self.tasks = TaskQueue::with_opts(opts);
+        self.max_tasks_per_tick = opts.max_tasks_per_tick;
+        self
+    }
+
+    pub(crate) fn with_panic_policy(mut self, policy: PanicPolicy) -> Self {
+        self.panic_policy = policy;
+        self
+    }
+
+    #[cfg(feature = "watchdog")]
+    pub(crate) fn with_watchdog(mut self, watchdog: Option<watchdog::Watchdog>) -> Self {
+        self.watchdog = watchdog.map(watchdog::Shared::start);
+        self
     }
 
     #[allow(unused)]
@@ -114,6 +260,68 @@ impl Context {
             self.waker_sender_cache.borrow_mut().insert(id, s);
         }
     }
+
+    /// Wake the tasks woken by other threads, if the driver does not receive them.
+    #[cfg(feature = "sync")]
+    #[inline]
+    fn wake_remote(&self) {
+        if let Some(rx) = self.remote_wakers.as_ref() {
+            let mut n = 0;
+            while let Ok(w) = rx.try_recv() {
+                w.wake();
+                n += 1;
+            }
+            metrics::foreign_wakeups(n);
+        }
+    }
+
+    /// Run the ready tasks, at most `max_tasks_per_tick` or twice the tasks ready now(maybe
+    /// there's a looping task). Returns the number of tasks polled.
+    fn run_tasks(&self) -> usize {
+        let max_round = self
+            .max_tasks_per_tick
+            .unwrap_or(self.tasks.len() * 2)
+            .max(1);
+        let mut polled = 0;
+        while polled < max_round {
+            let Some(t) = self.tasks.pop() else {
+                break;
+            };
+            crate::task::coop::budget(self.coop_budget, || self.tasks.run(t));
+            self.metrics.task_polled();
+            polled += 1;
+        }
+        polled
+    }
+}
+
+/// If there are tasks to run, or the future blocked on is woken.
+#[cfg(feature = "test-util")]
+pub(crate) fn has_woken_tasks() -> bool {
+    crate::task::waker_fn::is_poll_set()
+        || (CURRENT.is_set() && CURRENT.with(|ctx| !ctx.tasks.is_empty()))
+}
+
+#[cfg(feature = "sync")]
+impl Drop for Context {
+    fn drop(&mut self) {
+        if self.remote_wakers.is_some() {
+            use crate::driver::thread::{unregister_unpark_handle, unregister_waker_sender};
+            unregister_unpark_handle(self.thread_id);
+            unregister_waker_sender(self.thread_id);
+        }
+    }
+}
+
+/// The result of [`Runtime::turn`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TurnResult {
+    /// Tasks polled in the turn.
+    pub polled: usize,
+    /// If there are tasks ready to run after the turn, i.e. the runtime should be turned again
+    /// without waiting.
+    pub has_ready: bool,
 }
 
 /// Monoio runtime
@@ -127,6 +335,18 @@ impl<D> Runtime<D> {
         Self { context, driver }
     }
 
+    /// Get a [`Handle`] to spawn tasks on the runtime from other threads.
+    #[cfg(feature = "sync")]
+    pub fn handle(&self) -> Handle {
+        Handle::new(self.context.thread_id)
+    }
+
+    /// Get the driver of the runtime, e.g. to get
+    /// [`Driver::completion_eventfd`](crate::Driver::completion_eventfd).
+    pub fn driver(&self) -> &D {
+        &self.driver
+    }
+
     /// Block on
     pub fn block_on<F>(&mut self, future: F) -> F::Output
     where
@@ -150,29 +370,27 @@ impl<D> Runtime<D> {
 
                 let mut join = std::pin::pin!(join);
                 set_poll();
+                #[cfg(feature = "watchdog")]
+                let _watchdog = self.context.watchdog.as_ref().map(|w| w.enter());
                 loop {
                     loop {
                         // Consume all tasks(with max round to prevent io starvation)
-                        let mut max_round = self.context.tasks.len() * 2;
-                        while let Some(t) = self.context.tasks.pop() {
-                            t.run();
-                            if max_round == 0 {
-                                // maybe there's a looping task
-                                break;
-                            } else {
-                                max_round -= 1;
-                            }
-                        }
+                        self.context.run_tasks();
 
-                        // Check main future
-                        while should_poll() {
+                        // Check main future, once a round so it can yield to the tasks and
+                        // the driver
+                        if should_poll() {
                             // check if ready
-                            if let std::task::Poll::Ready(t) = join.as_mut().poll(cx) {
+                            if let std::task::Poll::Ready(t) =
+                                crate::task::coop::budget(self.context.coop_budget, || {
+                                    join.as_mut().poll(cx)
+                                })
+                            {
                                 return t;
                             }
                         }
 
-                        if self.context.tasks.is_empty() {
+                        if self.context.tasks.is_empty() && !is_poll_set() {
                             // No task to execute, we should wait for io blockingly
                             // Hot path
                             break;
@@ -180,16 +398,211 @@ impl<D> Runtime<D> {
 
                         // Cold path
                         let _ = self.driver.submit();
+                        #[cfg(feature = "watchdog")]
+                        if let Some(watchdog) = self.context.watchdog.as_ref() {
+                            watchdog.tick();
+                        }
                     }
 
-                    // Wait and Process CQ(the error is ignored for not debug mode)
-                    #[cfg(not(all(debug_assertions, feature = "debug")))]
-                    let _ = self.driver.park();
+                    // Wait and Process CQ
+                    self.park(None);
+                }
+            })
+        })
+    }
+
+    /// Run one turn of the runtime, for embedding it into an external loop, e.g. the main loop
+    /// of a game engine or a GUI: wait at most `max_timeout` for io or timers if no task is
+    /// ready, then run the ready tasks. The tasks are spawned inside
+    /// [`block_on`](Runtime::block_on), and keep running in the turns after it returns.
+    ///
+    /// Note the turn may return before the timeout even if no task is woken.
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// let mut rt = monoio::RuntimeBuilder::<monoio::FusionDriver>::new()
+    ///     .enable_timer()
+    ///     .build()
+    ///     .unwrap();
+    /// let mut join = None;
+    /// rt.block_on(async {
+    ///     join = Some(monoio::spawn(async {
+    ///         monoio::time::sleep(Duration::from_millis(10)).await;
+    ///     }));
+    /// });
+    /// let join = join.unwrap();
+    /// while !join.is_finished() {
+    ///     // Do other work of the loop.
+    ///     rt.turn(Duration::from_millis(16));
+    /// }
+    /// ```
+    pub fn turn(&mut self, max_timeout: Duration) -> TurnResult
+    where
+        D: Driver,
+    {
+        assert!(!CURRENT.is_set(), "Can not turn a runtime inside a runtime");
+
+        self.driver.with(|| {
+            CURRENT.set(&self.context, || {
+                #[cfg(feature = "watchdog")]
+                let _watchdog = self.context.watchdog.as_ref().map(|w| w.enter());
+                let timeout = if self.context.tasks.is_empty() {
+                    max_timeout
+                } else {
+                    Duration::ZERO
+                };
+                self.park(Some(timeout));
+                let polled = self.context.run_tasks();
+                let _ = self.driver.submit();
+                TurnResult {
+                    polled,
+                    has_ready: !self.context.tasks.is_empty(),
+                }
+            })
+        })
+    }
+
+    /// Turn the runtime without waiting until no task is ready, i.e. the tasks left are waiting
+    /// for io, timers or wakeups from other threads. Returns the number of tasks polled.
+    pub fn run_until_idle(&mut self) -> usize
+    where
+        D: Driver,
+    {
+        let mut polled = 0;
+        loop {
+            let turn = self.turn(Duration::ZERO);
+            polled += turn.polled;
+            if turn.polled == 0 && !turn.has_ready {
+                return polled;
+            }
+        }
+    }
+
+    /// Get an fd which becomes readable when the runtime has io events to process, for
+    /// integrating the runtime into an external event loop, e.g. glib, libuv or a custom C loop:
+    /// wait for the fd to be readable there, then call
+    /// [`drive_nonblocking`](Runtime::drive_nonblocking). Drive the runtime until no task is
+    /// ready before waiting, since the ready tasks, e.g. the ones just spawned, do not make it
+    /// readable.
+    ///
+    /// It is the eventfd registered to the ring for the io_uring driver, and the epoll or kqueue
+    /// fd for the legacy driver. The timers do not make it readable, so the loop should also
+    /// drive the runtime periodically if timers are used.
+    #[cfg(unix)]
+    pub fn pollable_fd(&self) -> std::io::Result<std::os::unix::io::RawFd>
+    where
+        D: Driver,
+    {
+        self.driver.pollable_fd()
+    }
+
+    /// Drive the runtime without blocking: reset the [`pollable_fd`](Runtime::pollable_fd),
+    /// process the io events and the timers, then run the ready tasks. If
+    /// [`has_ready`](TurnResult::has_ready) is set in the result, the runtime should be driven
+    /// again without waiting for the fd.
+    pub fn drive_nonblocking(&mut self) -> TurnResult
+    where
+        D: Driver,
+    {
+        #[cfg(unix)]
+        self.driver.with(|| {
+            if crate::driver::CURRENT.is_set() {
+                crate::driver::CURRENT.with(|inner| inner.reset_pollable_fd());
+            }
+        });
+        self.turn(Duration::ZERO)
+    }
+
+    /// Wait for io or timers, infinitely if `timeout` is `None`, and process the events.
+    fn park(&self, timeout: Option<Duration>)
+    where
+        D: Driver,
+    {
+        self.context.metrics.parked();
+        trace_event!("runtime parked");
+        #[cfg(feature = "watchdog")]
+        if let Some(watchdog) = self.context.watchdog.as_ref() {
+            watchdog.parked();
+        }
+        let _result = match timeout {
+            Some(timeout) => self.driver.park_timeout(timeout),
+            None => self.driver.park(),
+        };
+        // The error is ignored for not debug mode
+        #[cfg(all(debug_assertions, feature = "debug"))]
+        if let Err(e) = _result {
+            trace!("park error: {:?}", e);
+        }
+
+        trace_event!("runtime unparked");
+        #[cfg(feature = "watchdog")]
+        if let Some(watchdog) = self.context.watchdog.as_ref() {
+            watchdog.unparked();
+        }
+        self.context.recent.tick();
+
+        #[cfg(feature = "sync")]
+        self.context.wake_remote();
+    }
+
+    /// Shut down the runtime gracefully, waiting at most `timeout` for the in-flight io.
+    ///
+    /// The spawned tasks are aborted, and their futures are dropped inside the runtime so the
+    /// drop handlers can still use it. The tasks spawned from then on are aborted immediately.
+    /// The in-flight io_uring ops are canceled, and their completions are awaited until the
+    /// timeout before the registered fds are unregistered. The buffers of the ops still in flight
+    /// after the timeout are leaked rather than freed, since the kernel may still write to them.
+    ///
+    /// Dropping the runtime directly, the tasks are dropped outside of it, and the buffers of
+    /// the in-flight ops are freed with the ring.
+    pub fn shutdown_timeout(self, timeout: Duration)
+    where
+        D: Driver,
+    {
+        assert!(
+            !CURRENT.is_set(),
+            "Can not shutdown a runtime inside a runtime"
+        );
 
-                    #[cfg(all(debug_assertions, feature = "debug"))]
-                    if let Err(e) = self.driver.park() {
-                        trace!("park error: {:?}", e);
+        let deadline = Instant::now().checked_add(timeout);
+        self.driver.with(|| {
+            CURRENT.set(&self.context, || {
+                self.context.owned.close_and_abort_all();
+                let inner = crate::driver::CURRENT
+                    .is_set()
+                    .then(|| crate::driver::CURRENT.with(Clone::clone));
+                let mut canceled = false;
+                loop {
+                    // Drop the futures of the aborted tasks, which cancel their ops, and run the
+                    // tasks woken meanwhile.
+                    let budget = self.context.coop_budget;
+                    while let Some(t) = self.context.tasks.pop() {
+                        crate::task::coop::budget(budget, || self.context.tasks.run(t));
                     }
+                    let Some(inner) = inner.as_ref() else {
+                        break;
+                    };
+                    if inner.metrics().in_flight == 0 {
+                        break;
+                    }
+                    if !canceled {
+                        // The ops held outside of the tasks
+                        inner.cancel_all();
+                        canceled = true;
+                    }
+                    let now = Instant::now();
+                    let _ = match deadline {
+                        Some(deadline) if deadline <= now => break,
+                        Some(deadline) => self.driver.park_timeout(deadline - now),
+                        None => self.driver.park(),
+                    };
+                    self.context.recent.tick();
+                    #[cfg(feature = "sync")]
+                    self.context.wake_remote();
+                }
+                if let Some(inner) = inner {
+                    inner.shutdown();
                 }
             })
         })
@@ -237,6 +650,57 @@ where
             }
         }
     }
+
+    /// Shut down the runtime gracefully, see [`Runtime::shutdown_timeout`].
+    pub fn shutdown_timeout(self, timeout: Duration) {
+        match self {
+            FusionRuntime::Uring(inner) => inner.shutdown_timeout(timeout),
+            FusionRuntime::Legacy(inner) => inner.shutdown_timeout(timeout),
+        }
+    }
+
+    /// Run one turn of the runtime, see [`Runtime::turn`].
+    pub fn turn(&mut self, max_timeout: Duration) -> TurnResult {
+        match self {
+            FusionRuntime::Uring(inner) => inner.turn(max_timeout),
+            FusionRuntime::Legacy(inner) => inner.turn(max_timeout),
+        }
+    }
+
+    /// Turn the runtime until no task is ready, see [`Runtime::run_until_idle`].
+    pub fn run_until_idle(&mut self) -> usize {
+        match self {
+            FusionRuntime::Uring(inner) => inner.run_until_idle(),
+            FusionRuntime::Legacy(inner) => inner.run_until_idle(),
+        }
+    }
+
+    /// Get an fd which becomes readable when the runtime has io events to process, see
+    /// [`Runtime::pollable_fd`].
+    #[cfg(unix)]
+    pub fn pollable_fd(&self) -> std::io::Result<std::os::unix::io::RawFd> {
+        match self {
+            FusionRuntime::Uring(inner) => inner.pollable_fd(),
+            FusionRuntime::Legacy(inner) => inner.pollable_fd(),
+        }
+    }
+
+    /// Drive the runtime without blocking, see [`Runtime::drive_nonblocking`].
+    pub fn drive_nonblocking(&mut self) -> TurnResult {
+        match self {
+            FusionRuntime::Uring(inner) => inner.drive_nonblocking(),
+            FusionRuntime::Legacy(inner) => inner.drive_nonblocking(),
+        }
+    }
+
+    /// Get a [`Handle`] to spawn tasks on the runtime from other threads.
+    #[cfg(feature = "sync")]
+    pub fn handle(&self) -> Handle {
+        match self {
+            FusionRuntime::Uring(inner) => inner.handle(),
+            FusionRuntime::Legacy(inner) => inner.handle(),
+        }
+    }
 }
 
 #[cfg(all(feature = "legacy", not(all(target_os = "linux", feature = "iouring"))))]
@@ -253,6 +717,51 @@ where
             FusionRuntime::Legacy(inner) => inner.block_on(future),
         }
     }
+
+    /// Shut down the runtime gracefully, see [`Runtime::shutdown_timeout`].
+    pub fn shutdown_timeout(self, timeout: Duration) {
+        match self {
+            FusionRuntime::Legacy(inner) => inner.shutdown_timeout(timeout),
+        }
+    }
+
+    /// Run one turn of the runtime, see [`Runtime::turn`].
+    pub fn turn(&mut self, max_timeout: Duration) -> TurnResult {
+        match self {
+            FusionRuntime::Legacy(inner) => inner.turn(max_timeout),
+        }
+    }
+
+    /// Turn the runtime until no task is ready, see [`Runtime::run_until_idle`].
+    pub fn run_until_idle(&mut self) -> usize {
+        match self {
+            FusionRuntime::Legacy(inner) => inner.run_until_idle(),
+        }
+    }
+
+    /// Get an fd which becomes readable when the runtime has io events to process, see
+    /// [`Runtime::pollable_fd`].
+    #[cfg(unix)]
+    pub fn pollable_fd(&self) -> std::io::Result<std::os::unix::io::RawFd> {
+        match self {
+            FusionRuntime::Legacy(inner) => inner.pollable_fd(),
+        }
+    }
+
+    /// Drive the runtime without blocking, see [`Runtime::drive_nonblocking`].
+    pub fn drive_nonblocking(&mut self) -> TurnResult {
+        match self {
+            FusionRuntime::Legacy(inner) => inner.drive_nonblocking(),
+        }
+    }
+
+    /// Get a [`Handle`] to spawn tasks on the runtime from other threads.
+    #[cfg(feature = "sync")]
+    pub fn handle(&self) -> Handle {
+        match self {
+            FusionRuntime::Legacy(inner) => inner.handle(),
+        }
+    }
 }
 
 #[cfg(all(not(feature = "legacy"), all(target_os = "linux", feature = "iouring")))]
@@ -269,6 +778,51 @@ where
             FusionRuntime::Uring(inner) => inner.block_on(future),
         }
     }
+
+    /// Shut down the runtime gracefully, see [`Runtime::shutdown_timeout`].
+    pub fn shutdown_timeout(self, timeout: Duration) {
+        match self {
+            FusionRuntime::Uring(inner) => inner.shutdown_timeout(timeout),
+        }
+    }
+
+    /// Run one turn of the runtime, see [`Runtime::turn`].
+    pub fn turn(&mut self, max_timeout: Duration) -> TurnResult {
+        match self {
+            FusionRuntime::Uring(inner) => inner.turn(max_timeout),
+        }
+    }
+
+    /// Turn the runtime until no task is ready, see [`Runtime::run_until_idle`].
+    pub fn run_until_idle(&mut self) -> usize {
+        match self {
+            FusionRuntime::Uring(inner) => inner.run_until_idle(),
+        }
+    }
+
+    /// Get an fd which becomes readable when the runtime has io events to process, see
+    /// [`Runtime::pollable_fd`].
+    #[cfg(unix)]
+    pub fn pollable_fd(&self) -> std::io::Result<std::os::unix::io::RawFd> {
+        match self {
+            FusionRuntime::Uring(inner) => inner.pollable_fd(),
+        }
+    }
+
+    /// Drive the runtime without blocking, see [`Runtime::drive_nonblocking`].
+    pub fn drive_nonblocking(&mut self) -> TurnResult {
+        match self {
+            FusionRuntime::Uring(inner) => inner.drive_nonblocking(),
+        }
+    }
+
+    /// Get a [`Handle`] to spawn tasks on the runtime from other threads.
+    #[cfg(feature = "sync")]
+    pub fn handle(&self) -> Handle {
+        match self {
+            FusionRuntime::Uring(inner) => inner.handle(),
+        }
+    }
 }
 
 // L -> Fusion<L, R>
@@ -365,21 +919,86 @@ impl From<Runtime<TimeDriver<IoUringDriver>>> for FusionRuntime<TimeDriver<IoUri
 ///     handle.await;
 /// }
 /// ```
+#[track_caller]
 pub fn spawn<T>(future: T) -> JoinHandle<T::Output>
 where
     T: Future + 'static,
     T::Output: 'static,
 {
-    let (task, join) = new_task(
-        crate::utils::thread_id::get_current_thread_id(),
-        future,
-        LocalScheduler,
-    );
+    spawn_with_priority(Priority::Normal, future)
+}
 
+/// Spawns a new asynchronous task with the given [`Priority`], returning a [`JoinHandle`] for
+/// it. The ready tasks with higher priority run first, e.g. accepting connections ahead of the
+/// bulk transfers on the same thread.
+///
+/// ```no_run
+/// use monoio::task::Priority;
+///
+/// #[monoio::main]
+/// async fn main() {
+///     monoio::spawn_with_priority(Priority::Low, async {
+///         println!("hello from a background task");
+///     });
+///     monoio::spawn_with_priority(Priority::High, async {
+///         println!("hello from a latency-sensitive task");
+///     });
+/// }
+/// ```
+#[track_caller]
+pub fn spawn_with_priority<T>(priority: Priority, future: T) -> JoinHandle<T::Output>
+where
+    T: Future + 'static,
+    T::Output: 'static,
+{
+    spawn_at(priority, future, std::panic::Location::caller())
+}
+
+/// Spawn the task, which is spawned at `location` by the user.
+pub(crate) fn spawn_at<T>(
+    priority: Priority,
+    future: T,
+    location: &'static std::panic::Location<'static>,
+) -> JoinHandle<T::Output>
+where
+    T: Future + 'static,
+    T::Output: 'static,
+{
+    #[cfg(feature = "tracing")]
+    let future = instrument::Instrumented::new(future, priority, location);
+    #[cfg(feature = "tracing")]
+    let span_id = future.span_id();
     CURRENT.with(|ctx| {
-        ctx.tasks.push(task);
-    });
-    join
+        #[cfg(feature = "watchdog")]
+        let future = watchdog::Watched::new(future, ctx.watchdog.as_ref(), location);
+        let future = shutdown::Owned::new(metrics::Counted::new(future, &ctx.metrics), &ctx.owned);
+        let id = future.id();
+        let (task, join) = new_task(
+            crate::utils::thread_id::get_current_thread_id(),
+            future,
+            LocalScheduler::new(priority),
+        );
+        #[cfg(feature = "tracing")]
+        task.set_tracing_id(span_id);
+        if !matches!(ctx.panic_policy, PanicPolicy::Propagate) {
+            task.catch_panic(location);
+        }
+        ctx.tasks.push(task, priority);
+        ctx.owned.insert(id, join.abort_handle());
+        join
+    })
+}
+
+/// Submit the operations queued by the driver of current runtime to the kernel now, for the
+/// latency-critical code which does not want to wait for the driver to submit them(see
+/// [`SubmitPolicy`](crate::driver::SubmitPolicy)). It is a no-op for the legacy driver, which
+/// does not queue operations.
+///
+/// # Panics
+///
+/// Panics if called outside of a monoio runtime with a builtin driver.
+pub fn flush_submissions() -> std::io::Result<()> {
+    crate::driver::CURRENT.with(|inner| inner.flush_submissions())
 }
 
 #[cfg(feature = "sync")]
@@ -391,11 +1010,11 @@ where
     let (task, join) = new_task_holding(
         crate::utils::thread_id::get_current_thread_id(),
         future,
-        LocalScheduler,
+        LocalScheduler::default(),
     );
 
     CURRENT.with(|ctx| {
-        ctx.tasks.push(task);
+        ctx.tasks.push(task, Priority::Normal);
     });
     join
 }
@@ -0,0 +1,163 @@
+//! Handle to spawn tasks on a runtime from other threads.
+
+use std::{
+    future::Future,
+    panic::Location,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Wake, Waker},
+};
+
+use crate::task::JoinHandle;
+
+/// A handle to a runtime, which spawns tasks on it from other threads, e.g. a control thread
+/// which injects work into the runtime of a specific core. It is cheap to clone and `Send`.
+///
+/// The tasks are sent with the waker channel of the runtime, which unparks it, so they start
+/// when the runtime runs next time. They are dropped if the runtime is gone.
+///
+/// ```
+/// let (tx, rx) = std::sync::mpsc::channel();
+/// let (stop_tx, stop_rx) = futures::channel::oneshot::channel::<()>();
+/// let thread = std::thread::spawn(move || {
+///     let mut rt = monoio::RuntimeBuilder::<monoio::FusionDriver>::new()
+///         .build()
+///         .unwrap();
+///     tx.send(rt.handle()).unwrap();
+///     rt.block_on(async move {
+///         let _ = stop_rx.await;
+///     });
+/// });
+/// let handle = rx.recv().unwrap();
+/// let join = handle.spawn(async { 1 });
+/// assert_eq!(futures::executor::block_on(join), 1);
+/// drop(stop_tx);
+/// thread.join().unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Handle {
+    thread_id: usize,
+}
+
+impl Handle {
+    pub(crate) fn new(thread_id: usize) -> Self {
+        Self { thread_id }
+    }
+
+    /// Get the handle of the current runtime.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a monoio runtime.
+    pub fn current() -> Self {
+        super::CURRENT.with(|ctx| Self::new(ctx.thread_id))
+    }
+
+    /// Spawn the future on the runtime, and return a handle to wait for its output from any
+    /// thread.
+    #[track_caller]
+    pub fn spawn<F>(&self, future: F) -> RemoteJoinHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        self.spawn_local_with(move || future)
+    }
+
+    /// Spawn the future created by `f` on the runtime, which runs `f` on the thread of the
+    /// runtime, so the future does not need to be `Send`.
+    #[track_caller]
+    pub fn spawn_local_with<F, Fut>(&self, f: F) -> RemoteJoinHandle<Fut::Output>
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future + 'static,
+        Fut::Output: Send + 'static,
+    {
+        let location = Location::caller();
+        let (tx, rx) = flume::bounded(1);
+        let spawn = Arc::new(Spawn(Mutex::new(Some(Box::new(move || {
+            let join = super::spawn_at(crate::task::Priority::Normal, f(), location);
+            let _ = tx.send(join);
+        })))));
+        wake_thread(self.thread_id, Waker::from(spawn));
+        RemoteJoinHandle {
+            state: State::Spawning(rx.into_recv_async()),
+        }
+    }
+}
+
+/// Send the waker to the thread of the runtime as the remote wakeups do.
+fn wake_thread(id: usize, waker: Waker) {
+    super::CURRENT.try_with(|maybe_ctx| match maybe_ctx {
+        Some(ctx) => ctx.wake_thread(id, waker),
+        None => {
+            let _ = super::DEFAULT_CTX.try_with(|default_ctx| {
+                super::CURRENT.set(default_ctx, || {
+                    super::CURRENT.with(|ctx| ctx.wake_thread(id, waker));
+                });
+            });
+        }
+    });
+}
+
+type SpawnFn = Box<dyn FnOnce() + Send>;
+
+/// Spawns the task when it is woken by the runtime receiving it.
+struct Spawn(Mutex<Option<SpawnFn>>);
+
+impl Wake for Spawn {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        // Dropped if it is not woken inside the runtime.
+        if super::CURRENT.is_set() {
+            if let Some(f) = self.0.lock().unwrap().take() {
+                f();
+            }
+        }
+    }
+}
+
+/// Handle to wait for the output of a task spawned with [`Handle`], which can be awaited on any
+/// thread.
+///
+/// # Panics
+///
+/// Awaiting it panics if the task is not spawned since the runtime is gone, or the task is
+/// aborted.
+pub struct RemoteJoinHandle<T: 'static> {
+    state: State<T>,
+}
+
+enum State<T: 'static> {
+    Spawning(flume::r#async::RecvFut<'static, JoinHandle<T>>),
+    Spawned(JoinHandle<T>),
+}
+
+impl<T: 'static> Future for RemoteJoinHandle<T> {
+    type Output = T;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        loop {
+            match &mut self.state {
+                State::Spawning(rx) => {
+                    let join = std::task::ready!(Pin::new(rx).poll(cx))
+                        .expect("the task is not spawned since the runtime is gone");
+                    self.state = State::Spawned(join);
+                }
+                State::Spawned(join) => return Pin::new(join).poll(cx),
+            }
+        }
+    }
+}
+
+impl<T: 'static> std::fmt::Debug for RemoteJoinHandle<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let spawned = matches!(self.state, State::Spawned(_));
+        f.debug_struct("RemoteJoinHandle")
+            .field("spawned", &spawned)
+            .finish()
+    }
+}
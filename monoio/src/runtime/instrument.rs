@@ -0,0 +1,75 @@
+use std::{
+    future::Future,
+    mem::ManuallyDrop,
+    panic::Location,
+    pin::Pin,
+    sync::atomic::{AtomicU64, Ordering},
+    task::{Context, Poll},
+};
+
+use tracing::Span;
+
+use crate::scheduler::Priority;
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Wraps a spawned future with a `runtime.spawn` span, which is entered each time it is polled
+/// and when it is dropped. The span and the waker events(see `task::waker`) are in the form of
+/// tokio, so the tasks show up in tokio-console with their spawn locations, poll durations and
+/// wakes.
+pub(crate) struct Instrumented<F> {
+    future: ManuallyDrop<F>,
+    span: Span,
+}
+
+impl<F> Instrumented<F> {
+    pub(crate) fn new(future: F, priority: Priority, location: &'static Location<'static>) -> Self {
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        let span = tracing::trace_span!(
+            parent: None,
+            "runtime.spawn",
+            kind = "task",
+            task.id = id,
+            ?priority,
+            loc.file = location.file(),
+            loc.line = location.line(),
+            loc.col = location.column(),
+        );
+        tracing::trace!(parent: &span, "task spawned");
+        Instrumented {
+            future: ManuallyDrop::new(future),
+            span,
+        }
+    }
+
+    pub(crate) fn span_id(&self) -> Option<tracing::Id> {
+        self.span.id()
+    }
+}
+
+impl<F: Future> Future for Instrumented<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // # Safety
+        // The future is pinned with self, and never moved out.
+        let this = unsafe { self.get_unchecked_mut() };
+        let _enter = this.span.enter();
+        let future = unsafe { Pin::new_unchecked(&mut *this.future) };
+        let ret = future.poll(cx);
+        if ret.is_ready() {
+            tracing::trace!("task completed");
+        }
+        ret
+    }
+}
+
+impl<F> Drop for Instrumented<F> {
+    fn drop(&mut self) {
+        let _enter = self.span.enter();
+        tracing::trace!("task dropped");
+        // # Safety
+        // The future is dropped in place only once.
+        unsafe { ManuallyDrop::drop(&mut self.future) };
+    }
+}
@@ -0,0 +1,356 @@
+use std::{cell::Cell, time::Duration};
+
+/// A snapshot of the metrics of the current runtime, returned by [`metrics`].
+///
+/// The counters are cumulative since the runtime is built, and the high-water marks are the
+/// largest values observed. The driver metrics are zero for the drivers without them, e.g. the
+/// legacy driver has no submission queue.
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct RuntimeMetrics {
+    /// Tasks spawned.
+    pub tasks_spawned: u64,
+    /// Tasks spawned and not finished(completed or aborted).
+    pub tasks_alive: usize,
+    /// Times the tasks are polled.
+    pub tasks_polled: u64,
+    /// Tasks polled between the last two parks.
+    pub last_tick_polls: usize,
+    /// Most tasks polled between two parks.
+    pub max_tick_polls: usize,
+    /// Times the runtime parks on the driver to wait for io or timers.
+    pub park_count: u64,
+    /// Tasks woken by other threads.
+    pub foreign_wakeups: u64,
+    /// Operations submitted to the driver and not completed.
+    pub in_flight_ops: usize,
+    /// Operations tracked by the driver, including the canceled ones not reaped yet, or the
+    /// registered io sources of the legacy driver.
+    pub slab_size: usize,
+    /// Most entries in the io_uring submission queue when submitting.
+    pub sq_high_water: usize,
+    /// Most entries in the io_uring completion queue when reaping.
+    pub cq_high_water: usize,
+    /// Blocking tasks scheduled to the attached thread pool and not started.
+    pub blocking_queue_depth: usize,
+}
+
+/// Get the metrics of the current runtime.
+///
+/// # Panics
+///
+/// Panics if called outside of a monoio runtime.
+///
+/// ```
+/// #[monoio::main]
+/// async fn main() {
+///     monoio::spawn(async {}).await;
+///     let metrics = monoio::runtime::metrics();
+///     assert_eq!(metrics.tasks_spawned, 1);
+///     assert_eq!(metrics.tasks_alive, 0);
+/// }
+/// ```
+pub fn metrics() -> RuntimeMetrics {
+    let mut metrics = super::CURRENT.with(|ctx| {
+        let m = &ctx.metrics;
+        RuntimeMetrics {
+            tasks_spawned: m.tasks_spawned.get(),
+            tasks_alive: m.tasks_alive.get(),
+            tasks_polled: m.tasks_polled.get(),
+            last_tick_polls: m.last_tick_polls.get(),
+            max_tick_polls: m.max_tick_polls.get(),
+            park_count: m.park_count.get(),
+            foreign_wakeups: m.foreign_wakeups.get(),
+            #[cfg(feature = "sync")]
+            blocking_queue_depth: ctx
+                .blocking_queued
+                .load(std::sync::atomic::Ordering::Relaxed),
+            ..Default::default()
+        }
+    });
+    if crate::driver::CURRENT.is_set() {
+        let driver = crate::driver::CURRENT.with(|inner| inner.metrics());
+        metrics.in_flight_ops = driver.in_flight;
+        metrics.slab_size = driver.slab_size;
+        metrics.sq_high_water = driver.sq_high_water;
+        metrics.cq_high_water = driver.cq_high_water;
+    }
+    metrics
+}
+
+/// Counters of the runtime, only touched by the runtime thread.
+#[derive(Default)]
+pub(crate) struct Metrics {
+    tasks_spawned: Cell<u64>,
+    tasks_alive: Cell<usize>,
+    tasks_polled: Cell<u64>,
+    tick_polls: Cell<usize>,
+    last_tick_polls: Cell<usize>,
+    max_tick_polls: Cell<usize>,
+    park_count: Cell<u64>,
+    foreign_wakeups: Cell<u64>,
+}
+
+impl Metrics {
+    pub(crate) fn task_spawned(&self) {
+        self.tasks_spawned.set(self.tasks_spawned.get() + 1);
+        self.tasks_alive.set(self.tasks_alive.get() + 1);
+    }
+
+    pub(crate) fn task_polled(&self) {
+        self.tasks_polled.set(self.tasks_polled.get() + 1);
+        self.tick_polls.set(self.tick_polls.get() + 1);
+    }
+
+    pub(crate) fn parked(&self) {
+        let polls = self.tick_polls.replace(0);
+        self.last_tick_polls.set(polls);
+        self.max_tick_polls
+            .set(self.max_tick_polls.get().max(polls));
+        self.park_count.set(self.park_count.get() + 1);
+    }
+}
+
+/// Record the tasks woken by other threads, no-op outside of a runtime.
+#[cfg(feature = "sync")]
+pub(crate) fn foreign_wakeups(n: u64) {
+    if n != 0 && super::CURRENT.is_set() {
+        super::CURRENT.with(|ctx| {
+            let m = &ctx.metrics.foreign_wakeups;
+            m.set(m.get() + n);
+        });
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// Wraps a spawned future to count the alive tasks. It is dropped when the task finishes.
+    pub(crate) struct Counted<F> {
+        #[pin]
+        future: F,
+        guard: AliveGuard,
+    }
+}
+
+impl<F> Counted<F> {
+    pub(crate) fn new(future: F, metrics: &Metrics) -> Self {
+        metrics.task_spawned();
+        Counted {
+            future,
+            guard: AliveGuard,
+        }
+    }
+}
+
+impl<F: std::future::Future> std::future::Future for Counted<F> {
+    type Output = F::Output;
+
+    #[inline]
+    fn poll(
+        self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Self::Output> {
+        self.project().future.poll(cx)
+    }
+}
+
+pub(crate) struct AliveGuard;
+
+impl Drop for AliveGuard {
+    fn drop(&mut self) {
+        if super::CURRENT.is_set() {
+            super::CURRENT.with(|ctx| {
+                let m = &ctx.metrics.tasks_alive;
+                m.set(m.get().saturating_sub(1));
+            });
+        }
+    }
+}
+
+/// Get the latency histograms of the io_uring operations of the current runtime, recorded when
+/// enabled with [`uring_op_latency`](crate::RuntimeBuilder::uring_op_latency). It returns the
+/// opcodes(the `CODE` of types in `io_uring::opcode`) with their histograms, sorted by the
+/// opcode, and is empty if the recording is not enabled or the driver is not io_uring.
+///
+/// # Panics
+///
+/// Panics if called outside of a monoio runtime.
+pub fn op_latency() -> Vec<(u8, LatencyHistogram)> {
+    assert!(
+        super::CURRENT.is_set(),
+        "op_latency must be called in a monoio runtime"
+    );
+    if !crate::driver::CURRENT.is_set() {
+        return Vec::new();
+    }
+    crate::driver::CURRENT.with(|inner| inner.op_latency())
+}
+
+/// An io_uring operation slower than the threshold of
+/// [`uring_op_latency`](crate::RuntimeBuilder::uring_op_latency), which is passed to the
+/// callback set with [`uring_slow_op_callback`](crate::RuntimeBuilder::uring_slow_op_callback).
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct SlowOp {
+    /// The opcode(the `CODE` of types in `io_uring::opcode`).
+    pub opcode: u8,
+    /// The fd of the operation, which is the index in the fixed file table for the fixed ones.
+    pub fd: std::os::unix::io::RawFd,
+    /// Time from pushing the SQE to reaping its CQE.
+    pub latency: std::time::Duration,
+}
+
+/// The callback of the slow operations, kept by the builder.
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+#[derive(Clone)]
+pub(crate) struct SlowOpCallback(pub(crate) std::sync::Arc<dyn Fn(&SlowOp) + Send + Sync>);
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+impl std::fmt::Debug for SlowOpCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("SlowOpCallback")
+    }
+}
+
+/// Sub-buckets of each power of two, which bounds the relative error to 1/8.
+const SUB_BUCKET_BITS: u32 = 3;
+const SUB_BUCKETS: usize = 1 << SUB_BUCKET_BITS;
+const BUCKETS: usize = (64 - SUB_BUCKET_BITS as usize + 1) * SUB_BUCKETS;
+
+/// A histogram of durations with log-linear buckets in the style of HDR histograms: each power
+/// of two of nanoseconds is split into 8 buckets, so the values it reports are within 12.5% of
+/// the recorded ones, with fixed memory whatever the range is.
+#[derive(Clone)]
+pub struct LatencyHistogram {
+    counts: Box<[u64; BUCKETS]>,
+    count: u64,
+    sum: u128,
+    min: u64,
+    max: u64,
+}
+
+#[cfg_attr(not(all(target_os = "linux", feature = "iouring")), allow(unused))]
+impl LatencyHistogram {
+    pub(crate) fn new() -> Self {
+        LatencyHistogram {
+            counts: Box::new([0; BUCKETS]),
+            count: 0,
+            sum: 0,
+            min: u64::MAX,
+            max: 0,
+        }
+    }
+
+    pub(crate) fn record(&mut self, latency: Duration) {
+        let nanos = u64::try_from(latency.as_nanos()).unwrap_or(u64::MAX);
+        self.counts[Self::bucket(nanos)] += 1;
+        self.count += 1;
+        self.sum += nanos as u128;
+        self.min = self.min.min(nanos);
+        self.max = self.max.max(nanos);
+    }
+
+    fn bucket(nanos: u64) -> usize {
+        if nanos < SUB_BUCKETS as u64 {
+            return nanos as usize;
+        }
+        let magnitude = 63 - nanos.leading_zeros();
+        let sub = (nanos >> (magnitude - SUB_BUCKET_BITS)) as usize & (SUB_BUCKETS - 1);
+        (magnitude - SUB_BUCKET_BITS + 1) as usize * SUB_BUCKETS + sub
+    }
+
+    /// The largest value in the bucket.
+    fn bucket_high(index: usize) -> u64 {
+        if index < SUB_BUCKETS {
+            return index as u64;
+        }
+        let shift = (index / SUB_BUCKETS - 1) as u32;
+        let low = ((SUB_BUCKETS + index % SUB_BUCKETS) as u64) << shift;
+        low + ((1 << shift) - 1)
+    }
+
+    /// Number of the recorded values.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// The smallest recorded value, zero if nothing is recorded.
+    pub fn min(&self) -> Duration {
+        if self.count == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_nanos(self.min)
+    }
+
+    /// The largest recorded value.
+    pub fn max(&self) -> Duration {
+        Duration::from_nanos(self.max)
+    }
+
+    /// The mean of the recorded values, zero if nothing is recorded.
+    pub fn mean(&self) -> Duration {
+        if self.count == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_nanos((self.sum / self.count as u128) as u64)
+    }
+
+    /// The value below which the `quantile`(0.0 to 1.0) of the recorded values fall, e.g.
+    /// `value_at_quantile(0.99)` is the p99 latency. It is zero if nothing is recorded.
+    pub fn value_at_quantile(&self, quantile: f64) -> Duration {
+        if self.count == 0 {
+            return Duration::ZERO;
+        }
+        let rank = ((quantile.clamp(0.0, 1.0) * self.count as f64).ceil() as u64).max(1);
+        let mut seen = 0;
+        for (index, &count) in self.counts.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                let value = Self::bucket_high(index).clamp(self.min, self.max);
+                return Duration::from_nanos(value);
+            }
+        }
+        self.max()
+    }
+}
+
+impl std::fmt::Debug for LatencyHistogram {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LatencyHistogram")
+            .field("count", &self.count())
+            .field("min", &self.min())
+            .field("mean", &self.mean())
+            .field("p50", &self.value_at_quantile(0.5))
+            .field("p99", &self.value_at_quantile(0.99))
+            .field("max", &self.max())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn histogram_buckets() {
+        for nanos in (0..10_000).chain([u64::MAX / 3, u64::MAX]) {
+            let bucket = LatencyHistogram::bucket(nanos);
+            assert!(bucket < BUCKETS);
+            assert!(LatencyHistogram::bucket_high(bucket) >= nanos);
+            // Within 1/8 of the value.
+            assert!(LatencyHistogram::bucket_high(bucket) - nanos <= nanos / 8);
+        }
+
+        let mut histogram = LatencyHistogram::new();
+        for micros in 1..=100 {
+            histogram.record(Duration::from_micros(micros));
+        }
+        assert_eq!(histogram.count(), 100);
+        assert_eq!(histogram.min(), Duration::from_micros(1));
+        assert_eq!(histogram.max(), Duration::from_micros(100));
+        assert_eq!(histogram.mean(), Duration::from_nanos(50_500));
+        let p50 = histogram.value_at_quantile(0.5);
+        assert!(p50 >= Duration::from_micros(50) && p50 <= Duration::from_micros(57));
+        assert_eq!(histogram.value_at_quantile(1.0), histogram.max());
+    }
+}
@@ -0,0 +1,81 @@
+use std::{any::Any, panic::Location, sync::Arc};
+
+/// What to do when a spawned task panics, see
+/// [`RuntimeBuilder::with_panic_policy`](crate::RuntimeBuilder::with_panic_policy).
+///
+/// It applies to the tasks spawned on the runtime only, the panic of the future passed to
+/// `block_on` always unwinds out of `block_on`.
+#[derive(Clone, Default)]
+pub enum PanicPolicy {
+    /// The panic unwinds out of `block_on`, which stops the runtime.
+    #[default]
+    Propagate,
+    /// Abort the process.
+    Abort,
+    /// Print the task to stderr and keep running the other tasks. The panic message is
+    /// printed by the panic hook of std.
+    Log,
+    /// Call the hook with the panic and keep running the other tasks.
+    Hook(Arc<dyn Fn(&TaskPanic<'_>) + Send + Sync>),
+}
+
+impl std::fmt::Debug for PanicPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PanicPolicy::Propagate => f.write_str("Propagate"),
+            PanicPolicy::Abort => f.write_str("Abort"),
+            PanicPolicy::Log => f.write_str("Log"),
+            PanicPolicy::Hook(_) => f.write_str("Hook"),
+        }
+    }
+}
+
+/// A panic of a spawned task, passed to the [`PanicPolicy::Hook`].
+///
+/// The task is finished after the panic, and awaiting its `JoinHandle` panics.
+#[derive(Debug)]
+pub struct TaskPanic<'a> {
+    payload: &'a (dyn Any + Send),
+    location: &'static Location<'static>,
+}
+
+impl TaskPanic<'_> {
+    /// The payload of the panic, usually a `&'static str` or a `String`.
+    pub fn payload(&self) -> &(dyn Any + Send) {
+        self.payload
+    }
+
+    /// The message of the panic, if the payload is a string.
+    pub fn message(&self) -> Option<&str> {
+        match self.payload.downcast_ref::<&'static str>() {
+            Some(s) => Some(s),
+            None => self.payload.downcast_ref::<String>().map(String::as_str),
+        }
+    }
+
+    /// Where the task is spawned.
+    pub fn spawned_at(&self) -> &'static Location<'static> {
+        self.location
+    }
+}
+
+/// Handle the panic of the task spawned at `location` with the policy of the current runtime.
+pub(crate) fn task_panicked(payload: Box<dyn Any + Send>, location: &'static Location<'static>) {
+    let policy = if super::CURRENT.is_set() {
+        super::CURRENT.with(|ctx| ctx.panic_policy.clone())
+    } else {
+        PanicPolicy::Propagate
+    };
+    match policy {
+        PanicPolicy::Propagate => std::panic::resume_unwind(payload),
+        PanicPolicy::Abort => {
+            eprintln!("monoio: task spawned at {location} panicked, aborting");
+            std::process::abort();
+        }
+        PanicPolicy::Log => eprintln!("monoio: task spawned at {location} panicked"),
+        PanicPolicy::Hook(hook) => hook(&TaskPanic {
+            payload: &*payload,
+            location,
+        }),
+    }
+}
@@ -0,0 +1,97 @@
+//! Tracking of the spawned tasks, to abort them when the runtime shuts down.
+
+use std::{
+    cell::{Cell, RefCell},
+    future::Future,
+    pin::Pin,
+    rc::{Rc, Weak},
+    task::{Context, Poll},
+};
+
+use crate::task::AbortHandle;
+
+/// The tasks spawned on the runtime and not finished.
+#[derive(Default)]
+pub(crate) struct OwnedTasks {
+    tasks: RefCell<fxhash::FxHashMap<u64, AbortHandle>>,
+    next_id: Cell<u64>,
+    // The tasks spawned after closed are aborted
+    closed: Cell<bool>,
+}
+
+impl OwnedTasks {
+    /// Track the task, the handle is released when its future is dropped.
+    pub(crate) fn insert(&self, id: u64, handle: AbortHandle) {
+        if self.closed.get() {
+            handle.abort();
+            return;
+        }
+        self.tasks.borrow_mut().insert(id, handle);
+    }
+
+    fn remove(&self, id: u64) {
+        // Drop the handle after the borrow is released.
+        let handle = self.tasks.borrow_mut().remove(&id);
+        drop(handle);
+    }
+
+    /// Abort all the tasks and the tasks spawned later.
+    pub(crate) fn close_and_abort_all(&self) {
+        self.closed.set(true);
+        let handles: Vec<_> = self.tasks.borrow().values().cloned().collect();
+        for handle in handles {
+            handle.abort();
+        }
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// Wraps a spawned future to untrack the task when the future is dropped, i.e. the task
+    /// completes or is aborted.
+    pub(crate) struct Owned<F> {
+        #[pin]
+        future: F,
+        guard: OwnedGuard,
+    }
+}
+
+impl<F> Owned<F> {
+    pub(crate) fn new(future: F, owned: &Rc<OwnedTasks>) -> Self {
+        let id = owned.next_id.get();
+        owned.next_id.set(id + 1);
+        Owned {
+            future,
+            guard: OwnedGuard {
+                id,
+                owned: Rc::downgrade(owned),
+            },
+        }
+    }
+
+    pub(crate) fn id(&self) -> u64 {
+        self.guard.id
+    }
+}
+
+impl<F: Future> Future for Owned<F> {
+    type Output = F::Output;
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.project().future.poll(cx)
+    }
+}
+
+struct OwnedGuard {
+    id: u64,
+    // Weak since the tasks are kept alive by the set
+    owned: Weak<OwnedTasks>,
+}
+
+impl Drop for OwnedGuard {
+    fn drop(&mut self) {
+        if let Some(owned) = self.owned.upgrade() {
+            owned.remove(self.id);
+        }
+    }
+}
@@ -0,0 +1,205 @@
+//! Watchdog which detects the runtime threads blocked by the tasks.
+
+use std::{
+    future::Future,
+    panic::Location,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicPtr, AtomicU64, Ordering},
+        Arc, Mutex, Weak,
+    },
+    task::{Context, Poll},
+    thread::{Thread, ThreadId},
+    time::{Duration, Instant},
+};
+
+/// The report of a runtime thread which has not ticked its driver for longer than the timeout
+/// of the watchdog, see [`RuntimeBuilder::with_watchdog`](crate::RuntimeBuilder::with_watchdog).
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct BlockedReactor {
+    /// ID of the blocked thread.
+    pub thread_id: ThreadId,
+    /// Name of the blocked thread.
+    pub thread_name: Option<String>,
+    /// Time since the last tick of the driver.
+    pub blocked_for: Duration,
+    /// The task being polled, `None` if the thread is blocked outside of the spawned tasks,
+    /// e.g. in the future passed to `block_on`.
+    pub task: Option<BlockedTask>,
+}
+
+/// The task blocking the runtime thread.
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct BlockedTask {
+    /// ID of the task, unique in the runtime.
+    pub id: u64,
+    /// Where the task is spawned.
+    pub location: &'static Location<'static>,
+}
+
+type Callback = Arc<dyn Fn(&BlockedReactor) + Send + Sync>;
+
+/// Settings of the watchdog, kept by the builder.
+#[derive(Clone)]
+pub(crate) struct Watchdog {
+    pub(crate) timeout: Duration,
+    pub(crate) callback: Callback,
+}
+
+/// State of the runtime shared with the watchdog thread.
+pub(crate) struct Shared {
+    start: Instant,
+    // Nanos since start
+    last_tick: AtomicU64,
+    // Parked or not running, which is not blocked
+    idle: AtomicBool,
+    thread: Mutex<Option<Thread>>,
+    next_task_id: AtomicU64,
+    // 0 if no task is being polled
+    task_id: AtomicU64,
+    task_location: AtomicPtr<Location<'static>>,
+}
+
+impl Shared {
+    /// Create the state and start the watchdog thread, which exits after the state is dropped.
+    pub(crate) fn start(watchdog: Watchdog) -> Arc<Self> {
+        let shared = Arc::new(Shared {
+            start: Instant::now(),
+            last_tick: AtomicU64::new(0),
+            idle: AtomicBool::new(true),
+            thread: Mutex::new(None),
+            next_task_id: AtomicU64::new(1),
+            task_id: AtomicU64::new(0),
+            task_location: AtomicPtr::new(std::ptr::null_mut()),
+        });
+        let weak = Arc::downgrade(&shared);
+        std::thread::Builder::new()
+            .name("monoio-watchdog".into())
+            .spawn(move || watch(weak, watchdog))
+            .expect("failed to spawn the watchdog thread");
+        shared
+    }
+
+    fn now(&self) -> u64 {
+        self.start.elapsed().as_nanos() as u64
+    }
+
+    /// The runtime starts running on the current thread.
+    pub(crate) fn enter(&self) -> EnterGuard<'_> {
+        *self.thread.lock().unwrap() = Some(std::thread::current());
+        self.unparked();
+        EnterGuard(self)
+    }
+
+    /// The driver is ticked.
+    pub(crate) fn tick(&self) {
+        self.last_tick.store(self.now(), Ordering::Release);
+    }
+
+    pub(crate) fn parked(&self) {
+        self.idle.store(true, Ordering::Release);
+    }
+
+    pub(crate) fn unparked(&self) {
+        self.tick();
+        self.idle.store(false, Ordering::Release);
+    }
+
+    fn task(&self) -> Option<BlockedTask> {
+        let id = self.task_id.load(Ordering::Acquire);
+        let location = self.task_location.load(Ordering::Acquire);
+        if id == 0 || location.is_null() {
+            return None;
+        }
+        Some(BlockedTask {
+            id,
+            location: unsafe { &*location },
+        })
+    }
+}
+
+/// Marks the runtime idle when `block_on` returns.
+pub(crate) struct EnterGuard<'a>(&'a Shared);
+
+impl Drop for EnterGuard<'_> {
+    fn drop(&mut self) {
+        self.0.parked();
+    }
+}
+
+fn watch(shared: Weak<Shared>, watchdog: Watchdog) {
+    let interval = (watchdog.timeout / 4).max(Duration::from_millis(1));
+    // The tick reported, a blocked tick is reported once
+    let mut reported = None;
+    loop {
+        std::thread::sleep(interval);
+        let Some(shared) = shared.upgrade() else {
+            return;
+        };
+        if shared.idle.load(Ordering::Acquire) {
+            continue;
+        }
+        let tick = shared.last_tick.load(Ordering::Acquire);
+        let blocked_for = Duration::from_nanos(shared.now().saturating_sub(tick));
+        if blocked_for < watchdog.timeout || reported == Some(tick) {
+            continue;
+        }
+        let Some(thread) = shared.thread.lock().unwrap().clone() else {
+            continue;
+        };
+        reported = Some(tick);
+        let report = BlockedReactor {
+            thread_id: thread.id(),
+            thread_name: thread.name().map(Into::into),
+            blocked_for,
+            task: shared.task(),
+        };
+        (watchdog.callback)(&report);
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// Wraps a spawned future to record it as the current task when it is polled.
+    pub(crate) struct Watched<F> {
+        #[pin]
+        future: F,
+        shared: Option<Arc<Shared>>,
+        id: u64,
+        location: &'static Location<'static>,
+    }
+}
+
+impl<F> Watched<F> {
+    pub(crate) fn new(
+        future: F,
+        shared: Option<&Arc<Shared>>,
+        location: &'static Location<'static>,
+    ) -> Self {
+        let id = shared.map_or(0, |s| s.next_task_id.fetch_add(1, Ordering::Relaxed));
+        Watched {
+            future,
+            shared: shared.cloned(),
+            id,
+            location,
+        }
+    }
+}
+
+impl<F: Future> Future for Watched<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let Some(shared) = this.shared.as_ref() else {
+            return this.future.poll(cx);
+        };
+        let location = *this.location as *const Location<'static> as *mut _;
+        shared.task_location.store(location, Ordering::Release);
+        shared.task_id.store(*this.id, Ordering::Release);
+        let ret = this.future.poll(cx);
+        shared.task_id.store(0, Ordering::Release);
+        ret
+    }
+}
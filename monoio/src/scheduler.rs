@@ -1,26 +1,104 @@
-use std::{cell::UnsafeCell, collections::VecDeque, marker::PhantomData};
+use std::{
+    cell::{Cell, UnsafeCell},
+    collections::VecDeque,
+    marker::PhantomData,
+};
 
 use crate::task::{Schedule, Task};
 
-pub(crate) struct LocalScheduler;
+/// Priority of a task spawned with [`spawn_with_priority`](crate::spawn_with_priority).
+///
+/// The ready tasks with higher priority run first. To prevent starvation, a level which has
+/// been skipped for a while runs one of its tasks ahead of the higher ones.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Priority {
+    /// For latency-sensitive tasks, e.g. accepting connections or control-plane.
+    High,
+    /// The priority of the tasks spawned with [`spawn`](crate::spawn).
+    #[default]
+    Normal,
+    /// For background tasks, e.g. bulk transfer.
+    Low,
+}
+
+impl Priority {
+    const LEVELS: usize = 3;
+
+    fn level(self) -> usize {
+        self as usize
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+pub(crate) struct LocalScheduler {
+    priority: Priority,
+}
+
+impl LocalScheduler {
+    pub(crate) fn new(priority: Priority) -> Self {
+        Self { priority }
+    }
+}
 
 impl Schedule for LocalScheduler {
     fn schedule(&self, task: Task<Self>) {
-        crate::runtime::CURRENT.with(|cx| cx.tasks.push(task));
+        crate::runtime::CURRENT.with(|cx| cx.tasks.push(task, self.priority));
     }
 
     fn yield_now(&self, task: Task<Self>) {
-        crate::runtime::CURRENT.with(|cx| cx.tasks.push_front(task));
+        crate::runtime::CURRENT.with(|cx| cx.tasks.push_front(task, self.priority));
+    }
+}
+
+/// How many times a level with ready tasks can be skipped before it runs one ahead of the
+/// higher levels.
+const AGING_THRESHOLD: u32 = 16;
+
+/// How many times in a row the task in the LIFO slot can run before the queue.
+const MAX_LIFO_POLLS: u32 = 3;
+
+/// Run queue settings of the runtime.
+#[derive(Debug, Clone)]
+pub(crate) struct SchedulerOpts {
+    /// Put the tasks woken or spawned by the running task into a LIFO slot.
+    pub(crate) lifo_slot: bool,
+    /// Initial capacity of the run queue.
+    pub(crate) queue_capacity: usize,
+    /// Max tasks to run before checking the driver, twice the queued tasks if not set.
+    pub(crate) max_tasks_per_tick: Option<usize>,
+}
+
+impl Default for SchedulerOpts {
+    fn default() -> Self {
+        Self {
+            lifo_slot: false,
+            queue_capacity: TaskQueue::DEFAULT_TASK_QUEUE_SIZE,
+            max_tasks_per_tick: None,
+        }
     }
 }
 
 pub(crate) struct TaskQueue {
-    // Local queue.
-    queue: UnsafeCell<VecDeque<Task<LocalScheduler>>>,
+    // Local queue of each priority level.
+    queue: UnsafeCell<Levels>,
+    // If a task is running.
+    in_task: Cell<bool>,
     // Make sure the type is `!Send` and `!Sync`.
     _marker: PhantomData<*const ()>,
 }
 
+struct Levels {
+    queues: [VecDeque<Task<LocalScheduler>>; Priority::LEVELS],
+    // Times each level has been skipped while it has ready tasks.
+    ages: [u32; Priority::LEVELS],
+    // The task woken by the running task, which runs next.
+    lifo: Option<(Task<LocalScheduler>, Priority)>,
+    lifo_enabled: bool,
+    // Times the LIFO slot has run in a row.
+    lifo_polls: u32,
+    len: usize,
+}
+
 impl Default for TaskQueue {
     fn default() -> Self {
         Self::new()
@@ -30,45 +108,127 @@ impl Default for TaskQueue {
 impl Drop for TaskQueue {
     fn drop(&mut self) {
         unsafe {
-            let queue = &mut *self.queue.get();
-            while let Some(_task) = queue.pop_front() {}
+            let levels = &mut *self.queue.get();
+            levels.lifo.take();
+            for queue in levels.queues.iter_mut() {
+                while let Some(_task) = queue.pop_front() {}
+            }
         }
     }
 }
 
 impl TaskQueue {
+    const DEFAULT_TASK_QUEUE_SIZE: usize = 4096;
+
     pub(crate) fn new() -> Self {
-        const DEFAULT_TASK_QUEUE_SIZE: usize = 4096;
-        Self::new_with_capacity(DEFAULT_TASK_QUEUE_SIZE)
+        Self::new_with_capacity(Self::DEFAULT_TASK_QUEUE_SIZE)
     }
+
     pub(crate) fn new_with_capacity(capacity: usize) -> Self {
         Self {
-            queue: UnsafeCell::new(VecDeque::with_capacity(capacity)),
+            queue: UnsafeCell::new(Levels {
+                queues: [
+                    VecDeque::new(),
+                    VecDeque::with_capacity(capacity),
+                    VecDeque::new(),
+                ],
+                ages: [0; Priority::LEVELS],
+                lifo: None,
+                lifo_enabled: false,
+                lifo_polls: 0,
+                len: 0,
+            }),
+            in_task: Cell::new(false),
             _marker: PhantomData,
         }
     }
 
+    pub(crate) fn with_opts(opts: &SchedulerOpts) -> Self {
+        let queue = Self::new_with_capacity(opts.queue_capacity);
+        unsafe { (*queue.queue.get()).lifo_enabled = opts.lifo_slot };
+        queue
+    }
+
+    /// Run the task, the tasks it wakes or spawns may go to the LIFO slot.
+    pub(crate) fn run(&self, task: Task<LocalScheduler>) {
+        struct Guard<'a>(&'a Cell<bool>);
+
+        impl Drop for Guard<'_> {
+            fn drop(&mut self) {
+                self.0.set(false);
+            }
+        }
+
+        self.in_task.set(true);
+        let _guard = Guard(&self.in_task);
+        task.run();
+    }
+
     pub(crate) fn len(&self) -> usize {
-        unsafe { (*self.queue.get()).len() }
+        unsafe { (*self.queue.get()).len }
     }
 
     pub(crate) fn is_empty(&self) -> bool {
         self.len() == 0
     }
 
-    pub(crate) fn push(&self, runnable: Task<LocalScheduler>) {
+    pub(crate) fn push(&self, runnable: Task<LocalScheduler>, priority: Priority) {
         unsafe {
-            (*self.queue.get()).push_back(runnable);
+            let levels = &mut *self.queue.get();
+            levels.len += 1;
+            if levels.lifo_enabled && self.in_task.get() {
+                // The replaced task goes to the queue.
+                if let Some((prev, priority)) = levels.lifo.replace((runnable, priority)) {
+                    levels.queues[priority.level()].push_back(prev);
+                }
+            } else {
+                levels.queues[priority.level()].push_back(runnable);
+            }
         }
     }
 
-    pub(crate) fn push_front(&self, runnable: Task<LocalScheduler>) {
+    pub(crate) fn push_front(&self, runnable: Task<LocalScheduler>, priority: Priority) {
         unsafe {
-            (*self.queue.get()).push_front(runnable);
+            let levels = &mut *self.queue.get();
+            levels.queues[priority.level()].push_front(runnable);
+            levels.len += 1;
         }
     }
 
     pub(crate) fn pop(&self) -> Option<Task<LocalScheduler>> {
-        unsafe { (*self.queue.get()).pop_front() }
+        unsafe { (*self.queue.get()).pop() }
+    }
+}
+
+impl Levels {
+    fn pop(&mut self) -> Option<Task<LocalScheduler>> {
+        if self.len == 0 {
+            return None;
+        }
+        if let Some((task, priority)) = self.lifo.take() {
+            if self.lifo_polls < MAX_LIFO_POLLS {
+                self.lifo_polls += 1;
+                self.len -= 1;
+                return Some(task);
+            }
+            // Ran too many times in a row, let the queue go first.
+            self.queues[priority.level()].push_back(task);
+        }
+        self.lifo_polls = 0;
+        // A starved level goes first, the lowest one has waited the longest.
+        let level = (1..Priority::LEVELS)
+            .rev()
+            .find(|&level| self.ages[level] >= AGING_THRESHOLD && !self.queues[level].is_empty())
+            .or_else(|| (0..Priority::LEVELS).find(|&level| !self.queues[level].is_empty()))?;
+
+        let task = self.queues[level].pop_front();
+        self.len -= 1;
+        self.ages[level] = 0;
+        for lower in level + 1..Priority::LEVELS {
+            if !self.queues[lower].is_empty() {
+                self.ages[lower] += 1;
+            }
+        }
+        task
     }
 }
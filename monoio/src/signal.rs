@@ -0,0 +1,327 @@
+//! Async signal handling based on signalfd(Linux only).
+//!
+//! The signals listened to are blocked in the calling thread, and read from a signalfd
+//! shared by the listeners of the thread, so each listener of a signal receives it. Only the
+//! signals directed to the process or the calling thread are received, so they should be
+//! listened to in the main thread before spawning the other threads, which inherit the
+//! blocked signals. Otherwise the other threads may handle them with the default action.
+
+use std::{
+    cell::{Cell, RefCell},
+    io,
+    rc::{Rc, Weak},
+    task::{Poll, Waker},
+};
+
+use crate::{
+    driver::{op::Op, shared_fd::SharedFd},
+    io::stream::Stream,
+};
+
+/// The kind of a signal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SignalKind(libc::c_int);
+
+impl SignalKind {
+    /// Create a `SignalKind` of the signal number.
+    #[inline]
+    pub const fn from_raw(signum: libc::c_int) -> Self {
+        Self(signum)
+    }
+
+    /// Returns the signal number.
+    #[inline]
+    pub const fn as_raw_value(&self) -> libc::c_int {
+        self.0
+    }
+
+    /// `SIGINT`, sent by the terminal on Ctrl-C.
+    #[inline]
+    pub const fn interrupt() -> Self {
+        Self(libc::SIGINT)
+    }
+
+    /// `SIGTERM`, the default signal to request termination.
+    #[inline]
+    pub const fn terminate() -> Self {
+        Self(libc::SIGTERM)
+    }
+
+    /// `SIGHUP`, sent when the terminal is closed, and often used to reload configs.
+    #[inline]
+    pub const fn hangup() -> Self {
+        Self(libc::SIGHUP)
+    }
+
+    /// `SIGQUIT`, sent by the terminal on Ctrl-\.
+    #[inline]
+    pub const fn quit() -> Self {
+        Self(libc::SIGQUIT)
+    }
+
+    /// `SIGCHLD`, sent when a child process exits or stops.
+    #[inline]
+    pub const fn child() -> Self {
+        Self(libc::SIGCHLD)
+    }
+
+    /// `SIGPIPE`, sent when writing to a pipe without readers.
+    #[inline]
+    pub const fn pipe() -> Self {
+        Self(libc::SIGPIPE)
+    }
+
+    /// `SIGALRM`, sent when a timer set by `alarm(2)` expires.
+    #[inline]
+    pub const fn alarm() -> Self {
+        Self(libc::SIGALRM)
+    }
+
+    /// `SIGUSR1`, defined by the application.
+    #[inline]
+    pub const fn user_defined1() -> Self {
+        Self(libc::SIGUSR1)
+    }
+
+    /// `SIGUSR2`, defined by the application.
+    #[inline]
+    pub const fn user_defined2() -> Self {
+        Self(libc::SIGUSR2)
+    }
+
+    /// `SIGWINCH`, sent when the size of the terminal changes.
+    #[inline]
+    pub const fn window_change() -> Self {
+        Self(libc::SIGWINCH)
+    }
+}
+
+impl From<libc::c_int> for SignalKind {
+    #[inline]
+    fn from(signum: libc::c_int) -> Self {
+        Self(signum)
+    }
+}
+
+/// A listener of a signal, receiving each delivery of it after created.
+///
+/// The deliveries of a standard signal before it is received may be merged into one.
+pub struct Signal {
+    listener: Rc<Listener>,
+    registry: Rc<Registry>,
+}
+
+/// Create a listener of the signal. `SIGKILL`, `SIGSTOP` and the signals caused by faults
+/// like `SIGSEGV` can not be listened to.
+pub fn signal(kind: SignalKind) -> io::Result<Signal> {
+    let signum = kind.as_raw_value();
+    let forbidden = [
+        libc::SIGKILL,
+        libc::SIGSTOP,
+        libc::SIGILL,
+        libc::SIGFPE,
+        libc::SIGSEGV,
+        libc::SIGBUS,
+    ];
+    if signum <= 0 || signum > libc::SIGRTMAX() || forbidden.contains(&signum) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("signal {signum} can not be listened to"),
+        ));
+    }
+    let registry = Registry::current()?;
+    registry.add(signum)?;
+    let listener = Rc::new(Listener {
+        signum,
+        pending: Cell::new(0),
+        waker: RefCell::new(None),
+    });
+    registry
+        .listeners
+        .borrow_mut()
+        .push(Rc::downgrade(&listener));
+    Ok(Signal { listener, registry })
+}
+
+/// Wait for a Ctrl-C(`SIGINT`).
+///
+/// `SIGINT` is listened to when the future is first polled, and the deliveries before that
+/// terminate the process.
+pub async fn ctrl_c() -> io::Result<()> {
+    signal(SignalKind::interrupt())?.recv().await
+}
+
+impl Signal {
+    /// Wait for the next delivery of the signal.
+    pub async fn recv(&mut self) -> io::Result<()> {
+        loop {
+            if self.listener.take() {
+                return Ok(());
+            }
+            if self.registry.reading.replace(true) {
+                // Another listener is reading, wait for it to dispatch.
+                std::future::poll_fn(|cx| {
+                    if self.listener.pending.get() > 0 || !self.registry.reading.get() {
+                        return Poll::Ready(());
+                    }
+                    *self.listener.waker.borrow_mut() = Some(cx.waker().clone());
+                    Poll::Pending
+                })
+                .await;
+                continue;
+            }
+            let _guard = ReadingGuard(&self.registry);
+            Op::poll_read(&self.registry.fd, false)?.wait().await?;
+            self.registry.dispatch()?;
+        }
+    }
+}
+
+impl Stream for Signal {
+    type Item = io::Result<()>;
+
+    #[inline]
+    async fn next(&mut self) -> Option<Self::Item> {
+        Some(self.recv().await)
+    }
+}
+
+impl std::fmt::Debug for Signal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Signal")
+            .field("signum", &self.listener.signum)
+            .finish()
+    }
+}
+
+struct Listener {
+    signum: libc::c_int,
+    pending: Cell<usize>,
+    waker: RefCell<Option<Waker>>,
+}
+
+impl Listener {
+    fn take(&self) -> bool {
+        let pending = self.pending.get();
+        if pending == 0 {
+            return false;
+        }
+        self.pending.set(pending - 1);
+        true
+    }
+
+    fn wake(&self) {
+        if let Some(waker) = self.waker.borrow_mut().take() {
+            waker.wake();
+        }
+    }
+}
+
+/// The signalfd of the thread, and the listeners of it.
+struct Registry {
+    fd: SharedFd,
+    mask: RefCell<libc::sigset_t>,
+    listeners: RefCell<Vec<Weak<Listener>>>,
+    /// If a listener is reading the signalfd.
+    reading: Cell<bool>,
+}
+
+thread_local! {
+    static REGISTRY: RefCell<Weak<Registry>> = const { RefCell::new(Weak::new()) };
+}
+
+/// Stop reading, and wake the listeners waiting so that one of them takes over.
+struct ReadingGuard<'a>(&'a Registry);
+
+impl Drop for ReadingGuard<'_> {
+    fn drop(&mut self) {
+        self.0.reading.set(false);
+        self.0.wake_all();
+    }
+}
+
+impl Registry {
+    fn current() -> io::Result<Rc<Registry>> {
+        REGISTRY.with(|current| {
+            if let Some(registry) = current.borrow().upgrade() {
+                return Ok(registry);
+            }
+            // Safety: the set is initialized by `sigemptyset`.
+            let mask = unsafe {
+                let mut mask = std::mem::zeroed();
+                libc::sigemptyset(&mut mask);
+                mask
+            };
+            let fd = crate::syscall!(signalfd@RAW(
+                -1,
+                &mask,
+                libc::SFD_NONBLOCK | libc::SFD_CLOEXEC
+            ))?;
+            let registry = Rc::new(Registry {
+                fd: SharedFd::new::<false>(fd)?,
+                mask: RefCell::new(mask),
+                listeners: RefCell::new(Vec::new()),
+                reading: Cell::new(false),
+            });
+            *current.borrow_mut() = Rc::downgrade(&registry);
+            Ok(registry)
+        })
+    }
+
+    /// Block the signal in the current thread, and read it from the signalfd.
+    fn add(&self, signum: libc::c_int) -> io::Result<()> {
+        let mut mask = self.mask.borrow_mut();
+        let mut new_mask = *mask;
+        // Safety: the sets are valid, and the signal number is checked.
+        unsafe {
+            if libc::sigismember(&*mask, signum) == 1 {
+                return Ok(());
+            }
+            let mut block = std::mem::zeroed();
+            libc::sigemptyset(&mut block);
+            libc::sigaddset(&mut block, signum);
+            match libc::pthread_sigmask(libc::SIG_BLOCK, &block, std::ptr::null_mut()) {
+                0 => {}
+                errno => return Err(io::Error::from_raw_os_error(errno)),
+            }
+            libc::sigaddset(&mut new_mask, signum);
+        }
+        crate::syscall!(signalfd@RAW(self.fd.raw_fd(), &new_mask, 0))?;
+        *mask = new_mask;
+        Ok(())
+    }
+
+    /// Read the signals available, and notify the listeners of them.
+    fn dispatch(&self) -> io::Result<()> {
+        const SIGINFO_LEN: usize = std::mem::size_of::<libc::signalfd_siginfo>();
+        // Safety: all zero is a valid `signalfd_siginfo`.
+        let mut infos: [libc::signalfd_siginfo; 8] = unsafe { std::mem::zeroed() };
+        loop {
+            let n = match crate::syscall!(read@RAW(
+                self.fd.raw_fd(),
+                infos.as_mut_ptr() as *mut libc::c_void,
+                std::mem::size_of_val(&infos)
+            )) {
+                Ok(n) => n as usize / SIGINFO_LEN,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(()),
+                Err(e) => return Err(e),
+            };
+            let mut listeners = self.listeners.borrow_mut();
+            listeners.retain(|listener| listener.strong_count() > 0);
+            for info in &infos[..n] {
+                for listener in listeners.iter().filter_map(Weak::upgrade) {
+                    if listener.signum as u32 == info.ssi_signo {
+                        listener.pending.set(listener.pending.get() + 1);
+                        listener.wake();
+                    }
+                }
+            }
+        }
+    }
+
+    fn wake_all(&self) {
+        for listener in self.listeners.borrow().iter().filter_map(Weak::upgrade) {
+            listener.wake();
+        }
+    }
+}
@@ -0,0 +1,305 @@
+//! A multi-producer, multi-consumer channel for the tasks on the same thread, where each value
+//! sent is received by all the receivers.
+//!
+//! The channel keeps the last `capacity` values. Sending never waits, so a receiver falling
+//! behind skips the values overwritten, and gets [`RecvError::Lagged`] with the number of them.
+//!
+//! ```
+//! #[monoio::main]
+//! async fn main() {
+//!     let (tx, mut rx1) = monoio::sync::broadcast::channel(16);
+//!     let mut rx2 = tx.subscribe();
+//!     tx.send(1).unwrap();
+//!     assert_eq!(rx1.recv().await, Ok(1));
+//!     assert_eq!(rx2.recv().await, Ok(1));
+//! }
+//! ```
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    fmt,
+    future::Future,
+    rc::Rc,
+    task::{Context, Poll},
+};
+
+use super::Waiters;
+
+/// Create a broadcast channel which keeps at most `capacity` values.
+///
+/// # Panics
+///
+/// Panics if `capacity` is 0.
+pub fn channel<T: Clone>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(capacity > 0, "broadcast channel requires capacity > 0");
+    let shared = Rc::new(Shared {
+        buffer: RefCell::new(VecDeque::with_capacity(capacity)),
+        head: Cell::new(0),
+        capacity,
+        senders: Cell::new(1),
+        receivers: Cell::new(0),
+        waiters: RefCell::new(Waiters::default()),
+    });
+    let rx = Receiver::new(&shared, 0);
+    (Sender { shared }, rx)
+}
+
+struct Shared<T> {
+    buffer: RefCell<VecDeque<T>>,
+    // Position of the first value in the buffer
+    head: Cell<u64>,
+    capacity: usize,
+    senders: Cell<usize>,
+    receivers: Cell<usize>,
+    waiters: RefCell<Waiters>,
+}
+
+impl<T> Shared<T> {
+    /// Position of the next value sent.
+    fn tail(&self) -> u64 {
+        self.head.get() + self.buffer.borrow().len() as u64
+    }
+
+    fn wake_all(&self) {
+        let wakers = self.waiters.borrow_mut().take_all();
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
+
+/// Error of sending to the channel without receivers, which returns the value.
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub struct SendError<T>(pub T);
+
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SendError").finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "channel closed".fmt(f)
+    }
+}
+
+impl<T> std::error::Error for SendError<T> {}
+
+/// Error of [`Receiver::recv`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RecvError {
+    /// All the senders are dropped, and the values sent are received.
+    Closed,
+    /// The receiver falls behind, and the number of the values skipped.
+    Lagged(u64),
+}
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Closed => "channel closed".fmt(f),
+            Self::Lagged(n) => write!(f, "channel lagged by {n}"),
+        }
+    }
+}
+
+impl std::error::Error for RecvError {}
+
+/// Error of [`Receiver::try_recv`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TryRecvError {
+    /// There is no new value.
+    Empty,
+    /// All the senders are dropped, and the values sent are received.
+    Closed,
+    /// The receiver falls behind, and the number of the values skipped.
+    Lagged(u64),
+}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => "channel empty".fmt(f),
+            Self::Closed => "channel closed".fmt(f),
+            Self::Lagged(n) => write!(f, "channel lagged by {n}"),
+        }
+    }
+}
+
+impl std::error::Error for TryRecvError {}
+
+/// Sending half of the broadcast channel, which can be cloned.
+pub struct Sender<T> {
+    shared: Rc<Shared<T>>,
+}
+
+impl<T> Sender<T> {
+    /// Send the value to all the receivers, and return the number of them. The oldest value is
+    /// overwritten if the channel is full.
+    ///
+    /// The value is returned if there is no receiver.
+    pub fn send(&self, value: T) -> Result<usize, SendError<T>> {
+        let receivers = self.shared.receivers.get();
+        if receivers == 0 {
+            return Err(SendError(value));
+        }
+        let overwritten = {
+            let mut buffer = self.shared.buffer.borrow_mut();
+            let overwritten = if buffer.len() == self.shared.capacity {
+                self.shared.head.set(self.shared.head.get() + 1);
+                buffer.pop_front()
+            } else {
+                None
+            };
+            buffer.push_back(value);
+            overwritten
+        };
+        drop(overwritten);
+        self.shared.wake_all();
+        Ok(receivers)
+    }
+
+    /// Create a receiver, which receives the values sent after it.
+    pub fn subscribe(&self) -> Receiver<T> {
+        Receiver::new(&self.shared, self.shared.tail())
+    }
+
+    /// The number of the receivers.
+    pub fn receiver_count(&self) -> usize {
+        self.shared.receivers.get()
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.shared.senders.set(self.shared.senders.get() + 1);
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.shared.senders.set(self.shared.senders.get() - 1);
+        if self.shared.senders.get() == 0 {
+            self.shared.wake_all();
+        }
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender")
+            .field("receivers", &self.receiver_count())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Receiving half of the broadcast channel.
+pub struct Receiver<T> {
+    shared: Rc<Shared<T>>,
+    // Position of the next value to receive
+    next: u64,
+    // Id of the waiter
+    id: u64,
+}
+
+impl<T> Receiver<T> {
+    fn new(shared: &Rc<Shared<T>>, next: u64) -> Self {
+        shared.receivers.set(shared.receivers.get() + 1);
+        let id = shared.waiters.borrow_mut().next_id();
+        Self {
+            shared: shared.clone(),
+            next,
+            id,
+        }
+    }
+
+    /// Create a receiver, which receives the values sent after it.
+    pub fn resubscribe(&self) -> Self {
+        Self::new(&self.shared, self.shared.tail())
+    }
+
+    /// The number of the values not received yet.
+    pub fn len(&self) -> usize {
+        (self.shared.tail() - self.next.max(self.shared.head.get())) as usize
+    }
+
+    /// Whether there is no new value.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T: Clone> Receiver<T> {
+    /// Receive the next value.
+    pub fn recv(&mut self) -> impl Future<Output = Result<T, RecvError>> + '_ {
+        std::future::poll_fn(|cx| self.poll_recv(cx))
+    }
+
+    /// Poll to receive the next value.
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Result<T, RecvError>> {
+        match self.try_recv() {
+            Ok(value) => Poll::Ready(Ok(value)),
+            Err(TryRecvError::Closed) => Poll::Ready(Err(RecvError::Closed)),
+            Err(TryRecvError::Lagged(n)) => Poll::Ready(Err(RecvError::Lagged(n))),
+            Err(TryRecvError::Empty) => {
+                self.shared
+                    .waiters
+                    .borrow_mut()
+                    .register(self.id, cx.waker());
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Try to receive the next value without waiting.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        let head = self.shared.head.get();
+        if self.next < head {
+            let lagged = head - self.next;
+            self.next = head;
+            return Err(TryRecvError::Lagged(lagged));
+        }
+        let buffer = self.shared.buffer.borrow();
+        match buffer.get((self.next - head) as usize) {
+            Some(value) => {
+                self.next += 1;
+                Ok(value.clone())
+            }
+            None if self.shared.senders.get() == 0 => Err(TryRecvError::Closed),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.shared.receivers.set(self.shared.receivers.get() - 1);
+        self.shared.waiters.borrow_mut().remove(self.id);
+    }
+}
+
+impl<T: Clone> crate::io::stream::Stream for Receiver<T> {
+    type Item = Result<T, RecvError>;
+
+    /// Receive the next value, and end when the channel is closed.
+    #[inline]
+    async fn next(&mut self) -> Option<Self::Item> {
+        match self.recv().await {
+            Err(RecvError::Closed) => None,
+            res => Some(res),
+        }
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver")
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
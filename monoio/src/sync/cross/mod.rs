@@ -0,0 +1,235 @@
+//! Channels to communicate between the threads, e.g. the runtimes of a core-to-core pipeline.
+//!
+//! The channels are bounded and `Send`. The receiver is woken with the waker of its task, which
+//! unparks its runtime through the driver if it sleeps, and it is woken only once for all the
+//! values sent until it runs again, so a batch of values costs a single wakeup. The senders
+//! waiting for the capacity are woken in the same way.
+//!
+//! [`spsc`] is a lock-free ring for a single sender, and [`mpsc`] is a lock-free queue for
+//! multiple senders.
+
+pub mod mpsc;
+pub mod spsc;
+
+use std::{
+    marker::PhantomData,
+    sync::{
+        atomic::{fence, AtomicBool, AtomicU8, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll, Waker},
+};
+
+pub use super::mpsc::{SendError, TryRecvError, TrySendError};
+
+/// A bounded queue with a single consumer.
+trait Queue<T>: Send + Sync {
+    fn push(&self, value: T) -> Result<(), T>;
+
+    /// # Safety
+    ///
+    /// It must not be called concurrently.
+    unsafe fn pop(&self) -> Option<T>;
+
+    fn len(&self) -> usize;
+
+    fn capacity(&self) -> usize;
+}
+
+#[cfg_attr(target_arch = "x86_64", repr(align(128)))]
+#[cfg_attr(not(target_arch = "x86_64"), repr(align(64)))]
+#[derive(Debug, Default)]
+struct CachePadded<T>(T);
+
+// States of the receiver
+const IDLE: u8 = 0;
+const WAITING: u8 = 1;
+const NOTIFIED: u8 = 2;
+
+struct Chan<T, Q> {
+    queue: Q,
+    // Whether the receiver waits for a value, it is woken once when it changes to NOTIFIED
+    rx_state: AtomicU8,
+    rx_waker: Mutex<Option<Waker>>,
+    // Whether any sender waits for the capacity
+    tx_waiting: AtomicBool,
+    tx_wakers: Mutex<Vec<Waker>>,
+    senders: AtomicUsize,
+    rx_closed: AtomicBool,
+    _mark: PhantomData<T>,
+}
+
+impl<T, Q: Queue<T>> Chan<T, Q> {
+    fn new(queue: Q) -> Arc<Self> {
+        Arc::new(Self {
+            queue,
+            rx_state: AtomicU8::new(IDLE),
+            rx_waker: Mutex::new(None),
+            tx_waiting: AtomicBool::new(false),
+            tx_wakers: Mutex::new(Vec::new()),
+            senders: AtomicUsize::new(1),
+            rx_closed: AtomicBool::new(false),
+            _mark: PhantomData,
+        })
+    }
+
+    fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        if self.rx_closed.load(Ordering::Acquire) {
+            return Err(TrySendError::Closed(value));
+        }
+        self.queue.push(value).map_err(TrySendError::Full)?;
+        self.notify_rx();
+        Ok(())
+    }
+
+    /// Send the value in the slot, which is put back if it waits for the capacity.
+    fn poll_send(
+        &self,
+        cx: &mut Context<'_>,
+        slot: &mut Option<T>,
+    ) -> Poll<Result<(), SendError<T>>> {
+        let value = slot.take().expect("polled after completion");
+        let value = match self.try_send(value) {
+            Ok(()) => return Poll::Ready(Ok(())),
+            Err(TrySendError::Closed(v)) => return Poll::Ready(Err(SendError(v))),
+            Err(TrySendError::Full(v)) => v,
+        };
+        {
+            let mut wakers = self.tx_wakers.lock().unwrap();
+            if !wakers.iter().any(|w| w.will_wake(cx.waker())) {
+                wakers.push(cx.waker().clone());
+            }
+            self.tx_waiting.store(true, Ordering::SeqCst);
+        }
+        // The receiver may take a value before it sees the sender waiting.
+        fence(Ordering::SeqCst);
+        match self.try_send(value) {
+            Ok(()) => Poll::Ready(Ok(())),
+            Err(TrySendError::Closed(v)) => Poll::Ready(Err(SendError(v))),
+            Err(TrySendError::Full(v)) => {
+                *slot = Some(v);
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Wake the receiver if it waits, only the first value of a batch wakes it.
+    fn notify_rx(&self) {
+        fence(Ordering::SeqCst);
+        if self.rx_state.swap(NOTIFIED, Ordering::SeqCst) == WAITING {
+            let waker = self.rx_waker.lock().unwrap().take();
+            if let Some(waker) = waker {
+                waker.wake();
+            }
+        }
+    }
+
+    /// Wake the waiting senders after the receiver takes values.
+    fn notify_tx(&self) {
+        fence(Ordering::SeqCst);
+        if !self.tx_waiting.load(Ordering::SeqCst) {
+            return;
+        }
+        let wakers = {
+            let mut wakers = self.tx_wakers.lock().unwrap();
+            self.tx_waiting.store(false, Ordering::SeqCst);
+            std::mem::take(&mut *wakers)
+        };
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    /// # Safety
+    ///
+    /// It must be called by the receiver.
+    unsafe fn try_recv(&self) -> Result<T, TryRecvError> {
+        if let Some(value) = self.queue.pop() {
+            self.notify_tx();
+            return Ok(value);
+        }
+        if self.senders.load(Ordering::Acquire) == 0 || self.rx_closed.load(Ordering::Acquire) {
+            // The values sent before the senders are dropped are visible now.
+            return self.queue.pop().ok_or(TryRecvError::Disconnected);
+        }
+        Err(TryRecvError::Empty)
+    }
+
+    /// # Safety
+    ///
+    /// It must be called by the receiver.
+    unsafe fn poll_recv(&self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        match self.try_recv() {
+            Ok(value) => return Poll::Ready(Some(value)),
+            Err(TryRecvError::Disconnected) => return Poll::Ready(None),
+            Err(TryRecvError::Empty) => (),
+        }
+        {
+            let mut waker = self.rx_waker.lock().unwrap();
+            match waker.as_mut() {
+                Some(w) => w.clone_from(cx.waker()),
+                None => *waker = Some(cx.waker().clone()),
+            }
+        }
+        self.rx_state.store(WAITING, Ordering::SeqCst);
+        // A value may be sent before the receiver is seen waiting.
+        fence(Ordering::SeqCst);
+        match self.try_recv() {
+            Ok(value) => Poll::Ready(Some(value)),
+            Err(TryRecvError::Disconnected) => Poll::Ready(None),
+            Err(TryRecvError::Empty) => Poll::Pending,
+        }
+    }
+
+    /// # Safety
+    ///
+    /// It must be called by the receiver.
+    unsafe fn poll_recv_many(
+        &self,
+        cx: &mut Context<'_>,
+        buf: &mut Vec<T>,
+        limit: usize,
+    ) -> Poll<usize> {
+        if limit == 0 {
+            return Poll::Ready(0);
+        }
+        let Some(value) = std::task::ready!(self.poll_recv(cx)) else {
+            return Poll::Ready(0);
+        };
+        buf.push(value);
+        let mut n = 1;
+        while n < limit {
+            match self.try_recv() {
+                Ok(value) => buf.push(value),
+                Err(_) => break,
+            }
+            n += 1;
+        }
+        Poll::Ready(n)
+    }
+
+    fn add_sender(self: &Arc<Self>) -> Arc<Self> {
+        self.senders.fetch_add(1, Ordering::Relaxed);
+        self.clone()
+    }
+
+    fn drop_sender(&self) {
+        if self.senders.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.notify_rx();
+        }
+    }
+
+    fn close(&self) {
+        self.rx_closed.store(true, Ordering::Release);
+        self.tx_waiting.store(true, Ordering::SeqCst);
+        self.notify_tx();
+    }
+
+    /// # Safety
+    ///
+    /// It must be called by the receiver.
+    unsafe fn drop_receiver(&self) {
+        self.close();
+        while self.queue.pop().is_some() {}
+    }
+}
@@ -0,0 +1,286 @@
+//! A bounded multi-producer, single-consumer channel between the threads, backed by a lock-free
+//! queue.
+//!
+//! ```
+//! let (tx, mut rx) = monoio::sync::cross::mpsc::channel(16);
+//! let producers: Vec<_> = (0..4)
+//!     .map(|_| {
+//!         let tx = tx.clone();
+//!         std::thread::spawn(move || {
+//!             monoio::RuntimeBuilder::<monoio::FusionDriver>::new()
+//!                 .build()
+//!                 .unwrap()
+//!                 .block_on(async move {
+//!                     for i in 0..100 {
+//!                         tx.send(i).await.unwrap();
+//!                     }
+//!                 });
+//!         })
+//!     })
+//!     .collect();
+//! drop(tx);
+//! monoio::RuntimeBuilder::<monoio::FusionDriver>::new()
+//!     .build()
+//!     .unwrap()
+//!     .block_on(async move {
+//!         let mut sum = 0;
+//!         while let Some(i) = rx.recv().await {
+//!             sum += i;
+//!         }
+//!         assert_eq!(sum, 4950 * 4);
+//!     });
+//! for producer in producers {
+//!     producer.join().unwrap();
+//! }
+//! ```
+
+use std::{
+    cell::UnsafeCell,
+    fmt,
+    future::Future,
+    mem::MaybeUninit,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+};
+
+use super::{CachePadded, Chan, Queue, SendError, TryRecvError, TrySendError};
+
+/// Create a channel which buffers at most `capacity` values.
+///
+/// # Panics
+///
+/// Panics if `capacity` is 0.
+pub fn channel<T: Send>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(capacity > 0, "mpsc channel requires capacity > 0");
+    let chan = Chan::new(BoundedQueue::new(capacity));
+    (Sender { chan: chan.clone() }, Receiver { chan })
+}
+
+struct Slot<T> {
+    // `pos` if it is free for the value at `pos`, `pos + 1` if it holds the value at `pos`
+    seq: AtomicUsize,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+/// The bounded queue of Dmitry Vyukov with a single consumer.
+struct BoundedQueue<T> {
+    buffer: Box<[Slot<T>]>,
+    // Position of the next value to pop, written by the consumer
+    head: CachePadded<AtomicUsize>,
+    // Position of the next value to push, claimed by the producers
+    tail: CachePadded<AtomicUsize>,
+}
+
+// Safety: a slot is accessed by the producer claiming it or the consumer exclusively.
+unsafe impl<T: Send> Send for BoundedQueue<T> {}
+unsafe impl<T: Send> Sync for BoundedQueue<T> {}
+
+impl<T> BoundedQueue<T> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            buffer: (0..capacity)
+                .map(|pos| Slot {
+                    seq: AtomicUsize::new(pos),
+                    value: UnsafeCell::new(MaybeUninit::uninit()),
+                })
+                .collect(),
+            head: CachePadded(AtomicUsize::new(0)),
+            tail: CachePadded(AtomicUsize::new(0)),
+        }
+    }
+
+    #[inline]
+    fn slot(&self, pos: usize) -> &Slot<T> {
+        &self.buffer[pos % self.buffer.len()]
+    }
+}
+
+impl<T: Send> Queue<T> for BoundedQueue<T> {
+    fn push(&self, value: T) -> Result<(), T> {
+        let mut pos = self.tail.0.load(Ordering::Relaxed);
+        loop {
+            let slot = self.slot(pos);
+            let seq = slot.seq.load(Ordering::Acquire);
+            match (seq as isize).wrapping_sub(pos as isize) {
+                0 => match self.tail.0.compare_exchange_weak(
+                    pos,
+                    pos.wrapping_add(1),
+                    Ordering::Relaxed,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        unsafe { (*slot.value.get()).write(value) };
+                        slot.seq.store(pos.wrapping_add(1), Ordering::Release);
+                        return Ok(());
+                    }
+                    Err(current) => pos = current,
+                },
+                // The slot still holds the value of the last lap.
+                diff if diff < 0 => return Err(value),
+                // Claimed by another producer.
+                _ => pos = self.tail.0.load(Ordering::Relaxed),
+            }
+        }
+    }
+
+    unsafe fn pop(&self) -> Option<T> {
+        let pos = self.head.0.load(Ordering::Relaxed);
+        let slot = self.slot(pos);
+        if slot.seq.load(Ordering::Acquire) != pos.wrapping_add(1) {
+            return None;
+        }
+        let value = (*slot.value.get()).assume_init_read();
+        // Free the slot for the next lap.
+        slot.seq
+            .store(pos.wrapping_add(self.buffer.len()), Ordering::Release);
+        self.head.0.store(pos.wrapping_add(1), Ordering::Release);
+        Some(value)
+    }
+
+    fn len(&self) -> usize {
+        let head = self.head.0.load(Ordering::Acquire);
+        let tail = self.tail.0.load(Ordering::Acquire);
+        tail.wrapping_sub(head).min(self.buffer.len())
+    }
+
+    fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+}
+
+impl<T> Drop for BoundedQueue<T> {
+    fn drop(&mut self) {
+        let mut pos = *self.head.0.get_mut();
+        loop {
+            let slot = &mut self.buffer[pos % self.buffer.len()];
+            if *slot.seq.get_mut() != pos.wrapping_add(1) {
+                break;
+            }
+            unsafe { slot.value.get_mut().assume_init_drop() };
+            pos = pos.wrapping_add(1);
+        }
+    }
+}
+
+/// Sending half of the channel, which can be cloned.
+pub struct Sender<T: Send> {
+    chan: Arc<Chan<T, BoundedQueue<T>>>,
+}
+
+impl<T: Send> Sender<T> {
+    /// Send the value, and wait for the capacity if the channel is full.
+    ///
+    /// The value is returned if the receiver is closed. If the future is dropped, the value is
+    /// not sent.
+    pub fn send(&self, value: T) -> impl Future<Output = Result<(), SendError<T>>> + '_ {
+        let mut slot = Some(value);
+        std::future::poll_fn(move |cx| self.chan.poll_send(cx, &mut slot))
+    }
+
+    /// Try to send the value without waiting.
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        self.chan.try_send(value)
+    }
+
+    /// Whether the receiver is closed.
+    pub fn is_closed(&self) -> bool {
+        self.chan.rx_closed.load(Ordering::Acquire)
+    }
+
+    /// The capacity the channel is created with.
+    pub fn max_capacity(&self) -> usize {
+        self.chan.queue.capacity()
+    }
+}
+
+impl<T: Send> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            chan: self.chan.add_sender(),
+        }
+    }
+}
+
+impl<T: Send> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.chan.drop_sender();
+    }
+}
+
+impl<T: Send> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender")
+            .field("capacity", &self.max_capacity())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Receiving half of the channel.
+pub struct Receiver<T: Send> {
+    chan: Arc<Chan<T, BoundedQueue<T>>>,
+}
+
+impl<T: Send> Receiver<T> {
+    /// Receive the next value, or `None` if the channel is empty and all the senders are
+    /// dropped or the receiver is closed.
+    pub fn recv(&mut self) -> impl Future<Output = Option<T>> + '_ {
+        std::future::poll_fn(|cx| self.poll_recv(cx))
+    }
+
+    /// Poll to receive the next value.
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        // Safety: it is the only receiver.
+        unsafe { self.chan.poll_recv(cx) }
+    }
+
+    /// Wait for values, and receive at most `limit` of them into `buf`. It returns the number of
+    /// the values received, which is 0 only if the channel is closed or `limit` is 0.
+    pub fn recv_many<'a>(
+        &'a mut self,
+        buf: &'a mut Vec<T>,
+        limit: usize,
+    ) -> impl Future<Output = usize> + 'a {
+        // Safety: it is the only receiver.
+        std::future::poll_fn(move |cx| unsafe { self.chan.poll_recv_many(cx, buf, limit) })
+    }
+
+    /// Try to receive the next value without waiting.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        // Safety: it is the only receiver.
+        unsafe { self.chan.try_recv() }
+    }
+
+    /// Close the receiver, so no more values can be sent, while the buffered values can still
+    /// be received.
+    pub fn close(&mut self) {
+        self.chan.close();
+    }
+
+    /// The number of the buffered values.
+    pub fn len(&self) -> usize {
+        self.chan.queue.len()
+    }
+
+    /// Whether there is no buffered value.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T: Send> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        // Safety: it is the only receiver.
+        unsafe { self.chan.drop_receiver() };
+    }
+}
+
+impl<T: Send> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver")
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
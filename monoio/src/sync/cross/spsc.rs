@@ -0,0 +1,240 @@
+//! A bounded single-producer, single-consumer channel between the threads, backed by a lock-free
+//! ring.
+//!
+//! ```
+//! let (mut tx, mut rx) = monoio::sync::cross::spsc::channel(16);
+//! let producer = std::thread::spawn(move || {
+//!     monoio::RuntimeBuilder::<monoio::FusionDriver>::new()
+//!         .build()
+//!         .unwrap()
+//!         .block_on(async move {
+//!             for i in 0..100 {
+//!                 tx.send(i).await.unwrap();
+//!             }
+//!         });
+//! });
+//! monoio::RuntimeBuilder::<monoio::FusionDriver>::new()
+//!     .build()
+//!     .unwrap()
+//!     .block_on(async move {
+//!         let mut sum = 0;
+//!         while let Some(i) = rx.recv().await {
+//!             sum += i;
+//!         }
+//!         assert_eq!(sum, 4950);
+//!     });
+//! producer.join().unwrap();
+//! ```
+
+use std::{
+    cell::UnsafeCell,
+    fmt,
+    future::Future,
+    mem::MaybeUninit,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+};
+
+use super::{CachePadded, Chan, Queue, SendError, TryRecvError, TrySendError};
+
+/// Create a channel which buffers at most `capacity` values.
+///
+/// # Panics
+///
+/// Panics if `capacity` is 0.
+pub fn channel<T: Send>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(capacity > 0, "spsc channel requires capacity > 0");
+    let chan = Chan::new(Ring::new(capacity));
+    (Sender { chan: chan.clone() }, Receiver { chan })
+}
+
+struct Ring<T> {
+    buffer: Box<[UnsafeCell<MaybeUninit<T>>]>,
+    // Position of the next value to pop, written by the consumer
+    head: CachePadded<AtomicUsize>,
+    // Position of the next value to push, written by the producer
+    tail: CachePadded<AtomicUsize>,
+}
+
+// Safety: the slots are accessed by the producer and the consumer exclusively.
+unsafe impl<T: Send> Send for Ring<T> {}
+unsafe impl<T: Send> Sync for Ring<T> {}
+
+impl<T> Ring<T> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            buffer: (0..capacity)
+                .map(|_| UnsafeCell::new(MaybeUninit::uninit()))
+                .collect(),
+            head: CachePadded(AtomicUsize::new(0)),
+            tail: CachePadded(AtomicUsize::new(0)),
+        }
+    }
+
+    #[inline]
+    fn slot(&self, pos: usize) -> *mut MaybeUninit<T> {
+        self.buffer[pos % self.buffer.len()].get()
+    }
+}
+
+impl<T: Send> Queue<T> for Ring<T> {
+    /// It is only called by the sender with `&mut self`.
+    fn push(&self, value: T) -> Result<(), T> {
+        let tail = self.tail.0.load(Ordering::Relaxed);
+        let head = self.head.0.load(Ordering::Acquire);
+        if tail.wrapping_sub(head) == self.buffer.len() {
+            return Err(value);
+        }
+        unsafe { (*self.slot(tail)).write(value) };
+        self.tail.0.store(tail.wrapping_add(1), Ordering::Release);
+        Ok(())
+    }
+
+    unsafe fn pop(&self) -> Option<T> {
+        let head = self.head.0.load(Ordering::Relaxed);
+        let tail = self.tail.0.load(Ordering::Acquire);
+        if head == tail {
+            return None;
+        }
+        let value = (*self.slot(head)).assume_init_read();
+        self.head.0.store(head.wrapping_add(1), Ordering::Release);
+        Some(value)
+    }
+
+    fn len(&self) -> usize {
+        let head = self.head.0.load(Ordering::Acquire);
+        let tail = self.tail.0.load(Ordering::Acquire);
+        tail.wrapping_sub(head)
+    }
+
+    fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+}
+
+impl<T> Drop for Ring<T> {
+    fn drop(&mut self) {
+        let tail = *self.tail.0.get_mut();
+        let mut head = *self.head.0.get_mut();
+        while head != tail {
+            unsafe { (*self.slot(head)).assume_init_drop() };
+            head = head.wrapping_add(1);
+        }
+    }
+}
+
+/// Sending half of the channel.
+pub struct Sender<T: Send> {
+    // The ring is pushed with `&mut self` since it has a single producer
+    chan: Arc<Chan<T, Ring<T>>>,
+}
+
+impl<T: Send> Sender<T> {
+    /// Send the value, and wait for the capacity if the channel is full.
+    ///
+    /// The value is returned if the receiver is closed. If the future is dropped, the value is
+    /// not sent.
+    pub fn send(&mut self, value: T) -> impl Future<Output = Result<(), SendError<T>>> + '_ {
+        let mut slot = Some(value);
+        std::future::poll_fn(move |cx| self.chan.poll_send(cx, &mut slot))
+    }
+
+    /// Try to send the value without waiting.
+    pub fn try_send(&mut self, value: T) -> Result<(), TrySendError<T>> {
+        self.chan.try_send(value)
+    }
+
+    /// Whether the receiver is closed.
+    pub fn is_closed(&self) -> bool {
+        self.chan.rx_closed.load(Ordering::Acquire)
+    }
+
+    /// The capacity the channel is created with.
+    pub fn max_capacity(&self) -> usize {
+        self.chan.queue.capacity()
+    }
+}
+
+impl<T: Send> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.chan.drop_sender();
+    }
+}
+
+impl<T: Send> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender")
+            .field("capacity", &self.max_capacity())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Receiving half of the channel.
+pub struct Receiver<T: Send> {
+    chan: Arc<Chan<T, Ring<T>>>,
+}
+
+impl<T: Send> Receiver<T> {
+    /// Receive the next value, or `None` if the channel is empty and the sender is dropped or
+    /// the receiver is closed.
+    pub fn recv(&mut self) -> impl Future<Output = Option<T>> + '_ {
+        std::future::poll_fn(|cx| self.poll_recv(cx))
+    }
+
+    /// Poll to receive the next value.
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        // Safety: it is the only receiver.
+        unsafe { self.chan.poll_recv(cx) }
+    }
+
+    /// Wait for values, and receive at most `limit` of them into `buf`. It returns the number of
+    /// the values received, which is 0 only if the channel is closed or `limit` is 0.
+    pub fn recv_many<'a>(
+        &'a mut self,
+        buf: &'a mut Vec<T>,
+        limit: usize,
+    ) -> impl Future<Output = usize> + 'a {
+        // Safety: it is the only receiver.
+        std::future::poll_fn(move |cx| unsafe { self.chan.poll_recv_many(cx, buf, limit) })
+    }
+
+    /// Try to receive the next value without waiting.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        // Safety: it is the only receiver.
+        unsafe { self.chan.try_recv() }
+    }
+
+    /// Close the receiver, so no more values can be sent, while the buffered values can still
+    /// be received.
+    pub fn close(&mut self) {
+        self.chan.close();
+    }
+
+    /// The number of the buffered values.
+    pub fn len(&self) -> usize {
+        self.chan.queue.len()
+    }
+
+    /// Whether there is no buffered value.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T: Send> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        // Safety: it is the only receiver.
+        unsafe { self.chan.drop_receiver() };
+    }
+}
+
+impl<T: Send> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver")
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
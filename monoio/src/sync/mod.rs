@@ -0,0 +1,58 @@
+//! Channels, locks and semaphores for the tasks on the same thread.
+//!
+//! They are backed by `Rc` and `RefCell` without atomics, so they are cheap but `!Send`, and can
+//! only be used inside the thread they are created on. To communicate across threads, use the
+//! channels in `cross` with the `sync` feature instead.
+
+pub mod broadcast;
+#[cfg(feature = "sync")]
+pub mod cross;
+pub mod mpsc;
+mod mutex;
+pub mod oneshot;
+mod rwlock;
+mod semaphore;
+pub mod watch;
+
+use std::{collections::HashMap, task::Waker};
+
+pub use mutex::{Mutex, MutexGuard, OwnedMutexGuard, TryLockError};
+pub use rwlock::{
+    OwnedRwLockReadGuard, OwnedRwLockWriteGuard, RwLock, RwLockReadGuard, RwLockWriteGuard,
+};
+pub use semaphore::{
+    AcquireError, OwnedSemaphorePermit, Semaphore, SemaphorePermit, TryAcquireError,
+};
+
+/// Wakers of the tasks waiting for a change, indexed by the id of their waiter.
+#[derive(Default)]
+struct Waiters {
+    next_id: u64,
+    wakers: HashMap<u64, Waker>,
+}
+
+impl Waiters {
+    fn next_id(&mut self) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    fn register(&mut self, id: u64, waker: &Waker) {
+        match self.wakers.get_mut(&id) {
+            Some(w) => w.clone_from(waker),
+            None => {
+                self.wakers.insert(id, waker.clone());
+            }
+        }
+    }
+
+    fn remove(&mut self, id: u64) {
+        self.wakers.remove(&id);
+    }
+
+    /// Take the wakers to wake after the borrow is released.
+    fn take_all(&mut self) -> Vec<Waker> {
+        self.wakers.drain().map(|(_, waker)| waker).collect()
+    }
+}
@@ -0,0 +1,469 @@
+//! A multi-producer, single-consumer queue for the tasks on the same thread.
+//!
+//! The [`channel`] is bounded, sending waits for the capacity in order so the senders are slowed
+//! down by the receiver, while sending to the [`unbounded`] one never waits.
+//!
+//! ```
+//! #[monoio::main]
+//! async fn main() {
+//!     let (tx, mut rx) = monoio::sync::mpsc::channel(1);
+//!     monoio::spawn(async move {
+//!         for i in 0..3 {
+//!             tx.send(i).await.unwrap();
+//!         }
+//!     });
+//!     let mut received = Vec::new();
+//!     while let Some(i) = rx.recv().await {
+//!         received.push(i);
+//!     }
+//!     assert_eq!(received, [0, 1, 2]);
+//! }
+//! ```
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    fmt,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+/// Create a bounded channel which buffers at most `capacity` values.
+///
+/// # Panics
+///
+/// Panics if `capacity` is 0.
+pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(capacity > 0, "mpsc bounded channel requires capacity > 0");
+    let chan = Chan::new(Some(capacity));
+    (Sender { chan: chan.clone() }, Receiver { chan })
+}
+
+/// Create an unbounded channel.
+pub fn unbounded<T>() -> (UnboundedSender<T>, Receiver<T>) {
+    let chan = Chan::new(None);
+    (UnboundedSender { chan: chan.clone() }, Receiver { chan })
+}
+
+struct Chan<T> {
+    queue: RefCell<VecDeque<T>>,
+    // None if unbounded
+    capacity: Option<usize>,
+    senders: Cell<usize>,
+    rx_closed: Cell<bool>,
+    rx_waker: RefCell<Option<Waker>>,
+    // The senders waiting for the capacity in order
+    send_waiters: RefCell<VecDeque<(u64, Waker)>>,
+    next_waiter: Cell<u64>,
+}
+
+impl<T> Chan<T> {
+    fn new(capacity: Option<usize>) -> Rc<Self> {
+        Rc::new(Self {
+            queue: RefCell::new(VecDeque::new()),
+            capacity,
+            senders: Cell::new(1),
+            rx_closed: Cell::new(false),
+            rx_waker: RefCell::new(None),
+            send_waiters: RefCell::new(VecDeque::new()),
+            next_waiter: Cell::new(0),
+        })
+    }
+
+    fn has_capacity(&self) -> bool {
+        self.capacity
+            .is_none_or(|capacity| self.queue.borrow().len() < capacity)
+    }
+
+    fn push(&self, value: T) {
+        self.queue.borrow_mut().push_back(value);
+        self.wake_rx();
+    }
+
+    fn wake_rx(&self) {
+        let waker = self.rx_waker.borrow_mut().take();
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    /// Wake the first waiting sender if there is capacity.
+    fn wake_next_sender(&self) {
+        if !self.has_capacity() {
+            return;
+        }
+        let waiter = self.send_waiters.borrow_mut().pop_front();
+        if let Some((_, waker)) = waiter {
+            waker.wake();
+        }
+    }
+
+    fn wake_all_senders(&self) {
+        let waiters = std::mem::take(&mut *self.send_waiters.borrow_mut());
+        for (_, waker) in waiters {
+            waker.wake();
+        }
+    }
+
+    fn add_sender(self: &Rc<Self>) -> Rc<Self> {
+        self.senders.set(self.senders.get() + 1);
+        self.clone()
+    }
+
+    fn drop_sender(&self) {
+        self.senders.set(self.senders.get() - 1);
+        if self.senders.get() == 0 {
+            self.wake_rx();
+        }
+    }
+}
+
+/// Error of sending to the channel whose receiver is closed, which returns the value.
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub struct SendError<T>(pub T);
+
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SendError").finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "channel closed".fmt(f)
+    }
+}
+
+impl<T> std::error::Error for SendError<T> {}
+
+/// Error of [`Sender::try_send`], which returns the value.
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum TrySendError<T> {
+    /// The channel is full.
+    Full(T),
+    /// The receiver is closed.
+    Closed(T),
+}
+
+impl<T> TrySendError<T> {
+    /// Get the value which is not sent.
+    pub fn into_inner(self) -> T {
+        match self {
+            Self::Full(value) | Self::Closed(value) => value,
+        }
+    }
+}
+
+impl<T> fmt::Debug for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Full(_) => "Full(..)".fmt(f),
+            Self::Closed(_) => "Closed(..)".fmt(f),
+        }
+    }
+}
+
+impl<T> fmt::Display for TrySendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Full(_) => "no available capacity".fmt(f),
+            Self::Closed(_) => "channel closed".fmt(f),
+        }
+    }
+}
+
+impl<T> std::error::Error for TrySendError<T> {}
+
+/// Error of [`Receiver::try_recv`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TryRecvError {
+    /// The channel is empty.
+    Empty,
+    /// The channel is empty and all the senders are dropped.
+    Disconnected,
+}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => "channel empty".fmt(f),
+            Self::Disconnected => "channel disconnected".fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for TryRecvError {}
+
+/// Sending half of the bounded channel, which can be cloned.
+pub struct Sender<T> {
+    chan: Rc<Chan<T>>,
+}
+
+impl<T> Sender<T> {
+    /// Send the value, and wait for the capacity if the channel is full. The senders waiting
+    /// are served in order.
+    ///
+    /// The value is returned if the receiver is closed. If the future is dropped, the value is
+    /// not sent.
+    pub fn send(&self, value: T) -> impl Future<Output = Result<(), SendError<T>>> + '_ {
+        Send {
+            chan: &self.chan,
+            value: Some(value),
+            id: None,
+        }
+    }
+
+    /// Try to send the value without waiting.
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        if self.chan.rx_closed.get() {
+            return Err(TrySendError::Closed(value));
+        }
+        // Do not jump the queue of the waiting senders.
+        if !self.chan.send_waiters.borrow().is_empty() || !self.chan.has_capacity() {
+            return Err(TrySendError::Full(value));
+        }
+        self.chan.push(value);
+        Ok(())
+    }
+
+    /// Whether the receiver is closed.
+    pub fn is_closed(&self) -> bool {
+        self.chan.rx_closed.get()
+    }
+
+    /// The capacity available now.
+    pub fn capacity(&self) -> usize {
+        self.max_capacity() - self.chan.queue.borrow().len()
+    }
+
+    /// The capacity the channel is created with.
+    pub fn max_capacity(&self) -> usize {
+        self.chan.capacity.unwrap_or(usize::MAX)
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            chan: self.chan.add_sender(),
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.chan.drop_sender();
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender")
+            .field("capacity", &self.chan.capacity)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Future of sending to the bounded channel, which waits in the queue of the senders.
+struct Send<'a, T> {
+    chan: &'a Rc<Chan<T>>,
+    value: Option<T>,
+    /// Id in the queue, if it has been queued.
+    id: Option<u64>,
+}
+
+// The value is never pinned.
+impl<T> Unpin for Send<'_, T> {}
+
+impl<T> Future for Send<'_, T> {
+    type Output = Result<(), SendError<T>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let chan = this.chan;
+        if chan.rx_closed.get() {
+            // All the waiters are woken and removed from the queue when it is closed.
+            this.id = None;
+            let value = this.value.take().expect("polled after completion");
+            return Poll::Ready(Err(SendError(value)));
+        }
+        let mut waiters = chan.send_waiters.borrow_mut();
+        let queued = this
+            .id
+            .and_then(|id| waiters.iter().position(|(i, _)| *i == id));
+        // Do not jump the queue unless it is woken(removed from the queue).
+        if queued.is_none() && (this.id.is_some() || waiters.is_empty()) && chan.has_capacity() {
+            drop(waiters);
+            this.id = None;
+            chan.push(this.value.take().expect("polled after completion"));
+            return Poll::Ready(Ok(()));
+        }
+        match queued {
+            Some(pos) => waiters[pos].1.clone_from(cx.waker()),
+            None => {
+                let id = match this.id {
+                    // Woken but the capacity is taken, queue again at the front.
+                    Some(id) => {
+                        waiters.push_front((id, cx.waker().clone()));
+                        id
+                    }
+                    None => {
+                        let id = chan.next_waiter.get();
+                        chan.next_waiter.set(id + 1);
+                        waiters.push_back((id, cx.waker().clone()));
+                        id
+                    }
+                };
+                this.id = Some(id);
+            }
+        }
+        Poll::Pending
+    }
+}
+
+impl<T> Drop for Send<'_, T> {
+    fn drop(&mut self) {
+        let Some(id) = self.id else { return };
+        let mut waiters = self.chan.send_waiters.borrow_mut();
+        match waiters.iter().position(|(i, _)| *i == id) {
+            Some(pos) => {
+                waiters.remove(pos);
+            }
+            None => {
+                // It has been woken for the capacity, pass it on.
+                drop(waiters);
+                self.chan.wake_next_sender();
+            }
+        }
+    }
+}
+
+/// Sending half of the unbounded channel, which can be cloned.
+pub struct UnboundedSender<T> {
+    chan: Rc<Chan<T>>,
+}
+
+impl<T> UnboundedSender<T> {
+    /// Send the value, which is returned if the receiver is closed.
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        if self.chan.rx_closed.get() {
+            return Err(SendError(value));
+        }
+        self.chan.push(value);
+        Ok(())
+    }
+
+    /// Whether the receiver is closed.
+    pub fn is_closed(&self) -> bool {
+        self.chan.rx_closed.get()
+    }
+}
+
+impl<T> Clone for UnboundedSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            chan: self.chan.add_sender(),
+        }
+    }
+}
+
+impl<T> Drop for UnboundedSender<T> {
+    fn drop(&mut self) {
+        self.chan.drop_sender();
+    }
+}
+
+impl<T> fmt::Debug for UnboundedSender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UnboundedSender").finish_non_exhaustive()
+    }
+}
+
+/// Receiving half of the channel.
+pub struct Receiver<T> {
+    chan: Rc<Chan<T>>,
+}
+
+impl<T> Receiver<T> {
+    /// Receive the next value, or `None` if the channel is empty and all the senders are
+    /// dropped or the receiver is closed.
+    pub fn recv(&mut self) -> impl Future<Output = Option<T>> + '_ {
+        std::future::poll_fn(|cx| self.poll_recv(cx))
+    }
+
+    /// Poll to receive the next value.
+    pub fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        match self.try_recv() {
+            Ok(value) => Poll::Ready(Some(value)),
+            Err(TryRecvError::Disconnected) => Poll::Ready(None),
+            Err(TryRecvError::Empty) => {
+                let mut waker = self.chan.rx_waker.borrow_mut();
+                match waker.as_mut() {
+                    Some(w) => w.clone_from(cx.waker()),
+                    None => *waker = Some(cx.waker().clone()),
+                }
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Try to receive the next value without waiting.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        let value = self.chan.queue.borrow_mut().pop_front();
+        match value {
+            Some(value) => {
+                self.chan.wake_next_sender();
+                Ok(value)
+            }
+            None if self.chan.senders.get() == 0 || self.chan.rx_closed.get() => {
+                Err(TryRecvError::Disconnected)
+            }
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    /// Close the receiver, so no more values can be sent, while the buffered values can still
+    /// be received.
+    pub fn close(&mut self) {
+        self.chan.rx_closed.set(true);
+        self.chan.wake_all_senders();
+    }
+
+    /// The number of the buffered values.
+    pub fn len(&self) -> usize {
+        self.chan.queue.borrow().len()
+    }
+
+    /// Whether there is no buffered value.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.close();
+        // Drop the values after the borrow is released.
+        let values = std::mem::take(&mut *self.chan.queue.borrow_mut());
+        drop(values);
+    }
+}
+
+impl<T> crate::io::stream::Stream for Receiver<T> {
+    type Item = T;
+
+    #[inline]
+    async fn next(&mut self) -> Option<T> {
+        self.recv().await
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver")
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,204 @@
+use std::{
+    cell::UnsafeCell,
+    fmt,
+    ops::{Deref, DerefMut},
+    rc::Rc,
+};
+
+use super::Semaphore;
+
+/// An async mutex for the tasks on the same thread, whose guard can be held across `.await`.
+/// The tasks waiting for the lock are served in order.
+///
+/// ```
+/// use std::rc::Rc;
+///
+/// #[monoio::main]
+/// async fn main() {
+///     let count = Rc::new(monoio::sync::Mutex::new(0));
+///     let mut joins = Vec::new();
+///     for _ in 0..4 {
+///         let count = count.clone();
+///         joins.push(monoio::spawn(async move {
+///             let mut count = count.lock().await;
+///             monoio::task::yield_now().await;
+///             *count += 1;
+///         }));
+///     }
+///     for join in joins {
+///         join.await;
+///     }
+///     assert_eq!(*count.lock().await, 4);
+/// }
+/// ```
+pub struct Mutex<T: ?Sized> {
+    sem: Semaphore,
+    value: UnsafeCell<T>,
+}
+
+/// Error of trying to lock the locked [`Mutex`] or [`RwLock`](super::RwLock).
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct TryLockError(());
+
+impl fmt::Display for TryLockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "operation would block".fmt(f)
+    }
+}
+
+impl std::error::Error for TryLockError {}
+
+impl TryLockError {
+    pub(super) fn new() -> Self {
+        Self(())
+    }
+}
+
+impl<T> Mutex<T> {
+    /// Create an unlocked mutex with the value.
+    pub const fn new(value: T) -> Self {
+        Self {
+            sem: Semaphore::new(1),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    /// Consume the mutex and return the value.
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+impl<T: ?Sized> Mutex<T> {
+    /// Lock the mutex, and wait for it in order.
+    pub async fn lock(&self) -> MutexGuard<'_, T> {
+        self.acquire().await;
+        MutexGuard { lock: self }
+    }
+
+    /// Try to lock the mutex without waiting.
+    pub fn try_lock(&self) -> Result<MutexGuard<'_, T>, TryLockError> {
+        self.sem.try_take(1).map_err(|_| TryLockError::new())?;
+        Ok(MutexGuard { lock: self })
+    }
+
+    /// Lock the mutex with a guard which holds it, so it can be moved into a spawned task.
+    pub async fn lock_owned(self: Rc<Self>) -> OwnedMutexGuard<T> {
+        self.acquire().await;
+        OwnedMutexGuard { lock: self }
+    }
+
+    /// Try to lock the mutex with a guard which holds it without waiting.
+    pub fn try_lock_owned(self: Rc<Self>) -> Result<OwnedMutexGuard<T>, TryLockError> {
+        self.sem.try_take(1).map_err(|_| TryLockError::new())?;
+        Ok(OwnedMutexGuard { lock: self })
+    }
+
+    /// Get the value mutably, no locking is needed since it is borrowed mutably.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+
+    async fn acquire(&self) {
+        // The semaphore is never closed.
+        let _ = self.sem.acquire_raw(1).await;
+    }
+}
+
+impl<T: Default> Default for Mutex<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> From<T> for Mutex<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for Mutex<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("Mutex");
+        match self.try_lock() {
+            Ok(guard) => d.field("data", &&*guard),
+            Err(_) => d.field("data", &format_args!("<locked>")),
+        };
+        d.finish()
+    }
+}
+
+/// Guard of the locked [`Mutex`], which unlocks it when it is dropped.
+#[must_use = "the mutex is unlocked when the guard is dropped"]
+pub struct MutexGuard<'a, T: ?Sized> {
+    lock: &'a Mutex<T>,
+}
+
+impl<T: ?Sized> Deref for MutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // Safety: the value is accessed exclusively while the mutex is locked.
+        unsafe { &*self.lock.value.get() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for MutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // Safety: the value is accessed exclusively while the mutex is locked.
+        unsafe { &mut *self.lock.value.get() }
+    }
+}
+
+impl<T: ?Sized> Drop for MutexGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.sem.release(1);
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for MutexGuard<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+/// Guard of the locked [`Mutex`] which holds it, and unlocks it when it is dropped.
+#[must_use = "the mutex is unlocked when the guard is dropped"]
+pub struct OwnedMutexGuard<T: ?Sized> {
+    lock: Rc<Mutex<T>>,
+}
+
+impl<T: ?Sized> OwnedMutexGuard<T> {
+    /// The mutex locked by the guard.
+    pub fn mutex(&self) -> &Rc<Mutex<T>> {
+        &self.lock
+    }
+}
+
+impl<T: ?Sized> Deref for OwnedMutexGuard<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // Safety: the value is accessed exclusively while the mutex is locked.
+        unsafe { &*self.lock.value.get() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for OwnedMutexGuard<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // Safety: the value is accessed exclusively while the mutex is locked.
+        unsafe { &mut *self.lock.value.get() }
+    }
+}
+
+impl<T: ?Sized> Drop for OwnedMutexGuard<T> {
+    fn drop(&mut self) {
+        self.lock.sem.release(1);
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for OwnedMutexGuard<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
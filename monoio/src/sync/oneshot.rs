@@ -0,0 +1,200 @@
+//! A channel to send a single value between the tasks on the same thread.
+//!
+//! ```
+//! #[monoio::main]
+//! async fn main() {
+//!     let (tx, rx) = monoio::sync::oneshot::channel();
+//!     monoio::spawn(async move {
+//!         tx.send(1).unwrap();
+//!     });
+//!     assert_eq!(rx.await, Ok(1));
+//! }
+//! ```
+
+use std::{
+    cell::{Cell, RefCell},
+    fmt,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+/// Create a oneshot channel.
+pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    let inner = Rc::new(Inner {
+        value: RefCell::new(None),
+        rx_waker: RefCell::new(None),
+        tx_waker: RefCell::new(None),
+        tx_dropped: Cell::new(false),
+        rx_closed: Cell::new(false),
+    });
+    (
+        Sender {
+            inner: inner.clone(),
+        },
+        Receiver { inner },
+    )
+}
+
+struct Inner<T> {
+    value: RefCell<Option<T>>,
+    rx_waker: RefCell<Option<Waker>>,
+    // Waiting for the receiver to be closed
+    tx_waker: RefCell<Option<Waker>>,
+    tx_dropped: Cell<bool>,
+    rx_closed: Cell<bool>,
+}
+
+fn register(slot: &RefCell<Option<Waker>>, waker: &Waker) {
+    let mut slot = slot.borrow_mut();
+    match slot.as_mut() {
+        Some(w) => w.clone_from(waker),
+        None => *slot = Some(waker.clone()),
+    }
+}
+
+fn wake(slot: &RefCell<Option<Waker>>) {
+    let waker = slot.borrow_mut().take();
+    if let Some(waker) = waker {
+        waker.wake();
+    }
+}
+
+/// Error of receiving from the channel whose sender is dropped without sending.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct RecvError(());
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "channel closed".fmt(f)
+    }
+}
+
+impl std::error::Error for RecvError {}
+
+/// Error of [`Receiver::try_recv`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TryRecvError {
+    /// The value is not sent yet.
+    Empty,
+    /// The sender is dropped without sending, or the value has been received.
+    Closed,
+}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => "channel empty".fmt(f),
+            Self::Closed => "channel closed".fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for TryRecvError {}
+
+/// Sending half of the oneshot channel.
+pub struct Sender<T> {
+    inner: Rc<Inner<T>>,
+}
+
+impl<T> Sender<T> {
+    /// Send the value, which is returned if the receiver is closed.
+    pub fn send(self, value: T) -> Result<(), T> {
+        if self.inner.rx_closed.get() {
+            return Err(value);
+        }
+        *self.inner.value.borrow_mut() = Some(value);
+        // The receiver is woken when the sender is dropped.
+        Ok(())
+    }
+
+    /// Whether the receiver is closed.
+    pub fn is_closed(&self) -> bool {
+        self.inner.rx_closed.get()
+    }
+
+    /// Wait for the receiver to be closed, e.g. to stop computing the value nobody waits for.
+    pub fn closed(&mut self) -> impl Future<Output = ()> + '_ {
+        std::future::poll_fn(|cx| {
+            if self.inner.rx_closed.get() {
+                return Poll::Ready(());
+            }
+            register(&self.inner.tx_waker, cx.waker());
+            Poll::Pending
+        })
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.inner.tx_dropped.set(true);
+        wake(&self.inner.rx_waker);
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender")
+            .field("closed", &self.is_closed())
+            .finish()
+    }
+}
+
+/// Receiving half of the oneshot channel, which is a future of the value.
+pub struct Receiver<T> {
+    inner: Rc<Inner<T>>,
+}
+
+impl<T> Receiver<T> {
+    /// Try to receive the value without waiting.
+    pub fn try_recv(&mut self) -> Result<T, TryRecvError> {
+        let value = self.inner.value.borrow_mut().take();
+        match value {
+            Some(value) => Ok(value),
+            None if self.inner.tx_dropped.get() => Err(TryRecvError::Closed),
+            None => Err(TryRecvError::Empty),
+        }
+    }
+
+    /// Close the receiver, so the value can not be sent, while the value sent before can still
+    /// be received.
+    pub fn close(&mut self) {
+        if !self.inner.rx_closed.replace(true) {
+            wake(&self.inner.tx_waker);
+        }
+    }
+}
+
+impl<T> Future for Receiver<T> {
+    type Output = Result<T, RecvError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        match this.try_recv() {
+            Ok(value) => Poll::Ready(Ok(value)),
+            Err(TryRecvError::Closed) => Poll::Ready(Err(RecvError(()))),
+            Err(TryRecvError::Empty) => {
+                register(&this.inner.rx_waker, cx.waker());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.close();
+        // Drop the value after the borrow is released.
+        let value = self.inner.value.borrow_mut().take();
+        drop(value);
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver")
+            .field("closed", &self.inner.rx_closed.get())
+            .finish()
+    }
+}
@@ -0,0 +1,202 @@
+use std::{
+    cell::UnsafeCell,
+    fmt,
+    ops::{Deref, DerefMut},
+    rc::Rc,
+};
+
+use super::{Semaphore, TryLockError};
+
+// A reader takes a permit and a writer takes all of them.
+const MAX_READS: usize = u32::MAX as usize >> 3;
+
+/// An async reader-writer lock for the tasks on the same thread, whose guards can be held across
+/// `.await`.
+///
+/// The tasks waiting for the lock are served in order, so a waiting writer blocks the readers
+/// coming after it and is not starved.
+///
+/// ```
+/// #[monoio::main]
+/// async fn main() {
+///     let lock = monoio::sync::RwLock::new(1);
+///     {
+///         let r1 = lock.read().await;
+///         let r2 = lock.read().await;
+///         assert_eq!(*r1 + *r2, 2);
+///         assert!(lock.try_write().is_err());
+///     }
+///     *lock.write().await += 1;
+///     assert_eq!(*lock.read().await, 2);
+/// }
+/// ```
+pub struct RwLock<T: ?Sized> {
+    sem: Semaphore,
+    value: UnsafeCell<T>,
+}
+
+impl<T> RwLock<T> {
+    /// Create an unlocked lock with the value.
+    pub const fn new(value: T) -> Self {
+        Self {
+            sem: Semaphore::new(MAX_READS),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    /// Consume the lock and return the value.
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+impl<T: ?Sized> RwLock<T> {
+    /// Lock it for reading, and wait for the writer in order.
+    pub async fn read(&self) -> RwLockReadGuard<'_, T> {
+        self.acquire(1).await;
+        RwLockReadGuard { lock: self }
+    }
+
+    /// Try to lock it for reading without waiting.
+    pub fn try_read(&self) -> Result<RwLockReadGuard<'_, T>, TryLockError> {
+        self.sem.try_take(1).map_err(|_| TryLockError::new())?;
+        Ok(RwLockReadGuard { lock: self })
+    }
+
+    /// Lock it for writing, and wait for the readers and writers in order.
+    pub async fn write(&self) -> RwLockWriteGuard<'_, T> {
+        self.acquire(MAX_READS).await;
+        RwLockWriteGuard { lock: self }
+    }
+
+    /// Try to lock it for writing without waiting.
+    pub fn try_write(&self) -> Result<RwLockWriteGuard<'_, T>, TryLockError> {
+        self.sem
+            .try_take(MAX_READS)
+            .map_err(|_| TryLockError::new())?;
+        Ok(RwLockWriteGuard { lock: self })
+    }
+
+    /// Lock it for reading with a guard which holds it, so it can be moved into a spawned task.
+    pub async fn read_owned(self: Rc<Self>) -> OwnedRwLockReadGuard<T> {
+        self.acquire(1).await;
+        OwnedRwLockReadGuard { lock: self }
+    }
+
+    /// Try to lock it for reading with a guard which holds it without waiting.
+    pub fn try_read_owned(self: Rc<Self>) -> Result<OwnedRwLockReadGuard<T>, TryLockError> {
+        self.sem.try_take(1).map_err(|_| TryLockError::new())?;
+        Ok(OwnedRwLockReadGuard { lock: self })
+    }
+
+    /// Lock it for writing with a guard which holds it, so it can be moved into a spawned task.
+    pub async fn write_owned(self: Rc<Self>) -> OwnedRwLockWriteGuard<T> {
+        self.acquire(MAX_READS).await;
+        OwnedRwLockWriteGuard { lock: self }
+    }
+
+    /// Try to lock it for writing with a guard which holds it without waiting.
+    pub fn try_write_owned(self: Rc<Self>) -> Result<OwnedRwLockWriteGuard<T>, TryLockError> {
+        self.sem
+            .try_take(MAX_READS)
+            .map_err(|_| TryLockError::new())?;
+        Ok(OwnedRwLockWriteGuard { lock: self })
+    }
+
+    /// Get the value mutably, no locking is needed since it is borrowed mutably.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+
+    async fn acquire(&self, permits: usize) {
+        // The semaphore is never closed.
+        let _ = self.sem.acquire_raw(permits).await;
+    }
+}
+
+impl<T: Default> Default for RwLock<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T> From<T> for RwLock<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T: ?Sized + fmt::Debug> fmt::Debug for RwLock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("RwLock");
+        match self.try_read() {
+            Ok(guard) => d.field("data", &&*guard),
+            Err(_) => d.field("data", &format_args!("<locked>")),
+        };
+        d.finish()
+    }
+}
+
+macro_rules! guard {
+    ($(#[$doc:meta])* $name:ident$(<$lt:lifetime>)?, $lock:ty, $permits:expr) => {
+        $(#[$doc])*
+        #[must_use = "the lock is unlocked when the guard is dropped"]
+        pub struct $name<$($lt,)? T: ?Sized> {
+            lock: $lock,
+        }
+
+        impl<$($lt,)? T: ?Sized> Deref for $name<$($lt,)? T> {
+            type Target = T;
+
+            fn deref(&self) -> &T {
+                // Safety: the value is not written while the guard is held.
+                unsafe { &*self.lock.value.get() }
+            }
+        }
+
+        impl<$($lt,)? T: ?Sized> Drop for $name<$($lt,)? T> {
+            fn drop(&mut self) {
+                self.lock.sem.release($permits);
+            }
+        }
+
+        impl<$($lt,)? T: ?Sized + fmt::Debug> fmt::Debug for $name<$($lt,)? T> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Debug::fmt(&**self, f)
+            }
+        }
+    };
+}
+
+guard!(
+    /// Guard of the [`RwLock`] locked for reading, which unlocks it when it is dropped.
+    RwLockReadGuard<'a>, &'a RwLock<T>, 1
+);
+guard!(
+    /// Guard of the [`RwLock`] locked for writing, which unlocks it when it is dropped.
+    RwLockWriteGuard<'a>, &'a RwLock<T>, MAX_READS
+);
+guard!(
+    /// Guard of the [`RwLock`] locked for reading which holds it, and unlocks it when it is
+    /// dropped.
+    OwnedRwLockReadGuard, Rc<RwLock<T>>, 1
+);
+guard!(
+    /// Guard of the [`RwLock`] locked for writing which holds it, and unlocks it when it is
+    /// dropped.
+    OwnedRwLockWriteGuard, Rc<RwLock<T>>, MAX_READS
+);
+
+impl<T: ?Sized> DerefMut for RwLockWriteGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // Safety: the value is accessed exclusively while the lock is held for writing.
+        unsafe { &mut *self.lock.value.get() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for OwnedRwLockWriteGuard<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // Safety: the value is accessed exclusively while the lock is held for writing.
+        unsafe { &mut *self.lock.value.get() }
+    }
+}
@@ -0,0 +1,385 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    fmt,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::{Context, Poll, Waker},
+};
+
+/// An async semaphore for the tasks on the same thread, e.g. to limit the number of the
+/// connections handled at the same time.
+///
+/// The waiters are served in order: a waiter acquiring many permits blocks the ones after it
+/// even if there are enough permits for them.
+///
+/// ```
+/// use std::rc::Rc;
+///
+/// #[monoio::main]
+/// async fn main() {
+///     let limit = Rc::new(monoio::sync::Semaphore::new(2));
+///     for _ in 0..4 {
+///         let permit = limit.clone().acquire_owned().await.unwrap();
+///         monoio::spawn(async move {
+///             // Handle the connection.
+///             drop(permit);
+///         });
+///     }
+/// }
+/// ```
+pub struct Semaphore {
+    permits: Cell<usize>,
+    waiters: RefCell<VecDeque<Rc<Waiter>>>,
+    closed: Cell<bool>,
+}
+
+struct Waiter {
+    permits: usize,
+    // The permits are handed to the waiter when it is removed from the queue.
+    granted: Cell<bool>,
+    waker: RefCell<Waker>,
+}
+
+/// Error of acquiring from a closed semaphore.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct AcquireError(());
+
+impl fmt::Display for AcquireError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "semaphore closed".fmt(f)
+    }
+}
+
+impl std::error::Error for AcquireError {}
+
+/// Error of [`Semaphore::try_acquire`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TryAcquireError {
+    /// The semaphore is closed.
+    Closed,
+    /// There are not enough permits, or other tasks are waiting before.
+    NoPermits,
+}
+
+impl fmt::Display for TryAcquireError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Closed => "semaphore closed".fmt(f),
+            Self::NoPermits => "no permits available".fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for TryAcquireError {}
+
+impl Semaphore {
+    /// Create a semaphore with the number of permits.
+    pub const fn new(permits: usize) -> Self {
+        Self {
+            permits: Cell::new(permits),
+            waiters: RefCell::new(VecDeque::new()),
+            closed: Cell::new(false),
+        }
+    }
+
+    /// The number of the permits available.
+    pub fn available_permits(&self) -> usize {
+        self.permits.get()
+    }
+
+    /// Add the permits, which may be handed to the waiters.
+    pub fn add_permits(&self, n: usize) {
+        self.permits.set(self.permits.get() + n);
+        self.hand_over();
+    }
+
+    /// Close the semaphore, so the waiters and the later acquisitions fail. The permits
+    /// acquired are still valid.
+    pub fn close(&self) {
+        self.closed.set(true);
+        let waiters = std::mem::take(&mut *self.waiters.borrow_mut());
+        for waiter in waiters {
+            waiter.waker.borrow().wake_by_ref();
+        }
+    }
+
+    /// Whether the semaphore is closed.
+    pub fn is_closed(&self) -> bool {
+        self.closed.get()
+    }
+
+    /// Acquire a permit, and wait for it in order.
+    pub async fn acquire(&self) -> Result<SemaphorePermit<'_>, AcquireError> {
+        self.acquire_many(1).await
+    }
+
+    /// Acquire `n` permits, and wait for them in order.
+    pub async fn acquire_many(&self, n: usize) -> Result<SemaphorePermit<'_>, AcquireError> {
+        Acquire::new(self, n).await?;
+        Ok(SemaphorePermit {
+            sem: self,
+            permits: n,
+        })
+    }
+
+    /// Try to acquire a permit without waiting.
+    pub fn try_acquire(&self) -> Result<SemaphorePermit<'_>, TryAcquireError> {
+        self.try_acquire_many(1)
+    }
+
+    /// Try to acquire `n` permits without waiting.
+    pub fn try_acquire_many(&self, n: usize) -> Result<SemaphorePermit<'_>, TryAcquireError> {
+        self.try_take(n)?;
+        Ok(SemaphorePermit {
+            sem: self,
+            permits: n,
+        })
+    }
+
+    /// Acquire a permit which holds the semaphore, so it can be moved into a spawned task.
+    pub async fn acquire_owned(self: Rc<Self>) -> Result<OwnedSemaphorePermit, AcquireError> {
+        self.acquire_many_owned(1).await
+    }
+
+    /// Acquire `n` permits which hold the semaphore.
+    pub async fn acquire_many_owned(
+        self: Rc<Self>,
+        n: usize,
+    ) -> Result<OwnedSemaphorePermit, AcquireError> {
+        Acquire::new(&self, n).await?;
+        Ok(OwnedSemaphorePermit {
+            sem: self,
+            permits: n,
+        })
+    }
+
+    /// Try to acquire a permit which holds the semaphore without waiting.
+    pub fn try_acquire_owned(self: Rc<Self>) -> Result<OwnedSemaphorePermit, TryAcquireError> {
+        self.try_acquire_many_owned(1)
+    }
+
+    /// Try to acquire `n` permits which hold the semaphore without waiting.
+    pub fn try_acquire_many_owned(
+        self: Rc<Self>,
+        n: usize,
+    ) -> Result<OwnedSemaphorePermit, TryAcquireError> {
+        self.try_take(n)?;
+        Ok(OwnedSemaphorePermit {
+            sem: self,
+            permits: n,
+        })
+    }
+
+    pub(super) fn try_take(&self, n: usize) -> Result<(), TryAcquireError> {
+        if self.closed.get() {
+            return Err(TryAcquireError::Closed);
+        }
+        // Do not jump the queue.
+        if !self.waiters.borrow().is_empty() || self.permits.get() < n {
+            return Err(TryAcquireError::NoPermits);
+        }
+        self.permits.set(self.permits.get() - n);
+        Ok(())
+    }
+
+    /// Wait for `n` permits without a guard, they are released with `release`.
+    pub(super) fn acquire_raw(
+        &self,
+        n: usize,
+    ) -> impl Future<Output = Result<(), AcquireError>> + '_ {
+        Acquire::new(self, n)
+    }
+
+    pub(super) fn release(&self, n: usize) {
+        self.add_permits(n);
+    }
+
+    /// Hand the permits to the waiters in order.
+    fn hand_over(&self) {
+        let mut woken = Vec::new();
+        {
+            let mut waiters = self.waiters.borrow_mut();
+            while let Some(waiter) = waiters.front() {
+                if waiter.permits > self.permits.get() {
+                    break;
+                }
+                self.permits.set(self.permits.get() - waiter.permits);
+                waiter.granted.set(true);
+                woken.push(waiters.pop_front().unwrap());
+            }
+        }
+        for waiter in woken {
+            waiter.waker.borrow().wake_by_ref();
+        }
+    }
+}
+
+impl Default for Semaphore {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl fmt::Debug for Semaphore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Semaphore")
+            .field("permits", &self.permits.get())
+            .field("waiters", &self.waiters.borrow().len())
+            .field("closed", &self.closed.get())
+            .finish()
+    }
+}
+
+/// Future of acquiring the permits, which waits in the queue of the semaphore.
+struct Acquire<'a> {
+    sem: &'a Semaphore,
+    permits: usize,
+    waiter: Option<Rc<Waiter>>,
+}
+
+impl<'a> Acquire<'a> {
+    fn new(sem: &'a Semaphore, permits: usize) -> Self {
+        Self {
+            sem,
+            permits,
+            waiter: None,
+        }
+    }
+}
+
+impl Future for Acquire<'_> {
+    type Output = Result<(), AcquireError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let res = match &this.waiter {
+            Some(waiter) if waiter.granted.get() => Ok(()),
+            Some(waiter) => {
+                if this.sem.closed.get() {
+                    Err(AcquireError(()))
+                } else {
+                    waiter.waker.borrow_mut().clone_from(cx.waker());
+                    return Poll::Pending;
+                }
+            }
+            None => match this.sem.try_take(this.permits) {
+                Ok(()) => Ok(()),
+                Err(TryAcquireError::Closed) => Err(AcquireError(())),
+                Err(TryAcquireError::NoPermits) => {
+                    let waiter = Rc::new(Waiter {
+                        permits: this.permits,
+                        granted: Cell::new(false),
+                        waker: RefCell::new(cx.waker().clone()),
+                    });
+                    this.sem.waiters.borrow_mut().push_back(waiter.clone());
+                    this.waiter = Some(waiter);
+                    return Poll::Pending;
+                }
+            },
+        };
+        this.waiter = None;
+        Poll::Ready(res)
+    }
+}
+
+impl Drop for Acquire<'_> {
+    fn drop(&mut self) {
+        // Not queued or completed.
+        let Some(waiter) = self.waiter.take() else {
+            return;
+        };
+        if waiter.granted.get() {
+            // The permits are handed over but not taken.
+            self.sem.release(waiter.permits);
+            return;
+        }
+        let removed = {
+            let mut waiters = self.sem.waiters.borrow_mut();
+            let pos = waiters.iter().position(|w| Rc::ptr_eq(w, &waiter));
+            pos.and_then(|pos| waiters.remove(pos))
+        };
+        // The waiters after it may be able to take the permits now.
+        if removed.is_some() {
+            self.sem.hand_over();
+        }
+    }
+}
+
+/// Permits acquired from a [`Semaphore`], which are released when it is dropped.
+#[must_use = "the permits are released when the permit is dropped"]
+pub struct SemaphorePermit<'a> {
+    sem: &'a Semaphore,
+    permits: usize,
+}
+
+impl SemaphorePermit<'_> {
+    /// Forget the permits without releasing them, which decreases the permits of the
+    /// semaphore.
+    pub fn forget(mut self) {
+        self.permits = 0;
+    }
+
+    /// The number of the permits held.
+    pub fn num_permits(&self) -> usize {
+        self.permits
+    }
+}
+
+impl Drop for SemaphorePermit<'_> {
+    fn drop(&mut self) {
+        if self.permits != 0 {
+            self.sem.release(self.permits);
+        }
+    }
+}
+
+impl fmt::Debug for SemaphorePermit<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SemaphorePermit")
+            .field("permits", &self.permits)
+            .finish()
+    }
+}
+
+/// Permits acquired from a [`Semaphore`] which hold it, and are released when it is dropped.
+#[must_use = "the permits are released when the permit is dropped"]
+pub struct OwnedSemaphorePermit {
+    sem: Rc<Semaphore>,
+    permits: usize,
+}
+
+impl OwnedSemaphorePermit {
+    /// Forget the permits without releasing them, which decreases the permits of the
+    /// semaphore.
+    pub fn forget(mut self) {
+        self.permits = 0;
+    }
+
+    /// The number of the permits held.
+    pub fn num_permits(&self) -> usize {
+        self.permits
+    }
+
+    /// The semaphore the permits are acquired from.
+    pub fn semaphore(&self) -> &Rc<Semaphore> {
+        &self.sem
+    }
+}
+
+impl Drop for OwnedSemaphorePermit {
+    fn drop(&mut self) {
+        if self.permits != 0 {
+            self.sem.release(self.permits);
+        }
+    }
+}
+
+impl fmt::Debug for OwnedSemaphorePermit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OwnedSemaphorePermit")
+            .field("permits", &self.permits)
+            .finish()
+    }
+}
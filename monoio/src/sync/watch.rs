@@ -0,0 +1,239 @@
+//! A single-producer, multi-consumer channel for the tasks on the same thread, which keeps only
+//! the latest value, e.g. to watch the config or the state of a service.
+//!
+//! ```
+//! #[monoio::main]
+//! async fn main() {
+//!     let (tx, mut rx) = monoio::sync::watch::channel("init");
+//!     monoio::spawn(async move {
+//!         tx.send("updated").unwrap();
+//!     });
+//!     rx.changed().await.unwrap();
+//!     assert_eq!(*rx.borrow_and_update(), "updated");
+//! }
+//! ```
+
+use std::{
+    cell::{Cell, Ref, RefCell},
+    fmt,
+    future::Future,
+    rc::Rc,
+    task::Poll,
+};
+
+use super::Waiters;
+
+/// Create a watch channel with the initial value, which is seen by the receiver.
+pub fn channel<T>(init: T) -> (Sender<T>, Receiver<T>) {
+    let shared = Rc::new(Shared {
+        value: RefCell::new(init),
+        version: Cell::new(0),
+        tx_dropped: Cell::new(false),
+        receivers: Cell::new(0),
+        waiters: RefCell::new(Waiters::default()),
+    });
+    let rx = Receiver::new(&shared, 0);
+    (Sender { shared }, rx)
+}
+
+struct Shared<T> {
+    value: RefCell<T>,
+    // Increased when the value is updated
+    version: Cell<u64>,
+    tx_dropped: Cell<bool>,
+    receivers: Cell<usize>,
+    waiters: RefCell<Waiters>,
+}
+
+impl<T> Shared<T> {
+    fn wake_all(&self) {
+        let wakers = self.waiters.borrow_mut().take_all();
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
+
+/// Error of sending to the channel without receivers, which returns the value.
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub struct SendError<T>(pub T);
+
+impl<T> fmt::Debug for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SendError").finish_non_exhaustive()
+    }
+}
+
+impl<T> fmt::Display for SendError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "channel closed".fmt(f)
+    }
+}
+
+impl<T> std::error::Error for SendError<T> {}
+
+/// Error of waiting for a change on the channel whose sender is dropped.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct RecvError(());
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        "channel closed".fmt(f)
+    }
+}
+
+impl std::error::Error for RecvError {}
+
+/// Sending half of the watch channel.
+pub struct Sender<T> {
+    shared: Rc<Shared<T>>,
+}
+
+impl<T> Sender<T> {
+    /// Update the value and notify the receivers. The value is returned if there is no
+    /// receiver.
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        if self.shared.receivers.get() == 0 {
+            return Err(SendError(value));
+        }
+        self.send_replace(value);
+        Ok(())
+    }
+
+    /// Update the value and notify the receivers even if there is no receiver, and return the
+    /// old value.
+    pub fn send_replace(&self, value: T) -> T {
+        let old = self.shared.value.replace(value);
+        self.notify();
+        old
+    }
+
+    /// Modify the value in place and notify the receivers.
+    pub fn send_modify(&self, modify: impl FnOnce(&mut T)) {
+        modify(&mut self.shared.value.borrow_mut());
+        self.notify();
+    }
+
+    fn notify(&self) {
+        self.shared.version.set(self.shared.version.get() + 1);
+        self.shared.wake_all();
+    }
+
+    /// Borrow the latest value. Updating the value while it is borrowed panics.
+    pub fn borrow(&self) -> Ref<'_, T> {
+        self.shared.value.borrow()
+    }
+
+    /// Create a receiver, which sees the latest value.
+    pub fn subscribe(&self) -> Receiver<T> {
+        Receiver::new(&self.shared, self.shared.version.get())
+    }
+
+    /// The number of the receivers.
+    pub fn receiver_count(&self) -> usize {
+        self.shared.receivers.get()
+    }
+
+    /// Whether all the receivers are dropped.
+    pub fn is_closed(&self) -> bool {
+        self.receiver_count() == 0
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.shared.tx_dropped.set(true);
+        self.shared.wake_all();
+    }
+}
+
+impl<T> fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sender")
+            .field("receivers", &self.receiver_count())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Receiving half of the watch channel, which can be cloned.
+pub struct Receiver<T> {
+    shared: Rc<Shared<T>>,
+    // The version seen
+    version: u64,
+    // Id of the waiter
+    id: u64,
+}
+
+impl<T> Receiver<T> {
+    fn new(shared: &Rc<Shared<T>>, version: u64) -> Self {
+        shared.receivers.set(shared.receivers.get() + 1);
+        let id = shared.waiters.borrow_mut().next_id();
+        Self {
+            shared: shared.clone(),
+            version,
+            id,
+        }
+    }
+
+    /// Borrow the latest value without marking it as seen. Updating the value while it is
+    /// borrowed panics.
+    pub fn borrow(&self) -> Ref<'_, T> {
+        self.shared.value.borrow()
+    }
+
+    /// Borrow the latest value, and mark it as seen.
+    pub fn borrow_and_update(&mut self) -> Ref<'_, T> {
+        self.version = self.shared.version.get();
+        self.shared.value.borrow()
+    }
+
+    /// Whether the value is updated since it is seen last time. It fails if the sender is
+    /// dropped.
+    pub fn has_changed(&self) -> Result<bool, RecvError> {
+        if self.shared.tx_dropped.get() {
+            return Err(RecvError(()));
+        }
+        Ok(self.version != self.shared.version.get())
+    }
+
+    /// Wait for the value to be updated since it is seen last time, and mark it as seen. It
+    /// fails if the sender is dropped.
+    pub fn changed(&mut self) -> impl Future<Output = Result<(), RecvError>> + '_ {
+        std::future::poll_fn(|cx| {
+            let version = self.shared.version.get();
+            if self.version != version {
+                self.version = version;
+                return Poll::Ready(Ok(()));
+            }
+            if self.shared.tx_dropped.get() {
+                return Poll::Ready(Err(RecvError(())));
+            }
+            self.shared
+                .waiters
+                .borrow_mut()
+                .register(self.id, cx.waker());
+            Poll::Pending
+        })
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Self {
+        Self::new(&self.shared, self.version)
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.shared.receivers.set(self.shared.receivers.get() - 1);
+        self.shared.waiters.borrow_mut().remove(self.id);
+    }
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Receiver")
+            .field("version", &self.version)
+            .finish_non_exhaustive()
+    }
+}
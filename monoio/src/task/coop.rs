@@ -0,0 +1,160 @@
+//! Cooperative scheduling budget.
+//!
+//! Each time a task is polled it gets a budget, and every ready io operation consumes one unit.
+//! Once it runs out, the io operations return `Pending` and the task is woken after the poll, so
+//! it is scheduled behind the other tasks even if its resources are always ready.
+
+use std::{
+    cell::{Cell, RefCell},
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+
+/// Default budget of a task poll.
+pub(crate) const DEFAULT_BUDGET: u8 = 128;
+
+/// The remaining budget, `None` means unconstrained.
+#[derive(Debug, Clone, Copy)]
+struct Budget(Option<u8>);
+
+#[cfg(feature = "unstable")]
+#[thread_local]
+static CURRENT: Cell<Budget> = Cell::new(Budget(None));
+
+#[cfg(not(feature = "unstable"))]
+thread_local! {
+    static CURRENT: Cell<Budget> = const { Cell::new(Budget(None)) };
+}
+
+thread_local! {
+    // Wakers of the tasks which ran out of budget. Waking them inside the poll would put them
+    // at the front of the queue.
+    static DEFERRED: RefCell<Vec<Waker>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Run `f` with the given budget, and restore the previous one after. The tasks which ran out
+/// of budget in `f` are woken after it returns.
+#[inline]
+pub(crate) fn budget<R>(budget: Option<u8>, f: impl FnOnce() -> R) -> R {
+    let ret = with_budget(Budget(budget), f);
+    let deferred = DEFERRED.with(|deferred| std::mem::take(&mut *deferred.borrow_mut()));
+    for waker in deferred {
+        waker.wake();
+    }
+    ret
+}
+
+#[inline]
+fn with_budget<R>(budget: Budget, f: impl FnOnce() -> R) -> R {
+    struct ResetGuard(Budget);
+
+    impl Drop for ResetGuard {
+        fn drop(&mut self) {
+            CURRENT.set(self.0);
+        }
+    }
+
+    let _guard = ResetGuard(CURRENT.replace(budget));
+    f()
+}
+
+/// Wake the task after its current poll, so it is scheduled behind the other tasks. It is woken
+/// immediately outside the runtime.
+pub(crate) fn defer(waker: &Waker) {
+    if crate::runtime::CURRENT.is_set() {
+        DEFERRED.with(|deferred| deferred.borrow_mut().push(waker.clone()));
+    } else {
+        waker.wake_by_ref();
+    }
+}
+
+/// Consume one unit of the budget, or defer waking the task and return `Pending` if it runs
+/// out. The unit is given back if the returned guard is dropped before
+/// [`made_progress`](RestoreOnPending::made_progress) is called.
+#[inline]
+pub(crate) fn poll_proceed(cx: &mut Context<'_>) -> Poll<RestoreOnPending> {
+    let budget = CURRENT.get();
+    match budget.0 {
+        Some(0) => {
+            defer(cx.waker());
+            Poll::Pending
+        }
+        Some(n) => {
+            CURRENT.set(Budget(Some(n - 1)));
+            Poll::Ready(RestoreOnPending(Cell::new(budget)))
+        }
+        None => Poll::Ready(RestoreOnPending(Cell::new(budget))),
+    }
+}
+
+/// Gives the consumed unit back unless the operation made progress.
+pub(crate) struct RestoreOnPending(Cell<Budget>);
+
+impl RestoreOnPending {
+    #[inline]
+    pub(crate) fn made_progress(&self) {
+        self.0.set(Budget(None));
+    }
+}
+
+impl Drop for RestoreOnPending {
+    fn drop(&mut self) {
+        if let Some(n) = self.0.get().0 {
+            CURRENT.set(Budget(Some(n)));
+        }
+    }
+}
+
+/// Consume one unit of the cooperative scheduling budget of the task, and yield to the other
+/// tasks if it runs out.
+///
+/// It lets a compute-heavy loop which does not do io by itself take part in the cooperative
+/// scheduling, and it is cheaper than [`yield_now`](crate::task::yield_now) in each iteration.
+///
+/// ```
+/// #[monoio::main]
+/// async fn main() {
+///     let mut sum = 0u64;
+///     for i in 0..1_000_000 {
+///         monoio::task::consume_budget().await;
+///         sum += i;
+///     }
+///     assert_eq!(sum, 499_999_500_000);
+/// }
+/// ```
+pub async fn consume_budget() {
+    std::future::poll_fn(|cx| {
+        let coop = std::task::ready!(poll_proceed(cx));
+        coop.made_progress();
+        Poll::Ready(())
+    })
+    .await
+}
+
+/// Run the future without the cooperative scheduling budget, so its io operations never
+/// yield to other tasks by themselves.
+///
+/// Note it may starve the other tasks on the thread if its resources are always ready.
+pub fn unconstrained<F: Future>(future: F) -> Unconstrained<F> {
+    Unconstrained { future }
+}
+
+pin_project_lite::pin_project! {
+    /// Future returned by [`unconstrained`].
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    #[derive(Debug)]
+    pub struct Unconstrained<F> {
+        #[pin]
+        future: F,
+    }
+}
+
+impl<F: Future> Future for Unconstrained<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let future = self.project().future;
+        with_budget(Budget(None), || future.poll(cx))
+    }
+}
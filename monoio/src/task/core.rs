@@ -32,6 +32,8 @@ pub(crate) struct CoreStage<T: Future> {
 pub(crate) enum Stage<T: Future> {
     Running(T),
     Finished(T::Output),
+    Cancelled,
+    Panicked,
     Consumed,
 }
 
@@ -43,6 +45,12 @@ pub(crate) struct Header {
     pub(crate) vtable: &'static Vtable,
     /// Thread ID(sync: used for wake task on its thread; sync disabled: do checking)
     pub(crate) owner_id: usize,
+    /// Where the task is spawned, set if its panic is caught and handled by the panic policy of
+    /// the runtime.
+    pub(crate) catch_panic: Option<&'static std::panic::Location<'static>>,
+    /// ID of the span of the task, for the waker events.
+    #[cfg(feature = "tracing")]
+    pub(crate) tracing_id: Option<tracing::Id>,
 }
 
 pub(crate) struct Trailer {
@@ -59,6 +67,9 @@ impl<T: Future, S: Schedule> Cell<T, S> {
                 state: State::new(),
                 vtable: raw::vtable::<T, S>(),
                 owner_id,
+                catch_panic: None,
+                #[cfg(feature = "tracing")]
+                tracing_id: None,
             },
             core: Core {
                 scheduler,
@@ -113,6 +124,30 @@ impl<T: Future> CoreStage<T> {
         }
     }
 
+    /// Drop the future of the aborted task
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure it is safe to mutate the `stage` field.
+    pub(crate) fn cancel(&self) {
+        // Safety: the caller ensures mutual exclusion to the field.
+        unsafe {
+            self.set_stage(Stage::Cancelled);
+        }
+    }
+
+    /// Drop the future of the panicked task
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure it is safe to mutate the `stage` field.
+    pub(crate) fn panicked(&self) {
+        // Safety: the caller ensures mutual exclusion to the field.
+        unsafe {
+            self.set_stage(Stage::Panicked);
+        }
+    }
+
     /// Store the task output
     ///
     /// # Safety
@@ -137,6 +172,8 @@ impl<T: Future> CoreStage<T> {
             // Safety:: the caller ensures mutual exclusion to the field.
             match mem::replace(unsafe { &mut *ptr }, Stage::Consumed) {
                 Stage::Finished(output) => output,
+                Stage::Cancelled => panic!("JoinHandle polled after the task was aborted"),
+                Stage::Panicked => panic!("JoinHandle polled after the task panicked"),
                 _ => panic!("JoinHandle polled after completion"),
             }
         })
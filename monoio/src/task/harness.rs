@@ -73,10 +73,19 @@ where
         // notified -> running
         self.header().state.transition_to_running();
 
+        // The task is aborted, drop the future instead of polling it.
+        if self.header().state.load().is_cancelled() {
+            self.core().stage.cancel();
+            return PollFuture::Complete;
+        }
+
         // poll the future
         let waker_ref = waker_ref::<T, S>(self.header());
         let cx = Context::from_waker(&waker_ref);
-        let res = poll_future(&self.core().stage, cx);
+        let res = match self.header().catch_panic {
+            Some(location) => poll_future_catch_panic(&self.core().stage, cx, location),
+            None => poll_future(&self.core().stage, cx),
+        };
 
         if res == Poll::Ready(()) {
             return PollFuture::Complete;
@@ -151,6 +160,15 @@ where
         }
     }
 
+    /// Abort the task, which is canceled when it is polled next time. The caller should hold
+    /// a ref-count.
+    pub(super) fn abort(&self) {
+        trace!("MONOIO DEBUG[Harness]:: abort");
+        if self.header().state.transition_to_cancelled() {
+            self.wake_by_ref();
+        }
+    }
+
     // ===== waker behavior =====
 
     /// This call consumes a ref-count and notifies the task. This will create a
@@ -176,15 +194,13 @@ where
                 let waker = unsafe { Waker::from_raw(waker) };
                 crate::runtime::CURRENT.try_with(|maybe_ctx| match maybe_ctx {
                     Some(ctx) => {
-                        ctx.send_waker(owner_id, waker);
-                        ctx.unpark_thread(owner_id);
+                        ctx.wake_thread(owner_id, waker);
                     }
                     None => {
                         let _ = crate::runtime::DEFAULT_CTX.try_with(|default_ctx| {
                             crate::runtime::CURRENT.set(default_ctx, || {
                                 crate::runtime::CURRENT.with(|ctx| {
-                                    ctx.send_waker(owner_id, waker);
-                                    ctx.unpark_thread(owner_id);
+                                    ctx.wake_thread(owner_id, waker);
                                 });
                             });
                         });
@@ -233,15 +249,13 @@ where
                 self.header().state.ref_inc();
                 crate::runtime::CURRENT.try_with(|maybe_ctx| match maybe_ctx {
                     Some(ctx) => {
-                        ctx.send_waker(owner_id, waker);
-                        ctx.unpark_thread(owner_id);
+                        ctx.wake_thread(owner_id, waker);
                     }
                     None => {
                         let _ = crate::runtime::DEFAULT_CTX.try_with(|default_ctx| {
                             crate::runtime::CURRENT.set(default_ctx, || {
                                 crate::runtime::CURRENT.with(|ctx| {
-                                    ctx.send_waker(owner_id, waker);
-                                    ctx.unpark_thread(owner_id);
+                                    ctx.wake_thread(owner_id, waker);
                                 });
                             });
                         });
@@ -407,6 +421,24 @@ enum PollFuture {
     Done,
 }
 
+/// Poll the future and catch its panic, which is handled by the panic policy of the runtime.
+/// The task is completed after the panic.
+fn poll_future_catch_panic<T: Future>(
+    core: &CoreStage<T>,
+    cx: Context<'_>,
+    location: &'static std::panic::Location<'static>,
+) -> Poll<()> {
+    match panic::catch_unwind(panic::AssertUnwindSafe(|| poll_future(core, cx))) {
+        Ok(res) => res,
+        Err(payload) => {
+            // Catch and ignore panics if the future panics on drop.
+            let _ = panic::catch_unwind(panic::AssertUnwindSafe(|| core.panicked()));
+            crate::runtime::panic::task_panicked(payload, location);
+            Poll::Ready(())
+        }
+    }
+}
+
 /// Poll the future. If the future completes, the output is written to the
 /// stage field.
 fn poll_future<T: Future>(core: &CoreStage<T>, cx: Context<'_>) -> Poll<()> {
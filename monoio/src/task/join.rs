@@ -8,7 +8,12 @@ use std::{
 use super::raw::RawTask;
 
 /// JoinHandle can be used to wait task finished.
-/// Note if you drop it directly, task will not be terminated.
+/// Note if you drop it directly, task will not be terminated, it can be aborted with
+/// [`abort`](JoinHandle::abort) or an [`AbortHandle`].
+///
+/// # Panics
+///
+/// Awaiting the handle of an aborted task panics.
 pub struct JoinHandle<T> {
     raw: RawTask,
     _p: PhantomData<T>,
@@ -30,6 +35,17 @@ impl<T> JoinHandle<T> {
         let state = self.raw.header().state.load();
         state.is_complete()
     }
+
+    /// Abort the task. It is canceled when it is polled next time, i.e. its future is
+    /// dropped at the point it is waiting on. It does nothing if the task has finished.
+    pub fn abort(&self) {
+        self.raw.abort();
+    }
+
+    /// Returns an [`AbortHandle`] to abort the task, which does not keep the output.
+    pub fn abort_handle(&self) -> AbortHandle {
+        AbortHandle::new(self.raw)
+    }
 }
 
 impl<T> Unpin for JoinHandle<T> {}
@@ -68,3 +84,51 @@ impl<T> Drop for JoinHandle<T> {
         self.raw.drop_join_handle_slow();
     }
 }
+
+/// A handle to abort a spawned task, obtained by [`JoinHandle::abort_handle`].
+///
+/// It can be cloned, and dropping it does not abort the task.
+pub struct AbortHandle {
+    raw: RawTask,
+}
+
+impl AbortHandle {
+    fn new(raw: RawTask) -> AbortHandle {
+        raw.header().state.ref_inc();
+        AbortHandle { raw }
+    }
+
+    /// Abort the task. It is canceled when it is polled next time, i.e. its future is
+    /// dropped at the point it is waiting on. It does nothing if the task has finished.
+    pub fn abort(&self) {
+        self.raw.abort();
+    }
+
+    /// Checks if the task has finished, including being canceled.
+    pub fn is_finished(&self) -> bool {
+        let state = self.raw.header().state.load();
+        state.is_complete()
+    }
+}
+
+impl Clone for AbortHandle {
+    fn clone(&self) -> Self {
+        AbortHandle::new(self.raw)
+    }
+}
+
+impl Drop for AbortHandle {
+    fn drop(&mut self) {
+        if self.raw.header().state.ref_dec() {
+            self.raw.dealloc();
+        }
+    }
+}
+
+impl std::fmt::Debug for AbortHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AbortHandle")
+            .field("state", &self.raw.header().state)
+            .finish()
+    }
+}
@@ -2,6 +2,9 @@
 // Heavily borrowed from tokio.
 // Copyright (c) 2021 Tokio Contributors, licensed under the MIT license.
 
+pub(crate) mod coop;
+pub use self::coop::{consume_budget, unconstrained, Unconstrained};
+
 mod utils;
 pub(crate) mod waker_fn;
 
@@ -13,13 +16,20 @@ use self::harness::Harness;
 
 mod join;
 #[allow(unreachable_pub)] // https://github.com/rust-lang/rust/issues/57411
-pub use self::join::JoinHandle;
+pub use self::join::{AbortHandle, JoinHandle};
 
 mod raw;
 use self::raw::RawTask;
 
+mod scope;
+pub use self::scope::{scope, try_scope, Scope};
+
 mod state;
 
+mod yield_now;
+pub use self::yield_now::yield_now;
+pub use crate::scheduler::Priority;
+
 mod waker;
 
 use std::{future::Future, marker::PhantomData, ptr::NonNull};
@@ -43,10 +53,30 @@ impl<S: 'static> Task<S> {
         self.raw.header()
     }
 
+    /// Catch the panic of the new task spawned at `location`, which is handled by the panic
+    /// policy of the runtime.
+    pub(crate) fn catch_panic(&self, location: &'static std::panic::Location<'static>) {
+        // The task is not polled or woken before it is scheduled.
+        unsafe { self.raw.catch_panic(location) }
+    }
+
+    /// Set the span id of the new task, which is carried by the waker events.
+    #[cfg(feature = "tracing")]
+    pub(crate) fn set_tracing_id(&self, id: Option<tracing::Id>) {
+        // The task is not polled or woken before it is scheduled.
+        unsafe { self.raw.set_tracing_id(id) }
+    }
+
     pub(crate) fn run(self) {
         self.raw.poll();
     }
 
+    /// If the task is aborted before it runs.
+    #[cfg(feature = "sync")]
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.header().state.load().is_cancelled()
+    }
+
     #[cfg(feature = "sync")]
     pub(crate) unsafe fn finish(&mut self, val_slot: *mut ()) {
         self.raw.finish(val_slot);
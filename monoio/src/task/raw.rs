@@ -30,6 +30,9 @@ pub(crate) struct Vtable {
     /// The join handle has been dropped
     pub(crate) drop_join_handle_slow: unsafe fn(NonNull<Header>),
 
+    /// Abort the task
+    pub(crate) abort: unsafe fn(NonNull<Header>),
+
     /// Set future output
     #[cfg(feature = "sync")]
     pub(crate) finish: unsafe fn(NonNull<Header>, *mut ()),
@@ -42,6 +45,7 @@ pub(super) fn vtable<T: Future, S: Schedule>() -> &'static Vtable {
         dealloc: dealloc::<T, S>,
         try_read_output: try_read_output::<T, S>,
         drop_join_handle_slow: drop_join_handle_slow::<T, S>,
+        abort: abort::<T, S>,
         #[cfg(feature = "sync")]
         finish: finish::<T, S>,
     }
@@ -67,6 +71,17 @@ impl RawTask {
         unsafe { self.ptr.as_ref() }
     }
 
+    /// Safety: the task must not be polled or woken yet.
+    pub(crate) unsafe fn catch_panic(self, location: &'static std::panic::Location<'static>) {
+        (*self.ptr.as_ptr()).catch_panic = Some(location);
+    }
+
+    /// Safety: the task must not be polled or woken yet.
+    #[cfg(feature = "tracing")]
+    pub(crate) unsafe fn set_tracing_id(self, id: Option<tracing::Id>) {
+        (*self.ptr.as_ptr()).tracing_id = id;
+    }
+
     /// Safety: mutual exclusion is required to call this function.
     pub(crate) fn poll(self) {
         let vtable = self.header().vtable;
@@ -92,6 +107,11 @@ impl RawTask {
         unsafe { (vtable.drop_join_handle_slow)(self.ptr) }
     }
 
+    pub(crate) fn abort(self) {
+        let vtable = self.header().vtable;
+        unsafe { (vtable.abort)(self.ptr) }
+    }
+
     #[cfg(feature = "sync")]
     pub(crate) unsafe fn finish(self, val_slot: *mut ()) {
         let vtable = self.header().vtable;
@@ -131,3 +151,8 @@ unsafe fn drop_join_handle_slow<T: Future, S: Schedule>(ptr: NonNull<Header>) {
     let harness = Harness::<T, S>::from_raw(ptr);
     harness.drop_join_handle_slow()
 }
+
+unsafe fn abort<T: Future, S: Schedule>(ptr: NonNull<Header>) {
+    let harness = Harness::<T, S>::from_raw(ptr);
+    harness.abort()
+}
@@ -0,0 +1,235 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    convert::Infallible,
+    future::Future,
+    rc::Rc,
+    task::{Poll, Waker},
+};
+
+use super::{AbortHandle, JoinHandle};
+
+/// Run `f` with a [`Scope`] to spawn child tasks on, and wait for all of them to finish.
+///
+/// The returned future resolves only after the future returned by `f` and every task spawned on
+/// the scope (including the ones spawned by other children) have finished. If it is dropped
+/// before that, all the children are aborted, so no task outlives its scope.
+///
+/// ```
+/// #[monoio::main]
+/// async fn main() {
+///     let sum = std::rc::Rc::new(std::cell::Cell::new(0));
+///     let sum_ = sum.clone();
+///     monoio::task::scope(|s| async move {
+///         for i in 1..=4 {
+///             let sum = sum_.clone();
+///             s.spawn(async move { sum.set(sum.get() + i) });
+///         }
+///     })
+///     .await;
+///     assert_eq!(sum.get(), 10);
+/// }
+/// ```
+pub async fn scope<F, Fut>(f: F) -> Fut::Output
+where
+    F: FnOnce(Scope) -> Fut,
+    Fut: Future,
+{
+    let scope = Scope::new();
+    let _guard = CancelOnDrop(scope.clone());
+    let output = f(scope.clone()).await;
+    scope.join().await;
+    output
+}
+
+/// Like [`scope`], but the children spawned with [`Scope::spawn_try`] may fail.
+///
+/// The first error, either returned by `f` or by a child, aborts all the other children, and is
+/// returned after they have finished.
+pub async fn try_scope<F, Fut, T, E>(f: F) -> Result<T, E>
+where
+    F: FnOnce(Scope<E>) -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    E: 'static,
+{
+    let scope = Scope::new();
+    let _guard = CancelOnDrop(scope.clone());
+    let output = match f(scope.clone()).await {
+        Ok(output) => Some(output),
+        Err(e) => {
+            scope.inner.fail(e);
+            None
+        }
+    };
+    scope.join().await;
+    let error = scope.inner.error.borrow_mut().take();
+    match error {
+        Some(e) => Err(e),
+        None => Ok(output.expect("scope output missing without an error")),
+    }
+}
+
+/// A handle to spawn tasks bounded by a [`scope`] or [`try_scope`].
+///
+/// It can be cloned and moved into the children to spawn more tasks on the same scope. Tasks
+/// spawned after the scope has finished or been cancelled are aborted immediately.
+pub struct Scope<E = Infallible> {
+    inner: Rc<Inner<E>>,
+}
+
+struct Inner<E> {
+    children: RefCell<HashMap<usize, AbortHandle>>,
+    next_id: Cell<usize>,
+    cancelled: Cell<bool>,
+    error: RefCell<Option<E>>,
+    waker: RefCell<Option<Waker>>,
+}
+
+impl<E: 'static> Scope<E> {
+    fn new() -> Self {
+        Scope {
+            inner: Rc::new(Inner {
+                children: RefCell::new(HashMap::new()),
+                next_id: Cell::new(0),
+                cancelled: Cell::new(false),
+                error: RefCell::new(None),
+                waker: RefCell::new(None),
+            }),
+        }
+    }
+
+    /// Spawn a child task on the scope.
+    ///
+    /// Note awaiting the returned handle panics if the child has been aborted by the scope.
+    #[track_caller]
+    pub fn spawn<T>(&self, future: T) -> JoinHandle<T::Output>
+    where
+        T: Future + 'static,
+        T::Output: 'static,
+    {
+        let id = self.inner.next_id.get();
+        self.inner.next_id.set(id + 1);
+        let guard = ChildGuard {
+            inner: self.inner.clone(),
+            id,
+        };
+        let handle = crate::spawn(async move {
+            let _guard = guard;
+            future.await
+        });
+        self.inner
+            .children
+            .borrow_mut()
+            .insert(id, handle.abort_handle());
+        if self.inner.cancelled.get() {
+            handle.abort();
+        }
+        handle
+    }
+
+    /// Spawn a child task which may fail. If it fails, the error is kept by the scope and all
+    /// the other children are aborted; the returned handle yields `None` in that case.
+    #[track_caller]
+    pub fn spawn_try<T, F>(&self, future: F) -> JoinHandle<Option<T>>
+    where
+        F: Future<Output = Result<T, E>> + 'static,
+        T: 'static,
+    {
+        let inner = self.inner.clone();
+        self.spawn(async move {
+            match future.await {
+                Ok(output) => Some(output),
+                Err(e) => {
+                    inner.fail(e);
+                    None
+                }
+            }
+        })
+    }
+
+    /// Abort all the children, and the ones spawned later.
+    pub fn cancel(&self) {
+        self.inner.cancel();
+    }
+
+    /// Checks if the scope has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.get()
+    }
+
+    async fn join(&self) {
+        std::future::poll_fn(|cx| {
+            if self.inner.children.borrow().is_empty() {
+                return Poll::Ready(());
+            }
+            *self.inner.waker.borrow_mut() = Some(cx.waker().clone());
+            Poll::Pending
+        })
+        .await
+    }
+}
+
+impl<E> Inner<E> {
+    fn cancel(&self) {
+        self.cancelled.set(true);
+        // Collect first, aborting must not happen while the map is borrowed.
+        let children: Vec<AbortHandle> = self.children.borrow().values().cloned().collect();
+        for child in children {
+            child.abort();
+        }
+    }
+
+    fn fail(&self, e: E) {
+        {
+            let mut error = self.error.borrow_mut();
+            if error.is_none() {
+                *error = Some(e);
+            }
+        }
+        self.cancel();
+    }
+}
+
+impl<E> Clone for Scope<E> {
+    fn clone(&self) -> Self {
+        Scope {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<E> std::fmt::Debug for Scope<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Scope")
+            .field("children", &self.inner.children.borrow().len())
+            .field("cancelled", &self.inner.cancelled.get())
+            .finish()
+    }
+}
+
+/// Cancels the scope when the scope future is finished or dropped.
+struct CancelOnDrop<E>(Scope<E>);
+
+impl<E> Drop for CancelOnDrop<E> {
+    fn drop(&mut self) {
+        self.0.inner.cancel();
+    }
+}
+
+/// Lives in the child future, unregisters the child when it is completed or aborted.
+struct ChildGuard<E> {
+    inner: Rc<Inner<E>>,
+    id: usize,
+}
+
+impl<E> Drop for ChildGuard<E> {
+    fn drop(&mut self) {
+        let child = self.inner.children.borrow_mut().remove(&self.id);
+        drop(child);
+        if self.inner.children.borrow().is_empty() {
+            if let Some(waker) = self.inner.waker.borrow_mut().take() {
+                waker.wake();
+            }
+        }
+    }
+}
@@ -36,8 +36,12 @@ const JOIN_INTEREST: usize = 0b1_000;
 #[allow(clippy::unusual_byte_groupings)] // https://github.com/rust-lang/rust-clippy/issues/6556
 const JOIN_WAKER: usize = 0b10_000;
 
+/// The task has been aborted, and is canceled when it is polled next time
+#[allow(clippy::unusual_byte_groupings)] // https://github.com/rust-lang/rust-clippy/issues/6556
+const CANCELLED: usize = 0b100_000;
+
 /// All bits
-const STATE_MASK: usize = LIFECYCLE_MASK | NOTIFIED | JOIN_INTEREST | JOIN_WAKER;
+const STATE_MASK: usize = LIFECYCLE_MASK | NOTIFIED | JOIN_INTEREST | JOIN_WAKER | CANCELLED;
 
 /// Bits used by the ref count portion of the state.
 const REF_COUNT_MASK: usize = !STATE_MASK;
@@ -149,6 +153,23 @@ impl State {
         })
     }
 
+    /// Set the `CANCELLED` bit.
+    ///
+    /// Returns `true` if the bit is newly set, and the task should be notified to be
+    /// canceled. Returns `false` if the task has completed or been aborted.
+    pub(super) fn transition_to_cancelled(&self) -> bool {
+        self.fetch_update(|curr| {
+            if curr.is_complete() || curr.is_cancelled() {
+                return None;
+            }
+
+            let mut next = curr;
+            next.0 |= CANCELLED;
+            Some(next)
+        })
+        .is_ok()
+    }
+
     /// Optimistically tries to swap the state assuming the join handle is
     /// __immediately__ dropped on spawn
     pub(super) fn drop_join_handle_fast(&self) -> Result<(), ()> {
@@ -343,6 +364,11 @@ impl Snapshot {
         self.0 & COMPLETE == COMPLETE
     }
 
+    /// Returns `true` if the task has been aborted.
+    pub(super) fn is_cancelled(self) -> bool {
+        self.0 & CANCELLED == CANCELLED
+    }
+
     pub(super) fn is_join_interested(self) -> bool {
         self.0 & JOIN_INTEREST == JOIN_INTEREST
     }
@@ -381,6 +407,7 @@ impl fmt::Debug for Snapshot {
             .field("is_running", &self.is_running())
             .field("is_complete", &self.is_complete())
             .field("is_notified", &self.is_notified())
+            .field("is_cancelled", &self.is_cancelled())
             .field("is_join_interested", &self.is_join_interested())
             .field("has_join_waker", &self.has_join_waker())
             .field("ref_count", &self.ref_count())
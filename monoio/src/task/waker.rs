@@ -52,6 +52,8 @@ where
 {
     let header = ptr as *const Header;
     trace!("MONOIO DEBUG[Waker]: clone_waker");
+    #[cfg(feature = "tracing")]
+    trace_waker_op(header, "waker.clone");
     (*header).state.ref_inc();
     raw_waker::<T, S>(header)
 }
@@ -61,6 +63,8 @@ where
     T: Future,
     S: Schedule,
 {
+    #[cfg(feature = "tracing")]
+    trace_waker_op(ptr as *const Header, "waker.drop");
     let ptr = NonNull::new_unchecked(ptr as *mut Header);
     let harness = Harness::<T, S>::from_raw(ptr);
     harness.drop_reference();
@@ -71,6 +75,8 @@ where
     T: Future,
     S: Schedule,
 {
+    #[cfg(feature = "tracing")]
+    trace_waker_op(ptr as *const Header, "waker.wake");
     let ptr = NonNull::new_unchecked(ptr as *mut Header);
     let harness = Harness::<T, S>::from_raw(ptr);
     harness.wake_by_val();
@@ -82,11 +88,21 @@ where
     T: Future,
     S: Schedule,
 {
+    #[cfg(feature = "tracing")]
+    trace_waker_op(ptr as *const Header, "waker.wake_by_ref");
     let ptr = NonNull::new_unchecked(ptr as *mut Header);
     let harness = Harness::<T, S>::from_raw(ptr);
     harness.wake_by_ref();
 }
 
+/// Emit the waker event in the form of tokio, which is understood by tokio-console.
+#[cfg(feature = "tracing")]
+unsafe fn trace_waker_op(header: *const Header, op: &'static str) {
+    if let Some(id) = (*header).tracing_id.as_ref() {
+        tracing::trace!(target: "runtime::waker", op, task.id = id.into_u64());
+    }
+}
+
 pub(super) fn raw_waker<T, S>(header: *const Header) -> RawWaker
 where
     T: Future,
@@ -41,6 +41,11 @@ pub(crate) fn should_poll() -> bool {
     SHOULD_POLL.replace(false)
 }
 
+#[inline]
+pub(crate) fn is_poll_set() -> bool {
+    SHOULD_POLL.get()
+}
+
 #[inline]
 pub(crate) fn set_poll() {
     SHOULD_POLL.set(true);
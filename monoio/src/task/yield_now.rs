@@ -0,0 +1,39 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Yield to the other tasks and the driver, and resume after them.
+///
+/// The task is scheduled behind the ready tasks, and the runtime does not park while it is
+/// ready, so the driver only gets a chance to submit and reap completions without blocking.
+///
+/// ```
+/// #[monoio::main]
+/// async fn main() {
+///     let task = monoio::spawn(async { 1 });
+///     monoio::task::yield_now().await;
+///     assert!(task.is_finished());
+/// }
+/// ```
+pub async fn yield_now() {
+    struct YieldNow {
+        yielded: bool,
+    }
+
+    impl Future for YieldNow {
+        type Output = ();
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            if self.yielded {
+                return Poll::Ready(());
+            }
+            self.yielded = true;
+            super::coop::defer(cx.waker());
+            Poll::Pending
+        }
+    }
+
+    YieldNow { yielded: false }.await
+}
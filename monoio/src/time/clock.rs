@@ -1,24 +1,279 @@
 //! Source of time abstraction.
 //!
-//! By default, `std::time::Instant::now()` is used. However, when the
-//! `test-util` feature flag is enabled, the values returned for `now()` are
-//! configurable.
+//! By default, `std::time::Instant::now()` is used. However, the runtime may
+//! be built with a custom [`Clock`], and when the `test-util` feature flag is
+//! enabled, the values returned for `now()` are configurable.
 
 use crate::time::Instant;
 
-#[derive(Default, Debug, Clone)]
-pub(crate) struct Clock {}
+/// A source of time for a runtime, which all the timer APIs of the runtime,
+/// including [`Instant::now`](crate::time::Instant::now), are routed through.
+///
+/// It is set by [`RuntimeBuilder::with_clock`](crate::RuntimeBuilder::with_clock)
+/// for e.g. a clock reading the TSC, or a simulated clock. The instants
+/// returned are usually computed by adding the elapsed time of the clock to an
+/// instant of [`Instant::from_std`], and must never go backwards.
+///
+/// The driver parks for the durations measured by the clock, and the timers
+/// are driven by the time driver instead of the kernel.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use monoio::time::{Clock, Instant};
+///
+/// /// A clock running twice as fast.
+/// struct Fast(std::time::Instant);
+///
+/// impl Clock for Fast {
+///     fn now(&self) -> Instant {
+///         Instant::from_std(self.0 + self.0.elapsed() * 2)
+///     }
+/// }
+///
+/// let mut rt = monoio::RuntimeBuilder::<monoio::FusionDriver>::new()
+///     .enable_timer()
+///     .with_clock(Fast(std::time::Instant::now()))
+///     .build()
+///     .unwrap();
+/// rt.block_on(async {
+///     let begin = Instant::now();
+///     monoio::time::sleep(Duration::from_millis(100)).await;
+///     assert!(begin.elapsed() >= Duration::from_millis(100));
+/// });
+/// ```
+pub trait Clock: 'static {
+    /// Returns the current instant.
+    fn now(&self) -> Instant;
+}
 
+/// The current instant of the clock of the current runtime, or the system clock outside
+/// of a runtime.
 pub(crate) fn now() -> Instant {
-    Instant::from_std(std::time::Instant::now())
+    if crate::runtime::CURRENT.is_set() {
+        crate::runtime::CURRENT.with(|ctx| ctx.clock.now())
+    } else {
+        Instant::from_std(std::time::Instant::now())
+    }
+}
+
+/// Reads the custom clock, or the system clock.
+fn source_now(source: &Option<std::rc::Rc<dyn Clock>>) -> Instant {
+    match source {
+        Some(source) => source.now(),
+        None => Instant::from_std(std::time::Instant::now()),
+    }
+}
+
+#[cfg(not(feature = "test-util"))]
+mod variant {
+    use std::{fmt, rc::Rc};
+
+    use super::{source_now, Clock};
+    use crate::time::Instant;
+
+    /// The clock of a runtime, shared by the time driver.
+    #[derive(Default, Clone)]
+    pub(crate) struct ClockHandle {
+        source: Option<Rc<dyn Clock>>,
+    }
+
+    impl ClockHandle {
+        pub(crate) fn new(source: Option<Rc<dyn Clock>>) -> ClockHandle {
+            ClockHandle { source }
+        }
+
+        pub(crate) fn now(&self) -> Instant {
+            source_now(&self.source)
+        }
+
+        /// If the clock is not the system clock.
+        pub(crate) fn is_custom(&self) -> bool {
+            self.source.is_some()
+        }
+    }
+
+    impl fmt::Debug for ClockHandle {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("ClockHandle")
+                .field("custom", &self.is_custom())
+                .finish()
+        }
+    }
 }
 
-impl Clock {
-    pub(crate) fn new() -> Clock {
-        Clock {}
+#[cfg(feature = "test-util")]
+mod variant {
+    use std::{cell::RefCell, fmt, rc::Rc};
+
+    use super::{source_now, Clock};
+    use crate::time::{driver::Handle, Duration, Instant};
+
+    /// The clock of a runtime, which can be paused and advanced, shared by the time driver.
+    #[derive(Clone)]
+    pub(crate) struct ClockHandle {
+        inner: Rc<RefCell<Inner>>,
+        source: Option<Rc<dyn Clock>>,
+    }
+
+    #[derive(Debug)]
+    struct Inner {
+        /// The instant when the clock was paused last, plus the durations advanced.
+        base: Instant,
+
+        /// The instant of the source when the clock was resumed, or `None` if it is paused.
+        unfrozen: Option<Instant>,
+    }
+
+    /// Returns the clock of the current runtime, if the timer is enabled.
+    fn current() -> ClockHandle {
+        let clock = crate::runtime::CURRENT.is_set().then(|| {
+            crate::runtime::CURRENT.with(|ctx| ctx.time_handle.is_some().then(|| ctx.clock.clone()))
+        });
+        clock
+            .flatten()
+            .expect("the clock can only be controlled in a Monoio runtime with the timer enabled")
+    }
+
+    /// Pauses the clock of the current runtime(requires `test-util` feature).
+    ///
+    /// Once paused, [`Instant::now`] only moves forward by [`advance`], or by the
+    /// time driver when the runtime has no task to run: it then jumps to the
+    /// earliest deadline of the timers, so the timers expire without waiting.
+    /// The timer logic is tested deterministically and instantly.
+    ///
+    /// The clock is paused for the runtime, and the timers are driven by the time
+    /// driver instead of the kernel even with the uring driver.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the clock is already paused, or it is called outside of a
+    /// runtime with the timer enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use monoio::time::{self, Duration, Instant};
+    ///
+    /// #[monoio::main(timer_enabled = true)]
+    /// async fn main() {
+    ///     time::pause();
+    ///     let begin = Instant::now();
+    ///     // It completes at once.
+    ///     time::sleep(Duration::from_secs(3600)).await;
+    ///     assert!(begin.elapsed() >= Duration::from_secs(3600));
+    /// }
+    /// ```
+    pub fn pause() {
+        let clock = current();
+        // Pause at a whole millisecond of the time driver, so the deadlines after durations
+        // of whole milliseconds are reached exactly by advancing.
+        let start = Handle::current().start_time();
+        let elapsed = clock.now().saturating_duration_since(start);
+        let ms = Duration::from_millis(elapsed.as_nanos().div_ceil(1_000_000) as u64);
+        clock.pause_at(start + ms);
+    }
+
+    /// Resumes the clock of the current runtime paused by [`pause`], which moves
+    /// forward in real time again from the paused instant(requires `test-util`
+    /// feature).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the clock is not paused, or it is called outside of a runtime
+    /// with the timer enabled.
+    pub fn resume() {
+        current().resume();
+    }
+
+    /// Advances the paused clock of the current runtime by the duration, fires
+    /// the timers expired, and yields to let the tasks woken by them run
+    /// (requires `test-util` feature).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the clock is not paused, or it is called outside of a runtime
+    /// with the timer enabled.
+    pub async fn advance(duration: Duration) {
+        current().advance(duration);
+        Handle::current().process();
+        // The woken tasks are queued before it, so they run before it completes. Waking
+        // itself does not yield, since the future blocked on is polled again at once.
+        crate::spawn(async {}).await;
+    }
+
+    impl Default for ClockHandle {
+        fn default() -> Self {
+            ClockHandle::new(None)
+        }
+    }
+
+    impl ClockHandle {
+        pub(crate) fn new(source: Option<Rc<dyn Clock>>) -> ClockHandle {
+            let now = source_now(&source);
+            ClockHandle {
+                inner: Rc::new(RefCell::new(Inner {
+                    base: now,
+                    unfrozen: Some(now),
+                })),
+                source,
+            }
+        }
+
+        pub(crate) fn now(&self) -> Instant {
+            let inner = self.inner.borrow();
+            match inner.unfrozen {
+                Some(unfrozen) => {
+                    inner.base + source_now(&self.source).saturating_duration_since(unfrozen)
+                }
+                None => inner.base,
+            }
+        }
+
+        /// If the clock is not the system clock.
+        pub(crate) fn is_custom(&self) -> bool {
+            self.source.is_some()
+        }
+
+        pub(crate) fn is_paused(&self) -> bool {
+            self.inner.borrow().unfrozen.is_none()
+        }
+
+        /// Pause the clock at the instant, which is not earlier than now.
+        pub(crate) fn pause_at(&self, at: Instant) {
+            let mut inner = self.inner.borrow_mut();
+            assert!(
+                inner.unfrozen.take().is_some(),
+                "the clock is already paused"
+            );
+            inner.base = at;
+        }
+
+        pub(crate) fn resume(&self) {
+            let mut inner = self.inner.borrow_mut();
+            assert!(inner.unfrozen.is_none(), "the clock is not paused");
+            inner.unfrozen = Some(source_now(&self.source));
+        }
+
+        pub(crate) fn advance(&self, duration: Duration) {
+            let mut inner = self.inner.borrow_mut();
+            assert!(inner.unfrozen.is_none(), "the clock is not paused");
+            inner.base += duration;
+        }
     }
 
-    pub(crate) fn now(&self) -> Instant {
-        now()
+    impl fmt::Debug for ClockHandle {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("ClockHandle")
+                .field("inner", &self.inner)
+                .field("custom", &self.is_custom())
+                .finish()
+        }
     }
 }
+
+pub(crate) use self::variant::ClockHandle;
+#[cfg(feature = "test-util")]
+pub use self::variant::{advance, pause, resume};
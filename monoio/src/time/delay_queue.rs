@@ -0,0 +1,262 @@
+//! A queue of items yielded when their deadlines pass.
+//!
+//! See [`DelayQueue`] documentation for more details.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use crate::{
+    io::stream::Stream,
+    macros::support::poll_fn,
+    time::{sleep_until, Duration, Instant, Sleep},
+};
+
+/// A queue of items, each of which is yielded once its deadline has passed.
+///
+/// Inserting an item returns a [`Key`], with which the item can be removed or
+/// its deadline reset before it expires. The items are ordered by deadline, and
+/// only one timer of the runtime is armed, for the earliest deadline, so the
+/// queue can track many items like the idle connections or the cache entries
+/// to evict.
+///
+/// The expired items are yielded by [`poll_expired`](DelayQueue::poll_expired)
+/// or the [`Stream`] implementation, which yields `None` when the queue is
+/// empty.
+///
+/// # Examples
+///
+/// ```
+/// use monoio::{
+///     io::stream::Stream,
+///     time::{DelayQueue, Duration},
+/// };
+///
+/// #[monoio::main(timer_enabled = true)]
+/// async fn main() {
+///     let mut queue = DelayQueue::new();
+///     queue.insert("b", Duration::from_millis(20));
+///     let key = queue.insert("a", Duration::from_millis(10));
+///     queue.insert("c", Duration::from_millis(30));
+///
+///     // Refresh "a", so it expires last.
+///     queue.reset(&key, Duration::from_millis(40));
+///
+///     while let Some(expired) = queue.next().await {
+///         println!("{} expired", expired.into_inner());
+///     }
+/// }
+/// ```
+pub struct DelayQueue<T> {
+    /// The items in the queue and their deadlines.
+    entries: HashMap<u64, (T, Instant)>,
+
+    /// The keys ordered by deadline, where the earlier inserted item is the first of the
+    /// items of the same deadline.
+    expirations: BTreeSet<(Instant, u64)>,
+
+    /// The timer armed for the earliest deadline.
+    delay: Option<Pin<Box<Sleep>>>,
+
+    /// The key of the next inserted item. Keys are never reused.
+    next_key: u64,
+}
+
+/// The key of an item in a [`DelayQueue`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key(u64);
+
+/// An item yielded by a [`DelayQueue`] when it expires, or removed from it.
+#[derive(Debug)]
+pub struct Expired<T> {
+    data: T,
+    deadline: Instant,
+    key: Key,
+}
+
+impl<T> Expired<T> {
+    /// Returns a shared reference to the item.
+    pub fn get_ref(&self) -> &T {
+        &self.data
+    }
+
+    /// Returns a mutable reference to the item.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.data
+    }
+
+    /// Consumes `self` and returns the item.
+    pub fn into_inner(self) -> T {
+        self.data
+    }
+
+    /// Returns the deadline of the item.
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    /// Returns the key of the item, which is no longer in the queue.
+    pub fn key(&self) -> Key {
+        self.key
+    }
+}
+
+impl<T> DelayQueue<T> {
+    /// Creates an empty queue.
+    pub fn new() -> DelayQueue<T> {
+        DelayQueue {
+            entries: HashMap::new(),
+            expirations: BTreeSet::new(),
+            delay: None,
+            next_key: 0,
+        }
+    }
+
+    /// Inserts the item to be yielded at the deadline, and returns its key.
+    pub fn insert_at(&mut self, value: T, when: Instant) -> Key {
+        let key = self.next_key;
+        self.next_key += 1;
+        self.entries.insert(key, (value, when));
+        self.expirations.insert((when, key));
+        self.rearm(when);
+        Key(key)
+    }
+
+    /// Inserts the item to be yielded after the timeout, and returns its key.
+    pub fn insert(&mut self, value: T, timeout: Duration) -> Key {
+        self.insert_at(value, deadline_after(timeout))
+    }
+
+    /// Removes the item of the key. Returns `None` if it has expired or been removed.
+    pub fn remove(&mut self, key: &Key) -> Option<Expired<T>> {
+        let (data, deadline) = self.entries.remove(&key.0)?;
+        self.expirations.remove(&(deadline, key.0));
+        if self.expirations.is_empty() {
+            self.delay = None;
+        }
+        Some(Expired {
+            data,
+            deadline,
+            key: *key,
+        })
+    }
+
+    /// Resets the deadline of the item of the key. Returns `false` if it has expired or
+    /// been removed.
+    pub fn reset_at(&mut self, key: &Key, when: Instant) -> bool {
+        let Some((_, deadline)) = self.entries.get_mut(&key.0) else {
+            return false;
+        };
+        self.expirations.remove(&(*deadline, key.0));
+        *deadline = when;
+        self.expirations.insert((when, key.0));
+        self.rearm(when);
+        true
+    }
+
+    /// Resets the item of the key to be yielded after the timeout. Returns `false` if it
+    /// has expired or been removed.
+    pub fn reset(&mut self, key: &Key, timeout: Duration) -> bool {
+        self.reset_at(key, deadline_after(timeout))
+    }
+
+    /// Returns the deadline of the item of the key, or `None` if it has expired or been
+    /// removed.
+    pub fn deadline(&self, key: &Key) -> Option<Instant> {
+        self.entries.get(&key.0).map(|(_, deadline)| *deadline)
+    }
+
+    /// Returns the key of the item expiring first.
+    pub fn peek(&self) -> Option<Key> {
+        self.expirations.first().map(|(_, key)| Key(*key))
+    }
+
+    /// Returns the number of items in the queue.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if there is no item in the queue.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Removes all the items.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.expirations.clear();
+        self.delay = None;
+    }
+
+    /// Polls the next expired item. Returns `Ready(None)` if the queue is empty.
+    ///
+    /// When the method returns `Poll::Pending`, the `Waker` in the provided
+    /// `Context` is scheduled to receive a wakeup when the earliest item
+    /// expires, or an earlier one is inserted.
+    pub fn poll_expired(&mut self, cx: &mut Context<'_>) -> Poll<Option<Expired<T>>> {
+        let Some(&(deadline, key)) = self.expirations.first() else {
+            self.delay = None;
+            return Poll::Ready(None);
+        };
+        if deadline > Instant::now() {
+            let delay = match &mut self.delay {
+                Some(delay) => {
+                    if delay.deadline() != deadline {
+                        delay.as_mut().reset(deadline);
+                    }
+                    delay
+                }
+                None => self.delay.insert(Box::pin(sleep_until(deadline))),
+            };
+            ready!(delay.as_mut().poll(cx));
+        }
+        Poll::Ready(self.remove(&Key(key)))
+    }
+
+    /// Reset the armed timer if the item inserted or reset expires earlier, so the task
+    /// waiting for it is woken in time.
+    fn rearm(&mut self, when: Instant) {
+        if let Some(delay) = &mut self.delay {
+            if when < delay.deadline() {
+                delay.as_mut().reset(when);
+            }
+        }
+    }
+}
+
+fn deadline_after(timeout: Duration) -> Instant {
+    Instant::now()
+        .checked_add(timeout)
+        .unwrap_or_else(Instant::far_future)
+}
+
+impl<T> Default for DelayQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Stream for DelayQueue<T> {
+    type Item = Expired<T>;
+
+    async fn next(&mut self) -> Option<Self::Item> {
+        poll_fn(|cx| self.poll_expired(cx)).await
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len(), Some(self.len()))
+    }
+}
+
+impl<T> fmt::Debug for DelayQueue<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DelayQueue")
+            .field("len", &self.len())
+            .field("next_deadline", &self.expirations.first().map(|(d, _)| d))
+            .finish()
+    }
+}
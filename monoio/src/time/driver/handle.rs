@@ -21,6 +21,12 @@ impl Handle {
         &self.time_source
     }
 
+    /// Returns the instant of the tick 0 of the time source.
+    #[cfg(feature = "test-util")]
+    pub(crate) fn start_time(&self) -> crate::time::Instant {
+        self.time_source.start_time
+    }
+
     /// Access the driver's inner structure
     pub(super) fn get(&self) -> &super::Inner {
         &self.inner
@@ -0,0 +1,133 @@
+//! Timers armed in the kernel with `IORING_OP_TIMEOUT`, which are used instead of the
+//! timing wheel when the uring driver is active. The ring is waited without a user space
+//! park timeout, and each timer wakes its own task when it expires.
+
+use std::{
+    fmt,
+    future::Future,
+    io,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+
+use crate::{
+    driver::op::{timeout::Timeout, Op},
+    time::{Duration, Instant},
+};
+
+/// If the timers are armed in the kernel. The kernel only measures the system clock, so the
+/// time driver is used with a custom clock. With the `test-util` feature, the time driver is
+/// preferred when it is enabled, so that its clock can be paused.
+pub(crate) fn kernel_timer_enabled() -> bool {
+    if crate::runtime::CURRENT.is_set()
+        && crate::runtime::CURRENT.with(|ctx| {
+            ctx.clock.is_custom() || (cfg!(feature = "test-util") && ctx.time_handle.is_some())
+        })
+    {
+        return false;
+    }
+    crate::driver::kernel_timer_available()
+}
+
+/// A timer expiring once at a deadline.
+pub(crate) struct KernelTimer {
+    op: Option<Op<Timeout>>,
+    elapsed: bool,
+    /// The waker of the armed timer, which is woken on reset to arm it again.
+    waker: Option<Waker>,
+}
+
+impl KernelTimer {
+    pub(crate) fn new() -> Self {
+        Self {
+            op: None,
+            elapsed: false,
+            waker: None,
+        }
+    }
+
+    pub(crate) fn is_elapsed(&self) -> bool {
+        self.elapsed
+    }
+
+    /// Cancel the armed timer. It is armed again for the new deadline when polled, so the
+    /// task waiting for it is woken to poll again.
+    pub(crate) fn reset(&mut self) {
+        self.op = None;
+        self.elapsed = false;
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+
+    pub(crate) fn poll_elapsed(
+        &mut self,
+        deadline: Instant,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<()>> {
+        if self.elapsed {
+            return Poll::Ready(Ok(()));
+        }
+        let op = match &mut self.op {
+            Some(op) => op,
+            None => {
+                // The timer is armed relative to the submission, so it never expires early.
+                let duration = deadline.saturating_duration_since(Instant::now());
+                if duration.is_zero() {
+                    self.elapsed = true;
+                    return Poll::Ready(Ok(()));
+                }
+                self.op.insert(Op::timeout(duration)?)
+            }
+        };
+        let completion = match Pin::new(op).poll(cx) {
+            Poll::Ready(completion) => completion,
+            Poll::Pending => {
+                if !self.waker.as_ref().is_some_and(|w| w.will_wake(cx.waker())) {
+                    self.waker = Some(cx.waker().clone());
+                }
+                return Poll::Pending;
+            }
+        };
+        self.op = None;
+        self.waker = None;
+        Timeout::expired(completion.meta.result)?;
+        self.elapsed = true;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl fmt::Debug for KernelTimer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KernelTimer")
+            .field("armed", &self.op.is_some())
+            .field("elapsed", &self.elapsed)
+            .finish()
+    }
+}
+
+/// A multishot timer expiring every period, whose expirations are queued until taken.
+pub(crate) struct KernelInterval {
+    op: Op<Timeout>,
+}
+
+impl KernelInterval {
+    pub(crate) fn new(period: Duration) -> io::Result<Self> {
+        Ok(Self {
+            op: Op::timeout_multishot(period)?,
+        })
+    }
+
+    /// Poll the next expiration. Returns `None` if the timer has terminated, or is not
+    /// supported by the kernel.
+    pub(crate) fn poll_expired(&mut self, cx: &mut Context<'_>) -> Poll<Option<()>> {
+        let meta = ready!(self.op.poll_next_multishot(cx));
+        Poll::Ready(meta.and_then(|meta| Timeout::expired(meta.result).ok()))
+    }
+}
+
+impl fmt::Debug for KernelInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KernelInterval").finish()
+    }
+}
@@ -13,13 +13,18 @@ pub(crate) use self::handle::Handle;
 
 mod wheel;
 
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+mod kernel;
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+pub(crate) use self::kernel::{kernel_timer_enabled, KernelInterval, KernelTimer};
+
 pub(super) mod sleep;
 
 use std::{cell::RefCell, fmt, io, num::NonZeroU64, ptr::NonNull, rc::Rc};
 
 use crate::{
     driver::Driver,
-    time::{error::Error, Clock, Duration, Instant},
+    time::{error::Error, ClockHandle, Duration, Instant},
 };
 
 /// Time implementation that drives [`Sleep`][sleep], [`Interval`][interval],
@@ -93,12 +98,12 @@ pub struct TimeDriver<D: 'static> {
 /// A structure which handles conversion from Instants to u64 timestamps.
 #[derive(Debug, Clone)]
 struct ClockTime {
-    clock: super::clock::Clock,
+    clock: ClockHandle,
     start_time: Instant,
 }
 
 impl ClockTime {
-    pub(self) fn new(clock: Clock) -> Self {
+    pub(self) fn new(clock: ClockHandle) -> Self {
         Self {
             start_time: clock.now(),
             clock,
@@ -160,7 +165,7 @@ where
     /// thread and `time_source` to get the current time and convert to ticks.
     ///
     /// Specifying the source of time is useful when testing.
-    pub(crate) fn new(park: D, clock: Clock) -> TimeDriver<D> {
+    pub(crate) fn new(park: D, clock: ClockHandle) -> TimeDriver<D> {
         let time_source = ClockTime::new(clock);
 
         let inner = Inner::new(time_source.clone());
@@ -180,6 +185,23 @@ where
             next_wake.map(|t| NonZeroU64::new(t).unwrap_or_else(|| NonZeroU64::new(1).unwrap()));
         drop(inner_state);
 
+        // With the clock paused, wait for io without blocking. If nothing is woken, the
+        // runtime is idle, so advance the clock to the next timer.
+        #[cfg(feature = "test-util")]
+        if self.time_source.clock.is_paused() {
+            if let Some(when) = next_wake {
+                self.park.park_timeout(Duration::from_secs(0))?;
+                let now = self.time_source.now();
+                if when > now && !crate::runtime::has_woken_tasks() {
+                    self.time_source
+                        .clock
+                        .advance(self.time_source.tick_to_duration(when - now));
+                }
+                self.handle.process();
+                return Ok(());
+            }
+        }
+
         match next_wake {
             Some(when) => {
                 let now = self.time_source.now();
@@ -216,7 +238,7 @@ where
 
 impl Handle {
     /// Runs timer related logic, and returns the next wakeup time
-    pub(self) fn process(&self) {
+    pub(crate) fn process(&self) {
         let now = self.time_source().now();
 
         self.process_at_time(now)
@@ -316,7 +338,10 @@ where
     }
 
     fn submit(&self) -> io::Result<()> {
-        self.park.submit()
+        self.park.submit()?;
+        // The runtime does not park while there are ready tasks, fire the expired timers here.
+        self.handle.process();
+        Ok(())
     }
 
     fn park(&self) -> io::Result<()> {
@@ -334,6 +359,16 @@ where
     fn unpark(&self) -> Self::Unpark {
         self.park.unpark()
     }
+
+    #[cfg(unix)]
+    fn completion_eventfd(&self) -> io::Result<std::os::unix::io::RawFd> {
+        self.park.completion_eventfd()
+    }
+
+    #[cfg(unix)]
+    fn pollable_fd(&self) -> io::Result<std::os::unix::io::RawFd> {
+        self.park.pollable_fd()
+    }
 }
 
 impl<D> Drop for TimeDriver<D>
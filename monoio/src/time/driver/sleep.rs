@@ -6,6 +6,8 @@ use std::{
 
 use pin_project_lite::pin_project;
 
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+use crate::time::driver::{kernel_timer_enabled, KernelTimer};
 use crate::time::{
     driver::{Handle, TimerEntry},
     error::Error,
@@ -16,7 +18,9 @@ use crate::time::{
 ///
 /// No work is performed while awaiting on the sleep future to complete. `Sleep`
 /// operates at millisecond granularity and should not be used for tasks that
-/// require high-resolution timers.
+/// require high-resolution timers. With the uring driver(not set up with
+/// IOPOLL), the timer is armed in the kernel with `IORING_OP_TIMEOUT` at
+/// nanosecond granularity instead, and the time driver is not required.
 ///
 /// To run something regularly on a schedule, see [`interval`].
 ///
@@ -56,7 +60,9 @@ pub fn sleep_until(deadline: Instant) -> Sleep {
 ///
 /// No work is performed while awaiting on the sleep future to complete. `Sleep`
 /// operates at millisecond granularity and should not be used for tasks that
-/// require high-resolution timers.
+/// require high-resolution timers. With the uring driver(not set up with
+/// IOPOLL), the timer is armed in the kernel with `IORING_OP_TIMEOUT` at
+/// nanosecond granularity instead, and the time driver is not required.
 ///
 /// To run something regularly on a schedule, see [`interval`].
 ///
@@ -192,16 +198,56 @@ pin_project! {
 
         // The link between the `Sleep` instance and the timer that drives it.
         #[pin]
-        entry: TimerEntry,
+        timer: Timer,
+    }
+}
+
+/// The timing wheel of the time driver, or the kernel with the uring driver.
+// It is pinned in place, so the size difference does not cost moves.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug)]
+enum Timer {
+    Wheel(TimerEntry),
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    Kernel(KernelTimer),
+}
+
+enum TimerProj<'a> {
+    Wheel(Pin<&'a mut TimerEntry>),
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    Kernel(&'a mut KernelTimer),
+}
+
+impl Timer {
+    fn project(self: Pin<&mut Self>) -> TimerProj<'_> {
+        // Safety: the entry is never moved out, and `KernelTimer` is `Unpin`.
+        unsafe {
+            match self.get_unchecked_mut() {
+                Timer::Wheel(entry) => TimerProj::Wheel(Pin::new_unchecked(entry)),
+                #[cfg(all(target_os = "linux", feature = "iouring"))]
+                Timer::Kernel(timer) => TimerProj::Kernel(timer),
+            }
+        }
     }
 }
 
 impl Sleep {
     pub(crate) fn new_timeout(deadline: Instant) -> Sleep {
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        if kernel_timer_enabled() {
+            return Sleep {
+                deadline,
+                timer: Timer::Kernel(KernelTimer::new()),
+            };
+        }
+
         let handle = Handle::current();
         let entry = TimerEntry::new(&handle, deadline);
 
-        Sleep { deadline, entry }
+        Sleep {
+            deadline,
+            timer: Timer::Wheel(entry),
+        }
     }
 
     pub(crate) fn far_future() -> Sleep {
@@ -217,7 +263,11 @@ impl Sleep {
     ///
     /// A `Sleep` instance is elapsed when the requested duration has elapsed.
     pub fn is_elapsed(&self) -> bool {
-        self.entry.is_elapsed()
+        match &self.timer {
+            Timer::Wheel(entry) => entry.is_elapsed(),
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            Timer::Kernel(timer) => timer.is_elapsed(),
+        }
     }
 
     /// Resets the `Sleep` instance to a new deadline.
@@ -253,13 +303,25 @@ impl Sleep {
     /// [`Pin::as_mut`]: fn@std::pin::Pin::as_mut
     pub fn reset(self: Pin<&mut Self>, deadline: Instant) {
         let me = self.project();
-        me.entry.reset(deadline);
+        match me.timer.project() {
+            TimerProj::Wheel(entry) => entry.reset(deadline),
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            TimerProj::Kernel(timer) => timer.reset(),
+        }
         *me.deadline = deadline;
     }
 
     fn poll_elapsed(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Result<(), Error>> {
         let me = self.project();
-        me.entry.poll_elapsed(cx)
+        match me.timer.project() {
+            TimerProj::Wheel(entry) => entry.poll_elapsed(cx),
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            TimerProj::Kernel(timer) => match ready!(timer.poll_elapsed(*me.deadline, cx)) {
+                Ok(()) => Poll::Ready(Ok(())),
+                // Failing to submit the timer is as fatal as the errors of the wheel.
+                Err(e) => panic!("timer error: {e}"),
+            },
+        }
     }
 }
 
@@ -47,6 +47,41 @@ impl Instant {
         variant::now()
     }
 
+    /// Returns a recent instant cached by the current runtime, which is cheaper
+    /// than [`Instant::now`] when it is read many times, e.g. per request of a
+    /// busy server.
+    ///
+    /// The instant is read once after each time the driver is parked, and
+    /// returned until the next time, so it may lag behind [`Instant::now`] by
+    /// the time spent on running the tasks since then. It never goes
+    /// backwards. With [`RuntimeBuilder::with_coarse_clock`], it is read from
+    /// `CLOCK_MONOTONIC_COARSE` on Linux, which is even cheaper but only
+    /// updated every few milliseconds.
+    ///
+    /// Outside a runtime it is the same as [`Instant::now`].
+    ///
+    /// [`RuntimeBuilder::with_coarse_clock`]: crate::RuntimeBuilder::with_coarse_clock
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use monoio::time::Instant;
+    ///
+    /// #[monoio::main]
+    /// async fn main() {
+    ///     let begin = Instant::recent();
+    ///     // handle a request
+    ///     let cost = Instant::recent() - begin;
+    /// }
+    /// ```
+    pub fn recent() -> Instant {
+        if crate::runtime::CURRENT.is_set() {
+            crate::runtime::CURRENT.with(|ctx| ctx.recent.get())
+        } else {
+            Instant::now()
+        }
+    }
+
     /// Create a `monoio::time::Instant` from a `std::time::Instant`.
     pub fn from_std(std: std::time::Instant) -> Instant {
         Instant { std }
@@ -214,6 +249,6 @@ mod variant {
     use super::Instant;
 
     pub(super) fn now() -> Instant {
-        Instant::from_std(std::time::Instant::now())
+        crate::time::clock::now()
     }
 }
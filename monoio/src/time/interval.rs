@@ -4,11 +4,16 @@ use std::{
     task::{Context, Poll},
 };
 
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+use super::driver::{kernel_timer_enabled, KernelInterval};
 use crate::{
     macros::support::poll_fn,
     time::{sleep_until, Duration, Instant, Sleep},
 };
 
+/// A tick later than this after its scheduled time is missed.
+const MISSED_TICK_SLACK: Duration = Duration::from_millis(5);
+
 /// Creates new [`Interval`] that yields with interval of `period`.
 ///
 /// The first tick completes immediately. The default [`MissedTickBehavior`] is
@@ -117,6 +122,10 @@ pub fn interval_at(start: Instant, period: Duration) -> Interval {
         delay: Box::pin(sleep_until(start)),
         period,
         missed_tick_behavior: Default::default(),
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        kernel: None,
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        multishot_unsupported: false,
     }
 }
 
@@ -365,6 +374,15 @@ pub struct Interval {
 
     /// The strategy `Interval` should use when a tick is missed.
     missed_tick_behavior: MissedTickBehavior,
+
+    /// With the uring driver and `Burst`, the multishot kernel timer yielding the ticks
+    /// after `delay`, whose deadline then tracks the next tick without being armed.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    kernel: Option<KernelInterval>,
+
+    /// If the kernel does not support multishot timers(before 6.4).
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    multishot_unsupported: bool,
 }
 
 impl Interval {
@@ -405,6 +423,17 @@ impl Interval {
     /// [`Context`] passed to the most recent call is scheduled to receive a
     /// wakeup.
     pub fn poll_tick(&mut self, cx: &mut Context<'_>) -> Poll<Instant> {
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        if let Some(kernel) = &mut self.kernel {
+            match ready!(kernel.poll_expired(cx)) {
+                Some(()) => return Poll::Ready(self.tick_multishot()),
+                None => {
+                    self.kernel = None;
+                    self.multishot_unsupported = true;
+                }
+            }
+        }
+
         // Wait for the delay to be done
         ready!(Pin::new(&mut self.delay).poll(cx));
 
@@ -420,10 +449,12 @@ impl Interval {
         // However, if a tick took excessively long and we are now behind,
         // schedule the next tick according to how the user specified with
         // `MissedTickBehavior`
-        let next = if now > timeout + Duration::from_millis(5) {
+        let next = if now > timeout + MISSED_TICK_SLACK {
             self.missed_tick_behavior
                 .next_timeout(timeout, now, self.period)
         } else {
+            #[cfg(all(target_os = "linux", feature = "iouring"))]
+            self.arm_multishot();
             timeout + self.period
         };
 
@@ -433,6 +464,33 @@ impl Interval {
         Poll::Ready(timeout)
     }
 
+    /// Arm the multishot kernel timer to yield the following ticks, if it is on time.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fn arm_multishot(&mut self) {
+        if self.multishot_unsupported
+            || self.missed_tick_behavior != MissedTickBehavior::Burst
+            || !kernel_timer_enabled()
+        {
+            return;
+        }
+        // Fall back to the delay if it fails to submit.
+        self.kernel = KernelInterval::new(self.period).ok();
+    }
+
+    /// Take a tick of the multishot kernel timer.
+    #[cfg(all(target_os = "linux", feature = "iouring"))]
+    fn tick_multishot(&mut self) -> Instant {
+        let timeout = self.delay.deadline();
+        // Each expiration is armed again by the kernel relative to the last one, so the
+        // ticks drift late. Once a tick is missed or drifted, the delay takes over to follow
+        // the schedule, and arms the timer again when it is on time.
+        if Instant::now() > timeout + MISSED_TICK_SLACK {
+            self.kernel = None;
+        }
+        self.delay.as_mut().reset(timeout + self.period);
+        timeout
+    }
+
     /// Returns the [`MissedTickBehavior`] strategy currently being used.
     pub fn missed_tick_behavior(&self) -> MissedTickBehavior {
         self.missed_tick_behavior
@@ -441,6 +499,10 @@ impl Interval {
     /// Sets the [`MissedTickBehavior`] strategy that should be used.
     pub fn set_missed_tick_behavior(&mut self, behavior: MissedTickBehavior) {
         self.missed_tick_behavior = behavior;
+        #[cfg(all(target_os = "linux", feature = "iouring"))]
+        if behavior != MissedTickBehavior::Burst {
+            self.kernel = None;
+        }
     }
 
     /// Returns the period of the interval.
@@ -10,7 +10,11 @@
 //!
 //! * [`Timeout`]: Wraps a future or stream, setting an upper bound to the amount of time it is
 //!   allowed to execute. If the future or stream does not complete in time, then it is canceled and
-//!   an error is returned.
+//!   an error is returned. [`timeout_cancelable`] cancels an IO operation in the driver instead,
+//!   and waits for it to return the buffers it owns.
+//!
+//! * [`DelayQueue`] is a queue yielding each item inserted when its deadline has passed, with the
+//!   deadlines of the items reset or the items removed by key.
 //!
 //! These types are sufficient for handling a large number of scenarios
 //! involving time.
@@ -87,7 +91,10 @@
 // Copyright (c) 2021 Tokio Contributors, licensed under the MIT license.
 
 mod clock;
-pub(crate) use self::clock::Clock;
+pub use self::clock::Clock;
+pub(crate) use self::clock::ClockHandle;
+#[cfg(feature = "test-util")]
+pub use self::clock::{advance, pause, resume};
 
 pub(crate) mod driver;
 
@@ -99,9 +106,15 @@ pub use driver::{
 
 pub mod error;
 
+mod delay_queue;
+pub use delay_queue::{DelayQueue, Expired, Key};
+
 mod instant;
 pub use self::instant::Instant;
 
+mod recent;
+pub(crate) use self::recent::Recent;
+
 mod interval;
 pub use interval::{interval, interval_at, Interval, MissedTickBehavior};
 
@@ -111,4 +124,7 @@ mod timeout;
 pub use std::time::Duration;
 
 #[doc(inline)]
-pub use timeout::{timeout, timeout_at, Timeout};
+pub use timeout::{
+    timeout, timeout_at, timeout_cancelable, timeout_cancelable_at, CancelableTimeout, Timeout,
+    TimeoutExt,
+};
@@ -0,0 +1,93 @@
+//! The cached clock behind [`Instant::recent`].
+
+use std::cell::Cell;
+
+use crate::time::Instant;
+
+/// The timestamp of a runtime cached until the next driver tick.
+#[derive(Debug, Default)]
+pub(crate) struct Recent {
+    /// The cached timestamp, or `None` if it is read for the first time since the tick.
+    cached: Cell<Option<Instant>>,
+
+    /// The last timestamp read, which the next one is never earlier than.
+    last: Cell<Option<Instant>>,
+
+    /// Read `CLOCK_MONOTONIC_COARSE` instead of `Instant::now()`. It is ignored with
+    /// test-util, so `Instant::recent` follows the paused clock.
+    #[cfg(all(target_os = "linux", not(feature = "test-util")))]
+    coarse: Option<Coarse>,
+}
+
+/// `CLOCK_MONOTONIC_COARSE` mapped to `Instant` by the offset measured when it is created.
+#[cfg(all(target_os = "linux", not(feature = "test-util")))]
+#[derive(Debug)]
+struct Coarse {
+    base: Instant,
+    base_ts: std::time::Duration,
+}
+
+#[cfg(all(target_os = "linux", not(feature = "test-util")))]
+impl Coarse {
+    fn new() -> Coarse {
+        Coarse {
+            base: Instant::now(),
+            base_ts: coarse_timestamp(),
+        }
+    }
+
+    fn now(&self) -> Instant {
+        self.base + coarse_timestamp().saturating_sub(self.base_ts)
+    }
+}
+
+#[cfg(all(target_os = "linux", not(feature = "test-util")))]
+fn coarse_timestamp() -> std::time::Duration {
+    let mut ts = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // CLOCK_MONOTONIC_COARSE is served by the vDSO and never fails.
+    unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC_COARSE, &mut ts) };
+    std::time::Duration::new(ts.tv_sec as u64, ts.tv_nsec as u32)
+}
+
+impl Recent {
+    #[allow(unused_variables)]
+    pub(crate) fn new(coarse: bool) -> Recent {
+        Recent {
+            cached: Cell::new(None),
+            last: Cell::new(None),
+            #[cfg(all(target_os = "linux", not(feature = "test-util")))]
+            coarse: coarse.then(Coarse::new),
+        }
+    }
+
+    /// Returns the cached timestamp, which is read once per tick.
+    pub(crate) fn get(&self) -> Instant {
+        if let Some(now) = self.cached.get() {
+            return now;
+        }
+        let mut now = self.read();
+        if let Some(last) = self.last.get() {
+            now = now.max(last);
+        }
+        self.cached.set(Some(now));
+        self.last.set(Some(now));
+        now
+    }
+
+    /// Invalidate the cached timestamp on a driver tick.
+    #[inline]
+    pub(crate) fn tick(&self) {
+        self.cached.set(None);
+    }
+
+    fn read(&self) -> Instant {
+        #[cfg(all(target_os = "linux", not(feature = "test-util")))]
+        if let Some(coarse) = &self.coarse {
+            return coarse.now();
+        }
+        Instant::now()
+    }
+}
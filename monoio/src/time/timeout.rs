@@ -12,7 +12,10 @@ use std::{
 
 use pin_project_lite::pin_project;
 
-use crate::time::{error::Elapsed, sleep_until, Duration, Instant, Sleep};
+use crate::{
+    io::{CancelHandle, Canceller},
+    time::{error::Elapsed, sleep_until, Duration, Instant, Sleep},
+};
 
 /// Require a `Future` to complete before the specified duration has elapsed.
 ///
@@ -63,6 +66,85 @@ where
     }
 }
 
+/// Require an IO operation to complete before the specified duration has
+/// elapsed, and cancel it in the driver if it does not.
+///
+/// The operation is created by `f` with a [`CancelHandle`], e.g. by
+/// [`cancelable_read`](crate::io::CancelableAsyncReadRent::cancelable_read).
+/// When the duration has elapsed, the operation is canceled(`ASYNC_CANCEL` with
+/// the uring driver, and the canceled readiness with the legacy driver) and
+/// polled until it completes, so the buffer it owns is returned instead of
+/// being leaked with the dropped operation.
+///
+/// The output is the output of the operation, which is an error of
+/// `ECANCELED` if it is canceled, or the result if it completes before the
+/// cancellation takes effect.
+///
+/// # Examples
+///
+/// ```no_run
+/// use monoio::{
+///     io::CancelableAsyncReadRent,
+///     net::TcpStream,
+///     time::{timeout_cancelable, Duration},
+/// };
+///
+/// #[monoio::main(timer_enabled = true)]
+/// async fn main() {
+///     let mut stream = TcpStream::connect("127.0.0.1:8080").await.unwrap();
+///     let (res, buf) = timeout_cancelable(Duration::from_secs(1), |c| {
+///         stream.cancelable_read(Vec::with_capacity(1024), c)
+///     })
+///     .await;
+///     if res.is_err() {
+///         println!("read failed or timed out, buf is back: {}", buf.capacity());
+///     }
+/// }
+/// ```
+pub fn timeout_cancelable<F, Fut>(duration: Duration, f: F) -> CancelableTimeout<Fut>
+where
+    F: FnOnce(CancelHandle) -> Fut,
+    Fut: Future,
+{
+    let deadline = Instant::now().checked_add(duration);
+    let delay = match deadline {
+        Some(deadline) => Sleep::new_timeout(deadline),
+        None => Sleep::far_future(),
+    };
+    CancelableTimeout::new(f, delay)
+}
+
+/// Require an IO operation to complete before the specified instant in time,
+/// and cancel it in the driver if it does not.
+///
+/// See [`timeout_cancelable`] for more details.
+pub fn timeout_cancelable_at<F, Fut>(deadline: Instant, f: F) -> CancelableTimeout<Fut>
+where
+    F: FnOnce(CancelHandle) -> Fut,
+    Fut: Future,
+{
+    CancelableTimeout::new(f, sleep_until(deadline))
+}
+
+/// An extension trait for futures to require them to complete in time.
+pub trait TimeoutExt: Future + Sized {
+    /// Require the future to complete before the specified duration has elapsed.
+    ///
+    /// See [`timeout`] for more details.
+    fn timeout(self, duration: Duration) -> Timeout<Self> {
+        timeout(duration, self)
+    }
+
+    /// Require the future to complete before the specified instant in time.
+    ///
+    /// See [`timeout_at`] for more details.
+    fn timeout_at(self, deadline: Instant) -> Timeout<Self> {
+        timeout_at(deadline, self)
+    }
+}
+
+impl<T> TimeoutExt for T where T: Future {}
+
 pin_project! {
     /// Future returned by [`timeout`](timeout) and [`timeout_at`](timeout_at).
     #[must_use = "futures do nothing unless you `.await` or poll them"]
@@ -117,3 +199,69 @@ where
         }
     }
 }
+
+pin_project! {
+    /// Future returned by [`timeout_cancelable`](timeout_cancelable) and
+    /// [`timeout_cancelable_at`](timeout_cancelable_at).
+    #[must_use = "futures do nothing unless you `.await` or poll them"]
+    pub struct CancelableTimeout<T> {
+        #[pin]
+        value: T,
+        #[pin]
+        delay: Sleep,
+        // Taken when the operation is canceled.
+        canceller: Option<Canceller>,
+    }
+}
+
+impl<T> CancelableTimeout<T> {
+    fn new<F>(f: F, delay: Sleep) -> CancelableTimeout<T>
+    where
+        F: FnOnce(CancelHandle) -> T,
+    {
+        let canceller = Canceller::new();
+        CancelableTimeout {
+            value: f(canceller.handle()),
+            delay,
+            canceller: Some(canceller),
+        }
+    }
+
+    /// Returns `true` if the operation has been canceled for the timeout.
+    pub fn is_elapsed(&self) -> bool {
+        self.canceller.is_none()
+    }
+}
+
+impl<T> std::fmt::Debug for CancelableTimeout<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CancelableTimeout")
+            .field("delay", &self.delay)
+            .field("elapsed", &self.is_elapsed())
+            .finish()
+    }
+}
+
+impl<T> Future for CancelableTimeout<T>
+where
+    T: Future,
+{
+    type Output = T::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        let mut me = self.project();
+
+        if let Poll::Ready(v) = me.value.as_mut().poll(cx) {
+            return Poll::Ready(v);
+        }
+
+        // Cancel the operation once the timer fires, and wait for it to complete.
+        if me.canceller.is_some() && me.delay.poll(cx).is_ready() {
+            if let Some(canceller) = me.canceller.take() {
+                canceller.cancel();
+            }
+            return me.value.poll(cx);
+        }
+        Poll::Pending
+    }
+}
@@ -0,0 +1,208 @@
+//! A thread-local token to signal cancellation to tasks and io operations.
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    rc::{Rc, Weak},
+    task::{Context, Poll, Waker},
+};
+
+use crate::io::{CancelHandle, Canceller};
+
+/// A token to signal cancellation, which can be awaited by tasks and passed to io operations.
+///
+/// Cloned tokens share the same state. A token created by
+/// [`child_token`](CancellationToken::child_token) is cancelled when its parent is cancelled,
+/// but cancelling the child does not affect the parent, so cancellation flows down a tree of
+/// tokens, e.g. from a server to its connections.
+///
+/// It is backed by `Rc`, so it is cheap to clone but can only be used inside the thread it is
+/// created on.
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    node: Rc<Node>,
+}
+
+#[derive(Default)]
+struct Node {
+    cancelled: Cell<bool>,
+    parent: Option<Weak<Node>>,
+    id: usize,
+    next_id: Cell<usize>,
+    children: RefCell<HashMap<usize, Weak<Node>>>,
+    waiters: RefCell<HashMap<usize, Waker>>,
+    canceller: Canceller,
+}
+
+impl Node {
+    fn next_id(&self) -> usize {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        id
+    }
+
+    fn cancel(&self) {
+        if self.cancelled.replace(true) {
+            return;
+        }
+        self.canceller.cancel_in_place();
+        let waiters = std::mem::take(&mut *self.waiters.borrow_mut());
+        for (_, waker) in waiters {
+            waker.wake();
+        }
+        // Collect first, the children may be dropped and unregister themselves meanwhile.
+        let children: Vec<Rc<Node>> = std::mem::take(&mut *self.children.borrow_mut())
+            .into_values()
+            .filter_map(|child| child.upgrade())
+            .collect();
+        for child in children {
+            child.cancel();
+        }
+    }
+}
+
+impl Drop for Node {
+    fn drop(&mut self) {
+        if let Some(parent) = self.parent.as_ref().and_then(Weak::upgrade) {
+            parent.children.borrow_mut().remove(&self.id);
+        }
+    }
+}
+
+impl CancellationToken {
+    /// Create a new token which is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a child token, which is cancelled when this token is cancelled.
+    ///
+    /// If this token has been cancelled, the child is cancelled already.
+    pub fn child_token(&self) -> CancellationToken {
+        let id = self.node.next_id();
+        let child = Rc::new(Node {
+            parent: Some(Rc::downgrade(&self.node)),
+            id,
+            cancelled: Cell::new(false),
+            next_id: Cell::new(0),
+            children: RefCell::new(HashMap::new()),
+            waiters: RefCell::new(HashMap::new()),
+            canceller: Canceller::new(),
+        });
+        if self.node.cancelled.get() {
+            child.cancel();
+        } else {
+            self.node
+                .children
+                .borrow_mut()
+                .insert(id, Rc::downgrade(&child));
+        }
+        CancellationToken { node: child }
+    }
+
+    /// Cancel the token and all its children. The waiting tasks are woken up and the associated
+    /// io operations are canceled. It does nothing if the token has been cancelled.
+    pub fn cancel(&self) {
+        self.node.cancel();
+    }
+
+    /// Checks if the token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.node.cancelled.get()
+    }
+
+    /// Returns a future which completes when the token is cancelled.
+    pub fn cancelled(&self) -> WaitForCancellation<'_> {
+        WaitForCancellation {
+            token: self,
+            key: None,
+        }
+    }
+
+    /// Returns a [`CancelHandle`] to pass to the cancelable io operations, e.g.
+    /// [`cancelable_read`](crate::io::CancelableAsyncReadRent::cancelable_read). The operations
+    /// are canceled in the driver when the token is cancelled, and fail with
+    /// `ECANCELED` if they are started after that.
+    pub fn cancel_handle(&self) -> CancelHandle {
+        self.node.canceller.handle()
+    }
+
+    /// Returns a guard which cancels the token when dropped, unless it is
+    /// [`disarm`](DropGuard::disarm)ed.
+    pub fn drop_guard(self) -> DropGuard {
+        DropGuard { token: Some(self) }
+    }
+}
+
+impl std::fmt::Debug for CancellationToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CancellationToken")
+            .field("is_cancelled", &self.is_cancelled())
+            .finish()
+    }
+}
+
+/// Future returned by [`CancellationToken::cancelled`].
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct WaitForCancellation<'a> {
+    token: &'a CancellationToken,
+    key: Option<usize>,
+}
+
+impl Future for WaitForCancellation<'_> {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let node = &self.token.node;
+        if node.cancelled.get() {
+            return Poll::Ready(());
+        }
+        let key = match self.key {
+            Some(key) => key,
+            None => node.next_id(),
+        };
+        node.waiters.borrow_mut().insert(key, cx.waker().clone());
+        self.key = Some(key);
+        Poll::Pending
+    }
+}
+
+impl Drop for WaitForCancellation<'_> {
+    fn drop(&mut self) {
+        if let Some(key) = self.key {
+            self.token.node.waiters.borrow_mut().remove(&key);
+        }
+    }
+}
+
+impl std::fmt::Debug for WaitForCancellation<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WaitForCancellation")
+            .field("token", self.token)
+            .finish()
+    }
+}
+
+/// A guard which cancels the token when dropped, created by
+/// [`CancellationToken::drop_guard`].
+#[derive(Debug)]
+pub struct DropGuard {
+    token: Option<CancellationToken>,
+}
+
+impl DropGuard {
+    /// Returns the token without cancelling it.
+    pub fn disarm(mut self) -> CancellationToken {
+        self.token.take().expect("token taken")
+    }
+}
+
+impl Drop for DropGuard {
+    fn drop(&mut self) {
+        if let Some(token) = self.token.take() {
+            token.cancel();
+        }
+    }
+}
@@ -1,6 +1,8 @@
 //! Common utils
 
-pub(crate) mod box_into_inner;
+mod cancellation_token;
+pub use cancellation_token::{CancellationToken, DropGuard, WaitForCancellation};
+
 pub(crate) mod linked_list;
 #[allow(dead_code)]
 pub(crate) mod slab;
@@ -23,3 +25,20 @@ pub use self::ctrlc::{CtrlC, Error as CtrlCError};
 mod bind_to_cpu_set;
 #[cfg(feature = "utils")]
 pub use bind_to_cpu_set::{bind_to_cpu_set, BindError};
+#[cfg(feature = "utils")]
+pub(crate) mod numa;
+#[cfg(feature = "utils")]
+pub use numa::{bind_to_numa_node, numa_node_cpus};
+
+#[cfg(all(
+    feature = "utils",
+    feature = "sync",
+    any(all(target_os = "linux", feature = "iouring"), feature = "legacy")
+))]
+mod runtime_pool;
+#[cfg(all(
+    feature = "utils",
+    feature = "sync",
+    any(all(target_os = "linux", feature = "iouring"), feature = "legacy")
+))]
+pub use runtime_pool::{RunningPool, RuntimePool};
@@ -0,0 +1,145 @@
+//! NUMA node placement of the runtime thread and its memory.
+
+use std::io;
+
+#[cfg(target_os = "linux")]
+const MAX_NODES: usize = 1024;
+#[cfg(target_os = "linux")]
+const MPOL_PREFERRED: libc::c_int = 1;
+#[cfg(target_os = "linux")]
+const MPOL_MF_MOVE: libc::c_uint = 1 << 1;
+
+#[cfg(target_os = "linux")]
+thread_local! {
+    // The node the thread is bound to
+    static CURRENT_NODE: std::cell::Cell<Option<usize>> = const { std::cell::Cell::new(None) };
+}
+
+/// Get the cpus of the NUMA node, which is empty for a node without cpus.
+#[cfg(target_os = "linux")]
+pub fn numa_node_cpus(node: usize) -> io::Result<Vec<usize>> {
+    let list = std::fs::read_to_string(format!("/sys/devices/system/node/node{node}/cpulist"))?;
+    parse_cpu_list(list.trim())
+}
+
+/// Get the cpus of the NUMA node(but not works for non-linux).
+#[cfg(not(target_os = "linux"))]
+pub fn numa_node_cpus(_: usize) -> io::Result<Vec<usize>> {
+    Err(io::ErrorKind::Unsupported.into())
+}
+
+/// Bind current thread to the cpus of the NUMA node, and prefer the memory of the node for the
+/// allocations of the thread, including the io_uring rings created later. The buffers of
+/// [`crate::buf`] allocated on the thread are bound to the node with `mbind`, since the pages
+/// may be faulted in by the kernel workers.
+#[cfg(target_os = "linux")]
+pub fn bind_to_numa_node(node: usize) -> io::Result<()> {
+    let cpus = numa_node_cpus(node)?;
+    // The memory only node is used for the memory.
+    if !cpus.is_empty() {
+        super::bind_to_cpu_set(cpus)?;
+    }
+    let mask = node_mask(node)?;
+    crate::syscall!(syscall@RAW(
+        libc::SYS_set_mempolicy,
+        MPOL_PREFERRED,
+        mask.as_ptr(),
+        MAX_NODES + 1
+    ))?;
+    CURRENT_NODE.with(|current| current.set(Some(node)));
+    Ok(())
+}
+
+/// Bind current thread to the NUMA node(but not works for non-linux).
+#[cfg(not(target_os = "linux"))]
+pub fn bind_to_numa_node(_: usize) -> io::Result<()> {
+    Err(io::ErrorKind::Unsupported.into())
+}
+
+/// Bind the pages inside the memory to the node of current thread, if it is bound with
+/// [`bind_to_numa_node`]. It is best effort, the memory is still usable if it fails.
+#[cfg(target_os = "linux")]
+pub(crate) fn bind_memory(ptr: *mut u8, len: usize) {
+    let Some(node) = CURRENT_NODE.with(|current| current.get()) else {
+        return;
+    };
+    let Ok(mask) = node_mask(node) else {
+        return;
+    };
+    // Only the whole pages can be bound.
+    let page = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+    let begin = (ptr as usize).next_multiple_of(page);
+    let end = (ptr as usize + len) / page * page;
+    if begin >= end {
+        return;
+    }
+    let _ = crate::syscall!(syscall@RAW(
+        libc::SYS_mbind,
+        begin,
+        end - begin,
+        MPOL_PREFERRED,
+        mask.as_ptr(),
+        MAX_NODES + 1,
+        MPOL_MF_MOVE
+    ));
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn bind_memory(_: *mut u8, _: usize) {}
+
+#[cfg(target_os = "linux")]
+fn node_mask(node: usize) -> io::Result<[libc::c_ulong; MAX_NODES / libc::c_ulong::BITS as usize]> {
+    const BITS: usize = libc::c_ulong::BITS as usize;
+    if node >= MAX_NODES {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "invalid numa node",
+        ));
+    }
+    let mut mask = [0; MAX_NODES / BITS];
+    mask[node / BITS] |= 1 << (node % BITS);
+    Ok(mask)
+}
+
+/// Parse the cpu list like `0-3,8,10-11`.
+#[cfg(target_os = "linux")]
+fn parse_cpu_list(list: &str) -> io::Result<Vec<usize>> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "invalid cpu list");
+    let mut cpus = Vec::new();
+    for range in list.split(',').filter(|range| !range.is_empty()) {
+        let (begin, end) = range.split_once('-').unwrap_or((range, range));
+        let begin: usize = begin.parse().map_err(|_| invalid())?;
+        let end: usize = end.parse().map_err(|_| invalid())?;
+        cpus.extend(begin..=end);
+    }
+    Ok(cpus)
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cpu_list() {
+        assert_eq!(parse_cpu_list("").unwrap(), Vec::<usize>::new());
+        assert_eq!(parse_cpu_list("0-2,5,7-8").unwrap(), vec![0, 1, 2, 5, 7, 8]);
+        assert!(parse_cpu_list("0-a").is_err());
+    }
+
+    #[test]
+    fn bind_node() {
+        if numa_node_cpus(0).is_err() {
+            // No NUMA support.
+            return;
+        }
+        let res = std::thread::spawn(|| {
+            bind_to_numa_node(0)?;
+            let mem = crate::buf::BufPool::new(4, 4096)?;
+            Ok::<_, io::Error>(mem.buf_cnt())
+        })
+        .join()
+        .unwrap();
+        assert_eq!(res.unwrap(), 4);
+        assert!(numa_node_cpus(MAX_NODES).is_err());
+    }
+}
@@ -0,0 +1,201 @@
+//! Thread per core runtimes.
+
+use std::{
+    future::Future,
+    io,
+    pin::Pin,
+    sync::Arc,
+    task::Poll,
+    thread::{self, JoinHandle},
+};
+
+use crate::runtime::Handle;
+
+/// Builds a runtime on each of the threads, optionally pinned to the given cpus or NUMA nodes,
+/// which runs a future created for its core.
+///
+/// The runtimes use [`FusionDriver`](crate::FusionDriver) with the timer enabled.
+///
+/// ```
+/// let pool = monoio::utils::RuntimePool::new(2)
+///     .bind_cpus([0])
+///     .spawn_per_core(|core_id| async move { core_id * 2 })
+///     .unwrap();
+/// assert_eq!(pool.handles().len(), 2);
+/// assert_eq!(pool.join(), vec![0, 2]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RuntimePool {
+    cores: usize,
+    cpus: Vec<usize>,
+    numa_nodes: Vec<usize>,
+    entries: Option<u32>,
+}
+
+impl RuntimePool {
+    /// Create a pool of `cores` runtimes.
+    pub fn new(cores: usize) -> Self {
+        Self {
+            cores,
+            cpus: Vec::new(),
+            numa_nodes: Vec::new(),
+            entries: None,
+        }
+    }
+
+    /// Pin the thread of core `i` to `cpus[i % cpus.len()]`. The threads are not pinned if the
+    /// list is empty, which is the default.
+    #[must_use]
+    pub fn bind_cpus(mut self, cpus: impl IntoIterator<Item = usize>) -> Self {
+        self.cpus = cpus.into_iter().collect();
+        self
+    }
+
+    /// Place the thread and the memory of core `i` on the NUMA node `nodes[i % nodes.len()]`,
+    /// see [`bind_to_numa_node`](super::bind_to_numa_node). The thread is pinned to the cpus of
+    /// the node unless the cpus are given by [`bind_cpus`](Self::bind_cpus).
+    #[must_use]
+    pub fn bind_numa_nodes(mut self, nodes: impl IntoIterator<Item = usize>) -> Self {
+        self.numa_nodes = nodes.into_iter().collect();
+        self
+    }
+
+    /// Set io_uring entries of the runtimes.
+    #[must_use]
+    pub fn with_entries(mut self, entries: u32) -> Self {
+        self.entries = Some(entries);
+        self
+    }
+
+    /// Start the threads, and run the future created by `f` with the core id, i.e. the index of
+    /// the runtime in `0..cores`, on each of them.
+    ///
+    /// Returns the error if any of the threads fails to be pinned or to build the runtime, the
+    /// started threads are stopped then.
+    pub fn spawn_per_core<F, Fut>(self, f: F) -> io::Result<RunningPool<Fut::Output>>
+    where
+        F: Fn(usize) -> Fut + Send + Sync + 'static,
+        Fut: Future + 'static,
+        Fut::Output: Send + 'static,
+    {
+        let f = Arc::new(f);
+        let mut pool = RunningPool {
+            handles: Vec::with_capacity(self.cores),
+            stops: Vec::with_capacity(self.cores),
+            threads: Vec::with_capacity(self.cores),
+        };
+        for core_id in 0..self.cores {
+            let cpu = (!self.cpus.is_empty()).then(|| self.cpus[core_id % self.cpus.len()]);
+            let node = (!self.numa_nodes.is_empty())
+                .then(|| self.numa_nodes[core_id % self.numa_nodes.len()]);
+            let entries = self.entries;
+            let f = f.clone();
+            let (handle_tx, handle_rx) = flume::bounded(1);
+            let (stop_tx, stop_rx) = flume::bounded::<()>(0);
+            let thread = thread::Builder::new()
+                .name(format!("monoio-core-{core_id}"))
+                .spawn(move || {
+                    let build = || {
+                        if let Some(node) = node {
+                            super::bind_to_numa_node(node)?;
+                        }
+                        if let Some(cpu) = cpu {
+                            super::bind_to_cpu_set(Some(cpu))?;
+                        }
+                        let mut builder = crate::RuntimeBuilder::<crate::FusionDriver>::new();
+                        if let Some(entries) = entries {
+                            builder = builder.with_entries(entries);
+                        }
+                        builder.enable_timer().build()
+                    };
+                    let mut rt = match build() {
+                        Ok(rt) => rt,
+                        Err(e) => {
+                            let _ = handle_tx.send(Err(e));
+                            return None;
+                        }
+                    };
+                    let _ = handle_tx.send(Ok(rt.handle()));
+                    rt.block_on(async move {
+                        let mut fut = std::pin::pin!(f(core_id));
+                        let mut stop = stop_rx.recv_async();
+                        std::future::poll_fn(|cx| {
+                            // Disconnected when the pool is shut down.
+                            if Pin::new(&mut stop).poll(cx).is_ready() {
+                                return Poll::Ready(None);
+                            }
+                            fut.as_mut().poll(cx).map(Some)
+                        })
+                        .await
+                    })
+                })?;
+            pool.stops.push(stop_tx);
+            pool.threads.push(thread);
+            match handle_rx.recv() {
+                Ok(Ok(handle)) => pool.handles.push(handle),
+                Ok(Err(e)) => return Err(e),
+                Err(_) => return Err(io::Error::other("the runtime thread panicked")),
+            }
+        }
+        Ok(pool)
+    }
+}
+
+/// The runtimes started by [`RuntimePool`], which are stopped and joined when it is dropped.
+pub struct RunningPool<T> {
+    handles: Vec<Handle>,
+    // Disconnected to stop the runtimes
+    stops: Vec<flume::Sender<()>>,
+    threads: Vec<JoinHandle<Option<T>>>,
+}
+
+impl<T> RunningPool<T> {
+    /// The handles of the runtimes indexed by the core id, to spawn tasks on them.
+    pub fn handles(&self) -> &[Handle] {
+        &self.handles
+    }
+
+    /// Wait for the futures of all the cores to complete, and return their outputs by the core
+    /// id.
+    ///
+    /// # Panics
+    ///
+    /// Panics with the panic of the runtime thread if it panics.
+    pub fn join(mut self) -> Vec<T> {
+        std::mem::take(&mut self.threads)
+            .into_iter()
+            .map(|thread| match thread.join() {
+                Ok(output) => output.expect("the runtime is stopped"),
+                Err(e) => std::panic::resume_unwind(e),
+            })
+            .collect()
+    }
+
+    /// Stop the runtimes by dropping the futures of the cores, and wait for the threads to exit.
+    /// The outputs of the futures completed already are returned by the core id.
+    pub fn shutdown(mut self) -> Vec<Option<T>> {
+        self.stop_and_join()
+    }
+
+    fn stop_and_join(&mut self) -> Vec<Option<T>> {
+        self.stops.clear();
+        std::mem::take(&mut self.threads)
+            .into_iter()
+            .map(|thread| thread.join().ok().flatten())
+            .collect()
+    }
+}
+
+impl<T> Drop for RunningPool<T> {
+    fn drop(&mut self) {
+        self.stop_and_join();
+    }
+}
+
+impl<T> std::fmt::Debug for RunningPool<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RunningPool")
+            .field("handles", &self.handles)
+            .finish()
+    }
+}
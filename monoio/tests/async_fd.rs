@@ -0,0 +1,78 @@
+#![cfg(unix)]
+use std::{
+    io::{Read, Write},
+    os::{fd::AsRawFd, unix::net::UnixStream},
+};
+
+use monoio::io::{AsyncFd, Interest};
+
+fn pair() -> (UnixStream, UnixStream) {
+    let (a, b) = UnixStream::pair().unwrap();
+    a.set_nonblocking(true).unwrap();
+    b.set_nonblocking(true).unwrap();
+    (a, b)
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn readable_guard() {
+    let (a, b) = pair();
+    let a = AsyncFd::new(a).unwrap();
+    let mut b = AsyncFd::new(b).unwrap();
+
+    let writer = monoio::spawn(async move {
+        monoio::time::sleep(std::time::Duration::from_millis(10)).await;
+        b.get_mut().write_all(b"hello").unwrap();
+        b
+    });
+
+    let mut buf = [0; 16];
+    let n = loop {
+        let mut guard = a.readable().await.unwrap();
+        if let Ok(res) = guard.try_io(|mut s| s.read(&mut buf)) {
+            break res.unwrap();
+        }
+    };
+    assert_eq!(&buf[..n], b"hello");
+
+    // Nothing left, so the readiness has gone.
+    let mut guard = a.writable().await.unwrap();
+    assert!(guard.try_io(|mut s| s.read(&mut buf)).is_err());
+    drop(writer.await);
+}
+
+#[monoio::test_all]
+async fn async_io() {
+    let (a, b) = pair();
+    let a = AsyncFd::new(a).unwrap();
+    let b = AsyncFd::new(b).unwrap();
+
+    let n = b
+        .async_io(Interest::Writable, |mut s| s.write(b"ping"))
+        .await
+        .unwrap();
+    assert_eq!(n, 4);
+    let mut buf = [0; 16];
+    let n = a
+        .async_io(Interest::Readable, |mut s| s.read(&mut buf))
+        .await
+        .unwrap();
+    assert_eq!(&buf[..n], b"ping");
+}
+
+#[monoio::test_all]
+async fn into_inner_keeps_fd() {
+    let (a, b) = pair();
+    let a = AsyncFd::new(a).unwrap();
+    let raw = a.as_raw_fd();
+    let mut a = a.into_inner();
+    assert_eq!(a.as_raw_fd(), raw);
+
+    // The fd is still open and can be registered again.
+    (&b).write_all(b"again").unwrap();
+    let mut buf = [0; 16];
+    let n = a.read(&mut buf).unwrap();
+    assert_eq!(&buf[..n], b"again");
+    let a = AsyncFd::new(a).unwrap();
+    drop(AsyncFd::new(b).unwrap());
+    assert!(a.get_ref().peer_addr().is_ok());
+}
@@ -0,0 +1,88 @@
+use std::{cell::RefCell, rc::Rc, task::Poll};
+
+use futures::FutureExt;
+use monoio::{
+    buf::{BufPool, IoBuf},
+    io::AsyncReadRent,
+};
+
+async fn yield_now() {
+    let mut yielded = false;
+    std::future::poll_fn(|cx| {
+        if yielded {
+            return Poll::Ready(());
+        }
+        yielded = true;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    })
+    .await
+}
+
+#[monoio::test_all]
+async fn get_and_give_back() {
+    let pool = BufPool::new(2, 16).unwrap();
+    assert_eq!(
+        (pool.buf_cnt(), pool.buf_len(), pool.available()),
+        (2, 16, 2)
+    );
+    assert!(!pool.is_registered());
+
+    let buf = pool.get().await;
+    assert_eq!((buf.capacity(), buf.bytes_init()), (16, 0));
+    let (res, buf) = b"hello pool".as_slice().read(buf).await;
+    assert_eq!(res.unwrap(), 10);
+    assert_eq!(&buf[..], b"hello pool");
+    let other = pool.try_get().unwrap();
+    assert!(pool.try_get().is_none());
+    drop(buf);
+    assert_eq!(pool.available(), 1);
+    // The buffer given back is cleared when checked out again.
+    assert!(pool.get().await.is_empty());
+    drop(other);
+}
+
+#[monoio::test_all]
+async fn backpressure_in_order() {
+    let pool = BufPool::new(1, 16).unwrap();
+    let held = pool.get().await;
+    let order = Rc::new(RefCell::new(Vec::new()));
+
+    let mut tasks = Vec::new();
+    for i in 0..3 {
+        let (pool, order) = (pool.clone(), order.clone());
+        tasks.push(monoio::spawn(async move {
+            let buf = pool.get().await;
+            order.borrow_mut().push(i);
+            yield_now().await;
+            drop(buf);
+        }));
+    }
+    yield_now().await;
+    assert!(order.borrow().is_empty());
+
+    // A waiter which is gone does not take the buffer.
+    let mut cancelled = Box::pin(pool.get());
+    assert!((&mut cancelled).now_or_never().is_none());
+    drop(held);
+    drop(cancelled);
+
+    for task in tasks {
+        task.await;
+    }
+    assert_eq!(*order.borrow(), [0, 1, 2]);
+    assert_eq!(pool.available(), 1);
+}
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+#[monoio::test]
+async fn registered() {
+    let pool = BufPool::new_registered(2, 4096).unwrap();
+    assert!(pool.is_registered());
+    let tempfile = tempfile::NamedTempFile::new().unwrap();
+    std::fs::write(tempfile.path(), b"hello registered").unwrap();
+    let file = monoio::fs::File::open(tempfile.path()).await.unwrap();
+    let (res, buf) = file.read_fixed_at(pool.get().await, 0).await;
+    assert_eq!(res.unwrap(), 16);
+    assert_eq!(&buf[..], b"hello registered");
+}
@@ -29,3 +29,297 @@ async fn ensure_buf_writter_write_properly() {
     assert!(size.is_ok());
     assert_eq!(s, b"123");
 }
+
+/// Counts the reads and writes reaching the inner io.
+struct Counted<T> {
+    inner: T,
+    calls: usize,
+}
+
+impl<T: AsyncReadRent> AsyncReadRent for Counted<T> {
+    async fn read<B: monoio::buf::IoBufMut>(&mut self, buf: B) -> monoio::BufResult<usize, B> {
+        self.calls += 1;
+        self.inner.read(buf).await
+    }
+
+    async fn readv<B: monoio::buf::IoVecBufMut>(&mut self, buf: B) -> monoio::BufResult<usize, B> {
+        self.calls += 1;
+        self.inner.readv(buf).await
+    }
+}
+
+impl<T: AsyncWriteRent> AsyncWriteRent for Counted<T> {
+    async fn write<B: monoio::buf::IoBuf>(&mut self, buf: B) -> monoio::BufResult<usize, B> {
+        self.calls += 1;
+        self.inner.write(buf).await
+    }
+
+    async fn writev<B: monoio::buf::IoVecBuf>(&mut self, buf: B) -> monoio::BufResult<usize, B> {
+        self.calls += 1;
+        self.inner.writev(buf).await
+    }
+
+    async fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush().await
+    }
+
+    async fn shutdown(&mut self) -> std::io::Result<()> {
+        self.inner.shutdown().await
+    }
+}
+
+#[monoio::test_all]
+async fn small_io_is_amortized() {
+    use monoio::io::{AsyncBufRead, AsyncBufReadExt};
+
+    let data: Vec<u8> = (0..100)
+        .flat_map(|i| format!("line {i}\n").into_bytes())
+        .collect();
+    let mut reader = BufReader::new(Counted {
+        inner: &data[..],
+        calls: 0,
+    });
+    assert_eq!(reader.capacity(), 8 * 1024);
+    let mut line = String::new();
+    reader.read_line(&mut line).await.unwrap();
+    assert_eq!(line, "line 0\n");
+    assert_eq!(reader.fill_buf().await.unwrap()[..7], *b"line 1\n");
+    reader.consume(7);
+    let mut read = 0;
+    loop {
+        let (res, _) = reader.read(Vec::with_capacity(3)).await;
+        match res.unwrap() {
+            0 => break,
+            n => read += n,
+        }
+    }
+    assert_eq!(read + 14, data.len());
+    // One read for the data, and one for EOF.
+    assert_eq!(reader.into_inner().calls, 2);
+
+    let mut writer = BufWriter::with_capacity(
+        64,
+        Counted {
+            inner: Vec::new(),
+            calls: 0,
+        },
+    );
+    for i in 0..100u8 {
+        writer.write(vec![i]).await.0.unwrap();
+    }
+    assert_eq!(writer.buffer().len(), 100 % 64);
+    writer.flush().await.unwrap();
+    let inner = writer.into_inner();
+    assert_eq!(inner.calls, 2);
+    assert_eq!(inner.inner, (0..100).collect::<Vec<u8>>());
+}
+
+#[monoio::test_all]
+async fn read_lines() {
+    use monoio::io::{stream::Stream, AsyncBufReadExt};
+
+    let data = b"first\r\nsecond\n\nlast".as_slice();
+    let mut lines = BufReader::with_capacity(4, data).lines();
+    assert_eq!(lines.next_line().await.unwrap().unwrap(), "first");
+    assert_eq!(lines.next().await.unwrap().unwrap(), "second");
+    assert_eq!(lines.next_line().await.unwrap().unwrap(), "");
+    assert_eq!(lines.next_line().await.unwrap().unwrap(), "last");
+    assert!(lines.next_line().await.unwrap().is_none());
+    assert!(lines.next().await.is_none());
+
+    let mut reader = BufReader::new(b"a,b,\xff\n".as_slice());
+    let mut buf = Vec::new();
+    assert_eq!(reader.read_until(b',', &mut buf).await.unwrap(), 2);
+    assert_eq!(reader.read_until(b',', &mut buf).await.unwrap(), 2);
+    assert_eq!(buf, b"a,b,");
+    let mut line = String::new();
+    let err = reader.read_line(&mut line).await.unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert!(line.is_empty());
+}
+
+#[monoio::test_all]
+async fn copy_reuses_buffer() {
+    let data: Vec<u8> = (0..100_000u32).map(|i| i as u8).collect();
+    for _ in 0..2 {
+        let mut reader = Counted {
+            inner: &data[..],
+            calls: 0,
+        };
+        let mut writer = Vec::new();
+        let n = monoio::io::copy(&mut reader, &mut writer).await.unwrap();
+        assert_eq!(n, data.len() as u64);
+        assert_eq!(writer, data);
+        // Chunks of 8KiB, and one read for EOF.
+        assert_eq!(reader.calls, data.len().div_ceil(8192) + 1);
+    }
+}
+
+/// Reads and writes at most 3 bytes at once, from the first iovec only.
+#[cfg(unix)]
+struct Short {
+    data: Vec<u8>,
+    pos: usize,
+}
+
+#[cfg(unix)]
+impl AsyncReadRent for Short {
+    async fn read<B: monoio::buf::IoBufMut>(&mut self, buf: B) -> monoio::BufResult<usize, B> {
+        (Ok(0), buf)
+    }
+
+    async fn readv<B: monoio::buf::IoVecBufMut>(
+        &mut self,
+        mut buf: B,
+    ) -> monoio::BufResult<usize, B> {
+        if buf.write_iovec_len() == 0 {
+            return (Ok(0), buf);
+        }
+        let iovec = unsafe { *buf.write_iovec_ptr() };
+        let n = iovec.iov_len.min(3).min(self.data.len() - self.pos);
+        unsafe {
+            std::ptr::copy_nonoverlapping(self.data[self.pos..].as_ptr(), iovec.iov_base.cast(), n);
+            buf.set_init(n);
+        }
+        self.pos += n;
+        (Ok(n), buf)
+    }
+}
+
+#[cfg(unix)]
+impl AsyncWriteRent for Short {
+    async fn write<B: monoio::buf::IoBuf>(&mut self, buf: B) -> monoio::BufResult<usize, B> {
+        (Ok(0), buf)
+    }
+
+    async fn writev<B: monoio::buf::IoVecBuf>(&mut self, buf: B) -> monoio::BufResult<usize, B> {
+        if buf.read_iovec_len() == 0 {
+            return (Ok(0), buf);
+        }
+        let iovec = unsafe { *buf.read_iovec_ptr() };
+        let n = iovec.iov_len.min(3);
+        self.data
+            .extend_from_slice(unsafe { std::slice::from_raw_parts(iovec.iov_base.cast(), n) });
+        (Ok(n), buf)
+    }
+
+    async fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    async fn shutdown(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+#[monoio::test_all]
+async fn vectored_exact_with_short_io() {
+    use monoio::{
+        buf::VecBuf,
+        io::{AsyncReadRentExt, AsyncWriteRentExt},
+    };
+
+    let mut w = Short {
+        data: Vec::new(),
+        pos: 0,
+    };
+    let buf = VecBuf::from(vec![b"hello".to_vec(), b"".to_vec(), b"monoio".to_vec()]);
+    let (res, _) = w.write_vectored_all(buf).await;
+    assert_eq!(res.unwrap(), 11);
+    assert_eq!(w.data, b"hellomonoio");
+
+    let buf = VecBuf::from(vec![vec![0; 4], vec![0; 5]]);
+    let (res, buf) = w.read_vectored_exact(buf).await;
+    assert_eq!(res.unwrap(), 9);
+    let raw: Vec<Vec<u8>> = buf.into();
+    assert_eq!(raw, [b"hell".to_vec(), b"omono".to_vec()]);
+
+    let buf = VecBuf::from(vec![vec![0; 4]]);
+    let (res, buf) = w.read_vectored_exact(buf).await;
+    assert_eq!(res.unwrap_err().kind(), std::io::ErrorKind::UnexpectedEof);
+    let raw: Vec<Vec<u8>> = buf.into();
+    assert_eq!(raw, [b"io".to_vec()]);
+}
+
+#[monoio::test_all]
+async fn numbers_round_trip() {
+    use monoio::io::{AsyncReadRentExt, AsyncWriteRentExt};
+
+    let mut w = Vec::new();
+    w.write_u8(1).await.unwrap();
+    w.write_u16(0x0203).await.unwrap();
+    w.write_u32_le(0x0405_0607).await.unwrap();
+    w.write_i64(-2).await.unwrap();
+    w.write_u128(u128::MAX - 1).await.unwrap();
+    w.write_f32_le(1.5).await.unwrap();
+    w.write_f64(-0.25).await.unwrap();
+    assert_eq!(w[..7], [1, 2, 3, 7, 6, 5, 4]);
+    assert_eq!(w.len(), 1 + 2 + 4 + 8 + 16 + 4 + 8);
+
+    let mut r = &w[..];
+    assert_eq!(r.read_u8().await.unwrap(), 1);
+    assert_eq!(r.read_u16().await.unwrap(), 0x0203);
+    assert_eq!(r.read_u32_le().await.unwrap(), 0x0405_0607);
+    assert_eq!(r.read_i64().await.unwrap(), -2);
+    assert_eq!(r.read_u128().await.unwrap(), u128::MAX - 1);
+    assert_eq!(r.read_f32_le().await.unwrap(), 1.5);
+    assert_eq!(r.read_f64().await.unwrap(), -0.25);
+    let err = r.read_u32().await.unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+}
+
+#[cfg(feature = "bytes")]
+#[monoio::test_all]
+async fn read_into_bytes_spare() {
+    use bytes::{Bytes, BytesMut};
+    use monoio::buf::{IoBuf, IoBufMut};
+
+    let mut reader = b"hello monoio".as_slice();
+    let mut buf = BytesMut::with_capacity(5);
+    buf.extend_from_slice(b">");
+    let (res, slice) = reader.read(buf.spare_mut()).await;
+    assert_eq!(res.unwrap(), 4);
+    let mut buf = slice.into_inner();
+    assert_eq!(&buf[..], b">hell");
+
+    buf.reserve(16);
+    let (res, slice) = reader.read(buf.spare_mut()).await;
+    assert_eq!(res.unwrap(), 8);
+    let buf = slice.into_inner().freeze();
+    assert_eq!(&buf[..], b">hello monoio");
+
+    let word = Bytes::from(IoBuf::slice(buf.clone(), 7..));
+    assert_eq!(&word[..], b"monoio");
+    // The slice shares the memory with the origin.
+    assert_eq!(word.as_ptr(), buf[7..].as_ptr());
+}
+
+#[monoio::test_all]
+async fn array_buf() {
+    use monoio::{
+        buf::{ArrayBuf, IoBufMut},
+        io::{AsyncReadRentExt, AsyncWriteRentExt},
+    };
+
+    let mut reader = b"\x00\x05hello".as_slice();
+    let (res, len) = reader.read_exact(ArrayBuf::<2>::new()).await;
+    res.unwrap();
+    assert!(len.is_full());
+    assert_eq!(u16::from_be_bytes(*len.as_array()), 5);
+
+    let (res, body) = reader.read(ArrayBuf::<16>::new()).await;
+    assert_eq!(res.unwrap(), 5);
+    assert_eq!(&body[..], b"hello");
+    // Read the rest into the spare capacity.
+    let (res, body) = b" world".as_slice().read(body.spare_mut()).await;
+    assert_eq!(res.unwrap(), 6);
+    assert_eq!(&body.into_inner()[..], b"hello world");
+
+    let mut w = Vec::new();
+    let (res, _) = w.write_all(ArrayBuf::from(*b"abc")).await;
+    assert_eq!(res.unwrap(), 3);
+    let (res, _) = w.write_all(Box::new(*b"de")).await;
+    assert_eq!(res.unwrap(), 2);
+    assert_eq!(w, b"abcde");
+}
@@ -0,0 +1,70 @@
+use std::time::Duration;
+
+use monoio::{
+    io::CancelableAsyncReadRent,
+    net::{TcpListener, TcpStream},
+    utils::CancellationToken,
+};
+
+#[monoio::test_all(timer_enabled = true)]
+async fn cancel_children() {
+    let root = CancellationToken::new();
+    let child = root.child_token();
+    let grandchild = child.child_token();
+    let other = root.child_token();
+
+    // Cancelling a child does not affect the parent.
+    other.cancel();
+    assert!(!root.is_cancelled());
+
+    let waiter = monoio::spawn({
+        let grandchild = grandchild.clone();
+        async move { grandchild.cancelled().await }
+    });
+    monoio::time::sleep(Duration::from_millis(10)).await;
+    assert!(!waiter.is_finished());
+
+    root.cancel();
+    assert!(child.is_cancelled());
+    assert!(grandchild.is_cancelled());
+    waiter.await;
+
+    // Children of a cancelled token are cancelled already.
+    assert!(root.child_token().is_cancelled());
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn drop_guard() {
+    let token = CancellationToken::new();
+    drop(token.clone().drop_guard().disarm());
+    assert!(!token.is_cancelled());
+    drop(token.clone().drop_guard());
+    assert!(token.is_cancelled());
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn cancel_read() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let _server = monoio::spawn(async move {
+        let (conn, _) = listener.accept().await.unwrap();
+        monoio::time::sleep(Duration::from_secs(3600)).await;
+        drop(conn);
+    });
+    let mut client = TcpStream::connect(addr).await.unwrap();
+
+    let token = CancellationToken::new();
+    let child = token.child_token();
+    monoio::spawn(async move {
+        monoio::time::sleep(Duration::from_millis(10)).await;
+        token.cancel();
+    });
+    let (res, buf) = client
+        .cancelable_read(vec![0; 16], child.cancel_handle())
+        .await;
+    assert!(res.is_err());
+
+    // Operations started after the cancellation fail immediately.
+    let (res, _) = client.cancelable_read(buf, child.cancel_handle()).await;
+    assert!(res.is_err());
+}
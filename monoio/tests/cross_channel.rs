@@ -0,0 +1,136 @@
+#![cfg(feature = "sync")]
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    task::{Context, Poll, Wake, Waker},
+    thread,
+};
+
+use monoio::sync::cross::{mpsc, spsc, TryRecvError, TrySendError};
+
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    monoio::RuntimeBuilder::<monoio::FusionDriver>::new()
+        .build()
+        .unwrap()
+        .block_on(future)
+}
+
+#[test]
+fn spsc_between_runtimes() {
+    let (mut tx, mut rx) = spsc::channel(4);
+    let producer = thread::spawn(move || {
+        block_on(async move {
+            for i in 0..10000 {
+                tx.send(i).await.unwrap();
+            }
+        })
+    });
+    block_on(async move {
+        let mut buf = Vec::new();
+        while rx.recv_many(&mut buf, 3).await != 0 {}
+        // Received in order.
+        assert!(buf.iter().copied().eq(0..10000));
+    });
+    producer.join().unwrap();
+}
+
+#[test]
+fn mpsc_from_threads() {
+    let (tx, mut rx) = mpsc::channel(8);
+    let producers: Vec<_> = (0..4)
+        .map(|p| {
+            let tx = tx.clone();
+            // The senders work outside of the runtimes too.
+            thread::spawn(move || {
+                futures::executor::block_on(async move {
+                    for i in 0..1000 {
+                        tx.send((p, i)).await.unwrap();
+                    }
+                })
+            })
+        })
+        .collect();
+    drop(tx);
+    let received = block_on(async move {
+        let mut received = vec![Vec::new(); 4];
+        while let Some((p, i)) = rx.recv().await {
+            received[p].push(i);
+        }
+        received
+    });
+    for (producer, received) in producers.into_iter().zip(received) {
+        producer.join().unwrap();
+        assert!(received.into_iter().eq(0..1000));
+    }
+}
+
+#[derive(Default)]
+struct CountWaker(AtomicUsize);
+
+impl Wake for CountWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn woken_once_per_batch() {
+    let (tx, mut rx) = mpsc::channel(16);
+    let count = Arc::new(CountWaker::default());
+    let waker = Waker::from(count.clone());
+    let mut cx = Context::from_waker(&waker);
+    assert!(rx.poll_recv(&mut cx).is_pending());
+
+    let sender = thread::spawn(move || {
+        for i in 0..10 {
+            tx.try_send(i).unwrap();
+        }
+        assert_eq!(tx.try_send(10), Ok(()));
+        tx
+    });
+    let tx = sender.join().unwrap();
+    assert_eq!(count.0.load(Ordering::SeqCst), 1);
+
+    for i in 0..11 {
+        assert_eq!(rx.poll_recv(&mut cx), Poll::Ready(Some(i)));
+    }
+    assert_eq!(rx.try_recv(), Err(TryRecvError::Empty));
+    assert!(rx.poll_recv(&mut cx).is_pending());
+    tx.try_send(11).unwrap();
+    tx.try_send(12).unwrap();
+    assert_eq!(count.0.load(Ordering::SeqCst), 2);
+
+    drop(tx);
+    assert_eq!(count.0.load(Ordering::SeqCst), 2);
+    assert_eq!(rx.try_recv(), Ok(11));
+    assert_eq!(rx.try_recv(), Ok(12));
+    assert_eq!(rx.try_recv(), Err(TryRecvError::Disconnected));
+}
+
+#[test]
+fn close_and_drop() {
+    let (mut tx, mut rx) = spsc::channel(2);
+    let value = Arc::new(());
+    tx.try_send(value.clone()).unwrap();
+    tx.try_send(value.clone()).unwrap();
+    assert!(matches!(
+        tx.try_send(value.clone()),
+        Err(TrySendError::Full(_))
+    ));
+
+    rx.close();
+    assert!(tx.is_closed());
+    assert!(matches!(
+        tx.try_send(value.clone()),
+        Err(TrySendError::Closed(_))
+    ));
+    // The buffered values can still be received.
+    assert!(rx.try_recv().is_ok());
+    drop(rx);
+    // The rest are dropped with the receiver.
+    assert_eq!(Arc::strong_count(&value), 1);
+    drop(tx);
+}
@@ -0,0 +1,138 @@
+use std::{
+    io,
+    sync::{Arc, Condvar, Mutex},
+    time::{Duration, Instant},
+};
+
+use monoio::{time::TimeDriver, Buildable, Driver, Runtime, RuntimeBuilder};
+
+/// A driver without any event source, which only sleeps until being unparked.
+struct ParkDriver {
+    parker: Arc<Parker>,
+}
+
+#[derive(Default)]
+struct Parker {
+    notified: Mutex<bool>,
+    cv: Condvar,
+}
+
+impl Parker {
+    fn park(&self, timeout: Option<Duration>) {
+        let mut notified = self.notified.lock().unwrap();
+        if !*notified {
+            notified = match timeout {
+                Some(to) => self.cv.wait_timeout(notified, to).unwrap().0,
+                None => self.cv.wait(notified).unwrap(),
+            };
+        }
+        *notified = false;
+    }
+}
+
+#[cfg(feature = "sync")]
+struct Unparker(Arc<Parker>);
+
+#[cfg(feature = "sync")]
+impl monoio::Unpark for Unparker {
+    fn unpark(&self) -> io::Result<()> {
+        *self.0.notified.lock().unwrap() = true;
+        self.0.cv.notify_one();
+        Ok(())
+    }
+}
+
+impl Driver for ParkDriver {
+    fn with<R>(&self, f: impl FnOnce() -> R) -> R {
+        f()
+    }
+
+    fn submit(&self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn park(&self) -> io::Result<()> {
+        self.parker.park(None);
+        Ok(())
+    }
+
+    fn park_timeout(&self, duration: Duration) -> io::Result<()> {
+        self.parker.park(Some(duration));
+        Ok(())
+    }
+
+    #[cfg(feature = "sync")]
+    type Unpark = Unparker;
+
+    #[cfg(feature = "sync")]
+    fn unpark(&self) -> Self::Unpark {
+        Unparker(self.parker.clone())
+    }
+}
+
+impl Buildable for ParkDriver {
+    fn build(this: RuntimeBuilder<Self>) -> io::Result<Runtime<Self>> {
+        Ok(this.build_with_driver(ParkDriver {
+            parker: Default::default(),
+        }))
+    }
+}
+
+#[test]
+fn spawn_tasks() {
+    let mut rt = RuntimeBuilder::<ParkDriver>::new().build_with_driver(ParkDriver {
+        parker: Default::default(),
+    });
+    let sum = rt.block_on(async {
+        let handles: Vec<_> = (0..10).map(|i| monoio::spawn(async move { i })).collect();
+        let mut sum = 0;
+        for h in handles {
+            sum += h.await;
+        }
+        sum
+    });
+    assert_eq!(sum, 45);
+}
+
+#[test]
+fn timer() {
+    let mut rt = Buildable::build(RuntimeBuilder::<TimeDriver<ParkDriver>>::new()).unwrap();
+    rt.block_on(async {
+        let begin = Instant::now();
+        monoio::time::sleep(Duration::from_millis(20)).await;
+        assert!(begin.elapsed() >= Duration::from_millis(20));
+    });
+}
+
+#[cfg(feature = "sync")]
+#[test]
+fn wake_from_other_thread() {
+    use std::{
+        future::poll_fn,
+        task::{Poll, Waker},
+    };
+
+    let state: Arc<Mutex<(bool, Option<Waker>)>> = Default::default();
+    let mut rt = Buildable::build(RuntimeBuilder::<ParkDriver>::new()).unwrap();
+    rt.block_on(async {
+        let remote = state.clone();
+        let t = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            let mut state = remote.lock().unwrap();
+            state.0 = true;
+            if let Some(w) = state.1.take() {
+                w.wake();
+            }
+        });
+        poll_fn(|cx| {
+            let mut state = state.lock().unwrap();
+            if state.0 {
+                return Poll::Ready(());
+            }
+            state.1 = Some(cx.waker().clone());
+            Poll::Pending
+        })
+        .await;
+        t.join().unwrap();
+    });
+}
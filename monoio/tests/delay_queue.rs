@@ -0,0 +1,85 @@
+use std::time::Duration;
+
+use monoio::{
+    io::stream::Stream,
+    time::{DelayQueue, Instant},
+};
+
+#[monoio::test_all(timer_enabled = true)]
+async fn expire_in_order() {
+    let mut queue = DelayQueue::new();
+    assert!(queue.next().await.is_none());
+
+    let begin = Instant::now();
+    let c = queue.insert("c", Duration::from_millis(30));
+    let a = queue.insert("a", Duration::from_millis(10));
+    let b = queue.insert_at("b", begin + Duration::from_millis(20));
+    assert_eq!(queue.len(), 3);
+    assert_eq!(queue.peek(), Some(a));
+    assert_eq!(queue.deadline(&b), Some(begin + Duration::from_millis(20)));
+
+    for (key, value) in [(a, "a"), (b, "b"), (c, "c")] {
+        let expired = queue.next().await.unwrap();
+        assert_eq!(expired.key(), key);
+        assert_eq!(*expired.get_ref(), value);
+        assert!(Instant::now() >= expired.deadline());
+    }
+    assert!(queue.is_empty());
+    assert!(queue.next().await.is_none());
+    assert_eq!(queue.deadline(&a), None);
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn remove_and_reset() {
+    let mut queue = DelayQueue::new();
+    let a = queue.insert("a", Duration::from_millis(10));
+    let b = queue.insert("b", Duration::from_millis(20));
+    let c = queue.insert("c", Duration::from_millis(30));
+
+    // "a" is refreshed to expire last, and "b" is removed.
+    assert!(queue.reset(&a, Duration::from_millis(40)));
+    let removed = queue.remove(&b).unwrap();
+    assert_eq!(removed.into_inner(), "b");
+    assert!(queue.remove(&b).is_none());
+    assert!(!queue.reset(&b, Duration::from_millis(1)));
+
+    assert_eq!(queue.next().await.unwrap().key(), c);
+    assert_eq!(queue.next().await.unwrap().key(), a);
+    assert!(queue.next().await.is_none());
+
+    queue.insert("d", Duration::from_secs(3600));
+    queue.clear();
+    assert!(queue.next().await.is_none());
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn insert_earlier_while_waiting() {
+    let queue = std::rc::Rc::new(std::cell::RefCell::new(DelayQueue::new()));
+    queue.borrow_mut().insert(1, Duration::from_secs(3600));
+
+    // The waiting task is woken for the earlier item inserted by another task.
+    let waiting = queue.clone();
+    let handle = monoio::spawn(async move {
+        std::future::poll_fn(|cx| waiting.borrow_mut().poll_expired(cx))
+            .await
+            .map(|expired| expired.into_inner())
+    });
+    monoio::time::sleep(Duration::from_millis(5)).await;
+    let begin = Instant::now();
+    queue.borrow_mut().insert(2, Duration::from_millis(5));
+    assert_eq!(handle.await, Some(2));
+    assert!(begin.elapsed() < Duration::from_secs(60));
+    assert_eq!(queue.borrow().len(), 1);
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn same_deadline() {
+    let mut queue = DelayQueue::new();
+    let when = Instant::now() + Duration::from_millis(5);
+    let keys: Vec<_> = (0..100).map(|i| queue.insert_at(i, when)).collect();
+    for (i, key) in keys.into_iter().enumerate() {
+        let expired = queue.next().await.unwrap();
+        assert_eq!(expired.key(), key);
+        assert_eq!(expired.into_inner(), i);
+    }
+}
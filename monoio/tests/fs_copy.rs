@@ -0,0 +1,66 @@
+#![cfg(target_os = "linux")]
+
+use std::os::unix::fs::PermissionsExt;
+
+use monoio::buf::FixedBufPool;
+
+#[monoio::test_all]
+async fn copy_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let from = dir.path().join("from");
+    let to = dir.path().join("to");
+    let data: Vec<u8> = (0..300_000).map(|i| i as u8).collect();
+    std::fs::write(&from, &data).unwrap();
+    std::fs::set_permissions(&from, std::fs::Permissions::from_mode(0o640)).unwrap();
+    // The existing file is truncated.
+    std::fs::write(&to, vec![1; 400_000]).unwrap();
+
+    assert_eq!(
+        monoio::fs::copy(&from, &to).await.unwrap(),
+        data.len() as u64
+    );
+    assert_eq!(std::fs::read(&to).unwrap(), data);
+    assert_eq!(
+        std::fs::metadata(&to).unwrap().permissions().mode() & 0o777,
+        0o640
+    );
+
+    let empty = dir.path().join("empty");
+    std::fs::write(&empty, b"").unwrap();
+    assert_eq!(monoio::fs::copy(&empty, &to).await.unwrap(), 0);
+    assert!(std::fs::read(&to).unwrap().is_empty());
+}
+
+#[monoio::test_all]
+async fn copy_fallback() {
+    let dir = tempfile::tempdir().unwrap();
+    let to = dir.path().join("to");
+
+    // procfs reports the length of zero, which is copied by read and write.
+    let n = monoio::fs::copy("/proc/self/status", &to).await.unwrap();
+    assert!(n > 0);
+    assert_eq!(std::fs::metadata(&to).unwrap().len(), n);
+    assert!(std::fs::read_to_string(&to).unwrap().contains("Pid:"));
+
+    let pool = FixedBufPool::new(1, 64).unwrap();
+    let n = monoio::fs::copy_with_pool("/proc/self/status", &to, &pool)
+        .await
+        .unwrap();
+    assert!(n > 64);
+    assert_eq!(std::fs::metadata(&to).unwrap().len(), n);
+    assert_eq!(pool.available(), 1);
+}
+
+#[monoio::test_all]
+async fn copy_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let to = dir.path().join("to");
+
+    let err = monoio::fs::copy(dir.path(), &to).await.unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    let err = monoio::fs::copy(dir.path().join("missing"), &to)
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    assert!(!to.exists());
+}
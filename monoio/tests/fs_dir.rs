@@ -0,0 +1,146 @@
+#![cfg(target_os = "linux")]
+
+use std::collections::BTreeSet;
+
+use monoio::fs::{Dir, OpenOptions};
+
+#[monoio::test_all]
+async fn read_dir() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut expected = BTreeSet::new();
+    // More entries than one `getdents64` call returns.
+    for i in 0..300 {
+        let name = format!("file-{i:04}-{}", "x".repeat(32));
+        std::fs::write(dir.path().join(&name), b"").unwrap();
+        expected.insert(name);
+    }
+    std::fs::create_dir(dir.path().join("sub")).unwrap();
+    std::os::unix::fs::symlink("sub", dir.path().join("link")).unwrap();
+
+    let mut entries = monoio::fs::read_dir(dir.path()).await.unwrap();
+    let mut files = BTreeSet::new();
+    while let Some(entry) = entries.next_entry().await.unwrap() {
+        let name = entry.file_name().into_string().unwrap();
+        assert_eq!(entry.path(), dir.path().join(&name));
+        let file_type = entry.file_type().await.unwrap();
+        match name.as_str() {
+            "sub" => assert!(file_type.is_dir()),
+            "link" => assert!(file_type.is_symlink()),
+            _ => {
+                assert!(file_type.is_file());
+                files.insert(name);
+            }
+        }
+        assert_eq!(
+            entry.ino(),
+            std::os::unix::fs::MetadataExt::ino(&entry.metadata().await.unwrap())
+        );
+    }
+    assert_eq!(files, expected);
+    assert!(entries.next_entry().await.unwrap().is_none());
+
+    let err = monoio::fs::read_dir(dir.path().join("sub/missing"))
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
+
+#[monoio::test_all]
+async fn read_dir_stream() {
+    use monoio::io::stream::Stream;
+
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("a"), b"").unwrap();
+    std::fs::write(dir.path().join("b"), b"").unwrap();
+
+    let dir = Dir::open(dir.path()).await.unwrap();
+    let mut names = Vec::new();
+    let mut entries = dir.read_dir().await.unwrap();
+    while let Some(entry) = entries.next().await {
+        names.push(entry.unwrap().file_name());
+    }
+    names.sort();
+    assert_eq!(names, ["a", "b"]);
+
+    // Each stream reads from the beginning.
+    let mut entries = dir.read_dir().await.unwrap();
+    assert!(entries.next().await.is_some());
+}
+
+#[monoio::test_all]
+async fn dir_at_ops() {
+    let tmp = tempfile::tempdir().unwrap();
+    std::fs::create_dir(tmp.path().join("sub")).unwrap();
+    std::fs::write(tmp.path().join("sub/foo"), b"foo").unwrap();
+
+    let dir = Dir::open(tmp.path()).await.unwrap();
+    let sub = dir.open_dir("sub").await.unwrap();
+    assert_eq!(sub.path(), tmp.path().join("sub"));
+
+    // The handle keeps working after the directory is renamed.
+    std::fs::rename(tmp.path().join("sub"), tmp.path().join("moved")).unwrap();
+    let file = sub
+        .open_file("foo", OpenOptions::new().read(true))
+        .await
+        .unwrap();
+    let (res, buf) = file.read_at(vec![0; 8], 0).await;
+    assert_eq!(&buf[..res.unwrap()], b"foo");
+
+    assert!(sub.metadata("foo").await.unwrap().is_file());
+    assert!(dir.metadata("moved").await.unwrap().is_dir());
+    assert_eq!(
+        dir.symlink_metadata("sub").await.unwrap_err().kind(),
+        std::io::ErrorKind::NotFound
+    );
+    assert!(Dir::open(tmp.path().join("moved/foo")).await.is_err());
+}
+
+#[cfg(all(feature = "mkdirat", feature = "unlinkat"))]
+#[monoio::test_all]
+async fn dir_create_remove() {
+    let tmp = tempfile::tempdir().unwrap();
+    let dir = Dir::open(tmp.path()).await.unwrap();
+
+    dir.create_dir("new", 0o755).await.unwrap();
+    assert!(tmp.path().join("new").is_dir());
+    let new = dir.open_dir("new").await.unwrap();
+    new.open_file("file", OpenOptions::new().write(true).create(true))
+        .await
+        .unwrap();
+    assert!(dir.remove_dir("new").await.is_err());
+    new.remove_file("file").await.unwrap();
+    dir.remove_dir("new").await.unwrap();
+    assert!(!tmp.path().join("new").exists());
+}
+
+#[cfg(feature = "unlinkat")]
+#[monoio::test_all]
+async fn remove_dir_all() {
+    let tmp = tempfile::tempdir().unwrap();
+    let outside = tmp.path().join("outside");
+    std::fs::create_dir(&outside).unwrap();
+    std::fs::write(outside.join("keep"), b"").unwrap();
+
+    let root = tmp.path().join("root");
+    std::fs::create_dir_all(root.join("a/b/c")).unwrap();
+    std::fs::write(root.join("a/b/c/file"), b"").unwrap();
+    std::fs::write(root.join("a/file"), b"").unwrap();
+    std::os::unix::fs::symlink(&outside, root.join("a/link")).unwrap();
+
+    monoio::fs::remove_dir_all(&root).await.unwrap();
+    assert!(!root.exists());
+    // The symlink is removed without following.
+    assert!(outside.join("keep").exists());
+
+    // A symlink to a directory is removed itself.
+    let link = tmp.path().join("link");
+    std::os::unix::fs::symlink(&outside, &link).unwrap();
+    monoio::fs::remove_dir_all(&link).await.unwrap();
+    assert!(!link.exists());
+    assert!(outside.join("keep").exists());
+
+    assert_eq!(
+        monoio::fs::remove_dir_all(&root).await.unwrap_err().kind(),
+        std::io::ErrorKind::NotFound
+    );
+}
@@ -142,6 +142,36 @@ async fn basic_write_vectored() {
     assert_eq!(result, [HELLO, HELLO, HELLO, HELLO].concat());
 }
 
+#[monoio::test_all]
+async fn fixed_read_write_at() {
+    use monoio::buf::FixedBufPool;
+
+    let tempfile = tempfile();
+    let pool = FixedBufPool::new(2, 64).unwrap();
+    let file = monoio::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(tempfile.path())
+        .await
+        .unwrap();
+
+    let mut buf = pool.try_next().unwrap();
+    buf.extend_from_slice(HELLO);
+    let (res, buf) = file.write_fixed_at(buf, 0).await;
+    assert_eq!(res.unwrap(), HELLO.len());
+    drop(buf);
+    file.sync_all().await.unwrap();
+
+    let _held = pool.try_next().unwrap();
+    let buf = pool.try_next().unwrap();
+    assert!(pool.try_next().is_none());
+    let (res, buf) = file.read_fixed_at(buf, 6).await;
+    assert_eq!(res.unwrap(), HELLO.len() - 6);
+    assert_eq!(&buf[..], &HELLO[6..]);
+    drop(buf);
+    assert_eq!(pool.available(), 1);
+}
+
 #[monoio::test_all]
 async fn basic_write_at() {
     let tempfile = tempfile();
@@ -178,6 +208,61 @@ async fn basic_write_all_at() {
     assert_eq!(file, HELLO);
 }
 
+#[monoio::test_all]
+async fn write_at_sync() {
+    let tempfile = tempfile();
+
+    let file = File::create(tempfile.path()).await.unwrap();
+    let (res, _) = file.write_at_sync(HELLO, 0).await;
+    assert_eq!(res.unwrap(), HELLO.len());
+    let (res, _) = file.write_at_sync(&b"monoio"[..], 6).await;
+    assert_eq!(res.unwrap(), 6);
+    assert_eq!(std::fs::read(tempfile.path()).unwrap(), b"hello monoio..");
+
+    // The write fails, and the error is from the write rather than the cancelled sync.
+    let file = File::open(tempfile.path()).await.unwrap();
+    let (res, _) = file.write_at_sync(HELLO, 0).await;
+    assert_eq!(res.unwrap_err().raw_os_error(), Some(libc::EBADF));
+}
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+#[monoio::test_all]
+async fn link_chain() {
+    use monoio::driver::link;
+
+    let tempfile = tempfile();
+    let file = File::create(tempfile.path()).await.unwrap();
+    let ((write, _), (read, buf), sync) = link::submit((
+        link::write_at(&file, HELLO, 0),
+        link::read_at(
+            &File::open(tempfile.path()).await.unwrap(),
+            vec![0; HELLO.len()],
+            0,
+        ),
+        link::sync_all(&file),
+    ))
+    .await;
+    assert_eq!(write.unwrap(), HELLO.len());
+    assert_eq!(read.unwrap(), HELLO.len());
+    assert_eq!(buf, HELLO);
+    sync.unwrap();
+
+    // The write to the read-only file fails, and the rest of the chain is cancelled.
+    let read_only = File::open(tempfile.path()).await.unwrap();
+    let ((first, _), (second, _), (third, _), sync) = link::submit((
+        link::write_at(&file, &b"monoio"[..], 0),
+        link::write_at(&read_only, &b"monoio"[..], 6),
+        link::write_at(&file, &b"monoio"[..], 6),
+        link::sync_data(&file),
+    ))
+    .await;
+    assert_eq!(first.unwrap(), 6);
+    assert_eq!(second.unwrap_err().raw_os_error(), Some(libc::EBADF));
+    assert_eq!(third.unwrap_err().raw_os_error(), Some(libc::ECANCELED));
+    assert_eq!(sync.unwrap_err().raw_os_error(), Some(libc::ECANCELED));
+    assert_eq!(std::fs::read(tempfile.path()).unwrap(), b"monoioworld...");
+}
+
 #[monoio::test(driver = "uring")]
 async fn cancel_read_at() {
     let mut tempfile = tempfile();
@@ -192,6 +277,28 @@ async fn cancel_read_at() {
     read_hello(&file, 0).await;
 }
 
+#[cfg(unix)]
+#[monoio::test(driver = "uring", timer = true)]
+async fn cancelable_read_pipe() {
+    use monoio::io::{CancelableAsyncReadRent, Canceller};
+
+    let mut fds = [0; 2];
+    assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+    let mut rx = File::from_std(unsafe { std::fs::File::from_raw_fd(fds[0]) }).unwrap();
+    let _tx = unsafe { std::fs::File::from_raw_fd(fds[1]) };
+
+    // The read blocks on the empty pipe until it is canceled.
+    let canceller = Canceller::new();
+    let handle = canceller.handle();
+    let read = monoio::spawn(async move { rx.cancelable_read(vec![0; 8], handle).await.0 });
+    monoio::time::sleep(std::time::Duration::from_millis(10)).await;
+    canceller.cancel();
+    assert_eq!(
+        read.await.unwrap_err().raw_os_error(),
+        Some(libc::ECANCELED)
+    );
+}
+
 #[monoio::test_all]
 async fn explicit_close() {
     let mut tempfile = tempfile();
@@ -326,3 +433,212 @@ async fn flush_and_shutdown() {
     let res = file.shutdown().await;
     assert!(matches!(res, Ok(())));
 }
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+#[test]
+fn fixed_file() {
+    let tempfile = tempfile();
+    let mut rt = monoio::RuntimeBuilder::<monoio::IoUringDriver>::new()
+        .with_fixed_files(4)
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let file = monoio::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(tempfile.path())
+            .await
+            .unwrap();
+        file.register_fixed().await.unwrap();
+        file.write_all_at(HELLO, 0).await.0.unwrap();
+        file.sync_data().await.unwrap();
+        read_hello(&file, 2).await;
+        file.close().await.unwrap();
+    });
+    assert_eq!(std::fs::read(tempfile.path()).unwrap(), HELLO);
+}
+
+#[cfg(target_os = "linux")]
+#[monoio::test_all]
+async fn sync_file() {
+    let tempfile = tempfile();
+    let file = File::create(tempfile.path()).await.unwrap();
+    file.write_all_at(HELLO, 0).await.0.unwrap();
+    file.sync_range(
+        0,
+        0,
+        libc::SYNC_FILE_RANGE_WAIT_BEFORE
+            | libc::SYNC_FILE_RANGE_WRITE
+            | libc::SYNC_FILE_RANGE_WAIT_AFTER,
+    )
+    .await
+    .unwrap();
+    file.sync_data().await.unwrap();
+    file.sync_all().await.unwrap();
+    assert_eq!(std::fs::read(tempfile.path()).unwrap(), HELLO);
+
+    // Invalid flags
+    let err = file.sync_range(0, 0, u32::MAX).await.unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::EINVAL));
+    file.close().await.unwrap();
+}
+
+#[cfg(target_os = "linux")]
+#[monoio::test_all]
+async fn allocate_and_set_len() {
+    let tempfile = tempfile();
+    let file = monoio::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(tempfile.path())
+        .await
+        .unwrap();
+    file.write_all_at(HELLO, 0).await.0.unwrap();
+
+    // Pre-allocate without changing the size.
+    file.allocate(0, 1 << 20, libc::FALLOC_FL_KEEP_SIZE)
+        .await
+        .unwrap();
+    assert_eq!(file.metadata().await.unwrap().len(), HELLO.len() as u64);
+    file.allocate(0, 4096, 0).await.unwrap();
+    assert_eq!(file.metadata().await.unwrap().len(), 4096);
+
+    // Punch a hole, which reads as zeros.
+    file.allocate(0, 4, libc::FALLOC_FL_PUNCH_HOLE | libc::FALLOC_FL_KEEP_SIZE)
+        .await
+        .unwrap();
+    let (res, buf) = file.read_at(vec![0; 8], 0).await;
+    res.unwrap();
+    assert_eq!(&buf[..], b"\0\0\0\0o wo");
+
+    file.set_len(5).await.unwrap();
+    assert_eq!(file.metadata().await.unwrap().len(), 5);
+    file.set_len(10).await.unwrap();
+    file.close().await.unwrap();
+    assert_eq!(
+        std::fs::read(tempfile.path()).unwrap(),
+        b"\0\0\0\0o\0\0\0\0\0"
+    );
+}
+
+#[cfg(target_os = "linux")]
+#[monoio::test_all]
+async fn advise() {
+    use monoio::fs::Advice;
+
+    let tempfile = tempfile();
+    tempfile.as_file().write_all(HELLO).unwrap();
+    let file = File::open(tempfile.path()).await.unwrap();
+    for advice in [
+        Advice::Sequential,
+        Advice::WillNeed,
+        Advice::Random,
+        Advice::NoReuse,
+        Advice::Normal,
+    ] {
+        file.advise(0, 0, advice).await.unwrap();
+    }
+    let (res, buf) = file.read_at(vec![0; HELLO.len()], 0).await;
+    res.unwrap();
+    assert_eq!(buf, HELLO);
+    file.advise(0, 4096, Advice::DontNeed).await.unwrap();
+}
+
+#[cfg(target_os = "linux")]
+#[monoio::test_all]
+async fn madvise() {
+    use monoio::fs::{madvise, Advice};
+
+    const LEN: usize = 2 * 4096;
+    let addr = unsafe {
+        libc::mmap(
+            std::ptr::null_mut(),
+            LEN,
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+            -1,
+            0,
+        )
+    };
+    assert_ne!(addr, libc::MAP_FAILED);
+    let addr = addr as *mut u8;
+    unsafe {
+        addr.write_bytes(1, LEN);
+        madvise(addr, LEN, Advice::WillNeed).await.unwrap();
+        // Private anonymous pages are zero-filled after DONTNEED.
+        madvise(addr, LEN, Advice::DontNeed).await.unwrap();
+        assert_eq!(*addr.add(LEN - 1), 0);
+
+        let err = madvise(addr.add(1), 4096, Advice::Normal)
+            .await
+            .unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(libc::EINVAL));
+        let err = madvise(addr, LEN, Advice::NoReuse).await.unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(libc::EINVAL));
+        libc::munmap(addr.cast(), LEN);
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[monoio::test_all]
+async fn direct_io() {
+    use monoio::{buf::AlignedBuf, fs::OpenOptions};
+
+    let buf = AlignedBuf::new(0, 512);
+    assert!(buf.is_empty());
+    let mut buf = AlignedBuf::with_capacity(8192);
+    assert_eq!(buf.as_ptr() as usize % AlignedBuf::DEFAULT_ALIGN, 0);
+    buf.extend_from_slice(&[3; 4096]);
+    buf.extend_from_slice(&[4; 4096]);
+    assert_eq!((buf.len(), buf.capacity()), (8192, 8192));
+
+    let tempfile = tempfile::tempdir().unwrap();
+    let path = tempfile.path().join("direct");
+    let file = match OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .direct(true)
+        .open(&path)
+        .await
+    {
+        Ok(file) => file,
+        // The file system does not support O_DIRECT.
+        Err(e) if e.raw_os_error() == Some(libc::EINVAL) => return,
+        Err(e) => panic!("{e}"),
+    };
+    let flags = unsafe { libc::fcntl(file.as_raw_fd(), libc::F_GETFL) };
+    assert_ne!(flags & libc::O_DIRECT, 0);
+
+    let (res, _) = file.write_all_at(buf, 0).await;
+    res.unwrap();
+    let (res, buf) = file.read_at(AlignedBuf::zeroed(4096, 4096), 4096).await;
+    assert_eq!(res.unwrap(), 4096);
+    assert!(buf.iter().all(|b| *b == 4));
+    file.close().await.unwrap();
+}
+
+#[monoio::test_all]
+async fn read_at_into_shared_windows() {
+    use monoio::buf::IoBufMut;
+
+    let mut tempfile = tempfile();
+    tempfile.write_all(HELLO).unwrap();
+    tempfile.as_file_mut().sync_data().unwrap();
+
+    let file = File::open(tempfile.path()).await.unwrap();
+    let buf = Vec::with_capacity(HELLO.len());
+    let (head, tail) = buf.slice_mut(..HELLO.len()).into_shared().split_at(5);
+    assert_eq!(head.get_ref().windows(), 2);
+    let ((res1, head), (res2, tail)) =
+        monoio::join!(file.read_exact_at(head, 0), file.read_exact_at(tail, 5));
+    res1.unwrap();
+    res2.unwrap();
+
+    // Windows out of order can not be merged.
+    let (tail, head) = tail.try_merge(head).err().unwrap();
+    let merged = head.try_merge(tail).ok().unwrap();
+    assert_eq!(&merged[..], HELLO);
+    let buf = merged.try_unshare().ok().unwrap().into_inner();
+    assert_eq!(buf, HELLO);
+}
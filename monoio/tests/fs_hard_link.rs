@@ -0,0 +1,32 @@
+#![cfg(all(unix, feature = "linkat"))]
+
+use std::os::unix::fs::MetadataExt;
+
+use monoio::fs::File;
+use tempfile::tempdir;
+
+const TEST_PAYLOAD: &[u8] = b"I am data in the original file";
+
+#[monoio::test_all]
+async fn create_hard_link() {
+    let tmpdir = tempdir().unwrap();
+    let original = tmpdir.path().join("original");
+    let link = tmpdir.path().join("link");
+    let file = File::create(&original).await.unwrap();
+    file.write_all_at(TEST_PAYLOAD, 0).await.0.unwrap();
+    file.close().await.unwrap();
+
+    monoio::fs::hard_link(&original, &link).await.unwrap();
+    assert_eq!(monoio::fs::read(&link).await.unwrap(), TEST_PAYLOAD);
+    let meta = std::fs::metadata(&link).unwrap();
+    assert_eq!(meta.ino(), std::fs::metadata(&original).unwrap().ino());
+    assert_eq!(meta.nlink(), 2);
+    assert!(!link.is_symlink());
+
+    let err = monoio::fs::hard_link(&original, &link).await.unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+    let err = monoio::fs::hard_link(tmpdir.path().join("missing"), tmpdir.path().join("x"))
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
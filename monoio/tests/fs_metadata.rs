@@ -80,3 +80,27 @@ async fn symlink_metadata() {
 
     assert_eq!(m_meta.is_symlink(), std_meta.is_symlink());
 }
+
+#[monoio::test_all]
+async fn metadata_ext_and_errors() {
+    use std::os::unix::fs::{MetadataExt, PermissionsExt};
+
+    let file = tempfile::NamedTempFile::new().unwrap();
+    std::fs::set_permissions(file.path(), std::fs::Permissions::from_mode(0o640)).unwrap();
+
+    let m_file = monoio::fs::File::open(file.path()).await.unwrap();
+    let m_meta = m_file.metadata().await.unwrap().clone();
+    let std_meta = std::fs::metadata(file.path()).unwrap();
+
+    assert!(m_meta.file_type().is_file());
+    assert_eq!(m_meta.permissions().mode() & 0o777, 0o640);
+    assert_eq!(m_meta.ino(), std_meta.ino());
+    assert_eq!(m_meta.dev(), std_meta.dev());
+    assert_eq!(m_meta.uid(), std_meta.uid());
+    assert_eq!(m_meta.nlink(), 1);
+
+    let err = monoio::fs::metadata(file.path().with_extension("missing"))
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
@@ -0,0 +1,118 @@
+#![cfg(target_os = "linux")]
+
+use monoio::fs::{File, OpenOptions};
+
+fn is_unsupported(e: &std::io::Error) -> bool {
+    // openat2 is available since Linux 5.6.
+    e.raw_os_error() == Some(libc::ENOSYS)
+}
+
+#[monoio::test_all]
+async fn open_at_dir() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("foo"), b"foo").unwrap();
+    let root = File::open(dir.path()).await.unwrap();
+
+    let file = OpenOptions::new()
+        .read(true)
+        .open_at(&root, "foo")
+        .await
+        .unwrap();
+    let (res, buf) = file.read_at(vec![0; 8], 0).await;
+    assert_eq!(&buf[..res.unwrap()], b"foo");
+
+    let file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open_at(&root, "bar")
+        .await
+        .unwrap();
+    file.write_all_at(&b"bar"[..], 0).await.0.unwrap();
+    file.close().await.unwrap();
+    assert_eq!(std::fs::read(dir.path().join("bar")).unwrap(), b"bar");
+}
+
+#[monoio::test_all]
+async fn open_beneath() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("sub")).unwrap();
+    std::fs::write(dir.path().join("secret"), b"secret").unwrap();
+    std::fs::write(dir.path().join("sub/public"), b"public").unwrap();
+    std::os::unix::fs::symlink("../secret", dir.path().join("sub/link")).unwrap();
+    let root = File::open(dir.path().join("sub")).await.unwrap();
+
+    let mut opts = OpenOptions::new();
+    opts.read(true).open_beneath(true);
+    match opts.open_at(&root, "public").await {
+        Ok(_) => {}
+        Err(e) if is_unsupported(&e) => return,
+        Err(e) => panic!("{e}"),
+    }
+    for path in ["../secret", "link"] {
+        let err = opts.open_at(&root, path).await.unwrap_err();
+        assert_eq!(err.raw_os_error(), Some(libc::EXDEV), "{path}");
+    }
+    let err = opts
+        .open_at(&root, dir.path().join("secret"))
+        .await
+        .unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::EXDEV));
+
+    // Without the option, the symlink is followed.
+    assert!(OpenOptions::new()
+        .read(true)
+        .open_at(&root, "link")
+        .await
+        .is_ok());
+}
+
+#[monoio::test_all]
+async fn resolve_options() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("file"), b"file").unwrap();
+    std::os::unix::fs::symlink("/file", dir.path().join("abs_link")).unwrap();
+    let root = File::open(dir.path()).await.unwrap();
+
+    // The absolute symlink is resolved in the directory.
+    let file = match OpenOptions::new()
+        .read(true)
+        .in_root(true)
+        .open_at(&root, "abs_link")
+        .await
+    {
+        Ok(file) => file,
+        Err(e) if is_unsupported(&e) => return,
+        Err(e) => panic!("{e}"),
+    };
+    let (res, buf) = file.read_at(vec![0; 8], 0).await;
+    assert_eq!(&buf[..res.unwrap()], b"file");
+
+    let err = OpenOptions::new()
+        .read(true)
+        .no_symlinks(true)
+        .open(dir.path().join("abs_link"))
+        .await
+        .unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::ELOOP));
+
+    let err = OpenOptions::new()
+        .read(true)
+        .no_magiclinks(true)
+        .open(format!(
+            "/proc/self/fd/{}",
+            std::os::fd::AsRawFd::as_raw_fd(&root)
+        ))
+        .await
+        .unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::ELOOP));
+
+    // Disabled options make no difference.
+    OpenOptions::new()
+        .read(true)
+        .no_symlinks(true)
+        .no_symlinks(false)
+        .no_xdev(true)
+        .open(dir.path().join("file"))
+        .await
+        .unwrap();
+}
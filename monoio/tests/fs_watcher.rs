@@ -0,0 +1,115 @@
+#![cfg(target_os = "linux")]
+use std::{fs, io::Write};
+
+use monoio::fs::{Event, EventKind, Watcher};
+
+async fn next(watcher: &mut Watcher) -> Event {
+    watcher.next_event().await.unwrap()
+}
+
+#[monoio::test_all]
+async fn create_modify_remove() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut watcher = Watcher::new().unwrap();
+    watcher.watch(dir.path()).unwrap();
+
+    let path = dir.path().join("file");
+    let mut file = fs::File::create(&path).unwrap();
+    file.write_all(b"hello").unwrap();
+    drop(file);
+    fs::remove_file(&path).unwrap();
+    fs::create_dir(dir.path().join("sub")).unwrap();
+
+    let event = next(&mut watcher).await;
+    assert_eq!(event.kind(), &EventKind::Created);
+    assert_eq!(event.path(), path);
+    assert!(!event.is_dir());
+    assert_eq!(next(&mut watcher).await.kind(), &EventKind::Modified);
+    assert_eq!(next(&mut watcher).await.kind(), &EventKind::Removed);
+    let event = next(&mut watcher).await;
+    assert_eq!(event.kind(), &EventKind::Created);
+    assert!(event.is_dir());
+}
+
+#[monoio::test_all]
+async fn moves() {
+    let watched = tempfile::tempdir().unwrap();
+    let unwatched = tempfile::tempdir().unwrap();
+    let mut watcher = Watcher::new().unwrap();
+    watcher.watch(watched.path()).unwrap();
+
+    let a = watched.path().join("a");
+    let b = watched.path().join("b");
+    let outside = unwatched.path().join("c");
+    fs::write(unwatched.path().join("in"), b"").unwrap();
+    fs::rename(unwatched.path().join("in"), &a).unwrap();
+    fs::rename(&a, &b).unwrap();
+    fs::rename(&b, &outside).unwrap();
+
+    let event = next(&mut watcher).await;
+    assert_eq!(event.kind(), &EventKind::MovedIn);
+    assert_eq!(event.path(), a);
+    let event = next(&mut watcher).await;
+    assert_eq!(event.kind(), &EventKind::Moved { from: a });
+    assert_eq!(event.path(), b);
+    let event = next(&mut watcher).await;
+    assert_eq!(event.kind(), &EventKind::MovedOut);
+    assert_eq!(event.path(), b);
+}
+
+#[monoio::test_all]
+async fn recursive() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("old")).unwrap();
+    let mut watcher = Watcher::new().unwrap();
+    watcher.watch_recursive(dir.path()).unwrap();
+
+    let old = dir.path().join("old").join("file");
+    fs::write(&old, b"").unwrap();
+    let event = next(&mut watcher).await;
+    assert_eq!(event.kind(), &EventKind::Created);
+    assert_eq!(event.path(), old);
+
+    // The new subdirectory is watched once its creation is received.
+    let new = dir.path().join("new");
+    fs::create_dir(&new).unwrap();
+    let event = next(&mut watcher).await;
+    assert_eq!(event.path(), new);
+    assert!(event.is_dir());
+    fs::write(new.join("file"), b"").unwrap();
+    let event = next(&mut watcher).await;
+    assert_eq!(event.kind(), &EventKind::Created);
+    assert_eq!(event.path(), new.join("file"));
+
+    // The watches follow the renamed directory.
+    let renamed = dir.path().join("renamed");
+    fs::rename(&new, &renamed).unwrap();
+    let event = next(&mut watcher).await;
+    assert_eq!(event.kind(), &EventKind::Moved { from: new });
+    fs::remove_file(renamed.join("file")).unwrap();
+    let event = next(&mut watcher).await;
+    assert_eq!(event.kind(), &EventKind::Removed);
+    assert_eq!(event.path(), renamed.join("file"));
+}
+
+#[monoio::test_all]
+async fn unwatch() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut watcher = Watcher::new().unwrap();
+    let wd = watcher.watch(dir.path()).unwrap();
+    let other = watcher.watch_with_mask(dir.path(), libc::IN_ATTRIB);
+    // Watching the same path returns the same watch with the new mask.
+    assert_eq!(other.unwrap(), wd);
+    fs::write(dir.path().join("file"), b"").unwrap();
+    fs::set_permissions(
+        dir.path().join("file"),
+        std::os::unix::fs::PermissionsExt::from_mode(0o600),
+    )
+    .unwrap();
+    assert_eq!(
+        next(&mut watcher).await.kind(),
+        &EventKind::Other(libc::IN_ATTRIB)
+    );
+    watcher.unwatch(wd).unwrap();
+    assert!(watcher.unwatch(wd).is_err());
+}
@@ -0,0 +1,80 @@
+#![cfg(target_os = "linux")]
+
+use monoio::fs::{File, XATTR_CREATE, XATTR_REPLACE};
+use tempfile::tempdir;
+
+#[monoio::test_all]
+async fn file_xattr() {
+    let tmpdir = tempdir().unwrap();
+    let file = File::create(tmpdir.path().join("xattr")).await.unwrap();
+    match file.set_xattr("user.monoio", b"value", 0).await {
+        // The file system may not support the user attributes.
+        Err(e) if e.raw_os_error() == Some(libc::EOPNOTSUPP) => return,
+        res => res.unwrap(),
+    }
+    assert_eq!(
+        file.get_xattr("user.monoio").await.unwrap().as_deref(),
+        Some(&b"value"[..])
+    );
+    assert_eq!(file.get_xattr("user.missing").await.unwrap(), None);
+
+    let err = file
+        .set_xattr("user.monoio", b"other", XATTR_CREATE)
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::AlreadyExists);
+    file.set_xattr("user.monoio", b"", XATTR_REPLACE)
+        .await
+        .unwrap();
+    assert_eq!(
+        file.get_xattr("user.monoio").await.unwrap().as_deref(),
+        Some(&b""[..])
+    );
+
+    file.set_xattr("user.other", &[7; 1000], 0).await.unwrap();
+    let mut names = file.list_xattr().await.unwrap();
+    names.sort();
+    assert_eq!(names, ["user.monoio", "user.other"]);
+    assert_eq!(
+        file.get_xattr("user.other").await.unwrap(),
+        Some(vec![7; 1000])
+    );
+
+    file.remove_xattr("user.monoio").await.unwrap();
+    assert_eq!(file.list_xattr().await.unwrap(), ["user.other"]);
+    let err = file.remove_xattr("user.monoio").await.unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::ENODATA));
+    file.close().await.unwrap();
+}
+
+#[monoio::test_all]
+async fn path_xattr() {
+    let tmpdir = tempdir().unwrap();
+    let path = tmpdir.path().join("xattr");
+    std::fs::write(&path, b"").unwrap();
+    match monoio::fs::set_xattr(&path, "user.monoio", b"value", 0).await {
+        Err(e) if e.raw_os_error() == Some(libc::EOPNOTSUPP) => return,
+        res => res.unwrap(),
+    }
+    assert_eq!(
+        monoio::fs::get_xattr(&path, "user.monoio").await.unwrap(),
+        Some(b"value".to_vec())
+    );
+    assert_eq!(
+        monoio::fs::list_xattr(&path).await.unwrap(),
+        ["user.monoio"]
+    );
+    monoio::fs::remove_xattr(&path, "user.monoio")
+        .await
+        .unwrap();
+    assert_eq!(
+        monoio::fs::get_xattr(&path, "user.monoio").await.unwrap(),
+        None
+    );
+    assert!(monoio::fs::list_xattr(&path).await.unwrap().is_empty());
+
+    let err = monoio::fs::get_xattr(tmpdir.path().join("missing"), "user.monoio")
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
@@ -0,0 +1,65 @@
+#![cfg(feature = "sync")]
+
+use std::{rc::Rc, sync::mpsc, thread};
+
+use futures::channel::oneshot;
+use monoio::runtime::Handle;
+
+/// Run a runtime on a new thread until the returned sender is dropped.
+fn runtime_thread() -> (Handle, oneshot::Sender<()>, thread::JoinHandle<()>) {
+    let (tx, rx) = mpsc::channel();
+    let (stop_tx, stop_rx) = oneshot::channel::<()>();
+    let thread = thread::spawn(move || {
+        let mut rt = monoio::RuntimeBuilder::<monoio::FusionDriver>::new()
+            .build()
+            .unwrap();
+        tx.send(rt.handle()).unwrap();
+        rt.block_on(async move {
+            let _ = stop_rx.await;
+        });
+    });
+    (rx.recv().unwrap(), stop_tx, thread)
+}
+
+#[test]
+fn spawn_on_cores() {
+    let runtimes: Vec<_> = (0..2).map(|_| runtime_thread()).collect();
+    for (handle, _, thread) in runtimes.iter() {
+        let id = handle.spawn(async { thread::current().id() });
+        assert_eq!(futures::executor::block_on(id), thread.thread().id());
+    }
+
+    // The future is created on the runtime thread, so it can hold `!Send` values.
+    let (handle, _, _) = &runtimes[0];
+    let join = handle.spawn_local_with(|| {
+        let local = Rc::new(1);
+        async move {
+            monoio::task::yield_now().await;
+            *local + 1
+        }
+    });
+    assert_eq!(futures::executor::block_on(join), 2);
+
+    for (_, stop, thread) in runtimes {
+        drop(stop);
+        thread.join().unwrap();
+    }
+}
+
+#[monoio::test_all]
+async fn spawn_on_current() {
+    let handle = Handle::current();
+    let join = thread::spawn(move || handle.spawn(async { thread::current().id() }))
+        .join()
+        .unwrap();
+    assert_eq!(join.await, thread::current().id());
+}
+
+#[test]
+#[should_panic = "runtime is gone"]
+fn runtime_gone() {
+    let (handle, stop, thread) = runtime_thread();
+    drop(stop);
+    thread.join().unwrap();
+    futures::executor::block_on(handle.spawn(async {}));
+}
@@ -0,0 +1,59 @@
+#![cfg(target_os = "linux")]
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use monoio::net::{icmp::ECHO_HEADER_LEN, IcmpSocket};
+
+fn skip(res: std::io::Result<IcmpSocket>) -> Option<IcmpSocket> {
+    match res {
+        Ok(socket) => Some(socket),
+        Err(e)
+            if matches!(
+                e.kind(),
+                std::io::ErrorKind::PermissionDenied | std::io::ErrorKind::AddrNotAvailable
+            ) =>
+        {
+            eprintln!("ping sockets are not allowed by net.ipv4.ping_group_range: {e}");
+            None
+        }
+        Err(e) => panic!("{e}"),
+    }
+}
+
+#[monoio::test_all]
+async fn echo_v4() {
+    let Some(socket) = skip(IcmpSocket::bind("0.0.0.0:0".parse().unwrap())) else {
+        return;
+    };
+    let ident = socket.ident().unwrap();
+    let target = IpAddr::V4(Ipv4Addr::LOCALHOST);
+    for seq in 1..=3 {
+        let (res, _) = socket.send_echo(target, seq, b"monoio").await;
+        assert_eq!(res.unwrap(), 6);
+        let (res, buf) = socket.recv_echo(vec![0; 64]).await;
+        let reply = res.unwrap();
+        assert_eq!(reply.addr(), target);
+        assert_eq!(reply.ident(), ident);
+        assert_eq!(reply.seq(), seq);
+        assert_eq!(reply.payload_len(), 6);
+        assert_eq!(&buf[ECHO_HEADER_LEN..ECHO_HEADER_LEN + 6], b"monoio");
+    }
+}
+
+#[monoio::test_all]
+async fn echo_v6() {
+    let addr = SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), 0);
+    let Some(socket) = skip(IcmpSocket::bind(addr)) else {
+        return;
+    };
+    let target = IpAddr::V6(Ipv6Addr::LOCALHOST);
+    let (res, _) = socket.send_echo(target, 7, vec![1u8; 32]).await;
+    if let Err(e) = res {
+        eprintln!("ipv6 loopback is unavailable: {e}");
+        return;
+    }
+    let (res, _) = socket.recv_echo(vec![0; 64]).await;
+    let reply = res.unwrap();
+    assert_eq!(reply.addr(), target);
+    assert_eq!(reply.seq(), 7);
+    assert_eq!(reply.payload_len(), 32);
+}
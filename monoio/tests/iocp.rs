@@ -0,0 +1,81 @@
+#![cfg(all(windows, feature = "iocp"))]
+
+use std::time::Duration;
+
+use monoio::{
+    io::{AsyncReadRent, AsyncReadRentExt, AsyncWriteRentExt, CancelableAsyncReadRent, Canceller},
+    net::{TcpListener, TcpStream},
+    time::TimeDriver,
+    IocpDriver, Runtime, RuntimeBuilder,
+};
+
+fn iocp_runtime() -> Runtime<TimeDriver<IocpDriver>> {
+    RuntimeBuilder::<IocpDriver>::new()
+        .enable_timer()
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn tcp_echo() {
+    iocp_runtime().block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = monoio::spawn(async move {
+            let (mut conn, peer) = listener.accept().await.unwrap();
+            assert_eq!(conn.peer_addr().unwrap(), peer);
+            let (res, buf) = conn.read_exact(vec![0; 5]).await;
+            res.unwrap();
+            conn.write_all(buf).await.0.unwrap();
+        });
+
+        let mut cli = TcpStream::connect(addr).await.unwrap();
+        assert_eq!(cli.peer_addr().unwrap(), addr);
+        cli.write_all(b"hello").await.0.unwrap();
+        let (res, buf) = cli.read_exact(vec![0; 5]).await;
+        res.unwrap();
+        assert_eq!(buf, b"hello");
+        server.await;
+    });
+}
+
+#[test]
+fn cancel_read() {
+    iocp_runtime().block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let mut cli = TcpStream::connect(addr).await.unwrap();
+        let (_conn, _) = listener.accept().await.unwrap();
+
+        let canceller = Canceller::new();
+        let handle = canceller.handle();
+        let read = cli.cancelable_read(vec![0; 5], handle);
+        monoio::spawn(async move {
+            monoio::time::sleep(Duration::from_millis(10)).await;
+            canceller.cancel();
+        });
+        let (res, _) = read.await;
+        assert!(res.is_err());
+    });
+}
+
+// The dropped read is only cancelled with async-cancel.
+#[cfg(feature = "async-cancel")]
+#[test]
+fn drop_in_flight_read() {
+    iocp_runtime().block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let mut cli = TcpStream::connect(addr).await.unwrap();
+        let (mut conn, _) = listener.accept().await.unwrap();
+
+        // The buffer is kept by the driver until the cancelled read completes.
+        let read = monoio::time::timeout(Duration::from_millis(10), cli.read(vec![0; 5])).await;
+        assert!(read.is_err());
+
+        conn.write_all(b"hello").await.0.unwrap();
+        let (res, buf) = cli.read_exact(vec![0; 5]).await;
+        res.unwrap();
+        assert_eq!(buf, b"hello");
+    });
+}
@@ -0,0 +1,131 @@
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+use monoio::sync::{broadcast, mpsc, oneshot, watch};
+
+#[monoio::test_all(timer_enabled = true)]
+async fn mpsc_backpressure() {
+    let (tx, mut rx) = mpsc::channel(2);
+    let sent = Rc::new(RefCell::new(Vec::new()));
+    for i in 0..3 {
+        let tx = tx.clone();
+        let sent = sent.clone();
+        monoio::spawn(async move {
+            for j in 0..2 {
+                tx.send(i * 10 + j).await.unwrap();
+                sent.borrow_mut().push(i * 10 + j);
+            }
+        });
+    }
+    monoio::time::sleep(Duration::from_millis(10)).await;
+    // The senders wait for the capacity.
+    assert_eq!(sent.borrow().len(), 2);
+    assert_eq!(tx.capacity(), 0);
+    assert_eq!(tx.try_send(100), Err(mpsc::TrySendError::Full(100)));
+    drop(tx);
+
+    let mut received = Vec::new();
+    while let Some(i) = rx.recv().await {
+        received.push(i);
+    }
+    // The waiting senders are served in order.
+    assert_eq!(received, *sent.borrow());
+    assert_eq!(received.len(), 6);
+    assert_eq!(rx.try_recv(), Err(mpsc::TryRecvError::Disconnected));
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn mpsc_close() {
+    let (tx, mut rx) = mpsc::channel(1);
+    tx.send(1).await.unwrap();
+    let waiting = monoio::spawn({
+        let tx = tx.clone();
+        async move { tx.send(2).await }
+    });
+    monoio::time::sleep(Duration::from_millis(10)).await;
+    rx.close();
+    assert_eq!(waiting.await, Err(mpsc::SendError(2)));
+    assert!(tx.is_closed());
+    // The buffered value can still be received.
+    assert_eq!(rx.recv().await, Some(1));
+    assert_eq!(rx.recv().await, None);
+
+    let (tx, rx) = mpsc::unbounded();
+    for i in 0..100 {
+        tx.send(i).unwrap();
+    }
+    assert_eq!(rx.len(), 100);
+    drop(rx);
+    assert_eq!(tx.send(0), Err(mpsc::SendError(0)));
+}
+
+#[monoio::test_all]
+async fn oneshot() {
+    let (tx, rx) = oneshot::channel();
+    monoio::spawn(async move { tx.send(1) });
+    assert_eq!(rx.await, Ok(1));
+
+    let (tx, rx) = oneshot::channel::<()>();
+    drop(tx);
+    assert!(rx.await.is_err());
+
+    let (mut tx, mut rx) = oneshot::channel();
+    assert_eq!(rx.try_recv(), Err(oneshot::TryRecvError::Empty));
+    let closed = monoio::spawn(async move {
+        tx.closed().await;
+        tx.send(1)
+    });
+    rx.close();
+    assert_eq!(closed.await, Err(1));
+}
+
+#[monoio::test_all]
+async fn broadcast() {
+    let (tx, mut rx1) = broadcast::channel(2);
+    let mut rx2 = tx.subscribe();
+    let join = monoio::spawn(async move {
+        let mut received = Vec::new();
+        while let Ok(i) = rx2.recv().await {
+            received.push(i);
+        }
+        received
+    });
+    for i in 0..3 {
+        assert_eq!(tx.send(i), Ok(2));
+        monoio::task::yield_now().await;
+    }
+    drop(tx);
+    assert_eq!(join.await, [0, 1, 2]);
+
+    // The first value is overwritten.
+    assert_eq!(rx1.recv().await, Err(broadcast::RecvError::Lagged(1)));
+    assert_eq!(rx1.recv().await, Ok(1));
+    assert_eq!(rx1.recv().await, Ok(2));
+    assert_eq!(rx1.recv().await, Err(broadcast::RecvError::Closed));
+}
+
+#[monoio::test_all]
+async fn watch() {
+    let (tx, rx1) = watch::channel(0);
+    let mut rx2 = rx1.clone();
+    assert_eq!(rx1.has_changed(), Ok(false));
+    let join = monoio::spawn(async move {
+        let mut seen = Vec::new();
+        while rx2.changed().await.is_ok() {
+            seen.push(*rx2.borrow_and_update());
+        }
+        seen
+    });
+    monoio::task::yield_now().await;
+    tx.send(1).unwrap();
+    tx.send_modify(|value| *value += 1);
+    monoio::task::yield_now().await;
+    tx.send_replace(3);
+    monoio::task::yield_now().await;
+    assert_eq!(tx.receiver_count(), 2);
+    drop(tx);
+    // Only the latest value is seen.
+    assert_eq!(join.await, [2, 3]);
+
+    assert!(rx1.has_changed().is_err());
+    assert_eq!(*rx1.borrow(), 3);
+}
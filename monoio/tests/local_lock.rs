@@ -0,0 +1,117 @@
+use std::{cell::RefCell, rc::Rc, time::Duration};
+
+use monoio::sync::{Mutex, RwLock, Semaphore, TryAcquireError};
+
+#[monoio::test_all(timer_enabled = true)]
+async fn semaphore_fifo() {
+    let sem = Rc::new(Semaphore::new(2));
+    let held = sem.clone().acquire_many_owned(2).await.unwrap();
+    let order = Rc::new(RefCell::new(Vec::new()));
+    for (i, n) in [2, 1, 1].into_iter().enumerate() {
+        let sem = sem.clone();
+        let order = order.clone();
+        monoio::spawn(async move {
+            let permit = sem.acquire_many(n).await.unwrap();
+            order.borrow_mut().push(i);
+            monoio::time::sleep(Duration::from_millis(5)).await;
+            drop(permit);
+        });
+    }
+    monoio::time::sleep(Duration::from_millis(10)).await;
+    assert!(order.borrow().is_empty());
+    // The waiters are not jumped even if there is a permit for one of them.
+    held.forget();
+    sem.add_permits(1);
+    assert_eq!(sem.try_acquire().unwrap_err(), TryAcquireError::NoPermits);
+    sem.add_permits(1);
+    monoio::time::sleep(Duration::from_millis(30)).await;
+    assert_eq!(*order.borrow(), [0, 1, 2]);
+    assert_eq!(sem.available_permits(), 2);
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn semaphore_close_and_cancel() {
+    let sem = Rc::new(Semaphore::new(1));
+    let permit = sem.try_acquire().unwrap();
+    // A dropped waiter passes on its turn.
+    let cancelled = monoio::spawn({
+        let sem = sem.clone();
+        async move {
+            monoio::select! {
+                _ = sem.acquire_many(2) => unreachable!(),
+                _ = monoio::time::sleep(Duration::from_millis(5)) => {}
+            }
+        }
+    });
+    let waiting = monoio::spawn({
+        let sem = sem.clone();
+        async move { sem.acquire().await.map(|p| p.forget()) }
+    });
+    cancelled.await;
+    drop(permit);
+    assert_eq!(waiting.await, Ok(()));
+    assert_eq!(sem.available_permits(), 0);
+
+    let waiting = monoio::spawn({
+        let sem = sem.clone();
+        async move { sem.acquire().await.is_err() }
+    });
+    monoio::time::sleep(Duration::from_millis(5)).await;
+    sem.close();
+    assert!(waiting.await);
+    assert_eq!(sem.try_acquire().unwrap_err(), TryAcquireError::Closed);
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn mutex_owned_guard() {
+    let mutex = Rc::new(Mutex::new(Vec::new()));
+    let guard = mutex.clone().lock_owned().await;
+    let joins: Vec<_> = (0..3)
+        .map(|i| {
+            let mutex = mutex.clone();
+            monoio::spawn(async move {
+                let mut guard = mutex.lock().await;
+                monoio::time::sleep(Duration::from_millis(1)).await;
+                guard.push(i);
+            })
+        })
+        .collect();
+    // The owned guard is released in another task.
+    monoio::spawn(async move {
+        monoio::time::sleep(Duration::from_millis(5)).await;
+        drop(guard);
+    });
+    assert!(mutex.try_lock().is_err());
+    for join in joins {
+        join.await;
+    }
+    assert_eq!(*mutex.try_lock().unwrap(), [0, 1, 2]);
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn rwlock_writer_blocks_readers() {
+    let lock = Rc::new(RwLock::new(0));
+    let reader = lock.clone().read_owned().await;
+    let writer = monoio::spawn({
+        let lock = lock.clone();
+        async move {
+            *lock.write().await += 1;
+        }
+    });
+    monoio::time::sleep(Duration::from_millis(5)).await;
+    // The later readers wait for the writer queued before them.
+    assert!(lock.try_read().is_err());
+    let late = monoio::spawn({
+        let lock = lock.clone();
+        async move { *lock.read().await }
+    });
+    monoio::time::sleep(Duration::from_millis(5)).await;
+    assert_eq!(*reader, 0);
+    drop(reader);
+    writer.await;
+    assert_eq!(late.await, 1);
+    let r1 = lock.try_read().unwrap();
+    let r2 = lock.clone().try_read_owned().unwrap();
+    assert_eq!(*r1 + *r2, 2);
+    assert!(lock.try_write().is_err());
+}
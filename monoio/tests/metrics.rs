@@ -0,0 +1,131 @@
+use std::time::Duration;
+
+use monoio::net::{TcpListener, TcpStream};
+
+#[monoio::test_all(timer_enabled = true)]
+async fn tasks() {
+    let before = monoio::runtime::metrics();
+    let pending = monoio::spawn(monoio::time::sleep(Duration::from_secs(3600)));
+    monoio::spawn(async {}).await;
+
+    let metrics = monoio::runtime::metrics();
+    assert_eq!(metrics.tasks_spawned, before.tasks_spawned + 2);
+    assert_eq!(metrics.tasks_alive, before.tasks_alive + 1);
+    assert!(metrics.tasks_polled >= before.tasks_polled + 2);
+
+    // Aborted tasks are not alive either.
+    pending.abort();
+    monoio::time::sleep(Duration::from_millis(10)).await;
+    let metrics = monoio::runtime::metrics();
+    assert_eq!(metrics.tasks_alive, before.tasks_alive);
+    assert!(metrics.park_count > before.park_count);
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn in_flight() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let accept = monoio::spawn(async move { listener.accept().await.unwrap() });
+    monoio::time::sleep(Duration::from_millis(10)).await;
+
+    let metrics = monoio::runtime::metrics();
+    if !monoio::utils::is_legacy() {
+        assert!(metrics.in_flight_ops >= 1);
+        assert!(metrics.slab_size >= metrics.in_flight_ops);
+        assert!(metrics.sq_high_water >= 1);
+    }
+
+    let _client = TcpStream::connect(addr).await.unwrap();
+    accept.await;
+    if !monoio::utils::is_legacy() {
+        assert!(monoio::runtime::metrics().cq_high_water >= 1);
+    }
+}
+
+#[cfg(feature = "sync")]
+#[test]
+fn blocking_queue_depth() {
+    use std::sync::{Arc, Mutex};
+
+    use monoio::blocking::{BlockingTask, ThreadPool};
+
+    // Holds the tasks until they are run explicitly.
+    #[derive(Clone, Default)]
+    struct ManualPool(Arc<Mutex<Vec<BlockingTask>>>);
+
+    impl ThreadPool for ManualPool {
+        fn schedule_task(&self, task: BlockingTask) {
+            self.0.lock().unwrap().push(task);
+        }
+    }
+
+    let pool = ManualPool::default();
+    let mut rt = monoio::RuntimeBuilder::<monoio::FusionDriver>::new()
+        .attach_thread_pool(Box::new(pool.clone()))
+        .build()
+        .unwrap();
+    rt.block_on(async move {
+        let a = monoio::spawn_blocking(|| 1);
+        let b = monoio::spawn_blocking(|| 2);
+        assert_eq!(monoio::runtime::metrics().blocking_queue_depth, 2);
+
+        let tasks = std::mem::take(&mut *pool.0.lock().unwrap());
+        let mut tasks = tasks.into_iter();
+        tasks.next().unwrap().run();
+        assert_eq!(monoio::runtime::metrics().blocking_queue_depth, 1);
+        // Dropped tasks are not queued either.
+        drop(tasks);
+        assert_eq!(monoio::runtime::metrics().blocking_queue_depth, 0);
+
+        assert_eq!(a.await.unwrap(), 1);
+        assert!(b.await.is_err());
+    });
+}
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+#[test]
+fn op_latency() {
+    // IORING_OP_ACCEPT
+    const ACCEPT: u8 = 13;
+
+    let slow = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let slow_ops = slow.clone();
+    let Ok(mut rt) = monoio::RuntimeBuilder::<monoio::IoUringDriver>::new()
+        .enable_timer()
+        .uring_op_latency(true, Some(Duration::from_millis(10)))
+        .uring_slow_op_callback(move |op| slow_ops.lock().unwrap().push(*op))
+        .build()
+    else {
+        return;
+    };
+    rt.block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let accept = monoio::spawn(async move { listener.accept().await.unwrap() });
+        monoio::time::sleep(Duration::from_millis(20)).await;
+        let _client = TcpStream::connect(addr).await.unwrap();
+        accept.await;
+
+        let histograms = monoio::runtime::op_latency();
+        let (_, accept) = histograms
+            .iter()
+            .find(|(opcode, _)| *opcode == ACCEPT)
+            .unwrap();
+        assert_eq!(accept.count(), 1);
+        assert!(accept.min() >= Duration::from_millis(20));
+        assert_eq!(accept.value_at_quantile(0.99), accept.max());
+        assert!(histograms.windows(2).all(|w| w[0].0 < w[1].0));
+    });
+    let slow = slow.lock().unwrap();
+    let op = slow.iter().find(|op| op.opcode == ACCEPT).unwrap();
+    assert!(op.latency >= Duration::from_millis(20));
+
+    // Not recorded unless enabled.
+    let mut rt = monoio::RuntimeBuilder::<monoio::IoUringDriver>::new()
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let _ = monoio::fs::File::open("Cargo.toml").await;
+        assert!(monoio::runtime::op_latency().is_empty());
+    });
+}
@@ -0,0 +1,124 @@
+#![cfg(target_os = "linux")]
+use monoio::net::{
+    netlink::{messages, NetlinkMessage},
+    NetlinkSocket,
+};
+
+// struct ifinfomsg of linux/rtnetlink.h.
+fn ifinfomsg(index: i32) -> Vec<u8> {
+    let mut payload = vec![0u8; 16];
+    payload[4..8].copy_from_slice(&index.to_ne_bytes());
+    payload
+}
+
+#[monoio::test_all]
+async fn dump_links() {
+    let socket = NetlinkSocket::new(libc::NETLINK_ROUTE).unwrap();
+    let port_id = socket.port_id().unwrap();
+    assert_ne!(port_id, 0);
+    let payload = ifinfomsg(0);
+    let flags = (libc::NLM_F_REQUEST | libc::NLM_F_DUMP) as u16;
+    let request = NetlinkMessage::new(libc::RTM_GETLINK, flags, 1, &payload);
+    socket.send_message(&request).await.unwrap();
+
+    let mut links = 0;
+    let mut buf = vec![0; 32 * 1024];
+    'recv: loop {
+        let (res, b) = socket.recv(buf).await;
+        buf = b;
+        let n = res.unwrap();
+        for msg in messages(&buf[..n]) {
+            let msg = msg.unwrap();
+            assert_eq!(msg.seq(), 1);
+            assert_eq!(msg.port_id(), port_id);
+            if msg.is_done() {
+                break 'recv;
+            }
+            assert!(msg.error().is_none());
+            assert_eq!(msg.ty(), libc::RTM_NEWLINK);
+            links += 1;
+        }
+    }
+    // The loopback interface at least.
+    assert!(links >= 1);
+}
+
+#[monoio::test_all]
+async fn error_ack() {
+    let socket = NetlinkSocket::new(libc::NETLINK_ROUTE).unwrap();
+    let payload = ifinfomsg(i32::MAX);
+    let flags = (libc::NLM_F_REQUEST | libc::NLM_F_ACK) as u16;
+    let request = NetlinkMessage::new(libc::RTM_GETLINK, flags, 7, &payload);
+    socket.send_message(&request).await.unwrap();
+
+    let (res, buf) = socket.recv(vec![0; 4096]).await;
+    let n = res.unwrap();
+    let msg = messages(&buf[..n]).next().unwrap().unwrap();
+    assert_eq!(msg.seq(), 7);
+    let err = msg.error().unwrap().unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::ENODEV));
+}
+
+#[monoio::test_all]
+async fn multicast() {
+    const GROUP: u32 = 3;
+    let receiver = NetlinkSocket::new(libc::NETLINK_USERSOCK).unwrap();
+    receiver.add_membership(GROUP).unwrap();
+    let sender = NetlinkSocket::new(libc::NETLINK_USERSOCK).unwrap();
+
+    let msg = NetlinkMessage::new(0x100, 0, 42, b"hello").to_vec();
+    let mut addr: libc::sockaddr_nl = unsafe { std::mem::zeroed() };
+    addr.nl_family = libc::AF_NETLINK as _;
+    addr.nl_groups = 1 << (GROUP - 1);
+    let ret = unsafe {
+        libc::sendto(
+            std::os::fd::AsRawFd::as_raw_fd(&sender),
+            msg.as_ptr() as *const _,
+            msg.len(),
+            0,
+            &addr as *const _ as *const libc::sockaddr,
+            std::mem::size_of::<libc::sockaddr_nl>() as _,
+        )
+    };
+    if ret < 0 {
+        let e = std::io::Error::last_os_error();
+        match e.kind() {
+            // The message is broadcast before the unicast to port 0, where there is no
+            // kernel socket of NETLINK_USERSOCK.
+            std::io::ErrorKind::ConnectionRefused => {}
+            std::io::ErrorKind::PermissionDenied => {
+                eprintln!("sending to netlink multicast groups requires CAP_NET_ADMIN: {e}");
+                return;
+            }
+            _ => panic!("{e}"),
+        }
+    }
+
+    let (res, buf) = receiver.recv(vec![0; 256]).await;
+    let n = res.unwrap();
+    let msg = messages(&buf[..n]).next().unwrap().unwrap();
+    assert_eq!(msg.ty(), 0x100);
+    assert_eq!(msg.seq(), 42);
+    assert_eq!(msg.payload(), b"hello");
+    receiver.drop_membership(GROUP).unwrap();
+}
+
+#[test]
+fn framing() {
+    let mut buf = Vec::new();
+    let first = NetlinkMessage::new(16, 1, 1, b"abc");
+    first.encode(&mut buf);
+    assert_eq!(buf.len(), first.encoded_len());
+    assert_eq!(buf.len(), 20);
+    NetlinkMessage::new(17, 2, 2, &[]).encode(&mut buf);
+
+    let parsed = messages(&buf).collect::<std::io::Result<Vec<_>>>().unwrap();
+    assert_eq!(parsed.len(), 2);
+    assert_eq!(parsed[0], first);
+    assert_eq!(parsed[1].ty(), 17);
+    assert!(parsed[1].payload().is_empty());
+
+    let mut iter = messages(&buf[..10]);
+    assert!(iter.next().unwrap().is_err());
+    assert!(iter.next().is_none());
+}
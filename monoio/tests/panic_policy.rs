@@ -0,0 +1,68 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use monoio::runtime::PanicPolicy;
+
+#[test]
+fn panic_hook() {
+    let panics = Arc::new(Mutex::new(Vec::new()));
+    let panics_ = panics.clone();
+    let mut rt = monoio::RuntimeBuilder::<monoio::FusionDriver>::new()
+        .enable_timer()
+        .with_panic_hook(move |panic| {
+            let message = panic.message().unwrap().to_string();
+            panics_
+                .lock()
+                .unwrap()
+                .push((message, panic.spawned_at().line()));
+        })
+        .build()
+        .unwrap();
+    let line = rt.block_on(async {
+        let line = line!() + 1;
+        let panicked = monoio::spawn(async {
+            monoio::time::sleep(Duration::from_millis(1)).await;
+            panic!("task panicked");
+        });
+        let ok = monoio::spawn(async {
+            monoio::time::sleep(Duration::from_millis(10)).await;
+            1
+        });
+        // The other tasks keep running.
+        assert_eq!(ok.await, 1);
+        assert!(panicked.is_finished());
+        line
+    });
+
+    let panics = panics.lock().unwrap();
+    assert_eq!(*panics, [("task panicked".to_string(), line)]);
+}
+
+#[test]
+fn panic_log_join() {
+    let mut rt = monoio::RuntimeBuilder::<monoio::FusionDriver>::new()
+        .with_panic_policy(PanicPolicy::Log)
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let panicked = monoio::spawn(async { panic!("task panicked") });
+        // Awaiting the panicked task panics, which is caught as well.
+        let joiner = monoio::spawn(panicked);
+        while !joiner.is_finished() {
+            monoio::task::yield_now().await;
+        }
+    });
+}
+
+#[test]
+#[should_panic(expected = "task panicked")]
+fn panic_propagate() {
+    let mut rt = monoio::RuntimeBuilder::<monoio::FusionDriver>::new()
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        monoio::spawn(async { panic!("task panicked") }).await;
+    });
+}
@@ -0,0 +1,128 @@
+#![cfg(target_os = "linux")]
+use std::{process::Stdio, time::Duration};
+
+use monoio::{
+    io::{AsyncReadRentExt, AsyncWriteRentExt},
+    process::{Command, PidFd},
+};
+
+#[monoio::test_all]
+async fn output() {
+    let output = Command::new("sh")
+        .args(["-c", "echo out; echo err >&2; exit 3"])
+        .output()
+        .await
+        .unwrap();
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(output.stdout, b"out\n");
+    assert_eq!(output.stderr, b"err\n");
+}
+
+#[monoio::test_all]
+async fn piped_stdio() {
+    let mut child = Command::new("cat")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    let (res, _) = stdin.write_all(b"hello monoio").await;
+    res.unwrap();
+    drop(stdin);
+
+    let mut stdout = child.stdout.take().unwrap();
+    let (res, buf) = stdout.read_exact(vec![0; 12]).await;
+    assert_eq!(res.unwrap(), 12);
+    assert_eq!(buf, b"hello monoio");
+    assert!(child.wait().await.unwrap().success());
+    // The status is kept after the child is reaped.
+    assert!(child.try_wait().unwrap().unwrap().success());
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn wait_concurrently() {
+    let begin = std::time::Instant::now();
+    let tasks = (0..4)
+        .map(|_| {
+            let mut child = Command::new("sleep").arg("0.2").spawn().unwrap();
+            monoio::spawn(async move { child.wait().await.unwrap() })
+        })
+        .collect::<Vec<_>>();
+    let ticker = monoio::spawn(async {
+        // The thread is not blocked while waiting.
+        monoio::time::sleep(Duration::from_millis(50)).await;
+    });
+    for task in tasks {
+        assert!(task.await.success());
+    }
+    ticker.await;
+    assert!(begin.elapsed() < Duration::from_secs(2));
+}
+
+#[monoio::test_all]
+async fn kill() {
+    let mut child = Command::new("sleep").arg("10").spawn().unwrap();
+    assert!(child.try_wait().unwrap().is_none());
+    child.kill().unwrap();
+    let status = child.wait().await.unwrap();
+    assert!(!status.success());
+}
+
+#[monoio::test_all]
+async fn spawn_error() {
+    let err = Command::new("/nonexistent/monoio").spawn().unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+}
+
+// The children are reaped through the pidfd.
+#[allow(clippy::zombie_processes)]
+fn spawn(cmd: &mut std::process::Command) -> u32 {
+    cmd.spawn().unwrap().id()
+}
+
+#[monoio::test_all]
+async fn pidfd_wait() {
+    let pid = spawn(std::process::Command::new("sh").args(["-c", "exit 7"]));
+    let pidfd = PidFd::open(pid).unwrap();
+    assert_eq!(pidfd.pid(), pid);
+    let status = pidfd.wait().await.unwrap();
+    assert_eq!(status.code(), Some(7));
+    // It has been reaped.
+    assert_eq!(
+        pidfd.try_wait().unwrap_err().raw_os_error(),
+        Some(libc::ECHILD)
+    );
+}
+
+#[monoio::test_all]
+async fn pidfd_send_signal() {
+    use std::os::unix::process::ExitStatusExt;
+
+    let pid = spawn(std::process::Command::new("sleep").arg("10"));
+    let pidfd = PidFd::open(pid).unwrap();
+    assert!(pidfd.try_wait().unwrap().is_none());
+    pidfd.send_signal(libc::SIGTERM).unwrap();
+    pidfd.exited().await.unwrap();
+    let status = pidfd.wait().await.unwrap();
+    assert_eq!(status.signal(), Some(libc::SIGTERM));
+}
+
+#[monoio::test_all]
+async fn pidfd_many() {
+    let pidfds = (0..8)
+        .map(|i| {
+            let script = format!("sleep 0.0{i}; exit {i}");
+            PidFd::open(spawn(
+                std::process::Command::new("sh").args(["-c", &script]),
+            ))
+            .unwrap()
+        })
+        .collect::<Vec<_>>();
+    let tasks = pidfds
+        .into_iter()
+        .map(|pidfd| monoio::spawn(async move { pidfd.wait().await.unwrap().code() }))
+        .collect::<Vec<_>>();
+    for (i, task) in tasks.into_iter().enumerate() {
+        assert_eq!(task.await, Some(i as i32));
+    }
+}
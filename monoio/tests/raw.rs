@@ -0,0 +1,109 @@
+#![cfg(target_os = "linux")]
+use std::net::{SocketAddr, UdpSocket};
+
+use monoio::net::RawSocket;
+
+fn skip(res: std::io::Result<RawSocket>) -> Option<RawSocket> {
+    match res {
+        Ok(socket) => Some(socket),
+        Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+            eprintln!("raw sockets require CAP_NET_RAW: {e}");
+            None
+        }
+        Err(e) => panic!("{e}"),
+    }
+}
+
+fn checksum(data: &[u8]) -> u16 {
+    let mut sum = data
+        .chunks(2)
+        .map(|c| u16::from_be_bytes([c[0], *c.get(1).unwrap_or(&0)]) as u32)
+        .sum::<u32>();
+    while sum > 0xffff {
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+#[monoio::test_all]
+async fn icmp_echo() {
+    let Some(socket) = skip(RawSocket::ipv4(libc::IPPROTO_ICMP)) else {
+        return;
+    };
+    let id = std::process::id() as u16;
+    // Echo request: type 8, code 0, checksum, id, sequence 1, payload.
+    let mut request = vec![8, 0, 0, 0];
+    request.extend_from_slice(&id.to_be_bytes());
+    request.extend_from_slice(&1u16.to_be_bytes());
+    request.extend_from_slice(b"monoio");
+    let sum = checksum(&request);
+    request[2..4].copy_from_slice(&sum.to_be_bytes());
+
+    let target: SocketAddr = "127.0.0.1:0".parse().unwrap();
+    let (res, _) = socket.send_to(request, target).await;
+    assert_eq!(res.unwrap(), 14);
+
+    // The request itself is received too on the loopback interface.
+    loop {
+        let (res, buf) = socket.recv_from(vec![0; 256]).await;
+        let (n, from) = res.unwrap();
+        assert_eq!(from.ip(), target.ip());
+        let ihl = (buf[0] & 0x0f) as usize * 4;
+        let icmp = &buf[ihl..n];
+        if icmp[0] == 0 && icmp[4..6] == id.to_be_bytes() {
+            assert_eq!(&icmp[8..], b"monoio");
+            break;
+        }
+    }
+}
+
+#[monoio::test_all]
+async fn packet_capture() {
+    let Some(socket) = skip(RawSocket::packet(libc::ETH_P_IP as u16)) else {
+        return;
+    };
+    let lo = unsafe { libc::if_nametoindex(c"lo".as_ptr()) };
+    assert_ne!(lo, 0);
+    socket.bind_interface(lo).unwrap();
+    // Accept whole frames: `ret #0xffff`.
+    let accept_all = [libc::sock_filter {
+        code: 0x06,
+        jt: 0,
+        jf: 0,
+        k: 0xffff,
+    }];
+    socket.attach_filter(&accept_all).unwrap();
+    assert!(socket.recv_from(vec![0; 16]).await.0.is_err());
+
+    let payload = format!("monoio-raw-{}", std::process::id());
+    let rx = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let tx = UdpSocket::bind("127.0.0.1:0").unwrap();
+    tx.send_to(payload.as_bytes(), rx.local_addr().unwrap())
+        .unwrap();
+
+    // Other traffic on the loopback interface may be captured too.
+    loop {
+        let (res, buf) = socket.recv(vec![0; 2048]).await;
+        let n = res.unwrap();
+        if buf[..n]
+            .windows(payload.len())
+            .any(|w| w == payload.as_bytes())
+        {
+            // The ethernet header of the loopback interface is zeroed, except the type.
+            assert_eq!(buf[12..14], (libc::ETH_P_IP as u16).to_be_bytes());
+            break;
+        }
+    }
+    socket.detach_filter().unwrap();
+}
+
+#[monoio::test_all]
+async fn bind_interface_requires_packet_socket() {
+    let Some(socket) = skip(RawSocket::ipv4(libc::IPPROTO_ICMP)) else {
+        return;
+    };
+    assert_eq!(
+        socket.bind_interface(1).unwrap_err().kind(),
+        std::io::ErrorKind::InvalidInput
+    );
+}
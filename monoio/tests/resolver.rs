@@ -0,0 +1,174 @@
+use std::{
+    io,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+};
+
+use monoio::net::{
+    resolver::{lookup_host, lookup_host_with, GaiResolver, Resolve},
+    TcpListener, TcpStream,
+};
+
+struct FixedResolver(IpAddr);
+
+impl Resolve for FixedResolver {
+    async fn resolve(&self, host: &str, port: u16) -> io::Result<Vec<SocketAddr>> {
+        match host {
+            "service.test" => Ok(vec![SocketAddr::new(self.0, port)]),
+            _ => Err(io::ErrorKind::NotFound.into()),
+        }
+    }
+}
+
+#[monoio::test_all]
+async fn lookup() {
+    let addr: SocketAddr = "127.0.0.1:80".parse().unwrap();
+    assert_eq!(lookup_host("127.0.0.1:80").await.unwrap(), [addr]);
+    assert_eq!(lookup_host(("127.0.0.1", 80)).await.unwrap(), [addr]);
+    assert_eq!(lookup_host(&addr).await.unwrap(), [addr]);
+    assert!(lookup_host("127.0.0.1").await.is_err());
+
+    let addrs = lookup_host(("localhost", 80)).await.unwrap();
+    assert!(addrs.iter().all(|addr| addr.ip().is_loopback()));
+
+    let resolver = FixedResolver(Ipv4Addr::LOCALHOST.into());
+    let addrs = lookup_host_with(&resolver, "service.test:8080")
+        .await
+        .unwrap();
+    assert_eq!(addrs, ["127.0.0.1:8080".parse::<SocketAddr>().unwrap()]);
+    assert!(lookup_host_with(&resolver, "other.test:8080")
+        .await
+        .is_err());
+}
+
+#[monoio::test_all]
+async fn connect_host() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let stream = TcpStream::connect_with_resolver(&GaiResolver, format!("localhost:{port}")).await;
+    // localhost may be resolved to ::1 first.
+    if let Ok(stream) = stream {
+        assert_eq!(stream.peer_addr().unwrap().port(), port);
+    }
+    let stream = TcpStream::connect_with_resolver(&GaiResolver, ("127.0.0.1".to_string(), port))
+        .await
+        .unwrap();
+    assert_eq!(stream.peer_addr().unwrap(), listener.local_addr().unwrap());
+
+    let resolver = FixedResolver(Ipv4Addr::LOCALHOST.into());
+    let stream = TcpStream::connect_with_resolver(&resolver, ("service.test", port))
+        .await
+        .unwrap();
+    assert_eq!(stream.peer_addr().unwrap(), listener.local_addr().unwrap());
+}
+
+#[monoio::test_all]
+async fn connect_std_addrs() {
+    // A type implementing only `std::net::ToSocketAddrs`.
+    struct Local(u16);
+
+    impl std::net::ToSocketAddrs for Local {
+        type Iter = std::option::IntoIter<SocketAddr>;
+
+        fn to_socket_addrs(&self) -> io::Result<Self::Iter> {
+            Ok(Some(SocketAddr::new(Ipv4Addr::LOCALHOST.into(), self.0)).into_iter())
+        }
+    }
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let stream = TcpStream::connect(Local(port)).await.unwrap();
+    assert_eq!(stream.peer_addr().unwrap(), listener.local_addr().unwrap());
+}
+
+#[cfg(feature = "stub-resolver")]
+#[monoio::test_all(timer_enabled = true)]
+async fn stub_resolver() {
+    use monoio::{
+        io::{AsyncReadRentExt, AsyncWriteRentExt},
+        net::{resolver::StubResolver, udp::UdpSocket},
+    };
+
+    // Answer the A queries over UDP and the AAAA queries only over TCP.
+    fn respond(query: &[u8], tcp: bool) -> Vec<u8> {
+        let qtype = u16::from_be_bytes([query[query.len() - 4], query[query.len() - 3]]);
+        let mut resp = query.to_vec();
+        // QR, RD and RA
+        resp[2] = 0x81;
+        resp[3] = 0x80;
+        if qtype == 28 && !tcp {
+            // TC
+            resp[2] |= 0x02;
+            return resp;
+        }
+        resp[7] = 1;
+        // A pointer to the question name, type, class, ttl.
+        resp.extend_from_slice(&[0xc0, 12]);
+        resp.extend_from_slice(&qtype.to_be_bytes());
+        resp.extend_from_slice(&[0, 1, 0, 0, 0, 60]);
+        if qtype == 1 {
+            resp.extend_from_slice(&[0, 4, 10, 0, 0, 1]);
+        } else {
+            resp.extend_from_slice(&[0, 16]);
+            resp.extend_from_slice(&[0xfd, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+        }
+        resp
+    }
+
+    let udp = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let ns = udp.local_addr().unwrap();
+    let tcp = TcpListener::bind(ns).unwrap();
+    monoio::spawn(async move {
+        loop {
+            let (res, buf) = udp.recv_from(vec![0; 512]).await;
+            let (_, peer) = res.unwrap();
+            let resp = respond(&buf, false);
+            udp.send_to(resp, peer).await.0.unwrap();
+        }
+    });
+    monoio::spawn(async move {
+        loop {
+            let (mut stream, _) = tcp.accept().await.unwrap();
+            let (res, len) = stream.read_exact(vec![0; 2]).await;
+            res.unwrap();
+            let len = u16::from_be_bytes([len[0], len[1]]) as usize;
+            let (res, query) = stream.read_exact(vec![0; len]).await;
+            res.unwrap();
+            let resp = respond(&query, true);
+            let mut out = (resp.len() as u16).to_be_bytes().to_vec();
+            out.extend_from_slice(&resp);
+            stream.write_all(out).await.0.unwrap();
+        }
+    });
+
+    let resolver = StubResolver::new(vec![ns]);
+    let addrs = lookup_host_with(&resolver, "service.test:443")
+        .await
+        .unwrap();
+    assert_eq!(
+        addrs,
+        [
+            "10.0.0.1:443".parse::<SocketAddr>().unwrap(),
+            "[fd00::1]:443".parse().unwrap()
+        ]
+    );
+    let addrs = lookup_host_with(&resolver, "localhost:80").await.unwrap();
+    assert!(addrs.iter().all(|addr| addr.ip().is_loopback()));
+
+    let conf = "# comment\nnameserver 192.0.2.1\nnameserver fe80::1%eth0\nsearch test\n";
+    let resolver = StubResolver::from_resolv_conf(conf);
+    assert_eq!(resolver.nameservers(), ["192.0.2.1:53".parse().unwrap()]);
+}
+
+#[cfg(feature = "sync")]
+#[test]
+fn lookup_without_blocking_pool() {
+    // The lookup blocks the current thread instead of panicking in `spawn_blocking`.
+    let mut rt = monoio::RuntimeBuilder::<monoio::FusionDriver>::new()
+        .with_blocking_strategy(monoio::blocking::BlockingStrategy::Panic)
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let addrs = lookup_host(("localhost", 80)).await.unwrap();
+        assert!(addrs.iter().all(|addr| addr.ip().is_loopback()));
+    });
+}
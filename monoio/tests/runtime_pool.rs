@@ -0,0 +1,66 @@
+#![cfg(all(feature = "sync", feature = "utils"))]
+
+use std::thread;
+
+use monoio::utils::RuntimePool;
+
+#[test]
+fn spawn_per_core() {
+    let pool = RuntimePool::new(3)
+        .spawn_per_core(|core_id| async move {
+            std::future::pending::<()>().await;
+            core_id
+        })
+        .unwrap();
+    assert_eq!(pool.handles().len(), 3);
+    for (core_id, handle) in pool.handles().iter().enumerate() {
+        let name = handle.spawn(async { thread::current().name().map(str::to_owned) });
+        assert_eq!(
+            futures::executor::block_on(name).unwrap(),
+            format!("monoio-core-{core_id}")
+        );
+    }
+    // The pending futures are dropped.
+    assert_eq!(pool.shutdown(), vec![None, None, None]);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn bind_cpus() {
+    let pool = RuntimePool::new(2)
+        .bind_cpus([0])
+        .spawn_per_core(|_| async {
+            let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+            let size = std::mem::size_of_val(&set);
+            assert_eq!(unsafe { libc::sched_getaffinity(0, size, &mut set) }, 0);
+            unsafe { libc::CPU_COUNT(&set) == 1 && libc::CPU_ISSET(0, &set) }
+        })
+        .unwrap();
+    assert_eq!(pool.join(), vec![true, true]);
+
+    assert!(RuntimePool::new(2)
+        .bind_cpus([100000])
+        .spawn_per_core(|_| async {})
+        .is_err());
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn bind_numa_nodes() {
+    let Ok(cpus) = monoio::utils::numa_node_cpus(0) else {
+        // No NUMA support.
+        return;
+    };
+    let pool = RuntimePool::new(2)
+        .bind_numa_nodes([0])
+        .spawn_per_core(|_| async {
+            let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+            let size = std::mem::size_of_val(&set);
+            assert_eq!(unsafe { libc::sched_getaffinity(0, size, &mut set) }, 0);
+            (0..libc::CPU_SETSIZE as usize)
+                .filter(|&cpu| unsafe { libc::CPU_ISSET(cpu, &set) })
+                .collect::<Vec<_>>()
+        })
+        .unwrap();
+    assert_eq!(pool.join(), vec![cpus.clone(), cpus]);
+}
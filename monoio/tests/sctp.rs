@@ -0,0 +1,68 @@
+#![cfg(target_os = "linux")]
+use monoio::{
+    io::{AsyncReadRentExt, AsyncWriteRentExt},
+    net::sctp::{SctpListener, SctpStream, SendInfo},
+};
+
+#[test]
+fn send_info() {
+    let info = SendInfo::new().stream(3).ppid(46).unordered(true);
+    assert_eq!(info.stream, 3);
+    assert_eq!(info.ppid, 46);
+    assert!(info.unordered);
+    assert_eq!(info.context, 0);
+    assert_eq!(SendInfo::default(), SendInfo::new());
+}
+
+/// SCTP is a kernel module which is not loaded in every environment.
+fn bind_local() -> Option<SctpListener> {
+    match SctpListener::bind("127.0.0.1:0".parse().unwrap()) {
+        Ok(listener) => Some(listener),
+        Err(e) => {
+            eprintln!("sctp is not available: {e}");
+            None
+        }
+    }
+}
+
+#[monoio::test_all]
+async fn streams() {
+    let Some(listener) = bind_local() else {
+        return;
+    };
+    let addr = listener.local_addr().unwrap();
+
+    let server = monoio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        // Echo each message back on the same stream.
+        for _ in 0..2 {
+            let (res, buf) = stream.recv_with_info(vec![0; 64]).await;
+            let (n, info) = res.unwrap();
+            assert!(info.is_complete());
+            let send = SendInfo::new().stream(info.stream()).ppid(info.ppid());
+            let (res, _) = stream.send_with_info(buf[..n].to_vec(), &send).await;
+            res.unwrap();
+        }
+        let (res, buf) = stream.read_exact(vec![0; 5]).await;
+        res.unwrap();
+        assert_eq!(buf, b"plain");
+    });
+
+    let mut client = SctpStream::connect(addr).await.unwrap();
+    client.set_nodelay(true).unwrap();
+    assert_eq!(client.peer_addr().unwrap(), addr);
+    for (stream, msg) in [(1, &b"first"[..]), (0, &b"second"[..])] {
+        let send = SendInfo::new().stream(stream).ppid(42);
+        let (res, _) = client.send_with_info(msg, &send).await;
+        assert_eq!(res.unwrap(), msg.len());
+        let (res, buf) = client.recv_with_info(vec![0; 64]).await;
+        let (n, info) = res.unwrap();
+        assert_eq!(&buf[..n], msg);
+        assert_eq!(info.stream(), stream);
+        assert_eq!(info.ppid(), 42);
+        assert!(info.is_complete());
+        assert!(!info.is_notification());
+    }
+    client.write_all(b"plain").await.0.unwrap();
+    server.await;
+}
@@ -0,0 +1,85 @@
+use std::{
+    cell::Cell,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use monoio::{
+    io::AsyncReadRent,
+    net::{TcpListener, TcpStream},
+    Driver, Runtime,
+};
+
+struct DropFlag(Rc<Cell<bool>>);
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        self.0.set(true);
+        // Still inside the runtime, the spawned task is aborted.
+        let join = monoio::spawn(async { unreachable!() });
+        assert!(!join.is_finished());
+    }
+}
+
+fn shutdown_drops_tasks<D: Driver>(mut rt: Runtime<D>) {
+    let dropped = Rc::new(Cell::new(false));
+    let flag = DropFlag(dropped.clone());
+    rt.block_on(async move {
+        monoio::spawn(async move {
+            let _flag = flag;
+            std::future::pending::<()>().await;
+        });
+        monoio::task::yield_now().await;
+    });
+    assert!(!dropped.get());
+
+    rt.shutdown_timeout(Duration::from_secs(1));
+    assert!(dropped.get());
+}
+
+fn shutdown_drains_ops<D: Driver>(mut rt: Runtime<D>) {
+    rt.block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        monoio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            // Never completes, the peer sends nothing.
+            let _ = stream.read(vec![0; 64]).await;
+        });
+        let stream = TcpStream::connect(addr).await.unwrap();
+        monoio::time::sleep(Duration::from_millis(50)).await;
+        // Keep the peer open.
+        std::mem::forget(stream);
+    });
+
+    let begin = Instant::now();
+    rt.shutdown_timeout(Duration::from_secs(10));
+    // The read is canceled rather than waited for until the timeout.
+    assert!(begin.elapsed() < Duration::from_secs(5));
+}
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+#[test]
+fn uring_shutdown() {
+    let rt = || {
+        monoio::RuntimeBuilder::<monoio::IoUringDriver>::new()
+            .enable_timer()
+            .build()
+            .unwrap()
+    };
+    shutdown_drops_tasks(rt());
+    shutdown_drains_ops(rt());
+}
+
+#[cfg(feature = "legacy")]
+#[test]
+fn legacy_shutdown() {
+    let rt = || {
+        monoio::RuntimeBuilder::<monoio::LegacyDriver>::new()
+            .enable_timer()
+            .build()
+            .unwrap()
+    };
+    shutdown_drops_tasks(rt());
+    shutdown_drains_ops(rt());
+}
@@ -0,0 +1,61 @@
+#![cfg(target_os = "linux")]
+use std::time::Duration;
+
+use monoio::signal::{ctrl_c, signal, SignalKind};
+
+// Direct the signal to the current thread, where it is blocked. Other threads of the test
+// process do not block it.
+fn raise(signum: libc::c_int) {
+    assert_eq!(
+        unsafe { libc::pthread_kill(libc::pthread_self(), signum) },
+        0
+    );
+}
+
+#[monoio::test_all]
+async fn recv() {
+    let mut sig = signal(SignalKind::user_defined1()).unwrap();
+    for _ in 0..3 {
+        raise(libc::SIGUSR1);
+        sig.recv().await.unwrap();
+    }
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn broadcast() {
+    let mut first = signal(SignalKind::user_defined2()).unwrap();
+    let mut second = signal(SignalKind::user_defined2()).unwrap();
+    let mut other = signal(SignalKind::hangup()).unwrap();
+    let task = monoio::spawn(async move {
+        second.recv().await.unwrap();
+    });
+    monoio::time::sleep(Duration::from_millis(10)).await;
+
+    raise(libc::SIGUSR2);
+    first.recv().await.unwrap();
+    task.await;
+
+    // Only the listeners of the signal are notified.
+    monoio::select! {
+        _ = other.recv() => panic!("unexpected SIGHUP"),
+        _ = monoio::time::sleep(Duration::from_millis(10)) => {}
+    }
+    raise(libc::SIGHUP);
+    other.recv().await.unwrap();
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn ctrl_c_signal() {
+    let task = monoio::spawn(ctrl_c());
+    // Let it listen to SIGINT.
+    monoio::time::sleep(Duration::from_millis(10)).await;
+    raise(libc::SIGINT);
+    task.await.unwrap();
+}
+
+#[monoio::test_all]
+async fn forbidden() {
+    let err = signal(SignalKind::from_raw(libc::SIGKILL)).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(signal(SignalKind::from_raw(0)).is_err());
+}
@@ -0,0 +1,313 @@
+use std::{cell::Cell, rc::Rc, time::Duration};
+
+use monoio::{
+    io::{AsyncReadRent, AsyncWriteRentExt},
+    net::{TcpListener, TcpStream},
+};
+
+/// Sets the flag when dropped.
+struct DropFlag(Rc<Cell<bool>>);
+
+impl Drop for DropFlag {
+    fn drop(&mut self) {
+        self.0.set(true);
+    }
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn abort_pending() {
+    let dropped = Rc::new(Cell::new(false));
+    let flag = DropFlag(dropped.clone());
+    let handle = monoio::spawn(async move {
+        let _flag = flag;
+        monoio::time::sleep(Duration::from_secs(3600)).await;
+    });
+    monoio::time::sleep(Duration::from_millis(10)).await;
+    assert!(!handle.is_finished());
+
+    handle.abort();
+    monoio::time::sleep(Duration::from_millis(10)).await;
+    assert!(dropped.get());
+    assert!(handle.is_finished());
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn abort_before_run() {
+    let ran = Rc::new(Cell::new(false));
+    let ran_ = ran.clone();
+    let handle = monoio::spawn(async move { ran_.set(true) });
+    let abort = handle.abort_handle();
+    drop(handle);
+    abort.abort();
+    monoio::time::sleep(Duration::from_millis(10)).await;
+    assert!(!ran.get());
+    assert!(abort.is_finished());
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn abort_finished() {
+    let handle = monoio::spawn(async { 1 });
+    let abort = handle.abort_handle();
+    monoio::time::sleep(Duration::from_millis(10)).await;
+    assert!(abort.is_finished());
+    // Aborting a finished task does nothing.
+    abort.abort();
+    assert_eq!(handle.await, 1);
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn abort_handle_detached() {
+    let handle = monoio::spawn(async {
+        monoio::time::sleep(Duration::from_millis(10)).await;
+        1
+    });
+    // Dropping an abort handle does not abort the task.
+    drop(handle.abort_handle().clone());
+    assert_eq!(handle.await, 1);
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn abort_connection() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = monoio::spawn(async move {
+        let (mut conn, _) = listener.accept().await.unwrap();
+        loop {
+            let (res, buf) = conn.read(Vec::with_capacity(16)).await;
+            if res.unwrap() == 0 {
+                break;
+            }
+            let (res, _) = conn.write_all(buf).await;
+            res.unwrap();
+        }
+    });
+
+    let mut client = TcpStream::connect(addr).await.unwrap();
+    let (res, _) = client.write_all("hello").await;
+    res.unwrap();
+    let (res, _) = client.read(vec![0; 16]).await;
+    assert_eq!(res.unwrap(), 5);
+    // Tear the connection down from outside, which closes the socket.
+    server.abort_handle().abort();
+    let (res, _) = client.read(vec![0; 16]).await;
+    assert_eq!(res.unwrap(), 0);
+}
+
+#[monoio::test_all(timer_enabled = true)]
+#[should_panic(expected = "aborted")]
+async fn await_aborted() {
+    let handle = monoio::spawn(monoio::time::sleep(Duration::from_secs(3600)));
+    handle.abort();
+    handle.await;
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn scope_waits_children() {
+    let done = Rc::new(Cell::new(0));
+    let done_ = done.clone();
+    let out = monoio::task::scope(|s| async move {
+        for i in 0..3 {
+            let done = done_.clone();
+            let s_ = s.clone();
+            s.spawn(async move {
+                monoio::time::sleep(Duration::from_millis(10 * i)).await;
+                // Nested children are waited too.
+                let done_nested = done.clone();
+                s_.spawn(async move { done_nested.set(done_nested.get() + 1) });
+                done.set(done.get() + 1);
+            });
+        }
+        7
+    })
+    .await;
+    assert_eq!(out, 7);
+    assert_eq!(done.get(), 6);
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn try_scope_cancels_on_error() {
+    let dropped = Rc::new(Cell::new(false));
+    let flag = DropFlag(dropped.clone());
+    let res: Result<(), &str> = monoio::task::try_scope(|s| async move {
+        s.spawn_try(async move {
+            let _flag = flag;
+            monoio::time::sleep(Duration::from_secs(3600)).await;
+            Ok(())
+        });
+        s.spawn_try(async {
+            monoio::time::sleep(Duration::from_millis(10)).await;
+            Err::<(), _>("failed")
+        });
+        Ok(())
+    })
+    .await;
+    assert_eq!(res, Err("failed"));
+    assert!(dropped.get());
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn scope_dropped_aborts_children() {
+    let dropped = Rc::new(Cell::new(false));
+    let flag = DropFlag(dropped.clone());
+    let scoped = monoio::task::scope(|s| async move {
+        s.spawn(async move {
+            let _flag = flag;
+            monoio::time::sleep(Duration::from_secs(3600)).await;
+        });
+        monoio::time::sleep(Duration::from_secs(3600)).await;
+    });
+    let res = monoio::time::timeout(Duration::from_millis(10), scoped).await;
+    assert!(res.is_err());
+    monoio::time::sleep(Duration::from_millis(10)).await;
+    assert!(dropped.get());
+}
+
+#[monoio::test_all]
+async fn coop_budget_yields() {
+    use monoio::net::udp::UdpSocket;
+
+    let passive = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let active = UdpSocket::bind("127.0.0.1:0").unwrap();
+    active.connect(passive.local_addr().unwrap()).await.unwrap();
+
+    let flag = Rc::new(Cell::new(false));
+    let flag_ = flag.clone();
+    // The sends are always ready, the task must yield to the other one by itself.
+    let hot = monoio::spawn(async move {
+        let mut count = 0;
+        while !flag_.get() && count < 100_000 {
+            active.send(&b"ping"[..]).await.0.unwrap();
+            count += 1;
+        }
+        count
+    });
+    monoio::spawn(async move { flag.set(true) });
+    assert!(hot.await < 100_000);
+    drop(passive);
+}
+
+#[monoio::test_all]
+async fn yield_now() {
+    let order = Rc::new(std::cell::RefCell::new(Vec::new()));
+    let order_ = order.clone();
+    let a = monoio::spawn(async move {
+        order_.borrow_mut().push(1);
+        monoio::task::yield_now().await;
+        order_.borrow_mut().push(3);
+    });
+    let order_ = order.clone();
+    let b = monoio::spawn(async move { order_.borrow_mut().push(2) });
+    a.await;
+    b.await;
+    assert_eq!(*order.borrow(), [1, 2, 3]);
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn yield_now_drives_io() {
+    // The timer fires while the loop yields, without parking.
+    let sleep = monoio::spawn(monoio::time::sleep(Duration::from_millis(10)));
+    while !sleep.is_finished() {
+        monoio::task::yield_now().await;
+    }
+}
+
+#[monoio::test_all]
+async fn consume_budget() {
+    let flag = Rc::new(Cell::new(false));
+    let flag_ = flag.clone();
+    let hot = monoio::spawn(async move {
+        let mut count = 0;
+        while !flag_.get() {
+            monoio::task::consume_budget().await;
+            count += 1;
+        }
+        count
+    });
+    monoio::spawn(async move { flag.set(true) });
+    assert!(hot.await < 1_000);
+}
+
+#[monoio::test_all]
+async fn priority_order() {
+    use monoio::task::Priority;
+
+    let order = Rc::new(std::cell::RefCell::new(Vec::new()));
+    let mut handles = Vec::new();
+    for priority in [Priority::Low, Priority::Normal, Priority::High] {
+        let order = order.clone();
+        handles.push(monoio::spawn_with_priority(priority, async move {
+            order.borrow_mut().push(priority)
+        }));
+    }
+    for handle in handles {
+        handle.await;
+    }
+    assert_eq!(
+        *order.borrow(),
+        [Priority::High, Priority::Normal, Priority::Low]
+    );
+}
+
+#[monoio::test_all]
+async fn priority_aging() {
+    use monoio::task::Priority;
+
+    let flag = Rc::new(Cell::new(false));
+    let flag_ = flag.clone();
+    // The high priority task is always ready, the low one still gets to run.
+    let hot = monoio::spawn_with_priority(Priority::High, async move {
+        let mut count = 0;
+        while !flag_.get() && count < 100_000 {
+            monoio::task::yield_now().await;
+            count += 1;
+        }
+        count
+    });
+    monoio::spawn_with_priority(Priority::Low, async move { flag.set(true) });
+    assert!(hot.await < 1_000);
+}
+
+fn lifo_slot<D: monoio::Driver>(mut rt: monoio::Runtime<D>) {
+    let order = rt.block_on(async {
+        monoio::spawn(async {
+            let order = Rc::new(std::cell::RefCell::new(Vec::new()));
+            let order_ = order.clone();
+            let a = monoio::spawn(async move {
+                order_.borrow_mut().push("a");
+                let order = order_.clone();
+                monoio::spawn(async move { order.borrow_mut().push("c") });
+            });
+            // The task spawned later takes the slot and runs first.
+            let order_ = order.clone();
+            let b = monoio::spawn(async move { order_.borrow_mut().push("b") });
+            a.await;
+            b.await;
+            monoio::task::yield_now().await;
+            order.take()
+        })
+        .await
+    });
+    assert_eq!(order, ["b", "a", "c"]);
+}
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+#[test]
+fn uring_lifo_slot() {
+    let rt = monoio::RuntimeBuilder::<monoio::IoUringDriver>::new()
+        .with_lifo_slot(true)
+        .with_max_tasks_per_tick(16)
+        .build()
+        .unwrap();
+    lifo_slot(rt);
+}
+
+#[cfg(feature = "legacy")]
+#[test]
+fn legacy_lifo_slot() {
+    let rt = monoio::RuntimeBuilder::<monoio::LegacyDriver>::new()
+        .with_lifo_slot(true)
+        .with_max_tasks_per_tick(16)
+        .build()
+        .unwrap();
+    lifo_slot(rt);
+}
@@ -29,3 +29,59 @@ test_accept! {
     (str_port_tuple, ("127.0.0.1", 0)),
     (ip_port_tuple, ("127.0.0.1".parse::<IpAddr>().unwrap(), 0)),
 }
+
+#[monoio::test_all]
+async fn accept_multi() {
+    use monoio::io::stream::Stream;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let mut incoming = listener.accept_multi().unwrap();
+
+    let mut clients = Vec::new();
+    for _ in 0..3 {
+        clients.push(TcpStream::connect(&addr).await.unwrap());
+    }
+    for cli in clients.iter() {
+        let srv = incoming.next().await.unwrap().unwrap();
+        assert_eq!(srv.peer_addr().unwrap(), cli.local_addr().unwrap());
+    }
+}
+
+#[monoio::test_all]
+async fn accept_with_opts() {
+    use std::time::Duration;
+
+    use monoio::{io::AsyncWriteRentExt, net::ListenerOpts};
+
+    let opts = ListenerOpts::new()
+        .backlog(16)
+        .tcp_fast_open_queue(8)
+        .defer_accept(Duration::from_secs(1));
+    let listener = TcpListener::bind_with_config("127.0.0.1:0", &opts).unwrap();
+    let addr = listener.local_addr().unwrap();
+    let mut cli = TcpStream::connect(&addr).await.unwrap();
+    // With deferred accept, the connection is accepted once the data arrives.
+    let (res, _) = cli.write_all(b"hello").await;
+    res.unwrap();
+    let (srv, _) = listener.accept().await.unwrap();
+    assert_eq!(cli.local_addr().unwrap(), srv.peer_addr().unwrap());
+}
+
+#[monoio::test_all]
+async fn ipv6_only() {
+    use monoio::net::ListenerOpts;
+
+    let opts = ListenerOpts::new()
+        .reuse_port(false)
+        .reuse_addr(false)
+        .ipv6_only(true);
+    let Ok(v6) = TcpListener::bind_with_config("[::]:0", &opts) else {
+        // IPv6 is not available.
+        return;
+    };
+    let port = v6.local_addr().unwrap().port();
+    // The port is still free for IPv4.
+    let v4 = TcpListener::bind_with_config(("0.0.0.0", port), &opts).unwrap();
+    assert_eq!(v4.local_addr().unwrap().port(), port);
+}
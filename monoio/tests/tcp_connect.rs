@@ -176,3 +176,53 @@ impl DropFlag {
         assert!(*self.0.borrow());
     }
 }
+
+#[monoio::test_all(timer_enabled = true)]
+async fn connect_with_opts() {
+    use std::time::Duration;
+
+    use monoio::net::TcpConnectOpts;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    // Pick a free local port.
+    let local = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+
+    let opts = TcpConnectOpts::new()
+        .local_addr(local)
+        .timeout(Duration::from_secs(5));
+    let mine = TcpStream::connect_with(addr, opts).await.unwrap();
+    let (theirs, peer) = listener.accept().await.unwrap();
+    assert_eq!(mine.local_addr().unwrap(), local);
+    assert_eq!(peer, local);
+    drop(theirs);
+
+    #[cfg(target_os = "linux")]
+    match TcpStream::connect_with(addr, TcpConnectOpts::new().bind_device("lo")).await {
+        Ok(_) => assert!(listener.accept().await.is_ok()),
+        Err(e) => assert_eq!(e.kind(), std::io::ErrorKind::PermissionDenied),
+    }
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn connect_happy() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    // The IPv6 attempt is refused, then it falls back to IPv4 without waiting.
+    let addrs: Vec<SocketAddr> = vec![
+        ([127, 0, 0, 1], port).into(),
+        ("::1".parse::<IpAddr>().unwrap(), port).into(),
+    ];
+    let begin = std::time::Instant::now();
+    let mine = TcpStream::connect_happy(&addrs[..]).await.unwrap();
+    assert!(begin.elapsed() < std::time::Duration::from_millis(250));
+    assert_eq!(mine.peer_addr().unwrap(), addrs[0]);
+    assert!(listener.accept().await.is_ok());
+
+    let empty: &[SocketAddr] = &[];
+    assert!(TcpStream::connect_happy(empty).await.is_err());
+}
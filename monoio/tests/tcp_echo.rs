@@ -122,3 +122,222 @@ async fn echo_tfo() {
     let active_addr = rx.await.unwrap();
     assert_eq!(active.local_addr().unwrap(), active_addr);
 }
+
+#[monoio::test_all]
+async fn recv_multi() {
+    use monoio::{
+        buf::BufGroup,
+        io::{stream::Stream, AsyncWriteRent},
+    };
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let mut cli = TcpStream::connect(addr).await.unwrap();
+    let (srv, _) = listener.accept().await.unwrap();
+
+    let group = BufGroup::new(2, 2, 16).unwrap();
+    let mut incoming = srv.recv_multi(&group).unwrap();
+    let mut received = Vec::new();
+    for i in 1..=8 {
+        cli.write_all(b"hello").await.0.unwrap();
+        while received.len() < 5 * i {
+            let buf = incoming.next().await.unwrap().unwrap();
+            received.extend_from_slice(&buf);
+        }
+    }
+    cli.shutdown().await.unwrap();
+    assert!(incoming.next().await.is_none());
+    assert_eq!(received, b"hello".repeat(8));
+}
+
+#[monoio::test_all]
+async fn recv_multi_buf_ring() {
+    use monoio::{buf::BufRing, io::stream::Stream};
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let mut cli = TcpStream::connect(addr).await.unwrap();
+    let (srv, _) = listener.accept().await.unwrap();
+
+    let ring = BufRing::builder(3)
+        .ring_entries(2)
+        .buf_len(16)
+        .build()
+        .unwrap();
+    let mut incoming = srv.recv_multi(&ring).unwrap();
+    let mut received = Vec::new();
+    for i in 1..=8 {
+        cli.write_all(b"world").await.0.unwrap();
+        while received.len() < 5 * i {
+            let buf = incoming.next().await.unwrap().unwrap();
+            assert_eq!(buf.bgid(), 3);
+            received.extend_from_slice(&buf);
+        }
+    }
+    assert_eq!(received, b"world".repeat(8));
+    assert!(BufRing::builder(4).ring_entries(3).build().is_err());
+}
+
+#[monoio::test_all]
+async fn fixed_read_write() {
+    use monoio::buf::FixedBufPool;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let mut cli = TcpStream::connect(addr).await.unwrap();
+    let (mut srv, _) = listener.accept().await.unwrap();
+
+    let pool = FixedBufPool::new(2, 32).unwrap();
+    let mut buf = pool.try_next().unwrap();
+    buf.extend_from_slice(b"hello fixed");
+    let (res, buf) = cli.write_fixed(buf).await;
+    assert_eq!(res.unwrap(), 11);
+    drop(buf);
+
+    let mut received = Vec::new();
+    while received.len() < 11 {
+        let buf = pool.try_next().unwrap();
+        let (res, buf) = srv.read_fixed(buf).await;
+        assert!(res.unwrap() > 0);
+        received.extend_from_slice(&buf);
+    }
+    assert_eq!(received, b"hello fixed");
+}
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+#[test]
+fn fixed_files() {
+    use monoio::io::AsyncReadRent;
+
+    let mut rt = monoio::RuntimeBuilder::<monoio::IoUringDriver>::new()
+        .with_fixed_files(16)
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let mut cli = TcpStream::connect(addr).await.unwrap();
+        let (mut srv, _) = listener.accept().await.unwrap();
+
+        let slot = srv.register_fixed().await.unwrap();
+        assert_eq!(srv.register_fixed().await.unwrap(), slot);
+        assert_ne!(cli.register_fixed().await.unwrap(), slot);
+
+        cli.write_all(b"hello fixed").await.0.unwrap();
+        let (res, buf) = srv.read_exact(vec![0; 11]).await;
+        res.unwrap();
+        assert_eq!(buf, b"hello fixed");
+        srv.write_all(buf).await.0.unwrap();
+        let (res, buf) = cli.read_exact(vec![0; 11]).await;
+        res.unwrap();
+        assert_eq!(buf, b"hello fixed");
+
+        // The slot is released with the socket, so the peer sees EOF.
+        drop(srv);
+        let (res, _) = cli.read(vec![0; 8]).await;
+        assert_eq!(res.unwrap(), 0);
+    });
+
+    // Without a registered table, installing fds fails.
+    let mut rt = monoio::RuntimeBuilder::<monoio::IoUringDriver>::new()
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        assert!(listener.register_fixed().await.is_err());
+    });
+}
+
+#[monoio::test_all]
+async fn send_zc() {
+    use monoio::buf::IoBuf;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let mut cli = TcpStream::connect(addr).await.unwrap();
+    let (mut srv, _) = listener.accept().await.unwrap();
+
+    let data: Vec<u8> = (0..64 * 1024).map(|i| i as u8).collect();
+    let expected = data.clone();
+    let reader = monoio::spawn(async move {
+        let (res, buf) = srv.read_exact(vec![0; 64 * 1024]).await;
+        res.unwrap();
+        buf
+    });
+
+    let mut buf = data;
+    let mut sent = 0;
+    while sent < expected.len() {
+        // Both of them send with zero-copy.
+        let (res, slice) = if sent % 2 == 0 {
+            cli.send_zc(buf.slice(sent..)).await
+        } else {
+            cli.write_zc(buf.slice(sent..)).await
+        };
+        buf = slice.into_inner();
+        sent += res.unwrap();
+    }
+    assert_eq!(buf, expected);
+    assert_eq!(reader.await, expected);
+}
+
+#[cfg(all(target_os = "linux", feature = "zero-copy"))]
+#[monoio::test_all]
+async fn write_msg_zerocopy() {
+    const LEN: usize = 12 * 1024 * 1024;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let mut cli = TcpStream::connect(addr).await.unwrap();
+    let (mut srv, _) = listener.accept().await.unwrap();
+
+    let data: Vec<u8> = (0..LEN).map(|i| (i % 251) as u8).collect();
+    let expected = data.clone();
+    let reader = monoio::spawn(async move {
+        let (res, buf) = srv.read_exact(vec![0; LEN]).await;
+        res.unwrap();
+        buf
+    });
+
+    // The large writes are sent with MSG_ZEROCOPY, and completed after the notification.
+    let (res, buf) = cli.write_all(data).await;
+    res.unwrap();
+    assert_eq!(buf, expected);
+    let (res, _) = cli.write_all(vec![1; 16]).await;
+    res.unwrap();
+    assert_eq!(reader.await, expected);
+}
+
+#[cfg(all(target_os = "linux", feature = "zero-copy"))]
+#[monoio::test_all(timer_enabled = true)]
+async fn write_msg_zerocopy_dropped() {
+    use monoio::io::{AsyncReadRent, AsyncWriteRent};
+
+    const LEN: usize = 12 * 1024 * 1024;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let mut cli = TcpStream::connect(addr).await.unwrap();
+    let (mut srv, _) = listener.accept().await.unwrap();
+
+    // The write is dropped before the peer reads, the socket keeps the buffer it was sending.
+    let data: Vec<u8> = (0..LEN).map(|i| (i % 251) as u8).collect();
+    let expected = data.clone();
+    monoio::select! {
+        _ = cli.write(data) => {}
+        _ = monoio::time::sleep(std::time::Duration::from_millis(5)) => {}
+    }
+    let junk = vec![0xff_u8; LEN];
+    drop(cli);
+
+    let mut received = Vec::new();
+    loop {
+        let (res, buf) = srv.read(Vec::with_capacity(64 * 1024)).await;
+        if res.unwrap() == 0 {
+            break;
+        }
+        received.extend_from_slice(&buf);
+    }
+    assert_eq!(received, &expected[..received.len()]);
+    drop(junk);
+}
@@ -1,5 +1,5 @@
 use std::{
-    io::{Error, ErrorKind, Read, Result, Write},
+    io::{Error, Read, Result, Write},
     net, thread,
 };
 
@@ -92,10 +92,7 @@ async fn drop_write() -> Result<()> {
         let mut read_buf = [0u8; 32];
         let res = match stream.read(&mut read_buf) {
             Ok(0) => Ok(()),
-            Ok(len) => Err(Error::new(
-                ErrorKind::Other,
-                format!("Unexpected read: {len} bytes."),
-            )),
+            Ok(len) => Err(Error::other(format!("Unexpected read: {len} bytes."))),
             Err(err) => Err(err),
         };
 
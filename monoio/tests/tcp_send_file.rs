@@ -0,0 +1,43 @@
+#![cfg(target_os = "linux")]
+
+use std::io::{Read, Write};
+
+use monoio::{fs::File, io::AsyncWriteRent, net::TcpListener};
+
+fn content() -> Vec<u8> {
+    (0..200 * 1024).map(|i| (i % 251) as u8).collect()
+}
+
+#[monoio::test_all]
+async fn send_file() {
+    let data = content();
+    let mut tempfile = tempfile::NamedTempFile::new().unwrap();
+    tempfile.write_all(&data).unwrap();
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let reader = std::thread::spawn(move || {
+        let mut stream = std::net::TcpStream::connect(addr).unwrap();
+        let mut buf = Vec::new();
+        stream.read_to_end(&mut buf).unwrap();
+        buf
+    });
+
+    let (mut stream, _) = listener.accept().await.unwrap();
+    let file = File::open(tempfile.path()).await.unwrap();
+    // Several chunks from an offset.
+    assert_eq!(
+        stream.send_file(&file, 1000, 150_000).await.unwrap(),
+        150_000
+    );
+    // Stops at the end of the file.
+    let tail = data.len() as u64 - 100;
+    assert_eq!(stream.send_file(&file, tail, 4096).await.unwrap(), 100);
+    // Closing the socket on drop is not submitted while the thread is blocked by join.
+    stream.shutdown().await.unwrap();
+
+    let received = reader.join().unwrap();
+    assert_eq!(received.len(), 150_100);
+    assert_eq!(&received[..150_000], &data[1000..151_000]);
+    assert_eq!(&received[150_000..], &data[data.len() - 100..]);
+}
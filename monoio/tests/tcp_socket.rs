@@ -0,0 +1,105 @@
+use monoio::{
+    io::{AsyncReadRentExt, AsyncWriteRentExt},
+    net::TcpSocket,
+};
+
+#[monoio::test_all]
+async fn connect_and_listen() {
+    let socket = TcpSocket::new_v4().unwrap();
+    socket.set_reuseaddr(true).unwrap();
+    assert!(socket.reuseaddr().unwrap());
+    socket.bind("127.0.0.1:0".parse().unwrap()).unwrap();
+    let addr = socket.local_addr().unwrap();
+    let listener = socket.listen(16).unwrap();
+    assert_eq!(listener.local_addr().unwrap(), addr);
+
+    let srv = monoio::spawn(async move {
+        let (mut stream, peer) = listener.accept().await.unwrap();
+        let (res, buf) = stream.read_exact(vec![0; 4]).await;
+        res.unwrap();
+        assert_eq!(buf, b"ping");
+        peer
+    });
+
+    let socket = TcpSocket::new_v4().unwrap();
+    socket.set_send_buffer_size(64 * 1024).unwrap();
+    assert!(socket.send_buffer_size().unwrap() > 0);
+    socket.bind("127.0.0.1:0".parse().unwrap()).unwrap();
+    let local = socket.local_addr().unwrap();
+    let mut stream = socket.connect(addr).await.unwrap();
+    assert_eq!(stream.local_addr().unwrap(), local);
+    let (res, _) = stream.write_all(b"ping").await;
+    res.unwrap();
+    assert_eq!(srv.await, local);
+}
+
+#[cfg(unix)]
+#[monoio::test_all]
+async fn reuseport() {
+    let bind = |addr| {
+        let socket = TcpSocket::new_v4().unwrap();
+        socket.set_reuseport(true).unwrap();
+        assert!(socket.reuseport().unwrap());
+        socket.bind(addr).unwrap();
+        socket.listen(16).unwrap()
+    };
+    let first = bind("127.0.0.1:0".parse().unwrap());
+    let addr = first.local_addr().unwrap();
+    let second = bind(addr);
+    assert_eq!(second.local_addr().unwrap(), addr);
+}
+
+#[monoio::test_all]
+async fn stream_options() {
+    use std::time::Duration;
+
+    use monoio::net::{TcpListener, TcpStream};
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let stream = TcpStream::connect(addr).await.unwrap();
+
+    stream.set_nodelay(true).unwrap();
+    assert!(stream.nodelay().unwrap());
+    stream.set_keepalive(true).unwrap();
+    assert!(stream.keepalive().unwrap());
+    stream
+        .set_tcp_keepalive(
+            Some(Duration::from_secs(30)),
+            Some(Duration::from_secs(5)),
+            Some(3),
+        )
+        .unwrap();
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "android",
+        target_os = "freebsd",
+        target_os = "ios",
+        target_os = "macos"
+    ))]
+    {
+        assert_eq!(stream.keepalive_time().unwrap(), Duration::from_secs(30));
+        assert_eq!(stream.keepalive_interval().unwrap(), Duration::from_secs(5));
+        assert_eq!(stream.keepalive_retries().unwrap(), 3);
+    }
+    stream.set_linger(Some(Duration::from_secs(1))).unwrap();
+    assert_eq!(stream.linger().unwrap(), Some(Duration::from_secs(1)));
+    stream.set_ttl(42).unwrap();
+    assert_eq!(stream.ttl().unwrap(), 42);
+
+    #[cfg(target_os = "linux")]
+    {
+        stream.set_quickack(true).unwrap();
+        assert!(stream.quickack().unwrap());
+        stream
+            .set_user_timeout(Some(Duration::from_secs(10)))
+            .unwrap();
+        assert_eq!(
+            stream.user_timeout().unwrap(),
+            Some(Duration::from_secs(10))
+        );
+        let info = stream.tcp_info().unwrap();
+        assert!(info.snd_mss() > 0);
+        assert_eq!(info.total_retrans(), 0);
+    }
+}
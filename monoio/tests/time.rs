@@ -0,0 +1,249 @@
+use std::{
+    cell::RefCell,
+    future::Future,
+    pin::Pin,
+    rc::Rc,
+    task::Poll,
+    time::{Duration, Instant},
+};
+
+use monoio::time::{sleep, Sleep};
+
+#[monoio::test_all(timer_enabled = true)]
+async fn timer_churn() {
+    // Spread the deadlines over the levels of the wheel, register them and cancel them all
+    // by dropping. With the uring driver, they are armed in and removed from the kernel.
+    let mut sleeps: Vec<Pin<Box<Sleep>>> = (0..200_000u64)
+        .map(|i| {
+            Box::pin(sleep(Duration::from_millis(
+                1000 + i * 7919 % (40 * 24 * 3600 * 1000),
+            )))
+        })
+        .collect();
+    std::future::poll_fn(|cx| {
+        for sleep in sleeps.iter_mut() {
+            assert!(sleep.as_mut().poll(cx).is_pending());
+        }
+        Poll::Ready(())
+    })
+    .await;
+    drop(sleeps);
+
+    let begin = Instant::now();
+    sleep(Duration::from_millis(10)).await;
+    assert!(begin.elapsed() >= Duration::from_millis(10));
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn timer_order() {
+    let fired = Rc::new(RefCell::new(Vec::new()));
+    let mut handles = Vec::new();
+    for ms in (1..=80u64).rev() {
+        let fired = fired.clone();
+        handles.push(monoio::spawn(async move {
+            sleep(Duration::from_millis(ms * 2)).await;
+            fired.borrow_mut().push(ms);
+        }));
+    }
+    for handle in handles {
+        handle.await;
+    }
+    let fired = fired.borrow();
+    assert_eq!(fired.len(), 80);
+    assert!(fired.windows(2).all(|w| w[0] < w[1]));
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn timer_reset() {
+    let mut sleep = Box::pin(sleep(Duration::from_secs(3600)));
+    std::future::poll_fn(|cx| {
+        assert!(sleep.as_mut().poll(cx).is_pending());
+        Poll::Ready(())
+    })
+    .await;
+    let begin = Instant::now();
+    sleep
+        .as_mut()
+        .reset(monoio::time::Instant::now() + Duration::from_millis(5));
+    sleep.await;
+    let elapsed = begin.elapsed();
+    assert!(elapsed >= Duration::from_millis(5));
+    assert!(elapsed < Duration::from_secs(60));
+}
+
+#[monoio::test(driver = "uring")]
+async fn kernel_timer_without_time_driver() {
+    // The timers are armed in the kernel, so the time driver is not required.
+    let begin = Instant::now();
+    sleep(Duration::from_micros(500)).await;
+    assert!(begin.elapsed() >= Duration::from_micros(500));
+
+    let res = monoio::time::timeout(Duration::from_millis(5), std::future::pending::<()>()).await;
+    assert!(res.is_err());
+    assert!(begin.elapsed() >= Duration::from_millis(5));
+}
+
+#[monoio::test(driver = "uring")]
+async fn kernel_timer_cancel() {
+    // Canceled timers are removed from the kernel, and the ring keeps working.
+    for _ in 0..10_000 {
+        let mut sleep = Box::pin(sleep(Duration::from_secs(3600)));
+        std::future::poll_fn(|cx| {
+            assert!(sleep.as_mut().poll(cx).is_pending());
+            Poll::Ready(())
+        })
+        .await;
+        assert!(!sleep.is_elapsed());
+    }
+    let mut sleep = Box::pin(sleep(Duration::from_millis(2)));
+    (&mut sleep).await;
+    assert!(sleep.is_elapsed());
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn interval_ticks() {
+    let period = Duration::from_millis(5);
+    let begin = Instant::now();
+    let mut interval = monoio::time::interval(period);
+    let first = interval.tick().await;
+    let mut last = first;
+    for _ in 0..20 {
+        let tick = interval.tick().await;
+        assert_eq!(tick - last, period);
+        last = tick;
+    }
+    assert_eq!(last - first, period * 20);
+    assert!(begin.elapsed() >= period * 20);
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn interval_missed_ticks() {
+    let period = Duration::from_millis(5);
+    let mut interval = monoio::time::interval(period);
+    let first = interval.tick().await;
+    interval.tick().await;
+    // The missed ticks are yielded in a burst.
+    std::thread::sleep(period * 4);
+    let begin = Instant::now();
+    for i in 2..5 {
+        assert_eq!(interval.tick().await - first, period * i);
+    }
+    assert!(begin.elapsed() < period);
+
+    // The later ticks are delayed from now.
+    interval.set_missed_tick_behavior(monoio::time::MissedTickBehavior::Delay);
+    std::thread::sleep(period * 3);
+    let late = interval.tick().await;
+    assert_eq!(late - first, period * 5);
+    assert!(interval.tick().await - late >= period * 3);
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn interval_at_skip() {
+    let period = Duration::from_millis(5);
+    let start = monoio::time::Instant::now() + period * 2;
+    let mut interval = monoio::time::interval_at(start, period);
+    interval.set_missed_tick_behavior(monoio::time::MissedTickBehavior::Skip);
+    assert_eq!(interval.period(), period);
+
+    // The first tick is at the start.
+    assert_eq!(interval.tick().await, start);
+    assert!(monoio::time::Instant::now() >= start);
+
+    // The missed ticks are skipped, and the later ones stay on the schedule.
+    std::thread::sleep(period * 3 + period / 2);
+    let late = interval.tick().await;
+    assert_eq!(late, start + period);
+    let next = interval.tick().await;
+    assert!(next - start >= period * 4);
+    assert_eq!((next - start).as_nanos() % period.as_nanos(), 0);
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn timer_reset_pending() {
+    // Resetting a pending timer without polling it again still wakes the task.
+    let mut sleep = Box::pin(sleep(Duration::from_secs(3600)));
+    let begin = Instant::now();
+    let mut reset = false;
+    std::future::poll_fn(|cx| {
+        if sleep.as_mut().poll(cx).is_ready() {
+            return Poll::Ready(());
+        }
+        if !reset {
+            reset = true;
+            sleep
+                .as_mut()
+                .reset(monoio::time::Instant::now() + Duration::from_millis(5));
+        }
+        Poll::Pending
+    })
+    .await;
+    assert!(begin.elapsed() < Duration::from_secs(60));
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn recent_cached_per_tick() {
+    let begin = monoio::time::Instant::recent();
+    std::thread::sleep(Duration::from_millis(5));
+    assert_eq!(monoio::time::Instant::recent(), begin);
+
+    // It is read again after the driver is parked.
+    sleep(Duration::from_millis(10)).await;
+    let recent = monoio::time::Instant::recent();
+    assert!(recent - begin >= Duration::from_millis(15));
+    assert!(recent <= monoio::time::Instant::now());
+}
+
+#[test]
+fn recent_coarse_clock() {
+    let mut rt = monoio::RuntimeBuilder::<monoio::FusionDriver>::new()
+        .enable_timer()
+        .with_coarse_clock(true)
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let begin = monoio::time::Instant::recent();
+        sleep(Duration::from_millis(50)).await;
+        let recent = monoio::time::Instant::recent();
+        // The coarse clock is updated every few milliseconds.
+        let elapsed = recent - begin;
+        assert!(elapsed >= Duration::from_millis(30), "{elapsed:?}");
+        assert!(elapsed < Duration::from_secs(1), "{elapsed:?}");
+    });
+}
+
+/// A clock which jumps forward by the offset set by the test.
+struct JumpClock {
+    offset: std::sync::Arc<std::sync::atomic::AtomicU64>,
+}
+
+impl monoio::time::Clock for JumpClock {
+    fn now(&self) -> monoio::time::Instant {
+        let offset = self.offset.load(std::sync::atomic::Ordering::Relaxed);
+        monoio::time::Instant::from_std(Instant::now() + Duration::from_secs(offset))
+    }
+}
+
+#[test]
+fn custom_clock() {
+    let offset = std::sync::Arc::new(std::sync::atomic::AtomicU64::new(0));
+    let mut rt = monoio::RuntimeBuilder::<monoio::FusionDriver>::new()
+        .enable_timer()
+        .with_clock(JumpClock {
+            offset: offset.clone(),
+        })
+        .build()
+        .unwrap();
+    rt.block_on(async move {
+        let real = Instant::now();
+        let begin = monoio::time::Instant::now();
+        let hour = Duration::from_secs(3600);
+        let mut sleep = std::pin::pin!(monoio::time::sleep_until(begin + hour));
+        assert!(futures::poll!(sleep.as_mut()).is_pending());
+
+        offset.store(3600, std::sync::atomic::Ordering::Relaxed);
+        assert!(monoio::time::Instant::now() - begin >= hour);
+        sleep.await;
+        assert!(real.elapsed() < Duration::from_secs(10));
+    });
+}
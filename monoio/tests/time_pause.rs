@@ -0,0 +1,67 @@
+#![cfg(feature = "test-util")]
+
+use std::{cell::Cell, rc::Rc, time::Duration};
+
+use monoio::time::{self, Instant};
+
+const HOUR: Duration = Duration::from_secs(3600);
+
+#[monoio::test_all(timer_enabled = true)]
+async fn auto_advance() {
+    let real = std::time::Instant::now();
+    time::pause();
+    let begin = Instant::now();
+    // The clock jumps to the deadline since the runtime is idle.
+    time::sleep(HOUR).await;
+    let elapsed = begin.elapsed();
+    assert!(elapsed >= HOUR && elapsed < HOUR + Duration::from_millis(1));
+
+    let res = time::timeout(HOUR, std::future::pending::<()>()).await;
+    assert!(res.is_err());
+    assert!(real.elapsed() < Duration::from_secs(60));
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn advance() {
+    time::pause();
+    let begin = Instant::now();
+    let done = Rc::new(Cell::new(false));
+    let done_ = done.clone();
+    let sleep = time::sleep(Duration::from_secs(10));
+    let handle = monoio::spawn(async move {
+        sleep.await;
+        done_.set(true);
+    });
+
+    time::advance(Duration::from_secs(5)).await;
+    assert!(!done.get());
+    assert_eq!(Instant::now() - begin, Duration::from_secs(5));
+
+    time::advance(Duration::from_secs(5)).await;
+    assert!(done.get());
+    handle.await;
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn interval() {
+    let real = std::time::Instant::now();
+    time::pause();
+    let mut interval = time::interval(HOUR);
+    let first = interval.tick().await;
+    for i in 1..=24 {
+        assert_eq!(interval.tick().await - first, HOUR * i);
+    }
+    assert!(real.elapsed() < Duration::from_secs(60));
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn resume() {
+    time::pause();
+    let paused = Instant::now();
+    std::thread::sleep(Duration::from_millis(10));
+    assert_eq!(Instant::now(), paused);
+
+    time::resume();
+    std::thread::sleep(Duration::from_millis(10));
+    assert!(Instant::now() - paused >= Duration::from_millis(10));
+}
@@ -0,0 +1,73 @@
+use std::time::Duration;
+
+use monoio::{
+    io::{
+        stream::{Stream, StreamExt},
+        AsyncReadRent, AsyncWriteRentExt, CancelableAsyncReadRent,
+    },
+    net::{TcpListener, TcpStream},
+    time::{self, TimeoutExt},
+};
+
+async fn pair() -> (TcpStream, TcpStream) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (client, server) = monoio::join!(TcpStream::connect(addr), listener.accept());
+    (client.unwrap(), server.unwrap().0)
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn cancelable_read_timeout() {
+    let (mut client, mut server) = pair().await;
+
+    let read = time::timeout_cancelable(Duration::from_millis(50), |c| {
+        client.cancelable_read(Vec::with_capacity(16), c)
+    });
+    let (res, buf) = read.await;
+    assert_eq!(res.unwrap_err().raw_os_error(), Some(125));
+    assert_eq!(buf.capacity(), 16);
+
+    // No operation is left in flight to consume the data.
+    let (res, _) = server.write_all("hello").await;
+    res.unwrap();
+    let (res, buf) = client.read(buf).await;
+    assert_eq!(res.unwrap(), 5);
+    assert_eq!(&buf, b"hello");
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn cancelable_read_in_time() {
+    let (mut client, mut server) = pair().await;
+
+    let (res, _) = server.write_all("hello").await;
+    res.unwrap();
+    let mut read = std::pin::pin!(time::timeout_cancelable(Duration::from_secs(5), |c| {
+        client.cancelable_read(Vec::with_capacity(16), c)
+    }));
+    let (res, buf) = (&mut read).await;
+    assert!(!read.is_elapsed());
+    assert_eq!(res.unwrap(), 5);
+    assert_eq!(&buf, b"hello");
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn future_timeout_ext() {
+    assert_eq!(async { 1 }.timeout(Duration::from_millis(10)).await, Ok(1));
+    assert!(time::sleep(Duration::from_secs(5))
+        .timeout(Duration::from_millis(10))
+        .await
+        .is_err());
+}
+
+#[monoio::test_all(timer_enabled = true)]
+async fn stream_timeout() {
+    let mut stream = monoio::io::stream::iter([0, 500])
+        .then(|ms| async move {
+            time::sleep(Duration::from_millis(ms)).await;
+            ms
+        })
+        .timeout(Duration::from_millis(50));
+    assert_eq!(stream.next().await, Some(Ok(0)));
+    assert!(stream.next().await.unwrap().is_err());
+    assert!(stream.next().await.is_none());
+}
@@ -0,0 +1,106 @@
+#![cfg(feature = "tracing")]
+
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, Mutex,
+};
+
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Metadata, Subscriber,
+};
+
+/// Records the names of the spans and the messages of the events.
+#[derive(Clone, Default)]
+struct Recorder {
+    records: Arc<Mutex<Vec<String>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+impl Recorder {
+    fn count(&self, record: &str) -> usize {
+        let records = self.records.lock().unwrap();
+        records.iter().filter(|r| *r == record).count()
+    }
+}
+
+/// Records the message of an event, or the `op` of a waker event and the `loc.file` of a span.
+struct Message(String);
+
+impl Visit for Message {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if matches!(field.name(), "op" | "loc.file") {
+            self.0 = value.to_string();
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.0 = format!("{value:?}");
+        }
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.target().starts_with("monoio") || metadata.target() == "runtime::waker"
+    }
+
+    fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+        let mut location = Message(String::new());
+        span.record(&mut location);
+        let name = format!("span {} {}", span.metadata().name(), location.0);
+        self.records.lock().unwrap().push(name);
+        span::Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+
+    fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut message = Message(String::new());
+        event.record(&mut message);
+        self.records.lock().unwrap().push(message.0);
+    }
+
+    fn enter(&self, _: &span::Id) {}
+
+    fn exit(&self, _: &span::Id) {}
+}
+
+#[test]
+fn tasks_and_ops() {
+    let recorder = Recorder::default();
+    tracing::subscriber::with_default(recorder.clone(), || {
+        let mut rt = monoio::RuntimeBuilder::<monoio::FusionDriver>::new()
+            .enable_timer()
+            .build()
+            .unwrap();
+        rt.block_on(async {
+            let file = monoio::fs::File::open("Cargo.toml").await.unwrap();
+            let (res, _) = file.read_at(vec![0; 16], 0).await;
+            assert_eq!(res.unwrap(), 16);
+            monoio::spawn(async {
+                monoio::time::sleep(std::time::Duration::from_millis(1)).await;
+            })
+            .await;
+        });
+    });
+
+    // The spawn span and the waker events are in the form of tokio for tokio-console.
+    let spawn = format!("span runtime.spawn {}", file!());
+    assert_eq!(recorder.count(&spawn), 1);
+    assert_eq!(recorder.count("task spawned"), 1);
+    assert_eq!(recorder.count("task completed"), 1);
+    assert_eq!(recorder.count("task dropped"), 1);
+    assert!(recorder.count("waker.clone") >= 1);
+    assert!(recorder.count("waker.wake") + recorder.count("waker.wake_by_ref") >= 1);
+    assert!(recorder.count("op submitted") >= 1);
+    assert!(recorder.count("op completed") >= 1);
+    assert!(recorder.count("runtime parked") >= 1);
+    assert_eq!(
+        recorder.count("runtime parked"),
+        recorder.count("runtime unparked")
+    );
+}
@@ -0,0 +1,129 @@
+use std::time::{Duration, Instant};
+
+use monoio::{Driver, Runtime};
+
+fn turn<D: Driver>(mut rt: Runtime<D>) {
+    let mut join = None;
+    rt.block_on(async {
+        join = Some(monoio::spawn(async {
+            monoio::time::sleep(Duration::from_millis(50)).await;
+            1
+        }));
+    });
+    let join = join.unwrap();
+    assert!(!join.is_finished());
+
+    // Waits for the timer when no task is ready.
+    let begin = Instant::now();
+    let mut turns = 0;
+    while !join.is_finished() {
+        rt.turn(Duration::from_secs(1));
+        turns += 1;
+    }
+    assert!(begin.elapsed() >= Duration::from_millis(50));
+    assert!(turns < 10);
+    assert_eq!(rt.block_on(join), 1);
+
+    // Returns after the timeout.
+    let begin = Instant::now();
+    let turn = rt.turn(Duration::from_millis(20));
+    assert_eq!(turn.polled, 0);
+    assert!(!turn.has_ready);
+    assert!(begin.elapsed() >= Duration::from_millis(20));
+}
+
+fn run_until_idle<D: Driver>(mut rt: Runtime<D>) {
+    let joins = rt.block_on(async {
+        (0..3)
+            .map(|_| {
+                monoio::spawn(async {
+                    for _ in 0..3 {
+                        monoio::task::yield_now().await;
+                    }
+                })
+            })
+            .collect::<Vec<_>>()
+    });
+    // Each task is polled once per yield and once to complete, the first polls may happen in
+    // `block_on`.
+    assert!(rt.run_until_idle() >= 9);
+    assert!(joins.iter().all(|join| join.is_finished()));
+    assert_eq!(rt.run_until_idle(), 0);
+}
+
+/// Drive the runtime in a loop waiting for the pollable fd, like an external event loop.
+#[cfg(unix)]
+fn pollable_fd<D: Driver>(mut rt: Runtime<D>) {
+    use std::io::Write;
+
+    use monoio::{io::AsyncReadRentExt, net::TcpListener};
+
+    let mut state = None;
+    rt.block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let join = monoio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let (res, buf) = stream.read_exact(vec![0; 5]).await;
+            res.unwrap();
+            buf
+        });
+        state = Some((addr, join));
+    });
+    let (addr, join) = state.unwrap();
+    let fd = rt.pollable_fd().unwrap();
+
+    let client = std::thread::spawn(move || {
+        std::thread::sleep(Duration::from_millis(50));
+        let mut stream = std::net::TcpStream::connect(addr).unwrap();
+        stream.write_all(b"hello").unwrap();
+        stream
+    });
+    let begin = Instant::now();
+    loop {
+        while rt.drive_nonblocking().has_ready {}
+        if join.is_finished() {
+            break;
+        }
+        let mut pollfd = libc::pollfd {
+            fd,
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let n = unsafe { libc::poll(&mut pollfd, 1, 1000) };
+        assert_eq!(n, 1, "woken by the fd");
+    }
+    assert!(begin.elapsed() < Duration::from_secs(1));
+    assert_eq!(rt.block_on(join), b"hello");
+    drop(client.join().unwrap());
+}
+
+#[cfg(all(target_os = "linux", feature = "iouring"))]
+#[test]
+fn uring_turn() {
+    let rt = || {
+        monoio::RuntimeBuilder::<monoio::IoUringDriver>::new()
+            .enable_timer()
+            .build()
+            .unwrap()
+    };
+    turn(rt());
+    run_until_idle(rt());
+    #[cfg(unix)]
+    pollable_fd(rt());
+}
+
+#[cfg(feature = "legacy")]
+#[test]
+fn legacy_turn() {
+    let rt = || {
+        monoio::RuntimeBuilder::<monoio::LegacyDriver>::new()
+            .enable_timer()
+            .build()
+            .unwrap()
+    };
+    turn(rt());
+    run_until_idle(rt());
+    #[cfg(unix)]
+    pollable_fd(rt());
+}
@@ -96,3 +96,169 @@ async fn cancel_recv_from() {
         }
     }
 }
+
+#[monoio::test_all]
+async fn recv_multi() {
+    use monoio::{buf::BufGroup, io::stream::Stream};
+
+    let passive = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let passive_addr = passive.local_addr().unwrap();
+    let active = UdpSocket::bind("127.0.0.1:0").unwrap();
+    active.connect(passive_addr).await.unwrap();
+
+    let group = BufGroup::new(1, 4, 64).unwrap();
+    let mut incoming = passive.recv_multi(&group).unwrap();
+    for msg in ["foo", "bar", "baz", "qux", "quux"] {
+        active.send(msg).await.0.unwrap();
+        let buf = incoming.next().await.unwrap().unwrap();
+        assert_eq!(&buf[..], msg.as_bytes());
+    }
+}
+
+#[cfg(target_os = "linux")]
+#[monoio::test_all]
+async fn msg_with_control() {
+    use std::net::{IpAddr, Ipv4Addr};
+
+    use monoio::net::udp::SendMeta;
+
+    let server = UdpSocket::bind("0.0.0.0:0").unwrap();
+    server.set_recv_pktinfo(true).unwrap();
+    server.set_recv_ttl(true).unwrap();
+    server.set_recv_timestamp(true).unwrap();
+    let port = server.local_addr().unwrap().port();
+
+    let client = UdpSocket::bind("127.0.0.1:0").unwrap();
+    client.set_recv_tos(true).unwrap();
+    let meta = SendMeta::new().ttl(42);
+    let (res, _) = client
+        .send_msg(b"ping", Some(([127, 0, 0, 1], port).into()), &meta)
+        .await;
+    assert_eq!(res.unwrap(), 4);
+
+    let (res, buf) = server.recv_msg(vec![0; 2]).await;
+    let (n, meta) = res.unwrap();
+    assert_eq!(&buf[..n], b"pi");
+    assert!(meta.is_truncated());
+    assert_eq!(meta.addr(), client.local_addr().unwrap());
+    assert_eq!(meta.dst_ip(), Some(IpAddr::V4(Ipv4Addr::LOCALHOST)));
+    assert!(meta.ifindex().unwrap() > 0);
+    assert_eq!(meta.ttl(), Some(42));
+    assert!(meta.timestamp().is_some());
+
+    // Reply from the address the request was sent to.
+    let reply = SendMeta::new().src_ip(meta.dst_ip().unwrap()).tos(0x10);
+    let (res, _) = server.send_msg(b"pong", Some(meta.addr()), &reply).await;
+    res.unwrap();
+    let (res, buf) = client.recv_msg(vec![0; 8]).await;
+    let (n, meta) = res.unwrap();
+    assert_eq!(&buf[..n], b"pong");
+    assert!(!meta.is_truncated());
+    assert_eq!(meta.addr(), ([127, 0, 0, 1], port).into());
+    assert_eq!(meta.tos(), Some(0x10));
+    assert_eq!(meta.dst_ip(), None);
+}
+
+#[monoio::test_all]
+async fn batch() {
+    let server = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let addr = server.local_addr().unwrap();
+    let client = UdpSocket::bind("127.0.0.1:0").unwrap();
+
+    let msgs = vec![
+        (b"a".to_vec(), addr),
+        (b"bb".to_vec(), addr),
+        (b"ccc".to_vec(), addr),
+    ];
+    let results = client.send_batch(msgs).await;
+    assert_eq!(results.len(), 3);
+    for (i, (res, _)) in results.into_iter().enumerate() {
+        assert_eq!(res.unwrap(), i + 1);
+    }
+
+    let mut expected: Vec<&[u8]> = vec![b"a", b"bb", b"ccc"];
+    let mut bufs = vec![vec![0; 8], vec![0; 8], vec![0; 8], vec![0; 8]];
+    while !expected.is_empty() {
+        let (res, b) = server.recv_batch(bufs).await;
+        bufs = b;
+        let received = res.unwrap();
+        assert!(!received.is_empty());
+        for (i, (n, from)) in received.into_iter().enumerate() {
+            assert_eq!(from, client.local_addr().unwrap());
+            assert_eq!(&bufs[i][..n], expected.remove(0));
+        }
+        assert_eq!(bufs.len(), 4);
+        bufs.iter_mut().for_each(Vec::clear);
+    }
+}
+
+#[monoio::test_all]
+async fn multicast() {
+    use std::net::Ipv4Addr;
+
+    let group = Ipv4Addr::new(239, 255, 0, 77);
+    let socket = UdpSocket::bind("0.0.0.0:0").unwrap();
+    let port = socket.local_addr().unwrap().port();
+    socket.set_multicast_loop_v4(true).unwrap();
+    assert!(socket.multicast_loop_v4().unwrap());
+    socket.set_multicast_ttl_v4(4).unwrap();
+    assert_eq!(socket.multicast_ttl_v4().unwrap(), 4);
+    socket.set_broadcast(true).unwrap();
+    assert!(socket.broadcast().unwrap());
+
+    // The sandbox may have no multicast route.
+    if socket
+        .join_multicast_v4(group, Ipv4Addr::LOCALHOST)
+        .is_err()
+    {
+        return;
+    }
+    socket.set_multicast_if_v4(Ipv4Addr::LOCALHOST).unwrap();
+    assert_eq!(socket.multicast_if_v4().unwrap(), Ipv4Addr::LOCALHOST);
+    let (res, _) = socket.send_to(b"hello", (group, port).into()).await;
+    res.unwrap();
+    let (res, buf) = socket.recv_from(vec![0; 8]).await;
+    let (n, _) = res.unwrap();
+    assert_eq!(&buf[..n], b"hello");
+    socket
+        .leave_multicast_v4(group, Ipv4Addr::LOCALHOST)
+        .unwrap();
+}
+
+#[monoio::test_all]
+async fn connected_peek_try() {
+    let a = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let b = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let a_addr = a.local_addr().unwrap();
+    let b_addr = b.local_addr().unwrap();
+    a.connect(b_addr).await.unwrap();
+    b.connect(a_addr).await.unwrap();
+
+    let mut buf = [0; 8];
+    assert_eq!(
+        b.try_recv(&mut buf).unwrap_err().kind(),
+        std::io::ErrorKind::WouldBlock
+    );
+    assert_eq!(a.try_send(b"ping").unwrap(), 4);
+    let (res, peeked) = b.peek(vec![0; 8]).await;
+    assert_eq!(&peeked[..res.unwrap()], b"ping");
+    let (res, peeked) = b.peek_from(vec![0; 8]).await;
+    let (n, from) = res.unwrap();
+    assert_eq!((&peeked[..n], from), (&b"ping"[..], a_addr));
+    b.readable(false).await.unwrap();
+    assert_eq!(b.try_recv(&mut buf).unwrap(), 4);
+    assert_eq!(&buf[..4], b"ping");
+
+    // The disconnected socket can talk to others.
+    #[cfg(unix)]
+    {
+        let c = UdpSocket::bind("127.0.0.1:0").unwrap();
+        a.disconnect().unwrap();
+        assert!(a.peer_addr().is_err());
+        a.try_send_to(b"pong", c.local_addr().unwrap()).unwrap();
+        c.readable(false).await.unwrap();
+        let (n, from) = c.try_recv_from(&mut buf).unwrap();
+        // Linux releases the port bound implicitly on disconnect.
+        assert_eq!((&buf[..n], from), (&b"pong"[..], a.local_addr().unwrap()));
+    }
+}
@@ -0,0 +1,93 @@
+#![cfg(unix)]
+use std::{
+    fs::File,
+    io::{Read, Seek, SeekFrom, Write},
+    os::fd::AsFd,
+};
+
+use monoio::net::{UnixDatagram, UnixStream};
+
+fn temp_file(content: &[u8]) -> File {
+    let mut file = tempfile::tempfile().unwrap();
+    file.write_all(content).unwrap();
+    file.seek(SeekFrom::Start(0)).unwrap();
+    file
+}
+
+fn read_all(fd: std::os::fd::OwnedFd) -> String {
+    let mut file = File::from(fd);
+    let mut s = String::new();
+    file.read_to_string(&mut s).unwrap();
+    s
+}
+
+#[monoio::test_all]
+async fn stream_pass_fds() {
+    let (a, b) = UnixStream::pair().unwrap();
+    let file1 = temp_file(b"file1");
+    let file2 = temp_file(b"file2");
+
+    let (res, _) = a.send_with_fd(b"one", file1.as_fd()).await;
+    assert_eq!(res.unwrap(), 3);
+    let (res, buf) = b.recv_with_fd(vec![0; 16]).await;
+    let (n, fd) = res.unwrap();
+    assert_eq!(&buf[..n], b"one");
+    assert_eq!(read_all(fd.unwrap()), "file1");
+
+    let (res, _) = a
+        .send_with_fds(b"two", &[file1.as_fd(), file2.as_fd()])
+        .await;
+    assert_eq!(res.unwrap(), 3);
+    let (res, buf) = b.recv_with_fds(vec![0; 16], 4).await;
+    let (n, fds) = res.unwrap();
+    assert_eq!(&buf[..n], b"two");
+    assert_eq!(fds.len(), 2);
+    // The descriptors share the offset with `file1`, which is read to the end.
+    let mut fds = fds.into_iter();
+    assert_eq!(read_all(fds.next().unwrap()), "");
+    assert_eq!(read_all(fds.next().unwrap()), "file2");
+
+    // Data without descriptors.
+    a.send_with_fds(b"three", &[]).await.0.unwrap();
+    let (res, _) = b.recv_with_fd(vec![0; 16]).await;
+    let (n, fd) = res.unwrap();
+    assert_eq!(n, 5);
+    assert!(fd.is_none());
+}
+
+#[monoio::test_all]
+async fn datagram_pass_fds() {
+    let (a, b) = UnixDatagram::pair().unwrap();
+    let file = temp_file(b"datagram");
+
+    a.send_with_fd(b"fd", file.as_fd()).await.0.unwrap();
+    let (res, buf) = b.recv_from_with_fds(vec![0; 16], 1).await;
+    let (n, addr, fds) = res.unwrap();
+    assert_eq!(&buf[..n], b"fd");
+    assert!(addr.is_unnamed());
+    assert_eq!(fds.len(), 1);
+    assert_eq!(read_all(fds.into_iter().next().unwrap()), "datagram");
+}
+
+#[cfg(target_os = "linux")]
+#[monoio::test_all]
+async fn recv_credentials() {
+    use monoio::io::AsyncWriteRent;
+
+    let (mut a, b) = UnixStream::pair().unwrap();
+    b.set_passcred(true).unwrap();
+    a.write(b"cred").await.0.unwrap();
+    let (res, _) = b.recv_with_cred(vec![0; 16]).await;
+    let (n, cred) = res.unwrap();
+    assert_eq!(n, 4);
+    let cred = cred.unwrap();
+    assert_eq!(cred.uid(), unsafe { libc::geteuid() });
+    assert_eq!(cred.gid(), unsafe { libc::getegid() });
+    assert_eq!(cred.pid(), Some(std::process::id() as libc::pid_t));
+
+    let (a, b) = UnixDatagram::pair().unwrap();
+    b.set_passcred(true).unwrap();
+    a.send(b"cred").await.0.unwrap();
+    let (res, _) = b.recv_with_cred(vec![0; 16]).await;
+    assert_eq!(res.unwrap().1.unwrap().uid(), unsafe { libc::geteuid() });
+}
@@ -63,3 +63,47 @@ async fn shutdown() -> std::io::Result<()> {
     assert_eq!(n, 0);
     Ok(())
 }
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[monoio::test_all]
+async fn abstract_namespace() -> std::io::Result<()> {
+    use monoio::net::unix::SocketAddr;
+
+    let name = format!("monoio-uds-tests-{}", std::process::id());
+    let addr = SocketAddr::from_abstract_name(&name)?;
+    let listener = UnixListener::bind_addr(&addr)?;
+    assert_eq!(
+        listener.local_addr()?.as_abstract_namespace(),
+        Some(name.as_bytes())
+    );
+
+    let accept = listener.accept();
+    let connect = UnixStream::connect_addr(addr);
+    let ((mut server, peer), mut client) = try_join(accept, connect).await?;
+    assert!(peer.is_unnamed());
+    assert_eq!(
+        client.peer_addr()?.as_abstract_namespace(),
+        Some(name.as_bytes())
+    );
+
+    client.write_all(b"hello").await.0?;
+    let (res, buf) = server.read_exact(vec![0; 5]).await;
+    res?;
+    assert_eq!(buf, b"hello");
+    Ok(())
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[monoio::test_all]
+async fn autobind_listener() -> std::io::Result<()> {
+    use monoio::net::unix::SocketAddr;
+
+    let listener = UnixListener::bind_addr(&SocketAddr::from_pathname("")?)?;
+    let addr = listener.local_addr()?;
+    assert_eq!(addr.as_abstract_namespace().map(|name| name.len()), Some(5));
+
+    let accept = listener.accept();
+    let connect = UnixStream::connect_addr(addr);
+    try_join(accept, connect).await?;
+    Ok(())
+}
@@ -43,3 +43,80 @@ async fn addr_type() -> std::io::Result<()> {
     assert_eq!(_res.unwrap().1.as_pathname(), Some(sock_path1.as_path()));
     Ok(())
 }
+
+#[monoio::test_all]
+async fn pair_send_recv() -> std::io::Result<()> {
+    let (dgram1, dgram2) = UnixDatagram::pair()?;
+
+    dgram1.send(b"ping").await.0.unwrap();
+    let (res, buf) = dgram2.recv(vec![0; 100]).await;
+    assert_eq!(res.unwrap(), 4);
+    assert_eq!(buf, b"ping");
+
+    dgram2.send(b"pong").await.0.unwrap();
+    let (res, buf) = dgram1.recv(vec![0; 100]).await;
+    assert_eq!(res.unwrap(), 4);
+    assert_eq!(buf, b"pong");
+    Ok(())
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[monoio::test_all]
+async fn abstract_namespace() -> std::io::Result<()> {
+    use monoio::net::unix::SocketAddr;
+
+    let name1 = format!("monoio-unix-datagram-{}-1", std::process::id());
+    let name2 = format!("monoio-unix-datagram-{}-2", std::process::id());
+    let addr1 = SocketAddr::from_abstract_name(&name1)?;
+    let addr2 = SocketAddr::from_abstract_name(&name2)?;
+
+    let dgram1 = UnixDatagram::bind_addr(&addr1)?;
+    let dgram2 = UnixDatagram::bind_addr(&addr2)?;
+    assert_eq!(
+        dgram1.local_addr()?.as_abstract_namespace(),
+        Some(name1.as_bytes())
+    );
+
+    dgram1.send_to_addr(b"hello", &addr2).await.0.unwrap();
+    let (res, buf) = dgram2.recv_from(vec![0; 100]).await;
+    let (n, from) = res.unwrap();
+    assert_eq!(n, 5);
+    assert_eq!(buf, b"hello");
+    assert_eq!(from.as_abstract_namespace(), Some(name1.as_bytes()));
+
+    // Reply to the received origin.
+    dgram2.send_to_addr(b"world", &from).await.0.unwrap();
+    let (res, buf) = dgram1.recv(vec![0; 100]).await;
+    assert_eq!(res.unwrap(), 5);
+    assert_eq!(buf, b"world");
+    Ok(())
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[monoio::test_all]
+async fn autobind() -> std::io::Result<()> {
+    use monoio::net::unix::SocketAddr;
+
+    let unnamed = SocketAddr::from_pathname("")?;
+    assert!(unnamed.is_unnamed());
+
+    let dgram1 = UnixDatagram::bind_addr(&unnamed)?;
+    let addr1 = dgram1.local_addr()?;
+    // Linux picks 5 hex digits.
+    assert_eq!(
+        addr1.as_abstract_namespace().map(|name| name.len()),
+        Some(5)
+    );
+    assert!(!addr1.is_unnamed());
+    assert!(format!("{addr1:?}").ends_with("(abstract)"));
+
+    let dgram2 = UnixDatagram::unbound()?;
+    assert!(dgram2.local_addr()?.is_unnamed());
+    dgram2.send_to_addr(b"hi", &addr1).await.0.unwrap();
+    let (res, _) = dgram1.recv_from(vec![0; 16]).await;
+    let (n, from) = res.unwrap();
+    assert_eq!(n, 2);
+    assert!(from.is_unnamed());
+    assert_eq!(format!("{from:?}"), "(unnamed)");
+    Ok(())
+}
@@ -0,0 +1,88 @@
+#![cfg(all(target_os = "linux", feature = "iouring"))]
+
+use std::{io::Write, os::fd::AsRawFd};
+
+use monoio::{
+    driver::{NvmeUringCmd, UblkCtrlCmd, UringCmd},
+    fs::File,
+    IoUringDriver, RuntimeBuilder,
+};
+
+// SOCKET_URING_OP_SIOCINQ(since 6.7): the bytes available to read on the socket.
+const SIOCINQ: u32 = 0;
+
+fn siocinq(big: bool) {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let mut client = std::net::TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (server, _) = listener.accept().unwrap();
+    client.write_all(b"uring cmd").unwrap();
+
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new()
+        .uring_big_entries(big)
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        assert_eq!(monoio::driver::capabilities().is_big_entries(), big);
+        let op = match unsafe { UringCmd::new(server.as_raw_fd(), SIOCINQ).submit(()) } {
+            Ok(op) => op,
+            Err(e) if e.kind() == std::io::ErrorKind::Unsupported => return,
+            Err(e) => panic!("{e}"),
+        };
+        let complete = op.await;
+        match complete.result {
+            Ok(n) => assert_eq!(n, 9),
+            // Before 6.7
+            Err(e) if e.raw_os_error() == Some(libc::EOPNOTSUPP) => {}
+            Err(e) => panic!("{e}"),
+        }
+        assert_eq!(complete.big_cqe, [0; 2]);
+
+        // The other ops work on the ring as well.
+        let mut tempfile = tempfile::NamedTempFile::new().unwrap();
+        tempfile.write_all(b"big entries").unwrap();
+        let file = File::open(tempfile.path()).await.unwrap();
+        let (res, buf) = file.read_at(vec![0; 16], 0).await;
+        assert_eq!(&buf[..res.unwrap()], b"big entries");
+    });
+}
+
+#[test]
+fn socket_cmd() {
+    siocinq(false);
+}
+
+#[test]
+fn socket_cmd_big_entries() {
+    siocinq(true);
+}
+
+#[test]
+fn long_cmd() {
+    assert_eq!(std::mem::size_of::<NvmeUringCmd>(), 72);
+    assert_eq!(std::mem::size_of::<UblkCtrlCmd>(), 32);
+
+    let file = tempfile::tempfile().unwrap();
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new().build().unwrap();
+    rt.block_on(async {
+        let cmd = UringCmd::nvme(file.as_raw_fd(), NvmeUringCmd::IO, &NvmeUringCmd::default());
+        let err = unsafe { cmd.submit(()) }.err().unwrap();
+        if err.kind() != std::io::ErrorKind::Unsupported {
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        }
+    });
+
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new()
+        .uring_big_entries(true)
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        // A regular file does not support the command.
+        let cmd = UringCmd::nvme(file.as_raw_fd(), NvmeUringCmd::IO, &NvmeUringCmd::default());
+        let op = match unsafe { cmd.submit(()) } {
+            Ok(op) => op,
+            Err(e) if e.kind() == std::io::ErrorKind::Unsupported => return,
+            Err(e) => panic!("{e}"),
+        };
+        assert!(op.await.result.is_err());
+    });
+}
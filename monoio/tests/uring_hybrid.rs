@@ -0,0 +1,90 @@
+#![cfg(all(target_os = "linux", feature = "iouring", feature = "poll-io"))]
+
+use std::{io::Write, os::fd::AsRawFd, time::Duration};
+
+use monoio::{
+    fs::File,
+    io::{AsyncReadRentExt, AsyncWriteRentExt, CancelableAsyncReadRent, Canceller},
+    net::{TcpListener, TcpStream},
+    time::TimeDriver,
+    IoUringDriver, Runtime, RuntimeBuilder,
+};
+
+fn hybrid_runtime() -> Runtime<TimeDriver<IoUringDriver>> {
+    RuntimeBuilder::<IoUringDriver>::new()
+        .uring_hybrid_net(true)
+        .enable_timer()
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn tcp_echo() {
+    hybrid_runtime().block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = monoio::spawn(async move {
+            let (mut conn, _) = listener.accept().await.unwrap();
+            let (res, buf) = conn.read_exact(vec![0; 5]).await;
+            res.unwrap();
+            conn.write_all(buf).await.0.unwrap();
+        });
+
+        let mut cli = TcpStream::connect(addr).await.unwrap();
+        // The socket is driven by readiness.
+        let flags = unsafe { libc::fcntl(cli.as_raw_fd(), libc::F_GETFL) };
+        assert_ne!(flags & libc::O_NONBLOCK, 0);
+        cli.write_all(b"hello").await.0.unwrap();
+        let (res, buf) = cli.read_exact(vec![0; 5]).await;
+        res.unwrap();
+        assert_eq!(buf, b"hello");
+        server.await;
+    });
+}
+
+#[test]
+fn file_with_socket() {
+    let mut tempfile = tempfile::NamedTempFile::new().unwrap();
+    tempfile.write_all(b"hello hybrid").unwrap();
+
+    hybrid_runtime().block_on(async {
+        // Files are still driven by io_uring.
+        assert!(!monoio::utils::is_legacy());
+        let file = File::open(tempfile.path()).await.unwrap();
+        let (res, buf) = file.read_exact_at(vec![0; 12], 0).await;
+        res.unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = monoio::spawn(async move {
+            let mut cli = TcpStream::connect(addr).await.unwrap();
+            cli.write_all(buf).await.0.unwrap();
+        });
+        let (mut conn, _) = listener.accept().await.unwrap();
+        let (res, buf) = conn.read_exact(vec![0; 12]).await;
+        res.unwrap();
+        assert_eq!(buf, b"hello hybrid");
+        client.await;
+    });
+}
+
+#[test]
+fn cancel_read() {
+    hybrid_runtime().block_on(async {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let _cli = TcpStream::connect(addr).await.unwrap();
+        let (mut conn, _) = listener.accept().await.unwrap();
+
+        // The read waits for readiness until it is canceled.
+        let canceller = Canceller::new();
+        let handle = canceller.handle();
+        let read = monoio::spawn(async move { conn.cancelable_read(vec![0; 8], handle).await.0 });
+        monoio::time::sleep(Duration::from_millis(10)).await;
+        canceller.cancel();
+        assert_eq!(
+            read.await.unwrap_err().raw_os_error(),
+            Some(libc::ECANCELED)
+        );
+    });
+}
@@ -0,0 +1,54 @@
+#![cfg(all(target_os = "linux", feature = "iouring", feature = "sync"))]
+
+use futures::{channel::mpsc, SinkExt, StreamExt};
+use monoio::{IoUringDriver, RuntimeBuilder};
+
+fn uring_thread<F>(f: impl FnOnce() -> F + Send + 'static) -> std::thread::JoinHandle<()>
+where
+    F: std::future::Future<Output = ()>,
+{
+    std::thread::spawn(move || {
+        RuntimeBuilder::<IoUringDriver>::new()
+            .build()
+            .unwrap()
+            .block_on(f())
+    })
+}
+
+// Wakers between uring runtimes are sent with MSG_RING when the kernel supports it, and with
+// channel and eventfd otherwise.
+#[test]
+fn ping_pong() {
+    const ROUNDS: usize = 1000;
+    let (mut ping_tx, mut ping_rx) = mpsc::channel::<usize>(1);
+    let (mut pong_tx, mut pong_rx) = mpsc::channel::<usize>(1);
+
+    let peer = uring_thread(move || async move {
+        while let Some(n) = ping_rx.next().await {
+            pong_tx.send(n + 1).await.unwrap();
+        }
+    });
+    let local = uring_thread(move || async move {
+        for i in 0..ROUNDS {
+            ping_tx.send(i).await.unwrap();
+            assert_eq!(pong_rx.next().await, Some(i + 1));
+        }
+    });
+    local.join().unwrap();
+    peer.join().unwrap();
+}
+
+// Wakes from threads without a runtime still go through eventfd.
+#[test]
+fn wake_from_plain_thread() {
+    let (mut tx, mut rx) = mpsc::channel::<usize>(1);
+    let receiver = uring_thread(move || async move {
+        for i in 0..100 {
+            assert_eq!(rx.next().await, Some(i));
+        }
+    });
+    for i in 0..100 {
+        futures::executor::block_on(tx.send(i)).unwrap();
+    }
+    receiver.join().unwrap();
+}
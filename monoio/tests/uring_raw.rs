@@ -0,0 +1,60 @@
+#![cfg(all(target_os = "linux", feature = "iouring"))]
+
+use std::{io::Write, os::fd::AsRawFd, time::Duration};
+
+use io_uring::{opcode, types};
+use monoio::{driver::submit_raw, IoUringDriver, RuntimeBuilder};
+
+#[test]
+fn nop_and_read() {
+    let mut tempfile = tempfile::NamedTempFile::new().unwrap();
+    tempfile.write_all(b"raw sqe").unwrap();
+
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new().build().unwrap();
+    rt.block_on(async {
+        let nop = unsafe { submit_raw(opcode::Nop::new().build(), ()) }
+            .unwrap()
+            .await;
+        assert_eq!(nop.result.unwrap(), 0);
+
+        // The buffer is owned by the op until it completes.
+        let file = std::fs::File::open(tempfile.path()).unwrap();
+        let mut buf = vec![0u8; 16];
+        let entry = opcode::Read::new(types::Fd(file.as_raw_fd()), buf.as_mut_ptr(), 16).build();
+        let read = unsafe { submit_raw(entry, buf) }.unwrap().await;
+        let n = read.result.unwrap() as usize;
+        assert_eq!(&read.data[..n], b"raw sqe");
+    });
+}
+
+#[test]
+fn drop_before_complete() {
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new()
+        .enable_timer()
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        // The timespec must live until the timeout completes or is canceled.
+        let ts = Box::new(types::Timespec::new().sec(10));
+        let entry = opcode::Timeout::new(&*ts as *const _).build();
+        let op = unsafe { submit_raw(entry, ts) }.unwrap();
+        monoio::select! {
+            _ = op => panic!("the timeout should not complete"),
+            _ = monoio::time::sleep(Duration::from_millis(10)) => {}
+        }
+    });
+}
+
+#[cfg(feature = "legacy")]
+#[test]
+fn legacy_unsupported() {
+    let mut rt = RuntimeBuilder::<monoio::LegacyDriver>::new()
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let err = unsafe { submit_raw(opcode::Nop::new().build(), ()) }
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), std::io::ErrorKind::Unsupported);
+    });
+}
@@ -0,0 +1,337 @@
+#![cfg(all(target_os = "linux", feature = "iouring"))]
+
+use monoio::{
+    driver::{CqOverflowPolicy, SubmitPolicy},
+    io::{AsyncReadRentExt, AsyncWriteRentExt},
+    net::{TcpListener, TcpStream},
+    Driver, IoUringDriver, RuntimeBuilder,
+};
+
+async fn echo_once() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = monoio::spawn(async move {
+        let (mut conn, _) = listener.accept().await.unwrap();
+        let (res, buf) = conn.read_exact(vec![0; 5]).await;
+        res.unwrap();
+        conn.write_all(buf).await.0.unwrap();
+    });
+
+    let mut cli = TcpStream::connect(addr).await.unwrap();
+    cli.write_all(b"hello").await.0.unwrap();
+    let (res, buf) = cli.read_exact(vec![0; 5]).await;
+    res.unwrap();
+    assert_eq!(buf, b"hello");
+    server.await;
+}
+
+#[test]
+fn sqpoll() {
+    let mut rt = match RuntimeBuilder::<IoUringDriver>::new()
+        .uring_sqpoll(10, None)
+        .enable_timer()
+        .build()
+    {
+        Ok(rt) => rt,
+        // Not permitted on old kernels without privilege.
+        Err(e) if e.raw_os_error() == Some(libc::EPERM) => return,
+        Err(e) => panic!("{e}"),
+    };
+    rt.block_on(async {
+        echo_once().await;
+        // Let the SQ thread go to sleep, then it must be woken up.
+        monoio::time::sleep(std::time::Duration::from_millis(50)).await;
+        echo_once().await;
+    });
+}
+
+#[test]
+fn taskrun_flags() {
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new()
+        .uring_coop_taskrun(true)
+        .uring_single_issuer(true)
+        .uring_defer_taskrun(true)
+        .enable_timer()
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        echo_once().await;
+        monoio::time::sleep(std::time::Duration::from_millis(10)).await;
+        echo_once().await;
+    });
+}
+
+#[test]
+fn defer_taskrun_with_sqpoll() {
+    // DEFER_TASKRUN can not be combined with SQPOLL, the runtime must fall back.
+    let mut rt = match RuntimeBuilder::<IoUringDriver>::new()
+        .uring_sqpoll(10, None)
+        .uring_defer_taskrun(true)
+        .build()
+    {
+        Ok(rt) => rt,
+        Err(e) if e.raw_os_error() == Some(libc::EPERM) => return,
+        Err(e) => panic!("{e}"),
+    };
+    rt.block_on(echo_once());
+}
+
+#[test]
+fn capabilities() {
+    use io_uring::opcode;
+
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new().build().unwrap();
+    rt.block_on(async {
+        let caps = monoio::driver::capabilities();
+        assert!(caps.is_uring());
+        // Nop is supported since the first version of io_uring.
+        assert!(caps.is_opcode_supported(opcode::Nop::CODE));
+        assert!(!caps.is_opcode_supported(u8::MAX));
+        echo_once().await;
+    });
+
+    #[cfg(feature = "legacy")]
+    monoio::RuntimeBuilder::<monoio::LegacyDriver>::new()
+        .build()
+        .unwrap()
+        .block_on(async {
+            let caps = monoio::driver::capabilities();
+            assert!(!caps.is_uring());
+            assert!(!caps.is_opcode_supported(opcode::Nop::CODE));
+        });
+}
+
+#[test]
+fn register_ring_fd() {
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new()
+        .uring_register_ring_fd(true)
+        .enable_timer()
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        echo_once().await;
+        // Park with timeout enters the ring as well.
+        monoio::time::sleep(std::time::Duration::from_millis(10)).await;
+    });
+}
+
+#[test]
+fn completion_eventfd() {
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new()
+        .enable_timer()
+        .build()
+        .unwrap();
+    let fd = rt.driver().completion_eventfd().unwrap();
+    assert_eq!(rt.driver().completion_eventfd().unwrap(), fd);
+
+    rt.block_on(echo_once());
+
+    // Completions have been posted since it is registered.
+    let mut count = 0u64;
+    let n = unsafe { libc::read(fd, &mut count as *mut u64 as *mut _, 8) };
+    assert_eq!(n, 8);
+    assert!(count > 0);
+}
+
+/// Accept `n` connections made in advance concurrently, and return the number of successes.
+async fn accept_many(n: usize) -> usize {
+    let listener = std::rc::Rc::new(TcpListener::bind("127.0.0.1:0").unwrap());
+    let addr = listener.local_addr().unwrap();
+    let clients: Vec<_> = (0..n)
+        .map(|_| std::net::TcpStream::connect(addr).unwrap())
+        .collect();
+    let tasks: Vec<_> = (0..n)
+        .map(|_| {
+            let listener = listener.clone();
+            monoio::spawn(async move { listener.accept().await })
+        })
+        .collect();
+    let mut accepted = 0;
+    for t in tasks {
+        match t.await {
+            Ok(_) => accepted += 1,
+            Err(e) => assert_eq!(e.raw_os_error(), Some(libc::EBUSY)),
+        }
+    }
+    drop(clients);
+    accepted
+}
+
+fn small_cq_runtime(policy: CqOverflowPolicy) -> monoio::Runtime<IoUringDriver> {
+    RuntimeBuilder::<IoUringDriver>::new()
+        .with_entries(256)
+        .uring_cq_entries(256)
+        .uring_cq_overflow(policy)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn cq_overflow_flush() {
+    let mut rt = small_cq_runtime(CqOverflowPolicy::Flush);
+    assert_eq!(rt.block_on(accept_many(512)), 512);
+}
+
+#[test]
+fn cq_overflow_backpressure() {
+    let mut rt = small_cq_runtime(CqOverflowPolicy::Backpressure);
+    assert_eq!(rt.block_on(accept_many(512)), 512);
+}
+
+#[test]
+fn cq_overflow_error() {
+    let mut rt = small_cq_runtime(CqOverflowPolicy::Error);
+    let accepted = rt.block_on(accept_many(512));
+    assert!(accepted > 0 && accepted < 512);
+}
+
+fn policy_runtime(policy: SubmitPolicy) -> monoio::Runtime<IoUringDriver> {
+    RuntimeBuilder::<IoUringDriver>::new()
+        .uring_submit_policy(policy)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn submit_policies() {
+    for policy in [
+        SubmitPolicy::Default,
+        SubmitPolicy::Batch(4),
+        SubmitPolicy::Ratio(50),
+        SubmitPolicy::Park,
+    ] {
+        policy_runtime(policy).block_on(echo_once());
+    }
+}
+
+/// Poll a write once and check the data is sent before parking if `flush` is true.
+async fn write_before_park(flush: bool) {
+    use std::io::Read;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let mut peer = std::net::TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (mut conn, _) = listener.accept().await.unwrap();
+
+    let mut write = std::pin::pin!(conn.write_all(b"hello"));
+    assert!(futures::poll!(write.as_mut()).is_pending());
+    if flush {
+        monoio::flush_submissions().unwrap();
+    }
+    let mut buf = [0; 5];
+    peer.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hello");
+    write.await.0.unwrap();
+}
+
+#[test]
+fn submit_batch() {
+    policy_runtime(SubmitPolicy::Batch(1)).block_on(write_before_park(false));
+}
+
+#[test]
+fn flush_submissions() {
+    policy_runtime(SubmitPolicy::Park).block_on(write_before_park(true));
+}
+
+#[test]
+fn iowq_workers() {
+    use std::io::Write;
+
+    let mut tempfile = tempfile::NamedTempFile::new().unwrap();
+    tempfile.write_all(b"hello io-wq").unwrap();
+
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new()
+        .uring_iowq_max_workers(2, 2)
+        .uring_iowq_affinity(Some(0))
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let file = monoio::fs::File::open(tempfile.path()).await.unwrap();
+        let (res, buf) = file.read_exact_at(vec![0; 11], 0).await;
+        res.unwrap();
+        assert_eq!(buf, b"hello io-wq");
+    });
+
+    let err = RuntimeBuilder::<IoUringDriver>::new()
+        .uring_iowq_affinity(Some(usize::MAX))
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(err.raw_os_error(), Some(libc::EINVAL));
+}
+
+// Falling back to syscalls requires legacy or poll-io.
+#[cfg(any(feature = "legacy", feature = "poll-io"))]
+#[test]
+fn restrictions() {
+    use std::io::Write;
+
+    use io_uring::opcode;
+    use monoio::driver::Restriction;
+
+    let mut tempfile = tempfile::NamedTempFile::new().unwrap();
+    tempfile.write_all(b"restricted").unwrap();
+
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new()
+        .uring_restrictions([
+            Restriction::Opcode(opcode::Read::CODE),
+            Restriction::Opcode(opcode::Write::CODE),
+        ])
+        .build()
+        .unwrap();
+    rt.block_on(async {
+        let caps = monoio::driver::capabilities();
+        assert!(caps.is_opcode_supported(opcode::Read::CODE));
+        assert!(!caps.is_opcode_supported(opcode::OpenAt::CODE));
+        assert!(!caps.is_opcode_supported(opcode::Close::CODE));
+
+        // Open and close are executed with syscalls.
+        let file = monoio::fs::File::open(tempfile.path()).await.unwrap();
+        let (res, buf) = file.read_exact_at(vec![0; 10], 0).await;
+        res.unwrap();
+        assert_eq!(buf, b"restricted");
+        file.close().await.unwrap();
+    });
+}
+
+#[test]
+fn iopoll() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let path = tmpdir.path().join("iopoll");
+    std::fs::write(&path, [1; 4096]).unwrap();
+
+    let mut rt = RuntimeBuilder::<IoUringDriver>::new()
+        .uring_iopoll(true)
+        .enable_timer()
+        .build()
+        .unwrap();
+    rt.block_on(async move {
+        // The runtime busy polls, tasks and timers still run.
+        let task = monoio::spawn(async { 1 });
+        monoio::time::sleep(std::time::Duration::from_millis(10)).await;
+        assert_eq!(task.await, 1);
+
+        let file = match monoio::fs::OpenOptions::new()
+            .read(true)
+            .direct(true)
+            .open(&path)
+            .await
+        {
+            Ok(file) => file,
+            // The file system does not support O_DIRECT.
+            Err(e) if e.raw_os_error() == Some(libc::EINVAL) => return,
+            Err(e) => panic!("{e}"),
+        };
+        let (res, buf) = file
+            .read_at(monoio::buf::AlignedBuf::with_capacity(4096), 0)
+            .await;
+        match res {
+            Ok(n) => {
+                assert_eq!(n, 4096);
+                assert!(buf.iter().all(|b| *b == 1));
+            }
+            // The device does not support polling.
+            Err(e) => assert_eq!(e.raw_os_error(), Some(libc::EOPNOTSUPP)),
+        }
+    });
+}
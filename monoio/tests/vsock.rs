@@ -0,0 +1,61 @@
+#![cfg(target_os = "linux")]
+use monoio::{
+    io::{AsyncReadRentExt, AsyncWriteRentExt},
+    net::{VsockAddr, VsockListener, VsockStream},
+};
+
+#[test]
+fn addr() {
+    let addr = VsockAddr::new(VsockAddr::CID_HOST, 1234);
+    assert_eq!(addr.cid(), 2);
+    assert_eq!(addr.port(), 1234);
+    assert_eq!(addr.to_string(), "2:1234");
+    assert_eq!(VsockAddr::CID_ANY, u32::MAX);
+    assert_eq!(VsockAddr::PORT_ANY, u32::MAX);
+}
+
+/// Bind a listener on the loopback transport, which is not loaded in every environment.
+fn bind_local() -> Option<VsockListener> {
+    match VsockListener::bind(VsockAddr::new(VsockAddr::CID_LOCAL, VsockAddr::PORT_ANY)) {
+        Ok(listener) => Some(listener),
+        Err(e) => {
+            eprintln!("vsock loopback is not available: {e}");
+            None
+        }
+    }
+}
+
+#[monoio::test_all]
+async fn echo() {
+    let Some(listener) = bind_local() else {
+        return;
+    };
+    let addr = listener.local_addr().unwrap();
+    assert_eq!(addr.cid(), VsockAddr::CID_LOCAL);
+    assert_ne!(addr.port(), VsockAddr::PORT_ANY);
+
+    let server = monoio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let (res, buf) = stream.read_exact(vec![0; 5]).await;
+        res.unwrap();
+        stream.write_all(buf).await.0.unwrap();
+    });
+
+    let mut client = VsockStream::connect(addr).await.unwrap();
+    assert_eq!(client.peer_addr().unwrap(), addr);
+    client.write_all(b"hello").await.0.unwrap();
+    let (res, buf) = client.read_exact(vec![0; 5]).await;
+    res.unwrap();
+    assert_eq!(buf, b"hello");
+    server.await;
+}
+
+#[monoio::test_all]
+async fn connect_refused() {
+    // Nothing listens on the port, or there is no transport at all.
+    assert!(
+        VsockStream::connect(VsockAddr::new(VsockAddr::CID_LOCAL, 1))
+            .await
+            .is_err()
+    );
+}
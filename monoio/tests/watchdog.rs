@@ -0,0 +1,63 @@
+#![cfg(feature = "watchdog")]
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use monoio::runtime::BlockedReactor;
+
+fn runtime(
+    reports: &Arc<Mutex<Vec<BlockedReactor>>>,
+) -> monoio::Runtime<monoio::time::TimeDriver<monoio::LegacyDriver>> {
+    let reports = reports.clone();
+    monoio::RuntimeBuilder::<monoio::LegacyDriver>::new()
+        .enable_timer()
+        .with_watchdog(Duration::from_millis(50), move |report| {
+            reports.lock().unwrap().push(report.clone());
+        })
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn blocked_task() {
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let mut rt = runtime(&reports);
+    let line = rt.block_on(async {
+        let line = line!() + 1;
+        monoio::spawn(async {
+            std::thread::sleep(Duration::from_millis(300));
+        })
+        .await;
+        line
+    });
+
+    let reports = reports.lock().unwrap();
+    // Reported once per blocked tick.
+    assert_eq!(reports.len(), 1);
+    let report = &reports[0];
+    assert_eq!(report.thread_id, std::thread::current().id());
+    assert!(report.blocked_for >= Duration::from_millis(50));
+    let task = report.task.unwrap();
+    assert_eq!(task.location.file(), file!());
+    assert_eq!(task.location.line(), line);
+}
+
+#[test]
+fn parked_or_idle() {
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let mut rt = runtime(&reports);
+    rt.block_on(async {
+        // Parked on the timer.
+        monoio::time::sleep(Duration::from_millis(200)).await;
+        // Blocked outside of the tasks.
+        std::thread::sleep(Duration::from_millis(200));
+    });
+    // Not running.
+    std::thread::sleep(Duration::from_millis(200));
+
+    let reports = reports.lock().unwrap();
+    assert_eq!(reports.len(), 1);
+    assert!(reports[0].task.is_none());
+}
@@ -61,3 +61,77 @@ async fn zero_copy_for_uds() {
     assert_eq!(zero_copy(&mut rx, &mut tx).await.unwrap(), MSG.len() as u64);
     c_tx.closed().await;
 }
+
+#[cfg(all(target_os = "linux", feature = "splice"))]
+#[monoio::test_all]
+async fn zero_copy_file_to_tcp() {
+    use std::io::Write;
+
+    use monoio::{
+        buf::IoBufMut,
+        io::{zero_copy, AsyncReadRentExt},
+        net::TcpStream,
+    };
+
+    const MSG: &[u8] = b"copy from file";
+    let mut tempfile = tempfile::NamedTempFile::new().unwrap();
+    tempfile.write_all(MSG).unwrap();
+
+    let srv = monoio::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = srv.local_addr().unwrap();
+    let (tx, rx) = local_sync::oneshot::channel::<()>();
+    monoio::spawn(async move {
+        let mut stream = TcpStream::connect(&addr).await.unwrap();
+        let buf = Vec::<u8>::with_capacity(MSG.len()).slice_mut(0..MSG.len());
+        let (res, buf) = stream.read_exact(buf).await;
+        res.unwrap();
+        assert_eq!(&buf.into_inner(), MSG);
+        tx.send(()).unwrap();
+    });
+    let (mut conn, _) = srv.accept().await.unwrap();
+    let mut file = monoio::fs::File::open(tempfile.path()).await.unwrap();
+    assert_eq!(
+        zero_copy(&mut file, &mut conn).await.unwrap(),
+        MSG.len() as u64
+    );
+    rx.await.unwrap();
+}
+
+#[cfg(all(target_os = "linux", feature = "splice"))]
+#[monoio::test_all]
+async fn tee_pipe() {
+    use std::io::Write;
+
+    use monoio::{
+        io::splice::{tee, SpliceDestination, SpliceSource},
+        net::unix::new_pipe,
+    };
+
+    const MSG: &[u8] = b"tee";
+    let mut src = tempfile::NamedTempFile::new().unwrap();
+    src.write_all(MSG).unwrap();
+    let dst1 = tempfile::NamedTempFile::new().unwrap();
+    let dst2 = tempfile::NamedTempFile::new().unwrap();
+
+    let (mut r1, mut w1) = new_pipe().unwrap();
+    let (mut r2, mut w2) = new_pipe().unwrap();
+    let mut file = monoio::fs::File::open(src.path()).await.unwrap();
+    assert_eq!(file.splice_to_pipe(&mut w1, 16).await.unwrap(), 3);
+    assert_eq!(tee(&mut r1, &mut w2, 16).await.unwrap(), 3);
+
+    // The data is still in the source pipe.
+    let mut out1 = monoio::fs::OpenOptions::new()
+        .write(true)
+        .open(dst1.path())
+        .await
+        .unwrap();
+    let mut out2 = monoio::fs::OpenOptions::new()
+        .write(true)
+        .open(dst2.path())
+        .await
+        .unwrap();
+    assert_eq!(out1.splice_from_pipe(&mut r1, 16).await.unwrap(), 3);
+    assert_eq!(out2.splice_from_pipe(&mut r2, 16).await.unwrap(), 3);
+    assert_eq!(std::fs::read(dst1.path()).unwrap(), MSG);
+    assert_eq!(std::fs::read(dst2.path()).unwrap(), MSG);
+}